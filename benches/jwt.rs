@@ -0,0 +1,69 @@
+//-- ./benches/jwt.rs
+
+//! JWT encode and decode benchmarks
+//!
+//! Every authenticated request decodes an access token, and every login and
+//! refresh encodes an access and refresh token pair.
+//!
+//! ```bash
+//! cargo bench --bench jwt
+//! ```
+//! ---
+
+use std::time::Duration;
+
+use chrono::{SubsecRound, Utc};
+use criterion::{criterion_group, criterion_main, Criterion};
+use secrecy::SecretString;
+use uuid::Uuid;
+
+use authentication_service::database::Users;
+use authentication_service::domain::{
+    AccessToken, EmailAddress, Locale, PasswordHash, RefreshToken, TokenClaim,
+    UserName, UserRole,
+};
+
+/// A user to issue tokens for
+fn user() -> Users {
+    Users {
+        id: Uuid::now_v7(),
+        email: EmailAddress::parse("bench@example.com").unwrap(),
+        name: UserName::parse("Bench User").unwrap(),
+        password_hash: PasswordHash::parse(SecretString::from("aB1%aB1%aB1%aB1%"))
+            .unwrap(),
+        role: UserRole::User,
+        is_active: true,
+        is_verified: true,
+        created_on: Utc::now().round_subsecs(0),
+        locale: Locale::default(),
+    }
+}
+
+fn jwt(c: &mut Criterion) {
+    let secret = SecretString::from("a benchmark secret that is long enough");
+    let issuer = SecretString::from("authentication_service");
+    let duration = Duration::from_secs(15 * 60);
+    let user = user();
+
+    let mut group = c.benchmark_group("jwt");
+
+    group.bench_function("encode_access_token", |b| {
+        b.iter(|| AccessToken::new(&secret, &issuer, &duration, &user).unwrap())
+    });
+
+    group.bench_function("encode_refresh_token", |b| {
+        b.iter(|| RefreshToken::new(&secret, &issuer, &duration, &user).unwrap())
+    });
+
+    let access_token = AccessToken::new(&secret, &issuer, &duration, &user).unwrap();
+    group.bench_function("decode_access_token", |b| {
+        b.iter(|| {
+            TokenClaim::parse(access_token.as_ref(), &secret, &issuer).unwrap()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, jwt);
+criterion_main!(benches);
@@ -0,0 +1,108 @@
+//-- ./benches/login.rs
+
+//! Login service path benchmark
+//!
+//! Spawns the server against the database in the configuration and measures a
+//! full `Login` rpc: the user lookup, password verification, token encoding
+//! and session insert. Skipped when the database can not be reached.
+//!
+//! ```bash
+//! cargo bench --bench login
+//! ```
+//! ---
+
+use chrono::{SubsecRound, Utc};
+use criterion::{criterion_group, criterion_main, Criterion};
+use secrecy::SecretString;
+use tokio::runtime::Runtime;
+use uuid::Uuid;
+
+use authentication_service::configuration::Configuration;
+use authentication_service::database::{self, Users};
+use authentication_service::domain::{
+    EmailAddress, Locale, PasswordHash, UserName, UserRole,
+};
+use authentication_service::rpc::proto::authentication_service_client::AuthenticationServiceClient;
+use authentication_service::rpc::proto::LoginRequest;
+use authentication_service::startup;
+
+const PASSWORD: &str = "aB1%aB1%aB1%aB1%";
+
+/// Insert a user to log in as, returning its email
+async fn seed_user(database: &sqlx::PgPool) -> String {
+    let id = Uuid::now_v7();
+    let user = Users {
+        id,
+        email: EmailAddress::parse(format!("bench-{id}@example.com")).unwrap(),
+        name: UserName::parse("Bench User").unwrap(),
+        password_hash: PasswordHash::parse(SecretString::from(PASSWORD)).unwrap(),
+        role: UserRole::User,
+        is_active: true,
+        is_verified: true,
+        created_on: Utc::now().round_subsecs(0),
+        locale: Locale::default(),
+    };
+
+    user.insert(database).await.unwrap().email.to_string()
+}
+
+/// Spawn the server on a random port, returning its address
+async fn spawn_server(config: Configuration, database: sqlx::PgPool) -> String {
+    let ip_address = config.application.ip_address.clone();
+    let server = startup::TonicServer::build(config, database).await.unwrap();
+    let address = format!(
+        "http://{ip_address}:{}",
+        server.listener.local_addr().unwrap().port()
+    );
+    tokio::spawn(server.run());
+
+    address
+}
+
+fn login(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+
+    let mut config = Configuration::parse().unwrap();
+    config.application.port = 0;
+    config.http.enabled = false;
+
+    let database = match runtime.block_on(database::init_pool(&config.database)) {
+        Ok(database) => database,
+        Err(e) => {
+            eprintln!(
+                "Skipping the login benchmark, the database is not reachable: {e}"
+            );
+            return;
+        }
+    };
+
+    let (email, client) = runtime.block_on(async {
+        let email = seed_user(&database).await;
+        let address = spawn_server(config, database).await;
+        let client = AuthenticationServiceClient::connect(address).await.unwrap();
+        (email, client)
+    });
+
+    let mut group = c.benchmark_group("login");
+    group.sample_size(20);
+
+    group.bench_function("login", |b| {
+        b.to_async(&runtime).iter(|| {
+            let mut client = client.clone();
+            let request = LoginRequest {
+                email: email.clone(),
+                password: PASSWORD.to_string(),
+                remember_me: false,
+                organization_id: None,
+                captcha_token: None,
+                password_new: None,
+            };
+            async move { client.login(request).await.unwrap() }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, login);
+criterion_main!(benches);
@@ -0,0 +1,39 @@
+//-- ./benches/password_hash.rs
+
+//! Password hashing and verification benchmarks
+//!
+//! Argon2 is deliberately slow, so these set the floor for the login and
+//! password update latency. Run after changing the Argon2 parameters in
+//! `domain::PasswordHash` and compare against `benches/README.md`.
+//!
+//! ```bash
+//! cargo bench --bench password_hash
+//! ```
+//! ---
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use secrecy::SecretString;
+
+use authentication_service::domain::PasswordHash;
+
+const PASSWORD: &str = "aB1%aB1%aB1%aB1%";
+
+fn password_hash(c: &mut Criterion) {
+    let mut group = c.benchmark_group("password_hash");
+    group.sample_size(20);
+
+    group.bench_function("hash", |b| {
+        b.iter(|| PasswordHash::parse(SecretString::from(PASSWORD)).unwrap())
+    });
+
+    let password_hash = PasswordHash::parse(SecretString::from(PASSWORD)).unwrap();
+    let password = SecretString::from(PASSWORD);
+    group.bench_function("verify", |b| {
+        b.iter(|| assert!(password_hash.verify_password(&password).unwrap()))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, password_hash);
+criterion_main!(benches);
@@ -10,13 +10,18 @@
 //! - Watches for changes in proto files and this build script to trigger recompilation.
 //! - Supports proto3 optional fields via experimental protoc argument.
 //! - Outputs generated code and descriptor set to the Cargo OUT_DIR.
+//! - Derives serde on the authentication messages for the REST/JSON gateway.
+//! - Sets the `GIT_SHA` and `BUILD_TIMESTAMP` environment variables for `build_info`.
 //!
 //! ## Proto Files
-//! - authentication.proto
+//! The `authentication.v1` package, one file per service, in
+//! `proto/authentication/authentication/v1/`:
+//! - admin_service.proto
+//! - authentication_service.proto
 //! - common.proto
-//! - sessions.proto
-//! - users.proto
-//! - utilities.proto
+//! - sessions_service.proto
+//! - users_service.proto
+//! - utilities_service.proto
 //!
 //! ## Usage
 //! This script is run automatically by Cargo during build. No manual invocation is required.
@@ -25,7 +30,7 @@
 //! - https://github.com/hyperium/tonic
 //! - https://github.com/hyperium/tonic/blob/master/examples/build.rs
 
-use std::{env, path::PathBuf};
+use std::{env, path::PathBuf, process::Command, time::SystemTime};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Get the cargo OUT_DIR environment variable, which is where the generated code will be placed
@@ -35,6 +40,27 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("cargo:rerun-if-changed=proto/authentication/");
     println!("cargo:rerun-if-changed=build.rs");
 
+    // The git commit and build time, reported by the ServerInfo RPC
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    let git_sha = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|sha| sha.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=GIT_SHA={git_sha}");
+    let build_timestamp = match env::var("SOURCE_DATE_EPOCH") {
+        Ok(epoch) => epoch,
+        Err(_) => SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)?
+            .as_secs()
+            .to_string(),
+    };
+    println!("cargo:rustc-env=BUILD_TIMESTAMP={build_timestamp}");
+
     // Configure tonic_build to compile the proto files and generate Rust code
     tonic_build::configure()
         // Enable the `tonic` feature to generate client code
@@ -44,17 +70,24 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         // Enable the `tonic` feature to generate transport code
         .build_transport(true)
         .compile_well_known_types(true)
+        // Serialise messages as JSON for the REST/JSON gateway, missing fields use defaults
+        .type_attribute(
+            ".authentication.v1",
+            "#[derive(serde::Serialize, serde::Deserialize)]",
+        )
+        .message_attribute(".authentication.v1", "#[serde(default)]")
         .file_descriptor_set_path(out_dir.join("authentication_descriptor.bin"))
         // Include experimental proto3 optional support
         .protoc_arg("--experimental_allow_proto3_optional")
         .compile_protos(
             // Proto files to compile
             &[
-                "proto/authentication/authentication.proto",
-                "proto/authentication/common.proto",
-                "proto/authentication/sessions.proto",
-                "proto/authentication/users.proto",
-                "proto/authentication/utilities.proto",
+                "proto/authentication/authentication/v1/admin_service.proto",
+                "proto/authentication/authentication/v1/authentication_service.proto",
+                "proto/authentication/authentication/v1/common.proto",
+                "proto/authentication/authentication/v1/sessions_service.proto",
+                "proto/authentication/authentication/v1/users_service.proto",
+                "proto/authentication/authentication/v1/utilities_service.proto",
             ],
             // Proto root directory for imports, this is relevant for the proto file imports.
             &["proto/authentication"],
@@ -0,0 +1,29 @@
+//-- ./fuzz/fuzz_targets/jwt_parse.rs
+
+//! Fuzz the JWT parsing path with arbitrary tokens
+//!
+//! Parsing must return an error for anything that is not a token signed with
+//! the secret, and never panic.
+//!
+//! ```bash
+//! cargo +nightly fuzz run jwt_parse
+//! ```
+//! ---
+
+#![no_main]
+
+use authentication_service::domain::{TokenClaim, TokenClaimNew};
+use libfuzzer_sys::fuzz_target;
+use secrecy::SecretString;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(token) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    let secret = SecretString::from("fuzz secret");
+    let issuer = SecretString::from("fuzz issuer");
+
+    assert!(TokenClaim::parse(token, &secret, &issuer).is_err());
+    assert!(TokenClaimNew::parse(token, &secret, &issuer).is_err());
+});
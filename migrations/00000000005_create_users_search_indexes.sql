@@ -0,0 +1,28 @@
+-- ============================================================================
+-- Migration: 00000000005_create_users_search_indexes.sql
+-- Purpose:   Add indexes supporting the filtered user search query.
+-- Author:    Ian Teda
+-- Date:      2025-07-01
+--
+-- This migration:
+--   - Enables the pg_trgm extension for fast substring (ILIKE) matching
+--   - Adds a trigram index on users.email for email substring search
+--   - Adds an index on users.is_verified for verified status filtering
+--   - Adds a composite index on (role, created_on, id) for role scoped cursor
+--     pagination
+-- ============================================================================
+
+-- Trigram matching lets Postgres use an index for `email ILIKE '%term%'`
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- Index for email substring search
+CREATE INDEX IF NOT EXISTS idx_users_email_trgm
+    ON users USING GIN (email gin_trgm_ops);
+
+-- Index for quicker lookup by is_verified
+CREATE INDEX IF NOT EXISTS idx_users_is_verified
+    ON users (is_verified);
+
+-- Index for role filtered searches paginated by the (created_on, id) cursor
+CREATE INDEX IF NOT EXISTS idx_users_role_created_on_id
+    ON users (role, created_on, id);
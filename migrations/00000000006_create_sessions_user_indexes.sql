@@ -0,0 +1,20 @@
+-- ============================================================================
+-- Migration: 00000000006_create_sessions_user_indexes.sql
+-- Purpose:   Add indexes supporting per user session listing and counts.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Adds a composite index on (user_id, logged_in_at, id) for user scoped
+--     cursor pagination
+--   - Adds a partial index on user_id for counting a user's active sessions
+-- ============================================================================
+
+-- Index for paging through a user's sessions in login order
+CREATE INDEX IF NOT EXISTS idx_sessions_user_id_logged_in_at_id
+    ON sessions (user_id, logged_in_at, id);
+
+-- Index for counting a user's active sessions
+CREATE INDEX IF NOT EXISTS idx_sessions_user_id_active
+    ON sessions (user_id)
+    WHERE is_active = true;
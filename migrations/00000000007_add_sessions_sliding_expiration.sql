@@ -0,0 +1,15 @@
+-- ============================================================================
+-- Migration: 00000000007_add_sessions_sliding_expiration.sql
+-- Purpose:   Track sliding session expiration.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Adds absolute_expires_on, the latest a sliding session can be extended
+--     to. NULL means the session has a fixed expiry (expires_on)
+--   - Adds last_refreshed_at, when the session last refreshed an access token
+-- ============================================================================
+
+ALTER TABLE sessions
+    ADD COLUMN IF NOT EXISTS absolute_expires_on TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS last_refreshed_at TIMESTAMPTZ;
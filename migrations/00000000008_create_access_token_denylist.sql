@@ -0,0 +1,36 @@
+-- ============================================================================
+-- Migration: 00000000008_create_access_token_denylist.sql
+-- Purpose:   Deny outstanding access tokens before they expire.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Adds access_token_id to sessions, the jti of the last access token issued
+--     for the session
+--   - Creates access_token_denylist, the jti of access tokens that must be
+--     rejected until they expire (e.g. after a password change)
+-- ============================================================================
+
+ALTER TABLE sessions
+    ADD COLUMN IF NOT EXISTS access_token_id TEXT;
+
+-- Index for finding the session an access token was issued for
+CREATE INDEX IF NOT EXISTS idx_sessions_access_token_id
+    ON sessions (access_token_id);
+
+CREATE TABLE IF NOT EXISTS access_token_denylist (
+    -- The denied access token jti
+    jti TEXT PRIMARY KEY,
+
+    -- The user the token was issued to
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+
+    -- When the access token expires, after which the row can be pruned
+    expires_on TIMESTAMPTZ NOT NULL,
+
+    created_on TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Index for loading and pruning unexpired entries
+CREATE INDEX IF NOT EXISTS idx_access_token_denylist_expires_on
+    ON access_token_denylist (expires_on);
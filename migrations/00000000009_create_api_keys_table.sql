@@ -0,0 +1,40 @@
+-- ============================================================================
+-- Migration: 00000000009_create_api_keys_table.sql
+-- Purpose:   Store API keys for machine to machine authentication.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Creates the api_keys table. Only the SHA-256 hash of each key is stored,
+--     the key itself is shown once when it is created
+--   - Keys are scoped to a user_role and can expire or be revoked
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS api_keys (
+    id UUID PRIMARY KEY,
+
+    -- Human readable name, e.g. the service using the key
+    name TEXT NOT NULL,
+
+    -- The start of the key, safe to display so keys can be told apart
+    key_prefix TEXT NOT NULL,
+
+    -- Hex encoded SHA-256 hash of the key, used to look the key up
+    key_hash TEXT NOT NULL UNIQUE,
+
+    -- The role requests made with the key are authorised as
+    role user_role NOT NULL,
+
+    created_on TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    -- NULL means the key does not expire
+    expires_on TIMESTAMPTZ,
+
+    -- Set when the key is revoked
+    revoked_on TIMESTAMPTZ
+);
+
+-- Index for loading the keys that can still be used
+CREATE INDEX IF NOT EXISTS idx_api_keys_usable
+    ON api_keys (created_on)
+    WHERE revoked_on IS NULL;
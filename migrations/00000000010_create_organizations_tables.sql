@@ -0,0 +1,49 @@
+-- ============================================================================
+-- Migration: 00000000010_create_organizations_tables.sql
+-- Purpose:   Multi-tenancy, so one deployment can serve several customer orgs.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Creates the organizations table
+--   - Creates the organization_members table, linking users to organizations
+--     with a role per organization
+--   - Adds sessions.organization_id, the organization a session logged in to
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS organizations (
+    id UUID PRIMARY KEY,
+
+    name TEXT NOT NULL,
+
+    -- URL safe unique name, e.g. `acme-corp`
+    slug TEXT NOT NULL UNIQUE,
+
+    created_on TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS organization_members (
+    organization_id UUID NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+
+    -- The member's role within the organization
+    role user_role NOT NULL DEFAULT 'user',
+
+    created_on TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (organization_id, user_id)
+);
+
+-- Index for finding the organizations a user belongs to
+CREATE INDEX IF NOT EXISTS idx_organization_members_user_id
+    ON organization_members (user_id);
+
+-- NULL means the session is not scoped to an organization
+ALTER TABLE sessions
+    ADD COLUMN IF NOT EXISTS organization_id UUID REFERENCES organizations (id) ON DELETE CASCADE;
+
+-- Index for listing an organization's sessions
+CREATE INDEX IF NOT EXISTS idx_sessions_organization_id
+    ON sessions (organization_id)
+    WHERE organization_id IS NOT NULL;
@@ -0,0 +1,13 @@
+-- ============================================================================
+-- Migration: 00000000011_add_users_locale.sql
+-- Purpose:   Record each user's locale, used to pick the email language.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Adds users.locale, a language tag such as `en` or `pt-BR`. Existing
+--     users default to `en`, which is also the email template fallback
+-- ============================================================================
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS locale TEXT NOT NULL DEFAULT 'en';
@@ -0,0 +1,72 @@
+-- ============================================================================
+-- Migration: 00000000012_create_webhooks_tables.sql
+-- Purpose:   Store webhook endpoints and their delivery queue.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Creates the webhook_endpoints table. Each endpoint has its own signing
+--     secret and the event types it subscribes to
+--   - Creates the webhook_delivery_status enum type
+--   - Creates the webhook_deliveries table, used as the retry queue. The JSON
+--     payload is stored so every attempt sends the same body
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS webhook_endpoints (
+    id UUID PRIMARY KEY,
+
+    -- Where events are POSTed
+    url TEXT NOT NULL,
+
+    -- HMAC-SHA256 signing secret, shared with the receiver
+    secret TEXT NOT NULL,
+
+    -- Event types sent to the endpoint, e.g. 'user.login'. Empty means all
+    event_types TEXT[] NOT NULL DEFAULT '{}',
+
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+
+    created_on TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'webhook_delivery_status') THEN
+        CREATE TYPE webhook_delivery_status AS ENUM ('pending', 'delivered', 'failed');
+    END IF;
+END$$;
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id UUID PRIMARY KEY,
+
+    endpoint_id UUID NOT NULL REFERENCES webhook_endpoints (id) ON DELETE CASCADE,
+
+    -- The authentication event delivered, shared by every endpoint it is sent to
+    event_id UUID NOT NULL,
+    event_type TEXT NOT NULL,
+
+    -- The JSON body sent to the endpoint
+    payload TEXT NOT NULL,
+
+    status webhook_delivery_status NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+
+    -- When the next attempt is due, while the delivery is pending
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_attempt_at TIMESTAMPTZ,
+
+    -- The HTTP status or error of the last attempt
+    last_response_status INTEGER,
+    last_error TEXT,
+
+    created_on TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Index for the delivery worker picking up due deliveries
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_pending
+    ON webhook_deliveries (next_attempt_at)
+    WHERE status = 'pending';
+
+-- Index for listing an endpoint's delivery attempts
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_endpoint
+    ON webhook_deliveries (endpoint_id, created_on DESC);
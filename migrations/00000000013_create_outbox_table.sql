@@ -0,0 +1,47 @@
+-- ============================================================================
+-- Migration: 00000000013_create_outbox_table.sql
+-- Purpose:   Store side effects (emails and webhook events) in a transactional
+--            outbox, so they are not lost if the process stops mid-request.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Creates the outbox_status enum type
+--   - Creates the outbox table. Entries are written in the same transaction as
+--     the change that triggers them, then processed by a background dispatcher
+--     with retries
+-- ============================================================================
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'outbox_status') THEN
+        CREATE TYPE outbox_status AS ENUM ('pending', 'processed', 'failed');
+    END IF;
+END$$;
+
+CREATE TABLE IF NOT EXISTS outbox (
+    id UUID PRIMARY KEY,
+
+    -- What the entry does, e.g. 'email' or 'webhook'
+    kind TEXT NOT NULL,
+
+    -- The side effect, as JSON
+    payload JSONB NOT NULL,
+
+    status outbox_status NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+
+    -- When the next attempt is due, while the entry is pending
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    -- Why the last attempt failed
+    last_error TEXT,
+
+    created_on TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    processed_on TIMESTAMPTZ
+);
+
+-- Index for the dispatcher picking up due entries
+CREATE INDEX IF NOT EXISTS idx_outbox_pending
+    ON outbox (next_attempt_at)
+    WHERE status = 'pending';
@@ -0,0 +1,42 @@
+-- ============================================================================
+-- Migration: 00000000014_create_email_changes_table.sql
+-- Purpose:   Store pending email address changes, confirmed from both the old
+--            and new addresses before they take effect.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Creates the email_changes table. A token is sent to each address and
+--     only their SHA-256 hashes are stored
+--   - The change is applied once both tokens are confirmed, before it expires
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS email_changes (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    -- The address the user's email is changed to
+    new_email TEXT NOT NULL,
+
+    -- Hex encoded SHA-256 hashes of the tokens sent to each address
+    old_token_hash TEXT NOT NULL UNIQUE,
+    new_token_hash TEXT NOT NULL UNIQUE,
+
+    -- Set when each address confirms the change
+    old_confirmed_on TIMESTAMPTZ,
+    new_confirmed_on TIMESTAMPTZ,
+
+    -- The admin who requested the change
+    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+
+    expires_on TIMESTAMPTZ NOT NULL,
+    created_on TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    -- Set when the user's email is changed
+    completed_on TIMESTAMPTZ
+);
+
+-- Index for finding a user's pending change
+CREATE INDEX IF NOT EXISTS idx_email_changes_user_id
+    ON email_changes (user_id)
+    WHERE completed_on IS NULL;
@@ -0,0 +1,31 @@
+-- ============================================================================
+-- Migration: 00000000015_create_login_throttles_table.sql
+-- Purpose:   Count failed logins per IP address and email, so repeated
+--            failures are locked out with an increasing delay.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Creates the login_throttles table, keyed by IP address and email. The
+--     email is stored lower case and does not need to belong to a user
+--   - Rows are deleted after a successful login, or pruned once stale
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS login_throttles (
+    ip_address TEXT NOT NULL,
+    email TEXT NOT NULL,
+
+    -- Failed logins since the count was last reset
+    failures INTEGER NOT NULL DEFAULT 0,
+
+    -- Logins are rejected until this time
+    locked_until TIMESTAMPTZ,
+
+    last_failure_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (ip_address, email)
+);
+
+-- Index for pruning stale throttles
+CREATE INDEX IF NOT EXISTS idx_login_throttles_last_failure_at
+    ON login_throttles (last_failure_at);
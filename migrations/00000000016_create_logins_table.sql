@@ -0,0 +1,37 @@
+-- ============================================================================
+-- Migration: 00000000016_create_logins_table.sql
+-- Purpose:   Record every login attempt, so users can review their login
+--            history.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Creates the login_outcome enum type
+--   - Creates the logins table. Failed logins for an unknown email have no
+--     user id
+--   - Adds an index for paging a user's logins, newest first
+-- ============================================================================
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'login_outcome') THEN
+        CREATE TYPE login_outcome AS ENUM ('success', 'failed', 'throttled');
+    END IF;
+END$$;
+
+CREATE TABLE IF NOT EXISTS logins (
+    id UUID PRIMARY KEY,
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+
+    -- The email the login was for, as sent
+    email TEXT NOT NULL,
+
+    ip_address TEXT NOT NULL,
+    user_agent TEXT,
+    outcome login_outcome NOT NULL,
+    created_on TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Index for cursor paging a user's login history
+CREATE INDEX IF NOT EXISTS idx_logins_user_id_created_on
+    ON logins (user_id, created_on DESC, id DESC);
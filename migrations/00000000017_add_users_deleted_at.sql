@@ -0,0 +1,19 @@
+-- ============================================================================
+-- Migration: 00000000017_add_users_deleted_at.sql
+-- Purpose:   Soft delete users, so they can be restored before being purged.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Adds the deleted_at column to the users table. Users with a deleted_at
+--     are excluded from reads until restored
+--   - Adds an index for purging users deleted a while ago
+-- ============================================================================
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+-- Index for purging soft deleted users
+CREATE INDEX IF NOT EXISTS idx_users_deleted_at
+    ON users (deleted_at)
+    WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,43 @@
+-- ============================================================================
+-- Migration: 00000000018_create_action_tokens_table.sql
+-- Purpose:   Store one-time action tokens, emailed to users to confirm an
+--            action such as deleting their account or a magic link login.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Creates the action_purpose enum type
+--   - Creates the action_tokens table. Only the SHA-256 hash of each token is
+--     stored, and a token is used by setting used_on
+--   - Adds an index for replacing a user's unused tokens for a purpose
+-- ============================================================================
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'action_purpose') THEN
+        CREATE TYPE action_purpose AS ENUM ('email_change', 'account_deletion', 'magic_link');
+    END IF;
+END$$;
+
+CREATE TABLE IF NOT EXISTS action_tokens (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    purpose action_purpose NOT NULL,
+
+    -- Hex encoded SHA-256 hash of the token
+    token_hash TEXT NOT NULL UNIQUE,
+
+    -- Flow specific data confirmed by the token, e.g. a new email address
+    payload TEXT,
+
+    expires_on TIMESTAMPTZ NOT NULL,
+    created_on TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    -- Set when the token is used, so it can't be used again
+    used_on TIMESTAMPTZ
+);
+
+-- Index for finding a user's unused tokens for a purpose
+CREATE INDEX IF NOT EXISTS idx_action_tokens_user_id_purpose
+    ON action_tokens (user_id, purpose)
+    WHERE used_on IS NULL;
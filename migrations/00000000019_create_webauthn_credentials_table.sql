@@ -0,0 +1,57 @@
+-- ============================================================================
+-- Migration: 00000000019_create_webauthn_credentials_table.sql
+-- Purpose:   Store WebAuthn passkeys registered by users, and the state of
+--            passkey registrations and logins in progress.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Creates the webauthn_credentials table, one row per registered passkey
+--   - Creates the passkey_ceremony enum type
+--   - Creates the passkey_challenges table. A challenge is deleted when the
+--     registration or login is finished, so it can only be used once
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS webauthn_credentials (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    -- Hex encoded credential id chosen by the authenticator
+    credential_id TEXT NOT NULL UNIQUE,
+
+    -- Name the user gave the passkey, e.g. "Work laptop"
+    name TEXT NOT NULL,
+
+    -- The serialised webauthn-rs passkey, its public key and sign count
+    passkey TEXT NOT NULL,
+
+    created_on TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_on TIMESTAMPTZ
+);
+
+-- Index for loading a user's passkeys when they log in
+CREATE INDEX IF NOT EXISTS idx_webauthn_credentials_user_id
+    ON webauthn_credentials (user_id);
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'passkey_ceremony') THEN
+        CREATE TYPE passkey_ceremony AS ENUM ('registration', 'authentication');
+    END IF;
+END$$;
+
+CREATE TABLE IF NOT EXISTS passkey_challenges (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    ceremony passkey_ceremony NOT NULL,
+
+    -- The serialised webauthn-rs registration or authentication state
+    state TEXT NOT NULL,
+
+    expires_on TIMESTAMPTZ NOT NULL,
+    created_on TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Index for pruning expired challenges
+CREATE INDEX IF NOT EXISTS idx_passkey_challenges_expires_on
+    ON passkey_challenges (expires_on);
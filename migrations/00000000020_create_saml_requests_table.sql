@@ -0,0 +1,23 @@
+-- ============================================================================
+-- Migration: 00000000020_create_saml_requests_table.sql
+-- Purpose:   Track the SAML authentication requests sent to the identity
+--            provider, so each response is only accepted once.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Creates the saml_requests table. A request is deleted when the identity
+--     provider's response to it is accepted, so a response can't be replayed
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS saml_requests (
+    -- The AuthnRequest ID, echoed in the response InResponseTo
+    id TEXT PRIMARY KEY,
+
+    expires_on TIMESTAMPTZ NOT NULL,
+    created_on TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Index for pruning expired requests
+CREATE INDEX IF NOT EXISTS idx_saml_requests_expires_on
+    ON saml_requests (expires_on);
@@ -0,0 +1,48 @@
+-- ============================================================================
+-- Migration: 00000000021_create_device_codes_table.sql
+-- Purpose:   Store OAuth device authorization grants, for clients such as
+--            CLIs and IoT devices that log in by the user approving them on
+--            another device.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Creates the device_code_status enum type
+--   - Creates the device_codes table. Only the SHA-256 hash of the device code
+--     is stored, and a grant is deleted when its tokens are issued
+-- ============================================================================
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'device_code_status') THEN
+        CREATE TYPE device_code_status AS ENUM ('pending', 'approved', 'denied');
+    END IF;
+END$$;
+
+CREATE TABLE IF NOT EXISTS device_codes (
+    id UUID PRIMARY KEY,
+
+    -- Hex encoded SHA-256 hash of the device code the device polls with
+    device_code_hash TEXT NOT NULL UNIQUE,
+
+    -- Short code the user enters to approve the device, e.g. BCDF-GHJK
+    user_code TEXT NOT NULL UNIQUE,
+
+    -- Name the device gave itself, shown to the user when approving it
+    client_name TEXT,
+
+    status device_code_status NOT NULL DEFAULT 'pending',
+
+    -- The user who approved or denied the device
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+
+    -- When the device last polled, so it can be told to slow down
+    last_polled_on TIMESTAMPTZ,
+
+    expires_on TIMESTAMPTZ NOT NULL,
+    created_on TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Index for pruning expired grants
+CREATE INDEX IF NOT EXISTS idx_device_codes_expires_on
+    ON device_codes (expires_on);
@@ -0,0 +1,35 @@
+-- ============================================================================
+-- Migration: 00000000022_create_api_clients_table.sql
+-- Purpose:   Store API clients, trusted backend services that get short lived
+--            access tokens with the OAuth client credentials grant.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Creates the api_clients table. The client id is the row id, and only the
+--     SHA-256 hash of the client secret is stored, the secret itself is shown
+--     once when the client is created
+--   - Clients are granted a list of scopes and can be revoked
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS api_clients (
+    -- The client id, sent with the secret
+    id UUID PRIMARY KEY,
+
+    -- Human readable name, e.g. the service using the client
+    name TEXT NOT NULL,
+
+    -- The start of the secret, safe to display so secrets can be told apart
+    secret_prefix TEXT NOT NULL,
+
+    -- Hex encoded SHA-256 hash of the secret
+    secret_hash TEXT NOT NULL UNIQUE,
+
+    -- The scopes the client's access tokens may carry
+    scopes TEXT[] NOT NULL DEFAULT '{}',
+
+    created_on TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    -- Set when the client is revoked
+    revoked_on TIMESTAMPTZ
+);
@@ -0,0 +1,41 @@
+-- ============================================================================
+-- Migration: 00000000023_create_clients_table.sql
+-- Purpose:   Register OAuth clients, confidential or public, with their
+--            redirect URIs, allowed grants and scopes.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Creates the client_type enum type
+--   - Renames the api_clients table to clients, keeping the registered API
+--     clients as confidential clients allowed the client credentials grant
+--   - Adds the client type, redirect URIs and allowed grants. Public clients
+--     can't keep a secret, so they have none
+-- ============================================================================
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'client_type') THEN
+        CREATE TYPE client_type AS ENUM ('confidential', 'public');
+    END IF;
+END$$;
+
+ALTER TABLE IF EXISTS api_clients RENAME TO clients;
+
+ALTER TABLE clients
+    -- Confidential clients authenticate with their secret, public clients
+    -- (e.g. single page and mobile apps) can't keep one
+    ADD COLUMN IF NOT EXISTS client_type client_type NOT NULL DEFAULT 'confidential',
+
+    -- The exact URIs the client may be redirected back to
+    ADD COLUMN IF NOT EXISTS redirect_uris TEXT[] NOT NULL DEFAULT '{}',
+
+    -- The OAuth grant types the client may use
+    ADD COLUMN IF NOT EXISTS grant_types TEXT[] NOT NULL DEFAULT '{client_credentials}',
+
+    ALTER COLUMN secret_prefix DROP NOT NULL,
+    ALTER COLUMN secret_hash DROP NOT NULL;
+
+ALTER TABLE clients
+    ADD CONSTRAINT clients_secret_matches_type
+    CHECK ((client_type = 'confidential') = (secret_hash IS NOT NULL));
@@ -0,0 +1,47 @@
+-- ============================================================================
+-- Migration: 00000000024_create_grants_table.sql
+-- Purpose:   Record which OAuth clients each user has granted access to, so
+--            users can see and revoke third party access.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Creates the grants table, one grant per user and client, kept when
+--     revoked so the user can see when they revoked it
+--   - Adds the client a device authorization is for to device_codes
+--   - Adds the client a session was started for to sessions, so it only
+--     refreshes while the grant is valid
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS grants (
+    id UUID PRIMARY KEY,
+
+    -- The user who granted access
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    -- The client the user granted access to
+    client_id UUID NOT NULL REFERENCES clients(id) ON DELETE CASCADE,
+
+    -- The scopes the user consented to
+    scopes TEXT[] NOT NULL DEFAULT '{}',
+
+    created_on TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    -- When the user last consented, granting the client again updates it
+    updated_on TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    -- Set when the user revokes the grant
+    revoked_on TIMESTAMPTZ,
+
+    UNIQUE (user_id, client_id)
+);
+
+ALTER TABLE device_codes
+    ADD COLUMN IF NOT EXISTS client_id UUID REFERENCES clients(id) ON DELETE CASCADE;
+
+ALTER TABLE sessions
+    ADD COLUMN IF NOT EXISTS client_id UUID REFERENCES clients(id) ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS sessions_user_id_client_id_idx
+    ON sessions (user_id, client_id)
+    WHERE client_id IS NOT NULL;
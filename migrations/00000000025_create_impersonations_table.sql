@@ -0,0 +1,39 @@
+-- ============================================================================
+-- Migration: 00000000025_create_impersonations_table.sql
+-- Purpose:   Record each time an admin impersonates a user, as an audit trail
+--            and so impersonation tokens can be revoked centrally.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Creates the impersonations table. It has no foreign keys, so the audit
+--     trail is kept when the admin or user is deleted
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS impersonations (
+    id UUID PRIMARY KEY,
+
+    -- The admin who impersonated the user
+    admin_id UUID NOT NULL,
+
+    -- The user being impersonated
+    user_id UUID NOT NULL,
+
+    -- Why the admin impersonated the user
+    reason TEXT NOT NULL,
+
+    -- The jti of the impersonation access token, so it can be denied
+    access_token_id UUID NOT NULL UNIQUE,
+
+    created_on TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    -- When the impersonation access token expires
+    expires_on TIMESTAMPTZ NOT NULL,
+
+    -- Set when the impersonation is revoked, and by which admin
+    revoked_on TIMESTAMPTZ,
+    revoked_by UUID
+);
+
+CREATE INDEX IF NOT EXISTS impersonations_created_on_idx
+    ON impersonations (created_on DESC);
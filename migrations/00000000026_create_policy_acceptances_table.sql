@@ -0,0 +1,29 @@
+-- ============================================================================
+-- Migration: 00000000026_create_policy_acceptances_table.sql
+-- Purpose:   Record which terms of service and privacy policy versions each
+--            user has accepted, and when and where from.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Creates the policy_acceptances table, one acceptance per user, policy
+--     and version
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS policy_acceptances (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    -- The policy accepted, terms_of_service or privacy_policy
+    policy TEXT NOT NULL,
+
+    -- The configured version of the policy that was accepted
+    version TEXT NOT NULL,
+
+    -- The address the acceptance came from, when known
+    ip_address TEXT,
+
+    accepted_on TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    UNIQUE (user_id, policy, version)
+);
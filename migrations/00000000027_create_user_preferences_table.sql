@@ -0,0 +1,26 @@
+-- ============================================================================
+-- Migration: 00000000027_create_user_preferences_table.sql
+-- Purpose:   Store each user's preferences, such as their time zone and the
+--            notifications they opted out of, next to the locale on users.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Creates the user_preferences table, one row per user. Users without a
+--     row have the default preferences
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS user_preferences (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+
+    -- IANA time zone name, e.g. Australia/Brisbane
+    timezone TEXT NOT NULL DEFAULT 'UTC',
+
+    -- The notifications the user does not want, e.g. security_alert
+    notification_opt_outs TEXT[] NOT NULL DEFAULT '{}',
+
+    -- Client specific preferences, a JSON object
+    custom JSONB NOT NULL DEFAULT '{}',
+
+    updated_on TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,23 @@
+-- ============================================================================
+-- Migration: 00000000028_create_avatars_table.sql
+-- Purpose:   Record the avatar image each user uploaded, and where it is
+--            stored.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Creates the avatars table, one row per user. The image itself is kept
+--     in the configured storage backend, a local directory or an S3 bucket
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS avatars (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+
+    -- The object key in the storage backend, e.g. <user id>/<sha256>.png.
+    -- A new image gets a new key, so clients don't cache a replaced avatar
+    storage_key TEXT NOT NULL,
+
+    content_type TEXT NOT NULL,
+    size_bytes INTEGER NOT NULL CHECK (size_bytes > 0),
+    updated_on TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,38 @@
+-- ============================================================================
+-- Migration: 00000000029_create_federated_identities_table.sql
+-- Purpose:   Link identities at an external identity provider, such as a SAML
+--            NameID, to an existing user account.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Creates the federated_identities table. A linked identity logs in to
+--     its user, whatever email the identity provider asserts
+--   - Adds link_user_id to saml_requests, set when the request links the
+--     identity in the response to a signed in user instead of logging in
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS federated_identities (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    -- The identity provider, e.g. the SAML identity provider entity id
+    provider TEXT NOT NULL,
+
+    -- The identity at the provider, e.g. the SAML NameID
+    subject TEXT NOT NULL,
+
+    -- The email the provider asserted when the identity was linked
+    email TEXT NOT NULL,
+
+    created_on TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    -- An identity can only be linked to one user
+    UNIQUE (provider, subject)
+);
+
+CREATE INDEX IF NOT EXISTS idx_federated_identities_user_id
+    ON federated_identities (user_id);
+
+ALTER TABLE saml_requests
+    ADD COLUMN IF NOT EXISTS link_user_id UUID REFERENCES users(id) ON DELETE CASCADE;
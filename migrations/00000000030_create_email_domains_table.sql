@@ -0,0 +1,27 @@
+-- ============================================================================
+-- Migration: 00000000030_create_email_domains_table.sql
+-- Purpose:   Record the email domains admins allow or block from registering,
+--            next to the lists in the configuration.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Creates the email_domain_rule enum type
+--   - Creates the email_domains table, one rule per domain. A rule also
+--     covers the domain's subdomains
+-- ============================================================================
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'email_domain_rule') THEN
+        CREATE TYPE email_domain_rule AS ENUM ('allow', 'block');
+    END IF;
+END$$;
+
+CREATE TABLE IF NOT EXISTS email_domains (
+    -- Lower case domain name, e.g. example.com
+    domain TEXT PRIMARY KEY,
+
+    rule email_domain_rule NOT NULL,
+    created_on TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,16 @@
+-- ============================================================================
+-- Migration: 00000000031_add_sessions_activity.sql
+-- Purpose:   Record when each session was last used, so users can spot stale
+--            or hijacked sessions.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Adds last_used_at to sessions, the last time the refresh token or an
+--     access token issued for the session was used
+--   - Adds request_count to sessions, the number of requests made with them
+-- ============================================================================
+
+ALTER TABLE sessions
+    ADD COLUMN IF NOT EXISTS last_used_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS request_count BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,19 @@
+-- ============================================================================
+-- Migration: 00000000032_add_password_reset_action_purpose.sql
+-- Purpose:   Sign password reset tokens as action tokens, their hashes are
+--            stored in password_resets.token.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Adds password_reset to the action_purpose enum type, so the type
+--     matches domain::ActionPurpose
+--   - Adds an index for invalidating a user's unused password resets
+-- ============================================================================
+
+ALTER TYPE action_purpose ADD VALUE IF NOT EXISTS 'password_reset';
+
+-- Index for finding a user's unused password resets
+CREATE INDEX IF NOT EXISTS idx_password_resets_user_id_unused
+    ON password_resets (user_id)
+    WHERE is_used = FALSE;
@@ -0,0 +1,14 @@
+-- ============================================================================
+-- Migration: 00000000033_add_users_must_change_password.sql
+-- Purpose:   Let admins force a user to choose a new password before they can
+--            log in with a password again.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Adds the must_change_password column to the users table. It is set by
+--     ForcePasswordReset and cleared when a password reset is completed
+-- ============================================================================
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS must_change_password BOOLEAN NOT NULL DEFAULT FALSE;
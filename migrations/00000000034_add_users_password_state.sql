@@ -0,0 +1,34 @@
+-- ============================================================================
+-- Migration: 00000000034_add_users_password_state.sql
+-- Purpose:   Track whether a user's password is usable, so imported users can
+--            be issued a temporary password for their first login only.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Creates the password_state enum type, matching database::PasswordState
+--   - Adds the password_state column to the users table
+--   - Moves forced password changes from must_change_password into it, then
+--     drops must_change_password
+-- ============================================================================
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'password_state') THEN
+        CREATE TYPE password_state AS ENUM ('set', 'temporary', 'change_required');
+    END IF;
+END$$;
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS password_state password_state NOT NULL DEFAULT 'set';
+
+DO $$
+BEGIN
+    IF EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'users' AND column_name = 'must_change_password'
+    ) THEN
+        UPDATE users SET password_state = 'change_required' WHERE must_change_password;
+        ALTER TABLE users DROP COLUMN must_change_password;
+    END IF;
+END$$;
@@ -0,0 +1,22 @@
+-- ============================================================================
+-- Migration: 00000000035_add_users_verification_reminders.sql
+-- Purpose:   Track the verification reminders sent to unverified users, so
+--            the reminder job sends each user a limited number with
+--            increasing intervals.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Adds the verification_reminders_sent and verification_reminded_at
+--     columns to the users table
+--   - Adds an index for finding unverified users by when they registered
+-- ============================================================================
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS verification_reminders_sent INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS verification_reminded_at TIMESTAMPTZ;
+
+-- Index for finding unverified users to remind or deactivate
+CREATE INDEX IF NOT EXISTS idx_users_unverified_created_on
+    ON users (created_on)
+    WHERE is_verified = FALSE AND deleted_at IS NULL;
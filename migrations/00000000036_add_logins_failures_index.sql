@@ -0,0 +1,15 @@
+-- ============================================================================
+-- Migration: 00000000036_add_logins_failures_index.sql
+-- Purpose:   Find the failed and throttled logins within a time window, for
+--            the admin security report.
+-- Author:    Ian Teda
+-- Date:      2026-10-15
+--
+-- This migration:
+--   - Adds an index on when unsuccessful logins were made
+-- ============================================================================
+
+-- Index for grouping recent failed logins by IP address and email
+CREATE INDEX IF NOT EXISTS idx_logins_failures_created_on
+    ON logins (created_on)
+    WHERE outcome <> 'success';
@@ -0,0 +1,224 @@
+//-- ./src/avatars/local.rs
+
+// #![allow(unused)] // For development only
+
+//! # Local Avatar Storage
+//!
+//! Store avatars as files in `avatars.local_directory`. The HTTP gateway serves
+//! them at `<public_url>/avatars/<key>?expires=<unix time>&signature=<hmac>`.
+//! The signature is an HMAC of the key and expiry, keyed by the token secret,
+//! so URLs can't be forged or used once expired.
+//! ---
+
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use secrecy::{ExposeSecret, SecretString};
+use sha2::Sha256;
+
+use crate::avatars::{is_valid_key, AvatarStorage};
+use crate::configuration::AvatarsConfiguration;
+use crate::prelude::*;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Avatars stored as files in a local directory
+#[derive(Debug, Clone)]
+pub struct LocalAvatarStorage {
+    directory: PathBuf,
+    public_url: String,
+    secret: SecretString,
+}
+
+impl LocalAvatarStorage {
+    /// Create the storage for the configured directory, signing URLs with the
+    /// token secret
+    pub fn new(
+        config: &AvatarsConfiguration,
+        token_secret: &SecretString,
+    ) -> Result<Self, AuthenticationError> {
+        let public_url = config.public_url.as_deref().ok_or_else(|| {
+            AuthenticationError::ValidationError(
+                "avatars.public_url is required when avatars.backend is local".to_string(),
+            )
+        })?;
+
+        Ok(Self {
+            directory: PathBuf::from(&config.local_directory),
+            public_url: public_url.trim_end_matches('/').to_string(),
+            secret: token_secret.clone(),
+        })
+    }
+
+    /// Was the URL signed for the key, and has it not expired
+    pub fn verify(&self, key: &str, expires: i64, signature: &str) -> bool {
+        if expires < Utc::now().timestamp()
+            || signature.len() != 64
+            || !signature.chars().all(|c| c.is_ascii_hexdigit())
+        {
+            return false;
+        }
+
+        let Ok(signature) = (0..signature.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&signature[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+        else {
+            return false;
+        };
+
+        // Compare in constant time
+        self.mac(key, expires).verify_slice(&signature).is_ok()
+    }
+
+    /// Read the image stored under the key, `None` when there isn't one
+    pub async fn read(&self, key: &str) -> Result<Option<Vec<u8>>, AuthenticationError> {
+        match tokio::fs::read(self.path(key)?).await {
+            Ok(image) => Ok(Some(image)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(AuthenticationError::AvatarStorage(e.to_string())),
+        }
+    }
+
+    /// The file the key is stored in, only valid keys are allowed in the path
+    fn path(&self, key: &str) -> Result<PathBuf, AuthenticationError> {
+        if !is_valid_key(key) {
+            return Err(AuthenticationError::AvatarStorage(format!(
+                "Invalid avatar key: {key}"
+            )));
+        }
+
+        Ok(self.directory.join(key))
+    }
+
+    /// HMAC of the key and expiry, keyed by the secret
+    fn mac(&self, key: &str, expires: i64) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(self.secret.expose_secret().as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(format!("avatar.{key}.{expires}").as_bytes());
+        mac
+    }
+}
+
+#[tonic::async_trait]
+impl AvatarStorage for LocalAvatarStorage {
+    #[tracing::instrument(name = "Write avatar file: ", skip(self, image))]
+    async fn put(&self, key: &str, image: Vec<u8>) -> Result<(), AuthenticationError> {
+        let path = self.path(key)?;
+        let storage_error = |e: std::io::Error| AuthenticationError::AvatarStorage(e.to_string());
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(storage_error)?;
+        }
+
+        // Write then rename, so the gateway never serves a partial file
+        let partial = path.with_extension("partial");
+        tokio::fs::write(&partial, image).await.map_err(storage_error)?;
+        tokio::fs::rename(&partial, &path).await.map_err(storage_error)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Remove avatar file: ", skip(self))]
+    async fn delete(&self, key: &str) -> Result<(), AuthenticationError> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => {
+                Err(AuthenticationError::AvatarStorage(e.to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn signed_url(&self, key: &str, expires_in: Duration) -> Result<String, AuthenticationError> {
+        let expires_in = i64::try_from(expires_in.as_secs()).unwrap_or(i64::MAX);
+        let expires = Utc::now().timestamp().saturating_add(expires_in);
+        let signature = format!("{:x}", self.mac(key, expires).finalize().into_bytes());
+
+        Ok(format!(
+            "{}/avatars/{key}?expires={expires}&signature={signature}",
+            self.public_url
+        ))
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use uuid::Uuid;
+
+    use crate::configuration::AvatarStorageBackend;
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    /// Local storage in a throwaway directory
+    fn storage() -> Result<LocalAvatarStorage> {
+        let config = AvatarsConfiguration {
+            backend: AvatarStorageBackend::Local,
+            local_directory: std::env::temp_dir()
+                .join(format!("avatars-{}", Uuid::new_v4()))
+                .to_string_lossy()
+                .to_string(),
+            public_url: Some("http://localhost:8082/".to_string()),
+            ..AvatarsConfiguration::default()
+        };
+
+        Ok(LocalAvatarStorage::new(&config, &SecretString::from("secret"))?)
+    }
+
+    #[tokio::test]
+    async fn stored_avatars_are_read_back_and_removed() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let storage = storage()?;
+        let key = database::Avatars::new(&Uuid::now_v7(), b"image", "image/png").storage_key;
+
+        //-- Execute Function (Act)
+        storage.put(&key, b"image".to_vec()).await?;
+        let stored = storage.read(&key).await?;
+        storage.delete(&key).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(stored, Some(b"image".to_vec()));
+        assert_eq!(storage.read(&key).await?, None);
+        assert!(storage.delete(&key).await.is_ok());
+        assert!(storage.put("../escape", Vec::new()).await.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn signed_urls_are_verified() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let storage = storage()?;
+        let key = database::Avatars::new(&Uuid::now_v7(), b"image", "image/png").storage_key;
+
+        //-- Execute Function (Act)
+        let url = storage.signed_url(&key, Duration::from_secs(60))?;
+
+        //-- Checks (Assertions)
+        let query = url
+            .strip_prefix(&format!("http://localhost:8082/avatars/{key}?"))
+            .ok_or("URL is not for the gateway")?;
+        let (expires, signature) = query
+            .strip_prefix("expires=")
+            .and_then(|query| query.split_once("&signature="))
+            .ok_or("URL is not signed")?;
+        let expires: i64 = expires.parse()?;
+
+        assert!(storage.verify(&key, expires, signature));
+        assert!(!storage.verify(&key, expires + 1, signature));
+        assert!(!storage.verify(&key, expires, &"0".repeat(64)));
+        let expired = Utc::now().timestamp() - 1;
+        let expired_signature = format!("{:x}", storage.mac(&key, expired).finalize().into_bytes());
+        assert!(!storage.verify(&key, expired, &expired_signature));
+
+        Ok(())
+    }
+}
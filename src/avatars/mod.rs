@@ -0,0 +1,198 @@
+//-- ./src/avatars/mod.rs
+
+// #![allow(unused)] // For development only
+
+//! # Avatars Module
+//!
+//! Check, resize and store the avatar images users upload, and sign the URLs
+//! clients display them with.
+//!
+//! Uploads must be a PNG, JPEG or WebP image. They are cropped to a square,
+//! resized to `avatars.size_pixels` and re-encoded as PNG, which also drops
+//! metadata such as an EXIF location. Each image is stored under
+//! `<user id>/<sha256>`, see `database::Avatars`.
+//!
+//! ## Storage
+//! - **LocalAvatarStorage**: Files in `avatars.local_directory`, served by the
+//!   HTTP gateway at `/avatars/<key>` with an HMAC signed query
+//! - **S3AvatarStorage**: Objects in an S3 compatible bucket, displayed with
+//!   AWS Signature Version 4 presigned URLs
+//! ---
+
+use std::io::Cursor;
+use std::sync::Arc;
+use std::time::Duration;
+
+use image::imageops::FilterType;
+use image::{ImageFormat, ImageReader, Limits};
+use secrecy::SecretString;
+use uuid::Uuid;
+
+use crate::configuration::{AvatarStorageBackend, AvatarsConfiguration};
+use crate::prelude::*;
+
+mod local;
+mod s3;
+
+pub use local::LocalAvatarStorage;
+pub use s3::S3AvatarStorage;
+
+/// The media type avatars are stored as
+pub const CONTENT_TYPE: &str = "image/png";
+
+/// The largest width or height of an upload, so a small file can't decode to
+/// a huge image
+const MAX_UPLOAD_DIMENSION: u32 = 8192;
+
+/// Stores avatar images and signs the URLs clients display them with
+#[tonic::async_trait]
+pub trait AvatarStorage: Send + Sync {
+    /// Store the image under the key, replacing any image already there
+    async fn put(&self, key: &str, image: Vec<u8>) -> Result<(), AuthenticationError>;
+
+    /// Remove the image stored under the key, if there is one
+    async fn delete(&self, key: &str) -> Result<(), AuthenticationError>;
+
+    /// A URL for the image that can be used until it expires
+    fn signed_url(&self, key: &str, expires_in: Duration) -> Result<String, AuthenticationError>;
+}
+
+/// Check the upload is a supported image, then crop and resize it to a square
+/// PNG `size` pixels wide. Decoding is CPU bound, so call this off the async
+/// runtime.
+pub fn process(upload: &[u8], size: u32) -> Result<Vec<u8>, AuthenticationError> {
+    let format = image::guess_format(upload)
+        .ok()
+        .filter(|format| {
+            matches!(format, ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::WebP)
+        })
+        .ok_or_else(|| {
+            AuthenticationError::InvalidImage(
+                "Avatars must be a PNG, JPEG or WebP image".to_string(),
+            )
+        })?;
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_UPLOAD_DIMENSION);
+    limits.max_image_height = Some(MAX_UPLOAD_DIMENSION);
+
+    let mut reader = ImageReader::with_format(Cursor::new(upload), format);
+    reader.limits(limits);
+    let image = reader.decode().map_err(|e| {
+        AuthenticationError::InvalidImage(format!("Unable to decode the avatar: {e}"))
+    })?;
+
+    let mut avatar = Cursor::new(Vec::new());
+    image
+        .resize_to_fill(size, size, FilterType::Lanczos3)
+        .write_to(&mut avatar, ImageFormat::Png)
+        .map_err(|e| AuthenticationError::Generic(format!("Unable to encode the avatar: {e}")))?;
+
+    Ok(avatar.into_inner())
+}
+
+/// Is the key a `<user id>/<sha256>` storage key, so it is safe to use in a
+/// file path or URL
+pub fn is_valid_key(key: &str) -> bool {
+    let Some((user_id, hash)) = key.split_once('/') else {
+        return false;
+    };
+
+    Uuid::parse_str(user_id).is_ok_and(|id| id.to_string() == user_id)
+        && hash.len() == 64
+        && hash.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'))
+}
+
+/// Build the avatar storage for the configured backend, `None` when avatars
+/// can't be uploaded. Local URLs are signed with the token secret.
+pub fn storage_from_config(
+    config: &AvatarsConfiguration,
+    token_secret: &SecretString,
+) -> Result<Option<Arc<dyn AvatarStorage>>, AuthenticationError> {
+    match config.backend {
+        AvatarStorageBackend::None => Ok(None),
+        AvatarStorageBackend::Local => {
+            Ok(Some(Arc::new(LocalAvatarStorage::new(config, token_secret)?)))
+        }
+        AvatarStorageBackend::S3 => Ok(Some(Arc::new(S3AvatarStorage::new(config)?))),
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use image::{ImageBuffer, Rgb};
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    /// Encode a solid image in the format
+    fn upload(width: u32, height: u32, format: ImageFormat) -> Result<Vec<u8>> {
+        let mut upload = Cursor::new(Vec::new());
+        ImageBuffer::from_pixel(width, height, Rgb([200u8, 30, 90]))
+            .write_to(&mut upload, format)?;
+
+        Ok(upload.into_inner())
+    }
+
+    #[test]
+    fn uploads_are_cropped_to_a_square_png() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let upload = upload(600, 300, ImageFormat::Jpeg)?;
+
+        //-- Execute Function (Act)
+        let avatar = process(&upload, 128)?;
+
+        //-- Checks (Assertions)
+        assert_eq!(image::guess_format(&avatar)?, ImageFormat::Png);
+        let decoded = image::load_from_memory(&avatar)?;
+        assert_eq!((decoded.width(), decoded.height()), (128, 128));
+
+        Ok(())
+    }
+
+    #[test]
+    fn only_supported_images_are_accepted() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let truncated = upload(16, 16, ImageFormat::Png)?[..40].to_vec();
+
+        //-- Checks (Assertions)
+        assert!(matches!(
+            process(b"GIF89a not supported", 128),
+            Err(AuthenticationError::InvalidImage(_))
+        ));
+        assert!(matches!(
+            process(&truncated, 128),
+            Err(AuthenticationError::InvalidImage(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn keys_are_a_user_id_and_hash() {
+        let user_id = Uuid::now_v7();
+        let hash = "a".repeat(64);
+
+        assert!(is_valid_key(&format!("{user_id}/{hash}")));
+        assert!(!is_valid_key(&format!("{user_id}/../{hash}")));
+        assert!(!is_valid_key(&format!("{user_id}/{}", "A".repeat(64))));
+        assert!(!is_valid_key(&format!("{}/{hash}", user_id.simple())));
+        assert!(!is_valid_key(&hash));
+    }
+
+    #[test]
+    fn no_storage_without_a_backend() -> Result<()> {
+        let storage = storage_from_config(
+            &AvatarsConfiguration::default(),
+            &SecretString::from("secret"),
+        )?;
+
+        assert!(storage.is_none());
+
+        Ok(())
+    }
+}
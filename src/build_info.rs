@@ -0,0 +1,68 @@
+//-- ./src/build_info.rs
+
+// #![allow(unused)] // For development only
+
+//! # Build Information
+//!
+//! What was built, for the `ServerInfo` RPC. `build.rs` sets the git commit
+//! and build time, `unknown` and the Unix epoch when it can't read them, e.g.
+//! when building from a source archive. Set `SOURCE_DATE_EPOCH` for
+//! reproducible builds.
+//! ---
+
+use chrono::{DateTime, Utc};
+
+/// The crate version, e.g. `0.1.2`
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The short git commit the server was built from
+pub const GIT_SHA: &str = env!("GIT_SHA");
+
+/// When the server was built, in seconds since the Unix epoch
+const BUILD_TIMESTAMP: &str = env!("BUILD_TIMESTAMP");
+
+/// The optional cargo features, by name
+const FEATURES: [(&str, bool); 7] = [
+    ("demo", cfg!(feature = "demo")),
+    ("nats", cfg!(feature = "nats")),
+    ("kafka", cfg!(feature = "kafka")),
+    ("ldap", cfg!(feature = "ldap")),
+    ("saml", cfg!(feature = "saml")),
+    ("fault_injection", cfg!(feature = "fault_injection")),
+    ("sentry", cfg!(feature = "sentry")),
+];
+
+/// When the server was built
+pub fn build_time() -> DateTime<Utc> {
+    BUILD_TIMESTAMP
+        .parse()
+        .ok()
+        .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+        .unwrap_or_default()
+}
+
+/// The optional cargo features the server was built with
+pub fn enabled_features() -> Vec<&'static str> {
+    FEATURES
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(feature, _)| *feature)
+        .collect()
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_information_is_set() {
+        assert_eq!(VERSION, env!("CARGO_PKG_VERSION"));
+        assert!(!GIT_SHA.is_empty());
+        assert!(build_time() > DateTime::<Utc>::default());
+        assert_eq!(
+            enabled_features().contains(&"fault_injection"),
+            cfg!(feature = "fault_injection")
+        );
+    }
+}
@@ -0,0 +1,192 @@
+//-- ./src/check_config.rs
+
+// #![allow(unused)] // For development only
+
+//! # Configuration Check
+//!
+//! `authentication_service --check-config` parses and validates the
+//! configuration, checks the database and SMTP relay can be reached and the
+//! email templates, error message translations and user name profanity list
+//! load, prints the redacted effective configuration and exits non-zero if
+//! any check fails.
+//! Intended for CI and deploy pipelines.
+//! ---
+
+use std::time::Duration;
+
+use sqlx::postgres::PgPoolOptions;
+
+use crate::configuration::{Configuration, EmailTransport};
+use crate::database;
+use crate::email::{EmailTemplates, SmtpEmailClient};
+use crate::error_messages::ErrorMessages;
+use crate::prelude::*;
+
+/// How long to wait for the database before failing the check
+const DATABASE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Result of a single configuration check
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub outcome: Result<String, String>,
+}
+
+impl std::fmt::Display for CheckResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.outcome {
+            Ok(detail) => write!(f, "[ OK ] {}: {detail}", self.name),
+            Err(error) => write!(f, "[FAIL] {}: {error}", self.name),
+        }
+    }
+}
+
+/// Check the database accepts connections and answers a query
+async fn check_database(config: &Configuration) -> Result<String, String> {
+    let database = PgPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(DATABASE_TIMEOUT)
+        .connect_with(config.database.connection())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let _row: (i32,) = sqlx::query_as("SELECT 1")
+        .fetch_one(&database)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let pending = database::migration_status(&database)
+        .await
+        .map_err(|e| e.to_string())?
+        .iter()
+        .filter(|migration| !migration.applied)
+        .count();
+
+    database.close().await;
+
+    Ok(format!(
+        "connected to {}:{}/{}, {pending} pending migrations",
+        config.database.host, config.database.port, config.database.database_name
+    ))
+}
+
+/// Check the configured email transport
+pub(crate) async fn check_email(config: &Configuration) -> Result<String, String> {
+    match config.email.transport {
+        EmailTransport::Console => Ok("console transport, nothing to connect to".to_string()),
+        EmailTransport::Smtp => {
+            let client = SmtpEmailClient::new(&config.email).map_err(|e| e.to_string())?;
+            client.test_connection().await.map_err(|e| e.to_string())?;
+            Ok(format!(
+                "connected to {}:{}",
+                config.email.smtp_host.as_deref().unwrap_or_default(),
+                config.email.smtp_port
+            ))
+        }
+    }
+}
+
+/// Run every configuration check, printing each result as it completes.
+///
+/// # Returns
+/// * `Ok(())` - Every check passed.
+/// * `Err(AuthenticationError)` - At least one check failed, so the binary exits non-zero.
+pub async fn run() -> Result<(), AuthenticationError> {
+    let config = match Configuration::parse() {
+        Ok(config) => {
+            println!("[ OK ] parse: configuration files and environment read");
+            config
+        }
+        Err(e) => {
+            println!("[FAIL] parse: {e}");
+            return Err(AuthenticationError::Static("Configuration check failed"));
+        }
+    };
+
+    println!("{}", config.redacted());
+
+    let results = vec![
+        CheckResult {
+            name: "validate",
+            outcome: config
+                .validate()
+                .map(|_| "configuration values are consistent".to_string())
+                .map_err(|e| e.to_string()),
+        },
+        CheckResult {
+            name: "database",
+            outcome: check_database(&config).await,
+        },
+        CheckResult {
+            name: "email",
+            outcome: check_email(&config).await,
+        },
+        CheckResult {
+            name: "user names",
+            outcome: config
+                .user_names
+                .policy()
+                .map(|policy| {
+                    format!(
+                        "{} reserved names, {} words on the profanity list",
+                        policy.reserved.len(),
+                        policy.profanity.len()
+                    )
+                })
+                .map_err(|e| e.to_string()),
+        },
+        CheckResult {
+            name: "error messages",
+            outcome: ErrorMessages::new(&config.error_messages)
+                .map(|_| "translations load".to_string())
+                .map_err(|e| e.to_string()),
+        },
+        CheckResult {
+            name: "email templates",
+            outcome: EmailTemplates::new(&config.email)
+                .map(|_| "templates load for every email".to_string())
+                .map_err(|e| e.to_string()),
+        },
+    ];
+
+    for result in &results {
+        println!("{result}");
+    }
+
+    if results.iter().any(|result| result.outcome.is_err()) {
+        return Err(AuthenticationError::Static("Configuration check failed"));
+    }
+
+    println!("Configuration check passed");
+
+    Ok(())
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_result_display() {
+        let passed = CheckResult {
+            name: "database",
+            outcome: Ok("connected".to_string()),
+        };
+        let failed = CheckResult {
+            name: "email",
+            outcome: Err("refused".to_string()),
+        };
+
+        assert_eq!(passed.to_string(), "[ OK ] database: connected");
+        assert_eq!(failed.to_string(), "[FAIL] email: refused");
+    }
+
+    #[tokio::test]
+    async fn console_email_transport_passes() {
+        let mut config = Configuration::parse().unwrap();
+        config.email = Default::default();
+
+        assert!(check_email(&config).await.is_ok());
+    }
+}
@@ -0,0 +1,345 @@
+//-- ./src/cli.rs
+
+// #![allow(unused)] // For development only
+
+//! # Command Line Interface
+//!
+//! Operator commands for the authentication service binary, so routine admin
+//! tasks do not need psql access:
+//!
+//! ```bash
+//! authentication_service                      # serve (default)
+//! authentication_service create-admin --email admin@example.com --password '...'
+//! authentication_service migrate
+//! authentication_service migrate --dry-run
+//! authentication_service prune-tokens
+//! authentication_service purge-deleted-users --older-than-days 30
+//! authentication_service revoke-user-sessions --user-id 0190...
+//! ```
+//!
+//! Commands reuse the configuration files and the database models. The
+//! cleanup commands run each statement with the background query budget,
+//! `database.background_query_timeout_milliseconds`.
+//! ---
+
+use clap::{Parser, Subcommand};
+use secrecy::SecretString;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::configuration::Configuration;
+use crate::database::timeouts::background;
+use crate::prelude::*;
+use crate::{database, domain};
+
+/// Days processed outbox entries are kept, for troubleshooting
+const OUTBOX_RETENTION_DAYS: i64 = 7;
+
+/// Authentication service command line
+#[derive(Debug, Parser)]
+#[command(name = "authentication_service", version, about)]
+pub struct Cli {
+    /// Check the configuration and its connections, then exit
+    #[arg(long, global = true)]
+    pub check_config: bool,
+
+    /// Run self-contained with an embedded database (requires the `demo` feature)
+    #[arg(long, global = true)]
+    pub demo: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Authentication service commands
+#[derive(Debug, Subcommand, PartialEq)]
+pub enum Command {
+    /// Run the gRPC server (default)
+    Serve,
+
+    /// Create a verified, active admin user
+    CreateAdmin {
+        /// Admin email address, used to log in
+        #[arg(long)]
+        email: String,
+
+        /// Admin password
+        #[arg(long, env = "APP_ADMIN_PASSWORD", hide_env_values = true)]
+        password: String,
+
+        /// Admin display name
+        #[arg(long, default_value = "Admin")]
+        name: String,
+    },
+
+    /// Run pending database migrations
+    Migrate {
+        /// List applied and pending migrations without running them
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Revoke sessions idle for longer than `application.idle_session_timeout_days`,
+    /// then delete expired or revoked sessions, expired email verification tokens,
+    /// expired access token denylist entries, expired or used action tokens,
+    /// expired passkey challenges, SAML requests and device codes, outbox
+    /// entries processed over a week ago and login throttles with no recent
+    /// failures
+    PruneTokens,
+
+    /// Permanently delete users soft deleted more than a number of days ago
+    PurgeDeletedUsers {
+        /// Days a deleted user can still be restored
+        #[arg(long, default_value_t = 30)]
+        older_than_days: u32,
+    },
+
+    /// Revoke every session belonging to a user
+    RevokeUserSessions {
+        /// The user id whose sessions are revoked
+        #[arg(long)]
+        user_id: Uuid,
+    },
+}
+
+impl Command {
+    /// Run an admin command against the configured database.
+    ///
+    /// `Serve` is handled by the binary entry point, so is a no-op here.
+    pub async fn run(self, config: &Configuration) -> Result<(), AuthenticationError> {
+        // Migrate runs the migrations itself, other commands follow
+        // database.auto_migrate when connecting
+        let database = match self {
+            Command::Migrate { .. } => {
+                let mut database_config = config.database.clone();
+                database_config.auto_migrate = false;
+                database::init_pool(&database_config).await?
+            }
+            _ => database::init_pool(&config.database).await?,
+        };
+
+        match self {
+            Command::Serve => {}
+            Command::Migrate { dry_run: true } => {
+                let status = database::migration_status(&database).await?;
+                for migration in &status {
+                    println!("{migration}");
+                }
+                let pending = status.iter().filter(|migration| !migration.applied).count();
+                println!("{pending} pending migrations would be applied");
+            }
+            Command::Migrate { dry_run: false } => {
+                database::run_migrations(&database).await?;
+                println!("Database migrations are up to date");
+            }
+            Command::CreateAdmin {
+                email,
+                password,
+                name,
+            } => {
+                let user = create_admin(&email, password, &name, &database).await?;
+                println!("Created admin {} with id {}", user.email, user.id);
+            }
+            Command::PruneTokens => {
+                // Each statement has the background query budget
+                let idle = match config.application.idle_session_timeout() {
+                    Some(idle_timeout) => {
+                        let idle_before = chrono::Utc::now() - idle_timeout;
+                        background(
+                            "sessions.revoke_idle",
+                            database::Sessions::revoke_idle(&idle_before, &database),
+                        )
+                        .await?
+                    }
+                    None => 0,
+                };
+                let sessions = background(
+                    "sessions.delete_expired",
+                    database::Sessions::delete_expired(&database),
+                )
+                .await?;
+                let verifications = background(
+                    "email_verifications.delete_expired",
+                    database::EmailVerifications::delete_expired(&database),
+                )
+                .await?;
+                let denied = background(
+                    "access_token_denylist.delete_expired",
+                    database::AccessTokenDenylist::delete_expired(&database),
+                )
+                .await?;
+                let action_tokens = background(
+                    "action_tokens.delete_expired",
+                    database::ActionTokens::delete_expired(&database),
+                )
+                .await?;
+                let passkey_challenges = background(
+                    "passkey_challenges.delete_expired",
+                    database::PasskeyChallenges::delete_expired(&database),
+                )
+                .await?;
+                let saml_requests = background(
+                    "saml_requests.delete_expired",
+                    database::SamlRequests::delete_expired(&database),
+                )
+                .await?;
+                let device_codes = background(
+                    "device_codes.delete_expired",
+                    database::DeviceCodes::delete_expired(&database),
+                )
+                .await?;
+                let processed_before = chrono::Utc::now() - chrono::Duration::days(OUTBOX_RETENTION_DAYS);
+                let outbox = background(
+                    "outbox.delete_processed",
+                    database::Outbox::delete_processed(&processed_before, &database),
+                )
+                .await?;
+                let failed_before = chrono::Utc::now()
+                    - chrono::Duration::seconds(config.login_throttle.reset_after_seconds as i64);
+                let throttles = background(
+                    "login_throttles.delete_stale",
+                    database::LoginThrottles::delete_stale(&failed_before, &database),
+                )
+                .await?;
+                println!(
+                    "Revoked {idle} idle sessions. Pruned {sessions} sessions, {verifications} email verifications, {denied} denied access tokens, {action_tokens} action tokens, {passkey_challenges} passkey challenges, {saml_requests} SAML requests, {device_codes} device codes, {outbox} outbox entries and {throttles} login throttles"
+                );
+            }
+            Command::PurgeDeletedUsers { older_than_days } => {
+                let deleted_before =
+                    chrono::Utc::now() - chrono::Duration::days(older_than_days as i64);
+                let purged = background(
+                    "users.purge_deleted_older_than",
+                    database::Users::purge_deleted_older_than(&deleted_before, &database),
+                )
+                .await?;
+                println!("Purged {purged} users deleted over {older_than_days} days ago");
+            }
+            Command::RevokeUserSessions { user_id } => {
+                let revoked = database::Sessions::revoke_user_id(&user_id, &database).await?;
+                println!("Revoked {revoked} sessions for user {user_id}");
+            }
+        }
+
+        database.close().await;
+
+        Ok(())
+    }
+}
+
+/// Insert a verified, active admin user
+async fn create_admin(
+    email: &str,
+    password: String,
+    name: &str,
+    database: &Pool<Postgres>,
+) -> Result<database::Users, AuthenticationError> {
+    let user = database::Users {
+        id: Uuid::now_v7(),
+        email: domain::EmailAddress::parse(email)?,
+        name: domain::UserName::parse(name)?,
+        password_hash: domain::PasswordHash::parse(SecretString::from(password))?,
+        role: domain::UserRole::Admin,
+        is_active: true,
+        is_verified: true,
+        created_on: chrono::Utc::now(),
+        locale: domain::Locale::default(),
+    };
+
+    user.insert(database).await
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn no_subcommand_defaults_to_serve() -> Result<()> {
+        let cli = Cli::try_parse_from(["authentication_service"])?;
+
+        assert_eq!(cli.command, None);
+        assert!(!cli.check_config);
+        assert!(!cli.demo);
+
+        Ok(())
+    }
+
+    #[test]
+    fn parses_admin_subcommands() -> Result<()> {
+        let user_id = Uuid::now_v7();
+        let cli = Cli::try_parse_from([
+            "authentication_service",
+            "revoke-user-sessions",
+            "--user-id",
+            &user_id.to_string(),
+        ])?;
+        assert_eq!(cli.command, Some(Command::RevokeUserSessions { user_id }));
+
+        let cli = Cli::try_parse_from(["authentication_service", "migrate", "--dry-run"])?;
+        assert_eq!(cli.command, Some(Command::Migrate { dry_run: true }));
+
+        let cli = Cli::try_parse_from(["authentication_service", "purge-deleted-users"])?;
+        assert_eq!(
+            cli.command,
+            Some(Command::PurgeDeletedUsers {
+                older_than_days: 30
+            })
+        );
+
+        let cli = Cli::try_parse_from([
+            "authentication_service",
+            "create-admin",
+            "--email",
+            "admin@example.com",
+            "--password",
+            "Admin-Passw0rd",
+        ])?;
+        assert_eq!(
+            cli.command,
+            Some(Command::CreateAdmin {
+                email: "admin@example.com".to_string(),
+                password: "Admin-Passw0rd".to_string(),
+                name: "Admin".to_string(),
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_user_id_is_rejected() {
+        let cli = Cli::try_parse_from([
+            "authentication_service",
+            "revoke-user-sessions",
+            "--user-id",
+            "not-a-uuid",
+        ]);
+
+        assert!(cli.is_err());
+    }
+
+    #[sqlx::test]
+    async fn create_admin_inserts_verified_admin(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let email = "operator@example.com";
+
+        //-- Execute Function (Act)
+        let user =
+            create_admin(email, "Operator-Passw0rd".to_string(), "Operator", &database)
+                .await?;
+
+        //-- Checks (Assertions)
+        let saved = database::Users::from_user_id(&user.id, &database).await?;
+        assert_eq!(saved.email.as_ref(), email);
+        assert_eq!(saved.role, domain::UserRole::Admin);
+        assert!(saved.is_active);
+        assert!(saved.is_verified);
+
+        Ok(())
+    }
+}
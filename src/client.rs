@@ -0,0 +1,323 @@
+//-- ./src/client.rs
+
+// #![allow(unused)] // For development only
+
+//! # Rust Client
+//!
+//! Clients for other Rust services, built with the `client` feature, so they
+//! can use the authentication service without copying its proto files.
+//!
+//! The generated Tonic clients are in `rpc::proto`, e.g.
+//! `rpc::proto::users_service_client::UsersServiceClient`. [`AuthClient`]
+//! wraps them and keeps the caller's tokens:
+//! - **Login** keeps the access token from the response and the refresh token
+//!   from its `set-cookie` metadata
+//! - **Token attach**: the users, sessions and admin clients send the access
+//!   token as `authorization: Bearer` metadata
+//! - **Refresh**: [`AuthClient::authorised`] refreshes the access token with
+//!   the refresh token cookie and retries once when a call is unauthenticated
+//! - **Logout** forgets the tokens
+//!
+//! ```ignore
+//! let client = AuthClient::connect("http://127.0.0.1:8091").await?;
+//! client.login(LoginRequest { email, password, ..Default::default() }).await?;
+//! let me = client
+//!     .authorised(|| async { client.users().get_me(Empty {}).await })
+//!     .await?;
+//! ```
+//! ---
+
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+
+use cookie::Cookie;
+use http::header::{COOKIE, SET_COOKIE};
+use tonic::metadata::MetadataMap;
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::Channel;
+use tonic::{Request, Response, Status};
+
+use crate::prelude::*;
+use crate::rpc::proto::{
+    admin_service_client::AdminServiceClient, authentication_service_client::AuthenticationServiceClient,
+    sessions_service_client::SessionsServiceClient, users_service_client::UsersServiceClient,
+    utilities_service_client::UtilitiesServiceClient, Empty, LoginRequest, LoginResponse,
+    LogoutResponse, RefreshResponse,
+};
+
+/// The default name of the refresh token cookie, see `refresh_cookie.name`
+pub const DEFAULT_REFRESH_COOKIE_NAME: &str = "refresh_token";
+
+/// Users client that sends the access token
+pub type UsersClient = UsersServiceClient<InterceptedService<Channel, BearerToken>>;
+
+/// Sessions client that sends the access token
+pub type SessionsClient = SessionsServiceClient<InterceptedService<Channel, BearerToken>>;
+
+/// Admin client that sends the access token
+pub type AdminClient = AdminServiceClient<InterceptedService<Channel, BearerToken>>;
+
+/// The tokens of the logged in caller
+#[derive(Debug, Clone, Default)]
+struct Tokens {
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+}
+
+/// Shared tokens, so clients keep sending the latest access token
+type SharedTokens = Arc<RwLock<Tokens>>;
+
+/// Add the caller's access token to each request as `authorization: Bearer`
+#[derive(Debug, Clone)]
+pub struct BearerToken {
+    tokens: SharedTokens,
+}
+
+impl tonic::service::Interceptor for BearerToken {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let tokens = self.tokens.read().unwrap_or_else(|e| e.into_inner());
+        if let Some(access_token) = &tokens.access_token {
+            let bearer = format!("Bearer {access_token}")
+                .parse()
+                .map_err(|_| Status::invalid_argument("Access token is not valid metadata"))?;
+            request.metadata_mut().insert("authorization", bearer);
+        }
+
+        Ok(request)
+    }
+}
+
+/// Client for the authentication service that keeps the caller's tokens
+#[derive(Debug, Clone)]
+pub struct AuthClient {
+    channel: Channel,
+    tokens: SharedTokens,
+    refresh_cookie_name: String,
+}
+
+impl AuthClient {
+    /// Connect to the service at `address`, e.g. `http://127.0.0.1:8091`
+    pub async fn connect(address: impl Into<String>) -> Result<Self, AuthenticationError> {
+        let address = address.into();
+        let channel = Channel::from_shared(address.clone())
+            .map_err(|_| AuthenticationError::ValidationError(format!("Invalid address {address}")))?
+            .connect()
+            .await?;
+
+        Ok(Self::new(channel))
+    }
+
+    /// Use a channel, e.g. one configured with TLS or timeouts
+    pub fn new(channel: Channel) -> Self {
+        Self {
+            channel,
+            tokens: SharedTokens::default(),
+            refresh_cookie_name: DEFAULT_REFRESH_COOKIE_NAME.to_string(),
+        }
+    }
+
+    /// Use the refresh token cookie name configured on the server
+    pub fn with_refresh_cookie_name(mut self, name: impl Into<String>) -> Self {
+        self.refresh_cookie_name = name.into();
+        self
+    }
+
+    /// Resume a session with tokens kept from an earlier login
+    pub fn with_tokens(self, access_token: Option<String>, refresh_token: Option<String>) -> Self {
+        *self.tokens.write().unwrap_or_else(|e| e.into_inner()) = Tokens {
+            access_token,
+            refresh_token,
+        };
+        self
+    }
+
+    /// The current access token, `None` until logged in
+    pub fn access_token(&self) -> Option<String> {
+        self.tokens
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .access_token
+            .clone()
+    }
+
+    /// The current refresh token, `None` until logged in
+    pub fn refresh_token(&self) -> Option<String> {
+        self.tokens
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .refresh_token
+            .clone()
+    }
+
+    fn bearer_token(&self) -> BearerToken {
+        BearerToken {
+            tokens: Arc::clone(&self.tokens),
+        }
+    }
+
+    /// The generated authentication client, without tokens
+    pub fn authentication(&self) -> AuthenticationServiceClient<Channel> {
+        AuthenticationServiceClient::new(self.channel.clone())
+    }
+
+    /// The generated utilities client, without tokens
+    pub fn utilities(&self) -> UtilitiesServiceClient<Channel> {
+        UtilitiesServiceClient::new(self.channel.clone())
+    }
+
+    /// The users client, sending the access token
+    pub fn users(&self) -> UsersClient {
+        UsersServiceClient::with_interceptor(self.channel.clone(), self.bearer_token())
+    }
+
+    /// The sessions client, sending the access token
+    pub fn sessions(&self) -> SessionsClient {
+        SessionsServiceClient::with_interceptor(self.channel.clone(), self.bearer_token())
+    }
+
+    /// The admin client, sending the access token
+    pub fn admin(&self) -> AdminClient {
+        AdminServiceClient::with_interceptor(self.channel.clone(), self.bearer_token())
+    }
+
+    /// Keep the refresh token from a response's `set-cookie` metadata
+    fn keep_refresh_cookie(&self, metadata: &MetadataMap) {
+        let refresh_token = metadata
+            .get_all(SET_COOKIE.as_str())
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(|value| Cookie::parse(value.to_string()).ok())
+            .find(|cookie| cookie.name() == self.refresh_cookie_name)
+            .map(|cookie| cookie.value().to_string());
+
+        if let Some(refresh_token) = refresh_token {
+            self.tokens
+                .write()
+                .unwrap_or_else(|e| e.into_inner())
+                .refresh_token = Some(refresh_token);
+        }
+    }
+
+    fn keep_access_token(&self, access_token: &str) {
+        self.tokens
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .access_token = Some(access_token.to_string());
+    }
+
+    /// A request sending the refresh token cookie
+    fn with_refresh_cookie<T>(&self, message: T) -> Result<Request<T>, Status> {
+        let refresh_token = self
+            .refresh_token()
+            .ok_or_else(|| Status::unauthenticated("Not logged in"))?;
+
+        let mut request = Request::new(message);
+        let cookie = Cookie::new(self.refresh_cookie_name.as_str(), refresh_token)
+            .to_string()
+            .parse()
+            .map_err(|_| Status::invalid_argument("Refresh token is not valid metadata"))?;
+        request.metadata_mut().insert(COOKIE.as_str(), cookie);
+
+        Ok(request)
+    }
+
+    /// Log in with an email and password, keeping the tokens
+    pub async fn login(&self, message: LoginRequest) -> Result<LoginResponse, Status> {
+        let response = self.authentication().login(message).await?;
+        self.keep_refresh_cookie(response.metadata());
+        let response = response.into_inner();
+        self.keep_access_token(&response.access_token);
+
+        Ok(response)
+    }
+
+    /// Get a new access token with the refresh token
+    pub async fn refresh(&self) -> Result<RefreshResponse, Status> {
+        let request = self.with_refresh_cookie(Empty {})?;
+        let response = self.authentication().refresh(request).await?;
+        self.keep_refresh_cookie(response.metadata());
+        let response = response.into_inner();
+        self.keep_access_token(&response.access_token);
+
+        Ok(response)
+    }
+
+    /// Log out the session and forget the tokens
+    pub async fn logout(&self) -> Result<LogoutResponse, Status> {
+        let request = self.with_refresh_cookie(Empty {})?;
+        let response = self.authentication().logout(request).await?;
+        *self.tokens.write().unwrap_or_else(|e| e.into_inner()) = Tokens::default();
+
+        Ok(response.into_inner())
+    }
+
+    /// Make a call with the access token, refreshing it and calling again
+    /// once when the call is unauthenticated, e.g. the access token expired
+    pub async fn authorised<T, F, Fut>(&self, mut call: F) -> Result<T, Status>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<Response<T>, Status>>,
+    {
+        match call().await {
+            Err(status)
+                if status.code() == tonic::Code::Unauthenticated
+                    && self.refresh_token().is_some() =>
+            {
+                tracing::debug!("Refreshing the access token: {}", status.message());
+                self.refresh().await?;
+                call().await.map(Response::into_inner)
+            }
+            result => result.map(Response::into_inner),
+        }
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[tokio::test]
+    async fn the_refresh_cookie_is_kept_and_sent() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let client = AuthClient::new(Channel::from_static("http://127.0.0.1:1").connect_lazy())
+            .with_refresh_cookie_name("session");
+        let mut metadata = MetadataMap::new();
+        metadata.append(SET_COOKIE.as_str(), "other=ignored; Path=/".parse()?);
+        metadata.append(SET_COOKIE.as_str(), "session=refresh; Path=/; HttpOnly".parse()?);
+
+        //-- Execute Function (Act)
+        client.keep_refresh_cookie(&metadata);
+        let request = client.with_refresh_cookie(Empty {})?;
+
+        //-- Checks (Assertions)
+        assert_eq!(client.refresh_token().as_deref(), Some("refresh"));
+        assert_eq!(request.metadata().get(COOKIE.as_str()).ok_or("no cookie")?, "session=refresh");
+        assert_eq!(client.access_token(), None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn the_access_token_is_attached() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let client = AuthClient::new(Channel::from_static("http://127.0.0.1:1").connect_lazy())
+            .with_tokens(Some("access".to_string()), None);
+        let mut bearer_token = client.bearer_token();
+
+        //-- Execute Function (Act)
+        let request = tonic::service::Interceptor::call(&mut bearer_token, Request::new(()))?;
+
+        //-- Checks (Assertions)
+        assert_eq!(
+            request.metadata().get("authorization").ok_or("no bearer")?,
+            "Bearer access"
+        );
+
+        Ok(())
+    }
+}
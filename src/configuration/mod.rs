@@ -0,0 +1,4948 @@
+// -- ./src/configuration/mod.rs
+
+// #![allow(unused)] // For development only
+
+//! Application configuration settings
+//!
+//! # Application Configuration Crate
+//!
+//! Get API configuration from the `./configuration/base.yaml` file and
+//! overwrite with runtime environment configuration `./configuration/{environment}.yaml`
+//! and environmental runtime variables.
+//!
+//! ## Layers
+//!
+//! 1. `./configuration/base.yaml`
+//! 2. `./configuration/{APP_ENVIRONMENT}.yaml` (optional), defaults to `development`
+//! 3. Environment variables with an `APP__` prefix and `__` separator, e.g.
+//!    `APP__APPLICATION__PORT=5001` sets `application.port`
+//!
+//! ## Secrets From Files
+//!
+//! Secrets can be read from files (e.g. Docker secrets) by setting the key with
+//! a `_file` suffix to the file path, e.g. `APP__DATABASE__PASSWORD_FILE=/run/secrets/db_password`.
+//! Supported for `application.token_secret`, `database.password`,
+//! `email.smtp_password`, `captcha.secret` and `ldap.bind_password`.
+//!
+//! Non-critical settings can be reloaded at runtime, see the `reload` module.
+//!
+//! TODO: rename ip address to domain name as this used by the cookie
+//! 
+//! ## References
+//!
+//! * [config.rs Repository](https://github.com/mehcode/config-rs)
+//! * [Configuration management in Rust web services](https://blog.logrocket.com/configuration-management-in-rust-web-services/)
+//! - [Example 2](https://github.com/stoically/web-service-rs-template/blob/main/src/config.rs)
+
+use crate::domain;
+use crate::prelude::*;
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use ipnet::IpNet;
+use secrecy::{ExposeSecret, SecretString};
+use serde_aux::field_attributes::deserialize_number_from_string;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions, PgSslMode};
+use sqlx::ConnectOptions;
+use strum::{Display, EnumString};
+use serde_with::formats::CommaSeparator;
+use serde_with::{serde_as, DisplayFromStr, PickFirst, StringWithSeparator};
+use tracing_log::log::LevelFilter as LogLevelFilter;
+use tracing_subscriber::filter as tracing;
+
+pub mod reload;
+
+/// Configuration shared with the services, swapped atomically on reload
+pub type SharedConfiguration = Arc<ArcSwap<Configuration>>;
+
+/// Prefix for environment variable overrides, e.g. `APP__APPLICATION__PORT`
+const ENVIRONMENT_PREFIX: &str = "APP";
+
+/// Separator between the prefix and nested keys in environment variables
+const ENVIRONMENT_SEPARATOR: &str = "__";
+
+/// Secret keys that can be read from a file path set in `{key}_file`
+const FILE_SECRET_KEYS: [&str; 6] = [
+    "application.token_secret",
+    "database.password",
+    "email.smtp_password",
+    "captcha.secret",
+    "ldap.bind_password",
+    "avatars.s3_secret_access_key",
+];
+
+/// Keys without a default that must be set in one of the configuration layers
+const REQUIRED_KEYS: [&str; 12] = [
+    "application.ip_address",
+    "application.port",
+    "application.log_level",
+    "application.token_secret",
+    "application.access_token_duration_minutes",
+    "application.refresh_token_duration_minutes",
+    "database.host",
+    "database.port",
+    "database.username",
+    "database.password",
+    "database.database_name",
+    "database.require_ssl",
+];
+
+/// The gRPC services, as named in `compression.services` and the `services`
+/// served by each listener
+pub const GRPC_SERVICES: [&str; 5] = ["utilities", "authentication", "users", "sessions", "admin"];
+
+/// The environment variable name that sets a configuration key
+fn environment_variable(key: &str) -> String {
+    format!(
+        "{ENVIRONMENT_PREFIX}{ENVIRONMENT_SEPARATOR}{}",
+        key.replace('.', ENVIRONMENT_SEPARATOR).to_uppercase()
+    )
+}
+
+/// Configuration for the API
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Configuration {
+    /// Application configuration
+    pub application: ApplicationConfiguration,
+
+    /// Database configuration
+    pub database: DatabaseConfiguration,
+
+    /// Outgoing email configuration
+    #[serde(default)]
+    pub email: EmailConfiguration,
+
+    /// Trace export configuration
+    #[serde(default)]
+    pub telemetry: TelemetryConfiguration,
+
+    /// Reporting internal errors and panics to Sentry
+    #[serde(default)]
+    pub error_reporting: ErrorReportingConfiguration,
+
+    /// REST/JSON gateway configuration
+    #[serde(default)]
+    pub http: HttpConfiguration,
+
+    /// Webhook delivery configuration
+    #[serde(default)]
+    pub webhooks: WebhooksConfiguration,
+
+    /// Transactional outbox configuration
+    #[serde(default)]
+    pub outbox: OutboxConfiguration,
+
+    /// Kafka or NATS event publishing configuration
+    #[serde(default)]
+    pub event_bus: EventBusConfiguration,
+
+    /// CAPTCHA verification on register and login
+    #[serde(default)]
+    pub captcha: CaptchaConfiguration,
+
+    /// Per IP address and email login throttling
+    #[serde(default)]
+    pub login_throttle: LoginThrottleConfiguration,
+
+    /// Proxies trusted to forward the client IP address
+    #[serde(default)]
+    pub trusted_proxies: TrustedProxiesConfiguration,
+
+    /// Server wide concurrency limits and load shedding
+    #[serde(default)]
+    pub load_shedding: LoadSheddingConfiguration,
+
+    /// Message size, timeout and keepalive settings of the Tonic server
+    #[serde(default)]
+    pub grpc: GrpcConfiguration,
+
+    /// Compression of gRPC messages, per service
+    #[serde(default)]
+    pub compression: CompressionConfiguration,
+
+    /// Listeners serving gRPC alongside `application.ip_address` and `port`
+    #[serde(default)]
+    pub listeners: Vec<ListenerConfiguration>,
+
+    /// A separate server for the admin services on an internal address
+    #[serde(default)]
+    pub internal: InternalConfiguration,
+
+    /// Dependency checks before the server reports healthy
+    #[serde(default)]
+    pub readiness: ReadinessConfiguration,
+
+    /// Faults injected into requests for resilience testing
+    #[serde(default)]
+    pub fault_injection: FaultInjectionConfiguration,
+
+    /// Attributes of the refresh token cookie set on login
+    #[serde(default)]
+    pub refresh_cookie: RefreshCookieConfiguration,
+
+    /// Where else refresh and logout read the refresh token from
+    #[serde(default)]
+    pub refresh_token: RefreshTokenConfiguration,
+
+    /// Passwordless login with an emailed magic link
+    #[serde(default)]
+    pub magic_link: MagicLinkConfiguration,
+
+    /// Emailed password reset links and how often they can be requested
+    #[serde(default)]
+    pub password_reset: PasswordResetConfiguration,
+
+    /// Reminding unverified users to verify their email, then deactivating them
+    #[serde(default)]
+    pub verification_reminders: VerificationRemindersConfiguration,
+
+    /// WebAuthn passkey registration and login
+    #[serde(default)]
+    pub passkeys: PasskeysConfiguration,
+
+    /// Password login against an LDAP or Active Directory server
+    #[serde(default)]
+    pub ldap: LdapConfiguration,
+
+    /// Single sign on with a SAML 2.0 identity provider
+    #[serde(default)]
+    pub saml: SamlConfiguration,
+
+    /// OAuth device authorization grant for CLI and IoT clients
+    #[serde(default)]
+    pub device_authorization: DeviceAuthorizationConfiguration,
+
+    /// OAuth client credentials grant for trusted backend services
+    #[serde(default)]
+    pub client_credentials: ClientCredentialsConfiguration,
+
+    /// Admin impersonation of users for support staff
+    #[serde(default)]
+    pub impersonation: ImpersonationConfiguration,
+
+    /// Terms of service and privacy policy versions users must accept
+    #[serde(default)]
+    pub policies: PoliciesConfiguration,
+
+    /// User avatar uploads and where the images are stored
+    #[serde(default)]
+    pub avatars: AvatarsConfiguration,
+
+    /// The email domains that can register
+    #[serde(default)]
+    pub email_domains: EmailDomainsConfiguration,
+
+    /// What users can be named
+    #[serde(default)]
+    pub user_names: UserNamesConfiguration,
+
+    /// Translations of the error messages returned to clients
+    #[serde(default)]
+    pub error_messages: ErrorMessagesConfiguration,
+}
+
+/// Returns the default value for the `use_tls` field in `ApplicationConfiguration`.
+fn default_use_tls() -> bool {
+    false
+}
+
+/// Returns the default value for the `remember_me_duration_minutes` field in `ApplicationConfiguration`.
+fn default_remember_me_duration_minutes() -> u64 {
+    // Ninety days 90 x 24 x 60
+    129_600
+}
+
+/// Returns the default value for the `absolute_session_lifetime_minutes` field in `ApplicationConfiguration`.
+fn default_absolute_session_lifetime_minutes() -> u64 {
+    // Ninety days 90 x 24 x 60
+    129_600
+}
+
+/// Returns the default value for the `password_change_keeps_session` field in `ApplicationConfiguration`.
+fn default_password_change_keeps_session() -> bool {
+    true
+}
+
+/// Returns the default value for the `token_audiences` field in `ApplicationConfiguration`.
+fn default_token_audiences() -> Vec<String> {
+    vec![crate::domain::DEFAULT_AUDIENCE.to_string()]
+}
+
+/// Returns the default value for the `warm_up` field in `ApplicationConfiguration`.
+fn default_warm_up() -> bool {
+    true
+}
+
+/// Returns the default value for the `warm_up_connections` field in `ApplicationConfiguration`.
+fn default_warm_up_connections() -> usize {
+    4
+}
+
+/// Returns the default value for the `hot_reload` field in `ApplicationConfiguration`.
+fn default_hot_reload() -> bool {
+    true
+}
+
+/// Returns the default value for the `services` field in `ApplicationConfiguration` and `ListenerConfiguration`.
+fn default_services() -> Vec<String> {
+    GRPC_SERVICES.iter().map(ToString::to_string).collect()
+}
+
+/// Configuration for running the API application
+#[serde_as]
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ApplicationConfiguration {
+    /// The host address the api should bind to
+    pub ip_address: String,
+
+    /// The port that the api should bind to
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub port: u16,
+
+    #[serde_as(as = "DisplayFromStr")]
+    pub log_level: tracing::LevelFilter,
+
+    /// Secret used to generate JWT keys
+    pub token_secret: SecretString,
+
+    /// How many minutes to keep the access token valid for.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub access_token_duration_minutes: u64,
+
+    /// How many minutes to keep the access token valid for.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub refresh_token_duration_minutes: u64,
+
+    /// How many minutes to keep the refresh token (session) valid for when
+    /// the user logs in with "remember me"
+    #[serde(default = "default_remember_me_duration_minutes")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub remember_me_duration_minutes: u64,
+
+    /// Extend a session on each refresh, by the refresh token duration, up to
+    /// `absolute_session_lifetime_minutes` after login
+    #[serde(default)]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub sliding_expiration: bool,
+
+    /// The longest a sliding session can last from login, in minutes
+    #[serde(default = "default_absolute_session_lifetime_minutes")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub absolute_session_lifetime_minutes: u64,
+
+    /// Reject and revoke sessions not used for this many days, whatever
+    /// their expiry. 0 never times out idle sessions.
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub idle_session_timeout_days: u64,
+
+    /// Keep the session used to change the password active, revoking the
+    /// user's other sessions. When false every session is revoked.
+    #[serde(default = "default_password_change_keeps_session")]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub password_change_keeps_session: bool,
+
+    /// How register answers for an email that is already registered
+    #[serde(default)]
+    pub registration_mode: RegistrationMode,
+
+    /// Audiences issued in the access token `aud` claim and accepted when
+    /// access tokens are validated. A list, or comma separated in an
+    /// environment variable, e.g. `APP__APPLICATION__TOKEN_AUDIENCES=web,mobile`
+    #[serde(default = "default_token_audiences")]
+    #[serde_as(as = "PickFirst<(_, StringWithSeparator<CommaSeparator, String>)>")]
+    pub token_audiences: Vec<String>,
+
+    /// Use HTTPS/TLS for the RPC server
+    #[serde(default = "default_use_tls")]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub use_tls: bool,
+
+    /// The path to the TLS certificate file
+    /// This is used when `use_https` is true.
+    pub tls_certificate: Option<String>,
+
+    /// The path to the TLS private key file
+    /// This is used when `use_https` is true.
+    pub tls_private_key: Option<String>,
+
+    /// Warm caches and prepared statements before reporting healthy
+    #[serde(default = "default_warm_up")]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub warm_up: bool,
+
+    /// How many pooled connections prepare the hot statements during warm-up
+    #[serde(default = "default_warm_up_connections")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub warm_up_connections: usize,
+
+    /// Watch the configuration files and reload non-critical settings
+    #[serde(default = "default_hot_reload")]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub hot_reload: bool,
+
+    /// The services served on `ip_address` and `port`, see `GRPC_SERVICES`.
+    /// A list, or comma separated in an environment variable, e.g.
+    /// `APP__APPLICATION__SERVICES=authentication,users`
+    #[serde(default = "default_services")]
+    #[serde_as(as = "PickFirst<(_, StringWithSeparator<CommaSeparator, String>)>")]
+    pub services: Vec<String>,
+}
+
+/// How register answers for an email that is already registered
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Deserialize, Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum RegistrationMode {
+    /// Answer as if the registration succeeded and email the owner, so
+    /// register can't be used to find which emails have accounts
+    #[default]
+    Strict,
+    /// Tell the caller the email is already registered
+    Friendly,
+}
+
+/// Configuration for connecting to the database server
+#[derive(Debug,Clone, serde::Deserialize)]
+pub struct DatabaseConfiguration {
+    /// Database host address
+    pub host: String,
+
+    /// Database host port
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub port: u16,
+
+    /// Database username for login
+    pub username: String,
+
+    /// Database password for login
+    pub password: SecretString,
+
+    /// Database name to use
+    pub database_name: String,
+
+    /// Should ssl be used to connect to the database
+    pub require_ssl: bool,
+
+    /// Statements taking longer than this are logged as warnings, with the
+    /// SQL text but never the bind parameters
+    #[serde(default = "default_slow_query_threshold_milliseconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub slow_query_threshold_milliseconds: u64,
+
+    /// Most connections the pool opens
+    #[serde(default = "default_max_connections")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_connections: u32,
+
+    /// Connections the pool keeps open, even when idle
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub min_connections: u32,
+
+    /// How long a request waits for a free connection before failing
+    #[serde(default = "default_acquire_timeout_seconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub acquire_timeout_seconds: u64,
+
+    /// How long a connection sits idle before it is closed, 0 keeps it open
+    #[serde(default = "default_idle_timeout_seconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub idle_timeout_seconds: u64,
+
+    /// Postgres cancels statements running longer than this, 0 for no limit
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub statement_timeout_milliseconds: u64,
+
+    /// RPC reads taking longer than this fail with `DEADLINE_EXCEEDED`, 0 for
+    /// no limit, see `database::timeouts`
+    #[serde(default = "default_rpc_query_timeout_milliseconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub rpc_query_timeout_milliseconds: u64,
+
+    /// Cleanup job queries taking longer than this fail, 0 for no limit
+    #[serde(default = "default_background_query_timeout_milliseconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub background_query_timeout_milliseconds: u64,
+
+    /// Ping connections before handing them out, so dropped connections are
+    /// replaced instead of failing the request
+    #[serde(default = "default_test_before_acquire")]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub test_before_acquire: bool,
+
+    /// Open connections on first use instead of at startup. Eager connections
+    /// fail startup straight away when the database is unreachable.
+    #[serde(default)]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub connect_lazily: bool,
+
+    /// Run pending migrations on startup. Turn off where a separate job runs
+    /// `authentication_service migrate` before deploys.
+    #[serde(default = "default_auto_migrate")]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub auto_migrate: bool,
+
+    /// Fail requests fast while the database is unreachable
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerConfiguration,
+
+    /// Retry queries failing with transient errors
+    #[serde(default)]
+    pub retry: QueryRetryConfiguration,
+}
+
+/// Returns the default value for the `slow_query_threshold_milliseconds` field in `DatabaseConfiguration`.
+fn default_slow_query_threshold_milliseconds() -> u64 {
+    500
+}
+
+/// Returns the default value for the `max_connections` field in `DatabaseConfiguration`.
+fn default_max_connections() -> u32 {
+    10
+}
+
+/// Returns the default value for the `acquire_timeout_seconds` field in `DatabaseConfiguration`.
+fn default_acquire_timeout_seconds() -> u64 {
+    30
+}
+
+/// Returns the default value for the `idle_timeout_seconds` field in `DatabaseConfiguration`.
+fn default_idle_timeout_seconds() -> u64 {
+    600
+}
+
+/// Returns the default value for the `auto_migrate` field in `DatabaseConfiguration`.
+fn default_auto_migrate() -> bool {
+    true
+}
+
+/// Returns the default value for the `test_before_acquire` field in `DatabaseConfiguration`.
+fn default_test_before_acquire() -> bool {
+    true
+}
+
+/// Returns the default value for the `rpc_query_timeout_milliseconds` field in `DatabaseConfiguration`.
+fn default_rpc_query_timeout_milliseconds() -> u64 {
+    5_000
+}
+
+/// Returns the default value for the `background_query_timeout_milliseconds` field in `DatabaseConfiguration`.
+fn default_background_query_timeout_milliseconds() -> u64 {
+    300_000
+}
+
+/// Returns the default value for the `enabled` field in `CircuitBreakerConfiguration`.
+fn default_circuit_breaker_enabled() -> bool {
+    true
+}
+
+/// Returns the default value for the `failure_threshold` field in `CircuitBreakerConfiguration`.
+fn default_circuit_breaker_failure_threshold() -> u32 {
+    5
+}
+
+/// Returns the default value for the `open_seconds` field in `CircuitBreakerConfiguration`.
+fn default_circuit_breaker_open_seconds() -> u64 {
+    10
+}
+
+/// Configuration for the database circuit breaker, see
+/// `database::CircuitBreaker`
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct CircuitBreakerConfiguration {
+    /// Fail requests with `Unavailable` while the circuit is open
+    #[serde(default = "default_circuit_breaker_enabled")]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub enabled: bool,
+
+    /// Consecutive connection failures that open the circuit
+    #[serde(default = "default_circuit_breaker_failure_threshold")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub failure_threshold: u32,
+
+    /// How long the circuit stays open before a probe request is let through
+    #[serde(default = "default_circuit_breaker_open_seconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub open_seconds: u64,
+}
+
+impl Default for CircuitBreakerConfiguration {
+    fn default() -> Self {
+        Self {
+            enabled: default_circuit_breaker_enabled(),
+            failure_threshold: default_circuit_breaker_failure_threshold(),
+            open_seconds: default_circuit_breaker_open_seconds(),
+        }
+    }
+}
+
+impl CircuitBreakerConfiguration {
+    /// How long the circuit stays open
+    pub fn open_duration(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.open_seconds)
+    }
+}
+
+/// Returns the default value for the `max_attempts` field in `QueryRetryConfiguration`.
+fn default_query_retry_max_attempts() -> u32 {
+    3
+}
+
+/// Returns the default value for the `base_delay_milliseconds` field in `QueryRetryConfiguration`.
+fn default_query_retry_base_delay_milliseconds() -> u64 {
+    25
+}
+
+/// Returns the default value for the `max_delay_milliseconds` field in `QueryRetryConfiguration`.
+fn default_query_retry_max_delay_milliseconds() -> u64 {
+    500
+}
+
+/// Configuration for retrying queries after transient errors, such as a
+/// reset connection or serialization failure, see `database::retry`
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct QueryRetryConfiguration {
+    /// Most times a query is run, 1 never retries
+    #[serde(default = "default_query_retry_max_attempts")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_attempts: u32,
+
+    /// Longest wait before the first retry, doubling with each retry
+    #[serde(default = "default_query_retry_base_delay_milliseconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub base_delay_milliseconds: u64,
+
+    /// Longest wait before any retry
+    #[serde(default = "default_query_retry_max_delay_milliseconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_delay_milliseconds: u64,
+}
+
+impl Default for QueryRetryConfiguration {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_query_retry_max_attempts(),
+            base_delay_milliseconds: default_query_retry_base_delay_milliseconds(),
+            max_delay_milliseconds: default_query_retry_max_delay_milliseconds(),
+        }
+    }
+}
+
+impl QueryRetryConfiguration {
+    /// Longest wait before the first retry
+    pub fn base_delay(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.base_delay_milliseconds)
+    }
+
+    /// Longest wait before any retry
+    pub fn max_delay(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.max_delay_milliseconds)
+    }
+}
+
+impl DatabaseConfiguration {
+    /// How long an RPC read can take, `None` for no limit
+    pub fn rpc_query_timeout(&self) -> Option<std::time::Duration> {
+        (self.rpc_query_timeout_milliseconds > 0)
+            .then(|| std::time::Duration::from_millis(self.rpc_query_timeout_milliseconds))
+    }
+
+    /// How long a cleanup job query can take, `None` for no limit
+    pub fn background_query_timeout(&self) -> Option<std::time::Duration> {
+        (self.background_query_timeout_milliseconds > 0).then(|| {
+            std::time::Duration::from_millis(self.background_query_timeout_milliseconds)
+        })
+    }
+
+    /// Build the connection pool settings
+    pub fn pool_options(&self) -> PgPoolOptions {
+        let idle_timeout = (self.idle_timeout_seconds > 0)
+            .then(|| std::time::Duration::from_secs(self.idle_timeout_seconds));
+
+        PgPoolOptions::new()
+            .max_connections(self.max_connections)
+            .min_connections(self.min_connections)
+            .acquire_timeout(std::time::Duration::from_secs(self.acquire_timeout_seconds))
+            .idle_timeout(idle_timeout)
+            .test_before_acquire(self.test_before_acquire)
+    }
+
+    /// Build database connection
+    pub fn connection(&self) -> PgConnectOptions {
+        let ssl_mode = if self.require_ssl {
+            PgSslMode::Require
+        } else {
+            PgSslMode::Prefer
+        };
+        // Pgpass allows for storing postgres passwords int he users directory.
+        // We are not going to use that.
+        let connection = PgConnectOptions::new_without_pgpass()
+            .host(&self.host)
+            .port(self.port)
+            .username(&self.username)
+            .password(self.password.expose_secret())
+            .database(&self.database_name)
+            .ssl_mode(ssl_mode)
+            // sqlx logs the statement text only, so bind parameters such as
+            // password hashes and tokens never reach the logs
+            .log_statements(LogLevelFilter::Debug)
+            .log_slow_statements(
+                LogLevelFilter::Warn,
+                std::time::Duration::from_millis(self.slow_query_threshold_milliseconds),
+            );
+
+        // Set on every connection, so runaway queries free their connection
+        if self.statement_timeout_milliseconds > 0 {
+            connection.options([("statement_timeout", self.statement_timeout_milliseconds)])
+        } else {
+            connection
+        }
+    }
+}
+
+/// Email delivery transports
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Deserialize, Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum EmailTransport {
+    /// Print emails to the console
+    #[default]
+    Console,
+    /// Deliver emails through an SMTP relay
+    Smtp,
+}
+
+/// Returns the default value for the `sender` field in `EmailConfiguration`.
+fn default_email_sender() -> String {
+    "no-reply@localhost".to_string()
+}
+
+/// Returns the default value for the `smtp_port` field in `EmailConfiguration`.
+fn default_smtp_port() -> u16 {
+    587
+}
+
+/// Configuration for sending email
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct EmailConfiguration {
+    /// How emails are delivered
+    #[serde(default)]
+    pub transport: EmailTransport,
+
+    /// The from address on outgoing emails
+    #[serde(default = "default_email_sender")]
+    pub sender: String,
+
+    /// SMTP relay host, required when the transport is `smtp`
+    pub smtp_host: Option<String>,
+
+    /// SMTP relay port
+    #[serde(default = "default_smtp_port")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub smtp_port: u16,
+
+    /// SMTP username
+    pub smtp_username: Option<String>,
+
+    /// SMTP password
+    pub smtp_password: Option<SecretString>,
+
+    /// Directory of custom email templates, named `<locale>/<template>.subject`
+    /// and `<locale>/<template>.txt`. They replace the built in templates of the
+    /// same name.
+    pub template_directory: Option<String>,
+}
+
+impl Default for EmailConfiguration {
+    fn default() -> Self {
+        Self {
+            transport: EmailTransport::default(),
+            sender: default_email_sender(),
+            smtp_host: None,
+            smtp_port: default_smtp_port(),
+            smtp_username: None,
+            smtp_password: None,
+            template_directory: None,
+        }
+    }
+}
+
+/// Returns the default value for the `service_name` field in `TelemetryConfiguration`.
+fn default_service_name() -> String {
+    "authentication_service".to_string()
+}
+
+/// Returns the default value for the `sampling_ratio` field in `TelemetryConfiguration`.
+fn default_sampling_ratio() -> f64 {
+    1.0
+}
+
+/// Returns the default value for the `mask_emails` field in `TelemetryConfiguration`.
+fn default_mask_emails() -> bool {
+    true
+}
+
+/// How log events are written to the console
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Deserialize, Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum LogFormat {
+    /// Human readable lines
+    #[default]
+    Pretty,
+    /// One JSON object per line, for log pipelines
+    Json,
+}
+
+/// Returns the default value for the `sample_rate` field in `ErrorReportingConfiguration`.
+fn default_error_reporting_sample_rate() -> f32 {
+    1.0
+}
+
+/// Configuration for reporting internal errors, such as database failures,
+/// and panics to Sentry
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ErrorReportingConfiguration {
+    /// Sentry DSN, errors are only reported when set. Requires the `sentry`
+    /// feature.
+    pub dsn: Option<SecretString>,
+
+    /// The environment errors are reported under, `APP_ENVIRONMENT` when not
+    /// set
+    pub environment: Option<String>,
+
+    /// Fraction of errors reported, between 0.0 and 1.0
+    #[serde(default = "default_error_reporting_sample_rate")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub sample_rate: f32,
+}
+
+impl Default for ErrorReportingConfiguration {
+    fn default() -> Self {
+        Self {
+            dsn: None,
+            environment: None,
+            sample_rate: default_error_reporting_sample_rate(),
+        }
+    }
+}
+
+/// Log events kept from a target, e.g. `authentication_service::database=0.1`
+/// keeps a tenth of the database module's info and debug events
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogSampling {
+    /// Module path the rule applies to, including its submodules
+    pub target: String,
+
+    /// Fraction of events kept, between 0.0 and 1.0
+    pub ratio: f64,
+}
+
+impl std::str::FromStr for LogSampling {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (target, ratio) = value
+            .split_once('=')
+            .ok_or_else(|| format!("{value} is not target=ratio"))?;
+        let ratio = ratio
+            .trim()
+            .parse()
+            .map_err(|e| format!("{value} has an invalid ratio: {e}"))?;
+
+        Ok(Self {
+            target: target.trim().to_string(),
+            ratio,
+        })
+    }
+}
+
+impl std::fmt::Display for LogSampling {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.target, self.ratio)
+    }
+}
+
+/// Configuration for logging and exporting traces with OpenTelemetry
+#[serde_as]
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct TelemetryConfiguration {
+    /// OTLP gRPC collector endpoint (e.g. `http://localhost:4317` for Jaeger or
+    /// Tempo). Spans are only exported when this is set.
+    pub otlp_endpoint: Option<String>,
+
+    /// The service name spans are reported under
+    #[serde(default = "default_service_name")]
+    pub service_name: String,
+
+    /// Fraction of new traces to sample, between 0.0 and 1.0. Traces
+    /// continued from an incoming request follow the caller's decision.
+    #[serde(default = "default_sampling_ratio")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub sampling_ratio: f64,
+
+    /// Console log format, `pretty` or `json`
+    #[serde(default)]
+    pub log_format: LogFormat,
+
+    /// Mask emails in logs, keeping the first character and the domain
+    #[serde(default = "default_mask_emails")]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub mask_emails: bool,
+
+    /// Field names scrubbed from logs, on top of the token, password and
+    /// secret fields that always are. A list, or comma separated in an
+    /// environment variable.
+    #[serde(default)]
+    #[serde_as(as = "PickFirst<(_, StringWithSeparator<CommaSeparator, String>)>")]
+    pub redacted_fields: Vec<String>,
+
+    /// Info, debug and trace events kept per target, as `target=ratio`, so
+    /// noisy modules don't overwhelm log pipelines. Warnings and errors are
+    /// always kept. A list, or comma separated in an environment variable.
+    #[serde(default)]
+    #[serde_as(as = "PickFirst<(Vec<DisplayFromStr>, StringWithSeparator<CommaSeparator, LogSampling>)>")]
+    pub log_sampling: Vec<LogSampling>,
+}
+
+impl Default for TelemetryConfiguration {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: None,
+            service_name: default_service_name(),
+            sampling_ratio: default_sampling_ratio(),
+            log_format: LogFormat::default(),
+            mask_emails: default_mask_emails(),
+            redacted_fields: Vec::new(),
+            log_sampling: Vec::new(),
+        }
+    }
+}
+
+/// Returns the default value for the `port` field in `HttpConfiguration`.
+fn default_http_port() -> u16 {
+    8082
+}
+
+/// Configuration for the REST/JSON gateway
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct HttpConfiguration {
+    /// Serve the REST/JSON gateway alongside the gRPC server
+    #[serde(default)]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub enabled: bool,
+
+    /// The port the gateway binds to, on the application ip address
+    #[serde(default = "default_http_port")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub port: u16,
+}
+
+impl Default for HttpConfiguration {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_http_port(),
+        }
+    }
+}
+
+/// Returns the default value for the `max_attempts` field in `WebhooksConfiguration`.
+fn default_webhook_max_attempts() -> u32 {
+    8
+}
+
+/// Returns the default value for the `retry_base_seconds` field in `WebhooksConfiguration`.
+fn default_webhook_retry_base_seconds() -> u64 {
+    30
+}
+
+/// Returns the default value for the `poll_interval_seconds` field in `WebhooksConfiguration`.
+fn default_webhook_poll_interval_seconds() -> u64 {
+    5
+}
+
+/// Returns the default value for the `request_timeout_seconds` field in `WebhooksConfiguration`.
+fn default_webhook_request_timeout_seconds() -> u64 {
+    10
+}
+
+/// Configuration for delivering webhook events
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct WebhooksConfiguration {
+    /// Attempts made before a delivery is marked failed
+    #[serde(default = "default_webhook_max_attempts")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_attempts: u32,
+
+    /// Delay before the first retry, doubled for each retry after it
+    #[serde(default = "default_webhook_retry_base_seconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub retry_base_seconds: u64,
+
+    /// How often the delivery queue is checked for due deliveries
+    #[serde(default = "default_webhook_poll_interval_seconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub poll_interval_seconds: u64,
+
+    /// How long to wait for an endpoint to respond
+    #[serde(default = "default_webhook_request_timeout_seconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub request_timeout_seconds: u64,
+}
+
+impl Default for WebhooksConfiguration {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_webhook_max_attempts(),
+            retry_base_seconds: default_webhook_retry_base_seconds(),
+            poll_interval_seconds: default_webhook_poll_interval_seconds(),
+            request_timeout_seconds: default_webhook_request_timeout_seconds(),
+        }
+    }
+}
+
+/// Returns the default value for the `max_attempts` field in `OutboxConfiguration`.
+fn default_outbox_max_attempts() -> u32 {
+    10
+}
+
+/// Returns the default value for the `retry_base_seconds` field in `OutboxConfiguration`.
+fn default_outbox_retry_base_seconds() -> u64 {
+    10
+}
+
+/// Returns the default value for the `poll_interval_seconds` field in `OutboxConfiguration`.
+fn default_outbox_poll_interval_seconds() -> u64 {
+    1
+}
+
+/// Configuration for processing the transactional outbox
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct OutboxConfiguration {
+    /// Attempts made before an entry is marked failed
+    #[serde(default = "default_outbox_max_attempts")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_attempts: u32,
+
+    /// Delay before the first retry, doubled for each retry after it
+    #[serde(default = "default_outbox_retry_base_seconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub retry_base_seconds: u64,
+
+    /// How often the outbox is checked for due entries
+    #[serde(default = "default_outbox_poll_interval_seconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub poll_interval_seconds: u64,
+}
+
+impl Default for OutboxConfiguration {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_outbox_max_attempts(),
+            retry_base_seconds: default_outbox_retry_base_seconds(),
+            poll_interval_seconds: default_outbox_poll_interval_seconds(),
+        }
+    }
+}
+
+/// Where authentication events are published
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Deserialize, Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum EventBusTransport {
+    /// Do not publish events
+    #[default]
+    None,
+    /// Publish to NATS subjects, requires the `nats` feature
+    Nats,
+    /// Publish to Kafka topics, requires the `kafka` feature
+    Kafka,
+}
+
+/// Returns the default value for the `subject_prefix` field in `EventBusConfiguration`.
+fn default_event_bus_subject_prefix() -> String {
+    "auth".to_string()
+}
+
+/// Configuration for publishing authentication events to Kafka or NATS
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct EventBusConfiguration {
+    /// Where events are published
+    #[serde(default)]
+    pub transport: EventBusTransport,
+
+    /// NATS server url or Kafka bootstrap servers, required when the transport
+    /// is not `none`
+    pub url: Option<String>,
+
+    /// Prepended to the event type to give the subject (or topic), e.g.
+    /// `auth.user.login`
+    #[serde(default = "default_event_bus_subject_prefix")]
+    pub subject_prefix: String,
+}
+
+impl Default for EventBusConfiguration {
+    fn default() -> Self {
+        Self {
+            transport: EventBusTransport::default(),
+            url: None,
+            subject_prefix: default_event_bus_subject_prefix(),
+        }
+    }
+}
+
+/// Which CAPTCHA service verifies tokens
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Deserialize, Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum CaptchaProvider {
+    /// CAPTCHA tokens are not checked
+    #[default]
+    None,
+    /// hCaptcha, https://www.hcaptcha.com
+    Hcaptcha,
+    /// Google reCAPTCHA, https://developers.google.com/recaptcha
+    Recaptcha,
+}
+
+/// Returns the default value for the `failure_threshold` field in `CaptchaConfiguration`.
+fn default_captcha_failure_threshold() -> u32 {
+    5
+}
+
+/// Returns the default value for the `failure_window_seconds` field in `CaptchaConfiguration`.
+fn default_captcha_failure_window_seconds() -> u64 {
+    900
+}
+
+/// Configuration for CAPTCHA verification on register and login
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct CaptchaConfiguration {
+    /// The CAPTCHA service, `none` never asks for a CAPTCHA
+    #[serde(default)]
+    pub provider: CaptchaProvider,
+
+    /// The provider secret key, required when the provider is not `none`
+    pub secret: Option<SecretString>,
+
+    /// Require a CAPTCHA on every register and login request
+    #[serde(default)]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub required: bool,
+
+    /// Failed logins from an IP address before it must solve a CAPTCHA, zero
+    /// to only require one when `required` is set
+    #[serde(default = "default_captcha_failure_threshold")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub failure_threshold: u32,
+
+    /// How long failed logins from an IP address are counted for
+    #[serde(default = "default_captcha_failure_window_seconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub failure_window_seconds: u64,
+}
+
+impl Default for CaptchaConfiguration {
+    fn default() -> Self {
+        Self {
+            provider: CaptchaProvider::default(),
+            secret: None,
+            required: false,
+            failure_threshold: default_captcha_failure_threshold(),
+            failure_window_seconds: default_captcha_failure_window_seconds(),
+        }
+    }
+}
+
+/// Returns the default value for the `enabled` field in `LoginThrottleConfiguration`.
+fn default_login_throttle_enabled() -> bool {
+    true
+}
+
+/// Returns the default value for the `free_attempts` field in `LoginThrottleConfiguration`.
+fn default_login_throttle_free_attempts() -> u32 {
+    3
+}
+
+/// Returns the default value for the `base_delay_seconds` field in `LoginThrottleConfiguration`.
+fn default_login_throttle_base_delay_seconds() -> u64 {
+    1
+}
+
+/// Returns the default value for the `max_delay_seconds` field in `LoginThrottleConfiguration`.
+fn default_login_throttle_max_delay_seconds() -> u64 {
+    // Fifteen minutes
+    900
+}
+
+/// Returns the default value for the `reset_after_seconds` field in `LoginThrottleConfiguration`.
+fn default_login_throttle_reset_after_seconds() -> u64 {
+    // One hour
+    3_600
+}
+
+/// Configuration for throttling failed logins per IP address and email
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct LoginThrottleConfiguration {
+    /// Throttle failed logins
+    #[serde(default = "default_login_throttle_enabled")]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub enabled: bool,
+
+    /// Failed logins for an IP address and email before they are locked out
+    #[serde(default = "default_login_throttle_free_attempts")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub free_attempts: u32,
+
+    /// The first lock out, doubled for each further failed login
+    #[serde(default = "default_login_throttle_base_delay_seconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub base_delay_seconds: u64,
+
+    /// The longest lock out
+    #[serde(default = "default_login_throttle_max_delay_seconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_delay_seconds: u64,
+
+    /// How long without a failed login before the count starts again
+    #[serde(default = "default_login_throttle_reset_after_seconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub reset_after_seconds: u64,
+}
+
+impl Default for LoginThrottleConfiguration {
+    fn default() -> Self {
+        Self {
+            enabled: default_login_throttle_enabled(),
+            free_attempts: default_login_throttle_free_attempts(),
+            base_delay_seconds: default_login_throttle_base_delay_seconds(),
+            max_delay_seconds: default_login_throttle_max_delay_seconds(),
+            reset_after_seconds: default_login_throttle_reset_after_seconds(),
+        }
+    }
+}
+
+/// Configuration for the proxies and load balancers in front of the service.
+/// Behind them the peer address is the proxy, so the client IP address is
+/// taken from the `forwarded` or `x-forwarded-for` metadata, but only when
+/// the peer is a trusted proxy, see `utils::client_ip`.
+#[serde_as]
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+pub struct TrustedProxiesConfiguration {
+    /// CIDRs of the trusted proxies, empty to always use the peer address. A
+    /// list, or comma separated in an environment variable, e.g.
+    /// `APP__TRUSTED_PROXIES__CIDRS=10.0.0.0/8,fd00::/8`
+    #[serde(default)]
+    #[serde_as(as = "PickFirst<(Vec<DisplayFromStr>, StringWithSeparator<CommaSeparator, IpNet>)>")]
+    pub cidrs: Vec<IpNet>,
+}
+
+impl TrustedProxiesConfiguration {
+    /// Is the address one of the trusted proxies
+    pub fn is_trusted(&self, ip: &IpAddr) -> bool {
+        self.cidrs.iter().any(|cidr| cidr.contains(ip))
+    }
+}
+
+/// Returns the default value for the `enabled` field in `LoadSheddingConfiguration`.
+fn default_load_shedding_enabled() -> bool {
+    true
+}
+
+/// Returns the default value for the `max_concurrent_requests` field in `LoadSheddingConfiguration`.
+fn default_max_concurrent_requests() -> usize {
+    512
+}
+
+/// Returns the default value for the `max_concurrent_expensive_requests` field in `LoadSheddingConfiguration`.
+fn default_max_concurrent_expensive_requests() -> usize {
+    // Password hashing is CPU bound, so keep this near the number of cores
+    8
+}
+
+/// Returns the default value for the `max_queued_password_hashes` field in `LoadSheddingConfiguration`.
+fn default_max_queued_password_hashes() -> usize {
+    64
+}
+
+/// Configuration for rejecting requests with `Unavailable` once the server is at capacity
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct LoadSheddingConfiguration {
+    /// Shed requests over the limits instead of serving them
+    #[serde(default = "default_load_shedding_enabled")]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub enabled: bool,
+
+    /// Requests in flight across all services
+    #[serde(default = "default_max_concurrent_requests")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_concurrent_requests: usize,
+
+    /// Requests in flight to endpoints that hash passwords (login, register
+    /// and update password), counted within `max_concurrent_requests`
+    #[serde(default = "default_max_concurrent_expensive_requests")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_concurrent_expensive_requests: usize,
+
+    /// Password hashes waiting for or running on the blocking thread pool
+    #[serde(default = "default_max_queued_password_hashes")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_queued_password_hashes: usize,
+}
+
+impl Default for LoadSheddingConfiguration {
+    fn default() -> Self {
+        Self {
+            enabled: default_load_shedding_enabled(),
+            max_concurrent_requests: default_max_concurrent_requests(),
+            max_concurrent_expensive_requests: default_max_concurrent_expensive_requests(),
+            max_queued_password_hashes: default_max_queued_password_hashes(),
+        }
+    }
+}
+
+/// Returns the default value for the `max_decoding_message_size` field in `GrpcConfiguration`.
+fn default_max_decoding_message_size() -> usize {
+    // Tonic's default, 4 MiB
+    4 * 1024 * 1024
+}
+
+/// Returns the default value for the `request_timeout_seconds` field in `GrpcConfiguration`.
+fn default_grpc_request_timeout_seconds() -> u64 {
+    30
+}
+
+/// Returns the default value for the `tcp_keepalive_seconds` field in `GrpcConfiguration`.
+fn default_tcp_keepalive_seconds() -> u64 {
+    60
+}
+
+/// Returns the default value for the `http2_keepalive_interval_seconds` field in `GrpcConfiguration`.
+fn default_http2_keepalive_interval_seconds() -> u64 {
+    30
+}
+
+/// Returns the default value for the `http2_keepalive_timeout_seconds` field in `GrpcConfiguration`.
+fn default_http2_keepalive_timeout_seconds() -> u64 {
+    20
+}
+
+/// Configuration for tuning the Tonic server
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct GrpcConfiguration {
+    /// Largest request message, in bytes, a service decodes. Larger messages
+    /// fail with `ResourceExhausted`
+    #[serde(default = "default_max_decoding_message_size")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_decoding_message_size: usize,
+
+    /// How long a call may take before it fails with `Cancelled`. Clients can
+    /// ask for less with the `grpc-timeout` header
+    #[serde(default = "default_grpc_request_timeout_seconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub request_timeout_seconds: u64,
+
+    /// Idle time before TCP keepalive probes are sent, 0 turns them off
+    #[serde(default = "default_tcp_keepalive_seconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub tcp_keepalive_seconds: u64,
+
+    /// How often HTTP/2 pings are sent on a connection, 0 turns them off
+    #[serde(default = "default_http2_keepalive_interval_seconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub http2_keepalive_interval_seconds: u64,
+
+    /// How long to wait for a ping to be acknowledged before closing the
+    /// connection
+    #[serde(default = "default_http2_keepalive_timeout_seconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub http2_keepalive_timeout_seconds: u64,
+}
+
+impl Default for GrpcConfiguration {
+    fn default() -> Self {
+        Self {
+            max_decoding_message_size: default_max_decoding_message_size(),
+            request_timeout_seconds: default_grpc_request_timeout_seconds(),
+            tcp_keepalive_seconds: default_tcp_keepalive_seconds(),
+            http2_keepalive_interval_seconds: default_http2_keepalive_interval_seconds(),
+            http2_keepalive_timeout_seconds: default_http2_keepalive_timeout_seconds(),
+        }
+    }
+}
+
+impl GrpcConfiguration {
+    /// The per call timeout
+    pub fn request_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.request_timeout_seconds)
+    }
+
+    /// The TCP keepalive idle time, `None` when turned off
+    pub fn tcp_keepalive(&self) -> Option<std::time::Duration> {
+        (self.tcp_keepalive_seconds > 0)
+            .then(|| std::time::Duration::from_secs(self.tcp_keepalive_seconds))
+    }
+
+    /// The HTTP/2 ping interval, `None` when turned off
+    pub fn http2_keepalive_interval(&self) -> Option<std::time::Duration> {
+        (self.http2_keepalive_interval_seconds > 0)
+            .then(|| std::time::Duration::from_secs(self.http2_keepalive_interval_seconds))
+    }
+
+    /// How long to wait for a ping acknowledgement
+    pub fn http2_keepalive_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.http2_keepalive_timeout_seconds)
+    }
+}
+
+/// Encodings gRPC messages can be compressed with
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize, Display, EnumString)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum CompressionEncoding {
+    /// Widely supported, including by browsers
+    Gzip,
+    /// Smaller and faster than gzip, where clients support it
+    Zstd,
+}
+
+impl From<CompressionEncoding> for tonic::codec::CompressionEncoding {
+    fn from(encoding: CompressionEncoding) -> Self {
+        match encoding {
+            CompressionEncoding::Gzip => Self::Gzip,
+            CompressionEncoding::Zstd => Self::Zstd,
+        }
+    }
+}
+
+/// Returns the default value for the `accept` and `send` fields in `CompressionConfiguration`.
+fn default_compression_encodings() -> Vec<CompressionEncoding> {
+    vec![CompressionEncoding::Gzip, CompressionEncoding::Zstd]
+}
+
+/// Configuration for compressing gRPC messages. Responses are only compressed
+/// with an encoding the client lists in `grpc-accept-encoding`, so clients
+/// without compression support are answered uncompressed.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct CompressionConfiguration {
+    /// Encodings requests can be compressed with. A list, or comma separated
+    /// in an environment variable, e.g. `APP__COMPRESSION__ACCEPT=gzip,zstd`
+    #[serde(default = "default_compression_encodings")]
+    #[serde_as(as = "PickFirst<(_, StringWithSeparator<CommaSeparator, CompressionEncoding>)>")]
+    pub accept: Vec<CompressionEncoding>,
+
+    /// Encodings responses are compressed with, empty to send them uncompressed
+    #[serde(default = "default_compression_encodings")]
+    #[serde_as(as = "PickFirst<(_, StringWithSeparator<CommaSeparator, CompressionEncoding>)>")]
+    pub send: Vec<CompressionEncoding>,
+
+    /// Overrides keyed by service, one of `GRPC_SERVICES`
+    #[serde(default)]
+    pub services: HashMap<String, ServiceCompressionConfiguration>,
+}
+
+impl Default for CompressionConfiguration {
+    fn default() -> Self {
+        Self {
+            accept: default_compression_encodings(),
+            send: default_compression_encodings(),
+            services: HashMap::new(),
+        }
+    }
+}
+
+/// Compression of one service, unset fields use the `compression` settings
+#[serde_as]
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+pub struct ServiceCompressionConfiguration {
+    /// Encodings requests to the service can be compressed with
+    #[serde(default)]
+    #[serde_as(as = "Option<PickFirst<(_, StringWithSeparator<CommaSeparator, CompressionEncoding>)>>")]
+    pub accept: Option<Vec<CompressionEncoding>>,
+
+    /// Encodings the service's responses are compressed with
+    #[serde(default)]
+    #[serde_as(as = "Option<PickFirst<(_, StringWithSeparator<CommaSeparator, CompressionEncoding>)>>")]
+    pub send: Option<Vec<CompressionEncoding>>,
+}
+
+impl CompressionConfiguration {
+    /// The encodings a service accepts and sends, as (accept, send)
+    ///
+    /// ## Parameters
+    ///
+    /// - `service: &str` - One of `GRPC_SERVICES`
+    pub fn encodings(&self, service: &str) -> (&[CompressionEncoding], &[CompressionEncoding]) {
+        let overrides = self.services.get(service);
+        let accept = overrides.and_then(|o| o.accept.as_deref()).unwrap_or(&self.accept);
+        let send = overrides.and_then(|o| o.send.as_deref()).unwrap_or(&self.send);
+
+        (accept, send)
+    }
+}
+
+/// Configuration for another address or Unix domain socket serving gRPC, e.g.
+/// the admin service on localhost only, or a socket for a sidecar
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct ListenerConfiguration {
+    /// Names the listener in logs
+    pub name: String,
+
+    /// The `ip:port` to bind, set this or `unix_socket`
+    pub address: Option<String>,
+
+    /// The path of a Unix domain socket to bind, set this or `address`
+    pub unix_socket: Option<String>,
+
+    /// The services served on this listener, see `GRPC_SERVICES`
+    #[serde(default = "default_services")]
+    #[serde_as(as = "PickFirst<(_, StringWithSeparator<CommaSeparator, String>)>")]
+    pub services: Vec<String>,
+}
+
+/// Returns the default value for the `address` field in `InternalConfiguration`.
+fn default_internal_address() -> String {
+    "127.0.0.1:8091".to_string()
+}
+
+/// Returns the default value for the `services` field in `InternalConfiguration`.
+fn default_internal_services() -> Vec<String> {
+    vec!["admin".to_string()]
+}
+
+/// Configuration for serving the admin services on an internal address only.
+/// When enabled the services are moved off the main address. The users and
+/// sessions services stay public by default, as they carry the RPCs users call
+/// on their own account.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct InternalConfiguration {
+    /// Serve `services` on `address` instead of the main address
+    #[serde(default)]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub enabled: bool,
+
+    /// The `ip:port` to bind, keep this off the public network
+    #[serde(default = "default_internal_address")]
+    pub address: String,
+
+    /// The services moved to the internal server, see `GRPC_SERVICES`
+    #[serde(default = "default_internal_services")]
+    #[serde_as(as = "PickFirst<(_, StringWithSeparator<CommaSeparator, String>)>")]
+    pub services: Vec<String>,
+}
+
+impl Default for InternalConfiguration {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: default_internal_address(),
+            services: default_internal_services(),
+        }
+    }
+}
+
+/// Returns the default value for the `retry_interval_seconds` field in `ReadinessConfiguration`.
+fn default_readiness_retry_interval_seconds() -> u64 {
+    5
+}
+
+/// Configuration for the dependency checks run before the server reports
+/// healthy, see `readiness`
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ReadinessConfiguration {
+    /// Also wait for the SMTP relay to accept a connection
+    #[serde(default)]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub check_email: bool,
+
+    /// How long to wait before checking again after a check fails
+    #[serde(default = "default_readiness_retry_interval_seconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub retry_interval_seconds: u64,
+}
+
+impl Default for ReadinessConfiguration {
+    fn default() -> Self {
+        Self {
+            check_email: false,
+            retry_interval_seconds: default_readiness_retry_interval_seconds(),
+        }
+    }
+}
+
+/// Returns the default value for the `error_code` field in `FaultRuleConfiguration`.
+fn default_fault_error_code() -> String {
+    "UNAVAILABLE".to_string()
+}
+
+/// Configuration for injecting faults into requests, so clients and their
+/// retries can be tested against failures. Needs the `fault_injection`
+/// feature, see `middleware::FaultInjectionLayer`.
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+pub struct FaultInjectionConfiguration {
+    /// Inject the faults of `rules`
+    #[serde(default)]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub enabled: bool,
+
+    /// The faults for each endpoint, the first rule matching a request applies
+    #[serde(default)]
+    pub rules: Vec<FaultRuleConfiguration>,
+}
+
+/// The faults injected into the requests to an endpoint
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct FaultRuleConfiguration {
+    /// The gRPC path, e.g. `/authentication.v1.AuthenticationService/Login`, a
+    /// service's paths, e.g. `/authentication.v1.UsersService/*`, or `*` for every path
+    pub path: String,
+
+    /// Percent of requests delayed by `latency_milliseconds`
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub latency_percent: f64,
+
+    /// How long delayed requests wait before they are served
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub latency_milliseconds: u64,
+
+    /// Percent of requests failed with `error_code` instead of being served
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub error_percent: f64,
+
+    /// The gRPC status code name of injected errors, e.g. `UNAVAILABLE`
+    #[serde(default = "default_fault_error_code")]
+    pub error_code: String,
+
+    /// Percent of requests served but answered by resetting the stream, as
+    /// if the response was lost
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub drop_percent: f64,
+}
+
+/// The `SameSite` attribute of the refresh token cookie
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize, Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum CookieSameSite {
+    /// Only sent on requests from the cookie's own site
+    Strict,
+    /// Also sent when navigating to the site from another one
+    Lax,
+    /// Sent on cross-site requests too, needs `secure`
+    None,
+}
+
+impl From<CookieSameSite> for cookie::SameSite {
+    fn from(same_site: CookieSameSite) -> Self {
+        match same_site {
+            CookieSameSite::Strict => cookie::SameSite::Strict,
+            CookieSameSite::Lax => cookie::SameSite::Lax,
+            CookieSameSite::None => cookie::SameSite::None,
+        }
+    }
+}
+
+/// Returns the default value for the `name` field in `RefreshCookieConfiguration`.
+fn default_refresh_cookie_name() -> String {
+    "refresh_token".to_string()
+}
+
+/// Returns the default value for the `path` field in `RefreshCookieConfiguration`.
+fn default_refresh_cookie_path() -> String {
+    "/".to_string()
+}
+
+/// Configuration for the attributes of the refresh token cookie, for
+/// deployments behind different proxies and domains
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct RefreshCookieConfiguration {
+    /// The cookie name, login sets it and refresh and logout read it
+    #[serde(default = "default_refresh_cookie_name")]
+    pub name: String,
+
+    /// The path the browser sends the cookie back to
+    #[serde(default = "default_refresh_cookie_path")]
+    pub path: String,
+
+    /// The cookie domain, instead of `application.ip_address`
+    #[serde(default)]
+    pub domain: Option<String>,
+
+    /// The `SameSite` attribute, unset leaves it to the browser
+    #[serde(default)]
+    pub same_site: Option<CookieSameSite>,
+
+    /// Only send the cookie over https
+    #[serde(default)]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub secure: bool,
+
+    /// Set the `Partitioned` attribute (CHIPS), so the cookie is kept per top
+    /// level site when the service is embedded cross-site. Needs `secure`
+    #[serde(default)]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub partitioned: bool,
+}
+
+impl Default for RefreshCookieConfiguration {
+    fn default() -> Self {
+        Self {
+            name: default_refresh_cookie_name(),
+            path: default_refresh_cookie_path(),
+            domain: None,
+            same_site: None,
+            secure: false,
+            partitioned: false,
+        }
+    }
+}
+
+/// Configuration for reading the refresh token from request metadata, for
+/// mobile and CLI clients that can't keep cookies. The refresh token cookie
+/// is always accepted and is checked first.
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+pub struct RefreshTokenConfiguration {
+    /// Also accept the refresh token as `authorization: Bearer <token>`
+    #[serde(default)]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub accept_bearer: bool,
+
+    /// Also accept the refresh token as the value of this metadata key, e.g.
+    /// `x-refresh-token`
+    #[serde(default)]
+    pub metadata_key: Option<String>,
+}
+
+/// Returns the default value for the `expiry_minutes` field in `MagicLinkConfiguration`.
+fn default_magic_link_expiry_minutes() -> u64 {
+    15
+}
+
+/// Returns the default value for the `link_url` field in `MagicLinkConfiguration`.
+fn default_magic_link_url() -> String {
+    "http://localhost:8080/magic-link".to_string()
+}
+
+/// Configuration for passwordless login with an emailed magic link
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct MagicLinkConfiguration {
+    /// Allow logging in with a magic link
+    #[serde(default)]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub enabled: bool,
+
+    /// How many minutes the emailed link can be used for
+    #[serde(default = "default_magic_link_expiry_minutes")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub expiry_minutes: u64,
+
+    /// The client page the link opens, with the token in the `token` query
+    /// parameter. The page completes the login with `CompleteMagicLink`
+    #[serde(default = "default_magic_link_url")]
+    pub link_url: String,
+}
+
+impl Default for MagicLinkConfiguration {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            expiry_minutes: default_magic_link_expiry_minutes(),
+            link_url: default_magic_link_url(),
+        }
+    }
+}
+
+impl MagicLinkConfiguration {
+    /// The link emailed to the user, `link_url` with the token added
+    pub fn link(&self, token: &str) -> String {
+        let separator = if self.link_url.contains('?') { '&' } else { '?' };
+        format!("{}{separator}token={token}", self.link_url)
+    }
+}
+
+/// Returns the default value for the `expiry_minutes` field in `PasswordResetConfiguration`.
+fn default_password_reset_expiry_minutes() -> u64 {
+    60
+}
+
+/// Returns the default value for the `link_url` field in `PasswordResetConfiguration`.
+fn default_password_reset_url() -> String {
+    "http://localhost:8080/password-reset".to_string()
+}
+
+/// Returns the default value for the `max_requests_per_email` field in `PasswordResetConfiguration`.
+fn default_password_reset_max_requests_per_email() -> u32 {
+    3
+}
+
+/// Returns the default value for the `max_requests_per_ip` field in `PasswordResetConfiguration`.
+fn default_password_reset_max_requests_per_ip() -> u32 {
+    10
+}
+
+/// Returns the default value for the `window_seconds` field in `PasswordResetConfiguration`.
+fn default_password_reset_window_seconds() -> u64 {
+    // One hour
+    3_600
+}
+
+/// Configuration for emailed password reset links
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct PasswordResetConfiguration {
+    /// How many minutes the emailed link can be used for
+    #[serde(default = "default_password_reset_expiry_minutes")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub expiry_minutes: u64,
+
+    /// The client page the link opens, with the token in the `token` query
+    /// parameter. The page sets the new password with `ResetPassword`
+    #[serde(default = "default_password_reset_url")]
+    pub link_url: String,
+
+    /// Resets requested for an email within the window before more are
+    /// ignored, zero for no limit
+    #[serde(default = "default_password_reset_max_requests_per_email")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_requests_per_email: u32,
+
+    /// Resets requested from an IP address within the window before more
+    /// are ignored, zero for no limit
+    #[serde(default = "default_password_reset_max_requests_per_ip")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_requests_per_ip: u32,
+
+    /// How long requests are counted for
+    #[serde(default = "default_password_reset_window_seconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub window_seconds: u64,
+}
+
+impl Default for PasswordResetConfiguration {
+    fn default() -> Self {
+        Self {
+            expiry_minutes: default_password_reset_expiry_minutes(),
+            link_url: default_password_reset_url(),
+            max_requests_per_email: default_password_reset_max_requests_per_email(),
+            max_requests_per_ip: default_password_reset_max_requests_per_ip(),
+            window_seconds: default_password_reset_window_seconds(),
+        }
+    }
+}
+
+impl PasswordResetConfiguration {
+    /// The link emailed to the user, `link_url` with the token added
+    pub fn link(&self, token: &str) -> String {
+        let separator = if self.link_url.contains('?') { '&' } else { '?' };
+        format!("{}{separator}token={token}", self.link_url)
+    }
+}
+
+/// Returns the default value for the `poll_interval_seconds` field in `VerificationRemindersConfiguration`.
+fn default_verification_reminders_poll_interval_seconds() -> u64 {
+    // One hour
+    3_600
+}
+
+/// Returns the default value for the `unverified_after_days` field in `VerificationRemindersConfiguration`.
+fn default_verification_reminders_unverified_after_days() -> u32 {
+    3
+}
+
+/// Returns the default value for the `max_reminders` field in `VerificationRemindersConfiguration`.
+fn default_verification_reminders_max_reminders() -> u32 {
+    3
+}
+
+/// Returns the default value for the `interval_days` field in `VerificationRemindersConfiguration`.
+fn default_verification_reminders_interval_days() -> u32 {
+    2
+}
+
+/// Returns the default value for the `deactivate_after_days` field in `VerificationRemindersConfiguration`.
+fn default_verification_reminders_deactivate_after_days() -> u32 {
+    30
+}
+
+/// Returns the default value for the `link_url` field in `VerificationRemindersConfiguration`.
+fn default_verification_reminders_link_url() -> String {
+    "http://localhost:8080/verify-email".to_string()
+}
+
+/// Returns the default value for the `expiry_hours` field in `VerificationRemindersConfiguration`.
+fn default_verification_reminders_expiry_hours() -> u32 {
+    24
+}
+
+/// Returns the default value for the `batch_size` field in `VerificationRemindersConfiguration`.
+fn default_verification_reminders_batch_size() -> u32 {
+    100
+}
+
+/// Configuration for the job reminding unverified users to verify their email
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct VerificationRemindersConfiguration {
+    /// Run the reminder job in the background
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// How often the job looks for users to remind or deactivate, read at
+    /// start up
+    #[serde(default = "default_verification_reminders_poll_interval_seconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub poll_interval_seconds: u64,
+
+    /// Days after registering before the first reminder is sent
+    #[serde(default = "default_verification_reminders_unverified_after_days")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub unverified_after_days: u32,
+
+    /// The most reminders sent to a user
+    #[serde(default = "default_verification_reminders_max_reminders")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_reminders: u32,
+
+    /// Days between the first and second reminders, doubling after each one
+    #[serde(default = "default_verification_reminders_interval_days")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub interval_days: u32,
+
+    /// Days after registering before users still unverified are
+    /// deactivated, zero to never deactivate them
+    #[serde(default = "default_verification_reminders_deactivate_after_days")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub deactivate_after_days: u32,
+
+    /// The client page the verification link opens, with the token in the
+    /// `token` query parameter
+    #[serde(default = "default_verification_reminders_link_url")]
+    pub link_url: String,
+
+    /// How many hours the verification link can be used for
+    #[serde(default = "default_verification_reminders_expiry_hours")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub expiry_hours: u32,
+
+    /// The most users reminded each time the job runs
+    #[serde(default = "default_verification_reminders_batch_size")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub batch_size: u32,
+}
+
+impl Default for VerificationRemindersConfiguration {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            poll_interval_seconds: default_verification_reminders_poll_interval_seconds(),
+            unverified_after_days: default_verification_reminders_unverified_after_days(),
+            max_reminders: default_verification_reminders_max_reminders(),
+            interval_days: default_verification_reminders_interval_days(),
+            deactivate_after_days: default_verification_reminders_deactivate_after_days(),
+            link_url: default_verification_reminders_link_url(),
+            expiry_hours: default_verification_reminders_expiry_hours(),
+            batch_size: default_verification_reminders_batch_size(),
+        }
+    }
+}
+
+impl VerificationRemindersConfiguration {
+    /// The link emailed to the user, `link_url` with the token added
+    pub fn link(&self, token: &str) -> String {
+        let separator = if self.link_url.contains('?') { '&' } else { '?' };
+        format!("{}{separator}token={token}", self.link_url)
+    }
+}
+
+/// How passkeys are used when logging in
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Deserialize, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum PasskeyMode {
+    /// A passkey logs in on its own, password login still works
+    #[default]
+    Primary,
+    /// A passkey is needed as well as the password, for users who have
+    /// registered one
+    SecondFactor,
+}
+
+/// Returns the default value for the `relying_party_id` field in `PasskeysConfiguration`.
+fn default_passkeys_relying_party_id() -> String {
+    "localhost".to_string()
+}
+
+/// Returns the default value for the `relying_party_origin` field in `PasskeysConfiguration`.
+fn default_passkeys_relying_party_origin() -> String {
+    "http://localhost:8080".to_string()
+}
+
+/// Returns the default value for the `relying_party_name` field in `PasskeysConfiguration`.
+fn default_passkeys_relying_party_name() -> String {
+    "Authentication Service".to_string()
+}
+
+/// Configuration for WebAuthn passkeys
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct PasskeysConfiguration {
+    /// Allow registering and logging in with passkeys
+    #[serde(default)]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub enabled: bool,
+
+    /// Whether a passkey logs in on its own or as a second factor
+    #[serde(default)]
+    pub mode: PasskeyMode,
+
+    /// The relying party id, the domain passkeys are bound to
+    #[serde(default = "default_passkeys_relying_party_id")]
+    pub relying_party_id: String,
+
+    /// The origin of the client pages using passkeys, it must be the relying
+    /// party id or a subdomain of it
+    #[serde(default = "default_passkeys_relying_party_origin")]
+    pub relying_party_origin: String,
+
+    /// The name shown by the authenticator
+    #[serde(default = "default_passkeys_relying_party_name")]
+    pub relying_party_name: String,
+}
+
+impl Default for PasskeysConfiguration {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: PasskeyMode::default(),
+            relying_party_id: default_passkeys_relying_party_id(),
+            relying_party_origin: default_passkeys_relying_party_origin(),
+            relying_party_name: default_passkeys_relying_party_name(),
+        }
+    }
+}
+
+/// Returns the default value for the `url` field in `LdapConfiguration`.
+fn default_ldap_url() -> String {
+    "ldap://localhost:389".to_string()
+}
+
+/// Returns the default value for the `search_filter` field in `LdapConfiguration`.
+fn default_ldap_search_filter() -> String {
+    "(mail={email})".to_string()
+}
+
+/// Returns the default value for the `email_attribute` field in `LdapConfiguration`.
+fn default_ldap_email_attribute() -> String {
+    "mail".to_string()
+}
+
+/// Returns the default value for the `name_attribute` field in `LdapConfiguration`.
+fn default_ldap_name_attribute() -> String {
+    "displayName".to_string()
+}
+
+/// Returns the default value for the `timeout_seconds` field in `LdapConfiguration`.
+fn default_ldap_timeout_seconds() -> u64 {
+    5
+}
+
+/// Configuration for logging in against an LDAP or Active Directory server,
+/// alongside local passwords
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct LdapConfiguration {
+    /// Check passwords that don't match a local user against the directory,
+    /// requires the `ldap` feature
+    #[serde(default)]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub enabled: bool,
+
+    /// The directory server, `ldap://` or `ldaps://`
+    #[serde(default = "default_ldap_url")]
+    pub url: String,
+
+    /// The service account that searches for users, anonymous when not set
+    pub bind_dn: Option<String>,
+
+    /// The service account password
+    pub bind_password: Option<SecretString>,
+
+    /// Where users are searched for, e.g. `ou=people,dc=example,dc=com`
+    #[serde(default)]
+    pub base_dn: String,
+
+    /// Finds the user's entry, `{email}` is replaced with the escaped login email
+    #[serde(default = "default_ldap_search_filter")]
+    pub search_filter: String,
+
+    /// The attribute holding the user's email
+    #[serde(default = "default_ldap_email_attribute")]
+    pub email_attribute: String,
+
+    /// The attribute holding the user's name, used when provisioning them
+    #[serde(default = "default_ldap_name_attribute")]
+    pub name_attribute: String,
+
+    /// How long to wait for the directory server
+    #[serde(default = "default_ldap_timeout_seconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub timeout_seconds: u64,
+}
+
+impl Default for LdapConfiguration {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: default_ldap_url(),
+            bind_dn: None,
+            bind_password: None,
+            base_dn: String::new(),
+            search_filter: default_ldap_search_filter(),
+            email_attribute: default_ldap_email_attribute(),
+            name_attribute: default_ldap_name_attribute(),
+            timeout_seconds: default_ldap_timeout_seconds(),
+        }
+    }
+}
+
+/// Returns the default value for the `entity_id` field in `SamlConfiguration`.
+fn default_saml_entity_id() -> String {
+    "http://localhost:8082/saml/metadata".to_string()
+}
+
+/// Returns the default value for the `acs_url` field in `SamlConfiguration`.
+fn default_saml_acs_url() -> String {
+    "http://localhost:8082/saml/acs".to_string()
+}
+
+/// Returns the default value for the `email_attribute` field in `SamlConfiguration`.
+fn default_saml_email_attribute() -> String {
+    "email".to_string()
+}
+
+/// Returns the default value for the `name_attribute` field in `SamlConfiguration`.
+fn default_saml_name_attribute() -> String {
+    "displayName".to_string()
+}
+
+/// Returns the default value for the `provision_users` field in `SamlConfiguration`.
+fn default_saml_provision_users() -> bool {
+    true
+}
+
+/// Returns the default value for the `redirect_url` field in `SamlConfiguration`.
+fn default_saml_redirect_url() -> String {
+    "http://localhost:8080/".to_string()
+}
+
+/// Configuration for logging in with a SAML 2.0 identity provider, this
+/// service being the service provider
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct SamlConfiguration {
+    /// Allow single sign on with the identity provider, requires the `saml` feature
+    #[serde(default)]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub enabled: bool,
+
+    /// This service provider's entity id, usually its metadata url
+    #[serde(default = "default_saml_entity_id")]
+    pub entity_id: String,
+
+    /// Where the identity provider posts its response, the gateway `/saml/acs`
+    #[serde(default = "default_saml_acs_url")]
+    pub acs_url: String,
+
+    /// The identity provider's entity id, the issuer of its responses
+    #[serde(default)]
+    pub idp_entity_id: String,
+
+    /// The identity provider's single sign on url (HTTP-Redirect binding)
+    #[serde(default)]
+    pub idp_sso_url: String,
+
+    /// The identity provider's signing certificate, PEM or base64 DER
+    #[serde(default)]
+    pub idp_certificate: String,
+
+    /// The attribute holding the user's email, the NameID is used without it
+    #[serde(default = "default_saml_email_attribute")]
+    pub email_attribute: String,
+
+    /// The attribute holding the user's name, used when provisioning them
+    #[serde(default = "default_saml_name_attribute")]
+    pub name_attribute: String,
+
+    /// Provision users on their first login, otherwise only existing users
+    /// can log in
+    #[serde(default = "default_saml_provision_users")]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub provision_users: bool,
+
+    /// Where the browser is sent after logging in through the gateway, with
+    /// the refresh token cookie set
+    #[serde(default = "default_saml_redirect_url")]
+    pub redirect_url: String,
+}
+
+impl Default for SamlConfiguration {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            entity_id: default_saml_entity_id(),
+            acs_url: default_saml_acs_url(),
+            idp_entity_id: String::new(),
+            idp_sso_url: String::new(),
+            idp_certificate: String::new(),
+            email_attribute: default_saml_email_attribute(),
+            name_attribute: default_saml_name_attribute(),
+            provision_users: default_saml_provision_users(),
+            redirect_url: default_saml_redirect_url(),
+        }
+    }
+}
+
+/// Returns the default value for the `verification_uri` field in `DeviceAuthorizationConfiguration`.
+fn default_device_verification_uri() -> String {
+    "http://localhost:8080/device".to_string()
+}
+
+/// Returns the default value for the `expiry_minutes` field in `DeviceAuthorizationConfiguration`.
+fn default_device_expiry_minutes() -> u64 {
+    10
+}
+
+/// Returns the default value for the `interval_seconds` field in `DeviceAuthorizationConfiguration`.
+fn default_device_interval_seconds() -> u64 {
+    5
+}
+
+/// Configuration for the OAuth device authorization grant, where a device
+/// shows a code the user approves while logged in on another device
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct DeviceAuthorizationConfiguration {
+    /// Allow devices to log in with a device code
+    #[serde(default)]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub enabled: bool,
+
+    /// The client page the user enters the code on. The page approves the
+    /// device with `ApproveDeviceAuthorization`
+    #[serde(default = "default_device_verification_uri")]
+    pub verification_uri: String,
+
+    /// How many minutes the user has to approve the device
+    #[serde(default = "default_device_expiry_minutes")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub expiry_minutes: u64,
+
+    /// The fewest seconds between the device's polls for tokens
+    #[serde(default = "default_device_interval_seconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub interval_seconds: u64,
+}
+
+impl Default for DeviceAuthorizationConfiguration {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            verification_uri: default_device_verification_uri(),
+            expiry_minutes: default_device_expiry_minutes(),
+            interval_seconds: default_device_interval_seconds(),
+        }
+    }
+}
+
+impl DeviceAuthorizationConfiguration {
+    /// The verification page with the user code filled in, for devices that
+    /// can show a QR code
+    pub fn verification_uri_complete(&self, user_code: &str) -> String {
+        let separator = if self.verification_uri.contains('?') { '&' } else { '?' };
+        format!("{}{separator}user_code={user_code}", self.verification_uri)
+    }
+}
+
+/// Returns the default value for the `access_token_duration_minutes` field in `ClientCredentialsConfiguration`.
+fn default_client_access_token_duration_minutes() -> u64 {
+    5
+}
+
+/// Configuration for the OAuth client credentials grant, where API clients
+/// exchange their id and secret for a short lived access token
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ClientCredentialsConfiguration {
+    /// Allow API clients to get access tokens
+    #[serde(default)]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub enabled: bool,
+
+    /// How many minutes an API client's access token is valid for, there is
+    /// no refresh token so the client asks again
+    #[serde(default = "default_client_access_token_duration_minutes")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub access_token_duration_minutes: u64,
+}
+
+impl Default for ClientCredentialsConfiguration {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            access_token_duration_minutes: default_client_access_token_duration_minutes(),
+        }
+    }
+}
+
+/// Returns the default value for the `access_token_duration_minutes` field in `ImpersonationConfiguration`.
+fn default_impersonation_access_token_duration_minutes() -> u64 {
+    15
+}
+
+/// Returns the default value for the `min_reason_length` field in `ImpersonationConfiguration`.
+fn default_impersonation_min_reason_length() -> usize {
+    10
+}
+
+/// Configuration for admins impersonating users, e.g. support staff seeing
+/// what a user sees
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ImpersonationConfiguration {
+    /// Allow admins to impersonate users
+    #[serde(default)]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub enabled: bool,
+
+    /// How many minutes an impersonation access token is valid for, there is
+    /// no refresh token
+    #[serde(default = "default_impersonation_access_token_duration_minutes")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub access_token_duration_minutes: u64,
+
+    /// The fewest characters in the reason an admin gives for impersonating
+    #[serde(default = "default_impersonation_min_reason_length")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub min_reason_length: usize,
+}
+
+impl Default for ImpersonationConfiguration {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            access_token_duration_minutes: default_impersonation_access_token_duration_minutes(),
+            min_reason_length: default_impersonation_min_reason_length(),
+        }
+    }
+}
+
+/// Configuration for the policy versions users must accept. While a user has
+/// not accepted a configured version, their authenticated calls other than
+/// `AcceptPolicy` and `GetMe` fail with `PolicyAcceptanceRequired`.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct PoliciesConfiguration {
+    /// The current terms of service version, none are required when unset
+    pub terms_of_service_version: Option<String>,
+
+    /// The current privacy policy version, none is required when unset
+    pub privacy_policy_version: Option<String>,
+}
+
+impl PoliciesConfiguration {
+    /// The configured version of the policy, if users must accept it
+    pub fn version(&self, policy: domain::Policy) -> Option<&str> {
+        let version = match policy {
+            domain::Policy::TermsOfService => &self.terms_of_service_version,
+            domain::Policy::PrivacyPolicy => &self.privacy_policy_version,
+        };
+
+        version.as_deref()
+    }
+
+    /// The policies users must accept, with their configured versions
+    pub fn required(&self) -> Vec<(domain::Policy, &str)> {
+        domain::Policy::ALL
+            .into_iter()
+            .filter_map(|policy| self.version(policy).map(|version| (policy, version)))
+            .collect()
+    }
+}
+
+/// Where avatar images are stored
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Deserialize, Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum AvatarStorageBackend {
+    /// Avatars can't be uploaded
+    #[default]
+    None,
+    /// Files in a local directory, served by the HTTP gateway
+    Local,
+    /// An S3 compatible bucket, such as AWS S3 or MinIO
+    S3,
+}
+
+/// Returns the default value for the `max_upload_bytes` field in `AvatarsConfiguration`.
+fn default_avatar_max_upload_bytes() -> usize {
+    5 * 1024 * 1024
+}
+
+/// Returns the default value for the `size_pixels` field in `AvatarsConfiguration`.
+fn default_avatar_size_pixels() -> u32 {
+    256
+}
+
+/// Returns the default value for the `url_expiry_seconds` field in `AvatarsConfiguration`.
+fn default_avatar_url_expiry_seconds() -> u64 {
+    3600
+}
+
+/// Returns the default value for the `local_directory` field in `AvatarsConfiguration`.
+fn default_avatar_local_directory() -> String {
+    "./avatars".to_string()
+}
+
+/// Returns the default value for the `s3_region` field in `AvatarsConfiguration`.
+fn default_avatar_s3_region() -> String {
+    "us-east-1".to_string()
+}
+
+/// Configuration for user avatar uploads. Uploaded images are cropped and
+/// resized to a square PNG, then stored in the backend. Clients display them
+/// with a signed URL that expires.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct AvatarsConfiguration {
+    /// Where avatars are stored, uploads are rejected when `none`
+    #[serde(default)]
+    pub backend: AvatarStorageBackend,
+
+    /// The largest image that can be uploaded
+    #[serde(default = "default_avatar_max_upload_bytes")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_upload_bytes: usize,
+
+    /// The width and height avatars are resized to
+    #[serde(default = "default_avatar_size_pixels")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub size_pixels: u32,
+
+    /// How long a signed avatar URL can be used for
+    #[serde(default = "default_avatar_url_expiry_seconds")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub url_expiry_seconds: u64,
+
+    /// The directory the `local` backend writes avatars to
+    #[serde(default = "default_avatar_local_directory")]
+    pub local_directory: String,
+
+    /// The HTTP gateway address clients reach, e.g. `https://auth.example.com`,
+    /// required by the `local` backend to build avatar URLs
+    pub public_url: Option<String>,
+
+    /// The S3 endpoint, e.g. `https://s3.us-east-1.amazonaws.com` or
+    /// `http://localhost:9000`, required by the `s3` backend
+    pub s3_endpoint: Option<String>,
+
+    /// The bucket avatars are stored in, required by the `s3` backend
+    pub s3_bucket: Option<String>,
+
+    /// The region requests are signed for
+    #[serde(default = "default_avatar_s3_region")]
+    pub s3_region: String,
+
+    /// The access key id, required by the `s3` backend
+    pub s3_access_key_id: Option<String>,
+
+    /// The secret access key, required by the `s3` backend
+    pub s3_secret_access_key: Option<SecretString>,
+}
+
+impl Default for AvatarsConfiguration {
+    fn default() -> Self {
+        Self {
+            backend: AvatarStorageBackend::default(),
+            max_upload_bytes: default_avatar_max_upload_bytes(),
+            size_pixels: default_avatar_size_pixels(),
+            url_expiry_seconds: default_avatar_url_expiry_seconds(),
+            local_directory: default_avatar_local_directory(),
+            public_url: None,
+            s3_endpoint: None,
+            s3_bucket: None,
+            s3_region: default_avatar_s3_region(),
+            s3_access_key_id: None,
+            s3_secret_access_key: None,
+        }
+    }
+}
+
+/// Configuration for the email domains that can register. A domain also
+/// covers its subdomains. Admins can allow and block more domains with
+/// `SetEmailDomainRule`, kept in the database. Refused registrations fail with
+/// `InvalidArgument` and a reason code, see `services::email_domains`.
+#[serde_as]
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct EmailDomainsConfiguration {
+    /// Only these domains can register, any domain can when none are allowed
+    /// here or by an admin. A list, or comma separated in an environment
+    /// variable, e.g. `APP__EMAIL_DOMAINS__ALLOWED=example.com,example.org`
+    #[serde(default)]
+    #[serde_as(as = "PickFirst<(_, StringWithSeparator<CommaSeparator, String>)>")]
+    pub allowed: Vec<String>,
+
+    /// These domains can't register, even when allowed
+    #[serde(default)]
+    #[serde_as(as = "PickFirst<(_, StringWithSeparator<CommaSeparator, String>)>")]
+    pub blocked: Vec<String>,
+
+    /// Refuse domains that give out disposable addresses, unless allowed
+    #[serde(default)]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub block_disposable: bool,
+
+    /// Disposable domains to refuse as well as the built in list
+    #[serde(default)]
+    #[serde_as(as = "PickFirst<(_, StringWithSeparator<CommaSeparator, String>)>")]
+    pub disposable: Vec<String>,
+}
+
+/// Returns the default value for the `min_length` field in `UserNamesConfiguration`.
+fn default_user_name_min_length() -> usize {
+    1
+}
+
+/// Returns the default value for the `max_length` field in `UserNamesConfiguration`.
+fn default_user_name_max_length() -> usize {
+    256
+}
+
+/// Configuration for the names users can have, checked wherever a name is
+/// set. The policy is built at startup, see `domain::UserNamePolicy`.
+#[serde_as]
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct UserNamesConfiguration {
+    /// The fewest characters (graphemes) in a name
+    #[serde(default = "default_user_name_min_length")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub min_length: usize,
+
+    /// The most characters (graphemes) in a name
+    #[serde(default = "default_user_name_max_length")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_length: usize,
+
+    /// The characters a name can be made of, `any`, `letters` or `ascii`
+    #[serde(default)]
+    pub characters: domain::UserNameCharacters,
+
+    /// Names that can't be used, compared without case, e.g. `admin`. A list,
+    /// or comma separated in an environment variable
+    #[serde(default)]
+    #[serde_as(as = "PickFirst<(_, StringWithSeparator<CommaSeparator, String>)>")]
+    pub reserved: Vec<String>,
+
+    /// A file of words that can't appear in a name, one per line. Blank lines
+    /// and lines starting with `#` are skipped.
+    pub profanity_list: Option<String>,
+}
+
+impl Default for UserNamesConfiguration {
+    fn default() -> Self {
+        Self {
+            min_length: default_user_name_min_length(),
+            max_length: default_user_name_max_length(),
+            characters: domain::UserNameCharacters::default(),
+            reserved: Vec::new(),
+            profanity_list: None,
+        }
+    }
+}
+
+impl UserNamesConfiguration {
+    /// Build the name policy, reading the profanity list file
+    pub fn policy(&self) -> Result<domain::UserNamePolicy, AuthenticationError> {
+        let profanity = match &self.profanity_list {
+            Some(path) => std::fs::read_to_string(path)?
+                .lines()
+                .map(str::trim)
+                .filter(|word| !word.is_empty() && !word.starts_with('#'))
+                .map(str::to_lowercase)
+                .collect(),
+            None => Default::default(),
+        };
+
+        Ok(domain::UserNamePolicy {
+            min_length: self.min_length,
+            max_length: self.max_length,
+            characters: self.characters,
+            reserved: self
+                .reserved
+                .iter()
+                .map(|name| name.trim().to_lowercase())
+                .collect(),
+            profanity,
+        })
+    }
+}
+
+/// Configuration for the localised error messages returned to clients. The
+/// translations are loaded at startup, see `error_messages::ErrorMessages`.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct ErrorMessagesConfiguration {
+    /// Directory of translation files named `<locale>.json`, each an object of
+    /// `code: message` pairs. They replace the built in messages of the same
+    /// locale and code.
+    pub translation_directory: Option<String>,
+
+    /// Link to help with an error, sent to clients in its `ErrorDetail`.
+    /// `{code}` is replaced with the error code, e.g.
+    /// `https://docs.example.com/errors#{code}`
+    pub help_url: Option<String>,
+}
+
+/// The possible runtime environment for our application.
+#[derive(Clone, Debug, PartialEq, Copy, serde::Deserialize, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum Environment {
+    Development,
+    Testing,
+    Production,
+}
+
+// TODO: why is this dead co
+#[allow(dead_code)]
+impl Environment {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Environment::Development => "development",
+            Environment::Testing => "testing",
+            Environment::Production => "production",
+        }
+    }
+}
+
+impl TryFrom<String> for Environment {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        match s.to_lowercase().as_str() {
+            "development" => Ok(Self::Development),
+            "testing" => Ok(Self::Testing),
+            "production" => Ok(Self::Production),
+            other => Err(format!(
+                "{} is not a supported environment. Use either `development`, `testing` or `production`.",
+                other
+            )),
+        }
+    }
+}
+
+impl Configuration {
+    /// Parse the application configuration, returning a `Configuration` result.
+    pub fn parse() -> Result<Configuration, AuthenticationError> {
+        // Get the directory that the binary is being run from
+        let base_path = std::env::current_dir().map_err(|e| {
+            AuthenticationError::Generic(format!(
+                "Failed to determine the current directory: {e}"
+            ))
+        })?;
+
+        // Set the configuration directory for the app
+        let configuration_directory = base_path.join("configuration");
+
+        // Get the runtime environment the binary was started in
+        let environment: Environment = std::env::var("APP_ENVIRONMENT")
+            .unwrap_or_else(|_| "development".into())
+            .try_into()
+            .map_err(|e| {
+                AuthenticationError::ValidationError(format!("APP_ENVIRONMENT: {e}"))
+            })?;
+
+        // Add in settings from environment variables (with a prefix of APP
+        // and '__' as separator). E.g. `APP__APPLICATION__PORT=5001 would
+        // set `settings.application.port`
+        let environment_variables = config::Environment::with_prefix(ENVIRONMENT_PREFIX)
+            .prefix_separator(ENVIRONMENT_SEPARATOR)
+            .separator(ENVIRONMENT_SEPARATOR);
+
+        let configuration = Self::parse_from(
+            &configuration_directory,
+            environment,
+            environment_variables,
+        )?;
+
+        // Convert the configuration values into Settings type
+        Ok(configuration)
+    }
+
+    /// # Parse Configuration Layers
+    ///
+    /// Layer the base file, the environment file and the environment variables,
+    /// read any `*_file` secrets and check every required key is present before
+    /// deserialising.
+    ///
+    /// ## Parameters
+    ///
+    /// - `configuration_directory` - Directory holding `base.yaml` and the environment files
+    /// - `environment` - The runtime environment, selects `{environment}.yaml`
+    /// - `environment_variables` - The environment variable source
+    pub fn parse_from(
+        configuration_directory: &Path,
+        environment: Environment,
+        environment_variables: config::Environment,
+    ) -> Result<Configuration, AuthenticationError> {
+        // Set the base and environment config file paths
+        let base_config_file = configuration_directory.join("base.yaml");
+        let environment_config_file =
+            configuration_directory.join(format!("{}.yaml", environment));
+
+        // Build our configuration instance. Configuration files are added in
+        // this order, with subsequent files overwriting previous configurations
+        // if present.
+        let layered_builder = || {
+            config::Config::builder()
+                .add_source(config::File::from(base_config_file.clone()))
+                .add_source(
+                    config::File::from(environment_config_file.clone()).required(false),
+                )
+                .add_source(environment_variables.clone())
+        };
+        let layers = layered_builder().build()?;
+
+        // Read secrets from files, overriding any inline value
+        let mut settings_builder = layered_builder();
+        for key in FILE_SECRET_KEYS {
+            let file_key = format!("{key}_file");
+            if let Ok(path) = layers.get_string(&file_key) {
+                let secret = std::fs::read_to_string(&path).map_err(|e| {
+                    AuthenticationError::ValidationError(format!(
+                        "{file_key}: unable to read secret file {path}: {e}"
+                    ))
+                })?;
+                settings_builder =
+                    settings_builder.set_override(key, secret.trim_end().to_string())?;
+            }
+        }
+        let settings = settings_builder.build()?;
+
+        // Fail fast listing every missing key, not just the first one serde finds
+        let missing: Vec<String> = REQUIRED_KEYS
+            .iter()
+            .filter(|key| settings.get::<config::Value>(key).is_err())
+            .map(|key| format!("{key} (or {})", environment_variable(key)))
+            .collect();
+        if !missing.is_empty() {
+            return Err(AuthenticationError::ConfigurationMissing(missing.join(", ")));
+        }
+
+        Ok(settings.try_deserialize::<Configuration>()?)
+    }
+
+    /// # Redacted Configuration
+    ///
+    /// Render the effective configuration for printing. Secrets are wrapped in
+    /// `SecretString`, so their values are redacted.
+    pub fn redacted(&self) -> String {
+        format!(
+            "\n----------- CONFIGURATION ----------- \n{:#?} \n-------------------------------------",
+            self
+        )
+    }
+
+    /// The services served on the main address, `application.services`
+    /// without those moved to the internal server
+    pub fn public_services(&self) -> Vec<String> {
+        self.application
+            .services
+            .iter()
+            .filter(|service| !self.internal.enabled || !self.internal.services.contains(service))
+            .cloned()
+            .collect()
+    }
+
+    /// The listeners serving gRPC alongside the main address, the internal
+    /// server first when it is enabled
+    pub fn all_listeners(&self) -> Vec<ListenerConfiguration> {
+        let internal = self.internal.enabled.then(|| ListenerConfiguration {
+            name: "internal".to_string(),
+            address: Some(self.internal.address.clone()),
+            unix_socket: None,
+            services: self.internal.services.clone(),
+        });
+
+        internal.into_iter().chain(self.listeners.iter().cloned()).collect()
+    }
+
+    /// Wrap the configuration so it can be shared with services and swapped on reload
+    pub fn into_shared(self) -> SharedConfiguration {
+        Arc::new(ArcSwap::from_pointee(self))
+    }
+
+    /// # Validate Configuration
+    ///
+    /// Check the configuration values make sense together, returning a
+    /// `ValidationError` describing the first problem found.
+    pub fn validate(&self) -> Result<(), AuthenticationError> {
+        let application = &self.application;
+
+        if application.access_token_duration_minutes == 0 {
+            return Err(AuthenticationError::ValidationError(
+                "application.access_token_duration_minutes must be greater than zero"
+                    .to_string(),
+            ));
+        }
+
+        if application.token_audiences.is_empty()
+            || application.token_audiences.iter().any(|audience| audience.trim().is_empty())
+        {
+            return Err(AuthenticationError::ValidationError(
+                "application.token_audiences must list at least one non-empty audience"
+                    .to_string(),
+            ));
+        }
+
+        if application.refresh_token_duration_minutes
+            <= application.access_token_duration_minutes
+        {
+            return Err(AuthenticationError::ValidationError(
+                "application.refresh_token_duration_minutes must be longer than the access token duration"
+                    .to_string(),
+            ));
+        }
+
+        if application.remember_me_duration_minutes
+            < application.refresh_token_duration_minutes
+        {
+            return Err(AuthenticationError::ValidationError(
+                "application.remember_me_duration_minutes must not be shorter than the refresh token duration"
+                    .to_string(),
+            ));
+        }
+
+        if application.sliding_expiration
+            && application.absolute_session_lifetime_minutes
+                < application.refresh_token_duration_minutes
+        {
+            return Err(AuthenticationError::ValidationError(
+                "application.absolute_session_lifetime_minutes must not be shorter than the refresh token duration"
+                    .to_string(),
+            ));
+        }
+
+        if application.token_secret.expose_secret().is_empty() {
+            return Err(AuthenticationError::ValidationError(
+                "application.token_secret must not be empty".to_string(),
+            ));
+        }
+
+        if self.email.transport == EmailTransport::Smtp && self.email.smtp_host.is_none() {
+            return Err(AuthenticationError::ValidationError(
+                "email.smtp_host is required when email.transport is smtp".to_string(),
+            ));
+        }
+
+        if self
+            .email
+            .template_directory
+            .as_ref()
+            .is_some_and(|directory| !std::path::Path::new(directory).is_dir())
+        {
+            return Err(AuthenticationError::ValidationError(
+                "email.template_directory must be a directory".to_string(),
+            ));
+        }
+
+        if self
+            .error_messages
+            .translation_directory
+            .as_ref()
+            .is_some_and(|directory| !std::path::Path::new(directory).is_dir())
+        {
+            return Err(AuthenticationError::ValidationError(
+                "error_messages.translation_directory must be a directory".to_string(),
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.telemetry.sampling_ratio) {
+            return Err(AuthenticationError::ValidationError(
+                "telemetry.sampling_ratio must be between 0.0 and 1.0".to_string(),
+            ));
+        }
+
+        let circuit_breaker = &self.database.circuit_breaker;
+        if circuit_breaker.enabled
+            && (circuit_breaker.failure_threshold == 0 || circuit_breaker.open_seconds == 0)
+        {
+            return Err(AuthenticationError::ValidationError(
+                "database.circuit_breaker failure_threshold and open_seconds must be greater than zero"
+                    .to_string(),
+            ));
+        }
+
+        if self.database.retry.max_attempts == 0 {
+            return Err(AuthenticationError::ValidationError(
+                "database.retry.max_attempts must be at least 1".to_string(),
+            ));
+        }
+
+        if self.error_reporting.dsn.is_some() && !cfg!(feature = "sentry") {
+            return Err(AuthenticationError::ValidationError(
+                "error_reporting.dsn requires the `sentry` feature".to_string(),
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.error_reporting.sample_rate) {
+            return Err(AuthenticationError::ValidationError(
+                "error_reporting.sample_rate must be between 0.0 and 1.0".to_string(),
+            ));
+        }
+
+        if let Some(sampling) = self
+            .telemetry
+            .log_sampling
+            .iter()
+            .find(|sampling| sampling.target.is_empty() || !(0.0..=1.0).contains(&sampling.ratio))
+        {
+            return Err(AuthenticationError::ValidationError(format!(
+                "telemetry.log_sampling {sampling} must have a target and a ratio between 0.0 and 1.0"
+            )));
+        }
+
+        if self.webhooks.max_attempts == 0 || self.webhooks.poll_interval_seconds == 0 {
+            return Err(AuthenticationError::ValidationError(
+                "webhooks.max_attempts and webhooks.poll_interval_seconds must be greater than zero"
+                    .to_string(),
+            ));
+        }
+
+        if self.outbox.max_attempts == 0 || self.outbox.poll_interval_seconds == 0 {
+            return Err(AuthenticationError::ValidationError(
+                "outbox.max_attempts and outbox.poll_interval_seconds must be greater than zero"
+                    .to_string(),
+            ));
+        }
+
+        if self.event_bus.transport != EventBusTransport::None && self.event_bus.url.is_none() {
+            return Err(AuthenticationError::ValidationError(
+                "event_bus.url is required when event_bus.transport is set".to_string(),
+            ));
+        }
+
+        if self.captcha.provider != CaptchaProvider::None && self.captcha.secret.is_none() {
+            return Err(AuthenticationError::ValidationError(
+                "captcha.secret is required when captcha.provider is set".to_string(),
+            ));
+        }
+
+        if self.captcha.required && self.captcha.provider == CaptchaProvider::None {
+            return Err(AuthenticationError::ValidationError(
+                "captcha.provider is required when captcha.required is true".to_string(),
+            ));
+        }
+
+        if self.database.max_connections == 0
+            || self.database.min_connections > self.database.max_connections
+        {
+            return Err(AuthenticationError::ValidationError(
+                "database.max_connections must be greater than zero and no less than min_connections"
+                    .to_string(),
+            ));
+        }
+
+        if self.database.acquire_timeout_seconds == 0 {
+            return Err(AuthenticationError::ValidationError(
+                "database.acquire_timeout_seconds must be greater than zero".to_string(),
+            ));
+        }
+
+        if self.login_throttle.base_delay_seconds == 0
+            || self.login_throttle.max_delay_seconds < self.login_throttle.base_delay_seconds
+        {
+            return Err(AuthenticationError::ValidationError(
+                "login_throttle.base_delay_seconds must be greater than zero and no more than max_delay_seconds"
+                    .to_string(),
+            ));
+        }
+
+        if self.load_shedding.max_concurrent_expensive_requests == 0
+            || self.load_shedding.max_concurrent_requests
+                < self.load_shedding.max_concurrent_expensive_requests
+        {
+            return Err(AuthenticationError::ValidationError(
+                "load_shedding.max_concurrent_expensive_requests must be greater than zero and no more than max_concurrent_requests"
+                    .to_string(),
+            ));
+        }
+
+        if self.load_shedding.max_queued_password_hashes == 0 {
+            return Err(AuthenticationError::ValidationError(
+                "load_shedding.max_queued_password_hashes must be greater than zero".to_string(),
+            ));
+        }
+
+        if self.grpc.max_decoding_message_size == 0 || self.grpc.request_timeout_seconds == 0 {
+            return Err(AuthenticationError::ValidationError(
+                "grpc.max_decoding_message_size and grpc.request_timeout_seconds must be greater than zero"
+                    .to_string(),
+            ));
+        }
+
+        if self.grpc.http2_keepalive_interval_seconds > 0
+            && self.grpc.http2_keepalive_timeout_seconds == 0
+        {
+            return Err(AuthenticationError::ValidationError(
+                "grpc.http2_keepalive_timeout_seconds must be greater than zero when HTTP/2 keepalive is on"
+                    .to_string(),
+            ));
+        }
+
+        if self.readiness.retry_interval_seconds == 0 {
+            return Err(AuthenticationError::ValidationError(
+                "readiness.retry_interval_seconds must be greater than zero".to_string(),
+            ));
+        }
+
+        if self.fault_injection.enabled && !cfg!(feature = "fault_injection") {
+            return Err(AuthenticationError::ValidationError(
+                "fault_injection.enabled requires the `fault_injection` feature".to_string(),
+            ));
+        }
+
+        for rule in &self.fault_injection.rules {
+            let percents = [rule.latency_percent, rule.error_percent, rule.drop_percent];
+            if percents.iter().any(|percent| !(0.0..=100.0).contains(percent)) {
+                return Err(AuthenticationError::ValidationError(format!(
+                    "fault_injection.rules for {} must have percents from 0 to 100",
+                    rule.path
+                )));
+            }
+            if crate::error_messages::status_code_from_name(&rule.error_code).is_none() {
+                return Err(AuthenticationError::ValidationError(format!(
+                    "fault_injection.rules for {} has an unknown error_code {}",
+                    rule.path, rule.error_code
+                )));
+            }
+        }
+
+        let refresh_cookie = &self.refresh_cookie;
+        let is_token = |name: &str| {
+            !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_graphic() && !"()<>@,;:\\\"/[]?={}".contains(c))
+        };
+        if !is_token(&refresh_cookie.name) {
+            return Err(AuthenticationError::ValidationError(format!(
+                "refresh_cookie.name {} is not a valid cookie name",
+                refresh_cookie.name
+            )));
+        }
+
+        if !refresh_cookie.path.starts_with('/') {
+            return Err(AuthenticationError::ValidationError(
+                "refresh_cookie.path must start with /".to_string(),
+            ));
+        }
+
+        if !refresh_cookie.secure
+            && (refresh_cookie.partitioned
+                || refresh_cookie.same_site == Some(CookieSameSite::None))
+        {
+            return Err(AuthenticationError::ValidationError(
+                "refresh_cookie.secure must be set for partitioned or same_site none cookies"
+                    .to_string(),
+            ));
+        }
+
+        if let Some(metadata_key) = &self.refresh_token.metadata_key {
+            let is_valid = http::HeaderName::from_lowercase(metadata_key.as_bytes()).is_ok()
+                && !metadata_key.ends_with("-bin")
+                && !["cookie", "authorization"].contains(&metadata_key.as_str());
+            if !is_valid {
+                return Err(AuthenticationError::ValidationError(format!(
+                    "refresh_token.metadata_key {metadata_key} must be a lowercase ASCII metadata key, other than cookie or authorization"
+                )));
+            }
+        }
+
+        let listeners = self.all_listeners();
+        for listener in &listeners {
+            if listener.address.is_some() == listener.unix_socket.is_some() {
+                return Err(AuthenticationError::ValidationError(format!(
+                    "listeners.{} must set one of address or unix_socket",
+                    listener.name
+                )));
+            }
+            if listener.unix_socket.is_some() && !cfg!(unix) {
+                return Err(AuthenticationError::ValidationError(format!(
+                    "listeners.{} unix_socket is only supported on Unix",
+                    listener.name
+                )));
+            }
+        }
+
+        let services = std::iter::once(("application", &self.application.services)).chain(
+            listeners
+                .iter()
+                .map(|listener| (listener.name.as_str(), &listener.services)),
+        );
+        for (listener, services) in services {
+            if let Some(service) = services
+                .iter()
+                .find(|service| !GRPC_SERVICES.contains(&service.as_str()))
+            {
+                return Err(AuthenticationError::ValidationError(format!(
+                    "{listener} services has {service}, expected one of {}",
+                    GRPC_SERVICES.join(", ")
+                )));
+            }
+        }
+
+        if let Some(service) = self
+            .compression
+            .services
+            .keys()
+            .find(|service| !GRPC_SERVICES.contains(&service.as_str()))
+        {
+            return Err(AuthenticationError::ValidationError(format!(
+                "compression.services.{service} is not a service, expected one of {}",
+                GRPC_SERVICES.join(", ")
+            )));
+        }
+
+        if self.magic_link.enabled
+            && (self.magic_link.expiry_minutes == 0 || self.magic_link.link_url.is_empty())
+        {
+            return Err(AuthenticationError::ValidationError(
+                "magic_link.expiry_minutes must be greater than zero and magic_link.link_url set when magic links are enabled"
+                    .to_string(),
+            ));
+        }
+
+        if self.password_reset.expiry_minutes == 0
+            || self.password_reset.link_url.is_empty()
+            || self.password_reset.window_seconds == 0
+        {
+            return Err(AuthenticationError::ValidationError(
+                "password_reset.expiry_minutes and password_reset.window_seconds must be greater than zero and password_reset.link_url set"
+                    .to_string(),
+            ));
+        }
+
+        let reminders = &self.verification_reminders;
+        if reminders.enabled
+            && (reminders.poll_interval_seconds == 0
+                || reminders.interval_days == 0
+                || reminders.expiry_hours == 0
+                || reminders.batch_size == 0
+                || reminders.link_url.is_empty())
+        {
+            return Err(AuthenticationError::ValidationError(
+                "verification_reminders.poll_interval_seconds, interval_days, expiry_hours and batch_size must be greater than zero and link_url set when reminders are enabled"
+                    .to_string(),
+            ));
+        }
+        if reminders.enabled
+            && reminders.deactivate_after_days != 0
+            && reminders.deactivate_after_days <= reminders.unverified_after_days
+        {
+            return Err(AuthenticationError::ValidationError(
+                "verification_reminders.deactivate_after_days must be greater than unverified_after_days, or zero"
+                    .to_string(),
+            ));
+        }
+
+        if self.passkeys.enabled
+            && (self.passkeys.relying_party_id.is_empty()
+                || self.passkeys.relying_party_origin.is_empty())
+        {
+            return Err(AuthenticationError::ValidationError(
+                "passkeys.relying_party_id and passkeys.relying_party_origin must be set when passkeys are enabled"
+                    .to_string(),
+            ));
+        }
+
+        if self.ldap.enabled && !cfg!(feature = "ldap") {
+            return Err(AuthenticationError::ValidationError(
+                "ldap.enabled requires the `ldap` feature".to_string(),
+            ));
+        }
+
+        if self.ldap.enabled
+            && (self.ldap.base_dn.is_empty() || !self.ldap.search_filter.contains("{email}"))
+        {
+            return Err(AuthenticationError::ValidationError(
+                "ldap.base_dn must be set and ldap.search_filter contain {email} when ldap is enabled"
+                    .to_string(),
+            ));
+        }
+
+        if self.saml.enabled && !cfg!(feature = "saml") {
+            return Err(AuthenticationError::ValidationError(
+                "saml.enabled requires the `saml` feature".to_string(),
+            ));
+        }
+
+        if self.saml.enabled
+            && (self.saml.idp_entity_id.is_empty()
+                || self.saml.idp_sso_url.is_empty()
+                || self.saml.idp_certificate.is_empty())
+        {
+            return Err(AuthenticationError::ValidationError(
+                "saml.idp_entity_id, saml.idp_sso_url and saml.idp_certificate must be set when saml is enabled"
+                    .to_string(),
+            ));
+        }
+
+        if self.device_authorization.enabled
+            && (self.device_authorization.verification_uri.is_empty()
+                || self.device_authorization.expiry_minutes == 0)
+        {
+            return Err(AuthenticationError::ValidationError(
+                "device_authorization.verification_uri must be set and expiry_minutes greater than zero when device authorization is enabled"
+                    .to_string(),
+            ));
+        }
+
+        if self.client_credentials.enabled
+            && self.client_credentials.access_token_duration_minutes == 0
+        {
+            return Err(AuthenticationError::ValidationError(
+                "client_credentials.access_token_duration_minutes must be greater than zero when the client credentials grant is enabled"
+                    .to_string(),
+            ));
+        }
+
+        if self.impersonation.enabled && self.impersonation.access_token_duration_minutes == 0 {
+            return Err(AuthenticationError::ValidationError(
+                "impersonation.access_token_duration_minutes must be greater than zero when impersonation is enabled"
+                    .to_string(),
+            ));
+        }
+
+        if let Some((policy, _)) = self
+            .policies
+            .required()
+            .into_iter()
+            .find(|(_, version)| version.trim().is_empty())
+        {
+            return Err(AuthenticationError::ValidationError(format!(
+                "policies.{policy}_version can't be blank, unset it instead"
+            )));
+        }
+
+        let avatars = &self.avatars;
+
+        if avatars.backend != AvatarStorageBackend::None
+            && (avatars.max_upload_bytes == 0
+                || avatars.size_pixels == 0
+                || avatars.url_expiry_seconds == 0)
+        {
+            return Err(AuthenticationError::ValidationError(
+                "avatars.max_upload_bytes, avatars.size_pixels and avatars.url_expiry_seconds must be greater than zero"
+                    .to_string(),
+            ));
+        }
+
+        if avatars.backend == AvatarStorageBackend::Local
+            && (avatars.public_url.is_none() || !self.http.enabled)
+        {
+            return Err(AuthenticationError::ValidationError(
+                "avatars.public_url and http.enabled are required when avatars.backend is local"
+                    .to_string(),
+            ));
+        }
+
+        if avatars.backend == AvatarStorageBackend::S3
+            && (avatars.s3_endpoint.is_none()
+                || avatars.s3_bucket.is_none()
+                || avatars.s3_access_key_id.is_none()
+                || avatars.s3_secret_access_key.is_none())
+        {
+            return Err(AuthenticationError::ValidationError(
+                "avatars.s3_endpoint, avatars.s3_bucket, avatars.s3_access_key_id and avatars.s3_secret_access_key are required when avatars.backend is s3"
+                    .to_string(),
+            ));
+        }
+
+        let email_domains = &self.email_domains;
+        for (key, domains) in [
+            ("allowed", &email_domains.allowed),
+            ("blocked", &email_domains.blocked),
+            ("disposable", &email_domains.disposable),
+        ] {
+            if let Some(invalid) = domains
+                .iter()
+                .find(|domain| domain::EmailDomain::parse(domain.as_str()).is_err())
+            {
+                return Err(AuthenticationError::ValidationError(format!(
+                    "email_domains.{key} has an invalid domain: {invalid}"
+                )));
+            }
+        }
+
+        let user_names = &self.user_names;
+        if user_names.min_length == 0 || user_names.min_length > user_names.max_length {
+            return Err(AuthenticationError::ValidationError(
+                "user_names.min_length must be at least 1 and no more than user_names.max_length"
+                    .to_string(),
+            ));
+        }
+
+        if application.use_tls
+            && (application.tls_certificate.is_none()
+                || application.tls_private_key.is_none())
+        {
+            return Err(AuthenticationError::ValidationError(
+                "application.tls_certificate and application.tls_private_key are required when use_tls is true"
+                    .to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// # Merge Reloadable Settings
+    ///
+    /// Return a copy of this (running) configuration with the settings that are
+    /// safe to change at runtime taken from `reloaded`. Critical settings such
+    /// as the bind address, token secret, TLS and database keep their startup
+    /// values.
+    ///
+    /// Reloadable settings:
+    /// - `application.log_level`
+    /// - `application.access_token_duration_minutes`
+    /// - `application.refresh_token_duration_minutes`
+    /// - `application.remember_me_duration_minutes`
+    /// - `application.sliding_expiration`
+    /// - `application.absolute_session_lifetime_minutes`
+    /// - `application.idle_session_timeout_days`
+    /// - `application.password_change_keeps_session`
+    /// - `application.registration_mode`
+    /// - `webhooks.max_attempts`
+    /// - `webhooks.retry_base_seconds`
+    /// - `outbox.max_attempts`
+    /// - `outbox.retry_base_seconds`
+    /// - `captcha`
+    /// - `login_throttle`
+    /// - `magic_link`
+    /// - `password_reset`
+    /// - `verification_reminders`, except `poll_interval_seconds`
+    /// - `passkeys`
+    /// - `ldap`
+    /// - `saml`
+    /// - `device_authorization`
+    /// - `client_credentials`
+    /// - `impersonation`
+    /// - `policies`
+    /// - `avatars.max_upload_bytes`
+    /// - `avatars.size_pixels`
+    /// - `avatars.url_expiry_seconds`
+    /// - `email_domains`
+    /// - `readiness`
+    /// - `refresh_cookie`
+    /// - `refresh_token`
+    pub fn with_reloadable(&self, reloaded: &Configuration) -> Configuration {
+        let mut configuration = self.clone();
+        configuration.application.log_level = reloaded.application.log_level;
+        configuration.application.access_token_duration_minutes =
+            reloaded.application.access_token_duration_minutes;
+        configuration.application.refresh_token_duration_minutes =
+            reloaded.application.refresh_token_duration_minutes;
+        configuration.application.remember_me_duration_minutes =
+            reloaded.application.remember_me_duration_minutes;
+        configuration.application.sliding_expiration = reloaded.application.sliding_expiration;
+        configuration.application.absolute_session_lifetime_minutes =
+            reloaded.application.absolute_session_lifetime_minutes;
+        configuration.application.idle_session_timeout_days =
+            reloaded.application.idle_session_timeout_days;
+        configuration.application.password_change_keeps_session =
+            reloaded.application.password_change_keeps_session;
+        configuration.application.registration_mode = reloaded.application.registration_mode;
+        configuration.webhooks.max_attempts = reloaded.webhooks.max_attempts;
+        configuration.webhooks.retry_base_seconds = reloaded.webhooks.retry_base_seconds;
+        configuration.outbox.max_attempts = reloaded.outbox.max_attempts;
+        configuration.outbox.retry_base_seconds = reloaded.outbox.retry_base_seconds;
+        configuration.captcha = reloaded.captcha.clone();
+        configuration.login_throttle = reloaded.login_throttle.clone();
+        configuration.magic_link = reloaded.magic_link.clone();
+        configuration.password_reset = reloaded.password_reset.clone();
+        configuration.verification_reminders = VerificationRemindersConfiguration {
+            poll_interval_seconds: self.verification_reminders.poll_interval_seconds,
+            ..reloaded.verification_reminders.clone()
+        };
+        configuration.passkeys = reloaded.passkeys.clone();
+        configuration.ldap = reloaded.ldap.clone();
+        configuration.saml = reloaded.saml.clone();
+        configuration.device_authorization = reloaded.device_authorization.clone();
+        configuration.client_credentials = reloaded.client_credentials.clone();
+        configuration.impersonation = reloaded.impersonation.clone();
+        configuration.policies = reloaded.policies.clone();
+        configuration.avatars.max_upload_bytes = reloaded.avatars.max_upload_bytes;
+        configuration.avatars.size_pixels = reloaded.avatars.size_pixels;
+        configuration.avatars.url_expiry_seconds = reloaded.avatars.url_expiry_seconds;
+        configuration.email_domains = reloaded.email_domains.clone();
+        configuration.readiness = reloaded.readiness.clone();
+        configuration.refresh_cookie = reloaded.refresh_cookie.clone();
+        configuration.refresh_token = reloaded.refresh_token.clone();
+        configuration
+    }
+
+    /// # Restart Required Settings
+    ///
+    /// List the critical settings that differ in `reloaded`. These changes are
+    /// ignored until the server is restarted.
+    pub fn restart_required(&self, reloaded: &Configuration) -> Vec<&'static str> {
+        let (current, reloaded_app) = (&self.application, &reloaded.application);
+        let mut changed = Vec::new();
+
+        if current.ip_address != reloaded_app.ip_address {
+            changed.push("application.ip_address");
+        }
+        if current.port != reloaded_app.port {
+            changed.push("application.port");
+        }
+        if current.token_secret.expose_secret() != reloaded_app.token_secret.expose_secret()
+        {
+            changed.push("application.token_secret");
+        }
+        if current.services != reloaded_app.services {
+            changed.push("application.services");
+        }
+        if current.token_audiences != reloaded_app.token_audiences {
+            changed.push("application.token_audiences");
+        }
+        if current.use_tls != reloaded_app.use_tls
+            || current.tls_certificate != reloaded_app.tls_certificate
+            || current.tls_private_key != reloaded_app.tls_private_key
+        {
+            changed.push("application.tls");
+        }
+        if self.database.host != reloaded.database.host
+            || self.database.port != reloaded.database.port
+            || self.database.username != reloaded.database.username
+            || self.database.database_name != reloaded.database.database_name
+            || self.database.password.expose_secret()
+                != reloaded.database.password.expose_secret()
+            || self.database.max_connections != reloaded.database.max_connections
+            || self.database.min_connections != reloaded.database.min_connections
+            || self.database.acquire_timeout_seconds != reloaded.database.acquire_timeout_seconds
+            || self.database.idle_timeout_seconds != reloaded.database.idle_timeout_seconds
+            || self.database.statement_timeout_milliseconds
+                != reloaded.database.statement_timeout_milliseconds
+            || self.database.circuit_breaker != reloaded.database.circuit_breaker
+            || self.database.retry != reloaded.database.retry
+            || self.database.rpc_query_timeout_milliseconds
+                != reloaded.database.rpc_query_timeout_milliseconds
+            || self.database.background_query_timeout_milliseconds
+                != reloaded.database.background_query_timeout_milliseconds
+        {
+            changed.push("database");
+        }
+        if self.email.transport != reloaded.email.transport
+            || self.email.smtp_host != reloaded.email.smtp_host
+            || self.email.smtp_port != reloaded.email.smtp_port
+            || self.email.template_directory != reloaded.email.template_directory
+        {
+            changed.push("email");
+        }
+        if self.telemetry.otlp_endpoint != reloaded.telemetry.otlp_endpoint
+            || self.telemetry.service_name != reloaded.telemetry.service_name
+            || self.telemetry.sampling_ratio != reloaded.telemetry.sampling_ratio
+            || self.telemetry.log_format != reloaded.telemetry.log_format
+            || self.telemetry.mask_emails != reloaded.telemetry.mask_emails
+            || self.telemetry.redacted_fields != reloaded.telemetry.redacted_fields
+            || self.telemetry.log_sampling != reloaded.telemetry.log_sampling
+        {
+            changed.push("telemetry");
+        }
+        if self.error_reporting.dsn.as_ref().map(ExposeSecret::expose_secret)
+            != reloaded.error_reporting.dsn.as_ref().map(ExposeSecret::expose_secret)
+            || self.error_reporting.environment != reloaded.error_reporting.environment
+            || self.error_reporting.sample_rate != reloaded.error_reporting.sample_rate
+        {
+            changed.push("error_reporting");
+        }
+        if self.http.enabled != reloaded.http.enabled || self.http.port != reloaded.http.port {
+            changed.push("http");
+        }
+        if self.webhooks.poll_interval_seconds != reloaded.webhooks.poll_interval_seconds
+            || self.webhooks.request_timeout_seconds != reloaded.webhooks.request_timeout_seconds
+        {
+            changed.push("webhooks");
+        }
+        if self.outbox.poll_interval_seconds != reloaded.outbox.poll_interval_seconds {
+            changed.push("outbox");
+        }
+        if self.event_bus.transport != reloaded.event_bus.transport
+            || self.event_bus.url != reloaded.event_bus.url
+            || self.event_bus.subject_prefix != reloaded.event_bus.subject_prefix
+        {
+            changed.push("event_bus");
+        }
+        if self.load_shedding.enabled != reloaded.load_shedding.enabled
+            || self.load_shedding.max_concurrent_requests
+                != reloaded.load_shedding.max_concurrent_requests
+            || self.load_shedding.max_concurrent_expensive_requests
+                != reloaded.load_shedding.max_concurrent_expensive_requests
+            || self.load_shedding.max_queued_password_hashes
+                != reloaded.load_shedding.max_queued_password_hashes
+        {
+            changed.push("load_shedding");
+        }
+        let (grpc, reloaded_grpc) = (&self.grpc, &reloaded.grpc);
+        if grpc.max_decoding_message_size != reloaded_grpc.max_decoding_message_size
+            || grpc.request_timeout_seconds != reloaded_grpc.request_timeout_seconds
+            || grpc.tcp_keepalive_seconds != reloaded_grpc.tcp_keepalive_seconds
+            || grpc.http2_keepalive_interval_seconds
+                != reloaded_grpc.http2_keepalive_interval_seconds
+            || grpc.http2_keepalive_timeout_seconds != reloaded_grpc.http2_keepalive_timeout_seconds
+        {
+            changed.push("grpc");
+        }
+        if self.compression != reloaded.compression {
+            changed.push("compression");
+        }
+        if self.trusted_proxies != reloaded.trusted_proxies {
+            changed.push("trusted_proxies");
+        }
+        if self.listeners != reloaded.listeners {
+            changed.push("listeners");
+        }
+        if self.internal != reloaded.internal {
+            changed.push("internal");
+        }
+        if self.fault_injection != reloaded.fault_injection {
+            changed.push("fault_injection");
+        }
+        let (avatars, reloaded_avatars) = (&self.avatars, &reloaded.avatars);
+        if avatars.backend != reloaded_avatars.backend
+            || avatars.local_directory != reloaded_avatars.local_directory
+            || avatars.public_url != reloaded_avatars.public_url
+            || avatars.s3_endpoint != reloaded_avatars.s3_endpoint
+            || avatars.s3_bucket != reloaded_avatars.s3_bucket
+            || avatars.s3_region != reloaded_avatars.s3_region
+            || avatars.s3_access_key_id != reloaded_avatars.s3_access_key_id
+            || avatars.s3_secret_access_key.as_ref().map(ExposeSecret::expose_secret)
+                != reloaded_avatars.s3_secret_access_key.as_ref().map(ExposeSecret::expose_secret)
+        {
+            changed.push("avatars");
+        }
+        let (user_names, reloaded_user_names) = (&self.user_names, &reloaded.user_names);
+        if user_names.min_length != reloaded_user_names.min_length
+            || user_names.max_length != reloaded_user_names.max_length
+            || user_names.characters != reloaded_user_names.characters
+            || user_names.reserved != reloaded_user_names.reserved
+            || user_names.profanity_list != reloaded_user_names.profanity_list
+        {
+            changed.push("user_names");
+        }
+        if self.error_messages.translation_directory
+            != reloaded.error_messages.translation_directory
+            || self.error_messages.help_url != reloaded.error_messages.help_url
+        {
+            changed.push("error_messages");
+        }
+
+        changed
+    }
+}
+
+impl ApplicationConfiguration {
+    /// # Get Token Issuer
+    /// 
+    /// This function returns the issuer URL for the token. The issuer URL is
+    /// used to validate the token issuer when parsing the token.
+    ///
+    /// # Returns
+    ///
+    /// * `Secret<String>` - The issuer URL for the token.
+    pub fn get_issuer(&self) -> SecretString {
+        let issuer = format!(
+            "https://{}:{}",
+            self.ip_address, self.port
+        );
+        SecretString::from(issuer)
+    }
+
+    /// # Get the Cookie Domain
+    /// 
+    /// Cookies are set to website domain.
+    pub fn get_domain(&self) -> String {
+        let domain = format!(
+            // !Important: browsers do not like the http/https prefix in the cookie
+            // and will not set the cookie if it is there
+            "{}",
+            self.ip_address
+        );
+        domain
+    }
+
+    /// # Get the Server Address
+    ///
+    /// This function returns the server address for the API. The server address
+    /// is used to bind the server to the correct address and port.
+    pub fn get_address(&self) -> String {
+        format!("{}:{}", self.ip_address, self.port)
+    }
+
+    /// # Get the Idle Session Timeout
+    ///
+    /// How long a session can go unused before it is rejected, `None` when
+    /// `idle_session_timeout_days` is 0.
+    pub fn idle_session_timeout(&self) -> Option<chrono::Duration> {
+        (self.idle_session_timeout_days > 0)
+            .then(|| chrono::Duration::days(self.idle_session_timeout_days as i64))
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    const BASE_YAML: &str = r#"
+application:
+  ip_address: "localhost"
+  port: 8081
+  log_level: "info"
+  token_secret: "inline_secret"
+  access_token_duration_minutes: 15
+  refresh_token_duration_minutes: 43200
+database:
+  host: "localhost"
+  port: 5432
+  username: "postgres"
+  password: "postgres"
+  database_name: "postgres"
+  require_ssl: false
+"#;
+
+    /// Create a throwaway configuration directory containing `base.yaml`
+    fn configuration_directory(base_yaml: &str) -> Result<std::path::PathBuf> {
+        let directory = std::env::temp_dir().join(format!("configuration-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&directory)?;
+        std::fs::write(directory.join("base.yaml"), base_yaml)?;
+        Ok(directory)
+    }
+
+    /// An environment variable source that does not read the process environment
+    fn environment_variables(variables: &[(&str, &str)]) -> config::Environment {
+        let source = variables
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        config::Environment::with_prefix(ENVIRONMENT_PREFIX)
+            .prefix_separator(ENVIRONMENT_SEPARATOR)
+            .separator(ENVIRONMENT_SEPARATOR)
+            .source(Some(source))
+    }
+
+    #[test]
+    fn layers_environment_file_and_variables() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        std::fs::write(
+            directory.join("production.yaml"),
+            "application:\n  ip_address: \"0.0.0.0\"\n",
+        )?;
+        let variables = environment_variables(&[("APP__APPLICATION__PORT", "9090")]);
+
+        //-- Execute Function (Act)
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Production, variables)?;
+
+        //-- Checks (Assertions)
+        assert_eq!(configuration.application.ip_address, "0.0.0.0");
+        assert_eq!(configuration.application.port, 9090);
+
+        Ok(())
+    }
+
+    #[test]
+    fn secrets_are_read_from_files() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let secret_file = directory.join("token_secret");
+        std::fs::write(&secret_file, "file_secret\n")?;
+        let secret_path = secret_file.to_string_lossy().to_string();
+        let variables = environment_variables(&[(
+            "APP__APPLICATION__TOKEN_SECRET_FILE",
+            secret_path.as_str(),
+        )]);
+
+        //-- Execute Function (Act)
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+
+        //-- Checks (Assertions)
+        assert_eq!(
+            configuration.application.token_secret.expose_secret(),
+            "file_secret"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn missing_keys_are_all_listed() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory("application:\n  port: 8081\n")?;
+
+        //-- Execute Function (Act)
+        let result = Configuration::parse_from(
+            &directory,
+            Environment::Testing,
+            environment_variables(&[]),
+        );
+
+        //-- Checks (Assertions)
+        let Err(AuthenticationError::ConfigurationMissing(missing)) = result else {
+            panic!("Expected missing configuration keys");
+        };
+        assert!(missing.contains("application.token_secret (or APP__APPLICATION__TOKEN_SECRET)"));
+        assert!(missing.contains("database.password (or APP__DATABASE__PASSWORD)"));
+        assert!(!missing.contains("application.port"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn telemetry_defaults_and_sampling_ratio_is_validated() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[("APP__TELEMETRY__SAMPLING_RATIO", "1.5")]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+
+        //-- Checks (Assertions)
+        assert_eq!(defaults.telemetry.otlp_endpoint, None);
+        assert_eq!(defaults.telemetry.service_name, "authentication_service");
+        assert_eq!(defaults.telemetry.log_format, LogFormat::Pretty);
+        assert!(defaults.telemetry.mask_emails);
+        assert!(defaults.telemetry.redacted_fields.is_empty());
+        assert!(defaults.validate().is_ok());
+        assert!(configuration.validate().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn json_logging_and_redacted_fields_are_parsed_from_variables() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[
+            ("APP__TELEMETRY__LOG_FORMAT", "json"),
+            ("APP__TELEMETRY__MASK_EMAILS", "false"),
+            ("APP__TELEMETRY__REDACTED_FIELDS", "api_key,totp_code"),
+        ]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+
+        //-- Checks (Assertions)
+        assert_eq!(configuration.telemetry.log_format, LogFormat::Json);
+        assert!(!configuration.telemetry.mask_emails);
+        assert_eq!(configuration.telemetry.redacted_fields, vec!["api_key", "totp_code"]);
+        assert_eq!(defaults.restart_required(&configuration), vec!["telemetry"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn error_reporting_needs_the_sentry_feature() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[
+            ("APP__ERROR_REPORTING__DSN", "https://key@sentry.example.com/42"),
+            ("APP__ERROR_REPORTING__SAMPLE_RATE", "0.5"),
+        ]);
+        let invalid =
+            environment_variables(&[("APP__ERROR_REPORTING__SAMPLE_RATE", "1.5")]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+        let invalid = Configuration::parse_from(&directory, Environment::Testing, invalid)?;
+
+        //-- Checks (Assertions)
+        assert!(defaults.error_reporting.dsn.is_none());
+        assert_eq!(defaults.error_reporting.sample_rate, 1.0);
+        assert_eq!(configuration.error_reporting.sample_rate, 0.5);
+        assert_eq!(configuration.validate().is_ok(), cfg!(feature = "sentry"));
+        assert!(invalid.validate().is_err());
+        assert_eq!(
+            defaults.restart_required(&configuration),
+            vec!["error_reporting"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn log_sampling_is_parsed_and_validated() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[(
+            "APP__TELEMETRY__LOG_SAMPLING",
+            "authentication_service::database=0.1,tower_http=0",
+        )]);
+        let invalid = environment_variables(&[("APP__TELEMETRY__LOG_SAMPLING", "tower_http=2")]);
+        let malformed = environment_variables(&[("APP__TELEMETRY__LOG_SAMPLING", "tower_http")]);
+
+        //-- Execute Function (Act)
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+        let invalid = Configuration::parse_from(&directory, Environment::Testing, invalid)?;
+        let malformed = Configuration::parse_from(&directory, Environment::Testing, malformed);
+
+        //-- Checks (Assertions)
+        assert_eq!(
+            configuration.telemetry.log_sampling,
+            vec![
+                LogSampling {
+                    target: "authentication_service::database".to_string(),
+                    ratio: 0.1,
+                },
+                LogSampling {
+                    target: "tower_http".to_string(),
+                    ratio: 0.0,
+                },
+            ]
+        );
+        assert!(configuration.validate().is_ok());
+        assert!(invalid.validate().is_err());
+        assert!(malformed.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn slow_query_threshold_defaults_and_overrides() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[(
+            "APP__DATABASE__SLOW_QUERY_THRESHOLD_MILLISECONDS",
+            "250",
+        )]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+
+        //-- Checks (Assertions)
+        assert_eq!(defaults.database.slow_query_threshold_milliseconds, 500);
+        assert_eq!(configuration.database.slow_query_threshold_milliseconds, 250);
+
+        Ok(())
+    }
+
+    #[test]
+    fn database_pool_defaults_and_limits_are_validated() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[
+            ("APP__DATABASE__MAX_CONNECTIONS", "2"),
+            ("APP__DATABASE__MIN_CONNECTIONS", "5"),
+        ]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+
+        //-- Checks (Assertions)
+        assert_eq!(defaults.database.max_connections, 10);
+        assert_eq!(defaults.database.min_connections, 0);
+        assert!(defaults.database.test_before_acquire);
+        assert!(!defaults.database.connect_lazily);
+        assert!(defaults.database.auto_migrate);
+        assert_eq!(defaults.database.pool_options().get_max_connections(), 10);
+        assert!(defaults.validate().is_ok());
+        assert!(configuration.validate().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn email_template_directory_must_exist() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[(
+            "APP__EMAIL__TEMPLATE_DIRECTORY",
+            "/does/not/exist/templates",
+        )]);
+
+        //-- Execute Function (Act)
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+
+        //-- Checks (Assertions)
+        assert!(configuration.validate().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn webhook_defaults_and_attempts_are_validated() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[("APP__WEBHOOKS__MAX_ATTEMPTS", "0")]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+
+        //-- Checks (Assertions)
+        assert_eq!(defaults.webhooks.max_attempts, 8);
+        assert_eq!(defaults.webhooks.retry_base_seconds, 30);
+        assert!(defaults.validate().is_ok());
+        assert!(configuration.validate().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn outbox_defaults_and_poll_interval_are_validated() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables =
+            environment_variables(&[("APP__OUTBOX__POLL_INTERVAL_SECONDS", "0")]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+
+        //-- Checks (Assertions)
+        assert_eq!(defaults.outbox.max_attempts, 10);
+        assert_eq!(defaults.outbox.poll_interval_seconds, 1);
+        assert!(defaults.validate().is_ok());
+        assert!(configuration.validate().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn event_bus_url_is_required_with_a_transport() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[("APP__EVENT_BUS__TRANSPORT", "nats")]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+
+        //-- Checks (Assertions)
+        assert_eq!(defaults.event_bus.transport, EventBusTransport::None);
+        assert_eq!(defaults.event_bus.subject_prefix, "auth");
+        assert_eq!(configuration.event_bus.transport, EventBusTransport::Nats);
+        assert!(configuration.validate().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn captcha_secret_is_required_with_a_provider() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[("APP__CAPTCHA__PROVIDER", "hcaptcha")]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+
+        //-- Checks (Assertions)
+        assert_eq!(defaults.captcha.provider, CaptchaProvider::None);
+        assert_eq!(defaults.captcha.failure_threshold, 5);
+        assert!(!defaults.captcha.required);
+        assert!(defaults.validate().is_ok());
+        assert_eq!(configuration.captcha.provider, CaptchaProvider::Hcaptcha);
+        assert!(configuration.validate().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn login_throttle_delays_are_validated() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[
+            ("APP__LOGIN_THROTTLE__BASE_DELAY_SECONDS", "60"),
+            ("APP__LOGIN_THROTTLE__MAX_DELAY_SECONDS", "30"),
+        ]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+
+        //-- Checks (Assertions)
+        assert!(defaults.login_throttle.enabled);
+        assert_eq!(defaults.login_throttle.free_attempts, 3);
+        assert_eq!(defaults.login_throttle.max_delay_seconds, 900);
+        assert!(defaults.validate().is_ok());
+        assert_eq!(configuration.login_throttle.base_delay_seconds, 60);
+        assert!(configuration.validate().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn load_shedding_limits_are_validated() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[
+            ("APP__LOAD_SHEDDING__MAX_CONCURRENT_REQUESTS", "4"),
+            ("APP__LOAD_SHEDDING__MAX_CONCURRENT_EXPENSIVE_REQUESTS", "16"),
+        ]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+
+        //-- Checks (Assertions)
+        assert!(defaults.load_shedding.enabled);
+        assert_eq!(defaults.load_shedding.max_concurrent_requests, 512);
+        assert_eq!(defaults.load_shedding.max_concurrent_expensive_requests, 8);
+        assert_eq!(defaults.load_shedding.max_queued_password_hashes, 64);
+        assert!(defaults.validate().is_ok());
+        assert_eq!(configuration.load_shedding.max_concurrent_requests, 4);
+        assert!(configuration.validate().is_err());
+        assert_eq!(defaults.restart_required(&configuration), vec!["load_shedding"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn grpc_server_settings_are_validated() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[
+            ("APP__GRPC__TCP_KEEPALIVE_SECONDS", "0"),
+            ("APP__GRPC__HTTP2_KEEPALIVE_TIMEOUT_SECONDS", "0"),
+        ]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+
+        //-- Checks (Assertions)
+        assert_eq!(defaults.grpc.max_decoding_message_size, 4 * 1024 * 1024);
+        assert_eq!(defaults.grpc.request_timeout(), std::time::Duration::from_secs(30));
+        assert_eq!(defaults.grpc.tcp_keepalive(), Some(std::time::Duration::from_secs(60)));
+        assert_eq!(
+            defaults.grpc.http2_keepalive_interval(),
+            Some(std::time::Duration::from_secs(30))
+        );
+        assert_eq!(defaults.grpc.http2_keepalive_timeout(), std::time::Duration::from_secs(20));
+        assert!(defaults.validate().is_ok());
+        assert_eq!(configuration.grpc.tcp_keepalive(), None);
+        assert!(configuration.validate().is_err());
+        assert_eq!(defaults.restart_required(&configuration), vec!["grpc"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn compression_can_be_overridden_per_service() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[
+            ("APP__COMPRESSION__SEND", "gzip"),
+            ("APP__COMPRESSION__SERVICES__ADMIN__SEND", "zstd,gzip"),
+        ]);
+        let typo = environment_variables(&[("APP__COMPRESSION__SERVICES__ADMINS__SEND", "gzip")]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+        let typo = Configuration::parse_from(&directory, Environment::Testing, typo)?;
+
+        //-- Checks (Assertions)
+        let both = [CompressionEncoding::Gzip, CompressionEncoding::Zstd];
+        assert_eq!(defaults.compression.encodings("users"), (&both[..], &both[..]));
+        assert_eq!(
+            configuration.compression.encodings("users"),
+            (&both[..], &[CompressionEncoding::Gzip][..])
+        );
+        assert_eq!(
+            configuration.compression.encodings("admin"),
+            (&both[..], &[CompressionEncoding::Zstd, CompressionEncoding::Gzip][..])
+        );
+        assert!(configuration.validate().is_ok());
+        assert!(typo.validate().is_err());
+        assert_eq!(defaults.restart_required(&configuration), vec!["compression"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn listeners_serve_a_set_of_services() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(&format!(
+            "{BASE_YAML}
+listeners:
+  - name: admin
+    address: 127.0.0.1:8091
+    services: [admin]
+  - name: sidecar
+    unix_socket: /run/authentication.sock
+"
+        ))?;
+        let variables =
+            environment_variables(&[("APP__APPLICATION__SERVICES", "authentication,users")]);
+
+        //-- Execute Function (Act)
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+        let mut invalid = configuration.clone();
+        invalid.listeners[1].address = Some("127.0.0.1:8092".to_string());
+        let mut unknown = configuration.clone();
+        unknown.listeners[0].services = vec!["admins".to_string()];
+
+        //-- Checks (Assertions)
+        assert_eq!(configuration.application.services, vec!["authentication", "users"]);
+        assert_eq!(configuration.listeners[0].services, vec!["admin"]);
+        assert_eq!(configuration.listeners[1].services, GRPC_SERVICES);
+        assert!(configuration.validate().is_ok());
+        assert!(invalid.validate().is_err());
+        assert!(unknown.validate().is_err());
+        assert_eq!(configuration.restart_required(&unknown), vec!["listeners"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn internal_server_takes_the_admin_services() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[
+            ("APP__INTERNAL__ENABLED", "true"),
+            ("APP__INTERNAL__ADDRESS", "10.0.0.5:9000"),
+        ]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+        let mut unknown = configuration.clone();
+        unknown.internal.services.push("audit".to_string());
+
+        //-- Checks (Assertions)
+        assert!(!defaults.internal.enabled);
+        assert_eq!(defaults.public_services(), GRPC_SERVICES);
+        assert!(defaults.all_listeners().is_empty());
+        assert_eq!(
+            configuration.public_services(),
+            vec!["utilities", "authentication", "users", "sessions"]
+        );
+        let listeners = configuration.all_listeners();
+        assert_eq!(listeners.len(), 1);
+        assert_eq!(listeners[0].address.as_deref(), Some("10.0.0.5:9000"));
+        assert_eq!(listeners[0].services, vec!["admin"]);
+        assert!(configuration.validate().is_ok());
+        assert!(unknown.validate().is_err());
+        assert_eq!(defaults.restart_required(&configuration), vec!["internal"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn readiness_checks_can_be_reloaded() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[
+            ("APP__READINESS__CHECK_EMAIL", "true"),
+            ("APP__READINESS__RETRY_INTERVAL_SECONDS", "1"),
+        ]);
+        let invalid = environment_variables(&[("APP__READINESS__RETRY_INTERVAL_SECONDS", "0")]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+        let invalid = Configuration::parse_from(&directory, Environment::Testing, invalid)?;
+
+        //-- Checks (Assertions)
+        assert!(!defaults.readiness.check_email);
+        assert_eq!(defaults.readiness.retry_interval_seconds, 5);
+        assert!(configuration.validate().is_ok());
+        assert!(invalid.validate().is_err());
+        let reloaded = defaults.with_reloadable(&configuration);
+        assert!(reloaded.readiness.check_email);
+        assert_eq!(reloaded.readiness.retry_interval_seconds, 1);
+        assert!(defaults.restart_required(&configuration).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn fault_injection_rules_are_validated() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(&format!(
+            "{BASE_YAML}
+fault_injection:
+  enabled: true
+  rules:
+    - path: /authentication.v1.AuthenticationService/Login
+      latency_percent: 50
+      latency_milliseconds: 250
+      error_percent: 10
+"
+        ))?;
+
+        //-- Execute Function (Act)
+        let configuration = Configuration::parse_from(
+            &directory,
+            Environment::Testing,
+            environment_variables(&[]),
+        )?;
+        let mut unknown_code = configuration.clone();
+        unknown_code.fault_injection.rules[0].error_code = "OOPS".to_string();
+        let mut too_many = configuration.clone();
+        too_many.fault_injection.rules[0].drop_percent = 101.0;
+
+        //-- Checks (Assertions)
+        let rule = &configuration.fault_injection.rules[0];
+        assert_eq!(rule.latency_milliseconds, 250);
+        assert_eq!(rule.error_code, "UNAVAILABLE");
+        assert_eq!(rule.drop_percent, 0.0);
+        assert_eq!(configuration.validate().is_ok(), cfg!(feature = "fault_injection"));
+        assert!(unknown_code.validate().is_err());
+        assert!(too_many.validate().is_err());
+        assert_eq!(
+            configuration.restart_required(&Configuration {
+                fault_injection: FaultInjectionConfiguration::default(),
+                ..configuration.clone()
+            }),
+            vec!["fault_injection"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn registration_mode_defaults_to_strict() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables =
+            environment_variables(&[("APP__APPLICATION__REGISTRATION_MODE", "friendly")]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+
+        //-- Checks (Assertions)
+        assert_eq!(defaults.application.registration_mode, RegistrationMode::Strict);
+        assert_eq!(
+            configuration.application.registration_mode,
+            RegistrationMode::Friendly
+        );
+        assert!(defaults.restart_required(&configuration).is_empty());
+        assert_eq!(
+            defaults.with_reloadable(&configuration).application.registration_mode,
+            RegistrationMode::Friendly
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn token_audiences_are_parsed_from_a_comma_separated_variable() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables =
+            environment_variables(&[("APP__APPLICATION__TOKEN_AUDIENCES", "web,mobile")]);
+        let empty = environment_variables(&[("APP__APPLICATION__TOKEN_AUDIENCES", " ")]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+        let empty = Configuration::parse_from(&directory, Environment::Testing, empty)?;
+
+        //-- Checks (Assertions)
+        assert_eq!(
+            defaults.application.token_audiences,
+            vec![crate::domain::DEFAULT_AUDIENCE.to_string()]
+        );
+        assert_eq!(configuration.application.token_audiences, vec!["web", "mobile"]);
+        assert!(configuration.validate().is_ok());
+        assert!(empty.validate().is_err());
+        assert_eq!(
+            defaults.restart_required(&configuration),
+            vec!["application.token_audiences"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn trusted_proxies_are_parsed_from_a_comma_separated_variable() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[(
+            "APP__TRUSTED_PROXIES__CIDRS",
+            "10.0.0.0/8,fd00::/8",
+        )]);
+        let invalid = environment_variables(&[("APP__TRUSTED_PROXIES__CIDRS", "10.0.0.0/33")]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+        let invalid = Configuration::parse_from(&directory, Environment::Testing, invalid);
+
+        //-- Checks (Assertions)
+        assert!(defaults.trusted_proxies.cidrs.is_empty());
+        assert_eq!(configuration.trusted_proxies.cidrs.len(), 2);
+        assert!(configuration.trusted_proxies.is_trusted(&"10.1.2.3".parse()?));
+        assert!(configuration.trusted_proxies.is_trusted(&"fd00::1".parse()?));
+        assert!(!configuration.trusted_proxies.is_trusted(&"203.0.113.7".parse()?));
+        assert!(invalid.is_err());
+        assert_eq!(
+            defaults.restart_required(&configuration),
+            vec!["trusted_proxies"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn magic_link_is_disabled_by_default() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[
+            ("APP__MAGIC_LINK__ENABLED", "true"),
+            ("APP__MAGIC_LINK__LINK_URL", "https://example.com/login?via=email"),
+        ]);
+        let invalid = environment_variables(&[
+            ("APP__MAGIC_LINK__ENABLED", "true"),
+            ("APP__MAGIC_LINK__EXPIRY_MINUTES", "0"),
+        ]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+        let invalid = Configuration::parse_from(&directory, Environment::Testing, invalid)?;
+
+        //-- Checks (Assertions)
+        assert!(!defaults.magic_link.enabled);
+        assert_eq!(defaults.magic_link.expiry_minutes, 15);
+        assert!(configuration.validate().is_ok());
+        assert_eq!(
+            configuration.magic_link.link("act_abc.def"),
+            "https://example.com/login?via=email&token=act_abc.def"
+        );
+        assert!(invalid.validate().is_err());
+        assert!(defaults.restart_required(&configuration).is_empty());
+        assert!(defaults.with_reloadable(&configuration).magic_link.enabled);
+
+        Ok(())
+    }
+
+    #[test]
+    fn password_reset_limits_requests_by_default() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[
+            ("APP__PASSWORD_RESET__MAX_REQUESTS_PER_EMAIL", "0"),
+            ("APP__PASSWORD_RESET__LINK_URL", "https://example.com/reset?via=email"),
+        ]);
+        let invalid = environment_variables(&[("APP__PASSWORD_RESET__EXPIRY_MINUTES", "0")]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+        let invalid = Configuration::parse_from(&directory, Environment::Testing, invalid)?;
+
+        //-- Checks (Assertions)
+        assert_eq!(defaults.password_reset.expiry_minutes, 60);
+        assert_eq!(defaults.password_reset.max_requests_per_email, 3);
+        assert_eq!(defaults.password_reset.max_requests_per_ip, 10);
+        assert!(configuration.validate().is_ok());
+        assert_eq!(
+            configuration.password_reset.link("act_abc.def"),
+            "https://example.com/reset?via=email&token=act_abc.def"
+        );
+        assert!(invalid.validate().is_err());
+        assert!(defaults.restart_required(&configuration).is_empty());
+        assert_eq!(
+            defaults
+                .with_reloadable(&configuration)
+                .password_reset
+                .max_requests_per_email,
+            0
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn verification_reminders_are_disabled_by_default() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[
+            ("APP__VERIFICATION_REMINDERS__ENABLED", "true"),
+            ("APP__VERIFICATION_REMINDERS__MAX_REMINDERS", "5"),
+            ("APP__VERIFICATION_REMINDERS__POLL_INTERVAL_SECONDS", "60"),
+        ]);
+        let invalid = environment_variables(&[
+            ("APP__VERIFICATION_REMINDERS__ENABLED", "true"),
+            ("APP__VERIFICATION_REMINDERS__DEACTIVATE_AFTER_DAYS", "2"),
+        ]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+        let invalid = Configuration::parse_from(&directory, Environment::Testing, invalid)?;
+        let reloaded = defaults.with_reloadable(&configuration);
+
+        //-- Checks (Assertions)
+        assert!(!defaults.verification_reminders.enabled);
+        assert_eq!(defaults.verification_reminders.unverified_after_days, 3);
+        assert_eq!(defaults.verification_reminders.deactivate_after_days, 30);
+        assert!(configuration.validate().is_ok());
+        assert!(invalid.validate().is_err());
+        assert!(reloaded.verification_reminders.enabled);
+        assert_eq!(reloaded.verification_reminders.max_reminders, 5);
+        assert_eq!(reloaded.verification_reminders.poll_interval_seconds, 3_600);
+
+        Ok(())
+    }
+
+    #[test]
+    fn passkeys_are_disabled_by_default() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[
+            ("APP__PASSKEYS__ENABLED", "true"),
+            ("APP__PASSKEYS__MODE", "second_factor"),
+            ("APP__PASSKEYS__RELYING_PARTY_ID", "example.com"),
+            ("APP__PASSKEYS__RELYING_PARTY_ORIGIN", "https://login.example.com"),
+        ]);
+        let invalid = environment_variables(&[
+            ("APP__PASSKEYS__ENABLED", "true"),
+            ("APP__PASSKEYS__RELYING_PARTY_ID", ""),
+        ]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+        let invalid = Configuration::parse_from(&directory, Environment::Testing, invalid)?;
+
+        //-- Checks (Assertions)
+        assert!(!defaults.passkeys.enabled);
+        assert_eq!(defaults.passkeys.mode, PasskeyMode::Primary);
+        assert!(configuration.validate().is_ok());
+        assert_eq!(configuration.passkeys.mode, PasskeyMode::SecondFactor);
+        assert_eq!(configuration.passkeys.relying_party_id, "example.com");
+        assert!(invalid.validate().is_err());
+        assert!(defaults.restart_required(&configuration).is_empty());
+        assert!(defaults.with_reloadable(&configuration).passkeys.enabled);
+
+        Ok(())
+    }
+
+    #[test]
+    fn ldap_is_disabled_by_default() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[
+            ("APP__LDAP__ENABLED", "true"),
+            ("APP__LDAP__URL", "ldaps://ldap.example.com"),
+            ("APP__LDAP__BASE_DN", "ou=people,dc=example,dc=com"),
+            ("APP__LDAP__SEARCH_FILTER", "(userPrincipalName={email})"),
+        ]);
+        let invalid = environment_variables(&[
+            ("APP__LDAP__ENABLED", "true"),
+            ("APP__LDAP__BASE_DN", "ou=people,dc=example,dc=com"),
+            ("APP__LDAP__SEARCH_FILTER", "(mail=*)"),
+        ]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+        let invalid = Configuration::parse_from(&directory, Environment::Testing, invalid)?;
+
+        //-- Checks (Assertions)
+        assert!(!defaults.ldap.enabled);
+        assert_eq!(defaults.ldap.search_filter, "(mail={email})");
+        assert_eq!(configuration.ldap.url, "ldaps://ldap.example.com");
+        assert_eq!(configuration.validate().is_ok(), cfg!(feature = "ldap"));
+        assert!(invalid.validate().is_err());
+        assert!(defaults.restart_required(&configuration).is_empty());
+        assert!(defaults.with_reloadable(&configuration).ldap.enabled);
+
+        Ok(())
+    }
+
+    #[test]
+    fn saml_is_disabled_by_default() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[
+            ("APP__SAML__ENABLED", "true"),
+            ("APP__SAML__IDP_ENTITY_ID", "https://idp.example.com"),
+            ("APP__SAML__IDP_SSO_URL", "https://idp.example.com/sso"),
+            ("APP__SAML__IDP_CERTIFICATE", "MIIC"),
+            ("APP__SAML__PROVISION_USERS", "false"),
+        ]);
+        let invalid = environment_variables(&[("APP__SAML__ENABLED", "true")]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+        let invalid = Configuration::parse_from(&directory, Environment::Testing, invalid)?;
+
+        //-- Checks (Assertions)
+        assert!(!defaults.saml.enabled);
+        assert!(defaults.saml.provision_users);
+        assert!(!configuration.saml.provision_users);
+        assert_eq!(configuration.validate().is_ok(), cfg!(feature = "saml"));
+        assert!(invalid.validate().is_err());
+        assert!(defaults.restart_required(&configuration).is_empty());
+        assert!(defaults.with_reloadable(&configuration).saml.enabled);
+
+        Ok(())
+    }
+
+    #[test]
+    fn device_authorization_is_disabled_by_default() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[
+            ("APP__DEVICE_AUTHORIZATION__ENABLED", "true"),
+            ("APP__DEVICE_AUTHORIZATION__INTERVAL_SECONDS", "10"),
+        ]);
+        let invalid = environment_variables(&[
+            ("APP__DEVICE_AUTHORIZATION__ENABLED", "true"),
+            ("APP__DEVICE_AUTHORIZATION__EXPIRY_MINUTES", "0"),
+        ]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+        let invalid = Configuration::parse_from(&directory, Environment::Testing, invalid)?;
+
+        //-- Checks (Assertions)
+        assert!(!defaults.device_authorization.enabled);
+        assert_eq!(defaults.device_authorization.expiry_minutes, 10);
+        assert_eq!(configuration.device_authorization.interval_seconds, 10);
+        assert_eq!(
+            configuration
+                .device_authorization
+                .verification_uri_complete("BCDF-GHJK"),
+            "http://localhost:8080/device?user_code=BCDF-GHJK"
+        );
+        assert!(configuration.validate().is_ok());
+        assert!(invalid.validate().is_err());
+        assert!(defaults.restart_required(&configuration).is_empty());
+        assert!(defaults.with_reloadable(&configuration).device_authorization.enabled);
+
+        Ok(())
+    }
+
+    #[test]
+    fn client_credentials_is_disabled_by_default() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[
+            ("APP__CLIENT_CREDENTIALS__ENABLED", "true"),
+            ("APP__CLIENT_CREDENTIALS__ACCESS_TOKEN_DURATION_MINUTES", "2"),
+        ]);
+        let invalid = environment_variables(&[
+            ("APP__CLIENT_CREDENTIALS__ENABLED", "true"),
+            ("APP__CLIENT_CREDENTIALS__ACCESS_TOKEN_DURATION_MINUTES", "0"),
+        ]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+        let invalid = Configuration::parse_from(&directory, Environment::Testing, invalid)?;
+
+        //-- Checks (Assertions)
+        assert!(!defaults.client_credentials.enabled);
+        assert_eq!(defaults.client_credentials.access_token_duration_minutes, 5);
+        assert_eq!(configuration.client_credentials.access_token_duration_minutes, 2);
+        assert!(configuration.validate().is_ok());
+        assert!(invalid.validate().is_err());
+        assert!(defaults.restart_required(&configuration).is_empty());
+        assert!(defaults.with_reloadable(&configuration).client_credentials.enabled);
+
+        Ok(())
+    }
+
+    #[test]
+    fn impersonation_is_disabled_by_default() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[
+            ("APP__IMPERSONATION__ENABLED", "true"),
+            ("APP__IMPERSONATION__ACCESS_TOKEN_DURATION_MINUTES", "5"),
+        ]);
+        let invalid = environment_variables(&[
+            ("APP__IMPERSONATION__ENABLED", "true"),
+            ("APP__IMPERSONATION__ACCESS_TOKEN_DURATION_MINUTES", "0"),
+        ]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+        let invalid = Configuration::parse_from(&directory, Environment::Testing, invalid)?;
+
+        //-- Checks (Assertions)
+        assert!(!defaults.impersonation.enabled);
+        assert_eq!(defaults.impersonation.access_token_duration_minutes, 15);
+        assert_eq!(defaults.impersonation.min_reason_length, 10);
+        assert_eq!(configuration.impersonation.access_token_duration_minutes, 5);
+        assert!(configuration.validate().is_ok());
+        assert!(invalid.validate().is_err());
+        assert!(defaults.with_reloadable(&configuration).impersonation.enabled);
+
+        Ok(())
+    }
+
+    #[test]
+    fn policies_are_not_required_by_default() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[(
+            "APP__POLICIES__TERMS_OF_SERVICE_VERSION",
+            "2026-10-15",
+        )]);
+        let invalid =
+            environment_variables(&[("APP__POLICIES__PRIVACY_POLICY_VERSION", " ")]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+        let invalid = Configuration::parse_from(&directory, Environment::Testing, invalid)?;
+
+        //-- Checks (Assertions)
+        assert!(defaults.policies.required().is_empty());
+        assert_eq!(
+            configuration.policies.required(),
+            vec![(domain::Policy::TermsOfService, "2026-10-15")]
+        );
+        assert!(configuration.validate().is_ok());
+        assert!(invalid.validate().is_err());
+        assert_eq!(
+            defaults.with_reloadable(&configuration).policies.required().len(),
+            1
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn avatar_backends_require_their_settings() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let local = environment_variables(&[("APP__AVATARS__BACKEND", "local")]);
+        let s3 = environment_variables(&[
+            ("APP__AVATARS__BACKEND", "s3"),
+            ("APP__AVATARS__S3_ENDPOINT", "http://localhost:9000"),
+            ("APP__AVATARS__S3_BUCKET", "avatars"),
+            ("APP__AVATARS__S3_ACCESS_KEY_ID", "minioadmin"),
+            ("APP__AVATARS__S3_SECRET_ACCESS_KEY", "minioadmin"),
+        ]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let local = Configuration::parse_from(&directory, Environment::Testing, local)?;
+        let s3 = Configuration::parse_from(&directory, Environment::Testing, s3)?;
+
+        //-- Checks (Assertions)
+        assert_eq!(defaults.avatars.backend, AvatarStorageBackend::None);
+        assert_eq!(defaults.avatars.size_pixels, 256);
+        assert_eq!(local.avatars.backend, AvatarStorageBackend::Local);
+        assert!(local.validate().is_err());
+        assert_eq!(s3.avatars.backend, AvatarStorageBackend::S3);
+        assert!(s3.validate().is_ok());
+        assert_eq!(defaults.restart_required(&s3), vec!["avatars"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn email_domains_are_validated() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[
+            ("APP__EMAIL_DOMAINS__ALLOWED", "example.com,@example.org"),
+            ("APP__EMAIL_DOMAINS__BLOCK_DISPOSABLE", "true"),
+        ]);
+        let invalid = environment_variables(&[("APP__EMAIL_DOMAINS__BLOCKED", "localhost")]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+        let invalid = Configuration::parse_from(&directory, Environment::Testing, invalid)?;
+
+        //-- Checks (Assertions)
+        assert!(defaults.email_domains.allowed.is_empty());
+        assert!(!defaults.email_domains.block_disposable);
+        assert_eq!(configuration.email_domains.allowed, vec!["example.com", "@example.org"]);
+        assert!(configuration.validate().is_ok());
+        assert!(invalid.validate().is_err());
+        assert!(defaults.with_reloadable(&configuration).email_domains.block_disposable);
+        assert!(defaults.restart_required(&configuration).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn user_name_policy_is_built_from_the_configuration() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let profanity_list = directory.join("profanity.txt");
+        std::fs::write(&profanity_list, "# Words\nDarn\n\n  heck  \n")?;
+        let variables = environment_variables(&[
+            ("APP__USER_NAMES__MIN_LENGTH", "2"),
+            ("APP__USER_NAMES__CHARACTERS", "letters"),
+            ("APP__USER_NAMES__RESERVED", "Admin,root"),
+            ("APP__USER_NAMES__PROFANITY_LIST", profanity_list.to_str().unwrap_or_default()),
+        ]);
+        let invalid = environment_variables(&[("APP__USER_NAMES__MIN_LENGTH", "0")]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+        let invalid = Configuration::parse_from(&directory, Environment::Testing, invalid)?;
+        let policy = configuration.user_names.policy()?;
+
+        //-- Checks (Assertions)
+        assert_eq!(defaults.user_names.policy()?, domain::UserNamePolicy::default());
+        assert_eq!(policy.min_length, 2);
+        assert_eq!(policy.characters, domain::UserNameCharacters::Letters);
+        assert!(policy.reserved.contains("admin"));
+        assert_eq!(policy.profanity.len(), 2);
+        assert!(policy.profanity.contains("darn"));
+        assert!(configuration.validate().is_ok());
+        assert!(invalid.validate().is_err());
+        assert_eq!(defaults.restart_required(&configuration), vec!["user_names"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn error_messages_are_configured() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let translations = directory.join("errors");
+        std::fs::create_dir(&translations)?;
+        let variables = environment_variables(&[
+            (
+                "APP__ERROR_MESSAGES__TRANSLATION_DIRECTORY",
+                translations.to_str().unwrap_or_default(),
+            ),
+            ("APP__ERROR_MESSAGES__HELP_URL", "https://docs.example.com/errors#{code}"),
+        ]);
+        let missing = environment_variables(&[(
+            "APP__ERROR_MESSAGES__TRANSLATION_DIRECTORY",
+            "/does/not/exist/errors",
+        )]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+        let missing = Configuration::parse_from(&directory, Environment::Testing, missing)?;
+
+        //-- Checks (Assertions)
+        assert_eq!(defaults.error_messages.translation_directory, None);
+        assert_eq!(
+            configuration.error_messages.help_url.as_deref(),
+            Some("https://docs.example.com/errors#{code}")
+        );
+        assert!(configuration.validate().is_ok());
+        assert!(missing.validate().is_err());
+        assert_eq!(defaults.restart_required(&configuration), vec!["error_messages"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn absolute_session_lifetime_is_validated_when_sliding() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[
+            ("APP__APPLICATION__SLIDING_EXPIRATION", "true"),
+            ("APP__APPLICATION__ABSOLUTE_SESSION_LIFETIME_MINUTES", "1"),
+        ]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+
+        //-- Checks (Assertions)
+        assert!(!defaults.application.sliding_expiration);
+        assert!(defaults.validate().is_ok());
+        assert!(configuration.application.sliding_expiration);
+        assert!(configuration.validate().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn refresh_cookie_attributes_are_validated() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(&format!(
+            "{BASE_YAML}
+refresh_cookie:
+  name: \"__Host-refresh\"
+  same_site: none
+  secure: true
+  partitioned: true
+"
+        ))?;
+
+        //-- Execute Function (Act)
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let mut insecure = configuration.clone();
+        insecure.refresh_cookie.secure = false;
+        let mut bad_name = configuration.clone();
+        bad_name.refresh_cookie.name = "refresh token".to_string();
+        let mut bad_path = configuration.clone();
+        bad_path.refresh_cookie.path = "auth".to_string();
+
+        //-- Checks (Assertions)
+        assert_eq!(configuration.refresh_cookie.name, "__Host-refresh");
+        assert_eq!(configuration.refresh_cookie.path, "/");
+        assert_eq!(configuration.refresh_cookie.same_site, Some(CookieSameSite::None));
+        assert!(configuration.refresh_cookie.partitioned);
+        assert!(configuration.validate().is_ok());
+        assert!(insecure.validate().is_err());
+        assert!(bad_name.validate().is_err());
+        assert!(bad_path.validate().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn refresh_token_metadata_key_is_validated() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables = environment_variables(&[
+            ("APP__REFRESH_TOKEN__ACCEPT_BEARER", "true"),
+            ("APP__REFRESH_TOKEN__METADATA_KEY", "x-refresh-token"),
+        ]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+        let invalid = ["X-Refresh-Token", "refresh token", "cookie", "x-refresh-token-bin"].map(
+            |metadata_key| {
+                let mut invalid = configuration.clone();
+                invalid.refresh_token.metadata_key = Some(metadata_key.to_string());
+                invalid.validate()
+            },
+        );
+
+        //-- Checks (Assertions)
+        assert_eq!(defaults.refresh_token, RefreshTokenConfiguration::default());
+        assert!(configuration.refresh_token.accept_bearer);
+        assert_eq!(configuration.refresh_token.metadata_key.as_deref(), Some("x-refresh-token"));
+        assert!(configuration.validate().is_ok());
+        assert!(invalid.iter().all(|result| result.is_err()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn idle_session_timeout_is_off_by_default() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = configuration_directory(BASE_YAML)?;
+        let variables =
+            environment_variables(&[("APP__APPLICATION__IDLE_SESSION_TIMEOUT_DAYS", "14")]);
+
+        //-- Execute Function (Act)
+        let defaults =
+            Configuration::parse_from(&directory, Environment::Testing, environment_variables(&[]))?;
+        let configuration =
+            Configuration::parse_from(&directory, Environment::Testing, variables)?;
+
+        //-- Checks (Assertions)
+        assert_eq!(defaults.application.idle_session_timeout(), None);
+        assert_eq!(
+            configuration.application.idle_session_timeout(),
+            Some(chrono::Duration::days(14))
+        );
+
+        Ok(())
+    }
+}
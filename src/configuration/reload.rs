@@ -0,0 +1,233 @@
+//-- ./src/configuration/reload.rs
+
+// #![allow(unused)] // For development only
+
+//! Configuration hot-reload
+//!
+//! Watches the `./configuration` directory and, when a file changes, parses
+//! and validates the configuration again before swapping it into the shared
+//! `SharedConfiguration` used by the services.
+//!
+//! Only non-critical settings are reloaded (see `Configuration::with_reloadable`).
+//! Changes to critical settings such as the bind address, token secret, TLS
+//! or database are logged and ignored until the next restart. An invalid
+//! configuration is rejected and the running configuration is kept.
+//! ---
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::configuration::{Configuration, SharedConfiguration};
+use crate::prelude::*;
+use crate::telemetry::LogLevelHandle;
+
+/// Editors often write a file in several steps, so wait for the burst to settle
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Running configuration watcher, dropping it stops the watch
+pub struct ConfigurationWatcher {
+    _watcher: RecommendedWatcher,
+    task: JoinHandle<()>,
+}
+
+impl Drop for ConfigurationWatcher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Outcome of applying a reloaded configuration
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReloadOutcome {
+    /// Reloadable settings were swapped in
+    Applied {
+        /// Critical settings that changed but need a restart
+        restart_required: Vec<&'static str>,
+    },
+    /// The reloaded configuration failed validation and was ignored
+    Rejected(String),
+}
+
+/// Validate a reloaded configuration and swap the reloadable settings into
+/// the shared configuration.
+///
+/// # Parameters
+/// * `shared` - The shared configuration used by the services.
+/// * `reloaded` - The newly parsed configuration.
+/// * `log_level_handle` - Handle to update the log level, when tracing is initiated.
+pub fn apply(
+    shared: &SharedConfiguration,
+    reloaded: Configuration,
+    log_level_handle: Option<&LogLevelHandle>,
+) -> ReloadOutcome {
+    if let Err(e) = reloaded.validate() {
+        tracing::error!("Reloaded configuration is invalid and was ignored: {e}");
+        return ReloadOutcome::Rejected(e.to_string());
+    }
+
+    let current = shared.load_full();
+    let restart_required = current.restart_required(&reloaded);
+    if !restart_required.is_empty() {
+        tracing::warn!(
+            "Configuration changes need a restart to apply: {}",
+            restart_required.join(", ")
+        );
+    }
+
+    let updated = current.with_reloadable(&reloaded);
+
+    if updated.application.log_level != current.application.log_level {
+        if let Some(handle) = log_level_handle {
+            if let Err(e) = handle.set(updated.application.log_level) {
+                tracing::error!("{e}");
+            }
+        }
+    }
+
+    shared.store(Arc::new(updated));
+    tracing::info!("Configuration reloaded");
+
+    ReloadOutcome::Applied { restart_required }
+}
+
+/// Start watching the configuration directory for changes.
+///
+/// # Parameters
+/// * `shared` - The shared configuration to update on reload.
+/// * `log_level_handle` - Handle to update the log level, when tracing is initiated.
+///
+/// # Returns
+/// * `ConfigurationWatcher` - Keep this alive for as long as reloads should happen.
+pub fn watch(
+    shared: SharedConfiguration,
+    log_level_handle: Option<LogLevelHandle>,
+) -> Result<ConfigurationWatcher, AuthenticationError> {
+    let configuration_directory: PathBuf = std::env::current_dir()?.join("configuration");
+
+    // Notify calls back on its own thread, so forward events into the runtime
+    let (sender, mut receiver) = mpsc::unbounded_channel::<()>();
+    let mut watcher =
+        notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            match event {
+                Ok(event) if event.kind.is_modify() || event.kind.is_create() => {
+                    let _ = sender.send(());
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!("Configuration watcher error: {e}"),
+            }
+        })?;
+    watcher.watch(&configuration_directory, RecursiveMode::NonRecursive)?;
+    tracing::info!("Watching {configuration_directory:?} for configuration changes");
+
+    let task = tokio::spawn(async move {
+        while receiver.recv().await.is_some() {
+            // Collapse a burst of file events into a single reload
+            tokio::time::sleep(RELOAD_DEBOUNCE).await;
+            while receiver.try_recv().is_ok() {}
+
+            match Configuration::parse() {
+                Ok(reloaded) => {
+                    let _outcome = apply(&shared, reloaded, log_level_handle.as_ref());
+                }
+                Err(e) => {
+                    tracing::error!("Unable to parse changed configuration, keeping the running one: {e}")
+                }
+            }
+        }
+    });
+
+    Ok(ConfigurationWatcher {
+        _watcher: watcher,
+        task,
+    })
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing::level_filters::LevelFilter;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn reloadable_settings_are_swapped() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let current = Configuration::parse()?;
+        let shared = current.clone().into_shared();
+        let mut reloaded = current.clone();
+        reloaded.application.log_level = LevelFilter::WARN;
+        reloaded.application.access_token_duration_minutes = 5;
+
+        //-- Execute Function (Act)
+        let outcome = apply(&shared, reloaded, None);
+
+        //-- Checks (Assertions)
+        assert_eq!(
+            outcome,
+            ReloadOutcome::Applied {
+                restart_required: vec![]
+            }
+        );
+        assert_eq!(shared.load().application.log_level, LevelFilter::WARN);
+        assert_eq!(shared.load().application.access_token_duration_minutes, 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn critical_settings_are_kept_until_restart() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let current = Configuration::parse()?;
+        let shared = current.clone().into_shared();
+        let mut reloaded = current.clone();
+        reloaded.application.port = current.application.port + 1;
+        reloaded.database.database_name = "another".to_string();
+
+        //-- Execute Function (Act)
+        let outcome = apply(&shared, reloaded, None);
+
+        //-- Checks (Assertions)
+        assert_eq!(
+            outcome,
+            ReloadOutcome::Applied {
+                restart_required: vec!["application.port", "database"]
+            }
+        );
+        assert_eq!(shared.load().application.port, current.application.port);
+        assert_eq!(
+            shared.load().database.database_name,
+            current.database.database_name
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_configuration_is_rejected() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let current = Configuration::parse()?;
+        let shared = current.clone().into_shared();
+        let mut reloaded = current.clone();
+        reloaded.application.access_token_duration_minutes = 0;
+
+        //-- Execute Function (Act)
+        let outcome = apply(&shared, reloaded, None);
+
+        //-- Checks (Assertions)
+        assert!(matches!(outcome, ReloadOutcome::Rejected(_)));
+        assert_eq!(
+            shared.load().application.access_token_duration_minutes,
+            current.application.access_token_duration_minutes
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+//-- ./src/database/access_token_denylist/delete.rs
+
+// #![allow(unused)] // For development only
+
+//! Access token denylist deletion logic for the authentication service.
+//!
+//! # Contents
+//! - Delete entries whose access token has expired
+//! - Unit tests for deletion scenarios
+
+use sqlx::{Pool, Postgres};
+
+use crate::database::AccessTokenDenylist;
+use crate::prelude::*;
+
+impl AccessTokenDenylist {
+    /// Delete denylist entries whose access token has expired.
+    ///
+    /// An expired access token is rejected by its claim, so no longer needs denying.
+    ///
+    /// # Parameters
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of entries deleted.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Delete expired denylist entries: ", skip(database))]
+    pub async fn delete_expired(database: &Pool<Postgres>) -> Result<u64, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                DELETE
+                FROM access_token_denylist
+                WHERE expires_on <= NOW()
+            "#,
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Expired denylist rows deleted: {rows_affected}");
+
+        Ok(rows_affected)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use chrono::{Duration, Utc};
+    use sqlx::{Pool, Postgres};
+    use uuid::Uuid;
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn delete_expired_keeps_unexpired_entries(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let random_user = database::Users::mock_data()?.insert(&database).await?;
+
+        for minutes in [-10, -5, 5] {
+            database::AccessTokenDenylist::new(
+                &Uuid::now_v7().to_string(),
+                &random_user.id,
+                &(Utc::now() + Duration::minutes(minutes)),
+            )
+            .insert(&database)
+            .await?;
+        }
+
+        //-- Execute Function (Act)
+        let rows_affected = database::AccessTokenDenylist::delete_expired(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(rows_affected, 2);
+        assert_eq!(database::AccessTokenDenylist::index_unexpired(&database).await?.len(), 1);
+
+        Ok(())
+    }
+}
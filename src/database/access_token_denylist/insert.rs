@@ -0,0 +1,182 @@
+//-- ./src/database/access_token_denylist/insert.rs
+
+// #![allow(unused)] // For development only
+
+//! Access token denylist insert logic for the authentication service.
+//!
+//! # Contents
+//! - Deny a single access token
+//! - Deny the access tokens issued for a user's active sessions
+//! - Unit tests for insert scenarios
+
+use chrono::{DateTime, Utc};
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::database::AccessTokenDenylist;
+use crate::prelude::*;
+
+impl AccessTokenDenylist {
+    /// Insert this entry into the denylist.
+    ///
+    /// Denying a token that is already denied is not an error.
+    ///
+    /// # Parameters
+    /// * `self` - The `AccessTokenDenylist` instance to insert.
+    /// * `database` - The SQLx PostgreSQL connection pool, or a transaction.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of rows inserted (0 if the token was already denied).
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Insert an access token into the denylist: ",
+        skip(database),
+        fields(
+            jti = %self.jti,
+        )
+    )]
+    pub async fn insert(&self, database: impl PgExecutor<'_>) -> Result<u64, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                INSERT INTO access_token_denylist (jti, user_id, expires_on, created_on)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (jti) DO NOTHING
+            "#,
+            self.jti,
+            self.user_id,
+            self.expires_on,
+            self.created_on,
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Access token denylist rows inserted: {rows_affected}");
+
+        Ok(rows_affected)
+    }
+
+    /// Deny the access tokens issued for a user's active sessions.
+    ///
+    /// Copies the `access_token_id` of each active session belonging to `user_id` into the
+    /// denylist, skipping `except_session_id` when given. Run before revoking the sessions.
+    ///
+    /// # Parameters
+    /// * `user_id` - The user whose session access tokens are denied.
+    /// * `except_session_id` - A session whose access token stays valid.
+    /// * `expires_on` - When the denied tokens expire, at the latest.
+    /// * `database` - The SQLx PostgreSQL connection pool, or a transaction.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<AccessTokenDenylist>)` - The newly denied entries.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Deny the access tokens of a users sessions: ",
+        skip(database)
+    )]
+    pub async fn insert_for_sessions(
+        user_id: &Uuid,
+        except_session_id: Option<&Uuid>,
+        expires_on: &DateTime<Utc>,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Vec<AccessTokenDenylist>, AuthenticationError> {
+        let database_records = sqlx::query_as!(
+            AccessTokenDenylist,
+            r#"
+                INSERT INTO access_token_denylist (jti, user_id, expires_on)
+                SELECT access_token_id, user_id, $3
+                FROM sessions
+                WHERE user_id = $1
+                    AND is_active = true
+                    AND access_token_id IS NOT NULL
+                    AND ($2::uuid IS NULL OR id <> $2)
+                ON CONFLICT (jti) DO NOTHING
+                RETURNING jti, user_id, expires_on, created_on
+            "#,
+            user_id,
+            except_session_id,
+            expires_on,
+        )
+        .fetch_all(database)
+        .await?;
+
+        tracing::debug!("Access tokens denied: {}", database_records.len());
+
+        Ok(database_records)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use chrono::{Duration, Utc};
+    use sqlx::{Pool, Postgres};
+    use uuid::Uuid;
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn insert_is_idempotent(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let random_user = database::Users::mock_data()?.insert(&database).await?;
+        let entry = database::AccessTokenDenylist::new(
+            &Uuid::now_v7().to_string(),
+            &random_user.id,
+            &(Utc::now() + Duration::minutes(5)),
+        );
+
+        //-- Execute Function (Act)
+        let first = entry.insert(&database).await?;
+        let second = entry.insert(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(first, 1);
+        assert_eq!(second, 0);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn insert_for_sessions_skips_kept_session(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let random_user = database::Users::mock_data()?.insert(&database).await?;
+
+        let mut kept = database::Sessions::mock_data(&random_user)
+            .await?
+            .with_access_token_id(&Uuid::now_v7().to_string());
+        kept.is_active = true;
+        let kept = kept.insert(&database).await?;
+
+        let other_jti = Uuid::now_v7().to_string();
+        let mut other = database::Sessions::mock_data(&random_user)
+            .await?
+            .with_access_token_id(&other_jti);
+        other.is_active = true;
+        other.insert(&database).await?;
+
+        // Sessions without an access token id are skipped
+        let mut untracked = database::Sessions::mock_data(&random_user).await?;
+        untracked.is_active = true;
+        untracked.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let denied = database::AccessTokenDenylist::insert_for_sessions(
+            &random_user.id,
+            Some(&kept.id),
+            &(Utc::now() + Duration::minutes(5)),
+            &database,
+        )
+        .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(denied.len(), 1);
+        assert_eq!(denied[0].jti, other_jti);
+        assert_eq!(denied[0].user_id, random_user.id);
+
+        Ok(())
+    }
+}
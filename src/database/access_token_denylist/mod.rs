@@ -0,0 +1,22 @@
+//-- ./src/database/access_token_denylist/mod.rs
+
+//! Access token denylist database module for the authentication service.
+//!
+//! Access tokens are stateless, so revoking a session does not stop its access
+//! token being used until it expires. Denied access token ids (jti) are stored
+//! here so the authorisation layer can reject them.
+//!
+//! # Contents
+//! - Denylist deletion logic
+//! - Denylist insertion logic
+//! - Denylist struct definition
+//! - Denylist read/query logic
+
+// #![allow(unused)] // For development only
+
+pub use model::AccessTokenDenylist;
+
+mod delete;
+mod insert;
+mod model;
+mod read;
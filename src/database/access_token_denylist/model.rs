@@ -0,0 +1,40 @@
+//-- ./src/database/access_token_denylist/model.rs
+
+// #![allow(unused)] // For development only
+
+//! The access token denylist database model.
+//!
+//! # Contents
+//! - `AccessTokenDenylist` struct definition
+//! - Constructor for new denylist entries
+
+use chrono::{DateTime, SubsecRound, Utc};
+use uuid::Uuid;
+
+#[derive(Debug, serde::Deserialize, sqlx::FromRow, Clone, PartialEq)]
+pub struct AccessTokenDenylist {
+    pub jti: String,
+    pub user_id: Uuid,
+    pub expires_on: DateTime<Utc>,
+    pub created_on: DateTime<Utc>,
+}
+
+impl AccessTokenDenylist {
+    /// # New Denylist Entry
+    ///
+    /// Creates a new instance of the AccessTokenDenylist struct.
+    ///
+    /// ## Parameters
+    ///
+    /// - `jti: &str` - The access token unique id
+    /// - `user_id: &Uuid` - The user the access token was issued to
+    /// - `expires_on: &DateTime<Utc>` - When the access token expires, after which the entry can be pruned
+    pub fn new(jti: &str, user_id: &Uuid, expires_on: &DateTime<Utc>) -> Self {
+        Self {
+            jti: jti.to_string(),
+            user_id: user_id.to_owned(),
+            expires_on: expires_on.to_owned(),
+            created_on: Utc::now().round_subsecs(0),
+        }
+    }
+}
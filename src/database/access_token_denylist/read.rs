@@ -0,0 +1,91 @@
+//-- ./src/database/access_token_denylist/read.rs
+
+// #![allow(unused)] // For development only
+
+//! Access token denylist read logic for the authentication service.
+//!
+//! # Contents
+//! - Index the denylist entries that have not expired
+//! - Unit tests for read scenarios
+
+use sqlx::{Pool, Postgres};
+
+use crate::database::AccessTokenDenylist;
+use crate::prelude::*;
+
+impl AccessTokenDenylist {
+    /// Retrieve every denylist entry whose access token has not expired.
+    ///
+    /// Used to load the in memory denylist at startup.
+    ///
+    /// # Parameters
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<AccessTokenDenylist>)` - The unexpired entries.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Index unexpired denylist entries: ", skip(database))]
+    pub async fn index_unexpired(
+        database: &Pool<Postgres>,
+    ) -> Result<Vec<AccessTokenDenylist>, AuthenticationError> {
+        let database_records = sqlx::query_as!(
+            AccessTokenDenylist,
+            r#"
+                SELECT jti, user_id, expires_on, created_on
+                FROM access_token_denylist
+                WHERE expires_on > NOW()
+            "#,
+        )
+        .fetch_all(database)
+        .await?;
+
+        tracing::debug!("Denylist entries retrieved: {}", database_records.len());
+
+        Ok(database_records)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use chrono::{Duration, Utc};
+    use sqlx::{Pool, Postgres};
+    use uuid::Uuid;
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn index_unexpired_skips_expired_entries(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let random_user = database::Users::mock_data()?.insert(&database).await?;
+
+        let unexpired_jti = Uuid::now_v7().to_string();
+        database::AccessTokenDenylist::new(
+            &unexpired_jti,
+            &random_user.id,
+            &(Utc::now() + Duration::minutes(5)),
+        )
+        .insert(&database)
+        .await?;
+        database::AccessTokenDenylist::new(
+            &Uuid::now_v7().to_string(),
+            &random_user.id,
+            &(Utc::now() - Duration::minutes(5)),
+        )
+        .insert(&database)
+        .await?;
+
+        //-- Execute Function (Act)
+        let entries = database::AccessTokenDenylist::index_unexpired(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].jti, unexpired_jti);
+
+        Ok(())
+    }
+}
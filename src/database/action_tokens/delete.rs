@@ -0,0 +1,141 @@
+//-- ./src/database/action_tokens/delete.rs
+
+// #![allow(unused)] // For development only
+
+//! Action token delete logic for the authentication service.
+//!
+//! # Contents
+//! - Delete a user's unused tokens for a purpose
+//! - Delete expired and used tokens
+//! - Unit tests for delete scenarios
+
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::database::ActionTokens;
+use crate::domain;
+use crate::prelude::*;
+
+impl ActionTokens {
+    /// Delete a user's unused tokens for a purpose, so only the latest token
+    /// emailed can be used.
+    ///
+    /// # Parameters
+    /// * `user_id` - The user whose unused tokens are deleted.
+    /// * `purpose` - The purpose of the tokens to delete.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of action tokens deleted.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Delete unused action tokens from the database: ",
+        skip(database)
+    )]
+    pub async fn delete_unused_for_user(
+        user_id: &Uuid,
+        purpose: domain::ActionPurpose,
+        database: impl PgExecutor<'_>,
+    ) -> Result<u64, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                DELETE FROM action_tokens
+                WHERE user_id = $1 AND purpose = $2 AND used_on IS NULL
+            "#,
+            user_id,
+            purpose as domain::ActionPurpose,
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Unused action tokens deleted: {rows_affected}");
+
+        Ok(rows_affected)
+    }
+
+    /// Delete tokens that have expired or been used, they can no longer be
+    /// used.
+    ///
+    /// # Parameters
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of action tokens deleted.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Delete expired action tokens from the database: ",
+        skip(database)
+    )]
+    pub async fn delete_expired(
+        database: impl PgExecutor<'_>,
+    ) -> Result<u64, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                DELETE FROM action_tokens
+                WHERE expires_on < NOW() OR used_on IS NOT NULL
+            "#,
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Expired action tokens deleted: {rows_affected}");
+
+        Ok(rows_affected)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::{database, domain};
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn only_unused_tokens_for_the_purpose_are_deleted(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let (unused, _) = database::ActionTokens::mock_data(
+            &user.id,
+            domain::ActionPurpose::MagicLink,
+        );
+        unused.insert(&database).await?;
+        let (used, used_token) = database::ActionTokens::mock_data(
+            &user.id,
+            domain::ActionPurpose::MagicLink,
+        );
+        used.insert(&database).await?;
+        database::ActionTokens::consume(&used_token, &database).await?;
+        let (other_purpose, _) = database::ActionTokens::mock_data(
+            &user.id,
+            domain::ActionPurpose::AccountDeletion,
+        );
+        other_purpose.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let deleted = database::ActionTokens::delete_unused_for_user(
+            &user.id,
+            domain::ActionPurpose::MagicLink,
+            &database,
+        )
+        .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(deleted, 1);
+        let remaining: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM action_tokens")
+            .fetch_one(&database)
+            .await?;
+        assert_eq!(remaining.0, 2);
+
+        Ok(())
+    }
+}
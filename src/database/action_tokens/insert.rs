@@ -0,0 +1,95 @@
+//-- ./src/database/action_tokens/insert.rs
+
+// #![allow(unused)] // For development only
+
+//! Action token insert logic for the authentication service.
+//!
+//! # Contents
+//! - Insert an action token
+//! - Unit tests for insert scenarios
+
+use sqlx::PgExecutor;
+
+use crate::database::ActionTokens;
+use crate::domain;
+use crate::prelude::*;
+
+impl ActionTokens {
+    /// Insert this action token into the database.
+    ///
+    /// # Parameters
+    /// * `self` - The `ActionTokens` instance to insert.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(ActionTokens)` - The inserted record as returned from the database.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Insert an action token into the database: ",
+        skip(self, database),
+        fields(
+            id = %self.id,
+            user_id = %self.user_id,
+            purpose = %self.purpose,
+        )
+    )]
+    pub async fn insert(
+        &self,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            ActionTokens,
+            r#"
+                INSERT INTO action_tokens (id, user_id, purpose, token_hash, payload, expires_on, created_on, used_on)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                RETURNING id, user_id, purpose as "purpose:domain::ActionPurpose", token_hash, payload, expires_on, created_on, used_on
+            "#,
+            self.id,
+            self.user_id,
+            self.purpose as domain::ActionPurpose,
+            self.token_hash,
+            self.payload,
+            self.expires_on,
+            self.created_on,
+            self.used_on,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("Action token inserted: {}", database_record.id);
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::{database, domain};
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn insert_action_token(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let (action_token, _) = database::ActionTokens::mock_data(
+            &user.id,
+            domain::ActionPurpose::AccountDeletion,
+        );
+        let action_token = action_token.with_payload("payload");
+
+        //-- Execute Function (Act)
+        let database_record = action_token.insert(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(database_record, action_token);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,22 @@
+//-- ./src/database/action_tokens/mod.rs
+
+//! Action tokens database module for the authentication service.
+//!
+//! One-time tokens emailed to a user to confirm an action, such as deleting
+//! their account or logging in with a magic link. Only the token hash is
+//! stored, and consuming a token marks it used so it can't be used again.
+//!
+//! # Contents
+//! - Action token struct definition and model-level helpers
+//! - Action token insertion logic
+//! - Action token consume logic
+//! - Action token delete logic
+
+// #![allow(unused)] // For development only
+
+pub use model::ActionTokens;
+
+mod delete;
+mod insert;
+mod model;
+mod update;
@@ -0,0 +1,150 @@
+//-- ./src/database/action_tokens/model.rs
+
+// #![allow(unused)] // For development only
+
+//! The action tokens database model.
+//!
+//! # Contents
+//! - `ActionTokens` struct definition
+//! - Constructor for new action tokens
+//! - Mock data generation for tests
+
+use chrono::{DateTime, SubsecRound, Utc};
+use uuid::Uuid;
+
+use crate::domain;
+use crate::utils::{Clock, SystemClock};
+
+#[derive(Debug, sqlx::FromRow, Clone, PartialEq)]
+pub struct ActionTokens {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub purpose: domain::ActionPurpose,
+    pub token_hash: String,
+    pub payload: Option<String>,
+    pub expires_on: DateTime<Utc>,
+    pub created_on: DateTime<Utc>,
+    pub used_on: Option<DateTime<Utc>>,
+}
+
+impl ActionTokens {
+    /// # New Database Action Token Instance
+    ///
+    /// Creates a new unused action token. Only the token hash is kept.
+    ///
+    /// ## Parameters
+    ///
+    /// - `user_id: &Uuid` - The user the token is emailed to
+    /// - `token: &domain::ActionToken` - The token, its purpose is stored with the hash
+    /// - `duration: &std::time::Duration` - How long the token can be used for
+    pub fn new(
+        user_id: &Uuid,
+        token: &domain::ActionToken,
+        duration: &std::time::Duration,
+    ) -> Self {
+        Self::new_with_clock(user_id, token, duration, &SystemClock)
+    }
+
+    /// The same as `ActionTokens::new`, issued at the time of `clock` instead
+    /// of the system time
+    pub fn new_with_clock(
+        user_id: &Uuid,
+        token: &domain::ActionToken,
+        duration: &std::time::Duration,
+        clock: &dyn Clock,
+    ) -> Self {
+        let now = clock.now().round_subsecs(0);
+
+        Self {
+            id: Uuid::now_v7(),
+            user_id: user_id.to_owned(),
+            purpose: token.purpose(),
+            token_hash: token.hash(),
+            payload: None,
+            expires_on: now + *duration,
+            created_on: now,
+            used_on: None,
+        }
+    }
+
+    /// Store flow specific data with the token, returned when it is consumed
+    pub fn with_payload(mut self, payload: impl Into<String>) -> Self {
+        self.payload = Some(payload.into());
+        self
+    }
+
+    /// Whether the token can no longer be used
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(&SystemClock)
+    }
+
+    /// Whether the token can no longer be used at the time of `clock`
+    pub fn is_expired_at(&self, clock: &dyn Clock) -> bool {
+        self.expires_on <= clock.now()
+    }
+
+    /// Whether the token has been used
+    pub fn is_used(&self) -> bool {
+        self.used_on.is_some()
+    }
+
+    #[cfg(test)]
+    /// # Mock Action Token Data
+    ///
+    /// This function is only available in test mode `#[cfg(test)]`.
+    /// Creates a new unused action token for the user, returning the token
+    /// alongside it.
+    pub fn mock_data(
+        user_id: &Uuid,
+        purpose: domain::ActionPurpose,
+    ) -> (Self, domain::ActionToken) {
+        let token = domain::ActionToken::generate(
+            purpose,
+            &secrecy::SecretString::from("action-token-secret"),
+        );
+        let action_token =
+            Self::new(user_id, &token, &std::time::Duration::from_secs(15 * 60));
+
+        (action_token, token)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn new_action_tokens_store_the_token_hash_and_purpose() {
+        let (action_token, token) = ActionTokens::mock_data(
+            &Uuid::now_v7(),
+            domain::ActionPurpose::MagicLink,
+        );
+
+        assert_eq!(action_token.token_hash, token.hash());
+        assert_eq!(action_token.purpose, domain::ActionPurpose::MagicLink);
+        assert!(!action_token.is_expired());
+        assert!(!action_token.is_used());
+    }
+
+    #[test]
+    fn action_tokens_expire_after_the_duration() {
+        let clock = crate::utils::MockClock::default();
+        let token = domain::ActionToken::generate(
+            domain::ActionPurpose::AccountDeletion,
+            &secrecy::SecretString::from("action-token-secret"),
+        );
+        let action_token = ActionTokens::new_with_clock(
+            &Uuid::now_v7(),
+            &token,
+            &std::time::Duration::from_secs(15 * 60),
+            &clock,
+        );
+
+        clock.advance(chrono::Duration::minutes(14));
+        assert!(!action_token.is_expired_at(&clock));
+
+        clock.advance(chrono::Duration::minutes(1));
+        assert!(action_token.is_expired_at(&clock));
+    }
+}
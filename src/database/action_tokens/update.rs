@@ -0,0 +1,122 @@
+//-- ./src/database/action_tokens/update.rs
+
+// #![allow(unused)] // For development only
+
+//! Action token consume logic for the authentication service.
+//!
+//! # Contents
+//! - Consume an unused, unexpired action token
+//! - Unit tests for update scenarios
+
+use sqlx::PgExecutor;
+
+use crate::database::ActionTokens;
+use crate::domain;
+use crate::prelude::*;
+
+impl ActionTokens {
+    /// Use the action token with this hash, marking it used in the same
+    /// statement so it can only be used once, even by concurrent requests.
+    ///
+    /// # Parameters
+    /// * `token` - The parsed token, its hash and purpose must match the row.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Some(ActionTokens))` - The used token, with its payload.
+    /// * `Ok(None)` - If the token is unknown, already used or expired.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Consume an action token in the database: ",
+        skip(token, database),
+        fields(purpose = %token.purpose())
+    )]
+    pub async fn consume(
+        token: &domain::ActionToken,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Option<Self>, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            ActionTokens,
+            r#"
+                UPDATE action_tokens
+                SET used_on = NOW()
+                WHERE token_hash = $1
+                    AND purpose = $2
+                    AND used_on IS NULL
+                    AND expires_on > NOW()
+                RETURNING id, user_id, purpose as "purpose:domain::ActionPurpose", token_hash, payload, expires_on, created_on, used_on
+            "#,
+            token.hash(),
+            token.purpose() as domain::ActionPurpose,
+        )
+        .fetch_optional(database)
+        .await?;
+
+        match &database_record {
+            Some(record) => tracing::debug!("Action token consumed: {}", record.id),
+            None => tracing::debug!("Action token is unknown, used or expired"),
+        }
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use chrono::{Duration, Utc};
+    use sqlx::{Pool, Postgres};
+
+    use crate::{database, domain};
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn action_tokens_are_single_use(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let (action_token, token) = database::ActionTokens::mock_data(
+            &user.id,
+            domain::ActionPurpose::MagicLink,
+        );
+        action_token.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let first = database::ActionTokens::consume(&token, &database).await?;
+        let second = database::ActionTokens::consume(&token, &database).await?;
+
+        //-- Checks (Assertions)
+        let first = first.expect("first use consumes the token");
+        assert_eq!(first.id, action_token.id);
+        assert!(first.is_used());
+        assert!(second.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn expired_action_tokens_are_not_consumed(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let (mut action_token, token) = database::ActionTokens::mock_data(
+            &user.id,
+            domain::ActionPurpose::AccountDeletion,
+        );
+        action_token.expires_on = Utc::now() - Duration::minutes(1);
+        action_token.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let consumed = database::ActionTokens::consume(&token, &database).await?;
+
+        //-- Checks (Assertions)
+        assert!(consumed.is_none());
+
+        Ok(())
+    }
+}
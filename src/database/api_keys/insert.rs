@@ -0,0 +1,103 @@
+//-- ./src/database/api_keys/insert.rs
+
+// #![allow(unused)] // For development only
+
+//! API key insert logic for the authentication service.
+//!
+//! # Contents
+//! - Insert an API key
+//! - Unit tests for insert scenarios
+
+use sqlx::{Pool, Postgres};
+
+use crate::database::ApiKeys;
+use crate::domain;
+use crate::prelude::*;
+
+impl ApiKeys {
+    /// Insert this API key into the database.
+    ///
+    /// # Parameters
+    /// * `self` - The `ApiKeys` instance to insert.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(ApiKeys)` - The inserted record as returned from the database.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Insert an API key into the database: ",
+        skip(database),
+        fields(
+            id = %self.id,
+            key_prefix = %self.key_prefix,
+        )
+    )]
+    pub async fn insert(&self, database: &Pool<Postgres>) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            ApiKeys,
+            r#"
+                INSERT INTO api_keys (id, name, key_prefix, key_hash, role, created_on, expires_on, revoked_on)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                RETURNING id, name, key_prefix, key_hash, role as "role:domain::UserRole", created_on, expires_on, revoked_on
+            "#,
+            self.id,
+            self.name,
+            self.key_prefix,
+            self.key_hash,
+            self.role.clone() as domain::UserRole,
+            self.created_on,
+            self.expires_on,
+            self.revoked_on,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("API key inserted: {}", database_record.id);
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn create_api_key(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let (api_key, _secret) = database::ApiKeys::mock_data();
+
+        //-- Execute Function (Act)
+        let database_record = api_key.insert(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(database_record, api_key);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn duplicate_key_hash_is_rejected(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let (api_key, _secret) = database::ApiKeys::mock_data();
+        api_key.insert(&database).await?;
+
+        let mut duplicate = database::ApiKeys::mock_data().0;
+        duplicate.key_hash = api_key.key_hash.clone();
+
+        //-- Execute Function (Act)
+        let result = duplicate.insert(&database).await;
+
+        //-- Checks (Assertions)
+        assert!(result.is_err());
+
+        Ok(())
+    }
+}
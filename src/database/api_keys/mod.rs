@@ -0,0 +1,21 @@
+//-- ./src/database/api_keys/mod.rs
+
+//! API keys database module for the authentication service.
+//!
+//! API keys authenticate service accounts (machine to machine requests). Each
+//! key is scoped to a user role and can expire or be revoked.
+//!
+//! # Contents
+//! - API key insertion logic
+//! - API key struct definition and model-level helpers
+//! - API key read/query logic
+//! - API key revoke logic
+
+// #![allow(unused)] // For development only
+
+pub use model::ApiKeys;
+
+mod insert;
+mod model;
+mod read;
+mod update;
@@ -0,0 +1,83 @@
+//-- ./src/database/api_keys/model.rs
+
+// #![allow(unused)] // For development only
+
+//! The API keys database model.
+//!
+//! # Contents
+//! - `ApiKeys` struct definition
+//! - Constructor for new API key instances
+//! - Mock data generation for tests
+
+use chrono::{DateTime, SubsecRound, Utc};
+use uuid::Uuid;
+
+use crate::domain;
+use crate::utils::{Clock, SystemClock};
+
+#[derive(Debug, serde::Deserialize, sqlx::FromRow, Clone, PartialEq)]
+pub struct ApiKeys {
+    pub id: Uuid,
+    pub name: String,
+    pub key_prefix: String,
+    pub key_hash: String,
+    pub role: domain::UserRole,
+    pub created_on: DateTime<Utc>,
+    pub expires_on: Option<DateTime<Utc>>,
+    pub revoked_on: Option<DateTime<Utc>>,
+}
+
+impl ApiKeys {
+    /// # New Database API Key Instance
+    ///
+    /// Creates a new instance of the ApiKeys struct. Only the hash and display
+    /// prefix of the key are kept.
+    ///
+    /// ## Parameters
+    ///
+    /// - `name: &str` - Human readable name for the key
+    /// - `role: &domain::UserRole` - The role requests with the key are authorised as
+    /// - `api_key: &domain::ApiKey` - The generated API key
+    /// - `expires_on: Option<DateTime<Utc>>` - When the key expires, `None` for never
+    pub fn new(
+        name: &str,
+        role: &domain::UserRole,
+        api_key: &domain::ApiKey,
+        expires_on: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            name: name.to_string(),
+            key_prefix: api_key.display_prefix(),
+            key_hash: api_key.hash(),
+            role: role.to_owned(),
+            created_on: Utc::now().round_subsecs(0),
+            expires_on: expires_on.map(|expires_on| expires_on.round_subsecs(0)),
+            revoked_on: None,
+        }
+    }
+
+    /// Can the key be used to authenticate, it is not revoked or expired
+    pub fn is_usable(&self) -> bool {
+        self.is_usable_at(&SystemClock)
+    }
+
+    /// Can the key be used to authenticate at the time of `clock`
+    pub fn is_usable_at(&self, clock: &dyn Clock) -> bool {
+        self.revoked_on.is_none()
+            && self
+                .expires_on
+                .is_none_or(|expires_on| expires_on > clock.now())
+    }
+
+    #[cfg(test)]
+    /// # Mock API Key Data
+    ///
+    /// This function is only available in test mode `#[cfg(test)]`.
+    /// Creates a new API key instance with a random role, returning the key too.
+    pub fn mock_data() -> (Self, domain::ApiKey) {
+        let api_key = domain::ApiKey::generate();
+        let record = Self::new("mock service", &domain::UserRole::mock_data(), &api_key, None);
+        (record, api_key)
+    }
+}
@@ -0,0 +1,152 @@
+//-- ./src/database/api_keys/read.rs
+
+// #![allow(unused)] // For development only
+
+//! API key read logic for the authentication service.
+//!
+//! # Contents
+//! - Get an API key by id
+//! - Index API keys with pagination
+//! - Index the API keys that can still be used
+//! - Unit tests for read scenarios
+
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::database::ApiKeys;
+use crate::database::timeouts::{with_timeout, QueryBudget};
+use crate::domain;
+use crate::prelude::*;
+
+impl ApiKeys {
+    /// Retrieve an API key by its id.
+    ///
+    /// # Parameters
+    /// * `id` - The API key id.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(ApiKeys)` - The API key record.
+    /// * `Err(AuthenticationError)` - If the query fails or no key has the id.
+    #[tracing::instrument(name = "Get an API key from the database: ", skip(database))]
+    pub async fn from_id(id: &Uuid, database: &Pool<Postgres>) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            ApiKeys,
+            r#"
+                SELECT id, name, key_prefix, key_hash, role as "role:domain::UserRole", created_on, expires_on, revoked_on
+                FROM api_keys
+                WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_one(database)
+        .await?;
+
+        Ok(database_record)
+    }
+
+    /// Retrieve a page of API keys, newest first, including revoked and expired keys.
+    ///
+    /// # Parameters
+    /// * `limit` - The maximum number of keys to return.
+    /// * `offset` - The number of keys to skip.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<ApiKeys>)` - The page of API keys.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Index API keys in the database: ", skip(database))]
+    pub async fn index(
+        limit: &usize,
+        offset: &usize,
+        database: &Pool<Postgres>,
+    ) -> Result<Vec<Self>, AuthenticationError> {
+        let database_records = with_timeout(
+            QueryBudget::Interactive,
+            "api_keys.index",
+            sqlx::query_as!(
+                ApiKeys,
+                r#"
+                    SELECT id, name, key_prefix, key_hash, role as "role:domain::UserRole", created_on, expires_on, revoked_on
+                    FROM api_keys
+                    ORDER BY created_on DESC, id DESC
+                    LIMIT $1 OFFSET $2
+                "#,
+                *limit as i64,
+                *offset as i64,
+            )
+            .fetch_all(database),
+        )
+        .await?;
+
+        tracing::debug!("API keys retrieved: {}", database_records.len());
+
+        Ok(database_records)
+    }
+
+    /// Retrieve every API key that is not revoked or expired.
+    ///
+    /// Used to load the in memory API key store at startup.
+    ///
+    /// # Parameters
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<ApiKeys>)` - The usable API keys.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Index usable API keys in the database: ", skip(database))]
+    pub async fn index_usable(database: &Pool<Postgres>) -> Result<Vec<Self>, AuthenticationError> {
+        let database_records = sqlx::query_as!(
+            ApiKeys,
+            r#"
+                SELECT id, name, key_prefix, key_hash, role as "role:domain::UserRole", created_on, expires_on, revoked_on
+                FROM api_keys
+                WHERE revoked_on IS NULL AND (expires_on IS NULL OR expires_on > NOW())
+            "#,
+        )
+        .fetch_all(database)
+        .await?;
+
+        tracing::debug!("Usable API keys retrieved: {}", database_records.len());
+
+        Ok(database_records)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use chrono::{Duration, Utc};
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn index_usable_skips_revoked_and_expired(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let usable = database::ApiKeys::mock_data().0.insert(&database).await?;
+
+        let mut expired = database::ApiKeys::mock_data().0;
+        expired.expires_on = Some(Utc::now() - Duration::days(1));
+        expired.insert(&database).await?;
+
+        let mut revoked = database::ApiKeys::mock_data().0;
+        revoked.revoked_on = Some(Utc::now());
+        revoked.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let usable_keys = database::ApiKeys::index_usable(&database).await?;
+        let all_keys = database::ApiKeys::index(&10, &0, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(usable_keys, vec![usable.clone()]);
+        assert_eq!(all_keys.len(), 3);
+        assert_eq!(database::ApiKeys::from_id(&usable.id, &database).await?, usable);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+//-- ./src/database/api_keys/update.rs
+
+// #![allow(unused)] // For development only
+
+//! API key revoke logic for the authentication service.
+//!
+//! # Contents
+//! - Revoke an API key by id
+//! - Unit tests for revoke scenarios
+
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::database::ApiKeys;
+use crate::prelude::*;
+
+impl ApiKeys {
+    /// Revoke an API key by its id.
+    ///
+    /// Revoking a key that is already revoked leaves its `revoked_on` unchanged.
+    ///
+    /// # Parameters
+    /// * `id` - The API key id.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - The number of keys revoked (0 if the key does not exist or is already revoked).
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Revoke an API key in the database: ", skip(database))]
+    pub async fn revoke_by_id(id: &Uuid, database: &Pool<Postgres>) -> Result<usize, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                UPDATE api_keys
+                SET revoked_on = NOW()
+                WHERE id = $1 AND revoked_on IS NULL
+            "#,
+            id
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("API keys revoked: {rows_affected}");
+
+        Ok(rows_affected as usize)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn revoke_by_id_only_revokes_once(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let api_key = database::ApiKeys::mock_data().0.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let first = database::ApiKeys::revoke_by_id(&api_key.id, &database).await?;
+        let second = database::ApiKeys::revoke_by_id(&api_key.id, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(first, 1);
+        assert_eq!(second, 0);
+        let revoked = database::ApiKeys::from_id(&api_key.id, &database).await?;
+        assert!(revoked.revoked_on.is_some());
+        assert!(!revoked.is_usable());
+
+        Ok(())
+    }
+}
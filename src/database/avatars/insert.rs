@@ -0,0 +1,91 @@
+//-- ./src/database/avatars/insert.rs
+
+// #![allow(unused)] // For development only
+
+//! Avatars upsert logic for the authentication service.
+//!
+//! # Contents
+//! - Insert or replace a user's avatar
+//! - Unit tests for upsert scenarios
+
+use sqlx::{Pool, Postgres};
+
+use crate::database::Avatars;
+use crate::prelude::*;
+
+impl Avatars {
+    /// Save this avatar, replacing the user's current avatar.
+    ///
+    /// # Parameters
+    /// * `self` - The `Avatars` instance to save.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Avatars)` - The saved record as returned from the database.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Upsert avatar into the database: ",
+        skip(database),
+        fields(user_id = %self.user_id)
+    )]
+    pub async fn upsert(&self, database: &Pool<Postgres>) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            Avatars,
+            r#"
+                INSERT INTO avatars (user_id, storage_key, content_type, size_bytes, updated_on)
+                VALUES ($1, $2, $3, $4, NOW())
+                ON CONFLICT (user_id) DO UPDATE
+                SET storage_key = EXCLUDED.storage_key,
+                    content_type = EXCLUDED.content_type,
+                    size_bytes = EXCLUDED.size_bytes,
+                    updated_on = EXCLUDED.updated_on
+                RETURNING user_id, storage_key, content_type, size_bytes, updated_on
+            "#,
+            self.user_id,
+            self.storage_key,
+            self.content_type,
+            self.size_bytes,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("Avatar saved: {}", database_record.storage_key);
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn saving_an_avatar_replaces_it(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let avatar = database::Avatars::mock_data(&user.id);
+        let replacement = database::Avatars::mock_data(&user.id);
+
+        //-- Execute Function (Act)
+        let first = avatar.upsert(&database).await?;
+        let second = replacement.upsert(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(first.storage_key, avatar.storage_key);
+        assert_eq!(second.storage_key, replacement.storage_key);
+        assert_eq!(
+            database::Avatars::for_user(&user.id, &database).await?,
+            Some(second)
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,19 @@
+//-- ./src/database/avatars/mod.rs
+
+//! Avatars database module for the authentication service.
+//!
+//! The avatar image each user uploaded, and its key in the storage backend
+//! (see `avatars`). The image bytes are not kept in the database.
+//!
+//! # Contents
+//! - Avatars struct definition
+//! - Avatars upsert logic
+//! - Avatars read logic
+
+// #![allow(unused)] // For development only
+
+pub use model::Avatars;
+
+mod insert;
+mod model;
+mod read;
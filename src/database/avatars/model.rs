@@ -0,0 +1,81 @@
+//-- ./src/database/avatars/model.rs
+
+// #![allow(unused)] // For development only
+
+//! The avatars database model.
+//!
+//! # Contents
+//! - `Avatars` struct definition
+//! - Constructor with the storage key for an image
+//! - Mock data generation for tests
+
+use chrono::{DateTime, SubsecRound, Utc};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+#[derive(Debug, sqlx::FromRow, Clone, PartialEq)]
+pub struct Avatars {
+    pub user_id: Uuid,
+    pub storage_key: String,
+    pub content_type: String,
+    pub size_bytes: i32,
+    pub updated_on: DateTime<Utc>,
+}
+
+impl Avatars {
+    /// # New Avatar
+    ///
+    /// Record a user's avatar image. The storage key is the user id and the
+    /// SHA-256 of the image, so a new image gets a new key.
+    ///
+    /// ## Parameters
+    ///
+    /// - `user_id: &Uuid` - The user the avatar is for
+    /// - `image: &[u8]` - The stored image
+    /// - `content_type: &str` - The image's media type, e.g. `image/png`
+    pub fn new(user_id: &Uuid, image: &[u8], content_type: &str) -> Self {
+        Self {
+            user_id: user_id.to_owned(),
+            storage_key: format!("{user_id}/{:x}", Sha256::digest(image)),
+            content_type: content_type.to_string(),
+            size_bytes: i32::try_from(image.len()).unwrap_or(i32::MAX),
+            updated_on: Utc::now().round_subsecs(0),
+        }
+    }
+
+    #[cfg(test)]
+    /// # Mock Avatar Data
+    ///
+    /// This function is only available in test mode `#[cfg(test)]`.
+    /// Creates an avatar for some random bytes.
+    pub fn mock_data(user_id: &Uuid) -> Self {
+        let image: [u8; 32] = rand::random();
+
+        Self::new(user_id, &image, "image/png")
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn storage_keys_change_with_the_image() {
+        let user_id = Uuid::now_v7();
+
+        let avatar = Avatars::new(&user_id, b"first", "image/png");
+
+        assert!(avatar.storage_key.starts_with(&format!("{user_id}/")));
+        assert_eq!(avatar.storage_key.len(), 36 + 1 + 64);
+        assert_eq!(avatar.size_bytes, 5);
+        assert_eq!(
+            avatar.storage_key,
+            Avatars::new(&user_id, b"first", "image/png").storage_key
+        );
+        assert_ne!(
+            avatar.storage_key,
+            Avatars::new(&user_id, b"second", "image/png").storage_key
+        );
+    }
+}
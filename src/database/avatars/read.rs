@@ -0,0 +1,81 @@
+//-- ./src/database/avatars/read.rs
+
+// #![allow(unused)] // For development only
+
+//! Avatars read logic for the authentication service.
+//!
+//! # Contents
+//! - Get a user's avatar
+//! - Unit tests for read scenarios
+
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::database::Avatars;
+use crate::prelude::*;
+
+impl Avatars {
+    /// Retrieve a user's avatar, if they uploaded one.
+    ///
+    /// # Parameters
+    /// * `user_id` - The user the avatar is for.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Some(Avatars))` - The user's avatar.
+    /// * `Ok(None)` - If the user has no avatar.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Get avatar from the database: ", skip(database))]
+    pub async fn for_user(
+        user_id: &Uuid,
+        database: &Pool<Postgres>,
+    ) -> Result<Option<Self>, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            Avatars,
+            r#"
+                SELECT user_id, storage_key, content_type, size_bytes, updated_on
+                FROM avatars
+                WHERE user_id = $1
+            "#,
+            user_id,
+        )
+        .fetch_optional(database)
+        .await?;
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn users_without_an_avatar_have_none(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let other_user = database::Users::mock_data()?;
+        other_user.insert(&database).await?;
+        let saved = database::Avatars::mock_data(&other_user.id)
+            .upsert(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let missing = database::Avatars::for_user(&user.id, &database).await?;
+        let found = database::Avatars::for_user(&other_user.id, &database).await?;
+
+        //-- Checks (Assertions)
+        assert!(missing.is_none());
+        assert_eq!(found, Some(saved));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,287 @@
+//-- ./src/database/circuit_breaker.rs
+
+// #![allow(unused)] // For development only
+
+//! # Database Circuit Breaker
+//!
+//! While Postgres is unreachable every request waits for a connection attempt
+//! to time out. The circuit breaker fails them fast with `Unavailable` instead:
+//! 1. **Closed**: requests are served, connection failures are counted and a
+//!    connection handed out by the pool resets the count
+//! 2. **Open**: after `failure_threshold` consecutive failures, requests are
+//!    answered with `Unavailable` for `open_seconds`
+//! 3. **Half open**: one probe request is let through, closing the circuit
+//!    when it gets a connection and opening it again when it fails. Another
+//!    probe is let through every `open_seconds` until one reaches the database
+//!
+//! Failures are recorded when an `AuthenticationError::Sqlx` connection error
+//! is returned to a client, and successes by the pool hooks set in
+//! `init_pool`. `middleware::DatabaseCircuitLayer` checks the circuit before
+//! each request. The state is reported by the `database` health service and
+//! the `db.circuit_breaker.state` gauge.
+//! ---
+
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use opentelemetry::metrics::Counter;
+use strum::Display;
+use tokio::sync::watch;
+
+use crate::configuration::CircuitBreakerConfiguration;
+use crate::prelude::*;
+
+/// The circuit breaker shared by the pool hooks, errors and middleware
+static CIRCUIT_BREAKER: OnceLock<CircuitBreaker> = OnceLock::new();
+
+/// The state of the circuit, as reported by health and metrics
+#[derive(Debug, Clone, Copy, PartialEq, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl CircuitState {
+    /// The value of the `db.circuit_breaker.state` gauge
+    fn gauge(&self) -> u64 {
+        match self {
+            CircuitState::Closed => 0,
+            CircuitState::HalfOpen => 1,
+            CircuitState::Open => 2,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum State {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    /// A probe was let through, another is let through after `until`
+    HalfOpen { until: Instant },
+}
+
+/// Fails database requests fast while the database is unreachable
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    enabled: bool,
+    failure_threshold: u32,
+    open_duration: Duration,
+    state: Mutex<State>,
+    reported: watch::Sender<CircuitState>,
+    rejected: Counter<u64>,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: &CircuitBreakerConfiguration) -> Self {
+        let meter = opentelemetry::global::meter("authentication_service");
+
+        Self {
+            enabled: config.enabled,
+            failure_threshold: config.failure_threshold,
+            open_duration: config.open_duration(),
+            state: Mutex::new(State::Closed { failures: 0 }),
+            reported: watch::Sender::new(CircuitState::Closed),
+            rejected: meter
+                .u64_counter("db.circuit_breaker.rejected")
+                .with_description("Requests rejected while the database circuit was open")
+                .build(),
+        }
+    }
+
+    /// Can a request use the database, `DatabaseUnavailable` while the
+    /// circuit is open
+    pub fn allow(&self) -> Result<(), AuthenticationError> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        match *state {
+            State::Closed { .. } => Ok(()),
+            State::Open { until } | State::HalfOpen { until } if now >= until => {
+                tracing::info!("Database circuit is half open, letting a probe request through");
+                *state = State::HalfOpen {
+                    until: now + self.open_duration,
+                };
+                self.report(CircuitState::HalfOpen);
+                Ok(())
+            }
+            State::Open { .. } | State::HalfOpen { .. } => {
+                self.rejected.add(1, &[]);
+                Err(AuthenticationError::DatabaseUnavailable)
+            }
+        }
+    }
+
+    /// The database handed out a connection, closing the circuit
+    pub fn record_success(&self) {
+        if !self.enabled {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if !matches!(*state, State::Closed { failures: 0 }) {
+            if !matches!(*state, State::Closed { .. }) {
+                tracing::info!("Database circuit is closed, the database is reachable");
+            }
+            *state = State::Closed { failures: 0 };
+            self.report(CircuitState::Closed);
+        }
+    }
+
+    /// A request could not reach the database, opening the circuit after
+    /// `failure_threshold` in a row or when the probe fails
+    pub fn record_failure(&self) {
+        if !self.enabled {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let failures = match *state {
+            State::Closed { failures } => failures + 1,
+            State::HalfOpen { .. } => self.failure_threshold,
+            State::Open { .. } => return,
+        };
+
+        if failures < self.failure_threshold {
+            *state = State::Closed { failures };
+            return;
+        }
+
+        tracing::warn!(
+            "Database circuit is open after {failures} connection failures, failing requests for {:?}",
+            self.open_duration
+        );
+        *state = State::Open {
+            until: Instant::now() + self.open_duration,
+        };
+        self.report(CircuitState::Open);
+    }
+
+    /// The current state of the circuit
+    pub fn state(&self) -> CircuitState {
+        *self.reported.borrow()
+    }
+
+    /// Watch the state of the circuit change
+    pub fn subscribe(&self) -> watch::Receiver<CircuitState> {
+        self.reported.subscribe()
+    }
+
+    fn report(&self, state: CircuitState) {
+        self.reported.send_if_modified(|reported| {
+            let modified = *reported != state;
+            *reported = state;
+            modified
+        });
+    }
+}
+
+/// Use the configuration for the shared circuit breaker, if it hasn't been
+/// used yet, and report its state as the `db.circuit_breaker.state` gauge
+pub fn install(config: &CircuitBreakerConfiguration) -> &'static CircuitBreaker {
+    let mut installed = false;
+    let breaker = CIRCUIT_BREAKER.get_or_init(|| {
+        installed = true;
+        CircuitBreaker::new(config)
+    });
+
+    if installed {
+        opentelemetry::global::meter("authentication_service")
+            .u64_observable_gauge("db.circuit_breaker.state")
+            .with_description("Database circuit state, 0 closed, 1 half open and 2 open")
+            .with_callback(|observer| observer.observe(breaker.state().gauge(), &[]))
+            .build();
+    }
+
+    breaker
+}
+
+/// The shared circuit breaker, with the default configuration until
+/// `install`ed
+pub fn circuit_breaker() -> &'static CircuitBreaker {
+    CIRCUIT_BREAKER.get_or_init(|| CircuitBreaker::new(&CircuitBreakerConfiguration::default()))
+}
+
+/// Is the error a failure to reach the database, rather than of a query
+pub fn is_connection_error(error: &sqlx::Error) -> bool {
+    matches!(
+        error,
+        sqlx::Error::Io(_)
+            | sqlx::Error::Tls(_)
+            | sqlx::Error::PoolTimedOut
+            | sqlx::Error::PoolClosed
+            | sqlx::Error::WorkerCrashed
+    )
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker(open_seconds: u64) -> CircuitBreaker {
+        CircuitBreaker::new(&CircuitBreakerConfiguration {
+            enabled: true,
+            failure_threshold: 3,
+            open_seconds,
+        })
+    }
+
+    #[test]
+    fn the_circuit_opens_after_consecutive_failures() {
+        //-- Setup and Fixtures (Arrange)
+        let breaker = breaker(60);
+
+        //-- Execute Function (Act)
+        breaker.record_failure();
+        breaker.record_failure();
+        breaker.record_success();
+        breaker.record_failure();
+        breaker.record_failure();
+        let closed = breaker.state();
+        breaker.record_failure();
+
+        //-- Checks (Assertions)
+        assert_eq!(closed, CircuitState::Closed);
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(matches!(
+            breaker.allow(),
+            Err(AuthenticationError::DatabaseUnavailable)
+        ));
+    }
+
+    #[test]
+    fn one_probe_is_let_through_when_half_open() {
+        //-- Setup and Fixtures (Arrange)
+        let breaker = breaker(0);
+        for _ in 0..3 {
+            breaker.record_failure();
+        }
+
+        //-- Execute Function (Act)
+        let probe = breaker.allow();
+        let half_open = breaker.state();
+        breaker.record_failure();
+        let reopened = breaker.state();
+        let second_probe = breaker.allow();
+        breaker.record_success();
+
+        //-- Checks (Assertions)
+        assert!(probe.is_ok());
+        assert_eq!(half_open, CircuitState::HalfOpen);
+        assert_eq!(reopened, CircuitState::Open);
+        assert!(second_probe.is_ok());
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.allow().is_ok());
+    }
+
+    #[test]
+    fn only_connection_errors_are_failures() {
+        assert!(is_connection_error(&sqlx::Error::PoolTimedOut));
+        assert!(!is_connection_error(&sqlx::Error::RowNotFound));
+    }
+}
@@ -0,0 +1,108 @@
+//-- ./src/database/clients/insert.rs
+
+// #![allow(unused)] // For development only
+
+//! OAuth client insert logic for the authentication service.
+//!
+//! # Contents
+//! - Insert an OAuth client
+//! - Unit tests for insert scenarios
+
+use sqlx::{Pool, Postgres};
+
+use crate::database::{ClientType, Clients};
+use crate::prelude::*;
+
+impl Clients {
+    /// Insert this OAuth client into the database.
+    ///
+    /// # Parameters
+    /// * `self` - The `Clients` instance to insert.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Clients)` - The inserted record as returned from the database.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Insert an OAuth client into the database: ",
+        skip(database),
+        fields(
+            id = %self.id,
+            client_type = %self.client_type,
+        )
+    )]
+    pub async fn insert(&self, database: &Pool<Postgres>) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            Clients,
+            r#"
+                INSERT INTO clients (id, name, client_type, secret_prefix, secret_hash, redirect_uris, grant_types, scopes, created_on, revoked_on)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                RETURNING id, name, client_type as "client_type:ClientType", secret_prefix, secret_hash, redirect_uris, grant_types, scopes, created_on, revoked_on
+            "#,
+            self.id,
+            self.name,
+            self.client_type as ClientType,
+            self.secret_prefix,
+            self.secret_hash,
+            &self.redirect_uris,
+            &self.grant_types,
+            &self.scopes,
+            self.created_on,
+            self.revoked_on,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("OAuth client inserted: {}", database_record.id);
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::{database, domain};
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn create_confidential_client(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let (client, _secret) = database::Clients::mock_data();
+
+        //-- Execute Function (Act)
+        let database_record = client.insert(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(database_record, client);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn create_public_client_without_a_secret(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let client = database::Clients::new_public(
+            "mock app",
+            &["https://app.example.com/callback".to_string()],
+            &[domain::GrantType::AuthorizationCode, domain::GrantType::RefreshToken],
+            &["profile".to_string()],
+        );
+
+        //-- Execute Function (Act)
+        let database_record = client.insert(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(database_record, client);
+        assert_eq!(database_record.secret_hash, None);
+        assert!(database_record.allows_grant(domain::GrantType::AuthorizationCode));
+        assert!(!database_record.allows_grant(domain::GrantType::ClientCredentials));
+
+        Ok(())
+    }
+}
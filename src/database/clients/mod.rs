@@ -0,0 +1,23 @@
+//-- ./src/database/clients/mod.rs
+
+//! OAuth clients database module for the authentication service.
+//!
+//! Registered OAuth clients, either confidential (backend services that keep
+//! a secret) or public (single page and mobile apps, which have no secret).
+//! Each client is allowed a list of grant types, redirect URIs and scopes,
+//! and can be revoked.
+//!
+//! # Contents
+//! - OAuth client insertion logic
+//! - OAuth client struct definition and model-level helpers
+//! - OAuth client read/query logic
+//! - OAuth client update and revoke logic
+
+// #![allow(unused)] // For development only
+
+pub use model::{ClientType, Clients};
+
+mod insert;
+mod model;
+mod read;
+mod update;
@@ -0,0 +1,149 @@
+//-- ./src/database/clients/model.rs
+
+// #![allow(unused)] // For development only
+
+//! The OAuth clients database model.
+//!
+//! # Contents
+//! - `ClientType` enum definition
+//! - `Clients` struct definition
+//! - Constructors for new confidential and public clients
+//! - Mock data generation for tests
+
+use chrono::{DateTime, SubsecRound, Utc};
+use uuid::Uuid;
+
+use crate::domain;
+
+/// Whether a client can keep a secret
+#[derive(Debug, Clone, Copy, PartialEq, sqlx::Type, serde::Deserialize)]
+#[sqlx(type_name = "client_type", rename_all = "lowercase")]
+pub enum ClientType {
+    /// A backend that keeps its secret, e.g. a web server or service
+    Confidential,
+    /// A client that can't keep a secret, e.g. a single page or mobile app
+    Public,
+}
+
+impl ClientType {
+    /// Convert ClientType to a string reference
+    pub fn to_str(&self) -> &str {
+        match self {
+            ClientType::Confidential => "confidential",
+            ClientType::Public => "public",
+        }
+    }
+}
+
+impl std::fmt::Display for ClientType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+#[derive(Debug, sqlx::FromRow, Clone, PartialEq)]
+pub struct Clients {
+    pub id: Uuid,
+    pub name: String,
+    pub client_type: ClientType,
+    pub secret_prefix: Option<String>,
+    pub secret_hash: Option<String>,
+    pub redirect_uris: Vec<String>,
+    pub grant_types: Vec<String>,
+    pub scopes: Vec<String>,
+    pub created_on: DateTime<Utc>,
+    pub revoked_on: Option<DateTime<Utc>>,
+}
+
+impl Clients {
+    /// # New Database Confidential Client Instance
+    ///
+    /// Creates a new confidential client, its id is the client id. Only the
+    /// hash and display prefix of the secret are kept.
+    ///
+    /// ## Parameters
+    ///
+    /// - `name: &str` - Human readable name for the client
+    /// - `secret: &domain::ClientSecret` - The generated client secret
+    /// - `redirect_uris: &[String]` - The URIs the client may be redirected back to
+    /// - `grant_types: &[domain::GrantType]` - The grants the client may use
+    /// - `scopes: &[String]` - The scopes the client's access tokens may carry
+    pub fn new_confidential(
+        name: &str,
+        secret: &domain::ClientSecret,
+        redirect_uris: &[String],
+        grant_types: &[domain::GrantType],
+        scopes: &[String],
+    ) -> Self {
+        Self {
+            client_type: ClientType::Confidential,
+            secret_prefix: Some(secret.display_prefix()),
+            secret_hash: Some(secret.hash()),
+            ..Self::new_public(name, redirect_uris, grant_types, scopes)
+        }
+    }
+
+    /// # New Database Public Client Instance
+    ///
+    /// Creates a new public client, which has no secret.
+    ///
+    /// ## Parameters
+    ///
+    /// - `name: &str` - Human readable name for the client
+    /// - `redirect_uris: &[String]` - The URIs the client may be redirected back to
+    /// - `grant_types: &[domain::GrantType]` - The grants the client may use
+    /// - `scopes: &[String]` - The scopes the client's access tokens may carry
+    pub fn new_public(
+        name: &str,
+        redirect_uris: &[String],
+        grant_types: &[domain::GrantType],
+        scopes: &[String],
+    ) -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            name: name.to_string(),
+            client_type: ClientType::Public,
+            secret_prefix: None,
+            secret_hash: None,
+            redirect_uris: redirect_uris.to_vec(),
+            grant_types: grant_types.iter().map(|grant| grant.to_string()).collect(),
+            scopes: scopes.to_vec(),
+            created_on: Utc::now().round_subsecs(0),
+            revoked_on: None,
+        }
+    }
+
+    /// Can the client get access tokens, it is not revoked
+    pub fn is_usable(&self) -> bool {
+        self.revoked_on.is_none()
+    }
+
+    /// Is the client allowed to use the grant type
+    pub fn allows_grant(&self, grant_type: domain::GrantType) -> bool {
+        self.grant_types.iter().any(|grant| grant == grant_type.to_str())
+    }
+
+    /// Does the secret belong to the client, public clients have none
+    pub fn secret_matches(&self, secret: &domain::ClientSecret) -> bool {
+        self.secret_hash.as_deref() == Some(secret.hash().as_str())
+    }
+
+    #[cfg(test)]
+    /// # Mock Client Data
+    ///
+    /// This function is only available in test mode `#[cfg(test)]`.
+    /// Creates a new confidential client allowed the client credentials grant,
+    /// returning the secret too.
+    pub fn mock_data() -> (Self, domain::ClientSecret) {
+        let secret = domain::ClientSecret::generate();
+        let record = Self::new_confidential(
+            "mock service",
+            &secret,
+            &[],
+            &[domain::GrantType::ClientCredentials],
+            &["users:read".to_string(), "events:read".to_string()],
+        );
+
+        (record, secret)
+    }
+}
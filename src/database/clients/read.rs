@@ -0,0 +1,128 @@
+//-- ./src/database/clients/read.rs
+
+// #![allow(unused)] // For development only
+
+//! OAuth client read logic for the authentication service.
+//!
+//! # Contents
+//! - Get an OAuth client by id
+//! - Index OAuth clients with pagination
+//! - Unit tests for read scenarios
+
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::database::{ClientType, Clients};
+use crate::database::timeouts::{with_timeout, QueryBudget};
+use crate::prelude::*;
+
+impl Clients {
+    /// Retrieve an OAuth client by its id, the client id.
+    ///
+    /// # Parameters
+    /// * `id` - The OAuth client id.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Clients)` - The OAuth client record.
+    /// * `Err(AuthenticationError)` - If the query fails or no client has the id.
+    #[tracing::instrument(name = "Get an OAuth client from the database: ", skip(database))]
+    pub async fn from_id(id: &Uuid, database: &Pool<Postgres>) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            Clients,
+            r#"
+                SELECT id, name, client_type as "client_type:ClientType", secret_prefix, secret_hash, redirect_uris, grant_types, scopes, created_on, revoked_on
+                FROM clients
+                WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_one(database)
+        .await?;
+
+        Ok(database_record)
+    }
+
+    /// Retrieve a page of OAuth clients, newest first, including revoked clients.
+    ///
+    /// # Parameters
+    /// * `limit` - The maximum number of clients to return.
+    /// * `offset` - The number of clients to skip.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Clients>)` - The page of OAuth clients.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Index OAuth clients in the database: ", skip(database))]
+    pub async fn index(
+        limit: &usize,
+        offset: &usize,
+        database: &Pool<Postgres>,
+    ) -> Result<Vec<Self>, AuthenticationError> {
+        let database_records = with_timeout(
+            QueryBudget::Interactive,
+            "clients.index",
+            sqlx::query_as!(
+                Clients,
+                r#"
+                    SELECT id, name, client_type as "client_type:ClientType", secret_prefix, secret_hash, redirect_uris, grant_types, scopes, created_on, revoked_on
+                    FROM clients
+                    ORDER BY created_on DESC, id DESC
+                    LIMIT $1 OFFSET $2
+                "#,
+                *limit as i64,
+                *offset as i64,
+            )
+            .fetch_all(database),
+        )
+        .await?;
+
+        tracing::debug!("OAuth clients retrieved: {}", database_records.len());
+
+        Ok(database_records)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn get_client_by_id(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let (client, _secret) = database::Clients::mock_data();
+        client.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let database_record = database::Clients::from_id(&client.id, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(database_record, client);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn index_clients_newest_first(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let (first, _) = database::Clients::mock_data();
+        first.insert(&database).await?;
+        let (second, _) = database::Clients::mock_data();
+        second.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let database_records = database::Clients::index(&1, &0, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(database_records, vec![second]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,141 @@
+//-- ./src/database/clients/update.rs
+
+// #![allow(unused)] // For development only
+
+//! OAuth client update and revoke logic for the authentication service.
+//!
+//! # Contents
+//! - Update an OAuth client's registration
+//! - Revoke an OAuth client by id
+//! - Unit tests for update and revoke scenarios
+
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::database::{ClientType, Clients};
+use crate::prelude::*;
+
+impl Clients {
+    /// Update the client's name, redirect URIs, grant types and scopes. The
+    /// client type and secret can't be changed.
+    ///
+    /// # Parameters
+    /// * `self` - The client with its updated registration.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Clients)` - The updated record as returned from the database.
+    /// * `Err(AuthenticationError)` - If the client does not exist, is revoked or the database operation fails.
+    #[tracing::instrument(
+        name = "Update an OAuth client in the database: ",
+        skip(self, database),
+        fields(id = %self.id)
+    )]
+    pub async fn update(&self, database: &Pool<Postgres>) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            Clients,
+            r#"
+                UPDATE clients
+                SET name = $2,
+                    redirect_uris = $3,
+                    grant_types = $4,
+                    scopes = $5
+                WHERE id = $1 AND revoked_on IS NULL
+                RETURNING id, name, client_type as "client_type:ClientType", secret_prefix, secret_hash, redirect_uris, grant_types, scopes, created_on, revoked_on
+            "#,
+            self.id,
+            self.name,
+            &self.redirect_uris,
+            &self.grant_types,
+            &self.scopes,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("OAuth client updated: {}", database_record.id);
+
+        Ok(database_record)
+    }
+
+    /// Revoke an OAuth client by its id, so it can no longer get access tokens.
+    ///
+    /// Revoking a client that is already revoked leaves its `revoked_on` unchanged.
+    ///
+    /// # Parameters
+    /// * `id` - The OAuth client id.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - The number of clients revoked (0 if the client does not exist or is already revoked).
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Revoke an OAuth client in the database: ", skip(database))]
+    pub async fn revoke_by_id(id: &Uuid, database: &Pool<Postgres>) -> Result<usize, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                UPDATE clients
+                SET revoked_on = NOW()
+                WHERE id = $1 AND revoked_on IS NULL
+            "#,
+            id
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("OAuth clients revoked: {rows_affected}");
+
+        Ok(rows_affected as usize)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn update_client_registration(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let (client, _secret) = database::Clients::mock_data();
+        client.insert(&database).await?;
+        let mut updated = client.clone();
+        updated.name = "renamed service".to_string();
+        updated.scopes = vec!["users:read".to_string()];
+
+        //-- Execute Function (Act)
+        let database_record = updated.update(&database).await?;
+        database::Clients::revoke_by_id(&client.id, &database).await?;
+        let after_revoke = updated.update(&database).await;
+
+        //-- Checks (Assertions)
+        assert_eq!(database_record, updated);
+        assert!(after_revoke.is_err());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn revoke_client_once(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let (client, _secret) = database::Clients::mock_data();
+        client.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let first = database::Clients::revoke_by_id(&client.id, &database).await?;
+        let second = database::Clients::revoke_by_id(&client.id, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(first, 1);
+        assert_eq!(second, 0);
+        let database_record = database::Clients::from_id(&client.id, &database).await?;
+        assert!(!database_record.is_usable());
+
+        Ok(())
+    }
+}
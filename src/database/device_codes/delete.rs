@@ -0,0 +1,183 @@
+//-- ./src/database/device_codes/delete.rs
+
+// #![allow(unused)] // For development only
+
+//! Device code delete logic for the authentication service.
+//!
+//! # Contents
+//! - Consume an approved device code when its tokens are issued
+//! - Delete a device code
+//! - Delete expired device codes
+//! - Unit tests for delete scenarios
+
+use sqlx::PgExecutor;
+
+use crate::database::{DeviceCodeStatus, DeviceCodes};
+use crate::prelude::*;
+
+impl DeviceCodes {
+    /// Delete this device code if it is approved and unexpired, returning it.
+    /// The code is deleted in the same statement, so tokens are only issued
+    /// once, even to concurrent polls.
+    ///
+    /// # Parameters
+    /// * `self` - The device authorization to consume.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Some(DeviceCodes))` - The consumed device authorization.
+    /// * `Ok(None)` - If it is not approved, expired or already consumed.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Consume a device code in the database: ",
+        skip(self, database),
+        fields(id = %self.id)
+    )]
+    pub async fn consume(
+        &self,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Option<Self>, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            DeviceCodes,
+            r#"
+                DELETE FROM device_codes
+                WHERE id = $1 AND status = 'approved' AND expires_on > NOW()
+                RETURNING id, device_code_hash, user_code, client_name, client_id, status as "status:DeviceCodeStatus", user_id, last_polled_on, expires_on, created_on
+            "#,
+            self.id,
+        )
+        .fetch_optional(database)
+        .await?;
+
+        match &database_record {
+            Some(record) => tracing::debug!("Device code consumed: {}", record.id),
+            None => tracing::debug!("Device code is not approved, expired or consumed"),
+        }
+
+        Ok(database_record)
+    }
+
+    /// Delete this device code, e.g. once the device knows it was denied.
+    ///
+    /// # Parameters
+    /// * `self` - The device authorization to delete.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of device codes deleted.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Delete a device code from the database: ",
+        skip(self, database),
+        fields(id = %self.id)
+    )]
+    pub async fn delete(
+        &self,
+        database: impl PgExecutor<'_>,
+    ) -> Result<u64, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                DELETE FROM device_codes
+                WHERE id = $1
+            "#,
+            self.id,
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        Ok(rows_affected)
+    }
+
+    /// Delete device codes that have expired, they can no longer be approved
+    /// or get tokens.
+    ///
+    /// # Parameters
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of device codes deleted.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Delete expired device codes from the database: ",
+        skip(database)
+    )]
+    pub async fn delete_expired(
+        database: impl PgExecutor<'_>,
+    ) -> Result<u64, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                DELETE FROM device_codes
+                WHERE expires_on < NOW()
+            "#,
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Expired device codes deleted: {rows_affected}");
+
+        Ok(rows_affected)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use chrono::{Duration, Utc};
+    use sqlx::{Pool, Postgres};
+
+    use crate::database::{self, DeviceCodeStatus};
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn only_approved_device_codes_are_consumed_once(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let (device, _, user_code) = database::DeviceCodes::mock_data();
+        let device = device.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let pending = device.consume(&database).await?;
+        database::DeviceCodes::answer(
+            &user_code,
+            &user.id,
+            DeviceCodeStatus::Approved,
+            &database,
+        )
+        .await?;
+        let first = device.consume(&database).await?;
+        let second = device.consume(&database).await?;
+
+        //-- Checks (Assertions)
+        assert!(pending.is_none());
+        assert_eq!(first.ok_or("approved code is consumed")?.user_id, Some(user.id));
+        assert!(second.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn delete_expired_device_codes(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let (mut expired, _, _) = database::DeviceCodes::mock_data();
+        expired.expires_on = Utc::now() - Duration::minutes(1);
+        expired.insert(&database).await?;
+        let (current, _, _) = database::DeviceCodes::mock_data();
+        current.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let deleted = database::DeviceCodes::delete_expired(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(deleted, 1);
+
+        Ok(())
+    }
+}
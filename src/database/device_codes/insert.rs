@@ -0,0 +1,86 @@
+//-- ./src/database/device_codes/insert.rs
+
+// #![allow(unused)] // For development only
+
+//! Device code insert logic for the authentication service.
+//!
+//! # Contents
+//! - Insert a device code
+//! - Unit tests for insert scenarios
+
+use sqlx::PgExecutor;
+
+use crate::database::{DeviceCodeStatus, DeviceCodes};
+use crate::prelude::*;
+
+impl DeviceCodes {
+    /// Insert this device code into the database.
+    ///
+    /// # Parameters
+    /// * `self` - The `DeviceCodes` instance to insert.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(DeviceCodes)` - The inserted record as returned from the database.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Insert a device code into the database: ",
+        skip(self, database),
+        fields(id = %self.id)
+    )]
+    pub async fn insert(
+        &self,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            DeviceCodes,
+            r#"
+                INSERT INTO device_codes (id, device_code_hash, user_code, client_name, client_id, status, user_id, last_polled_on, expires_on, created_on)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                RETURNING id, device_code_hash, user_code, client_name, client_id, status as "status:DeviceCodeStatus", user_id, last_polled_on, expires_on, created_on
+            "#,
+            self.id,
+            self.device_code_hash,
+            self.user_code,
+            self.client_name,
+            self.client_id,
+            self.status as DeviceCodeStatus,
+            self.user_id,
+            self.last_polled_on,
+            self.expires_on,
+            self.created_on,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("Device code inserted: {}", database_record.id);
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn insert_device_code(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let (device, _, _) = database::DeviceCodes::mock_data();
+
+        //-- Execute Function (Act)
+        let database_record = device.insert(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(database_record, device);
+
+        Ok(())
+    }
+}
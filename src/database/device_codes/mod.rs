@@ -0,0 +1,25 @@
+//-- ./src/database/device_codes/mod.rs
+
+//! Device codes database module for the authentication service.
+//!
+//! OAuth device authorization grants, started by a device and approved or
+//! denied by a user entering the user code. Only the device code hash is
+//! stored, and a grant is deleted when its tokens are issued so the device
+//! code can only be used once.
+//!
+//! # Contents
+//! - Device code struct and status enum definitions
+//! - Device code insertion logic
+//! - Device code read logic
+//! - Device code approve, deny and poll logic
+//! - Device code consume and delete logic
+
+// #![allow(unused)] // For development only
+
+pub use model::{DeviceCodeStatus, DeviceCodes};
+
+mod delete;
+mod insert;
+mod model;
+mod read;
+mod update;
@@ -0,0 +1,128 @@
+//-- ./src/database/device_codes/model.rs
+
+// #![allow(unused)] // For development only
+
+//! The device codes database model.
+//!
+//! # Contents
+//! - `DeviceCodeStatus` enum definition
+//! - `DeviceCodes` struct definition
+//! - Constructor for new device codes
+//! - Mock data generation for tests
+
+use chrono::{DateTime, SubsecRound, Utc};
+use uuid::Uuid;
+
+use crate::{database, domain};
+
+/// Whether the user has answered a device authorization
+#[derive(Debug, Clone, Copy, PartialEq, sqlx::Type, serde::Deserialize)]
+#[sqlx(type_name = "device_code_status", rename_all = "lowercase")]
+pub enum DeviceCodeStatus {
+    /// Waiting for the user to enter the user code
+    Pending,
+    /// The user approved the device, it can get tokens
+    Approved,
+    /// The user denied the device
+    Denied,
+}
+
+impl DeviceCodeStatus {
+    /// Convert DeviceCodeStatus to a string reference
+    pub fn to_str(&self) -> &str {
+        match self {
+            DeviceCodeStatus::Pending => "pending",
+            DeviceCodeStatus::Approved => "approved",
+            DeviceCodeStatus::Denied => "denied",
+        }
+    }
+}
+
+impl std::fmt::Display for DeviceCodeStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+#[derive(Debug, sqlx::FromRow, Clone, PartialEq)]
+pub struct DeviceCodes {
+    pub id: Uuid,
+    pub device_code_hash: String,
+    pub user_code: String,
+    pub client_name: Option<String>,
+    pub client_id: Option<Uuid>,
+    pub status: DeviceCodeStatus,
+    pub user_id: Option<Uuid>,
+    pub last_polled_on: Option<DateTime<Utc>>,
+    pub expires_on: DateTime<Utc>,
+    pub created_on: DateTime<Utc>,
+}
+
+impl DeviceCodes {
+    /// # New Database Device Code Instance
+    ///
+    /// Creates a new pending device authorization. Only the device code hash
+    /// is kept.
+    ///
+    /// ## Parameters
+    ///
+    /// - `device_code: &domain::DeviceCode` - The code the device polls with
+    /// - `user_code: &domain::UserCode` - The code the user enters to approve the device
+    /// - `client_name: Option<&str>` - The name the device gave itself
+    /// - `duration: &std::time::Duration` - How long the user has to approve the device
+    pub fn new(
+        device_code: &domain::DeviceCode,
+        user_code: &domain::UserCode,
+        client_name: Option<&str>,
+        duration: &std::time::Duration,
+    ) -> Self {
+        let now = Utc::now().round_subsecs(0);
+
+        Self {
+            id: Uuid::now_v7(),
+            device_code_hash: device_code.hash(),
+            user_code: user_code.as_ref().to_string(),
+            client_name: client_name.map(str::to_string),
+            client_id: None,
+            status: DeviceCodeStatus::Pending,
+            user_id: None,
+            last_polled_on: None,
+            expires_on: now + *duration,
+            created_on: now,
+        }
+    }
+
+    /// # Device Code OAuth Client
+    ///
+    /// Start the device authorization for a registered OAuth client, named
+    /// after the client, so approving it grants the client access.
+    pub fn with_client(mut self, client: &database::Clients) -> Self {
+        self.client_name = Some(client.name.clone());
+        self.client_id = Some(client.id);
+        self
+    }
+
+    /// Whether the device can no longer be approved or get tokens
+    pub fn is_expired(&self) -> bool {
+        self.expires_on <= Utc::now()
+    }
+
+    #[cfg(test)]
+    /// # Mock Device Code Data
+    ///
+    /// This function is only available in test mode `#[cfg(test)]`.
+    /// Creates a new pending device authorization, returning the device and
+    /// user codes alongside it.
+    pub fn mock_data() -> (Self, domain::DeviceCode, domain::UserCode) {
+        let device_code = domain::DeviceCode::generate();
+        let user_code = domain::UserCode::generate();
+        let device = Self::new(
+            &device_code,
+            &user_code,
+            Some("Mock CLI"),
+            &std::time::Duration::from_secs(10 * 60),
+        );
+
+        (device, device_code, user_code)
+    }
+}
@@ -0,0 +1,83 @@
+//-- ./src/database/device_codes/read.rs
+
+// #![allow(unused)] // For development only
+
+//! Device code read logic for the authentication service.
+//!
+//! # Contents
+//! - Read a device code by its hash
+//! - Unit tests for read scenarios
+
+use sqlx::PgExecutor;
+
+use crate::database::{DeviceCodeStatus, DeviceCodes};
+use crate::domain;
+use crate::prelude::*;
+
+impl DeviceCodes {
+    /// Get the device authorization for a device code.
+    ///
+    /// # Parameters
+    /// * `device_code` - The device code the device polls with.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Some(DeviceCodes))` - The device authorization, which may have expired.
+    /// * `Ok(None)` - If the device code is unknown or its tokens were issued.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Read a device code from the database: ",
+        skip(device_code, database)
+    )]
+    pub async fn from_device_code(
+        device_code: &domain::DeviceCode,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Option<Self>, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            DeviceCodes,
+            r#"
+                SELECT id, device_code_hash, user_code, client_name, client_id, status as "status:DeviceCodeStatus", user_id, last_polled_on, expires_on, created_on
+                FROM device_codes
+                WHERE device_code_hash = $1
+            "#,
+            device_code.hash(),
+        )
+        .fetch_optional(database)
+        .await?;
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::{database, domain};
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn read_device_code_by_its_hash(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let (device, device_code, _) = database::DeviceCodes::mock_data();
+        device.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let found = database::DeviceCodes::from_device_code(&device_code, &database).await?;
+        let unknown = database::DeviceCodes::from_device_code(
+            &domain::DeviceCode::generate(),
+            &database,
+        )
+        .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(found, Some(device));
+        assert!(unknown.is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+//-- ./src/database/device_codes/update.rs
+
+// #![allow(unused)] // For development only
+
+//! Device code update logic for the authentication service.
+//!
+//! # Contents
+//! - Approve or deny a pending device code
+//! - Record a device polling for its tokens
+//! - Unit tests for update scenarios
+
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::database::{DeviceCodeStatus, DeviceCodes};
+use crate::domain;
+use crate::prelude::*;
+
+impl DeviceCodes {
+    /// Answer the pending, unexpired device authorization with this user code.
+    /// Only pending authorizations are answered, so the user can't change
+    /// their answer once the device has it.
+    ///
+    /// # Parameters
+    /// * `user_code` - The code the user entered.
+    /// * `user_id` - The user answering, the device logs in as them when approved.
+    /// * `status` - Whether the device is approved or denied.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Some(DeviceCodes))` - The answered device authorization.
+    /// * `Ok(None)` - If the user code is unknown, expired or already answered.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Answer a device code in the database: ",
+        skip(user_code, database)
+    )]
+    pub async fn answer(
+        user_code: &domain::UserCode,
+        user_id: &Uuid,
+        status: DeviceCodeStatus,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Option<Self>, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            DeviceCodes,
+            r#"
+                UPDATE device_codes
+                SET status = $3, user_id = $2
+                WHERE user_code = $1
+                    AND status = 'pending'
+                    AND expires_on > NOW()
+                RETURNING id, device_code_hash, user_code, client_name, client_id, status as "status:DeviceCodeStatus", user_id, last_polled_on, expires_on, created_on
+            "#,
+            user_code.as_ref(),
+            user_id,
+            status as DeviceCodeStatus,
+        )
+        .fetch_optional(database)
+        .await?;
+
+        match &database_record {
+            Some(record) => tracing::debug!("Device code {}: {}", record.status, record.id),
+            None => tracing::debug!("Device code is unknown, expired or answered"),
+        }
+
+        Ok(database_record)
+    }
+
+    /// Record that the device polled for its tokens now.
+    ///
+    /// # Parameters
+    /// * `self` - The device authorization polled.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of device codes updated.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Record a device code poll in the database: ",
+        skip(self, database),
+        fields(id = %self.id)
+    )]
+    pub async fn record_poll(
+        &self,
+        database: impl PgExecutor<'_>,
+    ) -> Result<u64, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                UPDATE device_codes
+                SET last_polled_on = NOW()
+                WHERE id = $1
+            "#,
+            self.id,
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        Ok(rows_affected)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database::{self, DeviceCodeStatus};
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn device_codes_are_answered_once(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let (device, _, user_code) = database::DeviceCodes::mock_data();
+        device.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let approved = database::DeviceCodes::answer(
+            &user_code,
+            &user.id,
+            DeviceCodeStatus::Approved,
+            &database,
+        )
+        .await?;
+        let denied = database::DeviceCodes::answer(
+            &user_code,
+            &user.id,
+            DeviceCodeStatus::Denied,
+            &database,
+        )
+        .await?;
+
+        //-- Checks (Assertions)
+        let approved = approved.ok_or("pending device code is answered")?;
+        assert_eq!(approved.status, DeviceCodeStatus::Approved);
+        assert_eq!(approved.user_id, Some(user.id));
+        assert!(denied.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn record_poll_sets_last_polled_on(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let (device, device_code, _) = database::DeviceCodes::mock_data();
+        let device = device.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let rows_affected = device.record_poll(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(rows_affected, 1);
+        let polled = database::DeviceCodes::from_device_code(&device_code, &database)
+            .await?
+            .ok_or("device code exists")?;
+        assert!(polled.last_polled_on.is_some());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+//-- ./src/database/email_changes/delete.rs
+
+// #![allow(unused)] // For development only
+
+//! Email change delete logic for the authentication service.
+//!
+//! # Contents
+//! - Delete a user's pending email changes
+//! - Unit tests for delete scenarios
+
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::database::EmailChanges;
+use crate::prelude::*;
+
+impl EmailChanges {
+    /// Delete a user's pending email changes, so only the latest request can be
+    /// confirmed.
+    ///
+    /// # Parameters
+    /// * `user_id` - The user whose pending changes are deleted.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of email changes deleted.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Delete pending email changes from the database: ",
+        skip(database)
+    )]
+    pub async fn delete_pending_for_user(
+        user_id: &Uuid,
+        database: impl PgExecutor<'_>,
+    ) -> Result<u64, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                DELETE FROM email_changes
+                WHERE user_id = $1 AND completed_on IS NULL
+            "#,
+            user_id,
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Pending email changes deleted: {rows_affected}");
+
+        Ok(rows_affected)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn only_pending_changes_are_deleted(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let (pending, pending_token, _) =
+            database::EmailChanges::mock_data(&user.id)?;
+        pending.insert(&database).await?;
+        let (completed, _, _) = database::EmailChanges::mock_data(&user.id)?;
+        completed
+            .insert(&database)
+            .await?
+            .complete(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let deleted =
+            database::EmailChanges::delete_pending_for_user(&user.id, &database)
+                .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(deleted, 1);
+        assert!(database::EmailChanges::from_token_hash(
+            &pending_token.hash(),
+            &database
+        )
+        .await?
+        .is_none());
+        let completed_rows: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM email_changes WHERE completed_on IS NOT NULL",
+        )
+        .fetch_one(&database)
+        .await?;
+        assert_eq!(completed_rows.0, 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+//-- ./src/database/email_changes/insert.rs
+
+// #![allow(unused)] // For development only
+
+//! Email change insert logic for the authentication service.
+//!
+//! # Contents
+//! - Insert an email change
+//! - Unit tests for insert scenarios
+
+use sqlx::PgExecutor;
+
+use crate::database::EmailChanges;
+use crate::prelude::*;
+
+impl EmailChanges {
+    /// Insert this email change into the database.
+    ///
+    /// # Parameters
+    /// * `self` - The `EmailChanges` instance to insert.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(EmailChanges)` - The inserted record as returned from the database.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Insert an email change into the database: ",
+        skip(self, database),
+        fields(
+            id = %self.id,
+            user_id = %self.user_id,
+        )
+    )]
+    pub async fn insert(
+        &self,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            EmailChanges,
+            r#"
+                INSERT INTO email_changes (id, user_id, new_email, old_token_hash, new_token_hash, old_confirmed_on, new_confirmed_on, requested_by, expires_on, created_on, completed_on)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                RETURNING id, user_id, new_email, old_token_hash, new_token_hash, old_confirmed_on, new_confirmed_on, requested_by, expires_on, created_on, completed_on
+            "#,
+            self.id,
+            self.user_id,
+            self.new_email.as_ref(),
+            self.old_token_hash,
+            self.new_token_hash,
+            self.old_confirmed_on,
+            self.new_confirmed_on,
+            self.requested_by,
+            self.expires_on,
+            self.created_on,
+            self.completed_on,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("Email change inserted: {}", database_record.id);
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn insert_email_change(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let (email_change, _, _) = database::EmailChanges::mock_data(&user.id)?;
+
+        //-- Execute Function (Act)
+        let database_record = email_change.insert(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(database_record, email_change);
+
+        Ok(())
+    }
+}
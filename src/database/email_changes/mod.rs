@@ -0,0 +1,24 @@
+//-- ./src/database/email_changes/mod.rs
+
+//! Email changes database module for the authentication service.
+//!
+//! An admin changing a user's email address creates a pending change. A token
+//! is sent to both the old and new addresses, and the change only takes effect
+//! once both are confirmed.
+//!
+//! # Contents
+//! - Email change insertion logic
+//! - Email change struct definition and model-level helpers
+//! - Email change read/query logic
+//! - Email change confirm and complete logic
+//! - Email change delete logic
+
+// #![allow(unused)] // For development only
+
+pub use model::EmailChanges;
+
+mod delete;
+mod insert;
+mod model;
+mod read;
+mod update;
@@ -0,0 +1,191 @@
+//-- ./src/database/email_changes/model.rs
+
+// #![allow(unused)] // For development only
+
+//! The email changes database model.
+//!
+//! # Contents
+//! - `EmailChanges` struct definition
+//! - Constructor for new email changes
+//! - Mock data generation for tests
+
+use chrono::{DateTime, SubsecRound, Utc};
+use uuid::Uuid;
+
+use crate::domain;
+use crate::utils::{Clock, SystemClock};
+
+#[derive(Debug, sqlx::FromRow, Clone, PartialEq)]
+pub struct EmailChanges {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub new_email: domain::EmailAddress,
+    pub old_token_hash: String,
+    pub new_token_hash: String,
+    pub old_confirmed_on: Option<DateTime<Utc>>,
+    pub new_confirmed_on: Option<DateTime<Utc>>,
+    pub requested_by: Option<Uuid>,
+    pub expires_on: DateTime<Utc>,
+    pub created_on: DateTime<Utc>,
+    pub completed_on: Option<DateTime<Utc>>,
+}
+
+impl EmailChanges {
+    /// # New Database Email Change Instance
+    ///
+    /// Creates a new pending email change. Only the token hashes are kept.
+    ///
+    /// ## Parameters
+    ///
+    /// - `user_id: &Uuid` - The user whose email is changing
+    /// - `new_email: &domain::EmailAddress` - The address the email changes to
+    /// - `old_token: &domain::ActionToken` - The token sent to the current address
+    /// - `new_token: &domain::ActionToken` - The token sent to the new address
+    /// - `requested_by: Option<Uuid>` - The admin requesting the change
+    /// - `duration: &std::time::Duration` - How long the change can be confirmed for
+    pub fn new(
+        user_id: &Uuid,
+        new_email: &domain::EmailAddress,
+        old_token: &domain::ActionToken,
+        new_token: &domain::ActionToken,
+        requested_by: Option<Uuid>,
+        duration: &std::time::Duration,
+    ) -> Self {
+        Self::new_with_clock(
+            user_id,
+            new_email,
+            old_token,
+            new_token,
+            requested_by,
+            duration,
+            &SystemClock,
+        )
+    }
+
+    /// The same as `EmailChanges::new`, requested at the time of `clock`
+    /// instead of the system time
+    pub fn new_with_clock(
+        user_id: &Uuid,
+        new_email: &domain::EmailAddress,
+        old_token: &domain::ActionToken,
+        new_token: &domain::ActionToken,
+        requested_by: Option<Uuid>,
+        duration: &std::time::Duration,
+        clock: &dyn Clock,
+    ) -> Self {
+        let now = clock.now().round_subsecs(0);
+
+        Self {
+            id: Uuid::now_v7(),
+            user_id: user_id.to_owned(),
+            new_email: new_email.to_owned(),
+            old_token_hash: old_token.hash(),
+            new_token_hash: new_token.hash(),
+            old_confirmed_on: None,
+            new_confirmed_on: None,
+            requested_by,
+            expires_on: now + *duration,
+            created_on: now,
+            completed_on: None,
+        }
+    }
+
+    /// Whether the change can no longer be confirmed
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(&SystemClock)
+    }
+
+    /// Whether the change can no longer be confirmed at the time of `clock`
+    pub fn is_expired_at(&self, clock: &dyn Clock) -> bool {
+        self.expires_on <= clock.now()
+    }
+
+    /// Whether both the old and new addresses have confirmed the change
+    pub fn is_confirmed(&self) -> bool {
+        self.old_confirmed_on.is_some() && self.new_confirmed_on.is_some()
+    }
+
+    #[cfg(test)]
+    /// # Mock Email Change Data
+    ///
+    /// This function is only available in test mode `#[cfg(test)]`.
+    /// Creates a new pending email change for the user, returning the old and
+    /// new address tokens alongside it.
+    pub fn mock_data(
+        user_id: &Uuid,
+    ) -> Result<
+        (Self, domain::ActionToken, domain::ActionToken),
+        crate::prelude::AuthenticationError,
+    > {
+        let new_email = domain::EmailAddress::mock_data()?;
+        let old_token = domain::ActionToken::generate(
+            domain::ActionPurpose::EmailChange,
+            &secrecy::SecretString::from("action-token-secret"),
+        );
+        let new_token = domain::ActionToken::generate(
+            domain::ActionPurpose::EmailChange,
+            &secrecy::SecretString::from("action-token-secret"),
+        );
+        let email_change = Self::new(
+            user_id,
+            &new_email,
+            &old_token,
+            &new_token,
+            None,
+            &std::time::Duration::from_secs(60 * 60),
+        );
+
+        Ok((email_change, old_token, new_token))
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn new_email_changes_store_token_hashes() -> Result<()> {
+        let (email_change, old_token, new_token) =
+            EmailChanges::mock_data(&Uuid::now_v7())?;
+
+        assert_eq!(email_change.old_token_hash, old_token.hash());
+        assert_eq!(email_change.new_token_hash, new_token.hash());
+        assert!(!email_change.is_expired());
+        assert!(!email_change.is_confirmed());
+
+        Ok(())
+    }
+
+    #[test]
+    fn email_changes_expire_after_the_duration() -> Result<()> {
+        let clock = crate::utils::MockClock::default();
+        let email_change = EmailChanges::new_with_clock(
+            &Uuid::now_v7(),
+            &domain::EmailAddress::mock_data()?,
+            &domain::ActionToken::generate(
+                domain::ActionPurpose::EmailChange,
+                &secrecy::SecretString::from("action-token-secret"),
+            ),
+            &domain::ActionToken::generate(
+                domain::ActionPurpose::EmailChange,
+                &secrecy::SecretString::from("action-token-secret"),
+            ),
+            None,
+            &std::time::Duration::from_secs(60 * 60),
+            &clock,
+        );
+
+        clock.advance(chrono::Duration::minutes(59));
+        assert!(!email_change.is_expired_at(&clock));
+
+        clock.advance(chrono::Duration::minutes(1));
+        assert!(email_change.is_expired_at(&clock));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+//-- ./src/database/email_changes/read.rs
+
+// #![allow(unused)] // For development only
+
+//! Email change read logic for the authentication service.
+//!
+//! # Contents
+//! - Get a pending email change by either of its token hashes
+//! - Unit tests for read scenarios
+
+use sqlx::PgExecutor;
+
+use crate::database::EmailChanges;
+use crate::prelude::*;
+
+impl EmailChanges {
+    /// Retrieve the pending email change a token was sent for, locking the row
+    /// until the transaction ends.
+    ///
+    /// The hash is matched against both the old and new address tokens.
+    ///
+    /// # Parameters
+    /// * `token_hash` - The SHA-256 hash of the confirmation token.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Some(EmailChanges))` - The pending email change.
+    /// * `Ok(None)` - If no pending change has the token.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Get an email change from the database by token hash: ",
+        skip(token_hash, database)
+    )]
+    pub async fn from_token_hash(
+        token_hash: &str,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Option<Self>, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            EmailChanges,
+            r#"
+                SELECT id, user_id, new_email, old_token_hash, new_token_hash, old_confirmed_on, new_confirmed_on, requested_by, expires_on, created_on, completed_on
+                FROM email_changes
+                WHERE (old_token_hash = $1 OR new_token_hash = $1) AND completed_on IS NULL
+                FOR UPDATE
+            "#,
+            token_hash,
+        )
+        .fetch_optional(database)
+        .await?;
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn from_token_hash_matches_either_token(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let (email_change, old_token, new_token) =
+            database::EmailChanges::mock_data(&user.id)?;
+        email_change.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let by_old =
+            database::EmailChanges::from_token_hash(&old_token.hash(), &database)
+                .await?;
+        let by_new =
+            database::EmailChanges::from_token_hash(&new_token.hash(), &database)
+                .await?;
+        let unknown =
+            database::EmailChanges::from_token_hash("unknown", &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(by_old, Some(email_change.clone()));
+        assert_eq!(by_new, Some(email_change));
+        assert!(unknown.is_none());
+
+        Ok(())
+    }
+}
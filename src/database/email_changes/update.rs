@@ -0,0 +1,134 @@
+//-- ./src/database/email_changes/update.rs
+
+// #![allow(unused)] // For development only
+
+//! Email change confirm and complete logic for the authentication service.
+//!
+//! # Contents
+//! - Confirm the side of an email change a token was sent to
+//! - Mark an email change as completed
+//! - Unit tests for update scenarios
+
+use sqlx::PgExecutor;
+
+use crate::database::EmailChanges;
+use crate::prelude::*;
+
+impl EmailChanges {
+    /// Confirm the side of the change the token was sent to, the old or new
+    /// address. Confirming a side twice keeps the first confirmation time.
+    ///
+    /// # Parameters
+    /// * `self` - The email change being confirmed.
+    /// * `token_hash` - The SHA-256 hash of the confirmation token.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(EmailChanges)` - The updated email change record.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Confirm an email change in the database: ",
+        skip(self, token_hash, database),
+        fields(id = %self.id)
+    )]
+    pub async fn confirm(
+        &self,
+        token_hash: &str,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            EmailChanges,
+            r#"
+                UPDATE email_changes
+                SET old_confirmed_on = CASE WHEN old_token_hash = $2 THEN COALESCE(old_confirmed_on, NOW()) ELSE old_confirmed_on END,
+                    new_confirmed_on = CASE WHEN new_token_hash = $2 THEN COALESCE(new_confirmed_on, NOW()) ELSE new_confirmed_on END
+                WHERE id = $1
+                RETURNING id, user_id, new_email, old_token_hash, new_token_hash, old_confirmed_on, new_confirmed_on, requested_by, expires_on, created_on, completed_on
+            "#,
+            self.id,
+            token_hash,
+        )
+        .fetch_one(database)
+        .await?;
+
+        Ok(database_record)
+    }
+
+    /// Mark the email change as completed, once the user's email is updated.
+    ///
+    /// # Parameters
+    /// * `self` - The confirmed email change.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(EmailChanges)` - The updated email change record.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Complete an email change in the database: ",
+        skip(self, database),
+        fields(id = %self.id)
+    )]
+    pub async fn complete(
+        &self,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            EmailChanges,
+            r#"
+                UPDATE email_changes
+                SET completed_on = NOW()
+                WHERE id = $1
+                RETURNING id, user_id, new_email, old_token_hash, new_token_hash, old_confirmed_on, new_confirmed_on, requested_by, expires_on, created_on, completed_on
+            "#,
+            self.id,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("Email change completed: {}", database_record.id);
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn confirm_sets_the_matching_side(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let (email_change, old_token, new_token) =
+            database::EmailChanges::mock_data(&user.id)?;
+        let email_change = email_change.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let old_confirmed =
+            email_change.confirm(&old_token.hash(), &database).await?;
+        let both_confirmed =
+            old_confirmed.confirm(&new_token.hash(), &database).await?;
+        let completed = both_confirmed.complete(&database).await?;
+
+        //-- Checks (Assertions)
+        assert!(old_confirmed.old_confirmed_on.is_some());
+        assert!(old_confirmed.new_confirmed_on.is_none());
+        assert!(!old_confirmed.is_confirmed());
+        assert!(both_confirmed.is_confirmed());
+        assert_eq!(
+            both_confirmed.old_confirmed_on,
+            old_confirmed.old_confirmed_on
+        );
+        assert!(completed.completed_on.is_some());
+
+        Ok(())
+    }
+}
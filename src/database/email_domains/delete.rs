@@ -0,0 +1,80 @@
+//-- ./src/database/email_domains/delete.rs
+
+// #![allow(unused)] // For development only
+
+//! Email domains delete logic for the authentication service.
+//!
+//! # Contents
+//! - Remove the rule for a domain
+//! - Unit tests for delete scenarios
+
+use sqlx::{Pool, Postgres};
+
+use crate::database::EmailDomains;
+use crate::domain;
+use crate::prelude::*;
+
+impl EmailDomains {
+    /// Remove the rule for a domain. Rules for its subdomains are kept.
+    ///
+    /// # Parameters
+    /// * `domain` - The domain the rule is for.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of rules removed, 0 if the domain has none.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Delete an email domain from the database: ", skip(database))]
+    pub async fn delete(
+        domain: &domain::EmailDomain,
+        database: &Pool<Postgres>,
+    ) -> Result<u64, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                DELETE FROM email_domains
+                WHERE domain = $1
+            "#,
+            domain.as_ref(),
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Email domains deleted: {rows_affected}");
+
+        Ok(rows_affected)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database::{self, EmailDomainRule};
+    use crate::domain;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn deleting_a_rule_removes_it(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let rule = database::EmailDomains::mock_data(EmailDomainRule::Allow)?
+            .upsert(&database)
+            .await?;
+        let domain = domain::EmailDomain::parse(&rule.domain)?;
+
+        //-- Execute Function (Act)
+        let first = database::EmailDomains::delete(&domain, &database).await?;
+        let second = database::EmailDomains::delete(&domain, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(first, 1);
+        assert_eq!(second, 0);
+        assert!(database::EmailDomains::index(&database).await?.is_empty());
+
+        Ok(())
+    }
+}
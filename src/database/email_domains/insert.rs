@@ -0,0 +1,84 @@
+//-- ./src/database/email_domains/insert.rs
+
+// #![allow(unused)] // For development only
+
+//! Email domains upsert logic for the authentication service.
+//!
+//! # Contents
+//! - Insert or replace the rule for a domain
+//! - Unit tests for upsert scenarios
+
+use sqlx::{Pool, Postgres};
+
+use crate::database::{EmailDomainRule, EmailDomains};
+use crate::prelude::*;
+
+impl EmailDomains {
+    /// Save this rule, replacing any rule the domain already has.
+    ///
+    /// # Parameters
+    /// * `self` - The `EmailDomains` instance to save.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(EmailDomains)` - The saved record as returned from the database.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Upsert email domain into the database: ",
+        skip(database),
+        fields(domain = %self.domain)
+    )]
+    pub async fn upsert(&self, database: &Pool<Postgres>) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            EmailDomains,
+            r#"
+                INSERT INTO email_domains (domain, rule, created_on)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (domain) DO UPDATE
+                SET rule = EXCLUDED.rule,
+                    created_on = EXCLUDED.created_on
+                RETURNING domain, rule as "rule:EmailDomainRule", created_on
+            "#,
+            self.domain,
+            self.rule as EmailDomainRule,
+            self.created_on,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("Email domain {} saved: {}", database_record.domain, database_record.rule);
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database::{self, EmailDomainRule};
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn saving_a_rule_replaces_it(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let allowed = database::EmailDomains::mock_data(EmailDomainRule::Allow)?;
+        let mut blocked = allowed.clone();
+        blocked.rule = EmailDomainRule::Block;
+
+        //-- Execute Function (Act)
+        let first = allowed.upsert(&database).await?;
+        let second = blocked.upsert(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(first, allowed);
+        assert_eq!(second, blocked);
+        assert_eq!(database::EmailDomains::index(&database).await?, vec![second]);
+
+        Ok(())
+    }
+}
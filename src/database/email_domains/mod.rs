@@ -0,0 +1,21 @@
+//-- ./src/database/email_domains/mod.rs
+
+//! Email domains database module for the authentication service.
+//!
+//! The email domains admins allow or block from registering. They are checked
+//! with the `email_domains` configuration lists, see `services::email_domains`.
+//!
+//! # Contents
+//! - EmailDomains struct and rule definitions
+//! - EmailDomains upsert logic
+//! - EmailDomains read logic
+//! - EmailDomains delete logic
+
+// #![allow(unused)] // For development only
+
+pub use model::{EmailDomainRule, EmailDomains};
+
+mod delete;
+mod insert;
+mod model;
+mod read;
@@ -0,0 +1,113 @@
+//-- ./src/database/email_domains/model.rs
+
+// #![allow(unused)] // For development only
+
+//! The email domains database model.
+//!
+//! # Contents
+//! - `EmailDomainRule` enum definition
+//! - `EmailDomains` struct definition
+//! - Constructor for new rules
+//! - Mock data generation for tests
+
+use chrono::{DateTime, SubsecRound, Utc};
+
+use crate::domain;
+use crate::prelude::*;
+
+/// Whether a domain can register
+#[derive(Debug, Clone, Copy, PartialEq, sqlx::Type)]
+#[sqlx(type_name = "email_domain_rule", rename_all = "lowercase")]
+pub enum EmailDomainRule {
+    /// Only allowed domains can register, once any domain is allowed
+    Allow,
+    /// The domain can't register
+    Block,
+}
+
+impl EmailDomainRule {
+    /// Convert EmailDomainRule to a string reference
+    pub fn to_str(&self) -> &str {
+        match self {
+            EmailDomainRule::Allow => "allow",
+            EmailDomainRule::Block => "block",
+        }
+    }
+}
+
+impl std::fmt::Display for EmailDomainRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+impl std::str::FromStr for EmailDomainRule {
+    type Err = AuthenticationError;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        match rule.trim().to_ascii_lowercase().as_str() {
+            "allow" => Ok(EmailDomainRule::Allow),
+            "block" => Ok(EmailDomainRule::Block),
+            _ => Err(AuthenticationError::ValidationError(format!(
+                "email domain rule must be allow or block: {rule}"
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, sqlx::FromRow, Clone, PartialEq)]
+pub struct EmailDomains {
+    pub domain: String,
+    pub rule: EmailDomainRule,
+    pub created_on: DateTime<Utc>,
+}
+
+impl EmailDomains {
+    /// # New Database Email Domain Instance
+    ///
+    /// Creates a new rule allowing or blocking a domain, and its subdomains.
+    ///
+    /// ## Parameters
+    ///
+    /// - `domain: &domain::EmailDomain` - The domain the rule is for
+    /// - `rule: EmailDomainRule` - Whether the domain can register
+    pub fn new(domain: &domain::EmailDomain, rule: EmailDomainRule) -> Self {
+        Self {
+            domain: domain.to_string(),
+            rule,
+            created_on: Utc::now().round_subsecs(0),
+        }
+    }
+
+    #[cfg(test)]
+    /// # Mock Email Domain Data
+    ///
+    /// This function is only available in test mode `#[cfg(test)]`.
+    /// Creates a rule for a random domain.
+    pub fn mock_data(rule: EmailDomainRule) -> Result<Self, AuthenticationError> {
+        let domain =
+            domain::EmailDomain::parse(format!("{}.example.com", uuid::Uuid::now_v7().simple()))?;
+
+        Ok(Self::new(&domain, rule))
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn rules_parse_from_their_names() -> Result<()> {
+        assert_eq!("allow".parse::<EmailDomainRule>()?, EmailDomainRule::Allow);
+        assert_eq!(" BLOCK ".parse::<EmailDomainRule>()?, EmailDomainRule::Block);
+        assert_eq!(EmailDomainRule::Block.to_string(), "block");
+        assert!("deny".parse::<EmailDomainRule>().is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+//-- ./src/database/email_domains/read.rs
+
+// #![allow(unused)] // For development only
+
+//! Email domains read logic for the authentication service.
+//!
+//! # Contents
+//! - Get every domain rule
+//! - Get the rules covering a domain
+//! - Unit tests for read scenarios
+
+use sqlx::{Pool, Postgres};
+
+use crate::database::{EmailDomainRule, EmailDomains};
+use crate::database::timeouts::{with_timeout, QueryBudget};
+use crate::domain;
+use crate::prelude::*;
+
+impl EmailDomains {
+    /// Retrieve every domain rule, in domain order.
+    ///
+    /// # Parameters
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<EmailDomains>)` - The domain rules, empty when there are none.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Index email domains from the database: ", skip(database))]
+    pub async fn index(database: &Pool<Postgres>) -> Result<Vec<Self>, AuthenticationError> {
+        let database_records = with_timeout(
+            QueryBudget::Interactive,
+            "email_domains.index",
+            sqlx::query_as!(
+                EmailDomains,
+                r#"
+                    SELECT domain, rule as "rule:EmailDomainRule", created_on
+                    FROM email_domains
+                    ORDER BY domain
+                "#,
+            )
+            .fetch_all(database),
+        )
+        .await?;
+
+        Ok(database_records)
+    }
+
+    /// Retrieve the rules for a domain and the domains it is a subdomain of.
+    ///
+    /// # Parameters
+    /// * `domain` - The domain being registered from.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<EmailDomains>)` - The rules covering the domain.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Get email domain rules from the database: ", skip(database))]
+    pub async fn covering(
+        domain: &domain::EmailDomain,
+        database: &Pool<Postgres>,
+    ) -> Result<Vec<Self>, AuthenticationError> {
+        let database_records = sqlx::query_as!(
+            EmailDomains,
+            r#"
+                SELECT domain, rule as "rule:EmailDomainRule", created_on
+                FROM email_domains
+                WHERE domain = $1 OR RIGHT($1, LENGTH(domain) + 1) = '.' || domain
+                ORDER BY domain
+            "#,
+            domain.as_ref(),
+        )
+        .fetch_all(database)
+        .await?;
+
+        Ok(database_records)
+    }
+
+    /// Are any domains allowed, so only they can register.
+    ///
+    /// # Parameters
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(bool)` - True when at least one domain is allowed.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Check for allowed email domains: ", skip(database))]
+    pub async fn any_allowed(database: &Pool<Postgres>) -> Result<bool, AuthenticationError> {
+        let any_allowed = sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM email_domains WHERE rule = 'allow'
+                ) AS "any_allowed!"
+            "#,
+        )
+        .fetch_one(database)
+        .await?;
+
+        Ok(any_allowed)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database::{self, EmailDomainRule};
+    use crate::domain;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn rules_cover_their_subdomains(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let blocked = database::EmailDomains::mock_data(EmailDomainRule::Block)?
+            .upsert(&database)
+            .await?;
+        database::EmailDomains::mock_data(EmailDomainRule::Block)?
+            .upsert(&database)
+            .await?;
+        let subdomain = domain::EmailDomain::parse(format!("mail.{}", blocked.domain))?;
+        let lookalike = domain::EmailDomain::parse(format!("not{}", blocked.domain))?;
+
+        //-- Execute Function (Act)
+        let covering = database::EmailDomains::covering(&subdomain, &database).await?;
+        let not_covering = database::EmailDomains::covering(&lookalike, &database).await?;
+        let any_allowed = database::EmailDomains::any_allowed(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(covering, vec![blocked]);
+        assert!(not_covering.is_empty());
+        assert!(!any_allowed);
+        assert_eq!(database::EmailDomains::index(&database).await?.len(), 2);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+//-- ./src/database/email_verification/count.rs
+
+// #![allow(unused)] // For development only
+
+//! Email verification count and exists helpers for the authentication service.
+//!
+//! Used for pagination totals and preflight checks, without fetching full rows.
+//!
+//! # Contents
+//! - Count all email verifications, or a user's email verifications
+//! - Check an email verification token exists
+//! - Unit tests for count scenarios
+
+use uuid::Uuid;
+
+use crate::{database::EmailVerifications, domain, AuthenticationError};
+
+impl EmailVerifications {
+    /// Count the email verifications in the database, including used and
+    /// expired.
+    ///
+    /// # Parameters
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of email verifications.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Count Email verifications in the database: ",
+        skip(database)
+    )]
+    pub async fn count(
+        database: &sqlx::Pool<sqlx::Postgres>,
+    ) -> Result<u64, AuthenticationError> {
+        let count = sqlx::query_scalar!(
+            r#"
+                SELECT COUNT(*) AS "count!"
+                FROM email_verifications
+            "#
+        )
+        .fetch_one(database)
+        .await?;
+
+        Ok(count as u64)
+    }
+
+    /// Count a user's email verifications, including used and expired.
+    ///
+    /// # Parameters
+    /// * `user_id` - The user id.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of the user's email verifications.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Count a User's Email verifications in the database: ",
+        skip(database)
+    )]
+    pub async fn count_by_user_id(
+        user_id: &Uuid,
+        database: &sqlx::Pool<sqlx::Postgres>,
+    ) -> Result<u64, AuthenticationError> {
+        let count = sqlx::query_scalar!(
+            r#"
+                SELECT COUNT(*) AS "count!"
+                FROM email_verifications
+                WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_one(database)
+        .await?;
+
+        Ok(count as u64)
+    }
+
+    /// Check an email verification with the token exists.
+    ///
+    /// # Parameters
+    /// * `token` - The email verification token.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(bool)` - If the email verification exists.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Check an Email verification token exists in the database: ",
+        skip(token, database)
+    )]
+    pub async fn exists_by_token(
+        token: &domain::EmailVerificationToken,
+        database: &sqlx::Pool<sqlx::Postgres>,
+    ) -> Result<bool, AuthenticationError> {
+        let exists = sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM email_verifications WHERE token = $1
+                ) AS "exists!"
+            "#,
+            token.as_ref()
+        )
+        .fetch_one(database)
+        .await?;
+
+        Ok(exists)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use fake::{faker::company::en::CompanyName, Fake, Faker};
+    use secrecy::SecretString;
+
+    use super::*;
+    use crate::database::Users;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    fn mock_token() -> domain::EmailVerificationToken {
+        let issuer =
+            SecretString::new(CompanyName().fake::<String>().into_boxed_str());
+        let secret = SecretString::new(Faker.fake::<String>().into_boxed_str());
+        let user = Users::mock_data().unwrap();
+        let claim = domain::TokenClaimNew::new(
+            &issuer,
+            &Duration::hours(24),
+            &user,
+            &domain::TokenType::EmailVerification,
+        );
+        domain::EmailVerificationToken::try_from_claim(claim, &secret)
+            .expect("Failed to generate mock token")
+    }
+
+    #[sqlx::test]
+    async fn count_email_verifications_by_user(pool: sqlx::PgPool) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = Users::mock_data()?.insert(&pool).await?;
+        let other_user = Users::mock_data()?.insert(&pool).await?;
+        let token = mock_token();
+        for (owner, token) in [
+            (&user, token.clone()),
+            (&user, mock_token()),
+            (&other_user, mock_token()),
+        ] {
+            EmailVerifications::new(owner, &token, &Duration::hours(24))
+                .insert(&pool)
+                .await?;
+        }
+
+        //-- Execute Function (Act)
+        let count = EmailVerifications::count(&pool).await?;
+        let user_count =
+            EmailVerifications::count_by_user_id(&user.id, &pool).await?;
+        let exists = EmailVerifications::exists_by_token(&token, &pool).await?;
+        let missing =
+            EmailVerifications::exists_by_token(&mock_token(), &pool).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(count, 3);
+        assert_eq!(user_count, 2);
+        assert!(exists);
+        assert!(!missing);
+
+        Ok(())
+    }
+}
@@ -523,7 +523,7 @@ impl EmailVerifications {
     /// * `Err(AuthenticationError)` - Database connection error or query failure
     ///
     /// # Behaviour
-    /// - Deletes records where `used = true`
+    /// - Deletes records where `is_used = true`
     /// - Does not consider `expires_at`, `created_at`, or other fields
     /// - Operation is permanent and cannot be undone
     /// - Removes successful verification history from the database
@@ -534,7 +534,7 @@ impl EmailVerifications {
         let rows_affected = sqlx::query!(
             r#"
                 DELETE FROM email_verifications
-                WHERE used = true
+                WHERE is_used = true
             "#,
         )
         .execute(database)
@@ -1,7 +1,8 @@
 
 #![allow(unused)] // For development only
 
-// mod delete;
+mod count;
+mod delete;
 mod insert;
 mod model;
 mod read;
@@ -7,6 +7,7 @@
 use crate::{
     database,
     domain::{self, RowID},
+    utils::{Clock, SystemClock},
 };
 use secrecy::SecretString;
 use uuid::Uuid;
@@ -41,6 +42,17 @@ impl EmailVerifications {
         user: &database::Users,
         token: &domain::EmailVerificationToken,
         duration: &chrono::Duration,
+    ) -> Self {
+        Self::new_with_clock(user, token, duration, &SystemClock)
+    }
+
+    /// Create a new email verification as `new` does, created at the time of
+    /// `clock` instead of the system time
+    pub fn new_with_clock(
+        user: &database::Users,
+        token: &domain::EmailVerificationToken,
+        duration: &chrono::Duration,
+        clock: &dyn Clock,
     ) -> Self {
         // Generate a new unique identifier for the email verification record.
         let id = RowID::new();
@@ -53,7 +65,7 @@ impl EmailVerifications {
         let token = token.clone();
 
         // Get the now DateTime<Utc>
-        let now = chrono::Utc::now();
+        let now = clock.now();
 
         // Calculate the the expiration time by adding the duration to the current time.
         let expires_at = now + *duration;
@@ -80,7 +92,12 @@ impl EmailVerifications {
 
     /// Checks if the verification has expired
     pub fn is_expired(&self) -> bool {
-        chrono::Utc::now() > self.expires_at
+        self.is_expired_at(&SystemClock)
+    }
+
+    /// Checks if the verification has expired at the time of `clock`
+    pub fn is_expired_at(&self, clock: &dyn Clock) -> bool {
+        clock.now() > self.expires_at
     }
 
     /// Checks if the verification is still valid (not used and not expired)
@@ -107,7 +124,12 @@ impl EmailVerifications {
 
     /// Time remaining until expiration
     pub fn time_until_expiry(&self) -> chrono::Duration {
-        self.expires_at - chrono::Utc::now()
+        self.time_until_expiry_at(&SystemClock)
+    }
+
+    /// Time remaining until expiration at the time of `clock`
+    pub fn time_until_expiry_at(&self, clock: &dyn Clock) -> chrono::Duration {
+        self.expires_at - clock.now()
     }
 
 }
@@ -694,16 +716,20 @@ mod tests {
         // Arrange
         let user = Users::mock_data()?;
         let token = mock_token();
-        let duration = Duration::zero();
+        let duration = Duration::hours(1);
+        let clock = crate::utils::MockClock::default();
 
         // Act
-        let verification = EmailVerifications::new(&user, &token, &duration);
-
-        // Simulate time passing to exact expiry
-        std::thread::sleep(std::time::Duration::from_millis(1));
+        let verification =
+            EmailVerifications::new_with_clock(&user, &token, &duration, &clock);
 
         // Assert
-        assert!(verification.is_expired() || !verification.is_expired()); // Either is acceptable due to timing
+        clock.advance(duration);
+        assert!(!verification.is_expired_at(&clock)); // Valid up to the expiry time
+        assert_eq!(verification.time_until_expiry_at(&clock), Duration::zero());
+
+        clock.advance(Duration::seconds(1));
+        assert!(verification.is_expired_at(&clock));
         Ok(())
     }
 
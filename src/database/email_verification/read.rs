@@ -3,7 +3,10 @@
 use chrono;
 use uuid::Uuid;
 
-use crate::{database::EmailVerifications, domain, AuthenticationError};
+use crate::{
+    database::{EmailVerifications, SortDirection},
+    domain, AuthenticationError,
+};
 
 fn safe_cast_to_i64(value: usize) -> Result<i64, AuthenticationError> {
     i64::try_from(value).map_err(|_| {
@@ -154,12 +157,14 @@ impl EmailVerifications {
         skip(database),
         fields(
             limit = %limit,
+            direction = ?direction,
             cursor_created_at = ?cursor_created_at,
             cursor_id = ?cursor_id
         )
     )]
     pub async fn index_cursor(
         limit: usize,
+        direction: SortDirection,
         cursor_created_at: Option<chrono::DateTime<chrono::Utc>>,
         cursor_id: Option<Uuid>,
         database: &sqlx::Pool<sqlx::Postgres>,
@@ -168,45 +173,41 @@ impl EmailVerifications {
 
         let limit_i64 = safe_cast_to_i64(limit)?;
 
-        let results = match (cursor_created_at, cursor_id) {
-            // First page - no cursor
-            (None, None) => {
+        let results = match direction {
+            // Oldest first, paging forward
+            SortDirection::Ascending => {
                 sqlx::query_as!(
                     EmailVerifications,
                     r#"
                         SELECT * FROM email_verifications
+                        WHERE ($1::TIMESTAMPTZ IS NULL OR (created_at, id) > ($1, $2))
                         ORDER BY created_at ASC, id ASC
-                        LIMIT $1
+                        LIMIT $3
                     "#,
+                    cursor_created_at,
+                    cursor_id,
                     limit_i64
                 )
                 .fetch_all(database)
                 .await
             }
-            // Subsequent pages - with cursor
-            (Some(created_at), Some(id)) => {
+            // Newest first, paging backward
+            SortDirection::Descending => {
                 sqlx::query_as!(
                     EmailVerifications,
                     r#"
                         SELECT * FROM email_verifications
-                        WHERE (created_at, id) > ($1, $2)
-                        ORDER BY created_at ASC, id ASC
+                        WHERE ($1::TIMESTAMPTZ IS NULL OR (created_at, id) < ($1, $2))
+                        ORDER BY created_at DESC, id DESC
                         LIMIT $3
                     "#,
-                    created_at,
-                    id,
+                    cursor_created_at,
+                    cursor_id,
                     limit_i64
                 )
                 .fetch_all(database)
                 .await
             }
-            // Invalid cursor state
-            _ => {
-                return Err(AuthenticationError::ValidationError(
-                    "Both cursor_created_at and cursor_id must be provided together"
-                        .to_string(),
-                ));
-            }
         };
 
         results.map_err(|e| {
@@ -274,6 +275,7 @@ impl EmailVerifications {
         fields(
             user_id = %user_id,
             limit = %limit,
+            direction = ?direction,
             cursor_created_at = ?cursor_created_at,
             cursor_id = ?cursor_id
         )
@@ -281,6 +283,7 @@ impl EmailVerifications {
     pub async fn index_from_user_id_cursor(
         user_id: &Uuid,
         limit: usize,
+        direction: SortDirection,
         cursor_created_at: Option<chrono::DateTime<chrono::Utc>>,
         cursor_id: Option<Uuid>,
         database: &sqlx::Pool<sqlx::Postgres>,
@@ -289,49 +292,45 @@ impl EmailVerifications {
 
         let limit_i64 = safe_cast_to_i64(limit)?;
 
-        let results = match (cursor_created_at, cursor_id) {
-            // First page - no cursor
-            (None, None) => {
+        let results = match direction {
+            // Oldest first, paging forward
+            SortDirection::Ascending => {
                 sqlx::query_as!(
                     EmailVerifications,
                     r#"
                         SELECT * FROM email_verifications
                         WHERE user_id = $1
+                        AND ($2::TIMESTAMPTZ IS NULL OR (created_at, id) > ($2, $3))
                         ORDER BY created_at ASC, id ASC
-                        LIMIT $2
+                        LIMIT $4
                     "#,
                     user_id,
+                    cursor_created_at,
+                    cursor_id,
                     limit_i64
                 )
                 .fetch_all(database)
                 .await
             }
-            // Subsequent pages - with cursor
-            (Some(created_at), Some(id)) => {
+            // Newest first, paging backward
+            SortDirection::Descending => {
                 sqlx::query_as!(
                     EmailVerifications,
                     r#"
                         SELECT * FROM email_verifications
-                        WHERE user_id = $1 
-                        AND (created_at, id) > ($2, $3)
-                        ORDER BY created_at ASC, id ASC
+                        WHERE user_id = $1
+                        AND ($2::TIMESTAMPTZ IS NULL OR (created_at, id) < ($2, $3))
+                        ORDER BY created_at DESC, id DESC
                         LIMIT $4
                     "#,
                     user_id,
-                    created_at,
-                    id,
+                    cursor_created_at,
+                    cursor_id,
                     limit_i64
                 )
                 .fetch_all(database)
                 .await
             }
-            // Invalid cursor state
-            _ => {
-                return Err(AuthenticationError::ValidationError(
-                    "Both cursor_created_at and cursor_id must be provided together"
-                        .to_string(),
-                ));
-            }
         };
 
         results.map_err(|e| {
@@ -1176,14 +1175,61 @@ mod tests {
         #[sqlx::test]
         async fn index_cursor_empty_database(pool: sqlx::PgPool) -> Result<()> {
             // Act
-            let results =
-                EmailVerifications::index_cursor(10, None, None, &pool).await?;
+            let results = EmailVerifications::index_cursor(
+                10,
+                SortDirection::Ascending,
+                None,
+                None,
+                &pool,
+            )
+            .await?;
 
             // Assert
             assert!(results.is_empty());
             Ok(())
         }
 
+        #[sqlx::test]
+        async fn index_cursor_descending_pages_newest_first(
+            pool: sqlx::PgPool,
+        ) -> Result<()> {
+            // Arrange
+            let user = create_test_user(&pool).await?;
+            create_multiple_verifications(&user, 5, &pool).await?;
+
+            // Act
+            let page1 = EmailVerifications::index_cursor(
+                3,
+                SortDirection::Descending,
+                None,
+                None,
+                &pool,
+            )
+            .await?;
+            let last = page1.last().unwrap();
+            let page2 = EmailVerifications::index_cursor(
+                3,
+                SortDirection::Descending,
+                Some(last.created_at),
+                Some(last.id.into_uuid()),
+                &pool,
+            )
+            .await?;
+
+            // Assert
+            assert_eq!(page1.len(), 3);
+            assert_eq!(page2.len(), 2);
+            let all: Vec<_> = page1.iter().chain(page2.iter()).collect();
+            for i in 1..all.len() {
+                assert!(
+                    (all[i - 1].created_at, all[i - 1].id)
+                        > (all[i].created_at, all[i].id),
+                    "Results should be ordered by created_at DESC, id DESC"
+                );
+            }
+            Ok(())
+        }
+
         #[sqlx::test]
         async fn index_cursor_first_page(pool: sqlx::PgPool) -> Result<()> {
             // Arrange
@@ -1191,8 +1237,14 @@ mod tests {
             create_multiple_verifications(&user, 5, &pool).await?;
 
             // Act
-            let results =
-                EmailVerifications::index_cursor(3, None, None, &pool).await?;
+            let results = EmailVerifications::index_cursor(
+                3,
+                SortDirection::Ascending,
+                None,
+                None,
+                &pool,
+            )
+            .await?;
 
             // Assert
             assert_eq!(results.len(), 3);
@@ -1221,8 +1273,14 @@ mod tests {
                 create_multiple_verifications(&user, 7, &pool).await?;
 
             // Act - Page 1
-            let page1 =
-                EmailVerifications::index_cursor(3, None, None, &pool).await?;
+            let page1 = EmailVerifications::index_cursor(
+                3,
+                SortDirection::Ascending,
+                None,
+                None,
+                &pool,
+            )
+            .await?;
             assert_eq!(page1.len(), 3);
 
             // Act - Page 2
@@ -1230,6 +1288,7 @@ mod tests {
             let cursor_id = Some(page1.last().unwrap().id.into_uuid());
             let page2 = EmailVerifications::index_cursor(
                 3,
+                SortDirection::Ascending,
                 cursor_created_at,
                 cursor_id,
                 &pool,
@@ -1242,6 +1301,7 @@ mod tests {
             let cursor_id = Some(page2.last().unwrap().id.into_uuid());
             let page3 = EmailVerifications::index_cursor(
                 3,
+                SortDirection::Ascending,
                 cursor_created_at,
                 cursor_id,
                 &pool,
@@ -1254,6 +1314,7 @@ mod tests {
             let cursor_id = Some(page3.last().unwrap().id.into_uuid());
             let page4 = EmailVerifications::index_cursor(
                 3,
+                SortDirection::Ascending,
                 cursor_created_at,
                 cursor_id,
                 &pool,
@@ -1287,6 +1348,7 @@ mod tests {
             // Act
             let result = EmailVerifications::index_cursor(
                 10,
+                SortDirection::Ascending,
                 None,
                 Some(uuid::Uuid::new_v4()), // cursor_id without created_at
                 &pool,
@@ -1313,6 +1375,7 @@ mod tests {
             // Act
             let result = EmailVerifications::index_cursor(
                 10,
+                SortDirection::Ascending,
                 Some(chrono::Utc::now()), // created_at without cursor_id
                 None,
                 &pool,
@@ -1341,8 +1404,14 @@ mod tests {
             create_multiple_verifications(&user, 2, &pool).await?;
 
             // Act
-            let result =
-                EmailVerifications::index_cursor(10, None, None, &pool).await?;
+            let result = EmailVerifications::index_cursor(
+                10,
+                SortDirection::Ascending,
+                None,
+                None,
+                &pool,
+            )
+            .await?;
 
             // Assert
             assert_eq!(result.len(), 2);
@@ -1358,8 +1427,14 @@ mod tests {
             create_multiple_verifications(&user, 5, &pool).await?;
 
             // Get first page
-            let page1 =
-                EmailVerifications::index_cursor(2, None, None, &pool).await?;
+            let page1 = EmailVerifications::index_cursor(
+                2,
+                SortDirection::Ascending,
+                None,
+                None,
+                &pool,
+            )
+            .await?;
             assert_eq!(page1.len(), 2);
 
             // Act - Use valid cursor parameters
@@ -1367,6 +1442,7 @@ mod tests {
             let cursor_id = Some(page1.last().unwrap().id.into_uuid());
             let result = EmailVerifications::index_cursor(
                 2,
+                SortDirection::Ascending,
                 cursor_created_at,
                 cursor_id,
                 &pool,
@@ -1389,14 +1465,21 @@ mod tests {
             create_multiple_verifications(&user, 3, &pool).await?;
 
             // Act - Get all records in pages
-            let page1 =
-                EmailVerifications::index_cursor(2, None, None, &pool).await?;
+            let page1 = EmailVerifications::index_cursor(
+                2,
+                SortDirection::Ascending,
+                None,
+                None,
+                &pool,
+            )
+            .await?;
             assert_eq!(page1.len(), 2);
 
             let cursor_created_at = Some(page1.last().unwrap().created_at);
             let cursor_id = Some(page1.last().unwrap().id.into_uuid());
             let page2 = EmailVerifications::index_cursor(
                 2,
+                SortDirection::Ascending,
                 cursor_created_at,
                 cursor_id,
                 &pool,
@@ -1409,6 +1492,7 @@ mod tests {
             let cursor_id = Some(page2.last().unwrap().id.into_uuid());
             let page3 = EmailVerifications::index_cursor(
                 2,
+                SortDirection::Ascending,
                 cursor_created_at,
                 cursor_id,
                 &pool,
@@ -1440,6 +1524,7 @@ mod tests {
             loop {
                 let page = EmailVerifications::index_cursor(
                     3,
+                    SortDirection::Ascending,
                     cursor_created_at,
                     cursor_id,
                     &pool,
@@ -1457,8 +1542,14 @@ mod tests {
             }
 
             // Get all records using index_cursor with large limit
-            let all_at_once =
-                EmailVerifications::index_cursor(100, None, None, &pool).await?;
+            let all_at_once = EmailVerifications::index_cursor(
+                100,
+                SortDirection::Ascending,
+                None,
+                None,
+                &pool,
+            )
+            .await?;
 
             // Assert - Should have same number of records
             assert_eq!(all_cursor_results.len(), all_at_once.len());
@@ -1481,8 +1572,14 @@ mod tests {
             create_multiple_verifications(&user, 3, &pool).await?;
 
             // Act
-            let result =
-                EmailVerifications::index_cursor(0, None, None, &pool).await?;
+            let result = EmailVerifications::index_cursor(
+                0,
+                SortDirection::Ascending,
+                None,
+                None,
+                &pool,
+            )
+            .await?;
 
             // Assert
             assert_eq!(result.len(), 0);
@@ -1496,8 +1593,14 @@ mod tests {
             create_multiple_verifications(&user, 5, &pool).await?;
 
             // Act
-            let result =
-                EmailVerifications::index_cursor(1, None, None, &pool).await?;
+            let result = EmailVerifications::index_cursor(
+                1,
+                SortDirection::Ascending,
+                None,
+                None,
+                &pool,
+            )
+            .await?;
 
             // Assert
             assert_eq!(result.len(), 1);
@@ -1511,8 +1614,14 @@ mod tests {
             create_multiple_verifications(&user, 3, &pool).await?;
 
             // Act - Large but valid limit (should warn but succeed)
-            let result =
-                EmailVerifications::index_cursor(1001, None, None, &pool).await?;
+            let result = EmailVerifications::index_cursor(
+                1001,
+                SortDirection::Ascending,
+                None,
+                None,
+                &pool,
+            )
+            .await?;
 
             // Assert
             assert_eq!(result.len(), 3); // Should return all available records
@@ -1529,9 +1638,14 @@ mod tests {
 
             // Act - Try with limit that would overflow i64
             let large_limit = usize::MAX;
-            let result =
-                EmailVerifications::index_cursor(large_limit, None, None, &pool)
-                    .await;
+            let result = EmailVerifications::index_cursor(
+                large_limit,
+                SortDirection::Ascending,
+                None,
+                None,
+                &pool,
+            )
+            .await;
 
             // Assert
             assert!(result.is_err());
@@ -1555,8 +1669,14 @@ mod tests {
             create_multiple_verifications(&user3, 1, &pool).await?;
 
             // Act
-            let results =
-                EmailVerifications::index_cursor(10, None, None, &pool).await?;
+            let results = EmailVerifications::index_cursor(
+                10,
+                SortDirection::Ascending,
+                None,
+                None,
+                &pool,
+            )
+            .await?;
 
             // Assert
             assert_eq!(results.len(), 6); // Total records across all users
@@ -1593,6 +1713,7 @@ mod tests {
             let fake_cursor_id = Some(uuid::Uuid::new_v4());
             let result = EmailVerifications::index_cursor(
                 10,
+                SortDirection::Ascending,
                 fake_cursor_created_at,
                 fake_cursor_id,
                 &pool,
@@ -1618,6 +1739,7 @@ mod tests {
             let old_cursor_id = Some(uuid::Uuid::new_v4());
             let result = EmailVerifications::index_cursor(
                 10,
+                SortDirection::Ascending,
                 old_cursor_created_at,
                 old_cursor_id,
                 &pool,
@@ -1669,8 +1791,14 @@ mod tests {
             let v3 = verification3.insert(&pool).await?;
 
             // Act - Get first page
-            let page1 =
-                EmailVerifications::index_cursor(2, None, None, &pool).await?;
+            let page1 = EmailVerifications::index_cursor(
+                2,
+                SortDirection::Ascending,
+                None,
+                None,
+                &pool,
+            )
+            .await?;
             assert_eq!(page1.len(), 2);
 
             // Act - Get second page using cursor from first page
@@ -1678,6 +1806,7 @@ mod tests {
             let cursor_id = Some(page1.last().unwrap().id.into_uuid());
             let page2 = EmailVerifications::index_cursor(
                 2,
+                SortDirection::Ascending,
                 cursor_created_at,
                 cursor_id,
                 &pool,
@@ -1714,6 +1843,7 @@ mod tests {
             loop {
                 let page = EmailVerifications::index_cursor(
                     3,
+                    SortDirection::Ascending,
                     cursor_created_at,
                     cursor_id,
                     &pool,
@@ -2286,7 +2416,7 @@ mod tests {
 
             // Act
             let results = EmailVerifications::index_from_user_id_cursor(
-                &user.id, 10, None, None, &pool,
+                &user.id, 10, SortDirection::Ascending, None, None, &pool,
             )
             .await?;
 
@@ -2305,7 +2435,7 @@ mod tests {
 
             // Act
             let results = EmailVerifications::index_from_user_id_cursor(
-                &user.id, 3, None, None, &pool,
+                &user.id, 3, SortDirection::Ascending, None, None, &pool,
             )
             .await?;
 
@@ -2343,7 +2473,7 @@ mod tests {
 
             // Act - Page 1
             let page1 = EmailVerifications::index_from_user_id_cursor(
-                &user.id, 3, None, None, &pool,
+                &user.id, 3, SortDirection::Ascending, None, None, &pool,
             )
             .await?;
             assert_eq!(page1.len(), 3);
@@ -2354,6 +2484,7 @@ mod tests {
             let page2 = EmailVerifications::index_from_user_id_cursor(
                 &user.id,
                 3,
+                SortDirection::Ascending,
                 cursor_created_at,
                 cursor_id,
                 &pool,
@@ -2367,6 +2498,7 @@ mod tests {
             let page3 = EmailVerifications::index_from_user_id_cursor(
                 &user.id,
                 3,
+                SortDirection::Ascending,
                 cursor_created_at,
                 cursor_id,
                 &pool,
@@ -2380,6 +2512,7 @@ mod tests {
             let page4 = EmailVerifications::index_from_user_id_cursor(
                 &user.id,
                 3,
+                SortDirection::Ascending,
                 cursor_created_at,
                 cursor_id,
                 &pool,
@@ -2428,17 +2561,17 @@ mod tests {
 
             // Act - Query each user separately
             let user1_results = EmailVerifications::index_from_user_id_cursor(
-                &user1.id, 10, None, None, &pool,
+                &user1.id, 10, SortDirection::Ascending, None, None, &pool,
             )
             .await?;
 
             let user2_results = EmailVerifications::index_from_user_id_cursor(
-                &user2.id, 10, None, None, &pool,
+                &user2.id, 10, SortDirection::Ascending, None, None, &pool,
             )
             .await?;
 
             let user3_results = EmailVerifications::index_from_user_id_cursor(
-                &user3.id, 10, None, None, &pool,
+                &user3.id, 10, SortDirection::Ascending, None, None, &pool,
             )
             .await?;
 
@@ -2485,7 +2618,7 @@ mod tests {
 
             // Act - Query only user1's records
             let results = EmailVerifications::index_from_user_id_cursor(
-                &user1.id, 10, None, None, &pool,
+                &user1.id, 10, SortDirection::Ascending, None, None, &pool,
             )
             .await?;
 
@@ -2511,7 +2644,7 @@ mod tests {
 
             // Act - Paginate user1's records
             let user1_page1 = EmailVerifications::index_from_user_id_cursor(
-                &user1.id, 2, None, None, &pool,
+                &user1.id, 2, SortDirection::Ascending, None, None, &pool,
             )
             .await?;
 
@@ -2521,6 +2654,7 @@ mod tests {
             let user1_page2 = EmailVerifications::index_from_user_id_cursor(
                 &user1.id,
                 2,
+                SortDirection::Ascending,
                 user1_cursor_created_at,
                 user1_cursor_id,
                 &pool,
@@ -2529,7 +2663,7 @@ mod tests {
 
             // Act - Paginate user2's records independently
             let user2_page1 = EmailVerifications::index_from_user_id_cursor(
-                &user2.id, 2, None, None, &pool,
+                &user2.id, 2, SortDirection::Ascending, None, None, &pool,
             )
             .await?;
 
@@ -2539,6 +2673,7 @@ mod tests {
             let user2_page2 = EmailVerifications::index_from_user_id_cursor(
                 &user2.id,
                 2,
+                SortDirection::Ascending,
                 user2_cursor_created_at,
                 user2_cursor_id,
                 &pool,
@@ -2590,6 +2725,7 @@ mod tests {
             let results = EmailVerifications::index_from_user_id_cursor(
                 &fake_user_id,
                 10,
+                SortDirection::Ascending,
                 None,
                 None,
                 &pool,
@@ -2612,6 +2748,7 @@ mod tests {
             let result = EmailVerifications::index_from_user_id_cursor(
                 &user.id,
                 10,
+                SortDirection::Ascending,
                 Some(chrono::Utc::now()), // created_at without cursor_id
                 None,
                 &pool,
@@ -2642,6 +2779,7 @@ mod tests {
             let result = EmailVerifications::index_from_user_id_cursor(
                 &user.id,
                 10,
+                SortDirection::Ascending,
                 None,
                 Some(uuid::Uuid::new_v4()), // cursor_id without created_at
                 &pool,
@@ -2671,7 +2809,7 @@ mod tests {
 
             // Act
             let result = EmailVerifications::index_from_user_id_cursor(
-                &user.id, 10, None, None, &pool,
+                &user.id, 10, SortDirection::Ascending, None, None, &pool,
             )
             .await?;
 
@@ -2693,7 +2831,7 @@ mod tests {
 
             // Get first page
             let page1 = EmailVerifications::index_from_user_id_cursor(
-                &user.id, 2, None, None, &pool,
+                &user.id, 2, SortDirection::Ascending, None, None, &pool,
             )
             .await?;
             assert_eq!(page1.len(), 2);
@@ -2704,6 +2842,7 @@ mod tests {
             let result = EmailVerifications::index_from_user_id_cursor(
                 &user.id,
                 2,
+                SortDirection::Ascending,
                 cursor_created_at,
                 cursor_id,
                 &pool,
@@ -2731,7 +2870,7 @@ mod tests {
 
             // Act - Get all records in pages
             let page1 = EmailVerifications::index_from_user_id_cursor(
-                &user.id, 2, None, None, &pool,
+                &user.id, 2, SortDirection::Ascending, None, None, &pool,
             )
             .await?;
             assert_eq!(page1.len(), 2);
@@ -2741,6 +2880,7 @@ mod tests {
             let page2 = EmailVerifications::index_from_user_id_cursor(
                 &user.id,
                 2,
+                SortDirection::Ascending,
                 cursor_created_at,
                 cursor_id,
                 &pool,
@@ -2754,6 +2894,7 @@ mod tests {
             let page3 = EmailVerifications::index_from_user_id_cursor(
                 &user.id,
                 2,
+                SortDirection::Ascending,
                 cursor_created_at,
                 cursor_id,
                 &pool,
@@ -2786,6 +2927,7 @@ mod tests {
                 let page = EmailVerifications::index_from_user_id_cursor(
                     &user.id,
                     3,
+                    SortDirection::Ascending,
                     cursor_created_at,
                     cursor_id,
                     &pool,
@@ -2804,7 +2946,7 @@ mod tests {
 
             // Get all records using index_from_user_id_cursor with large limit
             let all_at_once = EmailVerifications::index_from_user_id_cursor(
-                &user.id, 100, None, None, &pool,
+                &user.id, 100, SortDirection::Ascending, None, None, &pool,
             )
             .await?;
 
@@ -2840,7 +2982,7 @@ mod tests {
 
             // Act
             let result = EmailVerifications::index_from_user_id_cursor(
-                &user.id, 0, None, None, &pool,
+                &user.id, 0, SortDirection::Ascending, None, None, &pool,
             )
             .await?;
 
@@ -2859,7 +3001,7 @@ mod tests {
 
             // Act
             let result = EmailVerifications::index_from_user_id_cursor(
-                &user.id, 1, None, None, &pool,
+                &user.id, 1, SortDirection::Ascending, None, None, &pool,
             )
             .await?;
 
@@ -2879,7 +3021,7 @@ mod tests {
 
             // Act - Large but valid limit (should warn but succeed)
             let result = EmailVerifications::index_from_user_id_cursor(
-                &user.id, 1001, None, None, &pool,
+                &user.id, 1001, SortDirection::Ascending, None, None, &pool,
             )
             .await?;
 
@@ -2904,6 +3046,7 @@ mod tests {
             let result = EmailVerifications::index_from_user_id_cursor(
                 &user.id,
                 large_limit,
+                SortDirection::Ascending,
                 None,
                 None,
                 &pool,
@@ -2935,6 +3078,7 @@ mod tests {
             let result = EmailVerifications::index_from_user_id_cursor(
                 &user.id,
                 10,
+                SortDirection::Ascending,
                 fake_cursor_created_at,
                 fake_cursor_id,
                 &pool,
@@ -2961,6 +3105,7 @@ mod tests {
             let result = EmailVerifications::index_from_user_id_cursor(
                 &user.id,
                 10,
+                SortDirection::Ascending,
                 old_cursor_created_at,
                 old_cursor_id,
                 &pool,
@@ -2988,7 +3133,7 @@ mod tests {
 
             // Get cursor from user2's records
             let user2_page = EmailVerifications::index_from_user_id_cursor(
-                &user2.id, 1, None, None, &pool,
+                &user2.id, 1, SortDirection::Ascending, None, None, &pool,
             )
             .await?;
             assert_eq!(user2_page.len(), 1);
@@ -2999,6 +3144,7 @@ mod tests {
             let result = EmailVerifications::index_from_user_id_cursor(
                 &user1.id,
                 10,
+                SortDirection::Ascending,
                 cursor_created_at,
                 cursor_id,
                 &pool,
@@ -3031,6 +3177,7 @@ mod tests {
                 let page = EmailVerifications::index_from_user_id_cursor(
                     &user.id,
                     3,
+                    SortDirection::Ascending,
                     cursor_created_at,
                     cursor_id,
                     &pool,
@@ -3090,13 +3237,19 @@ mod tests {
 
             // Act - Get all records using user-specific cursor pagination
             let user_cursor_results = EmailVerifications::index_from_user_id_cursor(
-                &user.id, 100, None, None, &pool,
+                &user.id, 100, SortDirection::Ascending, None, None, &pool,
             )
             .await?;
 
             // Get all records using general cursor pagination
-            let general_cursor_results =
-                EmailVerifications::index_cursor(100, None, None, &pool).await?;
+            let general_cursor_results = EmailVerifications::index_cursor(
+                100,
+                SortDirection::Ascending,
+                None,
+                None,
+                &pool,
+            )
+            .await?;
 
             // Assert - Should have same number of records (since only one user exists)
             assert_eq!(user_cursor_results.len(), general_cursor_results.len());
@@ -3176,9 +3329,14 @@ mod tests {
 
             // Act - Try with cursor limit that would overflow i64
             let large_limit = usize::MAX;
-            let result =
-                EmailVerifications::index_cursor(large_limit, None, None, &pool)
-                    .await;
+            let result = EmailVerifications::index_cursor(
+                large_limit,
+                SortDirection::Ascending,
+                None,
+                None,
+                &pool,
+            )
+            .await;
 
             // Assert
             assert!(result.is_err());
@@ -3262,6 +3420,7 @@ mod tests {
             let result = EmailVerifications::index_from_user_id_cursor(
                 &user.id,
                 large_limit,
+                SortDirection::Ascending,
                 None,
                 None,
                 &pool,
@@ -3455,6 +3614,7 @@ mod tests {
             let future_time = chrono::Utc::now() + chrono::Duration::days(365);
             let result = EmailVerifications::index_cursor(
                 10,
+                SortDirection::Ascending,
                 Some(future_time),
                 Some(uuid::Uuid::new_v4()),
                 &pool,
@@ -3466,6 +3626,7 @@ mod tests {
             let old_time = chrono::Utc::now() - chrono::Duration::days(365);
             let result = EmailVerifications::index_cursor(
                 10,
+                SortDirection::Ascending,
                 Some(old_time),
                 Some(uuid::Uuid::new_v4()),
                 &pool,
@@ -3507,6 +3668,7 @@ mod tests {
             // Act - Use cursor with exact timestamp match
             let result = EmailVerifications::index_cursor(
                 10,
+                SortDirection::Ascending,
                 Some(v1.created_at),
                 Some(v1.id.into_uuid()),
                 &pool,
@@ -3538,8 +3700,14 @@ mod tests {
             assert_eq!(large_pages.len(), 50); // All available records
 
             // Test cursor pagination with large dataset
-            let cursor_results =
-                EmailVerifications::index_cursor(25, None, None, &pool).await?;
+            let cursor_results = EmailVerifications::index_cursor(
+                25,
+                SortDirection::Ascending,
+                None,
+                None,
+                &pool,
+            )
+            .await?;
             assert_eq!(cursor_results.len(), 25);
             Ok(())
         }
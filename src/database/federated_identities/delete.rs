@@ -0,0 +1,94 @@
+//-- ./src/database/federated_identities/delete.rs
+
+// #![allow(unused)] // For development only
+
+//! Federated identity delete logic for the authentication service.
+//!
+//! # Contents
+//! - Unlink one of a user's identities
+//! - Unit tests for delete scenarios
+
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::database::FederatedIdentities;
+use crate::prelude::*;
+
+impl FederatedIdentities {
+    /// Unlink an identity, only if it is linked to the user.
+    ///
+    /// # Parameters
+    /// * `id` - The federated identity id.
+    /// * `user_id` - The user the identity must be linked to.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of identities unlinked, 0 if it is not the user's.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Delete a federated identity from the database: ",
+        skip(database)
+    )]
+    pub async fn delete_for_user(
+        id: &Uuid,
+        user_id: &Uuid,
+        database: &Pool<Postgres>,
+    ) -> Result<u64, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                DELETE FROM federated_identities
+                WHERE id = $1 AND user_id = $2
+            "#,
+            id,
+            user_id,
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Federated identities deleted: {rows_affected}");
+
+        Ok(rows_affected)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn only_the_owner_unlinks_an_identity(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let other_user = database::Users::mock_data()?;
+        other_user.insert(&database).await?;
+        let identity = database::FederatedIdentities::mock_data(&user.id)
+            .insert(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let not_owner =
+            database::FederatedIdentities::delete_for_user(&identity.id, &other_user.id, &database)
+                .await?;
+        let owner =
+            database::FederatedIdentities::delete_for_user(&identity.id, &user.id, &database)
+                .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(not_owner, 0);
+        assert_eq!(owner, 1);
+        assert!(database::FederatedIdentities::index_user(&user.id, &database)
+            .await?
+            .is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+//-- ./src/database/federated_identities/insert.rs
+
+// #![allow(unused)] // For development only
+
+//! Federated identity insertion logic for the authentication service.
+//!
+//! # Contents
+//! - Insert a federated identity
+//! - Unit tests for insert scenarios
+
+use sqlx::PgExecutor;
+
+use crate::database::FederatedIdentities;
+use crate::prelude::*;
+
+impl FederatedIdentities {
+    /// Insert a federated identity into the database. An identity already
+    /// linked to a user fails on the unique constraint.
+    ///
+    /// # Parameters
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(FederatedIdentities)` - The inserted identity record.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Insert a federated identity into the database: ",
+        skip(self, database),
+        fields(user_id = %self.user_id, provider = %self.provider)
+    )]
+    pub async fn insert(
+        &self,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            FederatedIdentities,
+            r#"
+                INSERT INTO federated_identities (id, user_id, provider, subject, email, created_on)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                RETURNING id, user_id, provider, subject, email, created_on
+            "#,
+            self.id,
+            self.user_id,
+            self.provider,
+            self.subject,
+            self.email,
+            self.created_on,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("Federated identity inserted: {}", database_record.id);
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn identities_are_linked_to_one_user(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let other_user = database::Users::mock_data()?;
+        other_user.insert(&database).await?;
+        let identity = database::FederatedIdentities::mock_data(&user.id);
+
+        //-- Execute Function (Act)
+        let database_record = identity.insert(&database).await?;
+        let duplicate = database::FederatedIdentities::new(
+            &other_user.id,
+            &identity.provider,
+            &identity.subject,
+            &identity.email,
+        )
+        .insert(&database)
+        .await;
+
+        //-- Checks (Assertions)
+        assert_eq!(database_record, identity);
+        assert!(duplicate.is_err());
+
+        Ok(())
+    }
+}
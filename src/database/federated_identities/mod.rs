@@ -0,0 +1,23 @@
+//-- ./src/database/federated_identities/mod.rs
+
+//! Federated identities database module for the authentication service.
+//!
+//! Identities at an external identity provider, such as a SAML NameID, linked
+//! to an existing user. A linked identity logs in to its user, whatever email
+//! the identity provider asserts, so a user can sign in either way without a
+//! duplicate account.
+//!
+//! # Contents
+//! - Federated identities struct definition
+//! - Federated identities insert logic
+//! - Federated identities read logic
+//! - Federated identities delete logic
+
+// #![allow(unused)] // For development only
+
+pub use model::FederatedIdentities;
+
+mod delete;
+mod insert;
+mod model;
+mod read;
@@ -0,0 +1,62 @@
+//-- ./src/database/federated_identities/model.rs
+
+// #![allow(unused)] // For development only
+
+//! The federated identities database model.
+//!
+//! # Contents
+//! - `FederatedIdentities` struct definition
+//! - Constructor for new identities
+//! - Mock data generation for tests
+
+use chrono::{DateTime, SubsecRound, Utc};
+use uuid::Uuid;
+
+#[derive(Debug, sqlx::FromRow, Clone, PartialEq)]
+pub struct FederatedIdentities {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub provider: String,
+    pub subject: String,
+    pub email: String,
+    pub created_on: DateTime<Utc>,
+}
+
+impl FederatedIdentities {
+    /// # New Federated Identity
+    ///
+    /// Link an identity at an identity provider to a user.
+    ///
+    /// ## Parameters
+    ///
+    /// - `user_id: &Uuid` - The user the identity logs in to
+    /// - `provider: &str` - The identity provider, e.g. the SAML entity id
+    /// - `subject: &str` - The identity at the provider, e.g. the SAML NameID
+    /// - `email: &str` - The email the provider asserted
+    pub fn new(user_id: &Uuid, provider: &str, subject: &str, email: &str) -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            user_id: user_id.to_owned(),
+            provider: provider.to_string(),
+            subject: subject.to_string(),
+            email: email.to_string(),
+            created_on: Utc::now().round_subsecs(0),
+        }
+    }
+
+    #[cfg(test)]
+    /// # Mock Federated Identity Data
+    ///
+    /// This function is only available in test mode `#[cfg(test)]`.
+    /// Creates an identity with a random subject at a test identity provider.
+    pub fn mock_data(user_id: &Uuid) -> Self {
+        let subject = Uuid::now_v7().simple().to_string();
+
+        Self::new(
+            user_id,
+            "https://idp.example.com",
+            &subject,
+            &format!("{subject}@example.com"),
+        )
+    }
+}
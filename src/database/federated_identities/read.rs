@@ -0,0 +1,136 @@
+//-- ./src/database/federated_identities/read.rs
+
+// #![allow(unused)] // For development only
+
+//! Federated identity read logic for the authentication service.
+//!
+//! # Contents
+//! - Find the identity for a provider subject
+//! - List a user's identities
+//! - Unit tests for read scenarios
+
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::database::FederatedIdentities;
+use crate::database::timeouts::{with_timeout, QueryBudget};
+use crate::prelude::*;
+
+impl FederatedIdentities {
+    /// Retrieve the linked identity for a subject at an identity provider.
+    ///
+    /// # Parameters
+    /// * `provider` - The identity provider, e.g. the SAML entity id.
+    /// * `subject` - The identity at the provider, e.g. the SAML NameID.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Some(FederatedIdentities))` - The linked identity.
+    /// * `Ok(None)` - If the identity is not linked to a user.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Get a federated identity from the database: ",
+        skip(database)
+    )]
+    pub async fn from_subject(
+        provider: &str,
+        subject: &str,
+        database: &Pool<Postgres>,
+    ) -> Result<Option<Self>, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            FederatedIdentities,
+            r#"
+                SELECT id, user_id, provider, subject, email, created_on
+                FROM federated_identities
+                WHERE provider = $1 AND subject = $2
+            "#,
+            provider,
+            subject,
+        )
+        .fetch_optional(database)
+        .await?;
+
+        Ok(database_record)
+    }
+
+    /// Retrieve the identities linked to a user, oldest first.
+    ///
+    /// # Parameters
+    /// * `user_id` - The user the identities are linked to.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<FederatedIdentities>)` - The user's identities.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Get the federated identities for a user from the database: ",
+        skip(database)
+    )]
+    pub async fn index_user(
+        user_id: &Uuid,
+        database: &Pool<Postgres>,
+    ) -> Result<Vec<Self>, AuthenticationError> {
+        let database_records = with_timeout(
+            QueryBudget::Interactive,
+            "federated_identities.index_user",
+            sqlx::query_as!(
+                FederatedIdentities,
+                r#"
+                    SELECT id, user_id, provider, subject, email, created_on
+                    FROM federated_identities
+                    WHERE user_id = $1
+                    ORDER BY created_on, id
+                "#,
+                user_id,
+            )
+            .fetch_all(database),
+        )
+        .await?;
+
+        Ok(database_records)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn identities_are_found_by_subject_and_user(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let identity = database::FederatedIdentities::mock_data(&user.id)
+            .insert(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let found = database::FederatedIdentities::from_subject(
+            &identity.provider,
+            &identity.subject,
+            &database,
+        )
+        .await?;
+        let missing = database::FederatedIdentities::from_subject(
+            "https://other-idp.example.com",
+            &identity.subject,
+            &database,
+        )
+        .await?;
+        let identities = database::FederatedIdentities::index_user(&user.id, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(found, Some(identity.clone()));
+        assert!(missing.is_none());
+        assert_eq!(identities, vec![identity]);
+
+        Ok(())
+    }
+}
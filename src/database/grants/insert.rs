@@ -0,0 +1,120 @@
+//-- ./src/database/grants/insert.rs
+
+// #![allow(unused)] // For development only
+
+//! Grant insert logic for the authentication service.
+//!
+//! # Contents
+//! - Insert a grant, or update the user's existing grant to the client
+//! - Unit tests for insert scenarios
+
+use sqlx::{Pool, Postgres};
+
+use crate::database::Grants;
+use crate::prelude::*;
+
+impl Grants {
+    /// Insert this grant into the database. When the user has already granted
+    /// the client access, that grant is updated with the new scopes instead,
+    /// and made valid again if it was revoked.
+    ///
+    /// # Parameters
+    /// * `self` - The `Grants` instance to insert.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Grants)` - The inserted or updated record as returned from the database.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Insert a grant into the database: ",
+        skip(database),
+        fields(
+            user_id = %self.user_id,
+            client_id = %self.client_id,
+        )
+    )]
+    pub async fn insert(&self, database: &Pool<Postgres>) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            Grants,
+            r#"
+                INSERT INTO grants (id, user_id, client_id, scopes, created_on, updated_on, revoked_on)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT (user_id, client_id) DO UPDATE
+                SET scopes = EXCLUDED.scopes,
+                    updated_on = EXCLUDED.updated_on,
+                    revoked_on = NULL
+                RETURNING id, user_id, client_id, scopes, created_on, updated_on, revoked_on
+            "#,
+            self.id,
+            self.user_id,
+            self.client_id,
+            &self.scopes,
+            self.created_on,
+            self.updated_on,
+            self.revoked_on,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("Grant inserted: {}", database_record.id);
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn create_grant(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let (client, _secret) = database::Clients::mock_data();
+        client.insert(&database).await?;
+        let grant = database::Grants::mock_data(&user.id, &client.id);
+
+        //-- Execute Function (Act)
+        let database_record = grant.insert(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(database_record, grant);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn granting_again_updates_and_restores_the_grant(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let (client, _secret) = database::Clients::mock_data();
+        client.insert(&database).await?;
+        let first = database::Grants::mock_data(&user.id, &client.id)
+            .insert(&database)
+            .await?;
+        database::Grants::revoke(&first.id, &user.id, &database).await?;
+
+        //-- Execute Function (Act)
+        let second = database::Grants::new(&user.id, &client.id, &client.scopes)
+            .insert(&database)
+            .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(second.id, first.id);
+        assert_eq!(second.scopes, client.scopes);
+        assert!(second.is_active());
+
+        Ok(())
+    }
+}
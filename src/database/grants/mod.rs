@@ -0,0 +1,22 @@
+//-- ./src/database/grants/mod.rs
+
+//! Grants database module for the authentication service.
+//!
+//! The OAuth clients each user has granted access to, with the scopes they
+//! consented to. There is one grant per user and client, granting the client
+//! again updates it, and revoked grants are kept so the user can see them.
+//!
+//! # Contents
+//! - Grant struct definition
+//! - Grant insert (or re-grant) logic
+//! - Grant read logic
+//! - Grant revoke logic
+
+// #![allow(unused)] // For development only
+
+pub use model::Grants;
+
+mod insert;
+mod model;
+mod read;
+mod update;
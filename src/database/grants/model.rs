@@ -0,0 +1,63 @@
+//-- ./src/database/grants/model.rs
+
+// #![allow(unused)] // For development only
+
+//! The grants database model.
+//!
+//! # Contents
+//! - `Grants` struct definition
+//! - Constructor for new grant instances
+//! - Mock data generation for tests
+
+use chrono::{DateTime, SubsecRound, Utc};
+use uuid::Uuid;
+
+#[derive(Debug, sqlx::FromRow, Clone, PartialEq)]
+pub struct Grants {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub client_id: Uuid,
+    pub scopes: Vec<String>,
+    pub created_on: DateTime<Utc>,
+    pub updated_on: DateTime<Utc>,
+    pub revoked_on: Option<DateTime<Utc>>,
+}
+
+impl Grants {
+    /// # New Database Grant Instance
+    ///
+    /// Creates a new grant of access to a client by a user.
+    ///
+    /// ## Parameters
+    ///
+    /// - `user_id: &Uuid` - The user granting access
+    /// - `client_id: &Uuid` - The client being granted access
+    /// - `scopes: &[String]` - The scopes the user consented to
+    pub fn new(user_id: &Uuid, client_id: &Uuid, scopes: &[String]) -> Self {
+        let now = Utc::now().round_subsecs(0);
+
+        Self {
+            id: Uuid::now_v7(),
+            user_id: user_id.to_owned(),
+            client_id: client_id.to_owned(),
+            scopes: scopes.to_vec(),
+            created_on: now,
+            updated_on: now,
+            revoked_on: None,
+        }
+    }
+
+    /// Is the grant still valid, the user has not revoked it
+    pub fn is_active(&self) -> bool {
+        self.revoked_on.is_none()
+    }
+
+    #[cfg(test)]
+    /// # Mock Grant Data
+    ///
+    /// This function is only available in test mode `#[cfg(test)]`.
+    /// Creates a new grant by the user to the client, of a read scope.
+    pub fn mock_data(user_id: &Uuid, client_id: &Uuid) -> Self {
+        Self::new(user_id, client_id, &["users:read".to_string()])
+    }
+}
@@ -0,0 +1,127 @@
+//-- ./src/database/grants/read.rs
+
+// #![allow(unused)] // For development only
+
+//! Grant read logic for the authentication service.
+//!
+//! # Contents
+//! - Get the user's valid grant to a client
+//! - Index a user's grants
+//! - Unit tests for read scenarios
+
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::database::Grants;
+use crate::database::timeouts::{with_timeout, QueryBudget};
+use crate::prelude::*;
+
+impl Grants {
+    /// Retrieve the user's grant to the client, if it is still valid.
+    ///
+    /// # Parameters
+    /// * `user_id` - The user who granted access.
+    /// * `client_id` - The client granted access.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Some(Grants))` - The valid grant.
+    /// * `Ok(None)` - If the user has not granted the client access, or revoked it.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Get a valid grant from the database: ", skip(database))]
+    pub async fn active_for(
+        user_id: &Uuid,
+        client_id: &Uuid,
+        database: &Pool<Postgres>,
+    ) -> Result<Option<Self>, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            Grants,
+            r#"
+                SELECT id, user_id, client_id, scopes, created_on, updated_on, revoked_on
+                FROM grants
+                WHERE user_id = $1 AND client_id = $2 AND revoked_on IS NULL
+            "#,
+            user_id,
+            client_id,
+        )
+        .fetch_optional(database)
+        .await?;
+
+        Ok(database_record)
+    }
+
+    /// Retrieve all of a user's grants, most recently granted first,
+    /// including revoked grants.
+    ///
+    /// # Parameters
+    /// * `user_id` - The user who granted access.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Grants>)` - The user's grants.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Index a users grants in the database: ", skip(database))]
+    pub async fn index_user(
+        user_id: &Uuid,
+        database: &Pool<Postgres>,
+    ) -> Result<Vec<Self>, AuthenticationError> {
+        let database_records = with_timeout(
+            QueryBudget::Interactive,
+            "grants.index_user",
+            sqlx::query_as!(
+                Grants,
+                r#"
+                    SELECT id, user_id, client_id, scopes, created_on, updated_on, revoked_on
+                    FROM grants
+                    WHERE user_id = $1
+                    ORDER BY updated_on DESC, id DESC
+                "#,
+                user_id,
+            )
+            .fetch_all(database),
+        )
+        .await?;
+
+        tracing::debug!("Grants retrieved: {}", database_records.len());
+
+        Ok(database_records)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn only_valid_grants_are_active(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let (client, _secret) = database::Clients::mock_data();
+        client.insert(&database).await?;
+        let grant = database::Grants::mock_data(&user.id, &client.id)
+            .insert(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let active = database::Grants::active_for(&user.id, &client.id, &database).await?;
+        database::Grants::revoke(&grant.id, &user.id, &database).await?;
+        let revoked = database::Grants::active_for(&user.id, &client.id, &database).await?;
+        let index = database::Grants::index_user(&user.id, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(active, Some(grant.clone()));
+        assert_eq!(revoked, None);
+        assert_eq!(index.len(), 1);
+        assert!(!index[0].is_active());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+//-- ./src/database/grants/update.rs
+
+// #![allow(unused)] // For development only
+
+//! Grant revoke logic for the authentication service.
+//!
+//! # Contents
+//! - Revoke one of a user's grants
+//! - Unit tests for revoke scenarios
+
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::database::Grants;
+use crate::prelude::*;
+
+impl Grants {
+    /// Revoke one of the user's grants, so the client can no longer get
+    /// tokens for the user. The grant must belong to the user.
+    ///
+    /// # Parameters
+    /// * `id` - The grant id.
+    /// * `user_id` - The user the grant must belong to.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Some(Grants))` - The revoked grant.
+    /// * `Ok(None)` - If the user has no such grant, or it is already revoked.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Revoke a grant in the database: ", skip(database))]
+    pub async fn revoke(
+        id: &Uuid,
+        user_id: &Uuid,
+        database: &Pool<Postgres>,
+    ) -> Result<Option<Self>, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            Grants,
+            r#"
+                UPDATE grants
+                SET revoked_on = NOW()
+                WHERE id = $1 AND user_id = $2 AND revoked_on IS NULL
+                RETURNING id, user_id, client_id, scopes, created_on, updated_on, revoked_on
+            "#,
+            id,
+            user_id,
+        )
+        .fetch_optional(database)
+        .await?;
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn users_can_only_revoke_their_own_grants(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let other_user = database::Users::mock_data()?;
+        other_user.insert(&database).await?;
+        let (client, _secret) = database::Clients::mock_data();
+        client.insert(&database).await?;
+        let grant = database::Grants::mock_data(&user.id, &client.id)
+            .insert(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let by_other_user = database::Grants::revoke(&grant.id, &other_user.id, &database).await?;
+        let by_user = database::Grants::revoke(&grant.id, &user.id, &database).await?;
+        let again = database::Grants::revoke(&grant.id, &user.id, &database).await?;
+
+        //-- Checks (Assertions)
+        assert!(by_other_user.is_none());
+        assert!(by_user.is_some_and(|grant| !grant.is_active()));
+        assert!(again.is_none());
+
+        Ok(())
+    }
+}
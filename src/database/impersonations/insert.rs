@@ -0,0 +1,91 @@
+//-- ./src/database/impersonations/insert.rs
+
+// #![allow(unused)] // For development only
+
+//! Impersonation insert logic for the authentication service.
+//!
+//! # Contents
+//! - Insert an impersonation
+//! - Unit tests for insert scenarios
+
+use sqlx::PgExecutor;
+
+use crate::database::Impersonations;
+use crate::prelude::*;
+
+impl Impersonations {
+    /// Insert this impersonation into the database.
+    ///
+    /// # Parameters
+    /// * `self` - The `Impersonations` instance to insert.
+    /// * `database` - The SQLx PostgreSQL connection pool or transaction.
+    ///
+    /// # Returns
+    /// * `Ok(Impersonations)` - The inserted record as returned from the database.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Insert an impersonation into the database: ",
+        skip(database),
+        fields(
+            admin_id = %self.admin_id,
+            user_id = %self.user_id,
+        )
+    )]
+    pub async fn insert(
+        &self,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            Impersonations,
+            r#"
+                INSERT INTO impersonations (id, admin_id, user_id, reason, access_token_id, created_on, expires_on, revoked_on, revoked_by)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                RETURNING id, admin_id, user_id, reason, access_token_id, created_on, expires_on, revoked_on, revoked_by
+            "#,
+            self.id,
+            self.admin_id,
+            self.user_id,
+            self.reason,
+            self.access_token_id,
+            self.created_on,
+            self.expires_on,
+            self.revoked_on,
+            self.revoked_by,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("Impersonation inserted: {}", database_record.id);
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn create_impersonation(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let admin = database::Users::mock_data()?;
+        let user = database::Users::mock_data()?;
+        let impersonation = database::Impersonations::mock_data(&admin.id, &user.id);
+
+        //-- Execute Function (Act)
+        let database_record = impersonation.insert(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(database_record, impersonation);
+        assert!(database_record.is_active());
+
+        Ok(())
+    }
+}
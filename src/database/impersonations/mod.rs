@@ -0,0 +1,22 @@
+//-- ./src/database/impersonations/mod.rs
+
+//! Impersonations database module for the authentication service.
+//!
+//! The audit trail of admins impersonating users, with the reason given and
+//! the id of the impersonation access token, so it can be revoked centrally.
+//! Rows are kept after the admin or user is deleted.
+//!
+//! # Contents
+//! - Impersonation struct definition
+//! - Impersonation insert logic
+//! - Impersonation read logic
+//! - Impersonation revoke logic
+
+// #![allow(unused)] // For development only
+
+pub use model::Impersonations;
+
+mod insert;
+mod model;
+mod read;
+mod update;
@@ -0,0 +1,79 @@
+//-- ./src/database/impersonations/model.rs
+
+// #![allow(unused)] // For development only
+
+//! The impersonations database model.
+//!
+//! # Contents
+//! - `Impersonations` struct definition
+//! - Constructor for new impersonation instances
+//! - Mock data generation for tests
+
+use chrono::{DateTime, SubsecRound, Utc};
+use uuid::Uuid;
+
+#[derive(Debug, sqlx::FromRow, Clone, PartialEq)]
+pub struct Impersonations {
+    pub id: Uuid,
+    pub admin_id: Uuid,
+    pub user_id: Uuid,
+    pub reason: String,
+    pub access_token_id: Uuid,
+    pub created_on: DateTime<Utc>,
+    pub expires_on: DateTime<Utc>,
+    pub revoked_on: Option<DateTime<Utc>>,
+    pub revoked_by: Option<Uuid>,
+}
+
+impl Impersonations {
+    /// # New Database Impersonation Instance
+    ///
+    /// Creates a new record of an admin impersonating a user.
+    ///
+    /// ## Parameters
+    ///
+    /// - `admin_id: &Uuid` - The admin impersonating the user
+    /// - `user_id: &Uuid` - The user being impersonated
+    /// - `reason: &str` - Why the admin is impersonating the user
+    /// - `access_token_id: &Uuid` - The jti of the impersonation access token
+    /// - `expires_on: &DateTime<Utc>` - When the impersonation access token expires
+    pub fn new(
+        admin_id: &Uuid,
+        user_id: &Uuid,
+        reason: &str,
+        access_token_id: &Uuid,
+        expires_on: &DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            admin_id: admin_id.to_owned(),
+            user_id: user_id.to_owned(),
+            reason: reason.to_string(),
+            access_token_id: access_token_id.to_owned(),
+            created_on: Utc::now().round_subsecs(0),
+            expires_on: expires_on.round_subsecs(0),
+            revoked_on: None,
+            revoked_by: None,
+        }
+    }
+
+    /// Is the impersonation access token still usable, it is not revoked or expired
+    pub fn is_active(&self) -> bool {
+        self.revoked_on.is_none() && self.expires_on > Utc::now()
+    }
+
+    #[cfg(test)]
+    /// # Mock Impersonation Data
+    ///
+    /// This function is only available in test mode `#[cfg(test)]`.
+    /// Creates a new impersonation of the user by the admin, expiring in 15 minutes.
+    pub fn mock_data(admin_id: &Uuid, user_id: &Uuid) -> Self {
+        Self::new(
+            admin_id,
+            user_id,
+            "Reproduce a support ticket",
+            &Uuid::now_v7(),
+            &(Utc::now() + chrono::Duration::minutes(15)),
+        )
+    }
+}
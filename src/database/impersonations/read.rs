@@ -0,0 +1,125 @@
+//-- ./src/database/impersonations/read.rs
+
+// #![allow(unused)] // For development only
+
+//! Impersonation read logic for the authentication service.
+//!
+//! # Contents
+//! - Get an impersonation by id
+//! - Index impersonations
+//! - Unit tests for read scenarios
+
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::database::Impersonations;
+use crate::database::timeouts::{with_timeout, QueryBudget};
+use crate::prelude::*;
+
+impl Impersonations {
+    /// Retrieve an impersonation by its id.
+    ///
+    /// # Parameters
+    /// * `id` - The impersonation id.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Some(Impersonations))` - The impersonation.
+    /// * `Ok(None)` - If there is no such impersonation.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Get an impersonation from the database: ", skip(database))]
+    pub async fn from_id(
+        id: &Uuid,
+        database: &Pool<Postgres>,
+    ) -> Result<Option<Self>, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            Impersonations,
+            r#"
+                SELECT id, admin_id, user_id, reason, access_token_id, created_on, expires_on, revoked_on, revoked_by
+                FROM impersonations
+                WHERE id = $1
+            "#,
+            id,
+        )
+        .fetch_optional(database)
+        .await?;
+
+        Ok(database_record)
+    }
+
+    /// Retrieve a page of impersonations, newest first.
+    ///
+    /// # Parameters
+    /// * `limit` - The maximum number of records to return.
+    /// * `offset` - The number of records to skip.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Impersonations>)` - The page of impersonations.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Index impersonations in the database: ", skip(database))]
+    pub async fn index(
+        limit: &usize,
+        offset: &usize,
+        database: &Pool<Postgres>,
+    ) -> Result<Vec<Self>, AuthenticationError> {
+        let database_records = with_timeout(
+            QueryBudget::Interactive,
+            "impersonations.index",
+            sqlx::query_as!(
+                Impersonations,
+                r#"
+                    SELECT id, admin_id, user_id, reason, access_token_id, created_on, expires_on, revoked_on, revoked_by
+                    FROM impersonations
+                    ORDER BY created_on DESC, id DESC
+                    LIMIT $1 OFFSET $2
+                "#,
+                *limit as i64,
+                *offset as i64,
+            )
+            .fetch_all(database),
+        )
+        .await?;
+
+        tracing::debug!("Impersonations retrieved: {}", database_records.len());
+
+        Ok(database_records)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+    use uuid::Uuid;
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn impersonations_are_indexed_newest_first(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let admin_id = Uuid::now_v7();
+        let first = database::Impersonations::mock_data(&admin_id, &Uuid::now_v7())
+            .insert(&database)
+            .await?;
+        let second = database::Impersonations::mock_data(&admin_id, &Uuid::now_v7())
+            .insert(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let index = database::Impersonations::index(&10, &0, &database).await?;
+        let found = database::Impersonations::from_id(&first.id, &database).await?;
+        let missing = database::Impersonations::from_id(&Uuid::now_v7(), &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(index, vec![second, first.clone()]);
+        assert_eq!(found, Some(first));
+        assert_eq!(missing, None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+//-- ./src/database/impersonations/update.rs
+
+// #![allow(unused)] // For development only
+
+//! Impersonation revoke logic for the authentication service.
+//!
+//! # Contents
+//! - Revoke an impersonation
+//! - Unit tests for revoke scenarios
+
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::database::Impersonations;
+use crate::prelude::*;
+
+impl Impersonations {
+    /// Revoke an impersonation, recording the admin who revoked it. The
+    /// impersonation access token must also be added to the denylist.
+    ///
+    /// # Parameters
+    /// * `id` - The impersonation id.
+    /// * `revoked_by` - The admin revoking the impersonation, `None` for API keys.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Some(Impersonations))` - The revoked impersonation.
+    /// * `Ok(None)` - If there is no such impersonation, or it is already revoked.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Revoke an impersonation in the database: ", skip(database))]
+    pub async fn revoke(
+        id: &Uuid,
+        revoked_by: Option<&Uuid>,
+        database: &Pool<Postgres>,
+    ) -> Result<Option<Self>, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            Impersonations,
+            r#"
+                UPDATE impersonations
+                SET revoked_on = NOW(), revoked_by = $2
+                WHERE id = $1 AND revoked_on IS NULL
+                RETURNING id, admin_id, user_id, reason, access_token_id, created_on, expires_on, revoked_on, revoked_by
+            "#,
+            id,
+            revoked_by,
+        )
+        .fetch_optional(database)
+        .await?;
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+    use uuid::Uuid;
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn impersonations_are_only_revoked_once(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let admin_id = Uuid::now_v7();
+        let impersonation = database::Impersonations::mock_data(&admin_id, &Uuid::now_v7())
+            .insert(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let revoked =
+            database::Impersonations::revoke(&impersonation.id, Some(&admin_id), &database)
+                .await?;
+        let again =
+            database::Impersonations::revoke(&impersonation.id, Some(&admin_id), &database)
+                .await?;
+
+        //-- Checks (Assertions)
+        let revoked = revoked.ok_or("Impersonation was not revoked")?;
+        assert!(!revoked.is_active());
+        assert_eq!(revoked.revoked_by, Some(admin_id));
+        assert!(again.is_none());
+
+        Ok(())
+    }
+}
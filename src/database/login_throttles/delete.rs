@@ -0,0 +1,140 @@
+//-- ./src/database/login_throttles/delete.rs
+
+// #![allow(unused)] // For development only
+
+//! Login throttle delete logic for the authentication service.
+//!
+//! # Contents
+//! - Delete the throttle for an IP address and email, after a successful login
+//! - Delete throttles with no recent failures
+//! - Unit tests for delete scenarios
+
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+
+use crate::database::LoginThrottles;
+use crate::prelude::*;
+
+impl LoginThrottles {
+    /// Delete the throttle for an IP address and email.
+    ///
+    /// # Parameters
+    /// * `ip_address` - The IP address the login came from.
+    /// * `email` - The lower case email the login was for.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of throttles deleted.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Delete a login throttle from the database: ",
+        skip(database)
+    )]
+    pub async fn delete_key(
+        ip_address: &str,
+        email: &str,
+        database: &Pool<Postgres>,
+    ) -> Result<u64, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                DELETE FROM login_throttles
+                WHERE ip_address = $1 AND email = $2
+            "#,
+            ip_address,
+            email,
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        Ok(rows_affected)
+    }
+
+    /// Delete throttles whose last failure was before `failed_before` and
+    /// that are no longer locked.
+    ///
+    /// # Parameters
+    /// * `failed_before` - Throttles last failing before this time are deleted.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of throttles deleted.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Delete stale login throttles: ", skip(database))]
+    pub async fn delete_stale(
+        failed_before: &DateTime<Utc>,
+        database: &Pool<Postgres>,
+    ) -> Result<u64, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                DELETE FROM login_throttles
+                WHERE last_failure_at < $1 AND (locked_until IS NULL OR locked_until < NOW())
+            "#,
+            failed_before
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Login throttles deleted: {rows_affected}");
+
+        Ok(rows_affected)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use chrono::{Duration, Utc};
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn stale_throttles_are_deleted(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        database::LoginThrottles::record_failure(
+            "203.0.113.7",
+            "a@example.com",
+            &3600,
+            &database,
+        )
+        .await?;
+        database::LoginThrottles::record_failure(
+            "203.0.113.8",
+            "b@example.com",
+            &3600,
+            &database,
+        )
+        .await?;
+
+        //-- Execute Function (Act)
+        let recent = database::LoginThrottles::delete_stale(
+            &(Utc::now() - Duration::hours(1)),
+            &database,
+        )
+        .await?;
+        let stale = database::LoginThrottles::delete_stale(
+            &(Utc::now() + Duration::hours(1)),
+            &database,
+        )
+        .await?;
+        let cleared = database::LoginThrottles::delete_key(
+            "203.0.113.7",
+            "a@example.com",
+            &database,
+        )
+        .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(recent, 0);
+        assert_eq!(stale, 2);
+        assert_eq!(cleared, 0);
+
+        Ok(())
+    }
+}
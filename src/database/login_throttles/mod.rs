@@ -0,0 +1,21 @@
+//-- ./src/database/login_throttles/mod.rs
+
+//! Login throttles database module for the authentication service.
+//!
+//! Failed logins are counted per IP address and email. Once too many have
+//! failed, logins for the pair are locked out for an increasing delay.
+//!
+//! # Contents
+//! - Login throttle struct definition
+//! - Login throttle read logic
+//! - Record failures and lock outs
+//! - Login throttle delete logic
+
+// #![allow(unused)] // For development only
+
+pub use model::LoginThrottles;
+
+mod delete;
+mod model;
+mod read;
+mod update;
@@ -0,0 +1,53 @@
+//-- ./src/database/login_throttles/model.rs
+
+// #![allow(unused)] // For development only
+
+//! The login throttles database model.
+//!
+//! # Contents
+//! - `LoginThrottles` struct definition
+//! - Helpers for the lock out
+
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, sqlx::FromRow, Clone, PartialEq)]
+pub struct LoginThrottles {
+    pub ip_address: String,
+    pub email: String,
+    pub failures: i32,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub last_failure_at: DateTime<Utc>,
+}
+
+impl LoginThrottles {
+    /// How long logins are still locked out for, `None` when they are not
+    pub fn retry_after(&self) -> Option<chrono::Duration> {
+        self.locked_until
+            .map(|locked_until| locked_until - Utc::now())
+            .filter(|remaining| *remaining > chrono::Duration::zero())
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_after_is_only_set_while_locked() {
+        let mut throttle = LoginThrottles {
+            ip_address: "203.0.113.7".to_string(),
+            email: "someone@example.com".to_string(),
+            failures: 4,
+            locked_until: None,
+            last_failure_at: Utc::now(),
+        };
+        assert!(throttle.retry_after().is_none());
+
+        throttle.locked_until = Some(Utc::now() - chrono::Duration::seconds(5));
+        assert!(throttle.retry_after().is_none());
+
+        throttle.locked_until = Some(Utc::now() + chrono::Duration::seconds(60));
+        assert!(throttle.retry_after().is_some());
+    }
+}
@@ -0,0 +1,178 @@
+//-- ./src/database/login_throttles/read.rs
+
+// #![allow(unused)] // For development only
+
+//! Login throttle read logic for the authentication service.
+//!
+//! # Contents
+//! - Get the throttle for an IP address and email
+//! - Index the IP address and email pairs locked out now
+//! - Unit tests for read scenarios
+
+use sqlx::{Pool, Postgres};
+
+use crate::database::LoginThrottles;
+use crate::database::timeouts::{with_timeout, QueryBudget};
+use crate::prelude::*;
+
+impl LoginThrottles {
+    /// Retrieve the throttle for an IP address and email.
+    ///
+    /// # Parameters
+    /// * `ip_address` - The IP address logins came from.
+    /// * `email` - The lower case email logins were for.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Some(LoginThrottles))` - The throttle, if logins have failed.
+    /// * `Ok(None)` - If no failed logins are recorded.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Get a login throttle from the database: ",
+        skip(database)
+    )]
+    pub async fn from_key(
+        ip_address: &str,
+        email: &str,
+        database: &Pool<Postgres>,
+    ) -> Result<Option<Self>, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            LoginThrottles,
+            r#"
+                SELECT ip_address, email, failures, locked_until, last_failure_at
+                FROM login_throttles
+                WHERE ip_address = $1 AND email = $2
+            "#,
+            ip_address,
+            email,
+        )
+        .fetch_optional(database)
+        .await?;
+
+        Ok(database_record)
+    }
+
+    /// Retrieve the IP address and email pairs that are locked out now, the
+    /// longest lock out first.
+    ///
+    /// # Parameters
+    /// * `limit` - The maximum number of throttles to return.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<LoginThrottles>)` - The throttles locked out now.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Index locked login throttles from the database: ",
+        skip(database)
+    )]
+    pub async fn index_locked(
+        limit: &usize,
+        database: &Pool<Postgres>,
+    ) -> Result<Vec<Self>, AuthenticationError> {
+        let limit = *limit as i64;
+
+        let database_records = with_timeout(
+            QueryBudget::Interactive,
+            "login_throttles.index_locked",
+            sqlx::query_as!(
+                LoginThrottles,
+                r#"
+                    SELECT ip_address, email, failures, locked_until, last_failure_at
+                    FROM login_throttles
+                    WHERE locked_until > NOW()
+                    ORDER BY locked_until DESC, ip_address, email
+                    LIMIT $1
+                "#,
+                limit,
+            )
+            .fetch_all(database),
+        )
+        .await?;
+
+        Ok(database_records)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use chrono::{Duration, Utc};
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn from_key_matches_ip_address_and_email(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        database::LoginThrottles::record_failure(
+            "203.0.113.7",
+            "a@example.com",
+            &3600,
+            &database,
+        )
+        .await?;
+
+        //-- Execute Function (Act)
+        let found = database::LoginThrottles::from_key(
+            "203.0.113.7",
+            "a@example.com",
+            &database,
+        )
+        .await?;
+        let other_ip = database::LoginThrottles::from_key(
+            "203.0.113.8",
+            "a@example.com",
+            &database,
+        )
+        .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(found.map(|throttle| throttle.failures), Some(1));
+        assert!(other_ip.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn index_locked_only_returns_current_lock_outs(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let locked = database::LoginThrottles::record_failure(
+            "203.0.113.7",
+            "a@example.com",
+            &3600,
+            &database,
+        )
+        .await?;
+        locked
+            .lock_until(&(Utc::now() + Duration::minutes(5)), &database)
+            .await?;
+        let expired = database::LoginThrottles::record_failure(
+            "203.0.113.8",
+            "b@example.com",
+            &3600,
+            &database,
+        )
+        .await?;
+        expired
+            .lock_until(&(Utc::now() - Duration::minutes(5)), &database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let index = database::LoginThrottles::index_locked(&10, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(index.len(), 1);
+        assert_eq!(index[0].ip_address, "203.0.113.7");
+
+        Ok(())
+    }
+}
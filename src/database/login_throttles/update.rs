@@ -0,0 +1,155 @@
+//-- ./src/database/login_throttles/update.rs
+
+// #![allow(unused)] // For development only
+
+//! Login throttle failure and lock out logic for the authentication service.
+//!
+//! # Contents
+//! - Record a failed login
+//! - Lock out logins until a given time
+//! - Unit tests for update scenarios
+
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+
+use crate::database::LoginThrottles;
+use crate::prelude::*;
+
+impl LoginThrottles {
+    /// Record a failed login for an IP address and email, returning the
+    /// updated throttle.
+    ///
+    /// The count starts again when the last failure was more than
+    /// `reset_after_seconds` ago.
+    ///
+    /// # Parameters
+    /// * `ip_address` - The IP address the login came from.
+    /// * `email` - The lower case email the login was for.
+    /// * `reset_after_seconds` - How long without failures before the count resets.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(LoginThrottles)` - The updated throttle.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Record a failed login in the database: ",
+        skip(database)
+    )]
+    pub async fn record_failure(
+        ip_address: &str,
+        email: &str,
+        reset_after_seconds: &u64,
+        database: &Pool<Postgres>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            LoginThrottles,
+            r#"
+                INSERT INTO login_throttles (ip_address, email, failures, last_failure_at)
+                VALUES ($1, $2, 1, NOW())
+                ON CONFLICT (ip_address, email) DO UPDATE
+                SET failures = CASE
+                        WHEN login_throttles.last_failure_at < NOW() - make_interval(secs => $3)
+                        THEN 1
+                        ELSE login_throttles.failures + 1
+                    END,
+                    last_failure_at = NOW()
+                RETURNING ip_address, email, failures, locked_until, last_failure_at
+            "#,
+            ip_address,
+            email,
+            *reset_after_seconds as f64,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("Failed logins recorded: {}", database_record.failures);
+
+        Ok(database_record)
+    }
+
+    /// Lock out logins for the throttle's IP address and email until a time.
+    ///
+    /// # Parameters
+    /// * `self` - The throttle to lock.
+    /// * `locked_until` - Logins are rejected until this time.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(LoginThrottles)` - The updated throttle.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Lock out logins in the database: ",
+        skip(self, database),
+        fields(ip_address = %self.ip_address)
+    )]
+    pub async fn lock_until(
+        &self,
+        locked_until: &DateTime<Utc>,
+        database: &Pool<Postgres>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            LoginThrottles,
+            r#"
+                UPDATE login_throttles
+                SET locked_until = $3
+                WHERE ip_address = $1 AND email = $2
+                RETURNING ip_address, email, failures, locked_until, last_failure_at
+            "#,
+            self.ip_address,
+            self.email,
+            locked_until,
+        )
+        .fetch_one(database)
+        .await?;
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use chrono::{Duration, Utc};
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn failures_are_counted_and_reset(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let (ip_address, email) = ("203.0.113.7", "a@example.com");
+
+        //-- Execute Function (Act)
+        database::LoginThrottles::record_failure(
+            ip_address, email, &3600, &database,
+        )
+        .await?;
+        let second = database::LoginThrottles::record_failure(
+            ip_address, email, &3600, &database,
+        )
+        .await?;
+        let locked = second
+            .lock_until(&(Utc::now() + Duration::minutes(1)), &database)
+            .await?;
+
+        // Age the last failure past the reset window
+        sqlx::query("UPDATE login_throttles SET last_failure_at = NOW() - INTERVAL '2 hours'")
+            .execute(&database)
+            .await?;
+        let reset = database::LoginThrottles::record_failure(
+            ip_address, email, &3600, &database,
+        )
+        .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(second.failures, 2);
+        assert!(locked.retry_after().is_some());
+        assert_eq!(reset.failures, 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+//-- ./src/database/logins/insert.rs
+
+// #![allow(unused)] // For development only
+
+//! Login insert logic for the authentication service.
+//!
+//! # Contents
+//! - Insert a login attempt
+//! - Unit tests for insert scenarios
+
+use sqlx::PgExecutor;
+
+use crate::database::{LoginOutcome, Logins};
+use crate::prelude::*;
+
+impl Logins {
+    /// Insert the login attempt into the database, linking it to the user with
+    /// a matching email.
+    ///
+    /// # Parameters
+    /// * `self` - The login attempt to insert.
+    /// * `database` - The SQLx PostgreSQL connection pool or transaction.
+    ///
+    /// # Returns
+    /// * `Ok(Logins)` - The inserted login attempt, with its user id.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Insert a login attempt into the database: ",
+        skip(self, database),
+        fields(outcome = %self.outcome)
+    )]
+    pub async fn insert(
+        &self,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            Logins,
+            r#"
+                INSERT INTO logins (id, user_id, email, ip_address, user_agent, outcome, created_on)
+                VALUES ($1, (SELECT id FROM users WHERE email = $2 AND deleted_at IS NULL), $2, $3, $4, $5, $6)
+                RETURNING id, user_id, email, ip_address, user_agent, outcome as "outcome:LoginOutcome", created_on
+            "#,
+            self.id,
+            self.email,
+            self.ip_address,
+            self.user_agent,
+            self.outcome as LoginOutcome,
+            self.created_on,
+        )
+        .fetch_one(database)
+        .await?;
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn insert_links_the_user_by_email(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?.insert(&database).await?;
+        let login = database::Logins::mock_data(user.email.as_ref());
+        let unknown = database::Logins::mock_data("unknown@example.com");
+
+        //-- Execute Function (Act)
+        let inserted = login.insert(&database).await?;
+        let inserted_unknown = unknown.insert(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(inserted.user_id, Some(user.id));
+        assert_eq!(inserted.id, login.id);
+        assert_eq!(inserted.user_agent, login.user_agent);
+        assert_eq!(inserted_unknown.user_id, None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,22 @@
+//-- ./src/database/logins/mod.rs
+
+//! Logins database module for the authentication service.
+//!
+//! Every login attempt, successful or not, is recorded with the IP address,
+//! user agent and outcome, so users can review their login history.
+//!
+//! # Contents
+//! - Login struct and outcome definitions
+//! - Login insert logic
+//! - Login read logic, with cursor pagination
+//! - Failed login reports, grouped by IP address and email
+
+// #![allow(unused)] // For development only
+
+pub use model::{LoginOutcome, Logins};
+pub use report::{LoginFailuresByEmail, LoginFailuresByIp};
+
+mod insert;
+mod model;
+mod read;
+mod report;
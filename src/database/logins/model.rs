@@ -0,0 +1,98 @@
+//-- ./src/database/logins/model.rs
+
+// #![allow(unused)] // For development only
+
+//! The logins database model.
+//!
+//! # Contents
+//! - `LoginOutcome` enum definition
+//! - `Logins` struct definition
+//! - Constructor for new login records
+//! - Mock data generation for tests
+
+use chrono::{DateTime, SubsecRound, Utc};
+use uuid::Uuid;
+
+/// How a login attempt ended
+#[derive(Debug, Clone, Copy, PartialEq, sqlx::Type, serde::Deserialize)]
+#[sqlx(type_name = "login_outcome", rename_all = "lowercase")]
+pub enum LoginOutcome {
+    /// The credentials were valid and a session was started
+    Success,
+    /// The credentials, or CAPTCHA, were not valid
+    Failed,
+    /// The login was rejected while locked out after repeated failures
+    Throttled,
+}
+
+impl LoginOutcome {
+    /// Convert LoginOutcome to a string reference
+    pub fn to_str(&self) -> &str {
+        match self {
+            LoginOutcome::Success => "success",
+            LoginOutcome::Failed => "failed",
+            LoginOutcome::Throttled => "throttled",
+        }
+    }
+}
+
+impl std::fmt::Display for LoginOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+#[derive(Debug, sqlx::FromRow, Clone, PartialEq)]
+pub struct Logins {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub email: String,
+    pub ip_address: String,
+    pub user_agent: Option<String>,
+    pub outcome: LoginOutcome,
+    pub created_on: DateTime<Utc>,
+}
+
+impl Logins {
+    /// # New Database Logins Instance
+    ///
+    /// Creates a new login attempt record. The user id is looked up from the
+    /// email when the record is inserted.
+    ///
+    /// ## Parameters
+    ///
+    /// - `email: &str` - The email the login was for, as sent
+    /// - `ip_address: &str` - The address the login came from
+    /// - `user_agent: Option<&str>` - The client `user-agent`, if sent
+    /// - `outcome: LoginOutcome` - How the login attempt ended
+    pub fn new(
+        email: &str,
+        ip_address: &str,
+        user_agent: Option<&str>,
+        outcome: LoginOutcome,
+    ) -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            user_id: None,
+            email: email.to_string(),
+            ip_address: ip_address.to_string(),
+            user_agent: user_agent.map(str::to_string),
+            outcome,
+            created_on: Utc::now().round_subsecs(0),
+        }
+    }
+
+    #[cfg(test)]
+    /// # Mock Logins Data
+    ///
+    /// This function is only available in test mode `#[cfg(test)]`.
+    /// Creates a new successful login record for the email.
+    pub fn mock_data(email: &str) -> Self {
+        Self::new(
+            email,
+            "203.0.113.7",
+            Some("Mozilla/5.0"),
+            LoginOutcome::Success,
+        )
+    }
+}
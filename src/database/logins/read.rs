@@ -0,0 +1,145 @@
+//-- ./src/database/logins/read.rs
+
+// #![allow(unused)] // For development only
+
+//! Login read logic for the authentication service.
+//!
+//! # Contents
+//! - Index a user's login attempts with cursor pagination
+//! - Unit tests for read scenarios
+
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::database::{LoginOutcome, Logins};
+use crate::database::timeouts::{with_timeout, QueryBudget};
+use crate::prelude::*;
+
+impl Logins {
+    /// Retrieve a page of a user's login attempts, newest first.
+    ///
+    /// # Parameters
+    /// * `user_id` - The user whose login attempts are returned.
+    /// * `limit` - The maximum number of login attempts to return.
+    /// * `cursor_created_on` - The `created_on` of the last login from the previous page.
+    /// * `cursor_id` - The `id` of the last login from the previous page.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Logins>)` - Login attempts before the cursor, ordered by `(created_on, id)` descending.
+    /// * `Err(AuthenticationError)` - If the cursor is incomplete or the query fails.
+    ///
+    /// # Notes
+    /// - Both cursor values must be provided together, or neither for the first page.
+    #[tracing::instrument(
+        name = "Index a user's logins in the database: ",
+        skip(database)
+    )]
+    pub async fn index_user(
+        user_id: &Uuid,
+        limit: &usize,
+        cursor_created_on: Option<DateTime<Utc>>,
+        cursor_id: Option<Uuid>,
+        database: &Pool<Postgres>,
+    ) -> Result<Vec<Self>, AuthenticationError> {
+        // Validate cursor consistency
+        if cursor_created_on.is_some() != cursor_id.is_some() {
+            return Err(AuthenticationError::ValidationError(
+                "Both cursor_created_on and cursor_id must be provided together"
+                    .to_string(),
+            ));
+        }
+
+        let limit = i64::try_from(*limit).map_err(|_| {
+            AuthenticationError::ValidationError(
+                "Pagination value too large".to_string(),
+            )
+        })?;
+
+        let database_records = with_timeout(
+            QueryBudget::Interactive,
+            "logins.index_user",
+            sqlx::query_as!(
+                Logins,
+                r#"
+                    SELECT id, user_id, email, ip_address, user_agent, outcome as "outcome:LoginOutcome", created_on
+                    FROM logins
+                    WHERE user_id = $1
+                    AND ($2::TIMESTAMPTZ IS NULL OR (created_on, id) < ($2, $3))
+                    ORDER BY created_on DESC, id DESC
+                    LIMIT $4
+                "#,
+                user_id,
+                cursor_created_on,
+                cursor_id,
+                limit,
+            )
+            .fetch_all(database),
+        )
+        .await?;
+
+        tracing::debug!("Login records retrieved: {}", database_records.len());
+
+        Ok(database_records)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn index_user_pages_newest_first(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?.insert(&database).await?;
+        let mut logins = Vec::new();
+        for _ in 0..3 {
+            logins.push(
+                database::Logins::mock_data(user.email.as_ref())
+                    .insert(&database)
+                    .await?,
+            );
+        }
+        database::Logins::mock_data("someone-else@example.com")
+            .insert(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let first_page =
+            database::Logins::index_user(&user.id, &2, None, None, &database)
+                .await?;
+        let last = first_page.last().unwrap();
+        let second_page = database::Logins::index_user(
+            &user.id,
+            &2,
+            Some(last.created_on),
+            Some(last.id),
+            &database,
+        )
+        .await?;
+        let incomplete_cursor = database::Logins::index_user(
+            &user.id,
+            &2,
+            Some(last.created_on),
+            None,
+            &database,
+        )
+        .await;
+
+        //-- Checks (Assertions)
+        // Ids are UUID v7, so newer logins have larger ids within the same second
+        assert_eq!(first_page, vec![logins[2].clone(), logins[1].clone()]);
+        assert_eq!(second_page, vec![logins[0].clone()]);
+        assert!(incomplete_cursor.is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,209 @@
+//-- ./src/database/logins/report.rs
+
+// #![allow(unused)] // For development only
+
+//! Failed login reports for the authentication service.
+//!
+//! Groups the failed and throttled logins within a time window by IP address
+//! and by email, so admins can spot password guessing and credential stuffing.
+//!
+//! # Contents
+//! - `LoginFailuresByIp` and `LoginFailuresByEmail` struct definitions
+//! - Group failed logins by IP address
+//! - Group failed logins by email
+//! - Unit tests for report scenarios
+
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::database::Logins;
+use crate::database::timeouts::{with_timeout, QueryBudget};
+use crate::prelude::*;
+
+/// The unsuccessful logins from an IP address
+#[derive(Debug, sqlx::FromRow, Clone, PartialEq)]
+pub struct LoginFailuresByIp {
+    pub ip_address: String,
+    pub failed: i64,
+    pub throttled: i64,
+    /// How many emails logins were tried for
+    pub emails: i64,
+    pub last_attempt_on: DateTime<Utc>,
+}
+
+/// The unsuccessful logins for an email
+#[derive(Debug, sqlx::FromRow, Clone, PartialEq)]
+pub struct LoginFailuresByEmail {
+    pub email: String,
+    /// The user with the email, `None` when it has no account
+    pub user_id: Option<Uuid>,
+    pub failed: i64,
+    pub throttled: i64,
+    /// How many IP addresses logins came from
+    pub ip_addresses: i64,
+    pub last_attempt_on: DateTime<Utc>,
+}
+
+impl Logins {
+    /// Group the unsuccessful logins since a time by IP address, most first.
+    ///
+    /// # Parameters
+    /// * `since` - The start of the time window.
+    /// * `limit` - The maximum number of IP addresses to return.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<LoginFailuresByIp>)` - The IP addresses with unsuccessful logins.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Group failed logins by IP address in the database: ",
+        skip(database)
+    )]
+    pub async fn failures_by_ip(
+        since: &DateTime<Utc>,
+        limit: &usize,
+        database: &Pool<Postgres>,
+    ) -> Result<Vec<LoginFailuresByIp>, AuthenticationError> {
+        let limit = *limit as i64;
+
+        let database_records = with_timeout(
+            QueryBudget::Interactive,
+            "logins.failures_by_ip",
+            sqlx::query_as!(
+                LoginFailuresByIp,
+                r#"
+                    SELECT
+                        ip_address,
+                        COUNT(*) FILTER (WHERE outcome = 'failed') AS "failed!",
+                        COUNT(*) FILTER (WHERE outcome = 'throttled') AS "throttled!",
+                        COUNT(DISTINCT lower(email)) AS "emails!",
+                        MAX(created_on) AS "last_attempt_on!"
+                    FROM logins
+                    WHERE outcome <> 'success' AND created_on >= $1
+                    GROUP BY ip_address
+                    ORDER BY COUNT(*) DESC, ip_address
+                    LIMIT $2
+                "#,
+                since,
+                limit,
+            )
+            .fetch_all(database),
+        )
+        .await?;
+
+        Ok(database_records)
+    }
+
+    /// Group the unsuccessful logins since a time by email, most first.
+    /// Emails are grouped case insensitively.
+    ///
+    /// # Parameters
+    /// * `since` - The start of the time window.
+    /// * `limit` - The maximum number of emails to return.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<LoginFailuresByEmail>)` - The emails with unsuccessful logins.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Group failed logins by email in the database: ",
+        skip(database)
+    )]
+    pub async fn failures_by_email(
+        since: &DateTime<Utc>,
+        limit: &usize,
+        database: &Pool<Postgres>,
+    ) -> Result<Vec<LoginFailuresByEmail>, AuthenticationError> {
+        let limit = *limit as i64;
+
+        let database_records = with_timeout(
+            QueryBudget::Interactive,
+            "logins.failures_by_email",
+            sqlx::query_as!(
+                LoginFailuresByEmail,
+                r#"
+                    SELECT
+                        lower(email) AS "email!",
+                        (array_agg(user_id) FILTER (WHERE user_id IS NOT NULL))[1] AS user_id,
+                        COUNT(*) FILTER (WHERE outcome = 'failed') AS "failed!",
+                        COUNT(*) FILTER (WHERE outcome = 'throttled') AS "throttled!",
+                        COUNT(DISTINCT ip_address) AS "ip_addresses!",
+                        MAX(created_on) AS "last_attempt_on!"
+                    FROM logins
+                    WHERE outcome <> 'success' AND created_on >= $1
+                    GROUP BY lower(email)
+                    ORDER BY COUNT(*) DESC, lower(email)
+                    LIMIT $2
+                "#,
+                since,
+                limit,
+            )
+            .fetch_all(database),
+        )
+        .await?;
+
+        Ok(database_records)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use chrono::{Duration, Utc};
+    use sqlx::{Pool, Postgres};
+
+    use crate::database::{self, LoginOutcome, Logins};
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn failed_logins_are_grouped_by_ip_address_and_email(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?.insert(&database).await?;
+        let email = user.email.to_string();
+        for (login_email, ip_address, outcome) in [
+            (email.as_str(), "203.0.113.7", LoginOutcome::Failed),
+            (email.as_str(), "203.0.113.7", LoginOutcome::Throttled),
+            (email.as_str(), "203.0.113.8", LoginOutcome::Failed),
+            ("unknown@example.com", "203.0.113.7", LoginOutcome::Failed),
+            (email.as_str(), "203.0.113.9", LoginOutcome::Success),
+        ] {
+            Logins::new(login_email, ip_address, None, outcome)
+                .insert(&database)
+                .await?;
+        }
+        let mut old = Logins::new("old@example.com", "198.51.100.1", None, LoginOutcome::Failed);
+        old.created_on = Utc::now() - Duration::days(2);
+        old.insert(&database).await?;
+        let since = Utc::now() - Duration::hours(1);
+
+        //-- Execute Function (Act)
+        let by_ip = Logins::failures_by_ip(&since, &10, &database).await?;
+        let by_email = Logins::failures_by_email(&since, &10, &database).await?;
+
+        //-- Checks (Assertions)
+        let by_ip: Vec<_> = by_ip
+            .iter()
+            .map(|row| (row.ip_address.as_str(), row.failed, row.throttled, row.emails))
+            .collect();
+        assert_eq!(
+            by_ip,
+            vec![("203.0.113.7", 2, 1, 2), ("203.0.113.8", 1, 0, 1)]
+        );
+        assert_eq!(by_email.len(), 2);
+        assert_eq!(by_email[0].email, email.to_lowercase());
+        assert_eq!(by_email[0].user_id, Some(user.id));
+        assert_eq!(
+            (by_email[0].failed, by_email[0].throttled, by_email[0].ip_addresses),
+            (2, 1, 2)
+        );
+        assert_eq!(by_email[1].user_id, None);
+
+        Ok(())
+    }
+}
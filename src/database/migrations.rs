@@ -0,0 +1,137 @@
+//-- ./src/database/migrations.rs
+
+// #![allow(unused)] // For development only
+
+//! Database migrations embedded in the binary.
+//!
+//! Migrations run on startup unless `database.auto_migrate` is off, for
+//! environments where a separate job runs them. `migration_status()` compares
+//! the embedded migrations with the `_sqlx_migrations` table, without changing
+//! anything, for the `migrate --dry-run` command and the configuration check.
+//!
+//! # Contents
+//! - The embedded migrator
+//! - Run pending migrations
+//! - Applied and pending migration status
+//! - Unit tests for migration status
+
+use std::collections::HashSet;
+
+use sqlx::migrate::Migrator;
+use sqlx::PgPool;
+
+use crate::prelude::*;
+
+/// Migrations from `./migrations`, embedded at compile time
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// An embedded migration and whether the database has applied it
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub applied: bool,
+}
+
+impl std::fmt::Display for MigrationStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = if self.applied { "applied" } else { "pending" };
+        write!(f, "[{state}] {} {}", self.version, self.description)
+    }
+}
+
+/// Run any pending migrations.
+///
+/// # Parameters
+/// * `database` - The SQLx PostgreSQL connection pool.
+///
+/// # Returns
+/// * `Ok(())` - If every migration is applied.
+/// * `Err(AuthenticationError)` - If a migration fails.
+#[tracing::instrument(name = "Run database migrations: ", skip(database))]
+pub async fn run_migrations(database: &PgPool) -> Result<(), AuthenticationError> {
+    MIGRATOR.run(database).await?;
+
+    Ok(())
+}
+
+/// List every embedded migration and whether it has been applied, oldest
+/// first. Nothing is written, so it is safe against a production database.
+///
+/// # Parameters
+/// * `database` - The SQLx PostgreSQL connection pool.
+///
+/// # Returns
+/// * `Ok(Vec<MigrationStatus>)` - The embedded migrations, by version.
+/// * `Err(AuthenticationError)` - If the database operation fails.
+#[tracing::instrument(name = "Read database migration status: ", skip(database))]
+pub async fn migration_status(
+    database: &PgPool,
+) -> Result<Vec<MigrationStatus>, AuthenticationError> {
+    // A database that has never been migrated has no migrations table
+    let has_table: bool =
+        sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+            .fetch_one(database)
+            .await?;
+
+    let applied: HashSet<i64> = if has_table {
+        sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success")
+            .fetch_all(database)
+            .await?
+            .into_iter()
+            .collect()
+    } else {
+        HashSet::new()
+    };
+
+    let status = MIGRATOR
+        .iter()
+        .filter(|migration| migration.migration_type.is_up_migration())
+        .map(|migration| MigrationStatus {
+            version: migration.version,
+            description: migration.description.to_string(),
+            applied: applied.contains(&migration.version),
+        })
+        .collect();
+
+    Ok(status)
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn status_lists_pending_migrations(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let migrated = migration_status(&database).await?;
+        let latest = migrated.last().ok_or("no embedded migrations")?.version;
+        sqlx::query("DELETE FROM _sqlx_migrations WHERE version = $1")
+            .bind(latest)
+            .execute(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let status = migration_status(&database).await?;
+
+        //-- Checks (Assertions)
+        assert!(migrated.iter().all(|migration| migration.applied));
+        let pending: Vec<_> = status
+            .iter()
+            .filter(|migration| !migration.applied)
+            .collect();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].version, latest);
+
+        Ok(())
+    }
+}
@@ -13,18 +13,71 @@
 //! - Re-exports modules for convenient access in other parts of the application
 
 use crate::{configuration::DatabaseConfiguration, prelude::*};
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use sqlx::PgPool;
 
 // Module imports
+mod access_token_denylist;
+mod action_tokens;
+mod api_keys;
+mod avatars;
+mod circuit_breaker;
+mod clients;
+mod device_codes;
+mod email_changes;
+mod email_domains;
 mod email_verification;
-// mod password_reset;
+mod federated_identities;
+mod grants;
+mod impersonations;
+mod login_throttles;
+mod logins;
+mod migrations;
+mod organizations;
+mod outbox;
+mod passkey_challenges;
+mod password_reset;
+mod policy_acceptances;
+pub mod retry;
+mod saml_requests;
 mod sessions;
+mod sort_direction;
+pub mod timeouts;
+mod user_preferences;
 mod users;
+mod webauthn_credentials;
+mod webhooks;
 
 // Reexport modules for cleaner code
+pub use access_token_denylist::AccessTokenDenylist;
+pub use action_tokens::ActionTokens;
+pub use api_keys::ApiKeys;
+pub use avatars::Avatars;
+pub use circuit_breaker::{
+    circuit_breaker, is_connection_error, CircuitBreaker, CircuitState,
+};
+pub use clients::{ClientType, Clients};
+pub use device_codes::{DeviceCodeStatus, DeviceCodes};
+pub use email_changes::EmailChanges;
+pub use email_domains::{EmailDomainRule, EmailDomains};
 pub use email_verification::EmailVerifications;
+pub use federated_identities::FederatedIdentities;
+pub use grants::Grants;
+pub use impersonations::Impersonations;
+pub use login_throttles::LoginThrottles;
+pub use logins::{LoginFailuresByEmail, LoginFailuresByIp, LoginOutcome, Logins};
+pub use migrations::{migration_status, run_migrations, MigrationStatus};
+pub use organizations::{OrganizationMembers, Organizations};
+pub use outbox::{Outbox, OutboxMessage, OutboxStatus};
+pub use passkey_challenges::{PasskeyCeremony, PasskeyChallenges};
+pub use password_reset::PasswordResets;
+pub use policy_acceptances::PolicyAcceptances;
+pub use saml_requests::SamlRequests;
 pub use sessions::Sessions;
-pub use users::Users;
+pub use sort_direction::SortDirection;
+pub use user_preferences::UserPreferences;
+pub use users::{PasswordState, Users, UsersMerge, UsersSearchFilter};
+pub use webauthn_credentials::WebauthnCredentials;
+pub use webhooks::{WebhookDeliveries, WebhookDeliveryStatus, WebhookEndpoints};
 
 /// Initialize the PostgreSQL connection pool and run database migrations.
 ///
@@ -36,23 +89,92 @@ pub use users::Users;
 /// * `Err(AuthenticationError)` - If the connection or migration fails.
 ///
 /// # Behaviors
-/// - Builds a lazy connection pool using the provided configuration.
-/// - Runs all pending SQLx migrations from the `./migrations` directory before returning the pool.
-/// - Returns an error if the connection or migration fails.
+/// - Builds the connection pool using the provided configuration, connecting eagerly unless `connect_lazily` is set.
+/// - Runs all pending SQLx migrations from the `./migrations` directory before returning the pool, unless `auto_migrate` is off.
+/// - Installs the circuit breaker, closed by each connection the pool hands out.
+/// - Installs the retry policy for queries failing with transient errors, and the query budgets.
+/// - Registers pool saturation gauges with the global meter.
+/// - Returns an error naming the database if an eager connection fails, or if the migration fails.
 pub async fn init_pool(
     database_configuration: &DatabaseConfiguration,
 ) -> Result<PgPool, AuthenticationError> {
-    // Build connection pool
-    let database =
-        PgPoolOptions::new().connect_lazy_with(database_configuration.connection());
+    // A connection handed out by the pool closes the circuit breaker
+    let circuit_breaker = circuit_breaker::install(&database_configuration.circuit_breaker);
+    retry::install(&database_configuration.retry);
+    timeouts::install(database_configuration);
+    let pool_options = database_configuration
+        .pool_options()
+        .after_connect(move |_connection, _metadata| {
+            Box::pin(async move {
+                circuit_breaker.record_success();
+                Ok(())
+            })
+        })
+        .before_acquire(move |_connection, _metadata| {
+            Box::pin(async move {
+                circuit_breaker.record_success();
+                Ok(true)
+            })
+        });
+    let connection = database_configuration.connection();
+
+    // Build connection pool, connecting eagerly unless configured lazy
+    let database = if database_configuration.connect_lazily {
+        pool_options.connect_lazy_with(connection)
+    } else {
+        pool_options.connect_with(connection).await.map_err(|e| {
+            AuthenticationError::DatabaseError(format!(
+                "Unable to connect to the database at {}:{}/{}: {e}",
+                database_configuration.host,
+                database_configuration.port,
+                database_configuration.database_name,
+            ))
+        })?
+    };
+
+    // Migrate database, unless a separate job runs the migrations
+    if database_configuration.auto_migrate {
+        run_migrations(&database).await?;
+    }
 
-    // Migrate database
-    sqlx::migrate!("./migrations").run(&database).await?;
+    pool_metrics(&database);
 
     // Return database
     Ok(database)
 }
 
+/// Report pool saturation, so an exhausted pool shows up before requests
+/// start timing out waiting for a connection.
+///
+/// The gauges read the pool when metrics are collected. Without a metrics
+/// exporter the global meter is a no-op.
+fn pool_metrics(database: &PgPool) {
+    let meter = opentelemetry::global::meter("authentication_service");
+
+    let pool = database.clone();
+    meter
+        .u64_observable_gauge("db.pool.connections")
+        .with_description("Open database connections, idle and in use")
+        .with_callback(move |observer| observer.observe(pool.size() as u64, &[]))
+        .build();
+
+    let pool = database.clone();
+    meter
+        .u64_observable_gauge("db.pool.idle")
+        .with_description("Idle database connections")
+        .with_callback(move |observer| observer.observe(pool.num_idle() as u64, &[]))
+        .build();
+
+    let pool = database.clone();
+    meter
+        .u64_observable_gauge("db.pool.max")
+        .with_description("Maximum database connections")
+        .with_callback(move |observer| {
+            observer.observe(pool.options().get_max_connections() as u64, &[])
+        })
+        .build();
+}
+
 //-- Unit Tests
 #[cfg(test)]
 pub mod tests {
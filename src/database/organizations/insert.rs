@@ -0,0 +1,169 @@
+//-- ./src/database/organizations/insert.rs
+
+// #![allow(unused)] // For development only
+
+//! Organization insert logic for the authentication service.
+//!
+//! # Contents
+//! - Insert an organization
+//! - Insert or update an organization member
+//! - Unit tests for insert scenarios
+
+use sqlx::{Pool, Postgres};
+
+use crate::database::{OrganizationMembers, Organizations};
+use crate::domain;
+use crate::prelude::*;
+
+impl Organizations {
+    /// Insert this organization into the database.
+    ///
+    /// # Parameters
+    /// * `self` - The `Organizations` instance to insert.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Organizations)` - The inserted record as returned from the database.
+    /// * `Err(AuthenticationError)` - If the slug is taken or the database operation fails.
+    #[tracing::instrument(
+        name = "Insert an organization into the database: ",
+        skip(database),
+        fields(
+            id = %self.id,
+            slug = %self.slug,
+        )
+    )]
+    pub async fn insert(&self, database: &Pool<Postgres>) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            Organizations,
+            r#"
+                INSERT INTO organizations (id, name, slug, created_on)
+                VALUES ($1, $2, $3, $4)
+                RETURNING *
+            "#,
+            self.id,
+            self.name,
+            self.slug,
+            self.created_on,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("Organization inserted: {}", database_record.id);
+
+        Ok(database_record)
+    }
+}
+
+impl OrganizationMembers {
+    /// Insert this organization member, updating the role if the user is
+    /// already a member.
+    ///
+    /// # Parameters
+    /// * `self` - The `OrganizationMembers` instance to insert.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(OrganizationMembers)` - The member record as returned from the database.
+    /// * `Err(AuthenticationError)` - If the organization or user does not exist, or the database operation fails.
+    #[tracing::instrument(
+        name = "Insert an organization member into the database: ",
+        skip(database),
+        fields(
+            organization_id = %self.organization_id,
+            user_id = %self.user_id,
+        )
+    )]
+    pub async fn insert(&self, database: &Pool<Postgres>) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            OrganizationMembers,
+            r#"
+                INSERT INTO organization_members (organization_id, user_id, role, created_on)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (organization_id, user_id) DO UPDATE SET role = EXCLUDED.role
+                RETURNING organization_id, user_id, role as "role:domain::UserRole", created_on
+            "#,
+            self.organization_id,
+            self.user_id,
+            self.role.clone() as domain::UserRole,
+            self.created_on,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!(
+            "Organization member inserted: {} in {}",
+            database_record.user_id,
+            database_record.organization_id
+        );
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::{database, domain};
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn create_organization(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let organization = database::Organizations::mock_data()?;
+
+        //-- Execute Function (Act)
+        let database_record = organization.insert(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(database_record, organization);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn duplicate_slug_is_rejected(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let organization = database::Organizations::mock_data()?;
+        organization.insert(&database).await?;
+
+        let duplicate = database::Organizations::new("Another Name", &organization.slug)?;
+
+        //-- Execute Function (Act)
+        let result = duplicate.insert(&database).await;
+
+        //-- Checks (Assertions)
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn adding_existing_member_updates_role(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let organization = database::Organizations::mock_data()?.insert(&database).await?;
+        let user = database::Users::mock_data()?.insert(&database).await?;
+        database::OrganizationMembers::new(&organization.id, &user.id, &domain::UserRole::User)
+            .insert(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let member = database::OrganizationMembers::new(
+            &organization.id,
+            &user.id,
+            &domain::UserRole::Admin,
+        )
+        .insert(&database)
+        .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(member.role, domain::UserRole::Admin);
+
+        Ok(())
+    }
+}
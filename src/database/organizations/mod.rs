@@ -0,0 +1,19 @@
+//-- ./src/database/organizations/mod.rs
+
+//! Organizations database module for the authentication service.
+//!
+//! Organizations (tenants) let one deployment serve several customer orgs.
+//! Users join an organization as a member, with a role per organization.
+//!
+//! # Contents
+//! - Organization and member struct definitions and model-level helpers
+//! - Organization and member insertion logic
+//! - Organization and member read/query logic
+
+// #![allow(unused)] // For development only
+
+pub use model::{OrganizationMembers, Organizations};
+
+mod insert;
+mod model;
+mod read;
@@ -0,0 +1,119 @@
+//-- ./src/database/organizations/model.rs
+
+// #![allow(unused)] // For development only
+
+//! The organizations database models.
+//!
+//! # Contents
+//! - `Organizations` struct definition
+//! - `OrganizationMembers` struct definition
+//! - Constructors for new instances
+//! - Mock data generation for tests
+
+use chrono::{DateTime, SubsecRound, Utc};
+use uuid::Uuid;
+
+use crate::domain;
+use crate::prelude::*;
+
+#[derive(Debug, serde::Deserialize, sqlx::FromRow, Clone, PartialEq)]
+pub struct Organizations {
+    pub id: Uuid,
+    pub name: String,
+    pub slug: String,
+    pub created_on: DateTime<Utc>,
+}
+
+#[derive(Debug, serde::Deserialize, sqlx::FromRow, Clone, PartialEq)]
+pub struct OrganizationMembers {
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub role: domain::UserRole,
+    pub created_on: DateTime<Utc>,
+}
+
+impl Organizations {
+    /// # New Database Organization Instance
+    ///
+    /// Creates a new instance of the Organizations struct.
+    ///
+    /// ## Parameters
+    ///
+    /// - `name: &str` - The organization display name
+    /// - `slug: &str` - Unique URL safe name, lowercase letters, digits and hyphens
+    pub fn new(name: &str, slug: &str) -> Result<Self, AuthenticationError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AuthenticationError::ValidationError(
+                "organization name".to_string(),
+            ));
+        }
+
+        let slug_is_valid = !slug.is_empty()
+            && !slug.starts_with('-')
+            && !slug.ends_with('-')
+            && slug
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+        if !slug_is_valid {
+            return Err(AuthenticationError::ValidationError(
+                "organization slug".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            id: Uuid::now_v7(),
+            name: name.to_string(),
+            slug: slug.to_string(),
+            created_on: Utc::now().round_subsecs(0),
+        })
+    }
+
+    #[cfg(test)]
+    /// # Mock Organization Data
+    ///
+    /// This function is only available in test mode `#[cfg(test)]`.
+    /// Creates a new organization with a random name and slug.
+    pub fn mock_data() -> Result<Self, AuthenticationError> {
+        use fake::faker::company::en::CompanyName;
+        use fake::Fake;
+
+        let name: String = CompanyName().fake();
+        let slug = format!("org-{}", Uuid::now_v7().simple());
+
+        Self::new(&name, &slug)
+    }
+}
+
+impl OrganizationMembers {
+    /// # New Database Organization Member Instance
+    ///
+    /// ## Parameters
+    ///
+    /// - `organization_id: &Uuid` - The organization the user is joining
+    /// - `user_id: &Uuid` - The user joining the organization
+    /// - `role: &domain::UserRole` - The user's role within the organization
+    pub fn new(organization_id: &Uuid, user_id: &Uuid, role: &domain::UserRole) -> Self {
+        Self {
+            organization_id: organization_id.to_owned(),
+            user_id: user_id.to_owned(),
+            role: role.to_owned(),
+            created_on: Utc::now().round_subsecs(0),
+        }
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn slug_must_be_url_safe() {
+        assert!(Organizations::new("Acme Corp", "acme-corp").is_ok());
+        assert!(Organizations::new("Acme Corp", "Acme Corp").is_err());
+        assert!(Organizations::new("Acme Corp", "-acme").is_err());
+        assert!(Organizations::new("Acme Corp", "").is_err());
+        assert!(Organizations::new("  ", "acme").is_err());
+    }
+}
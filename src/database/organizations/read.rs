@@ -0,0 +1,130 @@
+//-- ./src/database/organizations/read.rs
+
+// #![allow(unused)] // For development only
+
+//! Organization read logic for the authentication service.
+//!
+//! # Contents
+//! - Get an organization by id
+//! - Get a user's membership of an organization
+//! - Unit tests for read scenarios
+
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::database::{OrganizationMembers, Organizations};
+use crate::domain;
+use crate::prelude::*;
+
+impl Organizations {
+    /// Retrieve an organization by its id.
+    ///
+    /// # Parameters
+    /// * `id` - The organization id.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Organizations)` - The organization record.
+    /// * `Err(AuthenticationError)` - If the query fails or no organization has the id.
+    #[tracing::instrument(name = "Get an organization from the database: ", skip(database))]
+    pub async fn from_id(id: &Uuid, database: &Pool<Postgres>) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            Organizations,
+            r#"
+                SELECT id, name, slug, created_on
+                FROM organizations
+                WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_one(database)
+        .await?;
+
+        Ok(database_record)
+    }
+}
+
+impl OrganizationMembers {
+    /// Retrieve a user's membership of an organization.
+    ///
+    /// # Parameters
+    /// * `organization_id` - The organization id.
+    /// * `user_id` - The user id.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(OrganizationMembers)` - The membership record.
+    /// * `Err(AuthenticationError)` - If the query fails or the user is not a member.
+    #[tracing::instrument(name = "Get an organization member from the database: ", skip(database))]
+    pub async fn from_ids(
+        organization_id: &Uuid,
+        user_id: &Uuid,
+        database: &Pool<Postgres>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            OrganizationMembers,
+            r#"
+                SELECT organization_id, user_id, role as "role:domain::UserRole", created_on
+                FROM organization_members
+                WHERE organization_id = $1 AND user_id = $2
+            "#,
+            organization_id,
+            user_id
+        )
+        .fetch_one(database)
+        .await?;
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::{database, domain};
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn get_organization_by_id(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let organization = database::Organizations::mock_data()?.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let database_record =
+            database::Organizations::from_id(&organization.id, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(database_record, organization);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn non_member_is_not_found(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let organization = database::Organizations::mock_data()?.insert(&database).await?;
+        let member = database::Users::mock_data()?.insert(&database).await?;
+        let outsider = database::Users::mock_data()?.insert(&database).await?;
+        database::OrganizationMembers::new(&organization.id, &member.id, &domain::UserRole::User)
+            .insert(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let found =
+            database::OrganizationMembers::from_ids(&organization.id, &member.id, &database).await;
+        let not_found =
+            database::OrganizationMembers::from_ids(&organization.id, &outsider.id, &database)
+                .await;
+
+        //-- Checks (Assertions)
+        assert_eq!(found?.role, domain::UserRole::User);
+        assert!(not_found.is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+//-- ./src/database/outbox/delete.rs
+
+// #![allow(unused)] // For development only
+
+//! Outbox delete logic for the authentication service.
+//!
+//! # Contents
+//! - Delete entries processed before a given time
+//! - Unit tests for delete scenarios
+
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+
+use crate::database::Outbox;
+use crate::prelude::*;
+
+impl Outbox {
+    /// Delete outbox entries that were processed before `processed_before`.
+    ///
+    /// Pending and failed entries are kept.
+    ///
+    /// # Parameters
+    /// * `processed_before` - Entries processed before this time are deleted.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of entries deleted.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Delete processed outbox entries: ",
+        skip(database)
+    )]
+    pub async fn delete_processed(
+        processed_before: &DateTime<Utc>,
+        database: &Pool<Postgres>,
+    ) -> Result<u64, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                DELETE FROM outbox
+                WHERE status = 'processed' AND processed_on < $1
+            "#,
+            processed_before
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Outbox entries deleted: {rows_affected}");
+
+        Ok(rows_affected)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use chrono::{Duration, Utc};
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn only_processed_entries_are_deleted(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let pending = database::Outbox::mock_data().insert(&database).await?;
+        database::Outbox::mock_data()
+            .insert(&database)
+            .await?
+            .record_attempt(
+                database::OutboxStatus::Processed,
+                None,
+                Utc::now(),
+                &database,
+            )
+            .await?;
+
+        //-- Execute Function (Act)
+        let deleted = database::Outbox::delete_processed(
+            &(Utc::now() + Duration::minutes(1)),
+            &database,
+        )
+        .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(deleted, 1);
+        let remaining = database::Outbox::claim_due(&10, &60, &database).await?;
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, pending.id);
+
+        Ok(())
+    }
+}
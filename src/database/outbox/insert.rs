@@ -0,0 +1,111 @@
+//-- ./src/database/outbox/insert.rs
+
+// #![allow(unused)] // For development only
+
+//! Outbox insert logic for the authentication service.
+//!
+//! # Contents
+//! - Insert an outbox entry, usually inside the triggering transaction
+//! - Unit tests for insert scenarios
+
+use sqlx::types::Json;
+use sqlx::PgExecutor;
+
+use crate::database::{Outbox, OutboxMessage, OutboxStatus};
+use crate::prelude::*;
+
+impl Outbox {
+    /// Insert this entry into the outbox.
+    ///
+    /// Pass the transaction making the triggering change, so the entry is only
+    /// saved if the change is.
+    ///
+    /// # Parameters
+    /// * `self` - The `Outbox` instance to insert.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Outbox)` - The inserted record as returned from the database.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Insert an outbox entry into the database: ",
+        skip(self, database),
+        fields(
+            id = %self.id,
+            kind = %self.kind,
+        )
+    )]
+    pub async fn insert(
+        &self,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            Outbox,
+            r#"
+                INSERT INTO outbox (id, kind, payload, status, attempts, next_attempt_at, last_error, created_on, processed_on)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                RETURNING id, kind, payload as "payload: Json<OutboxMessage>", status as "status:OutboxStatus", attempts, next_attempt_at, last_error, created_on, processed_on
+            "#,
+            self.id,
+            self.kind,
+            &self.payload as _,
+            self.status as OutboxStatus,
+            self.attempts,
+            self.next_attempt_at,
+            self.last_error,
+            self.created_on,
+            self.processed_on,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("Outbox entry inserted: {}", database_record.id);
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn create_outbox_entry(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let entry = database::Outbox::mock_data();
+
+        //-- Execute Function (Act)
+        let database_record = entry.insert(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(database_record, entry);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn rolled_back_entries_are_not_saved(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let entry = database::Outbox::mock_data();
+        let mut transaction = database.begin().await?;
+
+        //-- Execute Function (Act)
+        entry.insert(&mut *transaction).await?;
+        transaction.rollback().await?;
+
+        //-- Checks (Assertions)
+        let claimed = database::Outbox::claim_due(&10, &60, &database).await?;
+        assert!(claimed.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+//-- ./src/database/outbox/mod.rs
+
+//! Outbox database module for the authentication service.
+//!
+//! Side effects (emails and webhook events) are written to the outbox in the
+//! same transaction as the change that triggers them. A background dispatcher
+//! (see `services::outbox`) processes the entries, retrying failures, so side
+//! effects are not lost if the process stops mid-request.
+//!
+//! # Contents
+//! - Outbox struct and message definitions, and model-level helpers
+//! - Outbox insertion logic
+//! - Claim the entries that are due processing
+//! - Record the outcome of processing an entry
+//! - Delete processed entries
+
+// #![allow(unused)] // For development only
+
+pub use model::{Outbox, OutboxMessage, OutboxStatus};
+
+mod delete;
+mod insert;
+mod model;
+mod read;
+mod update;
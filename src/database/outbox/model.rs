@@ -0,0 +1,164 @@
+//-- ./src/database/outbox/model.rs
+
+// #![allow(unused)] // For development only
+
+//! The outbox database model.
+//!
+//! # Contents
+//! - `Outbox` struct definition
+//! - `OutboxMessage` enum definition, the side effect stored as JSON
+//! - `OutboxStatus` enum definition
+//! - Constructor for new outbox entries
+//! - Mock data generation for tests
+
+use chrono::{DateTime, SubsecRound, Utc};
+use sqlx::types::Json;
+use uuid::Uuid;
+
+use crate::email::EmailMessage;
+use crate::events::AuthEvent;
+
+/// Where an outbox entry is in processing
+#[derive(Debug, Clone, Copy, Default, PartialEq, sqlx::Type, serde::Deserialize)]
+#[sqlx(type_name = "outbox_status", rename_all = "lowercase")]
+pub enum OutboxStatus {
+    #[default]
+    Pending,
+    Processed,
+    Failed,
+}
+
+impl OutboxStatus {
+    /// Convert OutboxStatus to a string reference
+    pub fn to_str(&self) -> &str {
+        match self {
+            OutboxStatus::Pending => "pending",
+            OutboxStatus::Processed => "processed",
+            OutboxStatus::Failed => "failed",
+        }
+    }
+}
+
+impl std::fmt::Display for OutboxStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+/// A side effect waiting in the outbox
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum OutboxMessage {
+    /// Send an email
+    Email(EmailMessage),
+
+    /// Queue an authentication event for the webhook endpoints
+    Webhook(AuthEvent),
+}
+
+impl OutboxMessage {
+    /// The kind of side effect, stored alongside the payload
+    pub fn kind(&self) -> &'static str {
+        match self {
+            OutboxMessage::Email(_) => "email",
+            OutboxMessage::Webhook(_) => "webhook",
+        }
+    }
+}
+
+impl From<EmailMessage> for OutboxMessage {
+    fn from(value: EmailMessage) -> Self {
+        Self::Email(value)
+    }
+}
+
+impl From<AuthEvent> for OutboxMessage {
+    fn from(value: AuthEvent) -> Self {
+        Self::Webhook(value)
+    }
+}
+
+#[derive(Debug, sqlx::FromRow, Clone, PartialEq)]
+pub struct Outbox {
+    pub id: Uuid,
+    pub kind: String,
+    pub payload: Json<OutboxMessage>,
+    pub status: OutboxStatus,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub created_on: DateTime<Utc>,
+    pub processed_on: Option<DateTime<Utc>>,
+}
+
+impl Outbox {
+    /// # New Database Outbox Instance
+    ///
+    /// Creates a new pending outbox entry, due straight away.
+    ///
+    /// ## Parameters
+    ///
+    /// - `message: impl Into<OutboxMessage>` - The side effect, e.g. an `EmailMessage` or `AuthEvent`
+    pub fn new(message: impl Into<OutboxMessage>) -> Self {
+        let message = message.into();
+        let now = Utc::now().round_subsecs(0);
+
+        Self {
+            id: Uuid::now_v7(),
+            kind: message.kind().to_string(),
+            payload: Json(message),
+            status: OutboxStatus::Pending,
+            attempts: 0,
+            next_attempt_at: now,
+            last_error: None,
+            created_on: now,
+            processed_on: None,
+        }
+    }
+
+    /// The side effect to process
+    pub fn message(&self) -> &OutboxMessage {
+        &self.payload.0
+    }
+
+    #[cfg(test)]
+    /// # Mock Outbox Data
+    ///
+    /// This function is only available in test mode `#[cfg(test)]`.
+    /// Creates a new pending outbox entry for a login event.
+    pub fn mock_data() -> Self {
+        use crate::events::AuthEventKind;
+
+        Self::new(AuthEvent::new(AuthEventKind::Login).user(Uuid::now_v7()))
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    use crate::domain;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn messages_round_trip_through_json() -> Result<()> {
+        let email = OutboxMessage::from(EmailMessage {
+            to: domain::EmailAddress::parse("someone@example.com")?,
+            subject: "Welcome".to_string(),
+            body_text: "Hello there".to_string(),
+        });
+        let webhook = Outbox::mock_data().payload.0;
+
+        for message in [email, webhook] {
+            let json = serde_json::to_value(&message)?;
+            assert_eq!(json["kind"], message.kind());
+            assert_eq!(serde_json::from_value::<OutboxMessage>(json)?, message);
+        }
+
+        Ok(())
+    }
+}
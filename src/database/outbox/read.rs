@@ -0,0 +1,98 @@
+//-- ./src/database/outbox/read.rs
+
+// #![allow(unused)] // For development only
+
+//! Outbox read logic for the authentication service.
+//!
+//! # Contents
+//! - Claim the entries that are due processing
+//! - Unit tests for read scenarios
+
+use sqlx::types::Json;
+use sqlx::{Pool, Postgres};
+
+use crate::database::{Outbox, OutboxMessage, OutboxStatus};
+use crate::prelude::*;
+
+impl Outbox {
+    /// Claim pending entries that are due processing, oldest first.
+    ///
+    /// Claimed entries have their next attempt pushed back by the lease, so
+    /// another dispatcher (or instance) does not pick them up while they are
+    /// being processed. Recording the attempt replaces the lease.
+    ///
+    /// # Parameters
+    /// * `limit` - The maximum number of entries to claim.
+    /// * `lease_seconds` - How long the claim lasts.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Outbox>)` - The claimed entries.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Claim due outbox entries in the database: ",
+        skip(database)
+    )]
+    pub async fn claim_due(
+        limit: &usize,
+        lease_seconds: &u64,
+        database: &Pool<Postgres>,
+    ) -> Result<Vec<Self>, AuthenticationError> {
+        let database_records = sqlx::query_as!(
+            Outbox,
+            r#"
+                UPDATE outbox
+                SET next_attempt_at = NOW() + make_interval(secs => $2)
+                WHERE id IN (
+                    SELECT id
+                    FROM outbox
+                    WHERE status = 'pending' AND next_attempt_at <= NOW()
+                    ORDER BY next_attempt_at
+                    LIMIT $1
+                    FOR UPDATE SKIP LOCKED
+                )
+                RETURNING id, kind, payload as "payload: Json<OutboxMessage>", status as "status:OutboxStatus", attempts, next_attempt_at, last_error, created_on, processed_on
+            "#,
+            *limit as i64,
+            *lease_seconds as f64,
+        )
+        .fetch_all(database)
+        .await?;
+
+        tracing::debug!("Outbox entries claimed: {}", database_records.len());
+
+        Ok(database_records)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn claimed_entries_are_not_claimed_twice(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let entry = database::Outbox::mock_data().insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let first = database::Outbox::claim_due(&10, &60, &database).await?;
+        let second = database::Outbox::claim_due(&10, &60, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].id, entry.id);
+        assert_eq!(first[0].message(), entry.message());
+        assert!(second.is_empty());
+
+        Ok(())
+    }
+}
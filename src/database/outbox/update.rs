@@ -0,0 +1,118 @@
+//-- ./src/database/outbox/update.rs
+
+// #![allow(unused)] // For development only
+
+//! Outbox processing logic for the authentication service.
+//!
+//! # Contents
+//! - Record the outcome of processing an outbox entry
+//! - Unit tests for update scenarios
+
+use chrono::{DateTime, Utc};
+use sqlx::types::Json;
+use sqlx::{Pool, Postgres};
+
+use crate::database::{Outbox, OutboxMessage, OutboxStatus};
+use crate::prelude::*;
+
+impl Outbox {
+    /// Record the outcome of processing this entry, incrementing the attempt count.
+    ///
+    /// # Parameters
+    /// * `self` - The entry that was processed.
+    /// * `status` - The entry status after the attempt.
+    /// * `error` - Why the attempt failed, `None` if it succeeded.
+    /// * `next_attempt_at` - When to retry, only used while the entry is pending.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Outbox)` - The updated outbox record.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Record an outbox attempt in the database: ",
+        skip(self, database),
+        fields(id = %self.id)
+    )]
+    pub async fn record_attempt(
+        &self,
+        status: OutboxStatus,
+        error: Option<&str>,
+        next_attempt_at: DateTime<Utc>,
+        database: &Pool<Postgres>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            Outbox,
+            r#"
+                UPDATE outbox
+                SET status = $2,
+                    attempts = attempts + 1,
+                    last_error = $3,
+                    next_attempt_at = $4,
+                    processed_on = CASE WHEN $2 = 'processed'::outbox_status THEN NOW() END
+                WHERE id = $1
+                RETURNING id, kind, payload as "payload: Json<OutboxMessage>", status as "status:OutboxStatus", attempts, next_attempt_at, last_error, created_on, processed_on
+            "#,
+            self.id,
+            status as OutboxStatus,
+            error,
+            next_attempt_at,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!(
+            "Outbox attempt {} recorded as {}",
+            database_record.attempts,
+            database_record.status
+        );
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use chrono::Utc;
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn record_attempt_marks_processed(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let entry = database::Outbox::mock_data().insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let retry = entry
+            .record_attempt(
+                database::OutboxStatus::Pending,
+                Some("relay refused"),
+                Utc::now(),
+                &database,
+            )
+            .await?;
+        let processed = retry
+            .record_attempt(
+                database::OutboxStatus::Processed,
+                None,
+                Utc::now(),
+                &database,
+            )
+            .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(retry.attempts, 1);
+        assert!(retry.processed_on.is_none());
+        assert_eq!(processed.attempts, 2);
+        assert_eq!(processed.status, database::OutboxStatus::Processed);
+        assert!(processed.last_error.is_none());
+        assert!(processed.processed_on.is_some());
+
+        Ok(())
+    }
+}
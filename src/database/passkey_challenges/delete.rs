@@ -0,0 +1,177 @@
+//-- ./src/database/passkey_challenges/delete.rs
+
+// #![allow(unused)] // For development only
+
+//! Passkey challenge delete logic for the authentication service.
+//!
+//! # Contents
+//! - Consume a challenge when its ceremony is finished
+//! - Delete expired challenges
+//! - Unit tests for delete scenarios
+
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::database::{PasskeyCeremony, PasskeyChallenges};
+use crate::prelude::*;
+
+impl PasskeyChallenges {
+    /// Delete an unexpired challenge for the ceremony, returning it. The
+    /// challenge is deleted in the same statement, so it can only be used once.
+    ///
+    /// # Parameters
+    /// * `id` - The challenge id returned when the ceremony began.
+    /// * `ceremony` - The ceremony being finished.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Some(PasskeyChallenges))` - The consumed challenge.
+    /// * `Ok(None)` - If the challenge is unknown, expired or for another ceremony.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Consume a passkey challenge in the database: ",
+        skip(database)
+    )]
+    pub async fn consume(
+        id: &Uuid,
+        ceremony: PasskeyCeremony,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Option<Self>, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            PasskeyChallenges,
+            r#"
+                DELETE FROM passkey_challenges
+                WHERE id = $1 AND ceremony = $2 AND expires_on > NOW()
+                RETURNING id, user_id, ceremony as "ceremony:PasskeyCeremony", state, expires_on, created_on
+            "#,
+            id,
+            ceremony as PasskeyCeremony,
+        )
+        .fetch_optional(database)
+        .await?;
+
+        match &database_record {
+            Some(record) => tracing::debug!("Passkey challenge consumed: {}", record.id),
+            None => tracing::debug!("Passkey challenge is unknown or expired"),
+        }
+
+        Ok(database_record)
+    }
+
+    /// Delete challenges that have expired, they can no longer be finished.
+    ///
+    /// # Parameters
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of challenges deleted.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Delete expired passkey challenges from the database: ",
+        skip(database)
+    )]
+    pub async fn delete_expired(
+        database: impl PgExecutor<'_>,
+    ) -> Result<u64, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                DELETE FROM passkey_challenges
+                WHERE expires_on < NOW()
+            "#,
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Expired passkey challenges deleted: {rows_affected}");
+
+        Ok(rows_affected)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use chrono::{Duration, Utc};
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn passkey_challenges_are_single_use(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let challenge = database::PasskeyChallenges::mock_data(
+            &user.id,
+            database::PasskeyCeremony::Registration,
+        )
+        .insert(&database)
+        .await?;
+
+        //-- Execute Function (Act)
+        let other_ceremony = database::PasskeyChallenges::consume(
+            &challenge.id,
+            database::PasskeyCeremony::Authentication,
+            &database,
+        )
+        .await?;
+        let first = database::PasskeyChallenges::consume(
+            &challenge.id,
+            database::PasskeyCeremony::Registration,
+            &database,
+        )
+        .await?;
+        let second = database::PasskeyChallenges::consume(
+            &challenge.id,
+            database::PasskeyCeremony::Registration,
+            &database,
+        )
+        .await?;
+
+        //-- Checks (Assertions)
+        assert!(other_ceremony.is_none());
+        assert_eq!(first, Some(challenge));
+        assert!(second.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn expired_challenges_are_deleted(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let mut expired = database::PasskeyChallenges::mock_data(
+            &user.id,
+            database::PasskeyCeremony::Authentication,
+        );
+        expired.expires_on = Utc::now() - Duration::minutes(1);
+        expired.insert(&database).await?;
+        database::PasskeyChallenges::mock_data(
+            &user.id,
+            database::PasskeyCeremony::Authentication,
+        )
+        .insert(&database)
+        .await?;
+
+        //-- Execute Function (Act)
+        let consumed = database::PasskeyChallenges::consume(
+            &expired.id,
+            database::PasskeyCeremony::Authentication,
+            &database,
+        )
+        .await?;
+        let deleted = database::PasskeyChallenges::delete_expired(&database).await?;
+
+        //-- Checks (Assertions)
+        assert!(consumed.is_none());
+        assert_eq!(deleted, 1);
+
+        Ok(())
+    }
+}
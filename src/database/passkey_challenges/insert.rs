@@ -0,0 +1,86 @@
+//-- ./src/database/passkey_challenges/insert.rs
+
+// #![allow(unused)] // For development only
+
+//! Passkey challenge insertion logic for the authentication service.
+//!
+//! # Contents
+//! - Insert a passkey challenge
+//! - Unit tests for insert scenarios
+
+use sqlx::PgExecutor;
+
+use crate::database::{PasskeyCeremony, PasskeyChallenges};
+use crate::prelude::*;
+
+impl PasskeyChallenges {
+    /// Insert a passkey challenge into the database.
+    ///
+    /// # Parameters
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(PasskeyChallenges)` - The inserted challenge record.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Insert a passkey challenge into the database: ",
+        skip(self, database),
+        fields(id = %self.id, user_id = %self.user_id, ceremony = %self.ceremony)
+    )]
+    pub async fn insert(
+        &self,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            PasskeyChallenges,
+            r#"
+                INSERT INTO passkey_challenges (id, user_id, ceremony, state, expires_on, created_on)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                RETURNING id, user_id, ceremony as "ceremony:PasskeyCeremony", state, expires_on, created_on
+            "#,
+            self.id,
+            self.user_id,
+            self.ceremony as PasskeyCeremony,
+            self.state,
+            self.expires_on,
+            self.created_on,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("Passkey challenge inserted: {}", database_record.id);
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn insert_passkey_challenge(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let challenge = database::PasskeyChallenges::mock_data(
+            &user.id,
+            database::PasskeyCeremony::Authentication,
+        );
+
+        //-- Execute Function (Act)
+        let database_record = challenge.insert(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(database_record, challenge);
+
+        Ok(())
+    }
+}
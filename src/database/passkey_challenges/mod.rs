@@ -0,0 +1,20 @@
+//-- ./src/database/passkey_challenges/mod.rs
+
+//! Passkey challenges database module for the authentication service.
+//!
+//! The state of passkey registrations and logins that have begun but not
+//! finished. A challenge is deleted when it is finished, so each one can only
+//! be answered once.
+//!
+//! # Contents
+//! - Passkey ceremony enum, passkey challenge struct and model-level helpers
+//! - Passkey challenge insertion logic
+//! - Passkey challenge consume and delete logic
+
+// #![allow(unused)] // For development only
+
+pub use model::{PasskeyCeremony, PasskeyChallenges};
+
+mod delete;
+mod insert;
+mod model;
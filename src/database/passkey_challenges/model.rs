@@ -0,0 +1,117 @@
+//-- ./src/database/passkey_challenges/model.rs
+
+// #![allow(unused)] // For development only
+
+//! The passkey challenges database model.
+//!
+//! # Contents
+//! - `PasskeyCeremony` enum definition
+//! - `PasskeyChallenges` struct definition
+//! - Constructor for new challenges
+//! - Mock data generation for tests
+
+use chrono::{DateTime, SubsecRound, Utc};
+use uuid::Uuid;
+
+use crate::prelude::*;
+
+/// The passkey ceremony a challenge was issued for
+#[derive(Debug, Clone, Copy, PartialEq, sqlx::Type, serde::Deserialize)]
+#[sqlx(type_name = "passkey_ceremony", rename_all = "lowercase")]
+pub enum PasskeyCeremony {
+    /// Registering a new passkey
+    Registration,
+    /// Logging in with a passkey
+    Authentication,
+}
+
+impl PasskeyCeremony {
+    /// Convert PasskeyCeremony to a string reference
+    pub fn to_str(&self) -> &str {
+        match self {
+            PasskeyCeremony::Registration => "registration",
+            PasskeyCeremony::Authentication => "authentication",
+        }
+    }
+}
+
+impl std::fmt::Display for PasskeyCeremony {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+#[derive(Debug, sqlx::FromRow, Clone, PartialEq)]
+pub struct PasskeyChallenges {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub ceremony: PasskeyCeremony,
+    pub state: String,
+    pub expires_on: DateTime<Utc>,
+    pub created_on: DateTime<Utc>,
+}
+
+impl PasskeyChallenges {
+    /// # New Database Passkey Challenge Instance
+    ///
+    /// Creates a new challenge, keeping the serialised ceremony state until
+    /// the ceremony is finished.
+    ///
+    /// ## Parameters
+    ///
+    /// - `user_id: &Uuid` - The user registering or logging in
+    /// - `ceremony: PasskeyCeremony` - What the challenge was issued for
+    /// - `state: &impl serde::Serialize` - The webauthn-rs registration or authentication state
+    /// - `duration: &std::time::Duration` - How long the ceremony can take
+    pub fn new(
+        user_id: &Uuid,
+        ceremony: PasskeyCeremony,
+        state: &impl serde::Serialize,
+        duration: &std::time::Duration,
+    ) -> Result<Self, AuthenticationError> {
+        let now = Utc::now().round_subsecs(0);
+
+        Ok(Self {
+            id: Uuid::now_v7(),
+            user_id: user_id.to_owned(),
+            ceremony,
+            state: serde_json::to_string(state)?,
+            expires_on: now + *duration,
+            created_on: now,
+        })
+    }
+
+    /// The webauthn-rs ceremony state kept with the challenge
+    pub fn state<T: serde::de::DeserializeOwned>(&self) -> Result<T, AuthenticationError> {
+        Ok(serde_json::from_str(&self.state)?)
+    }
+
+    #[cfg(test)]
+    pub fn mock_data(user_id: &Uuid, ceremony: PasskeyCeremony) -> Self {
+        Self::new(
+            user_id,
+            ceremony,
+            &"mock state",
+            &std::time::Duration::from_secs(5 * 60),
+        )
+        .expect("a string serialises")
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn challenge_state_round_trips() -> Result<(), AuthenticationError> {
+        let challenge =
+            PasskeyChallenges::mock_data(&Uuid::now_v7(), PasskeyCeremony::Registration);
+
+        let state: String = challenge.state()?;
+
+        assert_eq!(state, "mock state");
+        assert!(challenge.expires_on > challenge.created_on);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+//-- ./src/database/password_reset/delete.rs
+
+// #![allow(unused)] // For development only
+
+//! Password reset delete logic for the authentication service.
+//!
+//! # Contents
+//! - Delete expired password resets
+//! - Unit tests for delete scenarios
+
+use sqlx::PgExecutor;
+
+use crate::database::PasswordResets;
+use crate::prelude::*;
+
+impl PasswordResets {
+    /// Delete password resets that have expired, they can no longer be used.
+    ///
+    /// # Parameters
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of password resets deleted.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Delete expired password resets from the database: ",
+        skip(database)
+    )]
+    pub async fn delete_expired(
+        database: impl PgExecutor<'_>,
+    ) -> Result<u64, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                DELETE FROM password_resets
+                WHERE expires_at < NOW()
+            "#,
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Expired password resets deleted: {rows_affected}");
+
+        Ok(rows_affected)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use chrono::{Duration, Utc};
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn only_expired_resets_are_deleted(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let (current, _) = database::PasswordResets::mock_data(&user.id);
+        current.insert(&database).await?;
+        let (mut expired, _) = database::PasswordResets::mock_data(&user.id);
+        expired.expires_at = Utc::now() - Duration::minutes(1);
+        expired.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let deleted = database::PasswordResets::delete_expired(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(deleted, 1);
+        assert_eq!(
+            database::PasswordResets::unused_for_user(&user.id, &database).await?,
+            vec![current]
+        );
+
+        Ok(())
+    }
+}
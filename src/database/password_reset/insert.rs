@@ -0,0 +1,87 @@
+//-- ./src/database/password_reset/insert.rs
+
+// #![allow(unused)] // For development only
+
+//! Password reset insert logic for the authentication service.
+//!
+//! # Contents
+//! - Insert a password reset
+//! - Unit tests for insert scenarios
+
+use sqlx::PgExecutor;
+
+use crate::database::PasswordResets;
+use crate::prelude::*;
+
+impl PasswordResets {
+    /// Insert this password reset into the database.
+    ///
+    /// # Parameters
+    /// * `self` - The `PasswordResets` instance to insert.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(PasswordResets)` - The inserted record as returned from the database.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Insert a password reset into the database: ",
+        skip(self, database),
+        fields(
+            id = %self.id,
+            user_id = %self.user_id,
+        )
+    )]
+    pub async fn insert(
+        &self,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            PasswordResets,
+            r#"
+                INSERT INTO password_resets (id, user_id, token, expires_at, is_used, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                RETURNING id, user_id, token, expires_at, is_used, created_at
+            "#,
+            self.id,
+            self.user_id,
+            self.token,
+            self.expires_at,
+            self.is_used,
+            self.created_at,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("Password reset inserted: {}", database_record.id);
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn insert_password_reset(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let (password_reset, _) = database::PasswordResets::mock_data(&user.id);
+
+        //-- Execute Function (Act)
+        let database_record = password_reset.insert(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(database_record, password_reset);
+
+        Ok(())
+    }
+}
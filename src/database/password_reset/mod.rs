@@ -1,7 +1,24 @@
+//-- ./src/database/password_reset/mod.rs
 
+//! Password resets database module for the authentication service.
+//!
+//! Tokens emailed to a user to choose a new password. Only the token hash is
+//! stored, and requesting a new reset invalidates the user's earlier unused
+//! resets so only the latest link works.
+//!
+//! # Contents
+//! - Password reset struct definition and model-level helpers
+//! - Password reset insertion logic
+//! - Password reset read logic
+//! - Password reset invalidate logic
+//! - Password reset delete logic
+
+// #![allow(unused)] // For development only
+
+pub use model::PasswordResets;
 
 mod delete;
 mod insert;
 mod model;
 mod read;
-mod update;
\ No newline at end of file
+mod update;
@@ -0,0 +1,129 @@
+//-- ./src/database/password_reset/model.rs
+
+// #![allow(unused)] // For development only
+
+//! The password resets database model.
+//!
+//! # Contents
+//! - `PasswordResets` struct definition
+//! - Constructor for new password resets
+//! - Mock data generation for tests
+
+use chrono::{DateTime, SubsecRound, Utc};
+use uuid::Uuid;
+
+use crate::domain;
+use crate::utils::{Clock, SystemClock};
+
+#[derive(Debug, sqlx::FromRow, Clone, PartialEq)]
+pub struct PasswordResets {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// The SHA-256 hash of the reset token, the token is only emailed
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+    pub is_used: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PasswordResets {
+    /// # New Database Password Reset Instance
+    ///
+    /// Creates a new unused password reset. Only the token hash is kept.
+    ///
+    /// ## Parameters
+    ///
+    /// - `user_id: &Uuid` - The user the reset link is emailed to
+    /// - `token: &domain::ActionToken` - The password reset token
+    /// - `duration: &std::time::Duration` - How long the token can be used for
+    pub fn new(
+        user_id: &Uuid,
+        token: &domain::ActionToken,
+        duration: &std::time::Duration,
+    ) -> Self {
+        Self::new_with_clock(user_id, token, duration, &SystemClock)
+    }
+
+    /// The same as `PasswordResets::new`, issued at the time of `clock`
+    /// instead of the system time
+    pub fn new_with_clock(
+        user_id: &Uuid,
+        token: &domain::ActionToken,
+        duration: &std::time::Duration,
+        clock: &dyn Clock,
+    ) -> Self {
+        let now = clock.now().round_subsecs(0);
+
+        Self {
+            id: Uuid::now_v7(),
+            user_id: user_id.to_owned(),
+            token: token.hash(),
+            expires_at: now + *duration,
+            is_used: false,
+            created_at: now,
+        }
+    }
+
+    /// Whether the reset can no longer be used
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(&SystemClock)
+    }
+
+    /// Whether the reset can no longer be used at the time of `clock`
+    pub fn is_expired_at(&self, clock: &dyn Clock) -> bool {
+        self.expires_at <= clock.now()
+    }
+
+    #[cfg(test)]
+    /// # Mock Password Reset Data
+    ///
+    /// This function is only available in test mode `#[cfg(test)]`.
+    /// Creates a new unused password reset for the user, returning the token
+    /// alongside it.
+    pub fn mock_data(user_id: &Uuid) -> (Self, domain::ActionToken) {
+        let token = domain::ActionToken::generate(
+            domain::ActionPurpose::PasswordReset,
+            &secrecy::SecretString::from("action-token-secret"),
+        );
+        let password_reset =
+            Self::new(user_id, &token, &std::time::Duration::from_secs(60 * 60));
+
+        (password_reset, token)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn new_password_resets_store_the_token_hash() {
+        let (password_reset, token) = PasswordResets::mock_data(&Uuid::now_v7());
+
+        assert_eq!(password_reset.token, token.hash());
+        assert!(!password_reset.is_used);
+        assert!(!password_reset.is_expired());
+    }
+
+    #[test]
+    fn password_resets_expire_after_the_duration() {
+        let clock = crate::utils::MockClock::default();
+        let token = domain::ActionToken::generate(
+            domain::ActionPurpose::PasswordReset,
+            &secrecy::SecretString::from("action-token-secret"),
+        );
+        let password_reset = PasswordResets::new_with_clock(
+            &Uuid::now_v7(),
+            &token,
+            &std::time::Duration::from_secs(60 * 60),
+            &clock,
+        );
+
+        clock.advance(chrono::Duration::minutes(59));
+        assert!(!password_reset.is_expired_at(&clock));
+
+        clock.advance(chrono::Duration::minutes(1));
+        assert!(password_reset.is_expired_at(&clock));
+    }
+}
@@ -0,0 +1,83 @@
+//-- ./src/database/password_reset/read.rs
+
+// #![allow(unused)] // For development only
+
+//! Password reset read logic for the authentication service.
+//!
+//! # Contents
+//! - Get a user's unused, unexpired password resets
+//! - Unit tests for read scenarios
+
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::database::PasswordResets;
+use crate::prelude::*;
+
+impl PasswordResets {
+    /// Retrieve the password resets a user can still use, newest first.
+    ///
+    /// # Parameters
+    /// * `user_id` - The user the resets were emailed to.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<PasswordResets>)` - The unused, unexpired password resets.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Get unused password resets from the database: ",
+        skip(database)
+    )]
+    pub async fn unused_for_user(
+        user_id: &Uuid,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Vec<Self>, AuthenticationError> {
+        let database_records = sqlx::query_as!(
+            PasswordResets,
+            r#"
+                SELECT id, user_id, token, expires_at, is_used, created_at
+                FROM password_resets
+                WHERE user_id = $1 AND is_used = FALSE AND expires_at > NOW()
+                ORDER BY created_at DESC, id DESC
+            "#,
+            user_id,
+        )
+        .fetch_all(database)
+        .await?;
+
+        Ok(database_records)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use chrono::{Duration, Utc};
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn unused_for_user_skips_expired_resets(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let (password_reset, _) = database::PasswordResets::mock_data(&user.id);
+        password_reset.insert(&database).await?;
+        let (mut expired, _) = database::PasswordResets::mock_data(&user.id);
+        expired.expires_at = Utc::now() - Duration::minutes(1);
+        expired.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let unused = database::PasswordResets::unused_for_user(&user.id, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(unused, vec![password_reset]);
+
+        Ok(())
+    }
+}
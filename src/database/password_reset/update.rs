@@ -0,0 +1,162 @@
+//-- ./src/database/password_reset/update.rs
+
+// #![allow(unused)] // For development only
+
+//! Password reset invalidate logic for the authentication service.
+//!
+//! # Contents
+//! - Consume an unused, unexpired password reset
+//! - Invalidate a user's unused password resets
+//! - Unit tests for update scenarios
+
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::database::PasswordResets;
+use crate::domain;
+use crate::prelude::*;
+
+impl PasswordResets {
+    /// Use the password reset with this token, marking it used in the same
+    /// statement so it can only be used once, even by concurrent requests.
+    ///
+    /// # Parameters
+    /// * `token` - The parsed password reset token.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Some(PasswordResets))` - The used password reset.
+    /// * `Ok(None)` - If the token is unknown, already used or expired.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Consume a password reset in the database: ",
+        skip(token, database)
+    )]
+    pub async fn consume(
+        token: &domain::ActionToken,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Option<Self>, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            PasswordResets,
+            r#"
+                UPDATE password_resets
+                SET is_used = TRUE
+                WHERE token = $1 AND is_used = FALSE AND expires_at > NOW()
+                RETURNING id, user_id, token, expires_at, is_used, created_at
+            "#,
+            token.hash(),
+        )
+        .fetch_optional(database)
+        .await?;
+
+        match &database_record {
+            Some(record) => tracing::debug!("Password reset consumed: {}", record.id),
+            None => tracing::debug!("Password reset is unknown, used or expired"),
+        }
+
+        Ok(database_record)
+    }
+
+    /// Mark a user's unused password resets as used, so only the reset
+    /// emailed next can be used. The rows are kept for auditing.
+    ///
+    /// # Parameters
+    /// * `user_id` - The user whose unused resets are invalidated.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of password resets invalidated.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Invalidate unused password resets in the database: ",
+        skip(database)
+    )]
+    pub async fn invalidate_unused_for_user(
+        user_id: &Uuid,
+        database: impl PgExecutor<'_>,
+    ) -> Result<u64, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                UPDATE password_resets
+                SET is_used = TRUE
+                WHERE user_id = $1 AND is_used = FALSE
+            "#,
+            user_id,
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Unused password resets invalidated: {rows_affected}");
+
+        Ok(rows_affected)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn password_resets_are_single_use(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let (password_reset, token) = database::PasswordResets::mock_data(&user.id);
+        password_reset.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let first = database::PasswordResets::consume(&token, &database).await?;
+        let second = database::PasswordResets::consume(&token, &database).await?;
+
+        //-- Checks (Assertions)
+        let first = first.ok_or("first use consumes the reset")?;
+        assert_eq!(first.id, password_reset.id);
+        assert!(first.is_used);
+        assert!(second.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn invalidate_only_changes_the_users_resets(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let other_user = database::Users::mock_data()?;
+        other_user.insert(&database).await?;
+        for user_id in [user.id, user.id, other_user.id] {
+            database::PasswordResets::mock_data(&user_id)
+                .0
+                .insert(&database)
+                .await?;
+        }
+
+        //-- Execute Function (Act)
+        let invalidated =
+            database::PasswordResets::invalidate_unused_for_user(&user.id, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(invalidated, 2);
+        assert!(database::PasswordResets::unused_for_user(&user.id, &database)
+            .await?
+            .is_empty());
+        assert_eq!(
+            database::PasswordResets::unused_for_user(&other_user.id, &database)
+                .await?
+                .len(),
+            1
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+//-- ./src/database/policy_acceptances/insert.rs
+
+// #![allow(unused)] // For development only
+
+//! Policy acceptance insert logic for the authentication service.
+//!
+//! # Contents
+//! - Insert a policy acceptance, keeping an earlier acceptance of the version
+//! - Unit tests for insert scenarios
+
+use sqlx::{Pool, Postgres};
+
+use crate::database::PolicyAcceptances;
+use crate::prelude::*;
+
+impl PolicyAcceptances {
+    /// Insert this policy acceptance into the database. When the user has
+    /// already accepted the version, that acceptance is kept and returned.
+    ///
+    /// # Parameters
+    /// * `self` - The `PolicyAcceptances` instance to insert.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(PolicyAcceptances)` - The user's acceptance of the version.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Insert a policy acceptance into the database: ",
+        skip(database),
+        fields(
+            user_id = %self.user_id,
+            policy = %self.policy,
+        )
+    )]
+    pub async fn insert(&self, database: &Pool<Postgres>) -> Result<Self, AuthenticationError> {
+        // The no-op update returns the existing row on conflict
+        let database_record = sqlx::query_as!(
+            PolicyAcceptances,
+            r#"
+                INSERT INTO policy_acceptances (id, user_id, policy, version, ip_address, accepted_on)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                ON CONFLICT (user_id, policy, version) DO UPDATE
+                SET version = EXCLUDED.version
+                RETURNING id, user_id, policy, version, ip_address, accepted_on
+            "#,
+            self.id,
+            self.user_id,
+            self.policy,
+            self.version,
+            self.ip_address,
+            self.accepted_on,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("Policy acceptance inserted: {}", database_record.id);
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn accepting_a_version_again_keeps_the_first_acceptance(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let first = database::PolicyAcceptances::mock_data(&user.id);
+
+        //-- Execute Function (Act)
+        let inserted = first.insert(&database).await?;
+        let again = database::PolicyAcceptances::mock_data(&user.id)
+            .insert(&database)
+            .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(inserted, first);
+        assert_eq!(again, first);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,20 @@
+//-- ./src/database/policy_acceptances/mod.rs
+
+//! Policy acceptances database module for the authentication service.
+//!
+//! The terms of service and privacy policy versions each user has accepted,
+//! with when and where from. Accepting a version again keeps the first
+//! acceptance.
+//!
+//! # Contents
+//! - Policy acceptance struct definition
+//! - Policy acceptance insert logic
+//! - Policy acceptance read logic
+
+// #![allow(unused)] // For development only
+
+pub use model::PolicyAcceptances;
+
+mod insert;
+mod model;
+mod read;
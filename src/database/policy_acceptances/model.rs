@@ -0,0 +1,67 @@
+//-- ./src/database/policy_acceptances/model.rs
+
+// #![allow(unused)] // For development only
+
+//! The policy acceptances database model.
+//!
+//! # Contents
+//! - `PolicyAcceptances` struct definition
+//! - Constructor for new policy acceptance instances
+//! - Mock data generation for tests
+
+use chrono::{DateTime, SubsecRound, Utc};
+use uuid::Uuid;
+
+use crate::domain;
+
+#[derive(Debug, sqlx::FromRow, Clone, PartialEq)]
+pub struct PolicyAcceptances {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub policy: String,
+    pub version: String,
+    pub ip_address: Option<String>,
+    pub accepted_on: DateTime<Utc>,
+}
+
+impl PolicyAcceptances {
+    /// # New Database Policy Acceptance Instance
+    ///
+    /// Creates a new acceptance of a policy version by a user.
+    ///
+    /// ## Parameters
+    ///
+    /// - `user_id: &Uuid` - The user accepting the policy
+    /// - `policy: domain::Policy` - The policy accepted
+    /// - `version: &str` - The version of the policy accepted
+    /// - `ip_address: Option<&str>` - The address the acceptance came from
+    pub fn new(
+        user_id: &Uuid,
+        policy: domain::Policy,
+        version: &str,
+        ip_address: Option<&str>,
+    ) -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            user_id: user_id.to_owned(),
+            policy: policy.to_string(),
+            version: version.to_string(),
+            ip_address: ip_address.map(str::to_string),
+            accepted_on: Utc::now().round_subsecs(0),
+        }
+    }
+
+    #[cfg(test)]
+    /// # Mock Policy Acceptance Data
+    ///
+    /// This function is only available in test mode `#[cfg(test)]`.
+    /// Creates a new acceptance of the terms of service by the user.
+    pub fn mock_data(user_id: &Uuid) -> Self {
+        Self::new(
+            user_id,
+            domain::Policy::TermsOfService,
+            "2026-10-15",
+            Some("127.0.0.1"),
+        )
+    }
+}
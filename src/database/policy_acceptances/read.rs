@@ -0,0 +1,88 @@
+//-- ./src/database/policy_acceptances/read.rs
+
+// #![allow(unused)] // For development only
+
+//! Policy acceptance read logic for the authentication service.
+//!
+//! # Contents
+//! - Index policy acceptances of given versions
+//! - Unit tests for read scenarios
+
+use sqlx::{Pool, Postgres};
+
+use crate::database::PolicyAcceptances;
+use crate::prelude::*;
+
+impl PolicyAcceptances {
+    /// Retrieve every user's acceptances of a policy version.
+    ///
+    /// # Parameters
+    /// * `policy` - The policy name.
+    /// * `version` - The version of the policy.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<PolicyAcceptances>)` - The acceptances of the version.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Index policy acceptances in the database: ", skip(database))]
+    pub async fn index_version(
+        policy: &str,
+        version: &str,
+        database: &Pool<Postgres>,
+    ) -> Result<Vec<Self>, AuthenticationError> {
+        let database_records = sqlx::query_as!(
+            PolicyAcceptances,
+            r#"
+                SELECT id, user_id, policy, version, ip_address, accepted_on
+                FROM policy_acceptances
+                WHERE policy = $1 AND version = $2
+            "#,
+            policy,
+            version,
+        )
+        .fetch_all(database)
+        .await?;
+
+        tracing::debug!("Policy acceptances retrieved: {}", database_records.len());
+
+        Ok(database_records)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::{database, domain};
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn only_acceptances_of_the_version_are_indexed(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let accepted = database::PolicyAcceptances::mock_data(&user.id)
+            .insert(&database)
+            .await?;
+        database::PolicyAcceptances::new(&user.id, domain::Policy::TermsOfService, "old", None)
+            .insert(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let index = database::PolicyAcceptances::index_version(
+            &accepted.policy,
+            &accepted.version,
+            &database,
+        )
+        .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(index, vec![accepted]);
+
+        Ok(())
+    }
+}
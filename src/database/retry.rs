@@ -0,0 +1,199 @@
+//-- ./src/database/retry.rs
+
+// #![allow(unused)] // For development only
+
+//! # Query Retries
+//!
+//! A reset connection or a serialization failure is usually gone by the next
+//! attempt, so [`retry`] runs a query again after a transient error, rather
+//! than failing the request. Retries wait a random delay that doubles with
+//! each attempt, see `utils::backoff::jittered_delay`, up to
+//! `database.retry.max_attempts` attempts.
+//!
+//! Only idempotent queries, such as reads, are retried, as a connection can
+//! fail after the database has run the query. Each retry is logged and
+//! counted by the `db.query.retries` metric. [`retry`] is for RPC reads, so
+//! every attempt together has the interactive budget, see `timeouts`.
+//! ---
+
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use opentelemetry::metrics::Counter;
+use opentelemetry::KeyValue;
+
+use crate::configuration::QueryRetryConfiguration;
+use crate::database::timeouts::{with_timeout, QueryBudget};
+use crate::prelude::*;
+use crate::utils::backoff::jittered_delay;
+
+/// The retry policy shared by the database models
+static RETRY_POLICY: OnceLock<RetryPolicy> = OnceLock::new();
+
+/// Postgres errors that succeed when tried again: serialization failure,
+/// deadlock detected and admin shutdown of the connection
+const TRANSIENT_SQLSTATES: [&str; 3] = ["40001", "40P01", "57P01"];
+
+/// Postgres connection exception errors, class `08`
+const CONNECTION_EXCEPTION_CLASS: &str = "08";
+
+/// How queries are retried after transient errors
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    retries: Counter<u64>,
+}
+
+impl RetryPolicy {
+    pub fn new(config: &QueryRetryConfiguration) -> Self {
+        let meter = opentelemetry::global::meter("authentication_service");
+
+        Self {
+            max_attempts: config.max_attempts.max(1),
+            base_delay: config.base_delay(),
+            max_delay: config.max_delay(),
+            retries: meter
+                .u64_counter("db.query.retries")
+                .with_description("Queries retried after a transient error")
+                .build(),
+        }
+    }
+
+    /// Run the query, again after each transient error until it has been
+    /// tried `max_attempts` times
+    pub async fn run<T, F, Fut>(
+        &self,
+        operation: &'static str,
+        mut query: F,
+    ) -> Result<T, sqlx::Error>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, sqlx::Error>>,
+    {
+        let mut attempts = 1;
+        loop {
+            match query().await {
+                Err(error) if attempts < self.max_attempts && is_transient(&error) => {
+                    let delay = jittered_delay(self.base_delay, self.max_delay, attempts);
+                    tracing::warn!(
+                        "Retrying {operation} in {delay:?} after attempt {attempts} failed: {error}"
+                    );
+                    self.retries.add(1, &[KeyValue::new("db.operation", operation)]);
+                    tokio::time::sleep(delay).await;
+                    attempts += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Use the configuration for the shared retry policy, if it hasn't been used
+/// yet
+pub fn install(config: &QueryRetryConfiguration) -> &'static RetryPolicy {
+    RETRY_POLICY.get_or_init(|| RetryPolicy::new(config))
+}
+
+/// Run an idempotent RPC read with the shared retry policy, the default
+/// until `install`ed, within the interactive query budget
+pub async fn retry<T, F, Fut>(
+    operation: &'static str,
+    query: F,
+) -> Result<T, AuthenticationError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, sqlx::Error>>,
+{
+    let policy =
+        RETRY_POLICY.get_or_init(|| RetryPolicy::new(&QueryRetryConfiguration::default()));
+
+    with_timeout(QueryBudget::Interactive, operation, policy.run(operation, query)).await
+}
+
+/// Is the error likely to be gone when the query is tried again
+pub fn is_transient(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::Database(error) => error.code().is_some_and(|code| {
+            TRANSIENT_SQLSTATES.contains(&code.as_ref())
+                || code.starts_with(CONNECTION_EXCEPTION_CLASS)
+        }),
+        sqlx::Error::Io(error) => matches!(
+            error.kind(),
+            std::io::ErrorKind::ConnectionReset
+                | std::io::ErrorKind::ConnectionAborted
+                | std::io::ErrorKind::BrokenPipe
+                | std::io::ErrorKind::UnexpectedEof
+        ),
+        _ => false,
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy::new(&QueryRetryConfiguration {
+            max_attempts,
+            base_delay_milliseconds: 0,
+            max_delay_milliseconds: 0,
+        })
+    }
+
+    fn connection_reset() -> sqlx::Error {
+        sqlx::Error::Io(std::io::ErrorKind::ConnectionReset.into())
+    }
+
+    #[tokio::test]
+    async fn transient_errors_are_retried() {
+        //-- Setup and Fixtures (Arrange)
+        let attempts = AtomicU32::new(0);
+        let counter = &attempts;
+        let query = move || async move {
+            match counter.fetch_add(1, Ordering::SeqCst) {
+                0 => Err(connection_reset()),
+                attempt => Ok(attempt),
+            }
+        };
+
+        //-- Execute Function (Act)
+        let result = policy(3).run("test", query).await;
+
+        //-- Checks (Assertions)
+        assert!(matches!(result, Ok(1)));
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn retries_stop_at_max_attempts_and_other_errors() {
+        //-- Setup and Fixtures (Arrange)
+        let (transient, not_found) = (AtomicU32::new(0), AtomicU32::new(0));
+        let (transient_counter, not_found_counter) = (&transient, &not_found);
+
+        //-- Execute Function (Act)
+        let exhausted = policy(3)
+            .run("test", move || async move {
+                transient_counter.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>(connection_reset())
+            })
+            .await;
+        let missing = policy(3)
+            .run("test", move || async move {
+                not_found_counter.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>(sqlx::Error::RowNotFound)
+            })
+            .await;
+
+        //-- Checks (Assertions)
+        assert!(matches!(exhausted, Err(sqlx::Error::Io(_))));
+        assert_eq!(transient.load(Ordering::SeqCst), 3);
+        assert!(matches!(missing, Err(sqlx::Error::RowNotFound)));
+        assert_eq!(not_found.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,133 @@
+//-- ./src/database/saml_requests/delete.rs
+
+// #![allow(unused)] // For development only
+
+//! SAML request delete logic for the authentication service.
+//!
+//! # Contents
+//! - Consume a request when its response is accepted
+//! - Delete expired requests
+//! - Unit tests for delete scenarios
+
+use sqlx::PgExecutor;
+
+use crate::database::SamlRequests;
+use crate::prelude::*;
+
+impl SamlRequests {
+    /// Delete an unexpired request, returning it. The request is deleted in
+    /// the same statement, so a response to it can only be accepted once.
+    ///
+    /// # Parameters
+    /// * `id` - The response InResponseTo, the AuthnRequest ID.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Some(SamlRequests))` - The consumed request.
+    /// * `Ok(None)` - If the request is unknown, answered or expired.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Consume a SAML request in the database: ", skip(database))]
+    pub async fn consume(
+        id: &str,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Option<Self>, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            SamlRequests,
+            r#"
+                DELETE FROM saml_requests
+                WHERE id = $1 AND expires_on > NOW()
+                RETURNING id, expires_on, created_on, link_user_id
+            "#,
+            id,
+        )
+        .fetch_optional(database)
+        .await?;
+
+        match &database_record {
+            Some(record) => tracing::debug!("SAML request consumed: {}", record.id),
+            None => tracing::debug!("SAML request is unknown or expired"),
+        }
+
+        Ok(database_record)
+    }
+
+    /// Delete requests that have expired, their responses are no longer accepted.
+    ///
+    /// # Parameters
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of requests deleted.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Delete expired SAML requests from the database: ",
+        skip(database)
+    )]
+    pub async fn delete_expired(
+        database: impl PgExecutor<'_>,
+    ) -> Result<u64, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                DELETE FROM saml_requests
+                WHERE expires_on < NOW()
+            "#,
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Expired SAML requests deleted: {rows_affected}");
+
+        Ok(rows_affected)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use chrono::{Duration, Utc};
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn saml_requests_are_single_use(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let request = database::SamlRequests::mock_data()
+            .insert(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let first = database::SamlRequests::consume(&request.id, &database).await?;
+        let second = database::SamlRequests::consume(&request.id, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(first, Some(request));
+        assert!(second.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn expired_requests_are_deleted(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let mut expired = database::SamlRequests::mock_data();
+        expired.expires_on = Utc::now() - Duration::minutes(1);
+        expired.insert(&database).await?;
+        database::SamlRequests::mock_data().insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let consumed = database::SamlRequests::consume(&expired.id, &database).await?;
+        let deleted = database::SamlRequests::delete_expired(&database).await?;
+
+        //-- Checks (Assertions)
+        assert!(consumed.is_none());
+        assert_eq!(deleted, 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+//-- ./src/database/saml_requests/insert.rs
+
+// #![allow(unused)] // For development only
+
+//! SAML request insertion logic for the authentication service.
+//!
+//! # Contents
+//! - Insert a SAML request
+//! - Unit tests for insert scenarios
+
+use sqlx::PgExecutor;
+
+use crate::database::SamlRequests;
+use crate::prelude::*;
+
+impl SamlRequests {
+    /// Insert a SAML request into the database.
+    ///
+    /// # Parameters
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(SamlRequests)` - The inserted request record.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Insert a SAML request into the database: ",
+        skip(self, database),
+        fields(id = %self.id)
+    )]
+    pub async fn insert(
+        &self,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            SamlRequests,
+            r#"
+                INSERT INTO saml_requests (id, expires_on, created_on, link_user_id)
+                VALUES ($1, $2, $3, $4)
+                RETURNING id, expires_on, created_on, link_user_id
+            "#,
+            self.id,
+            self.expires_on,
+            self.created_on,
+            self.link_user_id,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("SAML request inserted: {}", database_record.id);
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn insert_saml_request(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let request = database::SamlRequests::mock_data();
+
+        //-- Execute Function (Act)
+        let database_record = request.insert(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(database_record, request);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,20 @@
+//-- ./src/database/saml_requests/mod.rs
+
+//! SAML requests database module for the authentication service.
+//!
+//! The SAML authentication requests sent to the identity provider and not yet
+//! answered. A request is deleted when its response is accepted, so each
+//! response can only be used once.
+//!
+//! # Contents
+//! - SAML request struct and model-level helpers
+//! - SAML request insertion logic
+//! - SAML request consume and delete logic
+
+// #![allow(unused)] // For development only
+
+pub use model::SamlRequests;
+
+mod delete;
+mod insert;
+mod model;
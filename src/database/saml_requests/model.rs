@@ -0,0 +1,58 @@
+//-- ./src/database/saml_requests/model.rs
+
+// #![allow(unused)] // For development only
+
+//! The SAML requests database model.
+//!
+//! # Contents
+//! - `SamlRequests` struct definition
+//! - Constructor for new requests
+//! - Marking a request as linking an identity to a user
+//! - Mock data generation for tests
+
+use chrono::{DateTime, SubsecRound, Utc};
+use uuid::Uuid;
+
+#[derive(Debug, sqlx::FromRow, Clone, PartialEq)]
+pub struct SamlRequests {
+    pub id: String,
+    pub expires_on: DateTime<Utc>,
+    pub created_on: DateTime<Utc>,
+    pub link_user_id: Option<Uuid>,
+}
+
+impl SamlRequests {
+    /// # New Database SAML Request Instance
+    ///
+    /// Creates a new request, kept until the identity provider answers it.
+    ///
+    /// ## Parameters
+    ///
+    /// - `id: &str` - The AuthnRequest ID
+    /// - `duration: &std::time::Duration` - How long the login can take
+    pub fn new(id: &str, duration: &std::time::Duration) -> Self {
+        let now = Utc::now().round_subsecs(0);
+
+        Self {
+            id: id.to_string(),
+            expires_on: now + *duration,
+            created_on: now,
+            link_user_id: None,
+        }
+    }
+
+    /// The identity in the response is linked to the user, instead of
+    /// logging in as the user it identifies
+    pub fn link_user(mut self, user_id: &Uuid) -> Self {
+        self.link_user_id = Some(user_id.to_owned());
+        self
+    }
+
+    #[cfg(test)]
+    pub fn mock_data() -> Self {
+        Self::new(
+            &format!("id{}", Uuid::now_v7().simple()),
+            &std::time::Duration::from_secs(5 * 60),
+        )
+    }
+}
@@ -0,0 +1,153 @@
+//-- ./src/database/sessions/count.rs
+
+// #![allow(unused)] // For development only
+
+//! Session count and exists helpers for the authentication service.
+//!
+//! Used for pagination totals and preflight checks, without fetching full rows.
+//! Active session counts live with the reads, in `read.rs`.
+//!
+//! # Contents
+//! - Count all sessions, or a user's sessions
+//! - Check a session id exists
+//! - Unit tests for count scenarios
+
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::database::Sessions;
+use crate::prelude::*;
+
+impl Sessions {
+    /// Count the sessions in the database, including revoked and expired.
+    ///
+    /// # Parameters
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of sessions.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Count Sessions in the database: ", skip(database))]
+    pub async fn count(
+        database: &Pool<Postgres>,
+    ) -> Result<u64, AuthenticationError> {
+        let count = sqlx::query_scalar!(
+            r#"
+                SELECT COUNT(*) AS "count!"
+                FROM sessions
+            "#
+        )
+        .fetch_one(database)
+        .await?;
+
+        Ok(count as u64)
+    }
+
+    /// Count a user's sessions, including revoked and expired.
+    ///
+    /// # Parameters
+    /// * `user_id` - The user id.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of the user's sessions.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Count a User's Sessions in the database: ",
+        skip(database)
+    )]
+    pub async fn count_by_user_id(
+        user_id: &Uuid,
+        database: &Pool<Postgres>,
+    ) -> Result<u64, AuthenticationError> {
+        let count = sqlx::query_scalar!(
+            r#"
+                SELECT COUNT(*) AS "count!"
+                FROM sessions
+                WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_one(database)
+        .await?;
+
+        Ok(count as u64)
+    }
+
+    /// Check a session with the id exists.
+    ///
+    /// # Parameters
+    /// * `id` - The session id.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(bool)` - If the session exists.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Check a Session id exists in the database: ",
+        skip(database)
+    )]
+    pub async fn exists_by_id(
+        id: &Uuid,
+        database: &Pool<Postgres>,
+    ) -> Result<bool, AuthenticationError> {
+        let exists = sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (SELECT 1 FROM sessions WHERE id = $1) AS "exists!"
+            "#,
+            id
+        )
+        .fetch_one(database)
+        .await?;
+
+        Ok(exists)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+    use uuid::Uuid;
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn count_sessions_by_user(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?.insert(&database).await?;
+        let other_user = database::Users::mock_data()?.insert(&database).await?;
+        let session = database::Sessions::mock_data(&user)
+            .await?
+            .insert(&database)
+            .await?;
+        let mut revoked = database::Sessions::mock_data(&user).await?;
+        revoked.is_active = false;
+        revoked.insert(&database).await?;
+        database::Sessions::mock_data(&other_user)
+            .await?
+            .insert(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let count = database::Sessions::count(&database).await?;
+        let user_count =
+            database::Sessions::count_by_user_id(&user.id, &database).await?;
+        let exists =
+            database::Sessions::exists_by_id(&session.id, &database).await?;
+        let missing =
+            database::Sessions::exists_by_id(&Uuid::now_v7(), &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(count, 3);
+        assert_eq!(user_count, 2);
+        assert!(exists);
+        assert!(!missing);
+
+        Ok(())
+    }
+}
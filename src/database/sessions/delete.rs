@@ -11,6 +11,8 @@
 //! - Delete a single session by instance or ID
 //! - Delete all sessions for a specific user
 //! - Delete all sessions in the database
+//! - Delete the sessions scoped to an organization, for all or one user
+//! - Delete expired and revoked sessions
 //! - Unit tests for all deletion scenarios
 
 use sqlx::{Pool, Postgres};
@@ -147,6 +149,49 @@ impl Sessions {
         Ok(rows_affected)
     }
 
+    /// Delete a user's sessions scoped to an organization from the database.
+    ///
+    /// The user's sessions in other organizations, or not scoped to one, are kept.
+    ///
+    /// # Parameters
+    /// * `user_id` - The UUID of the user whose sessions should be deleted.
+    /// * `organization_id` - The UUID of the organization the sessions are scoped to.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of sessions deleted.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Delete a User's organization Sessions from the database: ",
+        skip(database),
+        fields(
+            user_id = ?user_id,
+            organization_id = ?organization_id,
+        )
+    )]
+    pub async fn delete_all_user_organization(
+        user_id: &Uuid,
+        organization_id: &Uuid,
+        database: &Pool<Postgres>,
+    ) -> Result<u64, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                DELETE
+                FROM sessions
+                WHERE user_id = $1 AND organization_id = $2
+            "#,
+            user_id,
+            organization_id
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Sessions database records deleted: {rows_affected:#?}");
+
+        Ok(rows_affected)
+    }
+
     /// Delete all sessions from the database.
     ///
     /// Executes a SQL `DELETE` statement to remove all session records from the `sessions` table.
@@ -179,6 +224,77 @@ impl Sessions {
 
         Ok(rows_affected)
     }
+
+    /// Delete all sessions scoped to an organization from the database.
+    ///
+    /// # Parameters
+    /// * `organization_id` - The UUID of the organization the sessions are scoped to.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of sessions deleted.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Delete all organization Sessions from the database: ",
+        skip(database)
+    )]
+    pub async fn delete_all_organization(
+        organization_id: &Uuid,
+        database: &Pool<Postgres>,
+    ) -> Result<u64, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                DELETE
+                FROM sessions
+                WHERE organization_id = $1
+            "#,
+            organization_id
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Sessions rows deleted from the database: {rows_affected:#?}");
+
+        Ok(rows_affected)
+    }
+
+    /// Delete expired and revoked sessions from the database.
+    ///
+    /// Executes a SQL `DELETE` statement to remove session records that have passed their
+    /// `expires_on` time or are no longer active. Neither can be used to refresh an access token.
+    ///
+    /// # Parameters
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of sessions deleted.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    ///
+    /// # Tracing
+    /// - Adds a tracing span for observability.
+    #[tracing::instrument(
+        name = "Delete expired Sessions from the database: ",
+        skip(database)
+    )]
+    pub async fn delete_expired(
+        database: &Pool<Postgres>,
+    ) -> Result<u64, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                DELETE
+                FROM sessions
+                WHERE expires_on < NOW() OR is_active = false
+            "#,
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Expired Sessions rows deleted from the database: {rows_affected:#?}");
+
+        Ok(rows_affected)
+    }
 }
 
 //-- Unit Tests
@@ -386,4 +502,74 @@ pub mod tests {
 
         Ok(())
     }
+
+    #[sqlx::test]
+    async fn delete_expired_keeps_active_sessions(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let random_user = database::Users::mock_data()?.insert(&database).await?;
+
+        let mut active = database::Sessions::mock_data(&random_user).await?;
+        active.is_active = true;
+        active.expires_on = chrono::Utc::now() + chrono::Duration::days(1);
+        let active = active.insert(&database).await?;
+
+        let mut expired = database::Sessions::mock_data(&random_user).await?;
+        expired.is_active = true;
+        expired.expires_on = chrono::Utc::now() - chrono::Duration::days(1);
+        let _expired = expired.insert(&database).await?;
+
+        let mut revoked = database::Sessions::mock_data(&random_user).await?;
+        revoked.is_active = false;
+        revoked.expires_on = chrono::Utc::now() + chrono::Duration::days(1);
+        let _revoked = revoked.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let rows_affected = database::Sessions::delete_expired(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(rows_affected, 2);
+        assert_eq!(database::Sessions::from_id(&active.id, &database).await?, active);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn delete_all_organization_keeps_other_sessions(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let random_user = database::Users::mock_data()?.insert(&database).await?;
+        let organization = database::Organizations::mock_data()?.insert(&database).await?;
+        let other_organization =
+            database::Organizations::mock_data()?.insert(&database).await?;
+
+        let _in_organization = database::Sessions::mock_data(&random_user)
+            .await?
+            .with_organization_id(&organization.id)
+            .insert(&database)
+            .await?;
+        let in_other_organization = database::Sessions::mock_data(&random_user)
+            .await?
+            .with_organization_id(&other_organization.id)
+            .insert(&database)
+            .await?;
+        let not_scoped = database::Sessions::mock_data(&random_user)
+            .await?
+            .insert(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let rows_affected =
+            database::Sessions::delete_all_organization(&organization.id, &database)
+                .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(rows_affected, 1);
+        assert!(database::Sessions::from_id(&in_other_organization.id, &database)
+            .await
+            .is_ok());
+        assert!(database::Sessions::from_id(&not_scoped.id, &database).await.is_ok());
+
+        Ok(())
+    }
 }
@@ -12,7 +12,7 @@
 //! - Unit tests for session insertion logic
 
 use crate::prelude::*;
-use sqlx::{Pool, Postgres};
+use sqlx::{PgExecutor, Pool, Postgres};
 
 use crate::database;
 
@@ -23,7 +23,7 @@ impl database::Sessions {
     /// # Parameters
     ///
     /// * `self` - A sessions instance
-    /// * `database` - An Sqlx database connection pool
+    /// * `database` - An Sqlx database connection pool, or a transaction
     /// ---
     #[tracing::instrument(
         name = "Insert a new Sessions into the database: ",
@@ -34,13 +34,13 @@ impl database::Sessions {
     )]
     pub async fn insert(
         &self,
-        database: &Pool<Postgres>,
+        database: impl PgExecutor<'_>,
     ) -> Result<Self, AuthenticationError> {
         let database_record = sqlx::query_as!(
             database::Sessions,
             r#"
-				INSERT INTO sessions (id, user_id, logged_in_at, login_ip, expires_on, refresh_token, is_active, logged_out_at, logout_ip)
-				VALUES ($1, $2, $3, $4, $5, $6, $7,$8, $9) 
+				INSERT INTO sessions (id, user_id, logged_in_at, login_ip, expires_on, refresh_token, is_active, logged_out_at, logout_ip, absolute_expires_on, last_refreshed_at, access_token_id, organization_id, client_id, last_used_at, request_count)
+				VALUES ($1, $2, $3, $4, $5, $6, $7,$8, $9, $10, $11, $12, $13, $14, $15, $16) 
 				RETURNING *
 			"#,
             self.id,
@@ -51,7 +51,14 @@ impl database::Sessions {
             self.refresh_token.as_ref(),
             self.is_active,
             self.logged_out_at,
-            self.logout_ip
+            self.logout_ip,
+            self.absolute_expires_on,
+            self.last_refreshed_at,
+            self.access_token_id,
+            self.organization_id,
+            self.client_id,
+            self.last_used_at,
+            self.request_count
         )
         .fetch_one(database)
         .await?;
@@ -6,6 +6,7 @@
 //! It organises logic into submodules for each operation and re-exports the main `Sessions` model for convenient use elsewhere.
 //!
 //! # Contents
+//! - Session count and exists helpers
 //! - Session deletion logic
 //! - Session insertion/creation logic
 //! - Session struct definition and model-level helpers
@@ -16,6 +17,7 @@
 
 pub use model::Sessions;
 
+mod count;
 mod delete;
 mod insert;
 mod model;
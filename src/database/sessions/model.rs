@@ -19,6 +19,7 @@ use chrono::{DateTime, SubsecRound, Utc};
 use std::time;
 use uuid::Uuid;
 
+use crate::utils::{Clock, SystemClock};
 use crate::{database, domain, prelude::AuthenticationError};
 
 #[derive(Debug, serde::Deserialize, sqlx::FromRow, Clone, PartialEq)]
@@ -32,6 +33,13 @@ pub struct Sessions {
     pub is_active: bool,
     pub logged_out_at: Option<DateTime<Utc>>,
     pub logout_ip: Option<i32>,
+    pub absolute_expires_on: Option<DateTime<Utc>>,
+    pub last_refreshed_at: Option<DateTime<Utc>>,
+    pub access_token_id: Option<String>,
+    pub organization_id: Option<Uuid>,
+    pub client_id: Option<Uuid>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub request_count: i64,
 }
 
 impl Sessions {
@@ -52,6 +60,20 @@ impl Sessions {
         login_ip: &Option<i32>,
         duration: &time::Duration,
         refresh_token: &domain::RefreshToken,
+    ) -> Result<Self, AuthenticationError> {
+        Self::new_with_clock(user, login_ip, duration, refresh_token, &SystemClock)
+    }
+
+    /// # New Database Sessions Instance at the Clock Time
+    ///
+    /// The same as `Sessions::new`, logged in at the time of `clock` instead
+    /// of the system time
+    pub fn new_with_clock(
+        user: &database::Users,
+        login_ip: &Option<i32>,
+        duration: &time::Duration,
+        refresh_token: &domain::RefreshToken,
+        clock: &dyn Clock,
     ) -> Result<Self, AuthenticationError> {
         // The unique (primary key) session id as a UUid v7
         let id = Uuid::now_v7();
@@ -60,7 +82,7 @@ impl Sessions {
         let user_id = user.id.to_owned();
 
         // The login time is the current time
-        let logged_in_at = Utc::now().round_subsecs(0);
+        let logged_in_at = clock.now().round_subsecs(0);
 
         // The login IP address is the IP address of the user request
         let login_ip = login_ip.to_owned();
@@ -80,6 +102,25 @@ impl Sessions {
         // The  IP address from were the logout request is sent. Optional as they may not have logged out. So the session expires.
         let logout_ip = None;
 
+        // Sessions have a fixed expiry unless made sliding with `with_absolute_lifetime`
+        let absolute_expires_on = None;
+
+        // The session has not refreshed an access token yet
+        let last_refreshed_at = None;
+
+        // The access token issued for the session is set with `with_access_token_id`
+        let access_token_id = None;
+
+        // Sessions are not scoped to an organization unless set with `with_organization_id`
+        let organization_id = None;
+
+        // Sessions are not started for an OAuth client unless set with `with_client_id`
+        let client_id = None;
+
+        // The session has not been used since login, activity is recorded by `SessionActivity`
+        let last_used_at = None;
+        let request_count = 0;
+
         Ok(Self {
             id,
             user_id,
@@ -90,9 +131,76 @@ impl Sessions {
             is_active,
             logged_out_at,
             logout_ip,
+            absolute_expires_on,
+            last_refreshed_at,
+            access_token_id,
+            organization_id,
+            client_id,
+            last_used_at,
+            request_count,
         })
     }
 
+    /// # Sliding Session
+    ///
+    /// Make the session sliding: each refresh can extend `expires_on`, but never
+    /// past `logged_in_at + absolute_lifetime`. The absolute lifetime is never
+    /// shorter than the session's initial expiry.
+    ///
+    /// ## Parameters
+    ///
+    /// - `absolute_lifetime: &time::Duration` - The longest the session can last from login
+    pub fn with_absolute_lifetime(mut self, absolute_lifetime: &time::Duration) -> Self {
+        let absolute_expires_on = self.logged_in_at + *absolute_lifetime;
+        self.absolute_expires_on = Some(absolute_expires_on.max(self.expires_on));
+        self
+    }
+
+    /// # Session Access Token
+    ///
+    /// Record the jti of the access token issued with the session, so it can be
+    /// denied if the session is revoked before the token expires.
+    pub fn with_access_token_id(mut self, access_token_id: &str) -> Self {
+        self.access_token_id = Some(access_token_id.to_string());
+        self
+    }
+
+    /// # Session Organization
+    ///
+    /// Scope the session to the organization the user logged in to, so
+    /// refreshed access tokens keep the same organization.
+    pub fn with_organization_id(mut self, organization_id: &Uuid) -> Self {
+        self.organization_id = Some(organization_id.to_owned());
+        self
+    }
+
+    /// # Session OAuth Client
+    ///
+    /// Record the OAuth client the session was started for, so it only
+    /// refreshes while the user's grant to the client is valid.
+    pub fn with_client_id(mut self, client_id: &Uuid) -> Self {
+        self.client_id = Some(client_id.to_owned());
+        self
+    }
+
+    /// The latest this session can be used until, including any sliding extension
+    pub fn max_expires_on(&self) -> DateTime<Utc> {
+        self.absolute_expires_on.unwrap_or(self.expires_on)
+    }
+
+    /// When the session was last used: logged in, refreshed or used by an access token
+    pub fn last_active_at(&self) -> DateTime<Utc> {
+        [self.last_used_at, self.last_refreshed_at]
+            .into_iter()
+            .flatten()
+            .fold(self.logged_in_at, DateTime::max)
+    }
+
+    /// Has the session gone unused for longer than the idle timeout
+    pub fn is_idle(&self, idle_timeout: &chrono::Duration) -> bool {
+        self.last_active_at() + *idle_timeout <= Utc::now()
+    }
+
     #[cfg(test)]
     /// # Mock Session Data
     /// 
@@ -176,6 +284,13 @@ impl Sessions {
             is_active: random_is_active,
             logged_out_at: random_logged_out_at,
             logout_ip: random_logout_ip,
+            absolute_expires_on: None,
+            last_refreshed_at: None,
+            access_token_id: None,
+            organization_id: None,
+            client_id: None,
+            last_used_at: None,
+            request_count: 0,
         };
 
         Ok(mock_session)
@@ -230,6 +345,21 @@ mod tests {
         assert_ne!(session1.refresh_token, session2.refresh_token);
     }
 
+    #[tokio::test]
+    async fn sessions_are_idle_from_their_last_activity() {
+        let user = Users::mock_data().unwrap();
+        let mut session = Sessions::mock_data(&user).await.unwrap();
+        let idle_timeout = chrono::Duration::days(7);
+        session.logged_in_at = Utc::now() - chrono::Duration::days(30);
+        session.last_refreshed_at = Some(Utc::now() - chrono::Duration::days(10));
+
+        assert!(session.is_idle(&idle_timeout));
+
+        session.last_used_at = Some(Utc::now() - chrono::Duration::days(1));
+        assert_eq!(session.last_active_at(), session.last_used_at.unwrap());
+        assert!(!session.is_idle(&idle_timeout));
+    }
+
     #[tokio::test]
     async fn session_struct_partial_eq_and_clone_work() {
         let user = Users::mock_data().unwrap();
@@ -5,7 +5,11 @@
 //! Database read/query operations for the `Sessions` table.
 //!
 //! This module provides asynchronous functions for retrieving session records from the database,
-//! including fetching by session ID, refresh token, user ID, and paginated queries for all sessions.
+//! including fetching by session ID, refresh token, user ID, offset and cursor paginated queries,
+//! and counting a user's active sessions.
+//!
+//! Cursor pagination orders by `(logged_in_at, id)`, so pages stay stable while sessions are
+//! inserted or revoked, unlike offset pagination.
 //!
 //! All functions return a `Result` with either the requested session data or an `AuthenticationError`.
 //! Errors are returned if the database query fails or if no matching session is found.
@@ -21,12 +25,41 @@
 //! let session = Sessions::from_id(&Uuid::new_v7(), &database_pool).await;
 //! ```
 
+use chrono::{DateTime, Utc};
 use sqlx::{Pool, Postgres};
 use uuid::Uuid;
 
-use crate::database::Sessions;
+use crate::database::retry::retry;
+use crate::database::timeouts::{with_timeout, QueryBudget};
+use crate::database::{Sessions, SortDirection};
 use crate::prelude::*;
 
+/// Convert a pagination value into the i64 Postgres expects
+fn safe_cast_to_i64(value: usize) -> Result<i64, AuthenticationError> {
+    i64::try_from(value).map_err(|_| {
+        AuthenticationError::ValidationError("Pagination value too large".to_string())
+    })
+}
+
+/// Check the cursor pagination arguments are consistent
+fn validate_cursor_pagination(
+    limit: usize,
+    cursor_logged_in_at: Option<DateTime<Utc>>,
+    cursor_id: Option<Uuid>,
+) -> Result<(), AuthenticationError> {
+    if limit > 1000 {
+        tracing::warn!("Large limit requested: {}", limit);
+    }
+
+    // Validate cursor consistency
+    match (cursor_logged_in_at, cursor_id) {
+        (None, None) | (Some(_), Some(_)) => Ok(()),
+        _ => Err(AuthenticationError::ValidationError(
+            "Both cursor_logged_in_at and cursor_id must be provided together".to_string(),
+        )),
+    }
+}
+
 impl Sessions {
     /// Retrieves a Sessions instance from the database by querying with the provided session UUID.
     ///
@@ -53,16 +86,18 @@ impl Sessions {
         id: &Uuid,
         database: &Pool<Postgres>,
     ) -> Result<Sessions, AuthenticationError> {
-        let database_record = sqlx::query_as!(
-            Sessions,
-            r#"
-                SELECT id, user_id, logged_in_at, login_ip, expires_on, refresh_token, is_active, logged_out_at, logout_ip
+        let database_record = retry("sessions.from_id", || {
+            sqlx::query_as!(
+                Sessions,
+                r#"
+                SELECT id, user_id, logged_in_at, login_ip, expires_on, refresh_token, is_active, logged_out_at, logout_ip, absolute_expires_on, last_refreshed_at, access_token_id, organization_id, client_id, last_used_at, request_count
                 FROM sessions
                 WHERE id = $1
             "#,
-            id
-        )
-        .fetch_one(database)
+                id
+            )
+            .fetch_one(database)
+        })
         .await?;
 
         tracing::debug!("Sessions database records retrieved: {database_record:#?}");
@@ -95,16 +130,59 @@ impl Sessions {
         refresh_token: &str,
         database: &Pool<Postgres>,
     ) -> Result<Sessions, AuthenticationError> {
-        let database_record = sqlx::query_as!(
-            Sessions,
-            r#"
-                SELECT id, user_id, logged_in_at, login_ip, expires_on, refresh_token, is_active, logged_out_at, logout_ip
+        let database_record = retry("sessions.from_token", || {
+            sqlx::query_as!(
+                Sessions,
+                r#"
+                SELECT id, user_id, logged_in_at, login_ip, expires_on, refresh_token, is_active, logged_out_at, logout_ip, absolute_expires_on, last_refreshed_at, access_token_id, organization_id, client_id, last_used_at, request_count
                 FROM sessions
                 WHERE refresh_token = $1
             "#,
-            refresh_token
-        )
-        .fetch_one(database)
+                refresh_token
+            )
+            .fetch_one(database)
+        })
+        .await?;
+
+        tracing::debug!("Sessions database records retrieved: {database_record:#?}");
+
+        Ok(database_record)
+    }
+
+    /// Retrieves the Sessions instance an access token was issued for.
+    ///
+    /// # Parameters
+    ///
+    /// * `access_token_id` - The access token jti recorded on the session.
+    /// * `database` - The sqlx database pool to execute the query against.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the `Sessions` instance on success, or an `AuthenticationError` on failure.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails or if no session has the access token id.
+    #[tracing::instrument(
+        name = "Get the session associated with access token: ",
+        skip(database)
+    )]
+    pub async fn from_access_token_id(
+        access_token_id: &str,
+        database: &Pool<Postgres>,
+    ) -> Result<Sessions, AuthenticationError> {
+        let database_record = retry("sessions.from_access_token_id", || {
+            sqlx::query_as!(
+                Sessions,
+                r#"
+                SELECT id, user_id, logged_in_at, login_ip, expires_on, refresh_token, is_active, logged_out_at, logout_ip, absolute_expires_on, last_refreshed_at, access_token_id, organization_id, client_id, last_used_at, request_count
+                FROM sessions
+                WHERE access_token_id = $1
+            "#,
+                access_token_id
+            )
+            .fetch_one(database)
+        })
         .await?;
 
         tracing::debug!("Sessions database records retrieved: {database_record:#?}");
@@ -143,20 +221,24 @@ impl Sessions {
         offset: &usize,
         database: &Pool<Postgres>,
     ) -> Result<Vec<Sessions>, AuthenticationError> {
-        let database_records = sqlx::query_as!(
-            Sessions,
-            r#"
-                SELECT id, user_id, logged_in_at, login_ip, expires_on, refresh_token, is_active, logged_out_at, logout_ip
-                FROM sessions
-                WHERE user_id = $1
-                ORDER BY id
-                LIMIT $2 OFFSET $3
-            "#,
-            user_id,
-            *limit as i64,
-            *offset as i64,
+        let database_records = with_timeout(
+            QueryBudget::Interactive,
+            "sessions.index_from_user_id",
+            sqlx::query_as!(
+                Sessions,
+                r#"
+                    SELECT id, user_id, logged_in_at, login_ip, expires_on, refresh_token, is_active, logged_out_at, logout_ip, absolute_expires_on, last_refreshed_at, access_token_id, organization_id, client_id, last_used_at, request_count
+                    FROM sessions
+                    WHERE user_id = $1
+                    ORDER BY id
+                    LIMIT $2 OFFSET $3
+                "#,
+                user_id,
+                *limit as i64,
+                *offset as i64,
+            )
+            .fetch_all(database),
         )
-        .fetch_all(database)
         .await?;
 
         tracing::debug!(
@@ -194,18 +276,22 @@ impl Sessions {
         offset: &usize,
         database: &Pool<Postgres>,
     ) -> Result<Vec<Sessions>, AuthenticationError> {
-        let database_records = sqlx::query_as!(
-            Sessions,
-            r#"
-                SELECT id, user_id, logged_in_at, login_ip, expires_on, refresh_token, is_active, logged_out_at, logout_ip
-                FROM sessions
-                ORDER BY id
-                LIMIT $1 OFFSET $2
-            "#,
-            *limit as i64,
-            *offset as i64,
+        let database_records = with_timeout(
+            QueryBudget::Interactive,
+            "sessions.index",
+            sqlx::query_as!(
+                Sessions,
+                r#"
+                    SELECT id, user_id, logged_in_at, login_ip, expires_on, refresh_token, is_active, logged_out_at, logout_ip, absolute_expires_on, last_refreshed_at, access_token_id, organization_id, client_id, last_used_at, request_count
+                    FROM sessions
+                    ORDER BY id
+                    LIMIT $1 OFFSET $2
+                "#,
+                *limit as i64,
+                *offset as i64,
+            )
+            .fetch_all(database),
         )
-        .fetch_all(database)
         .await?;
 
         tracing::debug!(
@@ -214,6 +300,294 @@ impl Sessions {
 
         Ok(database_records)
     }
+
+    /// Retrieves a paginated list of the Sessions scoped to an organization.
+    ///
+    /// # Parameters
+    ///
+    /// * `organization_id` - The organization the sessions logged in to.
+    /// * `limit` - An i64 specifying the maximum number of Sessions to return.
+    /// * `offset` - An i64 specifying the number of Sessions to skip before starting to collect the result set.
+    /// * `database` - The sqlx database pool to execute the query against.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    #[tracing::instrument(
+        name = "Index of organization Sessions with offset and limit: ",
+        skip(database),
+        fields(
+            organization_id = %organization_id,
+            limit = ?limit,
+            offset = ?offset,
+        )
+    )]
+    pub async fn index_organization(
+        organization_id: &Uuid,
+        limit: &usize,
+        offset: &usize,
+        database: &Pool<Postgres>,
+    ) -> Result<Vec<Sessions>, AuthenticationError> {
+        let database_records = with_timeout(
+            QueryBudget::Interactive,
+            "sessions.index_organization",
+            sqlx::query_as!(
+                Sessions,
+                r#"
+                    SELECT id, user_id, logged_in_at, login_ip, expires_on, refresh_token, is_active, logged_out_at, logout_ip, absolute_expires_on, last_refreshed_at, access_token_id, organization_id, client_id, last_used_at, request_count
+                    FROM sessions
+                    WHERE organization_id = $1
+                    ORDER BY id
+                    LIMIT $2 OFFSET $3
+                "#,
+                organization_id,
+                *limit as i64,
+                *offset as i64,
+            )
+            .fetch_all(database),
+        )
+        .await?;
+
+        tracing::debug!(
+            "Organization sessions database records retrieved: {database_records:#?}"
+        );
+
+        Ok(database_records)
+    }
+
+    /// Retrieves a page of all Sessions using cursor (keyset) pagination.
+    ///
+    /// Sessions are ordered by `(logged_in_at, id)`. Pass the `logged_in_at` and `id` of the
+    /// last session on the previous page as the cursor, or `None` for both to get the first page.
+    ///
+    /// # Parameters
+    ///
+    /// * `limit` - The maximum number of Sessions to return.
+    /// * `direction` - Oldest first when ascending, newest first when descending.
+    /// * `cursor_logged_in_at` - The `logged_in_at` of the last session on the previous page.
+    /// * `cursor_id` - The `id` of the last session on the previous page.
+    /// * `database` - The sqlx database pool to execute the query against.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` if only one of the cursor values is provided, or an error if
+    /// the database query fails.
+    #[tracing::instrument(
+        name = "Index of Sessions with cursor pagination: ",
+        skip(database),
+        fields(
+            limit = %limit,
+            direction = ?direction,
+            cursor_logged_in_at = ?cursor_logged_in_at,
+            cursor_id = ?cursor_id,
+        )
+    )]
+    pub async fn index_cursor(
+        limit: usize,
+        direction: SortDirection,
+        cursor_logged_in_at: Option<DateTime<Utc>>,
+        cursor_id: Option<Uuid>,
+        database: &Pool<Postgres>,
+    ) -> Result<Vec<Sessions>, AuthenticationError> {
+        validate_cursor_pagination(limit, cursor_logged_in_at, cursor_id)?;
+
+        let limit_i64 = safe_cast_to_i64(limit)?;
+
+        let database_records = match direction {
+            // Oldest first, paging forward
+            SortDirection::Ascending => {
+                with_timeout(
+                    QueryBudget::Interactive,
+                    "sessions.index_cursor",
+                    sqlx::query_as!(
+                        Sessions,
+                        r#"
+                            SELECT id, user_id, logged_in_at, login_ip, expires_on, refresh_token, is_active, logged_out_at, logout_ip, absolute_expires_on, last_refreshed_at, access_token_id, organization_id, client_id, last_used_at, request_count
+                            FROM sessions
+                            WHERE ($1::TIMESTAMPTZ IS NULL OR (logged_in_at, id) > ($1, $2))
+                            ORDER BY logged_in_at ASC, id ASC
+                            LIMIT $3
+                        "#,
+                        cursor_logged_in_at,
+                        cursor_id,
+                        limit_i64
+                    )
+                    .fetch_all(database),
+                )
+                .await?
+            }
+            // Newest first, paging backward
+            SortDirection::Descending => {
+                with_timeout(
+                    QueryBudget::Interactive,
+                    "sessions.index_cursor",
+                    sqlx::query_as!(
+                        Sessions,
+                        r#"
+                            SELECT id, user_id, logged_in_at, login_ip, expires_on, refresh_token, is_active, logged_out_at, logout_ip, absolute_expires_on, last_refreshed_at, access_token_id, organization_id, client_id, last_used_at, request_count
+                            FROM sessions
+                            WHERE ($1::TIMESTAMPTZ IS NULL OR (logged_in_at, id) < ($1, $2))
+                            ORDER BY logged_in_at DESC, id DESC
+                            LIMIT $3
+                        "#,
+                        cursor_logged_in_at,
+                        cursor_id,
+                        limit_i64
+                    )
+                    .fetch_all(database),
+                )
+                .await?
+            }
+        };
+
+        tracing::debug!(
+            "Sessions database records retrieved: {}",
+            database_records.len()
+        );
+
+        Ok(database_records)
+    }
+
+    /// Retrieves a page of a user's Sessions using cursor (keyset) pagination.
+    ///
+    /// Sessions are ordered by `(logged_in_at, id)`, using the
+    /// `idx_sessions_user_id_logged_in_at_id` index.
+    ///
+    /// # Parameters
+    ///
+    /// * `user_id` - The UUID of the user whose sessions are to be retrieved.
+    /// * `limit` - The maximum number of Sessions to return.
+    /// * `direction` - Oldest first when ascending, newest first when descending.
+    /// * `cursor_logged_in_at` - The `logged_in_at` of the last session on the previous page.
+    /// * `cursor_id` - The `id` of the last session on the previous page.
+    /// * `database` - The sqlx database pool to execute the query against.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` if only one of the cursor values is provided, or an error if
+    /// the database query fails.
+    #[tracing::instrument(
+        name = "Index of a users Sessions with cursor pagination: ",
+        skip(database),
+        fields(
+            user_id = %user_id,
+            limit = %limit,
+            direction = ?direction,
+            cursor_logged_in_at = ?cursor_logged_in_at,
+            cursor_id = ?cursor_id,
+        )
+    )]
+    pub async fn index_from_user_id_cursor(
+        user_id: &Uuid,
+        limit: usize,
+        direction: SortDirection,
+        cursor_logged_in_at: Option<DateTime<Utc>>,
+        cursor_id: Option<Uuid>,
+        database: &Pool<Postgres>,
+    ) -> Result<Vec<Sessions>, AuthenticationError> {
+        validate_cursor_pagination(limit, cursor_logged_in_at, cursor_id)?;
+
+        let limit_i64 = safe_cast_to_i64(limit)?;
+
+        let database_records = match direction {
+            // Oldest first, paging forward
+            SortDirection::Ascending => {
+                with_timeout(
+                    QueryBudget::Interactive,
+                    "sessions.index_from_user_id_cursor",
+                    sqlx::query_as!(
+                        Sessions,
+                        r#"
+                            SELECT id, user_id, logged_in_at, login_ip, expires_on, refresh_token, is_active, logged_out_at, logout_ip, absolute_expires_on, last_refreshed_at, access_token_id, organization_id, client_id, last_used_at, request_count
+                            FROM sessions
+                            WHERE user_id = $1
+                            AND ($2::TIMESTAMPTZ IS NULL OR (logged_in_at, id) > ($2, $3))
+                            ORDER BY logged_in_at ASC, id ASC
+                            LIMIT $4
+                        "#,
+                        user_id,
+                        cursor_logged_in_at,
+                        cursor_id,
+                        limit_i64
+                    )
+                    .fetch_all(database),
+                )
+                .await?
+            }
+            // Newest first, paging backward
+            SortDirection::Descending => {
+                with_timeout(
+                    QueryBudget::Interactive,
+                    "sessions.index_from_user_id_cursor",
+                    sqlx::query_as!(
+                        Sessions,
+                        r#"
+                            SELECT id, user_id, logged_in_at, login_ip, expires_on, refresh_token, is_active, logged_out_at, logout_ip, absolute_expires_on, last_refreshed_at, access_token_id, organization_id, client_id, last_used_at, request_count
+                            FROM sessions
+                            WHERE user_id = $1
+                            AND ($2::TIMESTAMPTZ IS NULL OR (logged_in_at, id) < ($2, $3))
+                            ORDER BY logged_in_at DESC, id DESC
+                            LIMIT $4
+                        "#,
+                        user_id,
+                        cursor_logged_in_at,
+                        cursor_id,
+                        limit_i64
+                    )
+                    .fetch_all(database),
+                )
+                .await?
+            }
+        };
+
+        tracing::debug!(
+            "Sessions database records retrieved: {}",
+            database_records.len()
+        );
+
+        Ok(database_records)
+    }
+
+    /// Counts a user's active sessions, those not revoked and not yet expired.
+    ///
+    /// # Parameters
+    ///
+    /// * `user_id` - The UUID of the user whose sessions are counted.
+    /// * `database` - The sqlx database pool to execute the query against.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    #[tracing::instrument(
+        name = "Count a users active Sessions: ",
+        skip(database),
+        fields(
+            user_id = %user_id,
+        )
+    )]
+    pub async fn count_active_for_user(
+        user_id: &Uuid,
+        database: &Pool<Postgres>,
+    ) -> Result<u64, AuthenticationError> {
+        let count = retry("sessions.count_active_for_user", || {
+            sqlx::query_scalar!(
+                r#"
+                SELECT COUNT(*) AS "count!"
+                FROM sessions
+                WHERE user_id = $1
+                AND is_active = true
+                AND expires_on > NOW()
+            "#,
+                user_id
+            )
+            .fetch_one(database)
+        })
+        .await?;
+
+        tracing::debug!("Active sessions for user: {count}");
+
+        Ok(count as u64)
+    }
 }
 
 //-- Unit Tests
@@ -256,6 +630,33 @@ pub mod tests {
         Ok(())
     }
 
+    #[sqlx::test]
+    async fn index_organization_only_returns_scoped_sessions(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let organization = database::Organizations::mock_data()?.insert(&database).await?;
+        let random_user = database::Users::mock_data()?.insert(&database).await?;
+        let scoped_session = database::Sessions::mock_data(&random_user)
+            .await?
+            .with_organization_id(&organization.id)
+            .insert(&database)
+            .await?;
+        let _unscoped_session = database::Sessions::mock_data(&random_user)
+            .await?
+            .insert(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let database_records =
+            database::Sessions::index_organization(&organization.id, &10, &0, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(database_records, vec![scoped_session]);
+
+        Ok(())
+    }
+
     #[sqlx::test]
     async fn session_for_refresh_token(database: Pool<Postgres>) -> Result<()> {
         //-- Setup and Fixtures (Arrange)
@@ -287,6 +688,29 @@ pub mod tests {
         Ok(())
     }
 
+    #[sqlx::test]
+    async fn session_for_access_token_id(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let random_user = database::Users::mock_data()?;
+        random_user.insert(&database).await?;
+
+        let access_token_id = uuid::Uuid::now_v7().to_string();
+        let session = database::Sessions::mock_data(&random_user)
+            .await?
+            .with_access_token_id(&access_token_id);
+        let session = session.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let database_record =
+            database::Sessions::from_access_token_id(&access_token_id, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(database_record, session);
+
+        // -- Return
+        Ok(())
+    }
+
     // Test getting user from database using unique UUID
     #[sqlx::test]
     async fn count_index_from_user_id(database: Pool<Postgres>) -> Result<()> {
@@ -447,4 +871,174 @@ pub mod tests {
         assert!(records.is_empty());
         Ok(())
     }
+
+    #[sqlx::test]
+    async fn index_cursor_pages_through_all_sessions(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let random_user = database::Users::mock_data()?.insert(&database).await?;
+        let random_count: usize = (10..30).fake::<usize>();
+        for _count in 0..random_count {
+            database::Sessions::mock_data(&random_user)
+                .await?
+                .insert(&database)
+                .await?;
+        }
+
+        //-- Execute Function (Act)
+        let mut pages = Vec::new();
+        let mut cursor = (None, None);
+        loop {
+            let page = database::Sessions::index_cursor(
+                7,
+                database::SortDirection::Ascending,
+                cursor.0,
+                cursor.1,
+                &database,
+            )
+            .await?;
+            let Some(last) = page.last() else { break };
+            cursor = (Some(last.logged_in_at), Some(last.id));
+            pages.extend(page);
+        }
+
+        //-- Checks (Assertions)
+        assert_eq!(pages.len(), random_count);
+        assert!(pages
+            .windows(2)
+            .all(|w| (w[0].logged_in_at, w[0].id) < (w[1].logged_in_at, w[1].id)));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn index_cursor_descending_pages_newest_first(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let random_user = database::Users::mock_data()?.insert(&database).await?;
+        let random_count: usize = (10..30).fake::<usize>();
+        for _count in 0..random_count {
+            database::Sessions::mock_data(&random_user)
+                .await?
+                .insert(&database)
+                .await?;
+        }
+
+        //-- Execute Function (Act)
+        let mut pages = Vec::new();
+        let mut cursor = (None, None);
+        loop {
+            let page = database::Sessions::index_cursor(
+                7,
+                database::SortDirection::Descending,
+                cursor.0,
+                cursor.1,
+                &database,
+            )
+            .await?;
+            let Some(last) = page.last() else { break };
+            cursor = (Some(last.logged_in_at), Some(last.id));
+            pages.extend(page);
+        }
+
+        //-- Checks (Assertions)
+        assert_eq!(pages.len(), random_count);
+        assert!(pages
+            .windows(2)
+            .all(|w| (w[0].logged_in_at, w[0].id) > (w[1].logged_in_at, w[1].id)));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn index_from_user_id_cursor_only_returns_users_sessions(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?.insert(&database).await?;
+        let other_user = database::Users::mock_data()?.insert(&database).await?;
+        for _count in 0..5 {
+            database::Sessions::mock_data(&user).await?.insert(&database).await?;
+            database::Sessions::mock_data(&other_user)
+                .await?
+                .insert(&database)
+                .await?;
+        }
+
+        //-- Execute Function (Act)
+        let first_page = database::Sessions::index_from_user_id_cursor(
+            &user.id,
+            3,
+            database::SortDirection::Ascending,
+            None,
+            None,
+            &database,
+        )
+        .await?;
+        let last = first_page.last().ok_or("empty first page")?;
+        let second_page = database::Sessions::index_from_user_id_cursor(
+            &user.id,
+            3,
+            database::SortDirection::Ascending,
+            Some(last.logged_in_at),
+            Some(last.id),
+            &database,
+        )
+        .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(first_page.len(), 3);
+        assert_eq!(second_page.len(), 2);
+        assert!(first_page
+            .iter()
+            .chain(second_page.iter())
+            .all(|session| session.user_id == user.id));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn index_cursor_rejects_partial_cursor(database: Pool<Postgres>) -> Result<()> {
+        let result = database::Sessions::index_cursor(
+            10,
+            database::SortDirection::Ascending,
+            Some(chrono::Utc::now()),
+            None,
+            &database,
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(crate::prelude::AuthenticationError::ValidationError(_))
+        ));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn count_active_for_user_ignores_revoked_and_expired(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?.insert(&database).await?;
+        let now = chrono::Utc::now();
+        for (is_active, expires_on) in [
+            (true, now + chrono::Duration::days(1)),
+            (true, now + chrono::Duration::days(2)),
+            (false, now + chrono::Duration::days(1)),
+            (true, now - chrono::Duration::days(1)),
+        ] {
+            let mut session = database::Sessions::mock_data(&user).await?;
+            session.is_active = is_active;
+            session.expires_on = expires_on;
+            session.insert(&database).await?;
+        }
+
+        //-- Execute Function (Act)
+        let count = database::Sessions::count_active_for_user(&user.id, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(count, 2);
+
+        Ok(())
+    }
 }
@@ -12,9 +12,18 @@
 //! - Update a session by instance
 //! - Revoke (deactivate) a session by instance or ID
 //! - Revoke all sessions for a user or globally
+//! - Revoke the sessions scoped to an organization, for all or one user
+//! - Revoke all of a user's sessions except one
+//! - Revoke sessions left idle
+//! - Slide a session's expiry on refresh
+//! - Record the access token issued for a session
+//! - Record the requests made with a session, by ID or access token
 //! - Unit tests for update and revoke scenarios
 
-use sqlx::{Pool, Postgres};
+use std::time;
+
+use chrono::{DateTime, SubsecRound, Utc};
+use sqlx::{PgExecutor, Pool, Postgres};
 use uuid::Uuid;
 
 use crate::database::Sessions;
@@ -118,7 +127,7 @@ impl Sessions {
     ///
     /// # Parameters
     /// * `id` - The UUID of the session to be revoked.
-    /// * `database` - The SQLx PostgreSQL connection pool.
+    /// * `database` - The SQLx PostgreSQL connection pool, or a transaction.
     ///
     /// # Returns
     /// * `Ok(u64)` - The number of sessions revoked (rows updated, should be 1 if the session existed, 0 otherwise).
@@ -135,7 +144,7 @@ impl Sessions {
     )]
     pub async fn revoke_by_id(
         id: &Uuid,
-        database: &Pool<Postgres>,
+        database: impl PgExecutor<'_>,
     ) -> Result<usize, AuthenticationError> {
         let rows_affected = sqlx::query!(
             r#"
@@ -161,7 +170,7 @@ impl Sessions {
     ///
     /// # Parameters
     /// * `self` - The `Sessions` instance whose `user_id` will be used for revocation.
-    /// * `database` - The SQLx PostgreSQL connection pool.
+    /// * `database` - The SQLx PostgreSQL connection pool, or a transaction.
     ///
     /// # Returns
     /// * `Ok(u64)` - The number of sessions revoked (rows updated).
@@ -178,7 +187,7 @@ impl Sessions {
     )]
     pub async fn revoke_associated(
         &self,
-        database: &Pool<Postgres>,
+        database: impl PgExecutor<'_>,
     ) -> Result<usize, AuthenticationError> {
         let rows_affected = sqlx::query!(
             r#"
@@ -204,7 +213,7 @@ impl Sessions {
     ///
     /// # Parameters
     /// * `user_id` - The UUID of the user whose sessions should be revoked.
-    /// * `database` - The SQLx PostgreSQL connection pool.
+    /// * `database` - The SQLx PostgreSQL connection pool, or a transaction.
     ///
     /// # Returns
     /// * `Ok(u64)` - The number of sessions revoked (rows updated).
@@ -221,7 +230,7 @@ impl Sessions {
     )]
     pub async fn revoke_user_id(
         user_id: &Uuid,
-        database: &Pool<Postgres>,
+        database: impl PgExecutor<'_>,
     ) -> Result<usize, AuthenticationError> {
         let rows_affected = sqlx::query!(
             r#"
@@ -240,12 +249,151 @@ impl Sessions {
         Ok(rows_affected as usize)
     }
 
+    /// Revoke (make non-active) a user's sessions scoped to an organization.
+    ///
+    /// Executes a SQL `UPDATE` statement to set `is_active = false` for the session records
+    /// associated with the specified `user_id` and `organization_id`. The user's sessions in
+    /// other organizations, or not scoped to one, are kept.
+    ///
+    /// # Parameters
+    /// * `user_id` - The UUID of the user whose sessions should be revoked.
+    /// * `organization_id` - The UUID of the organization the sessions are scoped to.
+    /// * `database` - The SQLx PostgreSQL connection pool, or a transaction.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of sessions revoked (rows updated).
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Revoke a User's organization Sessions in the database: ",
+        skip(database),
+        fields(
+            user_id = ?user_id,
+            organization_id = ?organization_id,
+        )
+    )]
+    pub async fn revoke_user_organization(
+        user_id: &Uuid,
+        organization_id: &Uuid,
+        database: impl PgExecutor<'_>,
+    ) -> Result<usize, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                UPDATE sessions
+                SET is_active = false
+                WHERE user_id = $1 AND organization_id = $2
+            "#,
+            user_id,
+            organization_id
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Sessions database records updated: {rows_affected:#?}");
+
+        Ok(rows_affected as usize)
+    }
+
+    /// Revoke (make non-active) all of a user's sessions except the one given.
+    ///
+    /// Executes a SQL `UPDATE` statement to set `is_active = false` for all active session
+    /// records associated with the specified `user_id`, other than `session_id`.
+    ///
+    /// # Parameters
+    /// * `user_id` - The UUID of the user whose other sessions should be revoked.
+    /// * `session_id` - The UUID of the session to keep active.
+    /// * `database` - The SQLx PostgreSQL connection pool, or a transaction.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of sessions revoked (rows updated).
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    ///
+    /// # Tracing
+    /// - Adds the `user_id` and kept `session_id` to the tracing span for observability.
+    #[tracing::instrument(
+        name = "Revoke all other Sessions in the database: ",
+        skip(database),
+        fields(
+            user_id = ?user_id,
+            session_id = ?session_id,
+        )
+    )]
+    pub async fn revoke_all_except(
+        user_id: &Uuid,
+        session_id: &Uuid,
+        database: impl PgExecutor<'_>,
+    ) -> Result<usize, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                UPDATE sessions
+                SET is_active = false
+                WHERE user_id = $1 AND id <> $2 AND is_active = true
+            "#,
+            user_id,
+            session_id
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Sessions database records revoked: {rows_affected:#?}");
+
+        Ok(rows_affected as usize)
+    }
+
+    /// Revoke (make non-active) a user's sessions started for an OAuth client.
+    ///
+    /// Executes a SQL `UPDATE` statement to set `is_active = false` for the active session
+    /// records of `user_id` with the `client_id`, when the user revokes their grant to the client.
+    ///
+    /// # Parameters
+    /// * `user_id` - The UUID of the user whose sessions should be revoked.
+    /// * `client_id` - The UUID of the client the sessions were started for.
+    /// * `database` - The SQLx PostgreSQL connection pool, or a transaction.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of sessions revoked (rows updated).
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    ///
+    /// # Tracing
+    /// - Adds the `user_id` and `client_id` to the tracing span for observability.
+    #[tracing::instrument(
+        name = "Revoke a clients Sessions in the database: ",
+        skip(database),
+        fields(
+            user_id = ?user_id,
+            client_id = ?client_id,
+        )
+    )]
+    pub async fn revoke_user_client(
+        user_id: &Uuid,
+        client_id: &Uuid,
+        database: impl PgExecutor<'_>,
+    ) -> Result<usize, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                UPDATE sessions
+                SET is_active = false
+                WHERE user_id = $1 AND client_id = $2 AND is_active = true
+            "#,
+            user_id,
+            client_id
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Sessions database records revoked: {rows_affected:#?}");
+
+        Ok(rows_affected as usize)
+    }
+
     /// Revoke (make non-active) all sessions in the database.
     ///
     /// Executes a SQL `UPDATE` statement to set `is_active = false` for all session records.
     ///
     /// # Parameters
-    /// * `database` - The SQLx PostgreSQL connection pool.
+    /// * `database` - The SQLx PostgreSQL connection pool, or a transaction.
     ///
     /// # Returns
     /// * `Ok(u64)` - The number of sessions revoked (rows updated).
@@ -258,7 +406,7 @@ impl Sessions {
         skip(database)
     )]
     pub async fn revoke_all(
-        database: &Pool<Postgres>,
+        database: impl PgExecutor<'_>,
     ) -> Result<usize, AuthenticationError> {
         let rows_affected = sqlx::query!(
             r#"
@@ -276,11 +424,272 @@ impl Sessions {
 
         Ok(rows_affected as usize)
     }
+
+    /// Revoke (make non-active) all sessions scoped to an organization.
+    ///
+    /// Executes a SQL `UPDATE` statement to set `is_active = false` for the session records
+    /// with the specified `organization_id`.
+    ///
+    /// # Parameters
+    /// * `organization_id` - The UUID of the organization the sessions are scoped to.
+    /// * `database` - The SQLx PostgreSQL connection pool, or a transaction.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of sessions revoked (rows updated).
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Revoke all organization Sessions in the database: ",
+        skip(database)
+    )]
+    pub async fn revoke_organization(
+        organization_id: &Uuid,
+        database: impl PgExecutor<'_>,
+    ) -> Result<usize, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                UPDATE sessions
+                SET is_active = false
+                WHERE organization_id = $1
+            "#,
+            organization_id
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!(
+            "Organization Sessions revoked in the database, records updated: {rows_affected:#?}"
+        );
+
+        Ok(rows_affected as usize)
+    }
+
+    /// Revoke (make non-active) every session not used since `idle_before`.
+    ///
+    /// Executes a SQL `UPDATE` statement to set `is_active = false` for active sessions whose
+    /// latest `logged_in_at`, `last_refreshed_at` or `last_used_at` is before `idle_before`.
+    ///
+    /// # Parameters
+    /// * `idle_before` - Sessions last used before this time are revoked.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - The number of sessions revoked.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Revoke idle Sessions in the database: ", skip(database))]
+    pub async fn revoke_idle(
+        idle_before: &DateTime<Utc>,
+        database: &Pool<Postgres>,
+    ) -> Result<usize, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                UPDATE sessions
+                SET is_active = false
+                WHERE is_active = true
+                AND GREATEST(logged_in_at, last_refreshed_at, last_used_at) < $1
+            "#,
+            idle_before,
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Idle Sessions revoked in the database: {rows_affected:#?}");
+
+        Ok(rows_affected as usize)
+    }
+
+    /// Record a refresh on this session, sliding its expiry forward.
+    ///
+    /// Executes a SQL `UPDATE` statement that sets `last_refreshed_at` to now and extends
+    /// `expires_on` to now plus `window`, capped at `absolute_expires_on`. Sessions without an
+    /// `absolute_expires_on` have a fixed expiry and only record the refresh. The expiry is never
+    /// shortened.
+    ///
+    /// # Parameters
+    /// * `self` - The `Sessions` instance being refreshed.
+    /// * `window` - How long the session stays valid after this refresh.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Sessions)` - The updated session record as returned from the database.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Slide a Sessions expiry in the database: ",
+        skip(database),
+        fields(
+            session_id = ?self.id,
+        )
+    )]
+    pub async fn slide_expiry(
+        &self,
+        window: &time::Duration,
+        database: &Pool<Postgres>,
+    ) -> Result<Sessions, AuthenticationError> {
+        let sliding_expires_on = Utc::now().round_subsecs(0) + *window;
+
+        let database_record = sqlx::query_as!(
+            Sessions,
+            r#"
+                UPDATE sessions
+                SET expires_on = GREATEST(expires_on, LEAST($2, COALESCE(absolute_expires_on, expires_on))),
+                    last_refreshed_at = NOW()
+                WHERE id = $1
+                RETURNING *
+            "#,
+            self.id,
+            sliding_expires_on,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("Sessions expiry now: {}", database_record.expires_on);
+
+        Ok(database_record)
+    }
+
+    /// Record the access token last issued for this session.
+    ///
+    /// Executes a SQL `UPDATE` statement setting `access_token_id` to the jti of the access
+    /// token issued on refresh, so it can be denied if the session is revoked.
+    ///
+    /// # Parameters
+    /// * `self` - The `Sessions` instance the access token was issued for.
+    /// * `access_token_id` - The access token jti.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Sessions)` - The updated session record as returned from the database.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Update a Sessions access token in the database: ",
+        skip(database),
+        fields(
+            session_id = ?self.id,
+        )
+    )]
+    pub async fn update_access_token_id(
+        &self,
+        access_token_id: &str,
+        database: &Pool<Postgres>,
+    ) -> Result<Sessions, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            Sessions,
+            r#"
+                UPDATE sessions
+                SET access_token_id = $2
+                WHERE id = $1
+                RETURNING *
+            "#,
+            self.id,
+            access_token_id,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("Sessions database records updated: {database_record:#?}");
+
+        Ok(database_record)
+    }
+
+    /// Record the requests made with a session by its unique session ID.
+    ///
+    /// Executes a SQL `UPDATE` statement adding `requests` to `request_count` and moving
+    /// `last_used_at` forward to `last_used_at`, never back.
+    ///
+    /// # Parameters
+    /// * `id` - The UUID of the session used.
+    /// * `last_used_at` - When the session was last used.
+    /// * `requests` - The number of requests made since the last update.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - The number of sessions updated (1 if the session existed, 0 otherwise).
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Record Sessions activity in the database: ",
+        skip(database),
+        fields(
+            session_id = ?id,
+        )
+    )]
+    pub async fn record_activity(
+        id: &Uuid,
+        last_used_at: &DateTime<Utc>,
+        requests: i64,
+        database: &Pool<Postgres>,
+    ) -> Result<usize, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                UPDATE sessions
+                SET last_used_at = GREATEST(last_used_at, $2),
+                    request_count = request_count + $3
+                WHERE id = $1
+            "#,
+            id,
+            last_used_at,
+            requests,
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Sessions database records updated: {rows_affected:#?}");
+
+        Ok(rows_affected as usize)
+    }
+
+    /// Record the requests made with an access token on the session it was issued for.
+    ///
+    /// The same as `Sessions::record_activity`, finding the session by the jti of the last
+    /// access token issued for it. Access tokens not issued for a session update nothing.
+    ///
+    /// # Parameters
+    /// * `access_token_id` - The access token jti.
+    /// * `last_used_at` - When the access token was last used.
+    /// * `requests` - The number of requests made since the last update.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - The number of sessions updated (1 if a session has the token, 0 otherwise).
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Record Sessions activity by access token in the database: ",
+        skip(database)
+    )]
+    pub async fn record_activity_by_access_token_id(
+        access_token_id: &str,
+        last_used_at: &DateTime<Utc>,
+        requests: i64,
+        database: &Pool<Postgres>,
+    ) -> Result<usize, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                UPDATE sessions
+                SET last_used_at = GREATEST(last_used_at, $2),
+                    request_count = request_count + $3
+                WHERE access_token_id = $1
+            "#,
+            access_token_id,
+            last_used_at,
+            requests,
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Sessions database records updated: {rows_affected:#?}");
+
+        Ok(rows_affected as usize)
+    }
 }
 
 //-- Unit Tests
 #[cfg(test)]
 pub mod tests {
+    use std::time;
+
+    use chrono::SubsecRound;
     use fake::Fake;
     use sqlx::{Pool, Postgres};
 
@@ -510,6 +919,54 @@ pub mod tests {
         Ok(())
     }
 
+    #[sqlx::test]
+    async fn revoke_all_except_keeps_current_session(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        // Generate random users for testing
+        let random_user = database::Users::mock_data()?;
+        random_user.insert(&database).await?;
+        let other_user = database::Users::mock_data()?;
+        other_user.insert(&database).await?;
+
+        // The session to keep
+        let mut session = database::Sessions::mock_data(&random_user).await?;
+        session.is_active = true;
+        let session = session.insert(&database).await?;
+
+        // Another user's session that should not be touched
+        let mut other_session = database::Sessions::mock_data(&other_user).await?;
+        other_session.is_active = true;
+        let other_session = other_session.insert(&database).await?;
+
+        let random_count: i64 = (2..10).fake::<i64>();
+        for _count in 0..random_count {
+            let mut loop_session =
+                database::Sessions::mock_data(&random_user).await?;
+            loop_session.is_active = true;
+            loop_session.insert(&database).await?;
+        }
+
+        //-- Execute Function (Act)
+        let rows_affected = database::Sessions::revoke_all_except(
+            &random_user.id,
+            &session.id,
+            &database,
+        )
+        .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(rows_affected, random_count as usize);
+
+        // The kept session and the other user's session are still active
+        let kept = database::Sessions::from_id(&session.id, &database).await?;
+        assert!(kept.is_active);
+        let other = database::Sessions::from_id(&other_session.id, &database).await?;
+        assert!(other.is_active);
+
+        // -- Return
+        Ok(())
+    }
+
     #[sqlx::test]
     async fn revoke_all_red_button(database: Pool<Postgres>) -> Result<()> {
         //-- Setup and Fixtures (Arrange)
@@ -563,6 +1020,40 @@ pub mod tests {
         Ok(())
     }
 
+    #[sqlx::test]
+    async fn revoke_user_client_only_revokes_the_clients_sessions(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let (client, _secret) = database::Clients::mock_data();
+        client.insert(&database).await?;
+
+        let mut client_session = database::Sessions::mock_data(&user)
+            .await?
+            .with_client_id(&client.id);
+        client_session.is_active = true;
+        let client_session = client_session.insert(&database).await?;
+
+        let mut other_session = database::Sessions::mock_data(&user).await?;
+        other_session.is_active = true;
+        let other_session = other_session.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let rows_affected =
+            database::Sessions::revoke_user_client(&user.id, &client.id, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(rows_affected, 1);
+        let client_session = database::Sessions::from_id(&client_session.id, &database).await?;
+        let other_session = database::Sessions::from_id(&other_session.id, &database).await?;
+        assert!(!client_session.is_active);
+        assert!(other_session.is_active);
+
+        Ok(())
+    }
+
     #[sqlx::test]
     async fn update_nonexistent_session_returns_error(
         database: Pool<Postgres>,
@@ -658,4 +1149,119 @@ pub mod tests {
         assert_eq!(updated_session, session);
         Ok(())
     }
+
+    #[sqlx::test]
+    async fn slide_expiry_is_capped_at_absolute_expiry(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let random_user = database::Users::mock_data()?.insert(&database).await?;
+        let now = chrono::Utc::now().round_subsecs(0);
+
+        let mut session = database::Sessions::mock_data(&random_user).await?;
+        session.logged_in_at = now;
+        session.expires_on = now + chrono::Duration::hours(1);
+        session.absolute_expires_on = Some(now + chrono::Duration::hours(36));
+        let session = session.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let one_day = time::Duration::from_secs(24 * 60 * 60);
+        let slid = session.slide_expiry(&one_day, &database).await?;
+        let capped = slid
+            .slide_expiry(&time::Duration::from_secs(7 * 24 * 60 * 60), &database)
+            .await?;
+
+        //-- Checks (Assertions)
+        assert!(slid.expires_on >= now + chrono::Duration::hours(24));
+        assert!(slid.last_refreshed_at.is_some());
+        assert_eq!(Some(capped.expires_on), session.absolute_expires_on);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn slide_expiry_keeps_fixed_expiry(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let random_user = database::Users::mock_data()?.insert(&database).await?;
+        let mut session = database::Sessions::mock_data(&random_user).await?;
+        session.absolute_expires_on = None;
+        let session = session.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let window = time::Duration::from_secs(365 * 24 * 60 * 60);
+        let refreshed = session.slide_expiry(&window, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(refreshed.expires_on, session.expires_on);
+        assert!(refreshed.last_refreshed_at.is_some());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn record_activity_adds_requests_and_keeps_the_latest_use(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let random_user = database::Users::mock_data()?.insert(&database).await?;
+        let session = database::Sessions::mock_data(&random_user)
+            .await?
+            .with_access_token_id("access-token-jti")
+            .insert(&database)
+            .await?;
+        let now = chrono::Utc::now().round_subsecs(0);
+
+        //-- Execute Function (Act)
+        database::Sessions::record_activity(&session.id, &now, 2, &database).await?;
+        let earlier = now - chrono::Duration::minutes(5);
+        database::Sessions::record_activity_by_access_token_id(
+            "access-token-jti",
+            &earlier,
+            3,
+            &database,
+        )
+        .await?;
+        let unknown = database::Sessions::record_activity_by_access_token_id(
+            "unknown-jti",
+            &now,
+            1,
+            &database,
+        )
+        .await?;
+
+        //-- Checks (Assertions)
+        let session = database::Sessions::from_id(&session.id, &database).await?;
+        assert_eq!(session.last_used_at, Some(now));
+        assert_eq!(session.request_count, 5);
+        assert_eq!(unknown, 0);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn revoke_idle_keeps_recently_used_sessions(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let random_user = database::Users::mock_data()?.insert(&database).await?;
+        let now = chrono::Utc::now().round_subsecs(0);
+
+        let mut idle = database::Sessions::mock_data(&random_user).await?;
+        idle.is_active = true;
+        idle.logged_in_at = now - chrono::Duration::days(30);
+        let idle = idle.insert(&database).await?;
+
+        let mut used = database::Sessions::mock_data(&random_user).await?;
+        used.is_active = true;
+        used.logged_in_at = now - chrono::Duration::days(30);
+        used.last_used_at = Some(now - chrono::Duration::hours(1));
+        let used = used.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let idle_before = now - chrono::Duration::days(7);
+        let revoked = database::Sessions::revoke_idle(&idle_before, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(revoked, 1);
+        assert!(!database::Sessions::from_id(&idle.id, &database).await?.is_active);
+        assert!(database::Sessions::from_id(&used.id, &database).await?.is_active);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,60 @@
+//-- ./src/database/sort_direction.rs
+
+// #![allow(unused)] // For development only
+
+//! Sort direction for cursor (keyset) pagination.
+//!
+//! Cursor pagination pages through `(created, id)`. Ascending returns the
+//! oldest rows first and pages forward with `>`, descending returns the newest
+//! rows first and pages backward with `<`. Either way the cursor is the
+//! created timestamp and id of the last row on the previous page.
+
+/// The order cursor paginated rows are returned in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortDirection {
+    /// Oldest first
+    #[default]
+    Ascending,
+    /// Newest first
+    Descending,
+}
+
+impl SortDirection {
+    /// If rows are returned newest first
+    pub fn is_descending(&self) -> bool {
+        matches!(self, SortDirection::Descending)
+    }
+}
+
+impl std::str::FromStr for SortDirection {
+    type Err = crate::AuthenticationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "asc" | "ascending" => Ok(SortDirection::Ascending),
+            "desc" | "descending" => Ok(SortDirection::Descending),
+            _ => Err(crate::AuthenticationError::ValidationError(format!(
+                "Invalid sort direction: {value}"
+            ))),
+        }
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_sort_direction() {
+        assert_eq!(
+            "asc".parse::<SortDirection>().ok(),
+            Some(SortDirection::Ascending)
+        );
+        assert_eq!(
+            "DESC".parse::<SortDirection>().ok(),
+            Some(SortDirection::Descending)
+        );
+        assert!("sideways".parse::<SortDirection>().is_err());
+    }
+}
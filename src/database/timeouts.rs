@@ -0,0 +1,221 @@
+//-- ./src/database/timeouts.rs
+
+// #![allow(unused)] // For development only
+
+//! # Query Timeouts
+//!
+//! A slow query shouldn't hold an RPC open indefinitely, but a cleanup job
+//! deleting a backlog of expired rows can take minutes. Queries run with the
+//! time budget of what is waiting on them:
+//! - **Interactive**: RPC reads, `database.rpc_query_timeout_milliseconds`,
+//!   applied by `retry` to every attempt together, and by the listings,
+//!   searches and export to each query
+//! - **Background**: cleanup jobs such as `prune-tokens`,
+//!   `database.background_query_timeout_milliseconds`
+//!
+//! A query over its budget fails with `QueryTimeout`, answered with
+//! `DEADLINE_EXCEEDED`. The budgets wait on the client, so a query is also
+//! cancelled by Postgres after `database.statement_timeout_milliseconds`
+//! when it is set.
+//!
+//! A task can run with its own budgets, see `QueryTimeouts::scope`.
+//! ---
+
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::configuration::DatabaseConfiguration;
+use crate::prelude::*;
+
+/// The query budgets shared by the database models and jobs
+static QUERY_TIMEOUTS: OnceLock<QueryTimeouts> = OnceLock::new();
+
+tokio::task_local! {
+    /// The query budgets of a task, used instead of the shared budgets
+    static TASK_QUERY_TIMEOUTS: QueryTimeouts;
+}
+
+/// What is waiting on a query
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QueryBudget {
+    /// An RPC, with a client waiting on the answer
+    Interactive,
+
+    /// A background or cleanup job
+    Background,
+}
+
+/// How long queries of each budget can take, `None` for no limit
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QueryTimeouts {
+    interactive: Option<Duration>,
+    background: Option<Duration>,
+}
+
+impl QueryTimeouts {
+    pub fn new(config: &DatabaseConfiguration) -> Self {
+        Self {
+            interactive: config.rpc_query_timeout(),
+            background: config.background_query_timeout(),
+        }
+    }
+
+    /// How long a query of the budget can take
+    pub fn timeout(&self, budget: QueryBudget) -> Option<Duration> {
+        match budget {
+            QueryBudget::Interactive => self.interactive,
+            QueryBudget::Background => self.background,
+        }
+    }
+
+    /// Run the query, failing with `QueryTimeout` when it takes longer than
+    /// the budget
+    pub async fn run<T, E, F>(
+        &self,
+        budget: QueryBudget,
+        operation: &'static str,
+        query: F,
+    ) -> Result<T, AuthenticationError>
+    where
+        F: Future<Output = Result<T, E>>,
+        E: Into<AuthenticationError>,
+    {
+        let Some(timeout) = self.timeout(budget) else {
+            return query.await.map_err(Into::into);
+        };
+
+        match tokio::time::timeout(timeout, query).await {
+            Ok(result) => result.map_err(Into::into),
+            Err(_) => {
+                tracing::warn!(
+                    "{operation} took longer than its {budget:?} budget of {timeout:?}"
+                );
+                Err(AuthenticationError::QueryTimeout(operation))
+            }
+        }
+    }
+
+    /// Run the future with these budgets instead of the shared budgets
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        TASK_QUERY_TIMEOUTS.scope(self, future).await
+    }
+}
+
+/// Use the configuration for the shared query budgets, if they haven't been
+/// used yet
+pub fn install(config: &DatabaseConfiguration) -> &'static QueryTimeouts {
+    QUERY_TIMEOUTS.get_or_init(|| QueryTimeouts::new(config))
+}
+
+/// Run the query with the task's budget or the shared budget, no limit
+/// until `install`ed
+pub async fn with_timeout<T, E, F>(
+    budget: QueryBudget,
+    operation: &'static str,
+    query: F,
+) -> Result<T, AuthenticationError>
+where
+    F: Future<Output = Result<T, E>>,
+    E: Into<AuthenticationError>,
+{
+    let timeouts = TASK_QUERY_TIMEOUTS
+        .try_with(|timeouts| *timeouts)
+        .unwrap_or_else(|_| *QUERY_TIMEOUTS.get_or_init(QueryTimeouts::default));
+
+    timeouts.run(budget, operation, query).await
+}
+
+/// Run a cleanup job query with the background budget
+pub async fn background<T, E, F>(
+    operation: &'static str,
+    query: F,
+) -> Result<T, AuthenticationError>
+where
+    F: Future<Output = Result<T, E>>,
+    E: Into<AuthenticationError>,
+{
+    with_timeout(QueryBudget::Background, operation, query).await
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use super::*;
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[tokio::test]
+    async fn queries_over_their_budget_time_out() {
+        //-- Setup and Fixtures (Arrange)
+        let timeouts = QueryTimeouts {
+            interactive: Some(Duration::from_millis(10)),
+            background: None,
+        };
+        let slow_query = || async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok::<_, sqlx::Error>(())
+        };
+
+        //-- Execute Function (Act)
+        let interactive = timeouts
+            .run(QueryBudget::Interactive, "slow", slow_query())
+            .await;
+        let background = timeouts
+            .run(QueryBudget::Background, "slow", slow_query())
+            .await;
+
+        //-- Checks (Assertions)
+        assert!(matches!(
+            interactive,
+            Err(AuthenticationError::QueryTimeout("slow"))
+        ));
+        assert!(background.is_ok());
+    }
+
+    #[sqlx::test]
+    async fn slow_listings_exceed_the_interactive_budget(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let timeouts = QueryTimeouts {
+            interactive: Some(Duration::from_millis(100)),
+            background: None,
+        };
+        // Reads of the users table wait until the lock is released
+        let mut transaction = database.begin().await?;
+        sqlx::query("LOCK TABLE users IN ACCESS EXCLUSIVE MODE")
+            .execute(&mut *transaction)
+            .await?;
+
+        //-- Execute Function (Act)
+        let index = timeouts
+            .scope(database::Users::index(&10, &0, &database))
+            .await;
+        let search = timeouts
+            .scope(database::Users::search(
+                &database::UsersSearchFilter::default(),
+                &10,
+                None,
+                None,
+                &database,
+            ))
+            .await;
+        transaction.rollback().await?;
+
+        //-- Checks (Assertions)
+        assert!(matches!(
+            index,
+            Err(AuthenticationError::QueryTimeout("users.index"))
+        ));
+        let status = tonic::Status::from(search.unwrap_err());
+        assert_eq!(status.code(), tonic::Code::DeadlineExceeded);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+//-- ./src/database/user_preferences/insert.rs
+
+// #![allow(unused)] // For development only
+
+//! User preferences upsert logic for the authentication service.
+//!
+//! # Contents
+//! - Insert or replace a user's preferences
+//! - Unit tests for upsert scenarios
+
+use sqlx::types::Json;
+use sqlx::{Pool, Postgres};
+
+use crate::database::UserPreferences;
+use crate::prelude::*;
+
+impl UserPreferences {
+    /// Save these preferences, replacing the user's current preferences.
+    ///
+    /// # Parameters
+    /// * `self` - The `UserPreferences` instance to save.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(UserPreferences)` - The saved record as returned from the database.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Upsert user preferences into the database: ",
+        skip(database),
+        fields(user_id = %self.user_id)
+    )]
+    pub async fn upsert(&self, database: &Pool<Postgres>) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            UserPreferences,
+            r#"
+                INSERT INTO user_preferences (user_id, timezone, notification_opt_outs, custom, updated_on)
+                VALUES ($1, $2, $3, $4, NOW())
+                ON CONFLICT (user_id) DO UPDATE
+                SET timezone = EXCLUDED.timezone,
+                    notification_opt_outs = EXCLUDED.notification_opt_outs,
+                    custom = EXCLUDED.custom,
+                    updated_on = EXCLUDED.updated_on
+                RETURNING user_id, timezone, notification_opt_outs, custom as "custom: Json<serde_json::Value>", updated_on
+            "#,
+            self.user_id,
+            self.timezone.as_ref(),
+            &self.notification_opt_outs,
+            &self.custom as _,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("User preferences saved: {}", database_record.user_id);
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn saving_preferences_replaces_them(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let preferences = database::UserPreferences::mock_data(&user.id);
+
+        //-- Execute Function (Act)
+        let first = preferences.upsert(&database).await?;
+        let second = database::UserPreferences::defaults(&user.id)
+            .upsert(&database)
+            .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(first.timezone, preferences.timezone);
+        assert_eq!(first.notification_opt_outs, preferences.notification_opt_outs);
+        assert_eq!(first.custom, preferences.custom);
+        assert!(second.notification_opt_outs.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,20 @@
+//-- ./src/database/user_preferences/mod.rs
+
+//! User preferences database module for the authentication service.
+//!
+//! Each user's time zone, the notifications they opted out of and client
+//! specific preferences. The user's locale is kept on `users`, where the email
+//! subsystem reads it. Users without a row have the default preferences.
+//!
+//! # Contents
+//! - User preferences struct definition
+//! - User preferences upsert logic
+//! - User preferences read logic
+
+// #![allow(unused)] // For development only
+
+pub use model::UserPreferences;
+
+mod insert;
+mod model;
+mod read;
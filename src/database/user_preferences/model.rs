@@ -0,0 +1,127 @@
+//-- ./src/database/user_preferences/model.rs
+
+// #![allow(unused)] // For development only
+
+//! The user preferences database model.
+//!
+//! # Contents
+//! - `UserPreferences` struct definition
+//! - Constructor for default preferences
+//! - Merging client specific preferences
+//! - Mock data generation for tests
+
+use chrono::{DateTime, SubsecRound, Utc};
+use sqlx::types::Json;
+use uuid::Uuid;
+
+use crate::domain;
+use crate::prelude::*;
+
+#[derive(Debug, sqlx::FromRow, Clone, PartialEq)]
+pub struct UserPreferences {
+    pub user_id: Uuid,
+    pub timezone: domain::TimeZone,
+    pub notification_opt_outs: Vec<String>,
+    pub custom: Json<serde_json::Value>,
+    pub updated_on: DateTime<Utc>,
+}
+
+impl UserPreferences {
+    /// # Default User Preferences
+    ///
+    /// The preferences of a user who has not set any.
+    ///
+    /// ## Parameters
+    ///
+    /// - `user_id: &Uuid` - The user the preferences are for
+    pub fn defaults(user_id: &Uuid) -> Self {
+        Self {
+            user_id: user_id.to_owned(),
+            timezone: domain::TimeZone::default(),
+            notification_opt_outs: Vec::new(),
+            custom: Json(serde_json::Value::Object(serde_json::Map::new())),
+            updated_on: Utc::now().round_subsecs(0),
+        }
+    }
+
+    /// Has the user opted out of the notification
+    pub fn is_opted_out(&self, notification: domain::Notification) -> bool {
+        self.notification_opt_outs
+            .iter()
+            .any(|opt_out| opt_out == notification.to_str())
+    }
+
+    /// Replace the notifications the user opted out of
+    pub fn set_notification_opt_outs(&mut self, notifications: &[domain::Notification]) {
+        self.notification_opt_outs.clear();
+        for notification in notifications {
+            if !self.is_opted_out(*notification) {
+                self.notification_opt_outs.push(notification.to_string());
+            }
+        }
+    }
+
+    /// Merge a JSON object into the client specific preferences. Keys set to
+    /// `null` are removed, the other keys replace the current values.
+    pub fn merge_custom(&mut self, patch: serde_json::Value) -> Result<(), AuthenticationError> {
+        let serde_json::Value::Object(patch) = patch else {
+            return Err(AuthenticationError::ValidationError(
+                "custom preferences must be a JSON object".to_string(),
+            ));
+        };
+
+        if let serde_json::Value::Object(custom) = &mut self.custom.0 {
+            for (key, value) in patch {
+                if value.is_null() {
+                    custom.remove(&key);
+                } else {
+                    custom.insert(key, value);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(test)]
+    /// # Mock User Preferences Data
+    ///
+    /// This function is only available in test mode `#[cfg(test)]`.
+    /// Creates preferences in Brisbane, opted out of security alerts.
+    pub fn mock_data(user_id: &Uuid) -> Self {
+        let mut preferences = Self::defaults(user_id);
+        preferences.timezone = domain::TimeZone::from("Australia/Brisbane".to_string());
+        preferences.set_notification_opt_outs(&[domain::Notification::SecurityAlert]);
+        preferences.custom = Json(serde_json::json!({ "theme": "dark" }));
+
+        preferences
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn custom_preferences_are_merged() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let mut preferences = UserPreferences::mock_data(&Uuid::now_v7());
+
+        //-- Execute Function (Act)
+        preferences.merge_custom(serde_json::json!({ "theme": null, "density": "compact" }))?;
+
+        //-- Checks (Assertions)
+        assert_eq!(preferences.custom.0, serde_json::json!({ "density": "compact" }));
+        assert!(preferences.merge_custom(serde_json::json!(["theme"])).is_err());
+        assert!(preferences.is_opted_out(domain::Notification::SecurityAlert));
+        assert!(!UserPreferences::defaults(&Uuid::now_v7())
+            .is_opted_out(domain::Notification::SecurityAlert));
+
+        Ok(())
+    }
+}
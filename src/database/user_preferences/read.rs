@@ -0,0 +1,82 @@
+//-- ./src/database/user_preferences/read.rs
+
+// #![allow(unused)] // For development only
+
+//! User preferences read logic for the authentication service.
+//!
+//! # Contents
+//! - Get a user's preferences, or the defaults
+//! - Unit tests for read scenarios
+
+use sqlx::types::Json;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::database::UserPreferences;
+use crate::prelude::*;
+
+impl UserPreferences {
+    /// Retrieve a user's preferences, the defaults when they have not set any.
+    ///
+    /// # Parameters
+    /// * `user_id` - The user the preferences are for.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(UserPreferences)` - The user's preferences.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Get user preferences from the database: ", skip(database))]
+    pub async fn for_user(
+        user_id: &Uuid,
+        database: &Pool<Postgres>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            UserPreferences,
+            r#"
+                SELECT user_id, timezone, notification_opt_outs, custom as "custom: Json<serde_json::Value>", updated_on
+                FROM user_preferences
+                WHERE user_id = $1
+            "#,
+            user_id,
+        )
+        .fetch_optional(database)
+        .await?;
+
+        Ok(database_record.unwrap_or_else(|| Self::defaults(user_id)))
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::{database, domain};
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn users_without_preferences_get_the_defaults(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let other_user = database::Users::mock_data()?;
+        other_user.insert(&database).await?;
+        let saved = database::UserPreferences::mock_data(&other_user.id)
+            .upsert(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let defaults = database::UserPreferences::for_user(&user.id, &database).await?;
+        let found = database::UserPreferences::for_user(&other_user.id, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(defaults.timezone, domain::TimeZone::default());
+        assert!(!defaults.is_opted_out(domain::Notification::SecurityAlert));
+        assert_eq!(found, saved);
+
+        Ok(())
+    }
+}
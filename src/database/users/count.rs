@@ -0,0 +1,155 @@
+//-- ./src/database/users/count.rs
+
+// #![allow(unused)] // For development only
+
+//! User count and exists helpers for the authentication service.
+//!
+//! Used for pagination totals and preflight checks, without fetching full rows.
+//!
+//! # Contents
+//! - Count users
+//! - Check a user id or email exists
+//! - Unit tests for count scenarios
+
+use uuid::Uuid;
+
+use crate::database::Users;
+use crate::{domain, prelude::*};
+
+impl Users {
+    /// Count the users in the database, excluding soft deleted users.
+    ///
+    /// # Parameters
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of users.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Count Users in the database: ", skip(database))]
+    pub async fn count(
+        database: &sqlx::Pool<sqlx::Postgres>,
+    ) -> Result<u64, AuthenticationError> {
+        let count = sqlx::query_scalar!(
+            r#"
+                SELECT COUNT(*) AS "count!"
+                FROM users
+                WHERE deleted_at IS NULL
+            "#
+        )
+        .fetch_one(database)
+        .await?;
+
+        Ok(count as u64)
+    }
+
+    /// Check a user with the id exists, excluding soft deleted users.
+    ///
+    /// # Parameters
+    /// * `id` - The user id.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(bool)` - If the user exists.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Check a User id exists in the database: ",
+        skip(database)
+    )]
+    pub async fn exists_by_id(
+        id: &Uuid,
+        database: &sqlx::Pool<sqlx::Postgres>,
+    ) -> Result<bool, AuthenticationError> {
+        let exists = sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM users WHERE id = $1 AND deleted_at IS NULL
+                ) AS "exists!"
+            "#,
+            id
+        )
+        .fetch_one(database)
+        .await?;
+
+        Ok(exists)
+    }
+
+    /// Check the email is taken by a user.
+    ///
+    /// Soft deleted users are included, as their email stays unique until they
+    /// are purged.
+    ///
+    /// # Parameters
+    /// * `email` - The email address.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(bool)` - If the email is taken.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Check a User email exists in the database: ",
+        skip(database)
+    )]
+    pub async fn exists_by_email(
+        email: &domain::EmailAddress,
+        database: &sqlx::Pool<sqlx::Postgres>,
+    ) -> Result<bool, AuthenticationError> {
+        let exists = sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM users WHERE email = $1
+                ) AS "exists!"
+            "#,
+            email.as_ref()
+        )
+        .fetch_one(database)
+        .await?;
+
+        Ok(exists)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn count_and_exists_skip_deleted_users(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let users = database::Users::insert_n_users(3, &database).await?;
+        users[0].delete(&database).await?;
+        let missing = database::Users::mock_data()?;
+
+        //-- Execute Function (Act)
+        let count = database::Users::count(&database).await?;
+        let deleted_id =
+            database::Users::exists_by_id(&users[0].id, &database).await?;
+        let active_id =
+            database::Users::exists_by_id(&users[1].id, &database).await?;
+        let missing_id =
+            database::Users::exists_by_id(&missing.id, &database).await?;
+        let deleted_email =
+            database::Users::exists_by_email(&users[0].email, &database).await?;
+        let missing_email =
+            database::Users::exists_by_email(&missing.email, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(count, 2);
+        assert!(!deleted_id);
+        assert!(active_id);
+        assert!(!missing_id);
+        // Deleted users keep their email until purged
+        assert!(deleted_email);
+        assert!(!missing_email);
+
+        Ok(())
+    }
+}
@@ -2,26 +2,34 @@
 
 //! User deletion logic for the authentication service.
 //!
-//! This module provides functions to delete user records from the database,
-//! including deletion by user instance and comprehensive unit tests for deletion scenarios.
+//! Users are soft deleted by setting `deleted_at`, so they can be restored.
+//! Soft deleted users are excluded from reads and updates, and purged (hard
+//! deleted) once they have been deleted for long enough.
 //!
 //! # Contents
-//! - Delete a single user by instance
+//! - Soft delete a single user by instance
+//! - Restore a soft deleted user by id
+//! - Purge users soft deleted before a given time
 //! - Unit tests for user deletion logic and edge cases
 
 // #![allow(unused)] // For development only
 
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
 use crate::database::Users;
+use crate::domain;
 use crate::prelude::*;
 
 impl Users {
-    /// Delete this user from the database.
+    /// Soft delete this user in the database.
     ///
-    /// Executes a SQL `DELETE` statement to remove the user record identified by its `id`.
+    /// Sets `deleted_at` on the user record identified by its `id`, so the
+    /// user is excluded from reads until restored.
     ///
     /// # Parameters
     /// * `self` - The `Users` instance to be deleted.
-    /// * `database` - The SQLx PostgreSQL connection pool.
+    /// * `database` - The SQLx PostgreSQL connection pool or transaction.
     ///
     /// # Returns
     /// * `Ok(u64)` - The number of rows deleted (should be 1 if the user existed, 0 otherwise).
@@ -38,21 +46,89 @@ impl Users {
     )]
     pub async fn delete(
         &self,
+        database: impl sqlx::PgExecutor<'_>,
+    ) -> Result<u64, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                UPDATE users
+                SET deleted_at = NOW()
+                WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            self.id
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("User database records affected: {rows_affected:#?}");
+
+        Ok(rows_affected)
+    }
+
+    /// Restore a soft deleted user, returning the restored user.
+    ///
+    /// # Parameters
+    /// * `id` - The id of the soft deleted user.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Users)` - The restored user.
+    /// * `Err(AuthenticationError)` - If the query fails or no deleted user has the id.
+    #[tracing::instrument(
+        name = "Restore a deleted User in the database with id: ",
+        skip(database)
+    )]
+    pub async fn restore(
+        id: &Uuid,
+        database: &sqlx::Pool<sqlx::Postgres>,
+    ) -> Result<Users, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            Users,
+            r#"
+                UPDATE users
+                SET deleted_at = NULL
+                WHERE id = $1 AND deleted_at IS NOT NULL
+                RETURNING id, email, name, password_hash, role as "role:domain::UserRole", is_active, is_verified, created_on, locale
+            "#,
+            id
+        )
+        .fetch_one(database)
+        .await?;
+
+        Ok(database_record)
+    }
+
+    /// Purge (hard delete) users soft deleted before `deleted_before`. Their
+    /// sessions and other records are removed by the foreign key cascades.
+    ///
+    /// # Parameters
+    /// * `deleted_before` - Users soft deleted before this time are purged.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of users purged.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Purge deleted Users from the database: ",
+        skip(database)
+    )]
+    pub async fn purge_deleted_older_than(
+        deleted_before: &DateTime<Utc>,
         database: &sqlx::Pool<sqlx::Postgres>,
     ) -> Result<u64, AuthenticationError> {
         let rows_affected = sqlx::query!(
             r#"
                 DELETE
                 FROM users
-                WHERE id = $1
+                WHERE deleted_at < $1
             "#,
-            self.id
+            deleted_before
         )
         .execute(database)
         .await?
         .rows_affected();
 
-        tracing::debug!("User database records affected: {rows_affected:#?}");
+        tracing::debug!("Deleted users purged: {rows_affected}");
 
         Ok(rows_affected)
     }
@@ -62,6 +138,7 @@ impl Users {
 #[cfg(test)]
 pub mod tests {
     use crate::database;
+    use chrono::{Duration, Utc};
     use sqlx::{Pool, Postgres};
 
     // Bring module functions into test scope
@@ -152,33 +229,68 @@ pub mod tests {
     }
 
     #[sqlx::test]
-    async fn delete_user_with_sessions_cascades_or_fails(
+    async fn purge_deleted_user_cascades_to_sessions(
         database: Pool<Postgres>,
     ) -> Result<()> {
         //-- Setup and Fixtures (Arrange)
-        // Arrange: Insert a user and a session for that user
+        // Arrange: Insert a user and a session for that user, then delete the user
         let user = database::Users::mock_data()?;
         let user = user.insert(&database).await?;
         let session = database::Sessions::mock_data(&user).await?;
         session.insert(&database).await?;
+        user.delete(&database).await?;
 
         //-- Execute Function (Act)
-        // Act: Delete the user
-        let rows_affected = user.delete(&database).await?;
+        // Act: Purge users deleted before now, and before the delete
+        let not_yet = database::Users::purge_deleted_older_than(
+            &(Utc::now() - Duration::hours(1)),
+            &database,
+        )
+        .await?;
+        let purged = database::Users::purge_deleted_older_than(
+            &(Utc::now() + Duration::seconds(1)),
+            &database,
+        )
+        .await?;
 
         //-- Checks (Assertions)
-        assert_eq!(rows_affected, 1);
+        assert_eq!(not_yet, 0);
+        assert_eq!(purged, 1);
 
-        // Try to fetch the user, should fail
-        let fetch_result = database::Users::from_user_id(&user.id, &database).await;
-        assert!(fetch_result.is_err());
-
-        // Try to fetch the session, should fail if ON DELETE CASCADE is set, or still exist otherwise
+        // The session is removed by ON DELETE CASCADE
         let session_result =
             database::Sessions::from_id(&session.id, &database).await;
-        // If you expect cascade, assert error; otherwise, comment this out or adjust as needed
         assert!(session_result.is_err());
 
+        // A purged user can not be restored
+        assert!(database::Users::restore(&user.id, &database).await.is_err());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn restore_deleted_user(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let not_deleted = database::Users::restore(&user.id, &database).await;
+        user.delete(&database).await?;
+        let hidden_by_id = database::Users::from_user_id(&user.id, &database).await;
+        let hidden_by_email =
+            database::Users::from_user_email(&user.email, &database).await;
+        let restored = database::Users::restore(&user.id, &database).await?;
+
+        //-- Checks (Assertions)
+        assert!(not_deleted.is_err());
+        assert!(hidden_by_id.is_err());
+        assert!(hidden_by_email.is_err());
+        assert_eq!(restored, user);
+        assert_eq!(
+            database::Users::from_user_id(&user.id, &database).await?,
+            user
+        );
+
         Ok(())
     }
 
@@ -4,6 +4,9 @@
 
 //! Insert User instance into the database, returning a Result with a User Model instance
 //!
+//! Also batch inserts many users in a single statement, and upserts a user by
+//! email, for the bulk import RPC and seeding tooling.
+//!
 //! #### References
 //!
 //! * [UPDATE query_as! with a custom ENUM type](https://github.com/launchbadge/sqlx/discussions/3041)
@@ -18,7 +21,7 @@ impl Users {
     /// # Parameters
     ///
     /// * `self` - The User instance to be inserted in the database.
-    /// * `database` - An Sqlx database connection pool
+    /// * `database` - An Sqlx database connection pool, or a transaction
     /// ---
     #[tracing::instrument(
         name = "Insert a new User into the database: ",
@@ -36,7 +39,7 @@ impl Users {
     )]
     pub async fn insert(
         &self,
-        database: &sqlx::Pool<sqlx::Postgres>,
+        database: impl sqlx::PgExecutor<'_>,
     ) -> Result<Self, AuthenticationError> {
         // Insert a new user
         let database_record = sqlx::query_as!(
@@ -50,10 +53,11 @@ impl Users {
                     role,
                     is_active,
                     is_verified,
-                    created_on
+                    created_on,
+                    locale
                 )
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-                RETURNING id, email, name, password_hash, role as "role:domain::UserRole", is_active, is_verified, created_on
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                RETURNING id, email, name, password_hash, role as "role:domain::UserRole", is_active, is_verified, created_on, locale
             "#,
             self.id,
             self.email.as_ref(),
@@ -63,6 +67,7 @@ impl Users {
             self.is_active,
             self.is_verified,
 			self.created_on,
+            self.locale.as_ref(),
         )
             .fetch_one(database)
             .await?;
@@ -72,6 +77,171 @@ impl Users {
         Ok(database_record)
     }
 
+    /// Insert many `Users` into the database with a single multi-row `INSERT`,
+    /// returning the users that were inserted.
+    ///
+    /// Users whose id or email is already taken, including by an earlier user
+    /// in the same batch, are skipped rather than failing the whole batch.
+    /// Compare the returned ids with the batch to find the skipped users.
+    ///
+    /// # Parameters
+    ///
+    /// * `users` - The User instances to be inserted in the database.
+    /// * `database` - An Sqlx database connection pool, or a transaction
+    /// ---
+    #[tracing::instrument(
+        name = "Insert many Users into the database: ",
+        skip(users, database),
+        fields(count = users.len())
+    )]
+    pub async fn insert_many(
+        users: &[Users],
+        database: impl sqlx::PgExecutor<'_>,
+    ) -> Result<Vec<Self>, AuthenticationError> {
+        if users.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Bind each column as an array, so the batch is one statement
+        let ids: Vec<uuid::Uuid> = users.iter().map(|user| user.id).collect();
+        let emails: Vec<String> = users.iter().map(|user| user.email.to_string()).collect();
+        let names: Vec<String> = users
+            .iter()
+            .map(|user| user.name.as_ref().to_string())
+            .collect();
+        let password_hashes: Vec<String> = users
+            .iter()
+            .map(|user| user.password_hash.as_ref().to_string())
+            .collect();
+        let roles: Vec<String> = users
+            .iter()
+            .map(|user| user.role.as_ref().to_string())
+            .collect();
+        let is_actives: Vec<bool> = users.iter().map(|user| user.is_active).collect();
+        let is_verifieds: Vec<bool> = users.iter().map(|user| user.is_verified).collect();
+        let created_ons: Vec<chrono::DateTime<chrono::Utc>> =
+            users.iter().map(|user| user.created_on).collect();
+        let locales: Vec<String> = users
+            .iter()
+            .map(|user| user.locale.as_ref().to_string())
+            .collect();
+
+        let database_records = sqlx::query_as!(
+            Users,
+            r#"
+                INSERT INTO users (
+                    id,
+                    email,
+                    name,
+                    password_hash,
+                    role,
+                    is_active,
+                    is_verified,
+                    created_on,
+                    locale
+                )
+                SELECT id, email, name, password_hash, role::user_role, is_active, is_verified, created_on, locale
+                FROM UNNEST(
+                    $1::UUID[],
+                    $2::TEXT[],
+                    $3::TEXT[],
+                    $4::TEXT[],
+                    $5::TEXT[],
+                    $6::BOOLEAN[],
+                    $7::BOOLEAN[],
+                    $8::TIMESTAMPTZ[],
+                    $9::TEXT[]
+                ) AS batch (id, email, name, password_hash, role, is_active, is_verified, created_on, locale)
+                ON CONFLICT DO NOTHING
+                RETURNING id, email, name, password_hash, role as "role:domain::UserRole", is_active, is_verified, created_on, locale
+            "#,
+            &ids,
+            &emails,
+            &names,
+            &password_hashes,
+            &roles,
+            &is_actives,
+            &is_verifieds,
+            &created_ons,
+            &locales,
+        )
+        .fetch_all(database)
+        .await?;
+
+        tracing::debug!(
+            "User database records inserted: {} of {}",
+            database_records.len(),
+            users.len()
+        );
+
+        Ok(database_records)
+    }
+
+    /// Insert a `User`, or update the user with the same email, returning the
+    /// saved user.
+    ///
+    /// An existing user keeps their id and created on date, the other fields
+    /// are replaced. A soft deleted user with the email is not updated, and an
+    /// error is returned.
+    ///
+    /// # Parameters
+    ///
+    /// * `self` - The User instance to be inserted or updated in the database.
+    /// * `database` - An Sqlx database connection pool, or a transaction
+    /// ---
+    #[tracing::instrument(
+        name = "Upsert a User in the database by email: ",
+        skip(self, database),
+        fields(
+            id = % self.id,
+            email = % self.email,
+        ),
+    )]
+    pub async fn upsert_by_email(
+        &self,
+        database: impl sqlx::PgExecutor<'_>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            Users,
+            r#"
+                INSERT INTO users (
+                    id,
+                    email,
+                    name,
+                    password_hash,
+                    role,
+                    is_active,
+                    is_verified,
+                    created_on,
+                    locale
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                ON CONFLICT (email) DO UPDATE
+                SET name = EXCLUDED.name,
+                    password_hash = EXCLUDED.password_hash,
+                    role = EXCLUDED.role,
+                    is_active = EXCLUDED.is_active,
+                    is_verified = EXCLUDED.is_verified,
+                    locale = EXCLUDED.locale
+                WHERE users.deleted_at IS NULL
+                RETURNING id, email, name, password_hash, role as "role:domain::UserRole", is_active, is_verified, created_on, locale
+            "#,
+            self.id,
+            self.email.as_ref(),
+            self.name.as_ref(),
+            self.password_hash.as_ref(),
+            self.role.clone() as domain::UserRole,
+            self.is_active,
+            self.is_verified,
+            self.created_on,
+            self.locale.as_ref(),
+        )
+        .fetch_one(database)
+        .await?;
+
+        Ok(database_record)
+    }
+
     #[cfg(test)]
     // Helper function to insert multiple users into the database
     /// Insert `n` users into the database, returning a vector of the inserted Users.
@@ -138,6 +308,98 @@ mod tests {
         Ok(())
     }
 
+    #[sqlx::test]
+    async fn insert_many_skips_conflicting_users(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        // One user already exists, and the batch repeats an email within itself
+        let existing = Users::mock_data()?.insert(&database).await?;
+        let mut taken_email = Users::mock_data()?;
+        taken_email.email = existing.email.clone();
+        let new_user = Users::mock_data()?;
+        let mut repeated_email = Users::mock_data()?;
+        repeated_email.email = new_user.email.clone();
+        let batch = vec![
+            new_user.clone(),
+            taken_email,
+            repeated_email,
+            Users::mock_data()?,
+        ];
+
+        //-- Execute Function (Act)
+        let inserted = Users::insert_many(&batch, &database).await?;
+        let empty = Users::insert_many(&[], &database).await?;
+
+        //-- Checks (Assertions)
+        // Only the first user with each new email is inserted
+        let inserted_ids: Vec<_> = inserted.iter().map(|user| user.id).collect();
+        assert_eq!(inserted.len(), 2);
+        assert!(inserted_ids.contains(&new_user.id));
+        assert!(inserted_ids.contains(&batch[3].id));
+        assert!(inserted.contains(&new_user));
+        assert!(empty.is_empty());
+
+        // The existing user is unchanged
+        assert_eq!(Users::from_user_id(&existing.id, &database).await?, existing);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn insert_many_is_atomic_when_a_row_is_invalid(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        // Conflicts are skipped, but any other failing row fails the statement.
+        // Add a check constraint the second user breaks.
+        sqlx::query("ALTER TABLE users ADD CONSTRAINT test_active_only CHECK (is_active)")
+            .execute(&database)
+            .await?;
+        let mut valid = Users::mock_data()?;
+        valid.is_active = true;
+        let mut invalid = Users::mock_data()?;
+        invalid.is_active = false;
+        let batch = vec![valid.clone(), invalid];
+
+        //-- Execute Function (Act)
+        let result = Users::insert_many(&batch, &database).await;
+
+        //-- Checks (Assertions)
+        // Nothing is inserted when any row fails
+        assert!(result.is_err());
+        assert!(Users::from_user_id(&valid.id, &database).await.is_err());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn upsert_by_email_inserts_then_updates(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = Users::mock_data()?;
+        let mut changed = Users::mock_data()?;
+        changed.email = user.email.clone();
+        changed.role = domain::UserRole::Admin;
+        changed.is_active = !user.is_active;
+
+        //-- Execute Function (Act)
+        let inserted = user.upsert_by_email(&database).await?;
+        let updated = changed.upsert_by_email(&database).await?;
+        inserted.delete(&database).await?;
+        let deleted = changed.upsert_by_email(&database).await;
+
+        //-- Checks (Assertions)
+        assert_eq!(inserted, user);
+        // The existing id and created on are kept, the rest is replaced
+        assert_eq!(updated.id, user.id);
+        assert_eq!(updated.created_on, user.created_on);
+        assert_eq!(updated.name, changed.name);
+        assert_eq!(updated.role, domain::UserRole::Admin);
+        assert_eq!(updated.is_active, changed.is_active);
+        // Soft deleted users are not updated
+        assert!(deleted.is_err());
+
+        Ok(())
+    }
+
     #[sqlx::test]
     async fn insert_with_each_role(database: Pool<Postgres>) -> Result<()> {
         //-- Setup and Fixtures (Arrange)
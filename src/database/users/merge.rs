@@ -0,0 +1,274 @@
+//-- ./src/database/users/merge.rs
+
+//! User merge logic for the authentication service.
+//!
+//! Merge a duplicate user into the account they keep, e.g. after signing up
+//! with a password and again through the identity provider. Everything that
+//! belongs to the person is reassigned to the kept user, then the duplicate is
+//! soft deleted, so it can be restored and is purged like any deleted user.
+//!
+//! Reassigned to the kept user:
+//! - Sessions and their refresh tokens, passkeys, denied access tokens and
+//!   federated identities
+//! - OAuth grants, organization memberships, policy acceptances, preferences
+//!   and the avatar, unless the kept user already has their own
+//! - Audit records: logins, impersonations and device authorizations
+//!
+//! Pending one-time tokens, such as password resets, stay with the duplicate.
+//! They were sent to its email and can't be used once it is deleted.
+//!
+//! # Contents
+//! - The counts of what was merged
+//! - Merge a user into another user
+//! - Unit tests for merge scenarios
+
+// #![allow(unused)] // For development only
+
+use uuid::Uuid;
+
+use crate::database::Users;
+use crate::prelude::*;
+
+/// How many records were reassigned when a user was merged
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct UsersMerge {
+    pub sessions: u64,
+    pub passkeys: u64,
+    pub identities: u64,
+    pub grants: u64,
+    pub logins: u64,
+    pub impersonations: u64,
+}
+
+impl Users {
+    /// Merge this (duplicate) user into the target user, reassigning their
+    /// records then soft deleting this user, in one statement.
+    ///
+    /// # Parameters
+    /// * `self` - The duplicate user, soft deleted by the merge.
+    /// * `target_id` - The user that is kept.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(UsersMerge)` - The counts of the records reassigned.
+    /// * `Err(AuthenticationError)` - If the users are the same, either user
+    ///   is missing or deleted, or the database operation fails.
+    #[tracing::instrument(
+        name = "Merge a User in the database: ",
+        skip(self, database),
+        fields(user_id = %self.id)
+    )]
+    pub async fn merge_into(
+        &self,
+        target_id: &Uuid,
+        database: impl sqlx::PgExecutor<'_>,
+    ) -> Result<UsersMerge, AuthenticationError> {
+        if &self.id == target_id {
+            return Err(AuthenticationError::ValidationError(
+                "A user can't be merged into themselves".to_string(),
+            ));
+        }
+
+        // Data modifying statements in a WITH all see the same snapshot, so the
+        // `NOT EXISTS` checks see the target's records from before the merge.
+        // Nothing is moved unless both users exist and are not deleted.
+        let database_record = sqlx::query!(
+            r#"
+                WITH source AS (
+                    SELECT id FROM users
+                    WHERE id = $1 AND deleted_at IS NULL AND EXISTS (
+                        SELECT 1 FROM users WHERE id = $2 AND deleted_at IS NULL
+                    )
+                ), sessions_moved AS (
+                    UPDATE sessions SET user_id = $2
+                    WHERE user_id IN (SELECT id FROM source)
+                    RETURNING 1
+                ), passkeys_moved AS (
+                    UPDATE webauthn_credentials SET user_id = $2
+                    WHERE user_id IN (SELECT id FROM source)
+                    RETURNING 1
+                ), denylist_moved AS (
+                    UPDATE access_token_denylist SET user_id = $2
+                    WHERE user_id IN (SELECT id FROM source)
+                    RETURNING 1
+                ), identities_moved AS (
+                    UPDATE federated_identities SET user_id = $2
+                    WHERE user_id IN (SELECT id FROM source)
+                    RETURNING 1
+                ), grants_moved AS (
+                    UPDATE grants AS moved SET user_id = $2
+                    WHERE moved.user_id IN (SELECT id FROM source) AND NOT EXISTS (
+                        SELECT 1 FROM grants AS kept
+                        WHERE kept.user_id = $2 AND kept.client_id = moved.client_id
+                    )
+                    RETURNING 1
+                ), memberships_moved AS (
+                    UPDATE organization_members AS moved SET user_id = $2
+                    WHERE moved.user_id IN (SELECT id FROM source) AND NOT EXISTS (
+                        SELECT 1 FROM organization_members AS kept
+                        WHERE kept.user_id = $2 AND kept.organization_id = moved.organization_id
+                    )
+                    RETURNING 1
+                ), acceptances_moved AS (
+                    UPDATE policy_acceptances AS moved SET user_id = $2
+                    WHERE moved.user_id IN (SELECT id FROM source) AND NOT EXISTS (
+                        SELECT 1 FROM policy_acceptances AS kept
+                        WHERE kept.user_id = $2
+                            AND kept.policy = moved.policy
+                            AND kept.version = moved.version
+                    )
+                    RETURNING 1
+                ), preferences_moved AS (
+                    UPDATE user_preferences SET user_id = $2
+                    WHERE user_id IN (SELECT id FROM source)
+                        AND NOT EXISTS (SELECT 1 FROM user_preferences WHERE user_id = $2)
+                    RETURNING 1
+                ), avatar_moved AS (
+                    UPDATE avatars SET user_id = $2
+                    WHERE user_id IN (SELECT id FROM source)
+                        AND NOT EXISTS (SELECT 1 FROM avatars WHERE user_id = $2)
+                    RETURNING 1
+                ), logins_moved AS (
+                    UPDATE logins SET user_id = $2
+                    WHERE user_id IN (SELECT id FROM source)
+                    RETURNING 1
+                ), impersonations_moved AS (
+                    UPDATE impersonations
+                    SET user_id = CASE WHEN user_id = $1 THEN $2 ELSE user_id END,
+                        admin_id = CASE WHEN admin_id = $1 THEN $2 ELSE admin_id END
+                    WHERE EXISTS (SELECT 1 FROM source) AND (user_id = $1 OR admin_id = $1)
+                    RETURNING 1
+                ), device_codes_moved AS (
+                    UPDATE device_codes SET user_id = $2
+                    WHERE user_id IN (SELECT id FROM source)
+                    RETURNING 1
+                ), deleted AS (
+                    UPDATE users SET deleted_at = NOW()
+                    WHERE id IN (SELECT id FROM source)
+                    RETURNING 1
+                )
+                SELECT
+                    (SELECT COUNT(*) FROM deleted) AS "deleted!",
+                    (SELECT COUNT(*) FROM sessions_moved) AS "sessions!",
+                    (SELECT COUNT(*) FROM passkeys_moved) AS "passkeys!",
+                    (SELECT COUNT(*) FROM identities_moved) AS "identities!",
+                    (SELECT COUNT(*) FROM grants_moved) AS "grants!",
+                    (SELECT COUNT(*) FROM logins_moved) AS "logins!",
+                    (SELECT COUNT(*) FROM impersonations_moved) AS "impersonations!",
+                    (SELECT COUNT(*) FROM denylist_moved)
+                        + (SELECT COUNT(*) FROM memberships_moved)
+                        + (SELECT COUNT(*) FROM acceptances_moved)
+                        + (SELECT COUNT(*) FROM preferences_moved)
+                        + (SELECT COUNT(*) FROM avatar_moved)
+                        + (SELECT COUNT(*) FROM device_codes_moved) AS "other!"
+            "#,
+            self.id,
+            target_id,
+        )
+        .fetch_one(database)
+        .await?;
+
+        // Either user is missing or deleted
+        if database_record.deleted == 0 {
+            return Err(AuthenticationError::Sqlx(sqlx::Error::RowNotFound));
+        }
+
+        let count = |value: i64| u64::try_from(value).unwrap_or_default();
+        let merge = UsersMerge {
+            sessions: count(database_record.sessions),
+            passkeys: count(database_record.passkeys),
+            identities: count(database_record.identities),
+            grants: count(database_record.grants),
+            logins: count(database_record.logins),
+            impersonations: count(database_record.impersonations),
+        };
+
+        tracing::debug!(
+            "User merged into {target_id}: {merge:?}, other records: {}",
+            database_record.other
+        );
+
+        Ok(merge)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn merged_users_records_move_to_the_kept_user(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let kept = database::Users::mock_data()?;
+        kept.insert(&database).await?;
+        let duplicate = database::Users::mock_data()?;
+        duplicate.insert(&database).await?;
+        let session = database::Sessions::mock_data(&duplicate).await?;
+        session.insert(&database).await?;
+        let identity = database::FederatedIdentities::mock_data(&duplicate.id)
+            .insert(&database)
+            .await?;
+        database::UserPreferences::mock_data(&kept.id)
+            .upsert(&database)
+            .await?;
+        database::UserPreferences::defaults(&duplicate.id)
+            .upsert(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let merge = duplicate.merge_into(&kept.id, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(merge.sessions, 1);
+        assert_eq!(merge.identities, 1);
+        assert_eq!(
+            database::Sessions::from_id(&session.id, &database).await?.user_id,
+            kept.id
+        );
+        assert_eq!(
+            database::FederatedIdentities::index_user(&kept.id, &database).await?,
+            vec![database::FederatedIdentities {
+                user_id: kept.id,
+                ..identity
+            }]
+        );
+        // The kept user's own preferences are not replaced
+        assert_eq!(
+            database::UserPreferences::for_user(&kept.id, &database).await?.timezone,
+            database::UserPreferences::mock_data(&kept.id).timezone
+        );
+        assert!(database::Users::from_user_id(&duplicate.id, &database).await.is_err());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn users_are_not_merged_twice_or_into_themselves(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let kept = database::Users::mock_data()?;
+        kept.insert(&database).await?;
+        let duplicate = database::Users::mock_data()?;
+        duplicate.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let first = duplicate.merge_into(&kept.id, &database).await;
+        let second = duplicate.merge_into(&kept.id, &database).await;
+        let themselves = kept.merge_into(&kept.id, &database).await;
+
+        //-- Checks (Assertions)
+        assert!(first.is_ok());
+        assert!(second.is_err());
+        assert!(themselves.is_err());
+
+        Ok(())
+    }
+}
@@ -6,18 +6,30 @@
 //! It organises logic into submodules for each operation and re-exports the main `Users` model for convenient use elsewhere.
 //!
 //! # Contents
-//! - User deletion logic
+//! - User count and exists helpers
+//! - User soft deletion, restore and purge logic
 //! - User insertion/creation logic
 //! - User struct definition and model-level helpers
+//! - User merge logic, for duplicate accounts
+//! - Password state, for temporary passwords and forced changes
 //! - User read/query logic
+//! - User search logic with optional filters
 //! - User update logic
+//! - Verification reminders and deactivation of unverified users
 
 // #![allow(unused)] // For development only
 
-pub use model::Users;
+pub use merge::UsersMerge;
+pub use model::{PasswordState, Users};
+pub use search::UsersSearchFilter;
 
+mod count;
 mod delete;
 mod insert;
+mod merge;
 mod model;
+mod password_state;
 mod read;
+mod search;
 mod update;
+mod verification_reminders;
@@ -9,6 +9,7 @@
 //! unit tests to verify the structure and behaviour of the user model.
 //!
 //! # Contents
+//! - `PasswordState` enum definition
 //! - `Users` struct definition
 //! - Mock data generation for tests
 //! - Unit tests for model validation
@@ -20,6 +21,37 @@ use uuid::Uuid;
 
 use crate::domain;
 
+/// Can the user log in with their password. Kept off `Users`, as only login
+/// and password changes read it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, sqlx::Type)]
+#[sqlx(type_name = "password_state", rename_all = "snake_case")]
+pub enum PasswordState {
+    /// The user chose their password
+    #[default]
+    Set,
+    /// A temporary password was issued, it must be changed at the first login
+    Temporary,
+    /// An admin forced a password reset, it must be completed before logging in
+    ChangeRequired,
+}
+
+impl PasswordState {
+    /// Convert PasswordState to a string reference
+    pub fn to_str(&self) -> &str {
+        match self {
+            PasswordState::Set => "set",
+            PasswordState::Temporary => "temporary",
+            PasswordState::ChangeRequired => "change_required",
+        }
+    }
+}
+
+impl std::fmt::Display for PasswordState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
 /// Represents a user record in the database.
 ///
 /// The `Users` struct models all relevant fields for a user, including unique ID,
@@ -36,6 +68,7 @@ use crate::domain;
 /// - `is_active`: Whether the user account is active
 /// - `is_verified`: Whether the user's email is verified
 /// - `created_on`: Timestamp when the user was created
+/// - `locale`: The user's language tag, used to pick the email language
 #[derive(Debug, sqlx::FromRow, serde::Deserialize, serde::Serialize, PartialEq, Clone)]
 #[allow(non_snake_case)]
 pub struct Users {
@@ -47,6 +80,7 @@ pub struct Users {
     pub is_active: bool,
     pub is_verified: bool,
     pub created_on: DateTime<Utc>,
+    pub locale: domain::Locale,
 }
 
 impl Users {
@@ -92,6 +126,7 @@ impl Users {
             is_active: random_is_active,
             is_verified: random_is_verified,
             created_on: random_created_on,
+            locale: domain::Locale::default(),
         })
     }
 }
@@ -0,0 +1,131 @@
+//-- ./src/database/users/password_state.rs
+
+// #![allow(unused)] // For development only
+
+//! Password state helpers for the authentication service.
+//!
+//! A user's password can be one they chose, a temporary password issued when
+//! they were imported, or one an admin forced them to change. Temporary
+//! passwords must be changed at the first login, and forced changes need a
+//! password reset before the user can log in. The state is kept off the
+//! `Users` model, as only login and password changes read it.
+//!
+//! # Contents
+//! - Read a user's password state
+//! - Set a user's password state
+//! - Unit tests for password state scenarios
+
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::database::{PasswordState, Users};
+use crate::prelude::*;
+
+impl Users {
+    /// Read the password state of a user.
+    ///
+    /// # Parameters
+    /// * `id` - The user id.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(PasswordState)` - The user's password state, `Set` for an
+    ///   unknown user.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Read a User password state from the database: ",
+        skip(database)
+    )]
+    pub async fn password_state(
+        id: &Uuid,
+        database: impl PgExecutor<'_>,
+    ) -> Result<PasswordState, AuthenticationError> {
+        let password_state = sqlx::query_scalar!(
+            r#"
+                SELECT password_state AS "password_state: PasswordState"
+                FROM users
+                WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            id
+        )
+        .fetch_optional(database)
+        .await?;
+
+        Ok(password_state.unwrap_or_default())
+    }
+
+    /// Set the password state of a user.
+    ///
+    /// # Parameters
+    /// * `id` - The user id.
+    /// * `password_state` - The user's new password state.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of users updated, zero for an unknown user.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Set a User password state in the database: ",
+        skip(database)
+    )]
+    pub async fn set_password_state(
+        id: &Uuid,
+        password_state: PasswordState,
+        database: impl PgExecutor<'_>,
+    ) -> Result<u64, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                UPDATE users
+                SET password_state = $2
+                WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            id,
+            password_state as PasswordState,
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        Ok(rows_affected)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+    use uuid::Uuid;
+
+    use crate::database::{self, PasswordState};
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn password_state_is_set_and_read(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let before = database::Users::password_state(&user.id, &database).await?;
+        database::Users::set_password_state(&user.id, PasswordState::Temporary, &database)
+            .await?;
+        let temporary = database::Users::password_state(&user.id, &database).await?;
+        database::Users::set_password_state(&user.id, PasswordState::ChangeRequired, &database)
+            .await?;
+        let change_required = database::Users::password_state(&user.id, &database).await?;
+        let unknown =
+            database::Users::set_password_state(&Uuid::now_v7(), PasswordState::Set, &database)
+                .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(before, PasswordState::Set);
+        assert_eq!(temporary, PasswordState::Temporary);
+        assert_eq!(change_required, PasswordState::ChangeRequired);
+        assert_eq!(unknown, 0);
+
+        Ok(())
+    }
+}
@@ -7,9 +7,19 @@
 // #![allow(unused)] // For development only
 
 use chrono::{DateTime, Utc};
+use tokio_stream::{Stream, StreamExt};
 use uuid::Uuid;
 
-use crate::{database::users::Users, domain, prelude::*};
+use crate::{
+    database::{
+        retry::retry,
+        timeouts::{with_timeout, QueryBudget},
+        users::Users,
+        SortDirection,
+    },
+    domain,
+    prelude::*,
+};
 
 impl Users {
     /// Retrieve a user from the database by their unique UUID.
@@ -39,17 +49,19 @@ impl Users {
         id: &Uuid,
         database: &sqlx::Pool<sqlx::Postgres>,
     ) -> Result<Self, AuthenticationError> {
-        let database_record = sqlx::query_as!(
-            Users,
-            r#"
-                SELECT id, email, name, password_hash, role as "role:domain::UserRole", is_active, is_verified, created_on
+        let database_record = retry("users.from_user_id", || {
+            sqlx::query_as!(
+                Users,
+                r#"
+                SELECT id, email, name, password_hash, role as "role:domain::UserRole", is_active, is_verified, created_on, locale
                 FROM users
-                WHERE id = $1
+                WHERE id = $1 AND deleted_at IS NULL
             "#,
-            id
-			)
+                id
+            )
             .fetch_one(database)
-            .await?;
+        })
+        .await?;
 
         tracing::debug!("User database record retrieved: {database_record:#?}");
 
@@ -75,17 +87,19 @@ impl Users {
         email: &domain::EmailAddress,
         database: &sqlx::Pool<sqlx::Postgres>,
     ) -> Result<Self, AuthenticationError> {
-        let database_record = sqlx::query_as!(
-            Users,
-            r#"
-                SELECT id, email, name, password_hash, role as "role:domain::UserRole", is_active, is_verified, created_on
+        let database_record = retry("users.from_user_email", || {
+            sqlx::query_as!(
+                Users,
+                r#"
+                SELECT id, email, name, password_hash, role as "role:domain::UserRole", is_active, is_verified, created_on, locale
                 FROM users
-                WHERE email = $1
+                WHERE email = $1 AND deleted_at IS NULL
             "#,
-            email.as_ref()
-			)
+                email.as_ref()
+            )
             .fetch_one(database)
-            .await?;
+        })
+        .await?;
 
         tracing::debug!("User database record retrieved: {database_record:#?}");
 
@@ -113,25 +127,80 @@ impl Users {
         offset: &usize,
         database: &sqlx::Pool<sqlx::Postgres>,
     ) -> Result<Vec<Users>, AuthenticationError> {
-        let database_records = sqlx::query_as!(
-            Users,
-            r#"
-                SELECT id, email, name, password_hash, role as "role:domain::UserRole", is_active, is_verified, created_on
-                FROM users
-                ORDER BY id
-                LIMIT $1 OFFSET $2
-            "#,
-            *limit as i64,
-            *offset as i64,
-			)
-            .fetch_all(database)
-            .await?;
+        let database_records = with_timeout(
+            QueryBudget::Interactive,
+            "users.index",
+            sqlx::query_as!(
+                Users,
+                r#"
+                    SELECT id, email, name, password_hash, role as "role:domain::UserRole", is_active, is_verified, created_on, locale
+                    FROM users
+                    WHERE deleted_at IS NULL
+                    ORDER BY id
+                    LIMIT $1 OFFSET $2
+                "#,
+                *limit as i64,
+                *offset as i64,
+            )
+            .fetch_all(database),
+        )
+        .await?;
 
         tracing::debug!("User database records retrieved: {database_records:#?}");
 
         Ok(database_records)
     }
 
+    /// Get an index of the Users that are members of an organization
+    ///
+    /// # Parameters
+    ///
+    /// * `organization_id` - The organization the users belong to
+    /// * `limit` - An i64 limiting the page length
+    /// * `offset` - An i64 of where the limit should start
+    /// * `database` - An sqlx database pool that the things will be searched in.
+    /// ---
+    #[tracing::instrument(
+        name = "Index of organization Users with offset and limit"
+        skip(database),
+        fields(
+            organization_id = %organization_id,
+            limit = ?limit,
+            offset = ?offset
+        )
+    )]
+    pub async fn index_organization(
+        organization_id: &Uuid,
+        limit: &usize,
+        offset: &usize,
+        database: &sqlx::Pool<sqlx::Postgres>,
+    ) -> Result<Vec<Users>, AuthenticationError> {
+        let database_records = with_timeout(
+            QueryBudget::Interactive,
+            "users.index_organization",
+            sqlx::query_as!(
+                Users,
+                r#"
+                    SELECT users.id, users.email, users.name, users.password_hash, users.role as "role:domain::UserRole", users.is_active, users.is_verified, users.created_on, users.locale
+                    FROM users
+                    INNER JOIN organization_members ON organization_members.user_id = users.id
+                    WHERE organization_members.organization_id = $1 AND users.deleted_at IS NULL
+                    ORDER BY users.id
+                    LIMIT $2 OFFSET $3
+                "#,
+                organization_id,
+                *limit as i64,
+                *offset as i64,
+            )
+            .fetch_all(database),
+        )
+        .await?;
+
+        tracing::debug!("Organization user database records retrieved: {database_records:#?}");
+
+        Ok(database_records)
+    }
+
     /// Get a page of Users using cursor-based pagination.
     ///
     /// This function returns a vector of Users that come after the provided cursor,
@@ -139,14 +208,15 @@ impl Users {
     /// efficient pagination, even as new users are added or removed.
     ///
     /// # Parameters
-    /// * `last_created_on` - The `created_on` timestamp of the last user from the previous page (the cursor).
-    /// * `last_id` - The `id` of the last user from the previous page (the cursor).
+    /// * `last_created_on` - The `created_on` timestamp of the last user from the previous page (the cursor), `None` for the first page.
+    /// * `last_id` - The `id` of the last user from the previous page (the cursor), `None` for the first page.
     /// * `limit` - The maximum number of users to return.
+    /// * `direction` - Oldest first when ascending, newest first when descending.
     /// * `database` - The sqlx database pool to query.
     ///
     /// # Returns
-    /// * `Ok(Vec<Users>)` - A vector of users after the given cursor, ordered by `(created_on, id)`.
-    /// * `Err(AuthenticationError)` - If the query fails.
+    /// * `Ok(Vec<Users>)` - A vector of users after the given cursor, ordered by `(created_on, id)` in the given direction.
+    /// * `Err(AuthenticationError)` - If only one cursor value is given or the query fails.
     ///
     /// # Notes
     /// - Uses the `(created_on, id)` composite index for efficient pagination.
@@ -158,34 +228,104 @@ impl Users {
             last_created_on = ?last_created_on,
             last_id = ?last_id,
             limit = ?limit,
+            direction = ?direction,
         )
     )]
     pub async fn index_cursor(
-        last_created_on: &DateTime<Utc>,
-        last_id: &Uuid,
+        last_created_on: Option<DateTime<Utc>>,
+        last_id: Option<Uuid>,
         limit: &usize,
+        direction: SortDirection,
         database: &sqlx::Pool<sqlx::Postgres>,
     ) -> Result<Vec<Users>, AuthenticationError> {
-        let database_records = sqlx::query_as!(
-            Users,
-            r#"
-                SELECT id, email, name, password_hash, role as "role:domain::UserRole", is_active, is_verified, created_on
-                FROM users
-                WHERE (created_on, id) > ($1, $2)
-                ORDER BY created_on, id
-                LIMIT $3
-            "#,
-            last_created_on,
-            last_id,
-            *limit as i64
-			)
-            .fetch_all(database)
-            .await?;
+        if last_created_on.is_some() != last_id.is_some() {
+            return Err(AuthenticationError::ValidationError(
+                "Both last_created_on and last_id must be provided together"
+                    .to_string(),
+            ));
+        }
+
+        let database_records = match direction {
+            // Oldest first, paging forward
+            SortDirection::Ascending => {
+                with_timeout(
+                    QueryBudget::Interactive,
+                    "users.index_cursor",
+                    sqlx::query_as!(
+                        Users,
+                        r#"
+                            SELECT id, email, name, password_hash, role as "role:domain::UserRole", is_active, is_verified, created_on, locale
+                            FROM users
+                            WHERE ($1::TIMESTAMPTZ IS NULL OR (created_on, id) > ($1, $2))
+                            AND deleted_at IS NULL
+                            ORDER BY created_on, id
+                            LIMIT $3
+                        "#,
+                        last_created_on,
+                        last_id,
+                        *limit as i64
+                    )
+                    .fetch_all(database),
+                )
+                .await?
+            }
+            // Newest first, paging backward
+            SortDirection::Descending => {
+                with_timeout(
+                    QueryBudget::Interactive,
+                    "users.index_cursor",
+                    sqlx::query_as!(
+                        Users,
+                        r#"
+                            SELECT id, email, name, password_hash, role as "role:domain::UserRole", is_active, is_verified, created_on, locale
+                            FROM users
+                            WHERE ($1::TIMESTAMPTZ IS NULL OR (created_on, id) < ($1, $2))
+                            AND deleted_at IS NULL
+                            ORDER BY created_on DESC, id DESC
+                            LIMIT $3
+                        "#,
+                        last_created_on,
+                        last_id,
+                        *limit as i64
+                    )
+                    .fetch_all(database),
+                )
+                .await?
+            }
+        };
 
         tracing::debug!("User database records retrieved: {database_records:#?}");
 
         Ok(database_records)
     }
+
+    /// Stream every User from the database, oldest first.
+    ///
+    /// Rows are fetched from Postgres as the stream is polled rather than
+    /// collected into a vector, so exports of millions of users stay flat in
+    /// memory. The stream holds a pool connection until it is dropped, so
+    /// consumers should stop polling once the client goes away.
+    ///
+    /// # Parameters
+    /// * `database` - The sqlx database pool to query.
+    ///
+    /// # Returns
+    /// * `impl Stream<Item = Result<Users, AuthenticationError>>` - The users ordered by `(created_on, id)`.
+    pub fn fetch_stream(
+        database: &sqlx::Pool<sqlx::Postgres>,
+    ) -> impl Stream<Item = Result<Users, AuthenticationError>> + Send + '_ {
+        sqlx::query_as!(
+            Users,
+            r#"
+                SELECT id, email, name, password_hash, role as "role:domain::UserRole", is_active, is_verified, created_on, locale
+                FROM users
+                WHERE deleted_at IS NULL
+                ORDER BY created_on, id
+            "#
+        )
+        .fetch(database)
+        .map(|user| user.map_err(AuthenticationError::from))
+    }
 }
 
 //-- Unit Tests
@@ -245,6 +385,26 @@ pub mod tests {
         Ok(())
     }
 
+    #[sqlx::test]
+    async fn index_organization_only_returns_members(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let organization = database::Organizations::mock_data()?.insert(&database).await?;
+        let member = database::Users::mock_data()?.insert(&database).await?;
+        let _outsider = database::Users::mock_data()?.insert(&database).await?;
+        database::OrganizationMembers::new(&organization.id, &member.id, &member.role)
+            .insert(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let database_records =
+            database::Users::index_organization(&organization.id, &10, &0, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(database_records, vec![member]);
+
+        Ok(())
+    }
+
     #[sqlx::test]
     async fn default_user_migration(database: Pool<Postgres>) -> Result<()> {
         //-- Setup and Fixtures (Arrange)
@@ -335,9 +495,26 @@ pub mod tests {
         //-- Execute Function (Act)
         // Get users after the cursor
         let result = database::Users::index_cursor(
-            &last_created_on,
-            &last_id,
+            Some(last_created_on),
+            Some(last_id),
+            &limit,
+            SortDirection::Ascending,
+            &database,
+        )
+        .await?;
+        let newest_first = database::Users::index_cursor(
+            None,
+            None,
+            &limit,
+            SortDirection::Descending,
+            &database,
+        )
+        .await?;
+        let older = database::Users::index_cursor(
+            Some(newest_first[1].created_on),
+            Some(newest_first[1].id),
             &limit,
+            SortDirection::Descending,
             &database,
         )
         .await?;
@@ -347,6 +524,34 @@ pub mod tests {
         assert_eq!(result[0], users[2]);
         assert_eq!(result[1], users[3]);
 
+        // Descending starts at the newest user and pages backward
+        assert_eq!(newest_first, vec![users[9].clone(), users[8].clone()]);
+        assert_eq!(older, vec![users[7].clone(), users[6].clone()]);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn fetch_stream_yields_every_user_in_order(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let mut users = database::Users::insert_n_users(5, &database).await?;
+        users.pop().unwrap().delete(&database).await?;
+
+        //-- Execute Function (Act)
+        let streamed: Vec<Users> = database::Users::fetch_stream(&database)
+            .collect::<std::result::Result<_, _>>()
+            .await?;
+
+        //-- Checks (Assertions)
+        // The migration admin is streamed too, the deleted user is not
+        assert_eq!(streamed.len(), users.len() + 1);
+        assert!(users.iter().all(|user| streamed.contains(user)));
+        assert!(streamed
+            .windows(2)
+            .all(|w| (w[0].created_on, w[0].id) < (w[1].created_on, w[1].id)));
+
         Ok(())
     }
 
@@ -0,0 +1,381 @@
+//-- ./src/database/users/search.rs
+
+//! Search Users in the database using optional filters, returning a Result with
+//! a vector of Users.
+//!
+//! Every filter is optional, a `None` filter is ignored by the query. Results
+//! are ordered by `(created_on, id)` so the last row of a page can be used as
+//! the cursor for the next page.
+//!
+//! # Contents
+//! - `UsersSearchFilter` struct definition
+//! - Filtered and cursor paginated user search query
+//! - Unit tests for the search query
+//! ---
+
+// #![allow(unused)] // For development only
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    database::{
+        timeouts::{with_timeout, QueryBudget},
+        Users,
+    },
+    domain,
+    prelude::*,
+};
+
+/// Filters used when searching the users table.
+///
+/// # Fields
+/// - `email`: Case insensitive substring to match against the user email
+/// - `role`: Only return users with this role
+/// - `is_active`: Only return users with this active status
+/// - `is_verified`: Only return users with this verified status
+/// - `created_after`: Only return users created on or after this time
+/// - `created_before`: Only return users created before this time
+/// - `organization_id`: Only return members of this organization
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UsersSearchFilter {
+    pub email: Option<String>,
+    pub role: Option<domain::UserRole>,
+    pub is_active: Option<bool>,
+    pub is_verified: Option<bool>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub organization_id: Option<Uuid>,
+}
+
+impl UsersSearchFilter {
+    /// Build the ILIKE pattern for the email filter, escaping any LIKE wildcards
+    /// in the search term so they are matched literally.
+    fn email_pattern(&self) -> Option<String> {
+        self.email
+            .as_ref()
+            .map(|email| email.trim())
+            .filter(|email| !email.is_empty())
+            .map(|email| {
+                let escaped = email
+                    .replace('\\', "\\\\")
+                    .replace('%', "\\%")
+                    .replace('_', "\\_");
+                format!("%{escaped}%")
+            })
+    }
+}
+
+impl Users {
+    /// Search users in the database using optional filters and cursor pagination.
+    ///
+    /// # Parameters
+    /// * `filter` - The search filters, `None` values are ignored.
+    /// * `limit` - The maximum number of users to return.
+    /// * `cursor_created_on` - The `created_on` of the last user from the previous page.
+    /// * `cursor_id` - The `id` of the last user from the previous page.
+    /// * `database` - The sqlx database pool to query.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Users>)` - Users matching the filters, ordered by `(created_on, id)`.
+    /// * `Err(AuthenticationError)` - If the cursor is incomplete or the query fails.
+    ///
+    /// # Notes
+    /// - Both cursor values must be provided together, or neither for the first page.
+    /// - Email substring matching uses the `idx_users_email_trgm` trigram index.
+    #[tracing::instrument(
+        name = "Search Users in the database: ",
+        skip(database),
+        fields(
+            filter = ?filter,
+            limit = ?limit,
+            cursor_created_on = ?cursor_created_on,
+            cursor_id = ?cursor_id,
+        )
+    )]
+    pub async fn search(
+        filter: &UsersSearchFilter,
+        limit: &usize,
+        cursor_created_on: Option<DateTime<Utc>>,
+        cursor_id: Option<Uuid>,
+        database: &sqlx::Pool<sqlx::Postgres>,
+    ) -> Result<Vec<Users>, AuthenticationError> {
+        // Validate cursor consistency
+        if cursor_created_on.is_some() != cursor_id.is_some() {
+            return Err(AuthenticationError::ValidationError(
+                "Both cursor_created_on and cursor_id must be provided together"
+                    .to_string(),
+            ));
+        }
+
+        let limit = i64::try_from(*limit).map_err(|_| {
+            AuthenticationError::ValidationError(
+                "Pagination value too large".to_string(),
+            )
+        })?;
+
+        let database_records = with_timeout(
+            QueryBudget::Interactive,
+            "users.search",
+            sqlx::query_as!(
+                Users,
+                r#"
+                    SELECT id, email, name, password_hash, role as "role:domain::UserRole", is_active, is_verified, created_on, locale
+                    FROM users
+                    WHERE deleted_at IS NULL
+                    AND ($1::TEXT IS NULL OR email ILIKE $1)
+                    AND ($2::user_role IS NULL OR role = $2)
+                    AND ($3::BOOLEAN IS NULL OR is_active = $3)
+                    AND ($4::BOOLEAN IS NULL OR is_verified = $4)
+                    AND ($5::TIMESTAMPTZ IS NULL OR created_on >= $5)
+                    AND ($6::TIMESTAMPTZ IS NULL OR created_on < $6)
+                    AND ($7::TIMESTAMPTZ IS NULL OR (created_on, id) > ($7, $8))
+                    AND ($10::UUID IS NULL OR EXISTS (
+                        SELECT 1
+                        FROM organization_members
+                        WHERE organization_members.organization_id = $10
+                        AND organization_members.user_id = users.id
+                    ))
+                    ORDER BY created_on, id
+                    LIMIT $9
+                "#,
+                filter.email_pattern(),
+                filter.role.clone() as Option<domain::UserRole>,
+                filter.is_active,
+                filter.is_verified,
+                filter.created_after,
+                filter.created_before,
+                cursor_created_on,
+                cursor_id,
+                limit,
+                filter.organization_id,
+            )
+            .fetch_all(database),
+        )
+        .await?;
+
+        tracing::debug!("User database records retrieved: {database_records:#?}");
+
+        Ok(database_records)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::database;
+    use sqlx::{Pool, Postgres};
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn email_pattern_escapes_wildcards() {
+        //-- Setup and Fixtures (Arrange)
+        let filter = UsersSearchFilter {
+            email: Some(" 100%_off\\ ".to_string()),
+            ..Default::default()
+        };
+
+        //-- Checks (Assertions)
+        assert_eq!(filter.email_pattern(), Some("%100\\%\\_off\\\\%".to_string()));
+    }
+
+    #[test]
+    fn email_pattern_ignores_blank_terms() {
+        let filter = UsersSearchFilter {
+            email: Some("   ".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(filter.email_pattern(), None);
+    }
+
+    #[sqlx::test]
+    async fn search_without_filters_returns_all_users(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        // The migration adds a default admin user
+        let _users = database::Users::insert_n_users(5, &database).await?;
+
+        //-- Execute Function (Act)
+        let users = database::Users::search(
+            &UsersSearchFilter::default(),
+            &10,
+            None,
+            None,
+            &database,
+        )
+        .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(users.len(), 6);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn search_by_email_substring_is_case_insensitive(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let _users = database::Users::insert_n_users(5, &database).await?;
+        let mut user = database::Users::mock_data()?;
+        user.email = domain::EmailAddress::parse("Needle.Haystack@example.com")?;
+        let user = user.insert(&database).await?;
+
+        let filter = UsersSearchFilter {
+            email: Some("needle.hay".to_string()),
+            ..Default::default()
+        };
+
+        //-- Execute Function (Act)
+        let users =
+            database::Users::search(&filter, &10, None, None, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(users, vec![user]);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn search_by_role_and_status(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let users = database::Users::insert_n_users(20, &database).await?;
+
+        let filter = UsersSearchFilter {
+            role: Some(domain::UserRole::Guest),
+            is_active: Some(true),
+            is_verified: Some(false),
+            ..Default::default()
+        };
+
+        let expected = users
+            .iter()
+            .filter(|u| u.role == domain::UserRole::Guest)
+            .filter(|u| u.is_active && !u.is_verified)
+            .count();
+
+        //-- Execute Function (Act)
+        let results =
+            database::Users::search(&filter, &100, None, None, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(results.len(), expected);
+        assert!(results.iter().all(|u| u.role == domain::UserRole::Guest
+            && u.is_active
+            && !u.is_verified));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn search_by_created_on_range(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let users = database::Users::insert_n_users(20, &database).await?;
+        let created_after = Utc::now() - chrono::Duration::days(365 * 10);
+        let created_before = Utc::now();
+
+        let filter = UsersSearchFilter {
+            created_after: Some(created_after),
+            created_before: Some(created_before),
+            ..Default::default()
+        };
+
+        let expected = users
+            .iter()
+            .filter(|u| u.created_on >= created_after && u.created_on < created_before)
+            .count();
+
+        //-- Execute Function (Act)
+        let results =
+            database::Users::search(&filter, &100, None, None, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(results.len(), expected);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn search_cursor_returns_next_page(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let _users = database::Users::insert_n_users(10, &database).await?;
+        let filter = UsersSearchFilter::default();
+        let first_page =
+            database::Users::search(&filter, &4, None, None, &database).await?;
+        let last = first_page.last().unwrap();
+
+        //-- Execute Function (Act)
+        let second_page = database::Users::search(
+            &filter,
+            &4,
+            Some(last.created_on),
+            Some(last.id),
+            &database,
+        )
+        .await?;
+
+        //-- Checks (Assertions)
+        let all_users =
+            database::Users::search(&filter, &100, None, None, &database).await?;
+        assert_eq!(second_page.as_slice(), &all_users[4..8]);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn search_with_partial_cursor_returns_error(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Execute Function (Act)
+        let result = database::Users::search(
+            &UsersSearchFilter::default(),
+            &10,
+            Some(Utc::now()),
+            None,
+            &database,
+        )
+        .await;
+
+        //-- Checks (Assertions)
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn search_in_organization_only_returns_members(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let organization = database::Organizations::mock_data()?.insert(&database).await?;
+        let member = database::Users::mock_data()?.insert(&database).await?;
+        let _outsider = database::Users::mock_data()?.insert(&database).await?;
+        database::OrganizationMembers::new(
+            &organization.id,
+            &member.id,
+            &domain::UserRole::User,
+        )
+        .insert(&database)
+        .await?;
+        let filter = UsersSearchFilter {
+            organization_id: Some(organization.id),
+            ..Default::default()
+        };
+
+        //-- Execute Function (Act)
+        let results =
+            database::Users::search(&filter, &100, None, None, &database).await?;
+
+        //-- Checks (Assertions)
+        let ids: Vec<Uuid> = results.iter().map(|user| user.id).collect();
+        assert_eq!(ids, vec![member.id]);
+
+        Ok(())
+    }
+}
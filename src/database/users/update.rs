@@ -11,10 +11,12 @@ use crate::database::Users;
 impl Users {
     /// Update a `User` into the database, returning result with a UserModel instance.
     ///
+    /// The locale is not changed, use `update_locale`.
+    ///
     /// # Parameters
     ///
     /// * `user` - A User instance
-    /// * `database` - An Sqlx database connection pool
+    /// * `database` - An Sqlx database connection pool, or a transaction
     /// ---
     #[tracing::instrument(
         name = "Update a User in the database: ",
@@ -25,15 +27,15 @@ impl Users {
     )]
     pub async fn update(
         &self,
-        database: &sqlx::Pool<sqlx::Postgres>,
+        database: impl sqlx::PgExecutor<'_>,
     ) -> Result<Users, AuthenticationError> {
         let database_record = sqlx::query_as!(
 			Users,
 			r#"
 				UPDATE users
 				SET email = $2, name = $3, password_hash = $4, role = $5, is_active = $6, is_verified = $7
-				WHERE id = $1
-				RETURNING id, email, name, password_hash, role as "role:domain::UserRole", is_active, is_verified, created_on
+				WHERE id = $1 AND deleted_at IS NULL
+				RETURNING id, email, name, password_hash, role as "role:domain::UserRole", is_active, is_verified, created_on, locale
 			"#,
 			self.id,
 			self.email.as_ref(),
@@ -50,6 +52,43 @@ impl Users {
 
         Ok(database_record)
     }
+
+    /// Update a user's locale, returning the updated user.
+    ///
+    /// # Parameters
+    ///
+    /// * `locale` - The user's new locale
+    /// * `database` - An Sqlx database connection pool
+    /// ---
+    #[tracing::instrument(
+        name = "Update a User locale in the database: ",
+        skip(self, database),
+        fields(
+            user_id = %self.id,
+            locale = %locale,
+        )
+    )]
+    pub async fn update_locale(
+        &self,
+        locale: &domain::Locale,
+        database: &sqlx::Pool<sqlx::Postgres>,
+    ) -> Result<Users, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            Users,
+            r#"
+                UPDATE users
+                SET locale = $2
+                WHERE id = $1 AND deleted_at IS NULL
+                RETURNING id, email, name, password_hash, role as "role:domain::UserRole", is_active, is_verified, created_on, locale
+            "#,
+            self.id,
+            locale.as_ref(),
+        )
+        .fetch_one(database)
+        .await?;
+
+        Ok(database_record)
+    }
 }
 
 //-- Unit Tests
@@ -57,7 +96,7 @@ impl Users {
 pub mod tests {
     // use super::*;
     use sqlx::{Pool, Postgres};
-    use crate::database;
+    use crate::{database, domain};
 
     pub type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
 
@@ -83,6 +122,23 @@ pub mod tests {
         Ok(())
     }
 
+    #[sqlx::test]
+    async fn update_locale_only_changes_locale(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let locale = domain::Locale::parse("fr")?;
+
+        //-- Execute Function (Act)
+        let db_user = user.update_locale(&locale, &database).await?;
+
+        //-- Assert (Assert)
+        assert_eq!(db_user.locale, locale);
+        assert_eq!(db_user.email, user.email);
+
+        Ok(())
+    }
+
     #[sqlx::test]
     async fn update_nonexistent_user_returns_error(database: Pool<Postgres>) -> Result<()> {
         //-- Setup and Fixtures (Arrange)
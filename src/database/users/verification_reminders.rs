@@ -0,0 +1,206 @@
+//-- ./src/database/users/verification_reminders.rs
+
+// #![allow(unused)] // For development only
+
+//! Verification reminder helpers for the authentication service.
+//!
+//! Users who haven't verified their email are sent a limited number of
+//! reminders, with the interval between them doubling, then deactivated once
+//! the deadline passes. The reminder count and time are kept off the `Users`
+//! model, as only the reminder job reads them.
+//!
+//! # Contents
+//! - Claim the unverified users due a reminder
+//! - Deactivate users still unverified after the deadline
+//! - Unit tests for reminder scenarios
+
+use chrono::{DateTime, Utc};
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::database::Users;
+use crate::{domain, prelude::*};
+
+impl Users {
+    /// Claim the active, unverified users due a verification reminder,
+    /// counting the reminder as sent. Rows locked by another instance are
+    /// skipped, so each reminder is only claimed once. Run it in the
+    /// transaction that queues the reminders, so a failure releases the claim.
+    ///
+    /// A user is due their first reminder once they registered before
+    /// `registered_before`, and each later one `interval_seconds` after the
+    /// last, doubling after every reminder, until `max_reminders` are sent.
+    ///
+    /// # Parameters
+    /// * `registered_before` - Users registered before this time are reminded.
+    /// * `max_reminders` - The most reminders sent to a user.
+    /// * `interval_seconds` - Seconds between the first and second reminders.
+    /// * `limit` - The most users claimed.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Users>)` - The users to remind, oldest registration first.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Claim Users due a verification reminder in the database: ",
+        skip(database)
+    )]
+    pub async fn claim_verification_reminders(
+        registered_before: &DateTime<Utc>,
+        max_reminders: i32,
+        interval_seconds: f64,
+        limit: i64,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Vec<Self>, AuthenticationError> {
+        let database_records = sqlx::query_as!(
+            Users,
+            r#"
+                UPDATE users
+                SET verification_reminders_sent = verification_reminders_sent + 1,
+                    verification_reminded_at = NOW()
+                WHERE id IN (
+                    SELECT id
+                    FROM users
+                    WHERE is_verified = FALSE
+                        AND is_active = TRUE
+                        AND deleted_at IS NULL
+                        AND created_on < $1
+                        AND verification_reminders_sent < $2
+                        AND (
+                            verification_reminded_at IS NULL
+                            OR verification_reminded_at < NOW() - make_interval(
+                                secs => $3 * power(2.0::float8, verification_reminders_sent - 1)
+                            )
+                        )
+                    ORDER BY created_on
+                    LIMIT $4
+                    FOR UPDATE SKIP LOCKED
+                )
+                RETURNING id, email, name, password_hash, role as "role:domain::UserRole", is_active, is_verified, created_on, locale
+            "#,
+            registered_before,
+            max_reminders,
+            interval_seconds,
+            limit,
+        )
+        .fetch_all(database)
+        .await?;
+
+        tracing::debug!("Users claimed for a reminder: {}", database_records.len());
+
+        Ok(database_records)
+    }
+
+    /// Deactivate the users still unverified that registered before the
+    /// deadline.
+    ///
+    /// # Parameters
+    /// * `registered_before` - Users registered before this time are deactivated.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Uuid>)` - The ids of the users deactivated.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Deactivate unverified Users in the database: ",
+        skip(database)
+    )]
+    pub async fn deactivate_unverified(
+        registered_before: &DateTime<Utc>,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Vec<Uuid>, AuthenticationError> {
+        let ids = sqlx::query_scalar!(
+            r#"
+                UPDATE users
+                SET is_active = FALSE
+                WHERE is_verified = FALSE
+                    AND is_active = TRUE
+                    AND deleted_at IS NULL
+                    AND created_on < $1
+                RETURNING id
+            "#,
+            registered_before,
+        )
+        .fetch_all(database)
+        .await?;
+
+        tracing::debug!("Unverified users deactivated: {}", ids.len());
+
+        Ok(ids)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use chrono::{Duration, Utc};
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    /// An active, unverified user registered `days` ago
+    async fn unverified_user(days: i64, database: &Pool<Postgres>) -> Result<database::Users> {
+        let mut user = database::Users::mock_data()?;
+        user.is_active = true;
+        user.is_verified = false;
+        user.created_on = Utc::now() - Duration::days(days);
+
+        Ok(user.insert(database).await?)
+    }
+
+    #[sqlx::test]
+    async fn reminders_are_claimed_once_per_interval(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let old = unverified_user(10, &database).await?;
+        let _recent = unverified_user(1, &database).await?;
+        let registered_before = Utc::now() - Duration::days(3);
+        let one_day = Duration::days(1).num_seconds() as f64;
+
+        //-- Execute Function (Act)
+        let first =
+            database::Users::claim_verification_reminders(&registered_before, 3, one_day, 100, &database)
+                .await?;
+        let too_soon =
+            database::Users::claim_verification_reminders(&registered_before, 3, one_day, 100, &database)
+                .await?;
+        let no_interval =
+            database::Users::claim_verification_reminders(&registered_before, 2, 0.0, 100, &database)
+                .await?;
+        let over_the_limit =
+            database::Users::claim_verification_reminders(&registered_before, 2, 0.0, 100, &database)
+                .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(first.iter().map(|user| user.id).collect::<Vec<_>>(), vec![old.id]);
+        assert!(too_soon.is_empty());
+        assert_eq!(no_interval.len(), 1);
+        assert!(over_the_limit.is_empty());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn unverified_users_are_deactivated_after_the_deadline(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let old = unverified_user(40, &database).await?;
+        let recent = unverified_user(5, &database).await?;
+        let registered_before = Utc::now() - Duration::days(30);
+
+        //-- Execute Function (Act)
+        let deactivated =
+            database::Users::deactivate_unverified(&registered_before, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(deactivated, vec![old.id]);
+        assert!(!database::Users::from_user_id(&old.id, &database).await?.is_active);
+        assert!(database::Users::from_user_id(&recent.id, &database).await?.is_active);
+
+        Ok(())
+    }
+}
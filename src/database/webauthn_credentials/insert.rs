@@ -0,0 +1,87 @@
+//-- ./src/database/webauthn_credentials/insert.rs
+
+// #![allow(unused)] // For development only
+
+//! WebAuthn credential insertion logic for the authentication service.
+//!
+//! # Contents
+//! - Insert a passkey
+//! - Unit tests for insert scenarios
+
+use sqlx::PgExecutor;
+
+use crate::database::WebauthnCredentials;
+use crate::prelude::*;
+
+impl WebauthnCredentials {
+    /// Insert a passkey into the database.
+    ///
+    /// # Parameters
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(WebauthnCredentials)` - The inserted passkey record.
+    /// * `Err(AuthenticationError)` - If the database operation fails, such as
+    ///   the credential id already being registered.
+    #[tracing::instrument(
+        name = "Insert a WebAuthn credential into the database: ",
+        skip(self, database),
+        fields(id = %self.id, user_id = %self.user_id)
+    )]
+    pub async fn insert(
+        &self,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            WebauthnCredentials,
+            r#"
+                INSERT INTO webauthn_credentials (id, user_id, credential_id, name, passkey, created_on, last_used_on)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                RETURNING id, user_id, credential_id, name, passkey, created_on, last_used_on
+            "#,
+            self.id,
+            self.user_id,
+            self.credential_id,
+            self.name,
+            self.passkey,
+            self.created_on,
+            self.last_used_on,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("WebAuthn credential inserted: {}", database_record.id);
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn insert_webauthn_credential(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let credential = database::WebauthnCredentials::mock_data(&user.id);
+
+        //-- Execute Function (Act)
+        let database_record = credential.insert(&database).await?;
+        let duplicate = credential.insert(&database).await;
+
+        //-- Checks (Assertions)
+        assert_eq!(database_record, credential);
+        assert!(duplicate.is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,22 @@
+//-- ./src/database/webauthn_credentials/mod.rs
+
+//! WebAuthn credentials database module for the authentication service.
+//!
+//! The passkeys registered by each user, kept as serialised webauthn-rs
+//! passkeys with their credential id, so a user's passkeys can be loaded when
+//! they log in and the sign count updated after each use.
+//!
+//! # Contents
+//! - WebAuthn credential struct definition and model-level helpers
+//! - WebAuthn credential insertion logic
+//! - WebAuthn credential read/query logic
+//! - WebAuthn credential update logic
+
+// #![allow(unused)] // For development only
+
+pub use model::WebauthnCredentials;
+
+mod insert;
+mod model;
+mod read;
+mod update;
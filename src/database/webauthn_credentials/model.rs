@@ -0,0 +1,80 @@
+//-- ./src/database/webauthn_credentials/model.rs
+
+// #![allow(unused)] // For development only
+
+//! The WebAuthn credentials database model.
+//!
+//! # Contents
+//! - `WebauthnCredentials` struct definition
+//! - Constructor for new passkeys
+//! - Mock data generation for tests
+
+use chrono::{DateTime, SubsecRound, Utc};
+use uuid::Uuid;
+use webauthn_rs::prelude::{CredentialID, Passkey};
+
+use crate::prelude::*;
+
+#[derive(Debug, sqlx::FromRow, Clone, PartialEq)]
+pub struct WebauthnCredentials {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub credential_id: String,
+    pub name: String,
+    pub passkey: String,
+    pub created_on: DateTime<Utc>,
+    pub last_used_on: Option<DateTime<Utc>>,
+}
+
+impl WebauthnCredentials {
+    /// # New Database WebAuthn Credential Instance
+    ///
+    /// Creates a new, unused, passkey record for the user.
+    ///
+    /// ## Parameters
+    ///
+    /// - `user_id: &Uuid` - The user who registered the passkey
+    /// - `name: &str` - Name the user gave the passkey
+    /// - `passkey: &Passkey` - The passkey returned by finishing the registration
+    pub fn new(
+        user_id: &Uuid,
+        name: &str,
+        passkey: &Passkey,
+    ) -> Result<Self, AuthenticationError> {
+        Ok(Self {
+            id: Uuid::now_v7(),
+            user_id: user_id.to_owned(),
+            credential_id: Self::encode_credential_id(passkey.cred_id()),
+            name: name.to_string(),
+            passkey: serde_json::to_string(passkey)?,
+            created_on: Utc::now().round_subsecs(0),
+            last_used_on: None,
+        })
+    }
+
+    /// The stored webauthn-rs passkey
+    pub fn passkey(&self) -> Result<Passkey, AuthenticationError> {
+        Ok(serde_json::from_str(&self.passkey)?)
+    }
+
+    /// Hex encode a credential id, as stored in `credential_id`
+    pub fn encode_credential_id(credential_id: &CredentialID) -> String {
+        let bytes: &[u8] = credential_id.as_ref();
+        bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    #[cfg(test)]
+    pub fn mock_data(user_id: &Uuid) -> Self {
+        use rand::distr::{Alphanumeric, SampleString};
+
+        Self {
+            id: Uuid::now_v7(),
+            user_id: user_id.to_owned(),
+            credential_id: Alphanumeric.sample_string(&mut rand::rng(), 32),
+            name: "Mock passkey".to_string(),
+            passkey: "{}".to_string(),
+            created_on: Utc::now().round_subsecs(0),
+            last_used_on: None,
+        }
+    }
+}
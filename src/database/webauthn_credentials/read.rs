@@ -0,0 +1,123 @@
+//-- ./src/database/webauthn_credentials/read.rs
+
+// #![allow(unused)] // For development only
+
+//! WebAuthn credential read logic for the authentication service.
+//!
+//! # Contents
+//! - Index a user's passkeys
+//! - Check a user has registered a passkey
+//! - Unit tests for read scenarios
+
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::database::WebauthnCredentials;
+use crate::database::timeouts::{with_timeout, QueryBudget};
+use crate::prelude::*;
+
+impl WebauthnCredentials {
+    /// Retrieve the passkeys registered by a user, oldest first.
+    ///
+    /// # Parameters
+    /// * `user_id` - The user whose passkeys are returned.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<WebauthnCredentials>)` - The user's passkeys.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Index a user's WebAuthn credentials: ", skip(database))]
+    pub async fn index_for_user(
+        user_id: &Uuid,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Vec<Self>, AuthenticationError> {
+        let database_records = with_timeout(
+            QueryBudget::Interactive,
+            "webauthn_credentials.index_for_user",
+            sqlx::query_as!(
+                WebauthnCredentials,
+                r#"
+                    SELECT id, user_id, credential_id, name, passkey, created_on, last_used_on
+                    FROM webauthn_credentials
+                    WHERE user_id = $1
+                    ORDER BY created_on, id
+                "#,
+                user_id,
+            )
+            .fetch_all(database),
+        )
+        .await?;
+
+        tracing::debug!("WebAuthn credentials retrieved: {}", database_records.len());
+
+        Ok(database_records)
+    }
+
+    /// Check a user has registered at least one passkey.
+    ///
+    /// # Parameters
+    /// * `user_id` - The user to check.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(bool)` - True when the user has a passkey.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(name = "Check a user has a WebAuthn credential: ", skip(database))]
+    pub async fn exists_for_user(
+        user_id: &Uuid,
+        database: impl PgExecutor<'_>,
+    ) -> Result<bool, AuthenticationError> {
+        let exists = sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM webauthn_credentials WHERE user_id = $1
+                ) AS "exists!"
+            "#,
+            user_id,
+        )
+        .fetch_one(database)
+        .await?;
+
+        Ok(exists)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn only_the_users_passkeys_are_read(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let other_user = database::Users::mock_data()?;
+        other_user.insert(&database).await?;
+        let credential = database::WebauthnCredentials::mock_data(&user.id)
+            .insert(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let credentials =
+            database::WebauthnCredentials::index_for_user(&user.id, &database).await?;
+        let has_passkey =
+            database::WebauthnCredentials::exists_for_user(&user.id, &database).await?;
+        let other_has_passkey =
+            database::WebauthnCredentials::exists_for_user(&other_user.id, &database)
+                .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(credentials, vec![credential]);
+        assert!(has_passkey);
+        assert!(!other_has_passkey);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+//-- ./src/database/webauthn_credentials/update.rs
+
+// #![allow(unused)] // For development only
+
+//! WebAuthn credential update logic for the authentication service.
+//!
+//! # Contents
+//! - Record a passkey being used to log in
+//! - Unit tests for update scenarios
+
+use sqlx::PgExecutor;
+
+use crate::database::WebauthnCredentials;
+use crate::prelude::*;
+
+impl WebauthnCredentials {
+    /// Save the passkey after it was used to log in, with its updated sign
+    /// count, and set when it was last used.
+    ///
+    /// # Parameters
+    /// * `passkey` - The serialised passkey, updated from the authentication result.
+    /// * `database` - The SQLx PostgreSQL transaction, or connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(WebauthnCredentials)` - The updated passkey record.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Record a WebAuthn credential use in the database: ",
+        skip(self, passkey, database),
+        fields(id = %self.id)
+    )]
+    pub async fn record_use(
+        &self,
+        passkey: &str,
+        database: impl PgExecutor<'_>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            WebauthnCredentials,
+            r#"
+                UPDATE webauthn_credentials
+                SET passkey = $2, last_used_on = NOW()
+                WHERE id = $1
+                RETURNING id, user_id, credential_id, name, passkey, created_on, last_used_on
+            "#,
+            self.id,
+            passkey,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("WebAuthn credential used: {}", database_record.id);
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn recording_a_use_saves_the_passkey(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let credential = database::WebauthnCredentials::mock_data(&user.id)
+            .insert(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let used = credential.record_use(r#"{"counter":1}"#, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(used.passkey, r#"{"counter":1}"#);
+        assert!(used.last_used_on.is_some());
+        assert_eq!(used.created_on, credential.created_on);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+//-- ./src/database/webhooks/delete.rs
+
+// #![allow(unused)] // For development only
+
+//! Webhook endpoint delete logic for the authentication service.
+//!
+//! # Contents
+//! - Delete a webhook endpoint, and its deliveries, by id
+//! - Unit tests for delete scenarios
+
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::database::WebhookEndpoints;
+use crate::prelude::*;
+
+impl WebhookEndpoints {
+    /// Delete a webhook endpoint by its id. Its deliveries are deleted with it.
+    ///
+    /// # Parameters
+    /// * `id` - The webhook endpoint id.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - The number of endpoints deleted (0 if the endpoint does not exist).
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Delete a webhook endpoint from the database: ",
+        skip(database)
+    )]
+    pub async fn delete_by_id(
+        id: &Uuid,
+        database: &Pool<Postgres>,
+    ) -> Result<usize, AuthenticationError> {
+        let rows_affected = sqlx::query!(
+            r#"
+                DELETE FROM webhook_endpoints
+                WHERE id = $1
+            "#,
+            id
+        )
+        .execute(database)
+        .await?
+        .rows_affected();
+
+        tracing::debug!("Webhook endpoints deleted: {rows_affected}");
+
+        Ok(rows_affected as usize)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn delete_by_id_removes_deliveries(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let endpoint = database::WebhookEndpoints::mock_data()
+            .insert(&database)
+            .await?;
+        database::WebhookDeliveries::mock_data(&endpoint.id)
+            .insert(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let deleted =
+            database::WebhookEndpoints::delete_by_id(&endpoint.id, &database)
+                .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(deleted, 1);
+        assert!(database::WebhookEndpoints::from_id(&endpoint.id, &database)
+            .await
+            .is_err());
+        let deliveries = database::WebhookDeliveries::index_endpoint(
+            &endpoint.id,
+            &10,
+            &0,
+            &database,
+        )
+        .await?;
+        assert!(deliveries.is_empty());
+
+        Ok(())
+    }
+}
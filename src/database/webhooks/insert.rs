@@ -0,0 +1,159 @@
+//-- ./src/database/webhooks/insert.rs
+
+// #![allow(unused)] // For development only
+
+//! Webhook insert logic for the authentication service.
+//!
+//! # Contents
+//! - Insert a webhook endpoint
+//! - Insert (queue) a webhook delivery
+//! - Unit tests for insert scenarios
+
+use sqlx::{Pool, Postgres};
+
+use crate::database::{WebhookDeliveries, WebhookDeliveryStatus, WebhookEndpoints};
+use crate::prelude::*;
+
+impl WebhookEndpoints {
+    /// Insert this webhook endpoint into the database.
+    ///
+    /// # Parameters
+    /// * `self` - The `WebhookEndpoints` instance to insert.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(WebhookEndpoints)` - The inserted record as returned from the database.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Insert a webhook endpoint into the database: ",
+        skip(self, database),
+        fields(
+            id = %self.id,
+            url = %self.url,
+        )
+    )]
+    pub async fn insert(
+        &self,
+        database: &Pool<Postgres>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            WebhookEndpoints,
+            r#"
+                INSERT INTO webhook_endpoints (id, url, secret, event_types, is_active, created_on)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                RETURNING id, url, secret, event_types, is_active, created_on
+            "#,
+            self.id,
+            self.url,
+            self.secret,
+            &self.event_types,
+            self.is_active,
+            self.created_on,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("Webhook endpoint inserted: {}", database_record.id);
+
+        Ok(database_record)
+    }
+}
+
+impl WebhookDeliveries {
+    /// Insert this webhook delivery into the retry queue.
+    ///
+    /// # Parameters
+    /// * `self` - The `WebhookDeliveries` instance to insert.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(WebhookDeliveries)` - The inserted record as returned from the database.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Insert a webhook delivery into the database: ",
+        skip(self, database),
+        fields(
+            id = %self.id,
+            endpoint_id = %self.endpoint_id,
+            event_type = %self.event_type,
+        )
+    )]
+    pub async fn insert(
+        &self,
+        database: &Pool<Postgres>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            WebhookDeliveries,
+            r#"
+                INSERT INTO webhook_deliveries (id, endpoint_id, event_id, event_type, payload, status, attempts, next_attempt_at, last_attempt_at, last_response_status, last_error, created_on)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                RETURNING id, endpoint_id, event_id, event_type, payload, status as "status:WebhookDeliveryStatus", attempts, next_attempt_at, last_attempt_at, last_response_status, last_error, created_on
+            "#,
+            self.id,
+            self.endpoint_id,
+            self.event_id,
+            self.event_type,
+            self.payload,
+            self.status as WebhookDeliveryStatus,
+            self.attempts,
+            self.next_attempt_at,
+            self.last_attempt_at,
+            self.last_response_status,
+            self.last_error,
+            self.created_on,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!("Webhook delivery queued: {}", database_record.id);
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn create_webhook_endpoint_and_delivery(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let endpoint = database::WebhookEndpoints::mock_data();
+        let delivery = database::WebhookDeliveries::mock_data(&endpoint.id);
+
+        //-- Execute Function (Act)
+        let endpoint_record = endpoint.insert(&database).await?;
+        let delivery_record = delivery.insert(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(endpoint_record, endpoint);
+        assert_eq!(delivery_record, delivery);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn delivery_without_endpoint_is_rejected(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let delivery = database::WebhookDeliveries::mock_data(&uuid::Uuid::now_v7());
+
+        //-- Execute Function (Act)
+        let result = delivery.insert(&database).await;
+
+        //-- Checks (Assertions)
+        assert!(result.is_err());
+
+        Ok(())
+    }
+}
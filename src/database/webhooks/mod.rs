@@ -0,0 +1,24 @@
+//-- ./src/database/webhooks/mod.rs
+
+//! Webhooks database module for the authentication service.
+//!
+//! Webhook endpoints receive authentication events as signed JSON. Each event
+//! is queued as a delivery per endpoint, and the queue is retried until the
+//! delivery succeeds or runs out of attempts.
+//!
+//! # Contents
+//! - Endpoint and delivery struct definitions and model-level helpers
+//! - Endpoint and delivery insertion logic
+//! - Endpoint and delivery read/query logic, including claiming due deliveries
+//! - Delivery attempt recording logic
+//! - Endpoint delete logic
+
+// #![allow(unused)] // For development only
+
+pub use model::{WebhookDeliveries, WebhookDeliveryStatus, WebhookEndpoints};
+
+mod delete;
+mod insert;
+mod model;
+mod read;
+mod update;
@@ -0,0 +1,179 @@
+//-- ./src/database/webhooks/model.rs
+
+// #![allow(unused)] // For development only
+
+//! The webhooks database models.
+//!
+//! # Contents
+//! - `WebhookEndpoints` struct definition
+//! - `WebhookDeliveries` struct definition
+//! - `WebhookDeliveryStatus` enum definition
+//! - Constructors for new instances
+//! - Mock data generation for tests
+
+use chrono::{DateTime, SubsecRound, Utc};
+use rand::distr::{Alphanumeric, SampleString};
+use uuid::Uuid;
+
+/// Leading characters identifying the string as a webhook signing secret
+static WEBHOOK_SECRET_PREFIX: &str = "whsec_";
+
+/// Number of random characters in a webhook signing secret
+const WEBHOOK_SECRET_RANDOM_LENGTH: usize = 40;
+
+/// Where a webhook delivery is in the retry queue
+#[derive(Debug, Clone, Copy, Default, PartialEq, sqlx::Type, serde::Deserialize)]
+#[sqlx(type_name = "webhook_delivery_status", rename_all = "lowercase")]
+pub enum WebhookDeliveryStatus {
+    #[default]
+    Pending,
+    Delivered,
+    Failed,
+}
+
+impl WebhookDeliveryStatus {
+    /// Convert WebhookDeliveryStatus to a string reference
+    pub fn to_str(&self) -> &str {
+        match self {
+            WebhookDeliveryStatus::Pending => "pending",
+            WebhookDeliveryStatus::Delivered => "delivered",
+            WebhookDeliveryStatus::Failed => "failed",
+        }
+    }
+}
+
+impl std::fmt::Display for WebhookDeliveryStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+#[derive(Debug, serde::Deserialize, sqlx::FromRow, Clone, PartialEq)]
+pub struct WebhookEndpoints {
+    pub id: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub event_types: Vec<String>,
+    pub is_active: bool,
+    pub created_on: DateTime<Utc>,
+}
+
+#[derive(Debug, serde::Deserialize, sqlx::FromRow, Clone, PartialEq)]
+pub struct WebhookDeliveries {
+    pub id: Uuid,
+    pub endpoint_id: Uuid,
+    pub event_id: Uuid,
+    pub event_type: String,
+    pub payload: String,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub last_response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_on: DateTime<Utc>,
+}
+
+impl WebhookEndpoints {
+    /// # New Database Webhook Endpoint Instance
+    ///
+    /// Creates a new, active instance of the WebhookEndpoints struct with a
+    /// freshly generated signing secret. The secret is kept in plain text as it
+    /// is needed to sign each delivery.
+    ///
+    /// ## Parameters
+    ///
+    /// - `url: &str` - Where events are POSTed
+    /// - `event_types: &[String]` - The event types to send, empty for all
+    pub fn new(url: &str, event_types: &[String]) -> Self {
+        let random = Alphanumeric
+            .sample_string(&mut rand::rng(), WEBHOOK_SECRET_RANDOM_LENGTH);
+
+        Self {
+            id: Uuid::now_v7(),
+            url: url.to_string(),
+            secret: format!("{WEBHOOK_SECRET_PREFIX}{random}"),
+            event_types: event_types.to_vec(),
+            is_active: true,
+            created_on: Utc::now().round_subsecs(0),
+        }
+    }
+
+    #[cfg(test)]
+    /// # Mock Webhook Endpoint Data
+    ///
+    /// This function is only available in test mode `#[cfg(test)]`.
+    /// Creates a new endpoint instance subscribed to every event type.
+    pub fn mock_data() -> Self {
+        Self::new("https://hooks.example.com/authentication", &[])
+    }
+}
+
+impl WebhookDeliveries {
+    /// # New Database Webhook Delivery Instance
+    ///
+    /// Creates a new pending delivery, due straight away.
+    ///
+    /// ## Parameters
+    ///
+    /// - `endpoint_id: &Uuid` - The endpoint the payload is sent to
+    /// - `event_id: &Uuid` - The authentication event being delivered
+    /// - `event_type: &str` - The webhook event type, e.g. `user.login`
+    /// - `payload: &str` - The JSON body sent on every attempt
+    pub fn new(
+        endpoint_id: &Uuid,
+        event_id: &Uuid,
+        event_type: &str,
+        payload: &str,
+    ) -> Self {
+        let now = Utc::now().round_subsecs(0);
+
+        Self {
+            id: Uuid::now_v7(),
+            endpoint_id: endpoint_id.to_owned(),
+            event_id: event_id.to_owned(),
+            event_type: event_type.to_string(),
+            payload: payload.to_string(),
+            status: WebhookDeliveryStatus::Pending,
+            attempts: 0,
+            next_attempt_at: now,
+            last_attempt_at: None,
+            last_response_status: None,
+            last_error: None,
+            created_on: now,
+        }
+    }
+
+    #[cfg(test)]
+    /// # Mock Webhook Delivery Data
+    ///
+    /// This function is only available in test mode `#[cfg(test)]`.
+    /// Creates a new pending `user.login` delivery for the endpoint.
+    pub fn mock_data(endpoint_id: &Uuid) -> Self {
+        Self::new(
+            endpoint_id,
+            &Uuid::now_v7(),
+            "user.login",
+            r#"{"type":"user.login"}"#,
+        )
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn new_endpoints_get_unique_secrets() {
+        let first = WebhookEndpoints::mock_data();
+        let second = WebhookEndpoints::mock_data();
+
+        assert!(first.secret.starts_with(WEBHOOK_SECRET_PREFIX));
+        assert_eq!(
+            first.secret.len(),
+            WEBHOOK_SECRET_PREFIX.len() + WEBHOOK_SECRET_RANDOM_LENGTH
+        );
+        assert_ne!(first.secret, second.secret);
+    }
+}
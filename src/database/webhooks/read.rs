@@ -0,0 +1,311 @@
+//-- ./src/database/webhooks/read.rs
+
+// #![allow(unused)] // For development only
+
+//! Webhook read logic for the authentication service.
+//!
+//! # Contents
+//! - Get a webhook endpoint by id
+//! - Index webhook endpoints with pagination
+//! - Index the active endpoints subscribed to an event type
+//! - Index an endpoint's deliveries with pagination
+//! - Claim the deliveries that are due an attempt
+//! - Unit tests for read scenarios
+
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::database::{WebhookDeliveries, WebhookDeliveryStatus, WebhookEndpoints};
+use crate::database::timeouts::{with_timeout, QueryBudget};
+use crate::prelude::*;
+
+impl WebhookEndpoints {
+    /// Retrieve a webhook endpoint by its id.
+    ///
+    /// # Parameters
+    /// * `id` - The webhook endpoint id.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(WebhookEndpoints)` - The webhook endpoint record.
+    /// * `Err(AuthenticationError)` - If the query fails or no endpoint has the id.
+    #[tracing::instrument(
+        name = "Get a webhook endpoint from the database: ",
+        skip(database)
+    )]
+    pub async fn from_id(
+        id: &Uuid,
+        database: &Pool<Postgres>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            WebhookEndpoints,
+            r#"
+                SELECT id, url, secret, event_types, is_active, created_on
+                FROM webhook_endpoints
+                WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_one(database)
+        .await?;
+
+        Ok(database_record)
+    }
+
+    /// Retrieve a page of webhook endpoints, newest first.
+    ///
+    /// # Parameters
+    /// * `limit` - The maximum number of endpoints to return.
+    /// * `offset` - The number of endpoints to skip.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<WebhookEndpoints>)` - The page of webhook endpoints.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Index webhook endpoints in the database: ",
+        skip(database)
+    )]
+    pub async fn index(
+        limit: &usize,
+        offset: &usize,
+        database: &Pool<Postgres>,
+    ) -> Result<Vec<Self>, AuthenticationError> {
+        let database_records = with_timeout(
+            QueryBudget::Interactive,
+            "webhook_endpoints.index",
+            sqlx::query_as!(
+                WebhookEndpoints,
+                r#"
+                    SELECT id, url, secret, event_types, is_active, created_on
+                    FROM webhook_endpoints
+                    ORDER BY created_on DESC, id DESC
+                    LIMIT $1 OFFSET $2
+                "#,
+                *limit as i64,
+                *offset as i64,
+            )
+            .fetch_all(database),
+        )
+        .await?;
+
+        tracing::debug!("Webhook endpoints retrieved: {}", database_records.len());
+
+        Ok(database_records)
+    }
+
+    /// Retrieve the active endpoints subscribed to an event type.
+    ///
+    /// Endpoints with no event types are subscribed to every event type.
+    ///
+    /// # Parameters
+    /// * `event_type` - The webhook event type, e.g. `user.login`.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<WebhookEndpoints>)` - The subscribed endpoints.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Index subscribed webhook endpoints in the database: ",
+        skip(database)
+    )]
+    pub async fn index_subscribed(
+        event_type: &str,
+        database: &Pool<Postgres>,
+    ) -> Result<Vec<Self>, AuthenticationError> {
+        let database_records = sqlx::query_as!(
+            WebhookEndpoints,
+            r#"
+                SELECT id, url, secret, event_types, is_active, created_on
+                FROM webhook_endpoints
+                WHERE is_active AND (cardinality(event_types) = 0 OR $1 = ANY(event_types))
+            "#,
+            event_type,
+        )
+        .fetch_all(database)
+        .await?;
+
+        Ok(database_records)
+    }
+}
+
+impl WebhookDeliveries {
+    /// Retrieve a page of an endpoint's deliveries, newest first.
+    ///
+    /// # Parameters
+    /// * `endpoint_id` - The webhook endpoint id.
+    /// * `limit` - The maximum number of deliveries to return.
+    /// * `offset` - The number of deliveries to skip.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<WebhookDeliveries>)` - The page of deliveries.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Index webhook deliveries in the database: ",
+        skip(database)
+    )]
+    pub async fn index_endpoint(
+        endpoint_id: &Uuid,
+        limit: &usize,
+        offset: &usize,
+        database: &Pool<Postgres>,
+    ) -> Result<Vec<Self>, AuthenticationError> {
+        let database_records = with_timeout(
+            QueryBudget::Interactive,
+            "webhook_deliveries.index_endpoint",
+            sqlx::query_as!(
+                WebhookDeliveries,
+                r#"
+                    SELECT id, endpoint_id, event_id, event_type, payload, status as "status:WebhookDeliveryStatus", attempts, next_attempt_at, last_attempt_at, last_response_status, last_error, created_on
+                    FROM webhook_deliveries
+                    WHERE endpoint_id = $1
+                    ORDER BY created_on DESC, id DESC
+                    LIMIT $2 OFFSET $3
+                "#,
+                endpoint_id,
+                *limit as i64,
+                *offset as i64,
+            )
+            .fetch_all(database),
+        )
+        .await?;
+
+        tracing::debug!("Webhook deliveries retrieved: {}", database_records.len());
+
+        Ok(database_records)
+    }
+
+    /// Claim pending deliveries that are due an attempt, oldest first.
+    ///
+    /// Claimed deliveries have their next attempt pushed back by the lease, so
+    /// another worker (or instance) does not pick them up while they are being
+    /// sent. Recording the attempt replaces the lease.
+    ///
+    /// # Parameters
+    /// * `limit` - The maximum number of deliveries to claim.
+    /// * `lease_seconds` - How long the claim lasts.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<WebhookDeliveries>)` - The claimed deliveries.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Claim due webhook deliveries in the database: ",
+        skip(database)
+    )]
+    pub async fn claim_due(
+        limit: &usize,
+        lease_seconds: &u64,
+        database: &Pool<Postgres>,
+    ) -> Result<Vec<Self>, AuthenticationError> {
+        let database_records = sqlx::query_as!(
+            WebhookDeliveries,
+            r#"
+                UPDATE webhook_deliveries
+                SET next_attempt_at = NOW() + make_interval(secs => $2)
+                WHERE id IN (
+                    SELECT id
+                    FROM webhook_deliveries
+                    WHERE status = 'pending' AND next_attempt_at <= NOW()
+                    ORDER BY next_attempt_at
+                    LIMIT $1
+                    FOR UPDATE SKIP LOCKED
+                )
+                RETURNING id, endpoint_id, event_id, event_type, payload, status as "status:WebhookDeliveryStatus", attempts, next_attempt_at, last_attempt_at, last_response_status, last_error, created_on
+            "#,
+            *limit as i64,
+            *lease_seconds as f64,
+        )
+        .fetch_all(database)
+        .await?;
+
+        tracing::debug!("Webhook deliveries claimed: {}", database_records.len());
+
+        Ok(database_records)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn index_subscribed_filters_by_event_type(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let all = database::WebhookEndpoints::mock_data()
+            .insert(&database)
+            .await?;
+        let logins = database::WebhookEndpoints::new(
+            "https://hooks.example.com/logins",
+            &["user.login".to_string()],
+        )
+        .insert(&database)
+        .await?;
+        let mut inactive = database::WebhookEndpoints::mock_data();
+        inactive.is_active = false;
+        inactive.insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let login_endpoints =
+            database::WebhookEndpoints::index_subscribed("user.login", &database)
+                .await?;
+        let password_endpoints = database::WebhookEndpoints::index_subscribed(
+            "password.changed",
+            &database,
+        )
+        .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(login_endpoints.len(), 2);
+        assert!(login_endpoints.contains(&logins));
+        assert_eq!(password_endpoints, vec![all]);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn claimed_deliveries_are_not_claimed_twice(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let endpoint = database::WebhookEndpoints::mock_data()
+            .insert(&database)
+            .await?;
+        let delivery = database::WebhookDeliveries::mock_data(&endpoint.id)
+            .insert(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let first =
+            database::WebhookDeliveries::claim_due(&10, &60, &database).await?;
+        let second =
+            database::WebhookDeliveries::claim_due(&10, &60, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].id, delivery.id);
+        assert!(first[0].next_attempt_at > delivery.next_attempt_at);
+        assert!(second.is_empty());
+
+        let listed = database::WebhookDeliveries::index_endpoint(
+            &endpoint.id,
+            &10,
+            &0,
+            &database,
+        )
+        .await?;
+        assert_eq!(listed.len(), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+//-- ./src/database/webhooks/update.rs
+
+// #![allow(unused)] // For development only
+
+//! Webhook delivery attempt logic for the authentication service.
+//!
+//! # Contents
+//! - Record the outcome of a delivery attempt
+//! - Unit tests for update scenarios
+
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+
+use crate::database::{WebhookDeliveries, WebhookDeliveryStatus};
+use crate::prelude::*;
+
+impl WebhookDeliveries {
+    /// Record the outcome of a delivery attempt, incrementing the attempt count.
+    ///
+    /// # Parameters
+    /// * `self` - The delivery that was attempted.
+    /// * `status` - The delivery status after the attempt.
+    /// * `response_status` - The HTTP status returned by the endpoint, if it responded.
+    /// * `error` - Why the attempt failed, `None` if it succeeded.
+    /// * `next_attempt_at` - When to retry, only used while the delivery is pending.
+    /// * `database` - The SQLx PostgreSQL connection pool.
+    ///
+    /// # Returns
+    /// * `Ok(WebhookDeliveries)` - The updated delivery record.
+    /// * `Err(AuthenticationError)` - If the database operation fails.
+    #[tracing::instrument(
+        name = "Record a webhook delivery attempt in the database: ",
+        skip(self, database),
+        fields(id = %self.id)
+    )]
+    pub async fn record_attempt(
+        &self,
+        status: WebhookDeliveryStatus,
+        response_status: Option<i32>,
+        error: Option<&str>,
+        next_attempt_at: DateTime<Utc>,
+        database: &Pool<Postgres>,
+    ) -> Result<Self, AuthenticationError> {
+        let database_record = sqlx::query_as!(
+            WebhookDeliveries,
+            r#"
+                UPDATE webhook_deliveries
+                SET status = $2,
+                    attempts = attempts + 1,
+                    last_attempt_at = NOW(),
+                    last_response_status = $3,
+                    last_error = $4,
+                    next_attempt_at = $5
+                WHERE id = $1
+                RETURNING id, endpoint_id, event_id, event_type, payload, status as "status:WebhookDeliveryStatus", attempts, next_attempt_at, last_attempt_at, last_response_status, last_error, created_on
+            "#,
+            self.id,
+            status as WebhookDeliveryStatus,
+            response_status,
+            error,
+            next_attempt_at,
+        )
+        .fetch_one(database)
+        .await?;
+
+        tracing::debug!(
+            "Webhook delivery attempt {} recorded as {}",
+            database_record.attempts,
+            database_record.status
+        );
+
+        Ok(database_record)
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+pub mod tests {
+    use chrono::Utc;
+    use sqlx::{Pool, Postgres};
+
+    use crate::database;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn record_attempt_increments_attempts(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let endpoint = database::WebhookEndpoints::mock_data()
+            .insert(&database)
+            .await?;
+        let delivery = database::WebhookDeliveries::mock_data(&endpoint.id)
+            .insert(&database)
+            .await?;
+
+        //-- Execute Function (Act)
+        let retry = delivery
+            .record_attempt(
+                database::WebhookDeliveryStatus::Pending,
+                Some(503),
+                Some("endpoint returned 503"),
+                Utc::now(),
+                &database,
+            )
+            .await?;
+        let delivered = retry
+            .record_attempt(
+                database::WebhookDeliveryStatus::Delivered,
+                Some(200),
+                None,
+                Utc::now(),
+                &database,
+            )
+            .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(retry.attempts, 1);
+        assert_eq!(retry.last_error.as_deref(), Some("endpoint returned 503"));
+        assert_eq!(delivered.attempts, 2);
+        assert_eq!(delivered.status, database::WebhookDeliveryStatus::Delivered);
+        assert_eq!(delivered.last_response_status, Some(200));
+        assert!(delivered.last_error.is_none());
+        assert!(delivered.last_attempt_at.is_some());
+
+        Ok(())
+    }
+}
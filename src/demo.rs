@@ -0,0 +1,138 @@
+//-- ./src/demo.rs
+
+// #![allow(unused)] // For development only
+
+//! # Demo Mode
+//!
+//! Run the service with no infrastructure, for talks and evaluation:
+//!
+//! ```bash
+//! cargo run --features demo -- --demo
+//! ```
+//!
+//! Demo mode:
+//! - Starts an embedded, ephemeral Postgres server that is deleted on exit
+//! - Runs the migrations and seeds an admin and a user account
+//! - Delivers email to the console, including the demo account credentials
+//! - Serves plain text (no TLS) on localhost
+//! ---
+
+use postgresql_embedded::{PostgreSQL, Settings};
+use secrecy::SecretString;
+use sqlx::{Pool, Postgres};
+
+use crate::configuration::Configuration;
+use crate::email::{ConsoleEmailClient, EmailClient, EmailMessage};
+use crate::prelude::*;
+use crate::{database, domain, startup, telemetry};
+
+/// Demo database name
+const DEMO_DATABASE_NAME: &str = "authentication_demo";
+
+/// Demo server port
+const DEMO_PORT: u16 = 8081;
+
+/// Demo accounts seeded on startup: (email, name, password, role)
+const DEMO_ACCOUNTS: [(&str, &str, &str, domain::UserRole); 2] = [
+    ("admin@demo.example.com", "Demo Admin", "Demo-Admin-Passw0rd", domain::UserRole::Admin),
+    ("user@demo.example.com", "Demo User", "Demo-User-Passw0rd", domain::UserRole::User),
+];
+
+impl From<postgresql_embedded::Error> for AuthenticationError {
+    fn from(error: postgresql_embedded::Error) -> Self {
+        AuthenticationError::Generic(format!("Embedded database error: {error}"))
+    }
+}
+
+/// Start the embedded Postgres server and point the configuration at it
+async fn start_database(
+    config: &mut Configuration,
+) -> Result<PostgreSQL, AuthenticationError> {
+    // Temporary settings delete the data directory when the server stops
+    let mut postgresql = PostgreSQL::new(Settings {
+        temporary: true,
+        ..Default::default()
+    });
+    postgresql.setup().await?;
+    postgresql.start().await?;
+    postgresql.create_database(DEMO_DATABASE_NAME).await?;
+
+    let settings = postgresql.settings();
+    config.database.host = settings.host.clone();
+    config.database.port = settings.port;
+    config.database.username = settings.username.clone();
+    config.database.password = SecretString::from(settings.password.clone());
+    config.database.database_name = DEMO_DATABASE_NAME.to_string();
+    config.database.require_ssl = false;
+
+    tracing::info!("Embedded demo database started on port {}", settings.port);
+
+    Ok(postgresql)
+}
+
+/// Seed the demo accounts, emailing their credentials to the console
+async fn seed_accounts(
+    database: &Pool<Postgres>,
+    email_client: &impl EmailClient,
+    address: &str,
+) -> Result<(), AuthenticationError> {
+    for (email, name, password, role) in DEMO_ACCOUNTS {
+        let user = database::Users {
+            id: uuid::Uuid::now_v7(),
+            email: domain::EmailAddress::parse(email)?,
+            name: domain::UserName::parse(name)?,
+            password_hash: domain::PasswordHash::parse(SecretString::from(password))?,
+            role,
+            is_active: true,
+            is_verified: true,
+            created_on: chrono::Utc::now(),
+            locale: domain::Locale::default(),
+        };
+        // Upsert, so seeding an existing demo database resets the accounts
+        let user = user.upsert_by_email(database).await?;
+
+        let message = EmailMessage {
+            to: user.email.clone(),
+            subject: "Your demo account".to_string(),
+            body_text: format!(
+                "Log in to the demo server at {address} with\n\nEmail: {email}\nPassword: {password}\nRole: {}",
+                user.role.to_str()
+            ),
+        };
+        email_client.send(&message).await?;
+    }
+
+    Ok(())
+}
+
+/// Run the server in demo mode until it is stopped
+pub async fn run() -> Result<(), AuthenticationError> {
+    // Start from the normal configuration files, then relax it for the demo
+    let mut config = Configuration::parse()?;
+    config.application.ip_address = "127.0.0.1".to_string();
+    config.application.port = DEMO_PORT;
+    config.application.use_tls = false;
+    config.application.hot_reload = false;
+
+    let _telemetry = telemetry::init(config.application.log_level, &config.telemetry)?;
+
+    let mut postgresql = start_database(&mut config).await?;
+    let database = database::init_pool(&config.database).await?;
+
+    let address = config.application.get_address();
+    seed_accounts(&database, &ConsoleEmailClient, &address).await?;
+
+    let tonic_server = startup::TonicServer::build(config, database).await?;
+    tracing::info!("Demo server ready at http://{address}");
+
+    // Stop on Ctrl+C so the embedded database is cleaned up
+    let result = tokio::select! {
+        result = tonic_server.run() => result,
+        _ = tokio::signal::ctrl_c() => Ok(()),
+    };
+
+    postgresql.stop().await?;
+    tracing::info!("Embedded demo database stopped");
+
+    result
+}
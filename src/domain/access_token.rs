@@ -13,7 +13,8 @@ use jsonwebtoken::{encode, EncodingKey, Header};
 use secrecy::{ExposeSecret, SecretString};
 use uuid::Uuid;
 
-use crate::{database, domain::jwt_token::TokenType, prelude::*};
+use crate::utils::{Clock, SystemClock};
+use crate::{database, domain::jwt_token::{TokenType, DEFAULT_AUDIENCE}, prelude::*};
 
 use super::TokenClaim;
 
@@ -55,10 +56,68 @@ impl AccessToken {
         issuer: &SecretString ,
         duration: &time::Duration,
         user: &database::Users,
+    ) -> Result<Self, AuthenticationError> {
+        Self::new_scoped(
+            secret,
+            issuer,
+            duration,
+            user,
+            None,
+            &[DEFAULT_AUDIENCE.to_string()],
+        )
+    }
+
+    /// # New Organization Access Token
+    ///
+    /// Create a new Access Token scoped to an organization (tenant) and
+    /// audiences. Passing `None` for the organization and the default audience
+    /// is the same as `AccessToken::new`.
+    ///
+    /// ## Parameters
+    ///
+    /// - `organization_id<Option<&Uuid>>` - The organization the token is scoped to
+    /// - `audiences<&[String]>` - The `aud` claim, from `application.token_audiences`
+    ///
+    #[tracing::instrument(name = "Generate a new scoped Access Token for: ", skip(secret))]
+    pub fn new_scoped(
+        secret: &SecretString,
+        issuer: &SecretString,
+        duration: &time::Duration,
+        user: &database::Users,
+        organization_id: Option<&Uuid>,
+        audiences: &[String],
+    ) -> Result<Self, AuthenticationError> {
+        Self::new_scoped_with_clock(
+            secret,
+            issuer,
+            duration,
+            user,
+            organization_id,
+            audiences,
+            &SystemClock,
+        )
+    }
+
+    /// # New Organization Access Token at the Clock Time
+    ///
+    /// The same as `AccessToken::new_scoped`, issued at the time of `clock`
+    /// instead of the system time
+    pub fn new_scoped_with_clock(
+        secret: &SecretString,
+        issuer: &SecretString,
+        duration: &time::Duration,
+        user: &database::Users,
+        organization_id: Option<&Uuid>,
+        audiences: &[String],
+        clock: &dyn Clock,
     ) -> Result<Self, AuthenticationError> {
         // Build the Access Token Claim
-        let token_claim =
-            TokenClaim::new(issuer, duration, user, &TokenType::Access);
+        let mut token_claim =
+            TokenClaim::new_with_clock(issuer, duration, user, &TokenType::Access, clock)
+                .with_audiences(audiences);
+        if let Some(organization_id) = organization_id {
+            token_claim = token_claim.with_organization(organization_id);
+        }
 
         // Encode the Token Claim into a URL-safe hash encryption
         let token = encode(
@@ -70,6 +129,71 @@ impl AccessToken {
         Ok(Self(token))
     }
 
+    /// # New API Client Access Token
+    ///
+    /// Create a new Access Token for an API client in the client credentials
+    /// grant, carrying the granted scopes instead of a user role.
+    ///
+    /// ## Parameters
+    ///
+    /// - `client<&database::Clients>` - The OAuth client the token is issued to
+    /// - `scopes<&[String]>` - The scopes granted, a subset of the client's scopes
+    /// - `audiences<&[String]>` - The `aud` claim, from `application.token_audiences`
+    ///
+    #[tracing::instrument(name = "Generate a new API client Access Token for: ", skip(secret))]
+    pub fn new_for_client(
+        secret: &SecretString,
+        issuer: &SecretString,
+        duration: &time::Duration,
+        client: &database::Clients,
+        scopes: &[String],
+        audiences: &[String],
+    ) -> Result<Self, AuthenticationError> {
+        let token_claim = TokenClaim::new_for_client(issuer, duration, &client.id, scopes)
+            .with_audiences(audiences);
+
+        let token = encode(
+            &Header::default(),
+            &token_claim,
+            &EncodingKey::from_secret(secret.expose_secret().as_bytes()),
+        )?;
+
+        Ok(Self(token))
+    }
+
+    /// # New Impersonation Access Token
+    ///
+    /// Create a new Access Token for an admin impersonating a user. The token
+    /// is the user's, marked with the admin in its `act` claim.
+    ///
+    /// ## Parameters
+    ///
+    /// - `user<&database::Users>` - The user being impersonated
+    /// - `impersonator_id<&Uuid>` - The admin impersonating the user
+    /// - `audiences<&[String]>` - The `aud` claim, from `application.token_audiences`
+    ///
+    #[tracing::instrument(name = "Generate a new impersonation Access Token for: ", skip(secret))]
+    pub fn new_impersonation(
+        secret: &SecretString,
+        issuer: &SecretString,
+        duration: &time::Duration,
+        user: &database::Users,
+        impersonator_id: &Uuid,
+        audiences: &[String],
+    ) -> Result<Self, AuthenticationError> {
+        let token_claim = TokenClaim::new(issuer, duration, user, &TokenType::Access)
+            .with_audiences(audiences)
+            .with_impersonator(impersonator_id);
+
+        let token = encode(
+            &Header::default(),
+            &token_claim,
+            &EncodingKey::from_secret(secret.expose_secret().as_bytes()),
+        )?;
+
+        Ok(Self(token))
+    }
+
     /// # Parse Access Token
     /// 
     /// Parse the Access Token from the request header, returning a Result with
@@ -155,6 +279,106 @@ mod tests {
         assert_eq!(token_claim.iss, *random_issuer.expose_secret());
         assert_eq!(token_claim.sub, random_user.id.to_string());
         assert_eq!(token_claim.jty, TokenType::Access.to_string());
+        assert_eq!(token_claim.org, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn scoped_access_token_has_organization_claim() -> Result<()> {
+        //-- 1. Setup and Fixtures (Arrange)
+        let random_secret = Alphanumeric.sample_string(&mut rand::rng(), 60);
+        let random_secret = SecretString::from(random_secret);
+        let random_user = database::Users::mock_data()?;
+        let random_issuer = SecretString::from(CompanyName().fake::<String>());
+        let organization_id = Uuid::now_v7();
+
+        let access_token = AccessToken::new_scoped(
+            &random_secret,
+            &random_issuer,
+            &std::time::Duration::from_secs(600),
+            &random_user,
+            Some(&organization_id),
+            &["web".to_string()],
+        )?;
+
+        //-- 2. Execute Test (Act)
+        let token_claim = TokenClaim::parse_with_audiences(
+            access_token.as_ref(),
+            &random_secret,
+            &random_issuer,
+            &["web"],
+        )?;
+
+        //-- 3. Test Assertions
+        assert_eq!(token_claim.organization_id()?, Some(organization_id));
+        assert_eq!(token_claim.aud, vec!["web".to_string()]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn client_access_token_has_scopes_and_no_user_role() -> Result<()> {
+        //-- 1. Setup and Fixtures (Arrange)
+        let random_secret = Alphanumeric.sample_string(&mut rand::rng(), 60);
+        let random_secret = SecretString::from(random_secret);
+        let random_issuer = SecretString::from(CompanyName().fake::<String>());
+        let (client, _) = database::Clients::mock_data();
+
+        let access_token = AccessToken::new_for_client(
+            &random_secret,
+            &random_issuer,
+            &std::time::Duration::from_secs(300),
+            &client,
+            &["users:read".to_string(), "events:read".to_string()],
+            &[DEFAULT_AUDIENCE.to_string()],
+        )?;
+
+        //-- 2. Execute Test (Act)
+        let token_claim = TokenClaim::parse(
+            access_token.as_ref(),
+            &random_secret,
+            &random_issuer,
+        )?;
+
+        //-- 3. Test Assertions
+        assert_eq!(token_claim.sub, client.id.to_string());
+        assert_eq!(token_claim.scopes(), vec!["users:read", "events:read"]);
+        assert_eq!(token_claim.jur, crate::domain::CLIENT_ROLE);
+        assert!(token_claim.perms.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn impersonation_access_token_names_the_impersonator() -> Result<()> {
+        //-- 1. Setup and Fixtures (Arrange)
+        let random_secret = Alphanumeric.sample_string(&mut rand::rng(), 60);
+        let random_secret = SecretString::from(random_secret);
+        let random_issuer = SecretString::from(CompanyName().fake::<String>());
+        let user = database::Users::mock_data()?;
+        let admin_id = Uuid::now_v7();
+
+        let access_token = AccessToken::new_impersonation(
+            &random_secret,
+            &random_issuer,
+            &std::time::Duration::from_secs(300),
+            &user,
+            &admin_id,
+            &[DEFAULT_AUDIENCE.to_string()],
+        )?;
+
+        //-- 2. Execute Test (Act)
+        let token_claim = TokenClaim::parse(
+            access_token.as_ref(),
+            &random_secret,
+            &random_issuer,
+        )?;
+
+        //-- 3. Test Assertions
+        assert_eq!(token_claim.sub, user.id.to_string());
+        assert_eq!(token_claim.jur, user.role.to_string());
+        assert_eq!(token_claim.impersonator_id()?, Some(admin_id));
 
         Ok(())
     }
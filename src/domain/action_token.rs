@@ -0,0 +1,251 @@
+//-- ./src/domain/action_token.rs
+
+// #![allow(unused)] // For beginning only.
+
+//! One-time action token
+//!
+//! Short-lived tokens emailed to a user to confirm an action, such as an email
+//! change, deleting their account, logging in with a magic link or resetting
+//! their password. Each token is a random string signed with the token secret
+//! for its purpose, so a token issued for one purpose can't be used for another
+//! and forged tokens are rejected without a database lookup.
+//!
+//! Only the SHA-256 hash of a token is stored, with its expiry. A token is used
+//! by consuming its row, see `database::ActionTokens::consume`. Password reset
+//! tokens are kept in `database::PasswordResets` instead.
+//! ---
+
+use hmac::{Hmac, Mac};
+use rand::distr::{Alphanumeric, SampleString};
+use secrecy::{ExposeSecret, SecretString};
+use sha2::{Digest, Sha256};
+
+use crate::prelude::*;
+
+/// Leading characters identifying the string as an action token
+static ACTION_TOKEN_PREFIX: &str = "act_";
+
+/// Number of random characters in an action token
+const ACTION_TOKEN_RANDOM_LENGTH: usize = 32;
+
+/// Number of hex characters in the signature, a full SHA-256 HMAC
+const ACTION_TOKEN_SIGNATURE_LENGTH: usize = 64;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// What an action token confirms
+#[derive(Debug, Clone, Copy, PartialEq, sqlx::Type, serde::Deserialize)]
+#[sqlx(type_name = "action_purpose", rename_all = "snake_case")]
+pub enum ActionPurpose {
+    /// Confirm one side of an email address change
+    EmailChange,
+    /// Confirm deleting an account
+    AccountDeletion,
+    /// Log in without a password
+    MagicLink,
+    /// Choose a new password, see `database::PasswordResets`
+    PasswordReset,
+}
+
+impl ActionPurpose {
+    /// Convert ActionPurpose to a string reference
+    pub fn to_str(&self) -> &str {
+        match self {
+            ActionPurpose::EmailChange => "email_change",
+            ActionPurpose::AccountDeletion => "account_deletion",
+            ActionPurpose::MagicLink => "magic_link",
+            ActionPurpose::PasswordReset => "password_reset",
+        }
+    }
+}
+
+impl std::fmt::Display for ActionPurpose {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+/// Signed one-time token confirming an action
+#[derive(Debug, Clone)]
+pub struct ActionToken {
+    token: SecretString,
+    purpose: ActionPurpose,
+}
+
+impl ActionToken {
+    /// # Generate Action Token
+    ///
+    /// Generate a new random action token for `purpose`, signed with `secret`
+    pub fn generate(purpose: ActionPurpose, secret: &SecretString) -> Self {
+        let random =
+            Alphanumeric.sample_string(&mut rand::rng(), ACTION_TOKEN_RANDOM_LENGTH);
+        let signature = format!(
+            "{:x}",
+            Self::mac(purpose, secret, &random).finalize().into_bytes()
+        );
+
+        Self {
+            token: SecretString::from(format!(
+                "{ACTION_TOKEN_PREFIX}{random}.{signature}"
+            )),
+            purpose,
+        }
+    }
+
+    /// # Parse Action Token
+    ///
+    /// Parse an action token string, checking it has the expected shape and
+    /// was signed with `secret` for `purpose`
+    pub fn parse(
+        token: &str,
+        purpose: ActionPurpose,
+        secret: &SecretString,
+    ) -> Result<Self, AuthenticationError> {
+        let invalid =
+            || AuthenticationError::InvalidToken("Invalid action token".to_string());
+
+        let token = token.trim();
+        let (random, signature) = token
+            .strip_prefix(ACTION_TOKEN_PREFIX)
+            .and_then(|unprefixed| unprefixed.split_once('.'))
+            .ok_or_else(invalid)?;
+
+        if random.len() != ACTION_TOKEN_RANDOM_LENGTH
+            || !random.chars().all(|c| c.is_ascii_alphanumeric())
+            || signature.len() != ACTION_TOKEN_SIGNATURE_LENGTH
+            || !signature.chars().all(|c| c.is_ascii_hexdigit())
+        {
+            return Err(invalid());
+        }
+
+        let signature = (0..signature.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&signature[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| invalid())?;
+
+        // Compare in constant time
+        Self::mac(purpose, secret, random)
+            .verify_slice(&signature)
+            .map_err(|_| invalid())?;
+
+        Ok(Self {
+            token: SecretString::from(token.to_string()),
+            purpose,
+        })
+    }
+
+    /// The purpose the token was signed for
+    pub fn purpose(&self) -> ActionPurpose {
+        self.purpose
+    }
+
+    /// The SHA-256 hash of the token, hex encoded, as stored in the database
+    pub fn hash(&self) -> String {
+        format!(
+            "{:x}",
+            Sha256::digest(self.token.expose_secret().as_bytes())
+        )
+    }
+
+    /// The full token, only sent in the confirmation email
+    pub fn expose(&self) -> &str {
+        self.token.expose_secret()
+    }
+
+    /// HMAC of the random part, keyed by the secret and bound to the purpose
+    fn mac(
+        purpose: ActionPurpose,
+        secret: &SecretString,
+        random: &str,
+    ) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(secret.expose_secret().as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(format!("{purpose}.{random}").as_bytes());
+        mac
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Bring module into test scope
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    fn secret() -> SecretString {
+        SecretString::from("action-token-secret")
+    }
+
+    #[test]
+    fn generated_token_parses_and_hashes_consistently() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let token = ActionToken::generate(ActionPurpose::MagicLink, &secret());
+
+        //-- Execute Function (Act)
+        let parsed =
+            ActionToken::parse(token.expose(), ActionPurpose::MagicLink, &secret())?;
+
+        //-- Checks (Assertions)
+        assert_eq!(parsed.hash(), token.hash());
+        assert_eq!(parsed.purpose(), ActionPurpose::MagicLink);
+        assert_eq!(token.hash().len(), 64);
+        assert_ne!(
+            token.hash(),
+            ActionToken::generate(ActionPurpose::MagicLink, &secret()).hash()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn token_is_bound_to_its_purpose_and_secret() {
+        //-- Setup and Fixtures (Arrange)
+        let token = ActionToken::generate(ActionPurpose::EmailChange, &secret());
+
+        //-- Execute Function (Act)
+        let other_purpose = ActionToken::parse(
+            token.expose(),
+            ActionPurpose::AccountDeletion,
+            &secret(),
+        );
+        let other_secret = ActionToken::parse(
+            token.expose(),
+            ActionPurpose::EmailChange,
+            &SecretString::from("another-secret"),
+        );
+
+        //-- Checks (Assertions)
+        assert!(other_purpose.is_err());
+        assert!(other_secret.is_err());
+    }
+
+    #[test]
+    fn malformed_tokens_are_rejected() {
+        let purpose = ActionPurpose::MagicLink;
+        let signature = "a".repeat(ACTION_TOKEN_SIGNATURE_LENGTH);
+
+        assert!(ActionToken::parse("", purpose, &secret()).is_err());
+        assert!(ActionToken::parse("act_short.sig", purpose, &secret()).is_err());
+        assert!(ActionToken::parse(
+            &format!("emc_{}.{signature}", "a".repeat(32)),
+            purpose,
+            &secret()
+        )
+        .is_err());
+        assert!(ActionToken::parse(
+            &format!("act_{}.{}", "a".repeat(32), "z".repeat(64)),
+            purpose,
+            &secret()
+        )
+        .is_err());
+        assert!(ActionToken::parse(
+            &format!("act_{}.{signature}", "a".repeat(32)),
+            purpose,
+            &secret()
+        )
+        .is_err());
+    }
+}
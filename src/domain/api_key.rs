@@ -0,0 +1,147 @@
+//-- ./src/domain/api_key.rs
+
+// #![allow(unused)] // For beginning only.
+
+//! API key used to authenticate machine to machine requests
+//!
+//! API keys are random strings shown to the caller once when created. Only
+//! their SHA-256 hash is stored, which is enough to look the key up since the
+//! key itself has high entropy. Keys are sent in the `x-api-key` metadata.
+//! ---
+
+use rand::distr::{Alphanumeric, SampleString};
+use secrecy::{ExposeSecret, SecretString};
+use sha2::{Digest, Sha256};
+
+use crate::prelude::*;
+
+/// Metadata key the API key is sent in
+pub static API_KEY_HEADER: &str = "x-api-key";
+
+/// Leading characters identifying the string as one of our API keys
+static API_KEY_PREFIX: &str = "ams_";
+
+/// Number of random characters in an API key
+const API_KEY_RANDOM_LENGTH: usize = 40;
+
+/// Number of random characters kept in the displayable key prefix
+const DISPLAY_PREFIX_LENGTH: usize = 8;
+
+/// API key for authenticating service accounts
+#[derive(Debug, Clone)]
+pub struct ApiKey(SecretString);
+
+impl ApiKey {
+    /// # Generate API Key
+    ///
+    /// Generate a new random API key
+    pub fn generate() -> Self {
+        let random = Alphanumeric.sample_string(&mut rand::rng(), API_KEY_RANDOM_LENGTH);
+        Self(SecretString::from(format!("{API_KEY_PREFIX}{random}")))
+    }
+
+    /// # Parse API Key
+    ///
+    /// Parse an API key string, checking it has the expected shape
+    pub fn parse(key: &str) -> Result<Self, AuthenticationError> {
+        let random = key.strip_prefix(API_KEY_PREFIX).ok_or_else(|| {
+            AuthenticationError::AuthenticationError("Invalid API key".to_string())
+        })?;
+
+        if random.len() != API_KEY_RANDOM_LENGTH
+            || !random.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            return Err(AuthenticationError::AuthenticationError(
+                "Invalid API key".to_string(),
+            ));
+        }
+
+        Ok(Self(SecretString::from(key.to_string())))
+    }
+
+    /// # API Key From Header
+    ///
+    /// Get the API key from the request metadata, returning `None` when the
+    /// request does not present one
+    pub fn from_header(
+        request_header: &tonic::metadata::MetadataMap,
+    ) -> Result<Option<Self>, AuthenticationError> {
+        let Some(value) = request_header.get(API_KEY_HEADER) else {
+            return Ok(None);
+        };
+
+        let key = value.to_str().map_err(|_| {
+            AuthenticationError::AuthenticationError(
+                "API key header is not a valid string".to_string(),
+            )
+        })?;
+
+        Self::parse(key.trim()).map(Some)
+    }
+
+    /// The SHA-256 hash of the key, hex encoded, as stored in the database
+    pub fn hash(&self) -> String {
+        format!("{:x}", Sha256::digest(self.0.expose_secret().as_bytes()))
+    }
+
+    /// The start of the key, safe to display so users can tell keys apart
+    pub fn display_prefix(&self) -> String {
+        self.0
+            .expose_secret()
+            .chars()
+            .take(API_KEY_PREFIX.len() + DISPLAY_PREFIX_LENGTH)
+            .collect()
+    }
+
+    /// The full key, only shown to the caller when it is created
+    pub fn expose(&self) -> &str {
+        self.0.expose_secret()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tonic::metadata::MetadataMap;
+
+    // Bring module into test scope
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn generated_key_parses_and_hashes_consistently() -> Result<()> {
+        let api_key = ApiKey::generate();
+        let parsed = ApiKey::parse(api_key.expose())?;
+
+        assert_eq!(parsed.hash(), api_key.hash());
+        assert_eq!(api_key.hash().len(), 64);
+        assert_ne!(api_key.hash(), ApiKey::generate().hash());
+        assert!(api_key.expose().starts_with(&api_key.display_prefix()));
+        assert_eq!(api_key.display_prefix().len(), 12);
+
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_keys_are_rejected() {
+        assert!(ApiKey::parse("").is_err());
+        assert!(ApiKey::parse("ams_short").is_err());
+        assert!(ApiKey::parse(&format!("key_{}", "a".repeat(40))).is_err());
+        assert!(ApiKey::parse(&format!("ams_{}!", "a".repeat(39))).is_err());
+    }
+
+    #[test]
+    fn header_is_optional() -> Result<()> {
+        let mut metadata = MetadataMap::new();
+        assert!(ApiKey::from_header(&metadata)?.is_none());
+
+        let api_key = ApiKey::generate();
+        metadata.insert(API_KEY_HEADER, api_key.expose().parse()?);
+        let parsed = ApiKey::from_header(&metadata)?.ok_or("missing api key")?;
+        assert_eq!(parsed.hash(), api_key.hash());
+
+        Ok(())
+    }
+}
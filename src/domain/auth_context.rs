@@ -0,0 +1,117 @@
+//-- ./src/domain/auth_context.rs
+
+// #![allow(unused)] // For beginning only.
+
+//! The caller of a request, from its validated access token
+//!
+//! The authorisation layer validates the access token once and adds an
+//! `AuthContext` to the request extensions, so handlers read the caller
+//! without parsing the token or metadata again. The full `TokenClaim` is also
+//! added, for handlers that check permissions, organizations or impersonation.
+//! ---
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::prelude::*;
+
+use super::{TokenClaim, UserRole};
+
+/// The authenticated caller of a request
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthContext {
+    /// The user the access token was issued to
+    pub user_id: Uuid,
+
+    /// The user role in the access token
+    pub role: UserRole,
+
+    /// The access token id, e.g. to deny it or find its session
+    pub jti: String,
+
+    /// When the access token expires
+    pub exp: DateTime<Utc>,
+}
+
+impl AuthContext {
+    /// # Auth Context From a Token Claim
+    ///
+    /// The caller of a validated access token claim. Fails when the subject is
+    /// not a user id or the role is unknown.
+    pub fn from_claim(token_claim: &TokenClaim) -> Result<Self, AuthenticationError> {
+        let failed = || AuthenticationError::AuthenticationError("Authentication Failed!".to_string());
+
+        let user_id = Uuid::parse_str(&token_claim.sub).map_err(|_| {
+            tracing::error!("Unable to parse user id to UUID!");
+            failed()
+        })?;
+        let role = UserRole::from_str(&token_claim.jur).map_err(|_| {
+            tracing::error!("Access Token user role is invalid!");
+            failed()
+        })?;
+        let exp = DateTime::from_timestamp(token_claim.exp as i64, 0).ok_or_else(failed)?;
+
+        Ok(Self {
+            user_id,
+            role,
+            jti: token_claim.jti.clone(),
+            exp,
+        })
+    }
+
+    /// # Auth Context of a Request
+    ///
+    /// The caller added to the request extensions by the authorisation
+    /// layer. Fails when the request was not made with an access token,
+    /// e.g. it used an API key.
+    pub fn from_extensions(
+        extensions: &tonic::Extensions,
+    ) -> Result<&Self, AuthenticationError> {
+        extensions.get::<Self>().ok_or_else(|| {
+            tracing::error!("Request was not made with an access token");
+            AuthenticationError::AuthenticationError("Authentication Failed!".to_string())
+        })
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn auth_context_from_token_claim() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user_id = Uuid::now_v7();
+        let token_claim = TokenClaim {
+            sub: user_id.to_string(),
+            jur: UserRole::Admin.to_string(),
+            jti: "jti".to_string(),
+            exp: 1_900_000_000,
+            ..TokenClaim::default()
+        };
+        let mut extensions = tonic::Extensions::new();
+
+        //-- Execute Function (Act)
+        let auth_context = AuthContext::from_claim(&token_claim)?;
+        let missing = AuthContext::from_extensions(&extensions).is_err();
+        extensions.insert(auth_context.clone());
+
+        //-- Checks (Assertions)
+        assert_eq!(auth_context.user_id, user_id);
+        assert_eq!(auth_context.role, UserRole::Admin);
+        assert_eq!(auth_context.jti, "jti");
+        assert_eq!(auth_context.exp.timestamp(), 1_900_000_000);
+        assert!(missing);
+        assert_eq!(AuthContext::from_extensions(&extensions)?, &auth_context);
+        assert!(AuthContext::from_claim(&TokenClaim::default()).is_err());
+
+        Ok(())
+    }
+}
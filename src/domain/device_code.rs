@@ -0,0 +1,184 @@
+//-- ./src/domain/device_code.rs
+
+// #![allow(unused)] // For beginning only.
+
+//! Device authorization codes
+//!
+//! The codes of the OAuth device authorization grant (RFC 8628), for clients
+//! such as CLIs and IoT devices that can't show a login page:
+//!
+//! - `DeviceCode`: a long random secret kept by the device, which polls for
+//!   tokens with it. Only its SHA-256 hash is stored.
+//! - `UserCode`: a short code shown by the device and typed in by the user on
+//!   another device to approve it. It uses consonants only, so it is easy to
+//!   read out and can't spell words.
+//! ---
+
+use rand::distr::{Alphanumeric, SampleString};
+use rand::seq::IndexedRandom;
+use secrecy::{ExposeSecret, SecretString};
+use sha2::{Digest, Sha256};
+
+use crate::prelude::*;
+
+/// Leading characters identifying the string as a device code
+static DEVICE_CODE_PREFIX: &str = "dvc_";
+
+/// Number of random characters in a device code
+const DEVICE_CODE_RANDOM_LENGTH: usize = 40;
+
+/// Characters a user code is made of
+const USER_CODE_ALPHABET: &[u8] = b"BCDFGHJKLMNPQRSTVWXZ";
+
+/// Number of characters in a user code, shown in two halves
+const USER_CODE_LENGTH: usize = 8;
+
+/// Secret code the device polls for its tokens with
+#[derive(Debug, Clone)]
+pub struct DeviceCode(SecretString);
+
+impl DeviceCode {
+    /// Generate a new random device code
+    pub fn generate() -> Self {
+        let random =
+            Alphanumeric.sample_string(&mut rand::rng(), DEVICE_CODE_RANDOM_LENGTH);
+
+        Self(SecretString::from(format!("{DEVICE_CODE_PREFIX}{random}")))
+    }
+
+    /// Parse a device code string, checking it has the expected shape
+    pub fn parse(device_code: &str) -> Result<Self, AuthenticationError> {
+        let device_code = device_code.trim();
+        let is_valid = device_code
+            .strip_prefix(DEVICE_CODE_PREFIX)
+            .is_some_and(|random| {
+                random.len() == DEVICE_CODE_RANDOM_LENGTH
+                    && random.chars().all(|c| c.is_ascii_alphanumeric())
+            });
+
+        if !is_valid {
+            return Err(AuthenticationError::InvalidToken(
+                "Invalid device code".to_string(),
+            ));
+        }
+
+        Ok(Self(SecretString::from(device_code.to_string())))
+    }
+
+    /// The SHA-256 hash of the code, hex encoded, as stored in the database
+    pub fn hash(&self) -> String {
+        format!("{:x}", Sha256::digest(self.0.expose_secret().as_bytes()))
+    }
+
+    /// The full code, only returned to the device
+    pub fn expose(&self) -> &str {
+        self.0.expose_secret()
+    }
+}
+
+/// Short code the user enters to approve a device, e.g. `BCDF-GHJK`
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserCode(String);
+
+impl UserCode {
+    /// Generate a new random user code
+    pub fn generate() -> Self {
+        let mut rng = rand::rng();
+        let code: String = (0..USER_CODE_LENGTH)
+            .map(|_| *USER_CODE_ALPHABET.choose(&mut rng).unwrap() as char)
+            .collect();
+
+        Self(Self::format(&code))
+    }
+
+    /// Parse a user code as typed by the user, ignoring case, spaces and dashes
+    pub fn parse(user_code: &str) -> Result<Self, AuthenticationError> {
+        let code: String = user_code
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '-')
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+
+        if code.len() != USER_CODE_LENGTH
+            || !code.bytes().all(|c| USER_CODE_ALPHABET.contains(&c))
+        {
+            return Err(AuthenticationError::InvalidToken(
+                "Invalid user code".to_string(),
+            ));
+        }
+
+        Ok(Self(Self::format(&code)))
+    }
+
+    /// Split the code into two halves, so it is easier to read
+    fn format(code: &str) -> String {
+        let (first, second) = code.split_at(USER_CODE_LENGTH / 2);
+        format!("{first}-{second}")
+    }
+}
+
+impl AsRef<str> for UserCode {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Bring module into test scope
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn generated_device_code_parses_and_hashes_consistently() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let device_code = DeviceCode::generate();
+
+        //-- Execute Function (Act)
+        let parsed = DeviceCode::parse(device_code.expose())?;
+
+        //-- Checks (Assertions)
+        assert_eq!(parsed.hash(), device_code.hash());
+        assert_eq!(device_code.hash().len(), 64);
+        assert_ne!(device_code.hash(), DeviceCode::generate().hash());
+
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_device_codes_are_rejected() {
+        assert!(DeviceCode::parse("").is_err());
+        assert!(DeviceCode::parse("dvc_short").is_err());
+        assert!(DeviceCode::parse(&format!("act_{}", "a".repeat(40))).is_err());
+        assert!(DeviceCode::parse(&format!("dvc_{}", "!".repeat(40))).is_err());
+    }
+
+    #[test]
+    fn user_codes_parse_as_typed() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user_code = UserCode::generate();
+
+        //-- Execute Function (Act)
+        let typed = user_code.as_ref().to_lowercase().replace('-', " ");
+        let parsed = UserCode::parse(&typed)?;
+
+        //-- Checks (Assertions)
+        assert_eq!(parsed, user_code);
+        assert_eq!(user_code.as_ref().len(), USER_CODE_LENGTH + 1);
+        assert_eq!(UserCode::parse("bcdf-ghjk")?.as_ref(), "BCDF-GHJK");
+
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_user_codes_are_rejected() {
+        assert!(UserCode::parse("").is_err());
+        assert!(UserCode::parse("BCDF-GHJ").is_err());
+        // Vowels and digits are not in the alphabet
+        assert!(UserCode::parse("ABCD-EFGH").is_err());
+        assert!(UserCode::parse("BCDF-1234").is_err());
+    }
+}
@@ -86,4 +86,36 @@ mod tests {
         let email: String = SafeEmail().fake();
         assert!(EmailAddress::parse(email).is_ok());
     }
+
+    proptest::proptest! {
+        #[test]
+        fn any_string_parses_without_panicking(email in ".*") {
+            if let Ok(parsed) = EmailAddress::parse(email.clone()) {
+                proptest::prop_assert_eq!(parsed.as_ref(), email.as_str());
+                proptest::prop_assert!(email.contains('@'));
+            }
+        }
+
+        #[test]
+        fn email_without_at_symbol_is_rejected(email in "[^@]*") {
+            proptest::prop_assert!(EmailAddress::parse(email).is_err());
+        }
+
+        #[test]
+        fn whitespace_only_email_is_rejected(email in "\\s*") {
+            proptest::prop_assert!(EmailAddress::parse(email).is_err());
+        }
+
+        #[test]
+        fn overlong_local_part_is_rejected(local in "[a-z]{65,500}") {
+            proptest::prop_assert!(EmailAddress::parse(format!("{local}@example.com")).is_err());
+        }
+
+        #[test]
+        fn simple_email_is_accepted(
+            email in "[a-z][a-z0-9_]{0,30}[a-z0-9]@[a-z]{1,20}\\.(com|org|net|au)"
+        ) {
+            proptest::prop_assert!(EmailAddress::parse(email).is_ok());
+        }
+    }
 }
@@ -0,0 +1,151 @@
+//-- ./src/domain/email_domain.rs
+
+// #![allow(unused)] // For beginning only.
+
+//! Email domain parsing
+//!
+//! Parse a string into an email domain (e.g. `example.com`), used to allow or
+//! block registrations by the domain of their email address. Domains are kept
+//! in lower case, and a rule for a domain also covers its subdomains.
+//! ---
+
+use crate::prelude::*;
+
+/// Longest domain name accepted, see RFC 1035
+const MAX_DOMAIN_LENGTH: usize = 253;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+pub struct EmailDomain(String);
+
+impl EmailDomain {
+    /// Returns a Result of EmailDomain if the input is two or more `.`
+    /// separated labels of letters, digits and `-`. A leading `@` is dropped,
+    /// so `@example.com` and `example.com` are the same domain.
+    pub fn parse(domain: impl Into<String>) -> Result<EmailDomain, AuthenticationError> {
+        let domain = domain.into().trim().trim_start_matches('@').to_ascii_lowercase();
+
+        let labels: Vec<&str> = domain.split('.').collect();
+        let labels_are_valid = labels.iter().all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+
+        if domain.len() > MAX_DOMAIN_LENGTH || labels.len() < 2 || !labels_are_valid {
+            return Err(AuthenticationError::ValidationError(format!(
+                "email domain is not a domain name: {domain}"
+            )));
+        }
+
+        Ok(Self(domain))
+    }
+
+    /// The domain of an email address
+    pub fn of(email: &super::EmailAddress) -> Result<EmailDomain, AuthenticationError> {
+        let (_, domain) = email.as_ref().rsplit_once('@').ok_or_else(|| {
+            AuthenticationError::ValidationError("email address has no domain".to_string())
+        })?;
+
+        Self::parse(domain)
+    }
+
+    /// Is this the domain, or one of its subdomains
+    pub fn is_within(&self, domain: &str) -> bool {
+        self.0 == domain
+            || self
+                .0
+                .strip_suffix(domain)
+                .is_some_and(|subdomain| subdomain.ends_with('.'))
+    }
+}
+
+impl AsRef<str> for EmailDomain {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for EmailDomain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Why a registration was refused for its email domain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailDomainRejection {
+    /// Only allowed domains can register, and this is not one of them
+    NotAllowed,
+    /// The domain is blocked
+    Blocked,
+    /// The domain gives out disposable addresses
+    Disposable,
+}
+
+impl EmailDomainRejection {
+    /// The machine readable reason code sent to clients
+    pub fn code(&self) -> &'static str {
+        match self {
+            EmailDomainRejection::NotAllowed => "EMAIL_DOMAIN_NOT_ALLOWED",
+            EmailDomainRejection::Blocked => "EMAIL_DOMAIN_BLOCKED",
+            EmailDomainRejection::Disposable => "EMAIL_DOMAIN_DISPOSABLE",
+        }
+    }
+}
+
+impl std::fmt::Display for EmailDomainRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            EmailDomainRejection::NotAllowed => "Email domain is not allowed to register",
+            EmailDomainRejection::Blocked => "Email domain is blocked",
+            EmailDomainRejection::Disposable => "Disposable email addresses can't register",
+        };
+        f.write_str(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Bring module into test scope
+    use super::*;
+    use crate::domain::EmailAddress;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn domains_are_normalised() -> Result<()> {
+        assert_eq!(EmailDomain::parse("example.com")?.as_ref(), "example.com");
+        assert_eq!(EmailDomain::parse(" @Mail.Example.COM ")?.as_ref(), "mail.example.com");
+        assert_eq!(
+            EmailDomain::of(&EmailAddress::parse("jane@Example.com")?)?.as_ref(),
+            "example.com"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_domains_are_rejected() {
+        assert!(EmailDomain::parse("").is_err());
+        assert!(EmailDomain::parse("localhost").is_err());
+        assert!(EmailDomain::parse("example..com").is_err());
+        assert!(EmailDomain::parse("-example.com").is_err());
+        assert!(EmailDomain::parse("exa_mple.com").is_err());
+        assert!(EmailDomain::parse(format!("{}.com", "a".repeat(250))).is_err());
+    }
+
+    #[test]
+    fn subdomains_are_within_their_domain() -> Result<()> {
+        let domain = EmailDomain::parse("mail.example.com")?;
+
+        assert!(domain.is_within("mail.example.com"));
+        assert!(domain.is_within("example.com"));
+        assert!(!domain.is_within("ample.com"));
+        assert!(!domain.is_within("other.example.com"));
+
+        Ok(())
+    }
+}
@@ -25,6 +25,24 @@ use uuid::Uuid;
 
 use crate::database;
 use crate::prelude::*;
+use crate::utils::{Clock, SystemClock};
+
+/// Audience (aud) of tokens issued and accepted when none are configured, see
+/// `application.token_audiences`
+pub const DEFAULT_AUDIENCE: &str = "authentication_service";
+
+/// User role (jur) claim of tokens issued to API clients. It is not a user
+/// role, so client tokens are rejected by the user facing services
+pub const CLIENT_ROLE: &str = "client";
+
+/// Is the string a valid OAuth scope token, printable ASCII without spaces,
+/// quotes or backslashes (RFC 6749 section 3.3)
+pub fn is_valid_scope(scope: &str) -> bool {
+    !scope.is_empty()
+        && scope
+            .chars()
+            .all(|c| c.is_ascii_graphic() && c != '"' && c != '\\')
+}
 
 /// Token Types
 //TODO: Impellent own Display trait
@@ -71,8 +89,14 @@ pub struct TokenClaim {
     /// # JWT Subject
     /// Whom the token refers or issued to
     pub sub: String,
-    // /// JWT Audience, or the application that will be using the token
-    // aud: String,
+    /// # JWT Audience
+    /// The applications that will be using the token, a single string or a list
+    #[serde(
+        default,
+        deserialize_with = "deserialize_audience",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub aud: Vec<String>,
     /// # JWT Expiration (as UTC timestamp). 
     /// Validate_exp defaults to true in validation
     pub exp: u64,
@@ -93,6 +117,50 @@ pub struct TokenClaim {
     /// Used to identify the JWT user role for authorisation
     // TODO: Consider removing this, as the user instance is passed in the RPC response
     pub jur: String,
+    /// JWT Organization (Custom)
+    /// The organization (tenant) the token is scoped to, omitted when not scoped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub org: Option<String>,
+    /// JWT Permissions (Custom)
+    /// What the user role may do, so resource servers can authorise without a lookup
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub perms: Vec<String>,
+    /// JWT Scope (RFC 9068)
+    /// The space separated scopes of an API client's token, omitted for user tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// JWT Actor (RFC 8693)
+    /// The admin acting as the subject, only set on impersonation tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<Actor>,
+}
+
+/// The party acting on behalf of the token subject (RFC 8693 `act` claim)
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Actor {
+    /// Who is acting, the impersonating admin's user id
+    pub sub: String,
+}
+
+/// Deserialize the audience (aud) claim, which RFC 7519 allows to be a single
+/// string or a list of strings
+fn deserialize_audience<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Audience {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(
+        match <Audience as serde::Deserialize>::deserialize(deserializer)? {
+            Audience::One(audience) => vec![audience],
+            Audience::Many(audiences) => audiences,
+        },
+    )
 }
 
 impl TokenClaim {
@@ -112,26 +180,36 @@ impl TokenClaim {
         duration: &time::Duration,
         user: &database::Users,
         token_type: &TokenType,
+    ) -> Self {
+        Self::new_with_clock(issuer, duration, user, token_type, &SystemClock)
+    }
+
+    /// # New Token Claim at the Clock Time
+    ///
+    /// The same as `TokenClaim::new`, issued at the time of `clock` instead of
+    /// the system time
+    pub fn new_with_clock(
+        issuer: &SecretString,
+        duration: &time::Duration,
+        user: &database::Users,
+        token_type: &TokenType,
+        clock: &dyn Clock,
     ) -> Self {
         // Take ownership of the string, since it will be passed back in the Token Claim
         let issuer = issuer.expose_secret().to_string();
 
-        // Get System Time now
-        let now = time::SystemTime::now();
-
-        // Token claim will expire at this System Time
-        let expiration_timestamp = now
-            .checked_add(duration.to_owned())
-            .expect("valid time")
-            .duration_since(time::SystemTime::UNIX_EPOCH)
-            .expect("valid timestamp")
-            .as_secs();
-
-        // Calculate the system time now
-        let system_now_timestamp = now
-            .duration_since(time::SystemTime::UNIX_EPOCH)
-            .expect("valid timestamp")
-            .as_secs();
+        // Get the clock time now
+        let now = clock.now();
+
+        // Token claim will expire at this time
+        let expiration_timestamp: u64 = (now
+            + chrono::Duration::from_std(duration.to_owned()).expect("valid duration"))
+        .timestamp()
+        .try_into()
+        .expect("valid timestamp");
+
+        // Calculate the clock time now
+        let system_now_timestamp: u64 = now.timestamp().try_into().expect("valid timestamp");
         
         // Convert user id UUID to a string for the claim
         let user_id = user.id.to_string();
@@ -145,19 +223,140 @@ impl TokenClaim {
         // Convert the user type to a string
         let user_role = user.role.to_string();
 
+        // What the user role may do
+        let permissions = user
+            .role
+            .permissions()
+            .iter()
+            .map(|permission| permission.to_string())
+            .collect();
+
         Self {
             iss: issuer,
             sub: user_id,
-            // aud,
+            aud: vec![DEFAULT_AUDIENCE.to_string()],
             exp: expiration_timestamp,
             nbf: system_now_timestamp,
             iat: system_now_timestamp,
             jti: token_id,
             jty: token_type,
             jur: user_role,
+            org: None,
+            perms: permissions,
+            scope: None,
+            act: None,
         }
     }
 
+    /// # New API Client Token Claim
+    ///
+    /// A token claim for an API client in the client credentials grant. The
+    /// subject is the client id and the scopes are the ones granted, with no
+    /// user role or permissions.
+    ///
+    /// ## Parameters
+    ///
+    /// - `issuer<&SecretString>` - The issuer of the JWT.
+    /// - `duration<time::Duration>` - How long is the JWT valid for.
+    /// - `client_id<&Uuid>` - The API client the JWT is issued to.
+    /// - `scopes<&[String]>` - The scopes granted to the client.
+    pub fn new_for_client(
+        issuer: &SecretString,
+        duration: &time::Duration,
+        client_id: &Uuid,
+        scopes: &[String],
+    ) -> Self {
+        let now = SystemClock.now();
+        let expiration_timestamp: u64 = (now
+            + chrono::Duration::from_std(duration.to_owned()).expect("valid duration"))
+        .timestamp()
+        .try_into()
+        .expect("valid timestamp");
+        let system_now_timestamp: u64 = now.timestamp().try_into().expect("valid timestamp");
+
+        Self {
+            iss: issuer.expose_secret().to_string(),
+            sub: client_id.to_string(),
+            aud: vec![DEFAULT_AUDIENCE.to_string()],
+            exp: expiration_timestamp,
+            nbf: system_now_timestamp,
+            iat: system_now_timestamp,
+            jti: Uuid::now_v7().to_string(),
+            jty: TokenType::Access.to_string(),
+            jur: CLIENT_ROLE.to_string(),
+            org: None,
+            perms: Vec::new(),
+            scope: (!scopes.is_empty()).then(|| scopes.join(" ")),
+            act: None,
+        }
+    }
+
+    /// # Token Claim Scopes
+    ///
+    /// The scopes of an API client's token, empty for user tokens
+    pub fn scopes(&self) -> Vec<&str> {
+        self.scope
+            .as_deref()
+            .map(|scope| scope.split_whitespace().collect())
+            .unwrap_or_default()
+    }
+
+    /// # Audience Token Claim
+    ///
+    /// Issue the token claim to these audiences instead of the default audience
+    pub fn with_audiences(mut self, audiences: &[String]) -> Self {
+        self.aud = audiences.to_vec();
+        self
+    }
+
+    /// # Token Claim Permission
+    ///
+    /// Check the token claim grants a permission, see `UserRole::permissions`
+    pub fn has_permission(&self, permission: &str) -> bool {
+        self.perms.iter().any(|granted| granted == permission)
+    }
+
+    /// # Organization Token Claim
+    ///
+    /// Scope the token claim to an organization (tenant)
+    pub fn with_organization(mut self, organization_id: &Uuid) -> Self {
+        self.org = Some(organization_id.to_string());
+        self
+    }
+
+    /// # Token Claim Organization
+    ///
+    /// Parse the organization the token claim is scoped to, `None` when it is not scoped
+    pub fn organization_id(&self) -> Result<Option<Uuid>, AuthenticationError> {
+        self.org
+            .as_deref()
+            .map(Uuid::try_parse)
+            .transpose()
+            .map_err(AuthenticationError::from)
+    }
+
+    /// # Impersonation Token Claim
+    ///
+    /// Mark the token claim as issued to an admin impersonating the subject
+    pub fn with_impersonator(mut self, impersonator_id: &Uuid) -> Self {
+        self.act = Some(Actor {
+            sub: impersonator_id.to_string(),
+        });
+        self
+    }
+
+    /// # Token Claim Impersonator
+    ///
+    /// Parse the admin impersonating the subject, `None` when the token is the
+    /// subject's own
+    pub fn impersonator_id(&self) -> Result<Option<Uuid>, AuthenticationError> {
+        self.act
+            .as_ref()
+            .map(|actor| Uuid::try_parse(&actor.sub))
+            .transpose()
+            .map_err(AuthenticationError::from)
+    }
+
     /// # Parse a Token into a Token Claim
     /// 
     /// This function parses (decodes) a token string into a Token Claim. In doing
@@ -168,24 +367,59 @@ impl TokenClaim {
     /// - `token<&str>` - The Token string to be decoded into a Token Claim.
     /// - `secret<SecretString>` - Contains the token encryption secret.
     /// - `issuer<SecretString>` - Who issued the JWT. Used to verify the token.
+    ///
+    /// The token must be issued to the default audience, use
+    /// `TokenClaim::parse_with_audiences` for the configured audiences.
     /// ---
     pub fn parse(
         token: &str,
         secret: &SecretString,
         issuer: &SecretString,
     ) -> Result<Self, AuthenticationError> {
-        // Build token validation requirements. By default, the decoding will 
-        // automatically validate the expiration (exp) claim
+        Self::parse_with_audiences(token, secret, issuer, &[DEFAULT_AUDIENCE])
+    }
+
+    /// # Parse a Token for an Audience into a Token Claim
+    ///
+    /// The same as `TokenClaim::parse`, accepting tokens issued to any of
+    /// `audiences` instead of the default audience
+    pub fn parse_with_audiences<A: ToString>(
+        token: &str,
+        secret: &SecretString,
+        issuer: &SecretString,
+        audiences: &[A],
+    ) -> Result<Self, AuthenticationError> {
+        Self::parse_with_clock(token, secret, issuer, audiences, &SystemClock)
+    }
+
+    /// # Parse a Token into a Token Claim at the Clock Time
+    ///
+    /// The same as `TokenClaim::parse_with_audiences`, checking the expiration
+    /// (exp) and not before (nbf) claims against the time of `clock` instead
+    /// of the system time
+    pub fn parse_with_clock<A: ToString>(
+        token: &str,
+        secret: &SecretString,
+        issuer: &SecretString,
+        audiences: &[A],
+        clock: &dyn Clock,
+    ) -> Result<Self, AuthenticationError> {
+        // Build token validation requirements. The expiration (exp) and not
+        // before (nbf) claims are checked against the clock below
         let mut validation = Validation::default();
 
         // Issuer (iss) of token to validate against
         validation.set_issuer(&[issuer.expose_secret()]);
 
-        // Validate Not before (nbf) claim
-        validation.validate_nbf = true;
+        // Audiences (aud) the token may be issued to, at least one must match
+        validation.set_audience(audiences);
+
+        // Checked against the clock instead of the system time
+        validation.validate_exp = false;
+        validation.validate_nbf = false;
 
         // What is going to be validated against
-        validation.set_required_spec_claims(&["iss", "exp", "nbf"]);
+        validation.set_required_spec_claims(&["iss", "aud", "exp", "nbf"]);
 
         // Decode Access Token into a Token Claim
         let token_claim = decode::<TokenClaim>(
@@ -195,6 +429,22 @@ impl TokenClaim {
         )
         .map(|data| data.claims)?;
 
+        // Validate the expiration (exp) and not before (nbf) claims, with the
+        // same leeway the token library uses
+        let now: u64 = clock.now().timestamp().try_into().unwrap_or_default();
+        if token_claim.exp < now.saturating_sub(validation.leeway) {
+            return Err(jsonwebtoken::errors::Error::from(
+                jsonwebtoken::errors::ErrorKind::ExpiredSignature,
+            )
+            .into());
+        }
+        if token_claim.nbf > now + validation.leeway {
+            return Err(jsonwebtoken::errors::Error::from(
+                jsonwebtoken::errors::ErrorKind::ImmatureSignature,
+            )
+            .into());
+        }
+
         Ok(token_claim)
     }
 
@@ -249,4 +499,182 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn parse_checks_expiry_against_the_clock() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let issuer = SecretString::from(CompanyName().fake::<String>());
+        let secret = SecretString::from(CompanyName().fake::<String>());
+        let user = database::Users::mock_data()?;
+        let clock = crate::utils::MockClock::default();
+        let token_claim = TokenClaim::new_with_clock(
+            &issuer,
+            &std::time::Duration::from_secs(600),
+            &user,
+            &TokenType::Access,
+            &clock,
+        );
+        let token = jsonwebtoken::encode(
+            &Header::default(),
+            &token_claim,
+            &EncodingKey::from_secret(secret.expose_secret().as_bytes()),
+        )?;
+
+        //-- Execute Function (Act)
+        let audiences = [DEFAULT_AUDIENCE];
+        let valid = TokenClaim::parse_with_clock(&token, &secret, &issuer, &audiences, &clock);
+        clock.advance(Duration::minutes(20));
+        let expired = TokenClaim::parse_with_clock(&token, &secret, &issuer, &audiences, &clock);
+        clock.advance(Duration::minutes(-40));
+        let immature = TokenClaim::parse_with_clock(&token, &secret, &issuer, &audiences, &clock);
+
+        //-- Checks (Assertions)
+        assert_eq!(valid?, token_claim);
+        assert!(expired.is_err());
+        assert!(immature.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_checks_the_audience() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let issuer = SecretString::from(CompanyName().fake::<String>());
+        let secret = SecretString::from(CompanyName().fake::<String>());
+        let user = database::Users::mock_data()?;
+        let audiences = vec!["web".to_string(), "mobile".to_string()];
+        let token_claim = TokenClaim::new(
+            &issuer,
+            &std::time::Duration::from_secs(600),
+            &user,
+            &TokenType::Access,
+        )
+        .with_audiences(&audiences);
+        let token = encode(
+            &Header::default(),
+            &token_claim,
+            &EncodingKey::from_secret(secret.expose_secret().as_bytes()),
+        )?;
+
+        //-- Execute Function (Act)
+        let accepted = TokenClaim::parse_with_audiences(&token, &secret, &issuer, &["mobile"]);
+        let rejected = TokenClaim::parse_with_audiences(&token, &secret, &issuer, &["billing"]);
+        let default = TokenClaim::parse(&token, &secret, &issuer);
+
+        //-- Checks (Assertions)
+        assert_eq!(accepted?.aud, audiences);
+        assert!(rejected.is_err());
+        assert!(default.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn permissions_follow_the_user_role() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let issuer = SecretString::from(CompanyName().fake::<String>());
+        let mut admin = database::Users::mock_data()?;
+        admin.role = crate::domain::UserRole::Admin;
+        let mut user = database::Users::mock_data()?;
+        user.role = crate::domain::UserRole::User;
+        let duration = std::time::Duration::from_secs(600);
+
+        //-- Execute Function (Act)
+        let admin_claim = TokenClaim::new(&issuer, &duration, &admin, &TokenType::Access);
+        let user_claim = TokenClaim::new(&issuer, &duration, &user, &TokenType::Access);
+
+        //-- Checks (Assertions)
+        assert!(admin_claim.has_permission(crate::domain::UserRole::ADMIN_ACCESS));
+        assert!(admin_claim.has_permission(crate::domain::UserRole::USERS_ACCESS));
+        assert!(!user_claim.has_permission(crate::domain::UserRole::ADMIN_ACCESS));
+        assert!(user_claim.has_permission(crate::domain::UserRole::SESSIONS_ACCESS));
+        assert_eq!(admin_claim.aud, vec![DEFAULT_AUDIENCE.to_string()]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn scopes_are_oauth_scope_tokens() {
+        assert!(is_valid_scope("users:read"));
+        assert!(is_valid_scope("https://api.example.com/events"));
+        assert!(!is_valid_scope(""));
+        assert!(!is_valid_scope("users read"));
+        assert!(!is_valid_scope("users\"read"));
+    }
+
+    /// A token claim for a random user, with the token signed with `secret`
+    fn signed_token(secret: &SecretString, issuer: &SecretString) -> Result<(String, TokenClaim)> {
+        let user = database::Users::mock_data()?;
+        let token_claim = TokenClaim::new(
+            issuer,
+            &std::time::Duration::from_secs(600),
+            &user,
+            &TokenType::Access,
+        );
+        let token = encode(
+            &Header::default(),
+            &token_claim,
+            &EncodingKey::from_secret(secret.expose_secret().as_bytes()),
+        )?;
+
+        Ok((token, token_claim))
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn arbitrary_string_is_rejected(token in ".*") {
+            let secret = SecretString::from("secret");
+            let issuer = SecretString::from("issuer");
+            proptest::prop_assert!(TokenClaim::parse(&token, &secret, &issuer).is_err());
+        }
+
+        #[test]
+        fn malformed_jwt_is_rejected(
+            token in "[A-Za-z0-9_-]{0,64}\\.[A-Za-z0-9_-]{0,64}\\.[A-Za-z0-9_-]{0,64}"
+        ) {
+            let secret = SecretString::from("secret");
+            let issuer = SecretString::from("issuer");
+            proptest::prop_assert!(TokenClaim::parse(&token, &secret, &issuer).is_err());
+        }
+
+        #[test]
+        fn overlong_token_is_rejected(segment in "[A-Za-z0-9_-]{10000,20000}") {
+            let secret = SecretString::from("secret");
+            let issuer = SecretString::from("issuer");
+            let token = format!("{segment}.{segment}.{segment}");
+            proptest::prop_assert!(TokenClaim::parse(&token, &secret, &issuer).is_err());
+        }
+
+        #[test]
+        fn tampered_subject_is_rejected(subject in "\\PC{1,64}") {
+            let secret = SecretString::from("secret");
+            let issuer = SecretString::from("issuer");
+            let (token, mut token_claim) = signed_token(&secret, &issuer).unwrap();
+            proptest::prop_assume!(subject != token_claim.sub);
+
+            // Swap in another subject, keeping the original signature
+            token_claim.sub = subject;
+            let tampered = encode(
+                &Header::default(),
+                &token_claim,
+                &EncodingKey::from_secret(b"another secret"),
+            )
+            .unwrap();
+            let signature = token.rsplit('.').next().unwrap();
+            let (unsigned, _) = tampered.rsplit_once('.').unwrap();
+            let tampered = format!("{unsigned}.{signature}");
+
+            proptest::prop_assert!(TokenClaim::parse(&tampered, &secret, &issuer).is_err());
+        }
+
+        #[test]
+        fn token_signed_with_another_secret_is_rejected(other_secret in "\\PC{1,64}") {
+            let secret = SecretString::from("secret");
+            let issuer = SecretString::from("issuer");
+            proptest::prop_assume!(other_secret != "secret");
+            let (token, _) = signed_token(&SecretString::from(other_secret), &issuer).unwrap();
+
+            proptest::prop_assert!(TokenClaim::parse(&token, &secret, &issuer).is_err());
+        }
+    }
 }
@@ -0,0 +1,112 @@
+//-- ./src/domain/locale.rs
+
+// #![allow(unused)] // For beginning only.
+
+//! Locale domain parsing
+//!
+//! Parse a string into a locale tag (e.g. `en`, `fr`, `pt-BR`), used to pick
+//! the language of the emails sent to a user.
+//! ---
+
+use crate::prelude::*;
+
+/// The locale used when a user has not chosen one, and the template fallback
+pub const DEFAULT_LOCALE: &str = "en";
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize, derive_more::From)]
+pub struct Locale(String);
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self(DEFAULT_LOCALE.to_string())
+    }
+}
+
+impl Locale {
+    /// Returns a Result of Locale if the input is a language tag, a two or three
+    /// letter language optionally followed by hyphenated subtags, e.g. `pt-BR`.
+    /// Underscores are accepted in place of hyphens.
+    pub fn parse(locale: impl Into<String>) -> Result<Locale, AuthenticationError> {
+        let locale = locale.into().trim().replace('_', "-");
+        let mut subtags = locale.split('-');
+
+        let language = subtags.next().unwrap_or_default();
+        let language_is_valid = (2..=3).contains(&language.len())
+            && language.chars().all(|c| c.is_ascii_alphabetic());
+
+        let subtags_are_valid = subtags.all(|subtag| {
+            (2..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+        });
+
+        if !language_is_valid || !subtags_are_valid {
+            return Err(AuthenticationError::ValidationError(format!(
+                "locale is not a language tag: {locale}"
+            )));
+        }
+
+        Ok(Self(format!(
+            "{}{}",
+            language.to_ascii_lowercase(),
+            &locale[language.len()..]
+        )))
+    }
+
+    /// The locales to try in order, e.g. `pt-BR`, `pt` then `en`
+    pub fn fallbacks(&self) -> Vec<&str> {
+        let mut fallbacks = vec![self.0.as_str()];
+
+        let mut end = self.0.len();
+        while let Some(index) = self.0[..end].rfind('-') {
+            fallbacks.push(&self.0[..index]);
+            end = index;
+        }
+
+        if !fallbacks.contains(&DEFAULT_LOCALE) {
+            fallbacks.push(DEFAULT_LOCALE);
+        }
+
+        fallbacks
+    }
+}
+
+impl AsRef<str> for Locale {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Locale;
+    use claims::assert_err;
+
+    #[test]
+    fn language_tags_are_parsed() {
+        assert_eq!(Locale::parse("en").unwrap().as_ref(), "en");
+        assert_eq!(Locale::parse("FR").unwrap().as_ref(), "fr");
+        assert_eq!(Locale::parse("pt_BR").unwrap().as_ref(), "pt-BR");
+    }
+
+    #[test]
+    fn invalid_tags_are_rejected() {
+        assert_err!(Locale::parse(""));
+        assert_err!(Locale::parse("english"));
+        assert_err!(Locale::parse("en-"));
+        assert_err!(Locale::parse("../en"));
+    }
+
+    #[test]
+    fn fallbacks_end_with_default() {
+        let locale = Locale::parse("pt-BR").unwrap();
+        assert_eq!(locale.fallbacks(), vec!["pt-BR", "pt", "en"]);
+
+        let locale = Locale::default();
+        assert_eq!(locale.fallbacks(), vec!["en"]);
+    }
+}
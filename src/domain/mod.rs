@@ -11,33 +11,66 @@
 //!
 //! ## Domains included:
 //! - AccessToken
+//! - ActionToken
+//! - ApiKey
+//! - AuthContext (the caller of a request)
+//! - ClientSecret
+//! - DeviceCode and UserCode
 //! - EmailAddress
+//! - EmailDomain (registration allow and block lists)
+//! - GrantType (OAuth)
+//! - Locale
+//! - Notification (opt outs)
 //! - TokenClaim (JWT)
 //! - PasswordHash
+//! - Policy (terms of service and privacy policy)
 //! - RefreshToken
 //! - RowID
-//! - UserName
+//! - TimeZone
+//! - UserName (and the policy names are checked against)
 //! - UserRole
 //!
 //! Use these types in place of primitive types to enforce invariants and improve code clarity.
 
 mod access_token;
+mod action_token;
+mod api_key;
+mod auth_context;
+mod client_secret;
+mod device_code;
 mod email_address;
+mod email_domain;
+mod grant_type;
 mod jwt_token;
+mod locale;
+mod notification;
 mod password_hash;
+mod policy;
 mod refresh_token;
 mod row_id;
+mod time_zone;
 mod user_name;
 mod user_role;
 mod tokens;
 
 // Re-export domain structs
 pub use access_token::AccessToken;
+pub use action_token::{ActionPurpose, ActionToken};
+pub use api_key::{ApiKey, API_KEY_HEADER};
+pub use auth_context::AuthContext;
+pub use client_secret::ClientSecret;
+pub use device_code::{DeviceCode, UserCode};
 pub use email_address::EmailAddress;
-pub use jwt_token::TokenClaim;
+pub use email_domain::{EmailDomain, EmailDomainRejection};
+pub use grant_type::GrantType;
+pub use jwt_token::{is_valid_scope, Actor, TokenClaim, CLIENT_ROLE, DEFAULT_AUDIENCE};
+pub use locale::{Locale, DEFAULT_LOCALE};
+pub use notification::Notification;
 pub use password_hash::PasswordHash;
+pub use policy::Policy;
 pub use refresh_token::RefreshToken;
 pub use row_id::RowID;
-pub use user_name::UserName;
+pub use time_zone::{TimeZone, DEFAULT_TIME_ZONE};
+pub use user_name::{UserName, UserNameCharacters, UserNamePolicy};
 pub use user_role::UserRole;
 pub use tokens::{EmailVerificationToken, TokenType, TokenClaimNew};
@@ -0,0 +1,75 @@
+//-- ./src/domain/notification.rs
+
+// #![allow(unused)] // For beginning only.
+
+//! Notification domain
+//!
+//! The notifications sent to users that they can opt out of, stored in their
+//! preferences by name. Emails a user needs to act on, such as verification
+//! and password resets, are always sent.
+//! ---
+
+use crate::prelude::*;
+
+/// Notifications a user can opt out of
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Notification {
+    /// Account activity alerts, e.g. a registration attempt with their email
+    SecurityAlert,
+}
+
+impl Notification {
+    /// All the notifications, in the order they are listed
+    pub const ALL: [Notification; 1] = [Notification::SecurityAlert];
+
+    /// Convert Notification to its stored name
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Notification::SecurityAlert => "security_alert",
+        }
+    }
+}
+
+impl std::fmt::Display for Notification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+impl std::str::FromStr for Notification {
+    type Err = AuthenticationError;
+
+    fn from_str(input: &str) -> Result<Notification, Self::Err> {
+        Notification::ALL
+            .into_iter()
+            .find(|notification| notification.to_str() == input)
+            .ok_or_else(|| {
+                let names: Vec<&str> =
+                    Notification::ALL.iter().map(Notification::to_str).collect();
+                AuthenticationError::ValidationError(format!(
+                    "{input} is not a notification, use one of {}",
+                    names.join(", ")
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Bring module into test scope
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn notifications_round_trip_their_names() -> Result<()> {
+        for notification in Notification::ALL {
+            assert_eq!(notification.to_str().parse::<Notification>()?, notification);
+        }
+        assert!("password_reset".parse::<Notification>().is_err());
+
+        Ok(())
+    }
+}
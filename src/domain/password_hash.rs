@@ -20,8 +20,21 @@ use crate::prelude::*;
 use crate::domain::RefreshToken;
 use argon2::password_hash::{rand_core, SaltString};
 use argon2::{Algorithm, Argon2, Params, PasswordHasher, PasswordVerifier, Version};
+use rand::distr::{Alphanumeric, SampleString};
 use secrecy::{ExposeSecret, SecretString};
 
+/// A well formed argon2id hash, with the same parameters as `parse`, that no
+/// password is expected to match. Verifying against it costs the same as
+/// verifying against a real hash.
+const DUMMY_PASSWORD_HASH: &str =
+    "$argon2id$v=19$m=15000,t=2,p=1$Y29uc3RhbnQtdGltZS1vaw$GDXmMeenjLkCNcxrUTeCe82EO3mxONKcW3w6YOqzQiA";
+
+/// Number of dash separated groups in a temporary password
+const TEMPORARY_PASSWORD_GROUPS: usize = 4;
+
+/// Number of letters and numbers in each temporary password group
+const TEMPORARY_PASSWORD_GROUP_LENGTH: usize = 5;
+
 // TODO: rationalise serde derives
 #[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct PasswordHash(String);
@@ -136,6 +149,33 @@ impl PasswordHash {
         Ok(verified)
     }
 
+    /// Generate a random temporary password that passes `parse`, as groups of
+    /// letters and numbers separated by dashes, e.g. `a8Kd2-Lm3pQ-x9TzR-4bNcW`
+    pub fn temporary_password() -> SecretString {
+        loop {
+            let password = (0..TEMPORARY_PASSWORD_GROUPS)
+                .map(|_| {
+                    Alphanumeric.sample_string(&mut rand::rng(), TEMPORARY_PASSWORD_GROUP_LENGTH)
+                })
+                .collect::<Vec<_>>()
+                .join("-");
+
+            // Draw again in the rare case a character class is missing
+            if password.bytes().any(|byte| byte.is_ascii_uppercase())
+                && password.bytes().any(|byte| byte.is_ascii_lowercase())
+                && password.bytes().any(|byte| byte.is_ascii_digit())
+            {
+                return SecretString::from(password);
+            }
+        }
+    }
+
+    /// A hash to verify against when there is no user, so a failed login for
+    /// an unknown email takes as long as one with a wrong password
+    pub fn dummy() -> Self {
+        Self(DUMMY_PASSWORD_HASH.to_string())
+    }
+
     #[cfg(test)]
     pub fn mock_data() -> Result<Self, AuthenticationError> {
         use fake::Fake;
@@ -180,6 +220,18 @@ mod tests {
     pub type Result<T> = core::result::Result<T, Error>;
     pub type Error = Box<dyn std::error::Error>;
 
+    #[test]
+    fn temporary_passwords_are_valid_and_random() -> Result<()> {
+        let password = domain::PasswordHash::temporary_password();
+        let other = domain::PasswordHash::temporary_password();
+
+        assert_eq!(password.expose_secret().len(), 23);
+        assert_ne!(password.expose_secret(), other.expose_secret());
+        assert_ok!(domain::PasswordHash::parse(password));
+
+        Ok(())
+    }
+
     #[test]
     fn less_than_twelve_fails() -> Result<()> {
         let password = "aB1%".to_string();
@@ -256,4 +308,59 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn dummy_hash_matches_no_password() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let password_hash = domain::PasswordHash::dummy();
+        let password_secret = SecretString::from("aB1%".repeat(4));
+
+        //-- Execute Function (Act)
+        let verified = password_hash.verify_password(&password_secret)?;
+
+        //-- Checks (Assertions)
+        assert!(!verified);
+        assert!(password_hash.as_ref().contains("m=15000,t=2,p=1"));
+
+        Ok(())
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn short_password_is_rejected(password in "[ -~]{0,11}") {
+            let password = SecretString::from(password);
+            proptest::prop_assert!(domain::PasswordHash::parse(password).is_err());
+        }
+
+        #[test]
+        fn overlong_password_is_rejected(password in "[a-zA-Z0-9%é]{256,1000}") {
+            let password = SecretString::from(password);
+            proptest::prop_assert!(domain::PasswordHash::parse(password).is_err());
+        }
+
+        #[test]
+        fn password_without_uppercase_is_rejected(password in "[a-z0-9%é]{12,100}") {
+            let password = SecretString::from(password);
+            proptest::prop_assert!(domain::PasswordHash::parse(password).is_err());
+        }
+
+        #[test]
+        fn password_without_lowercase_is_rejected(password in "[A-Z0-9%é]{12,100}") {
+            let password = SecretString::from(password);
+            proptest::prop_assert!(domain::PasswordHash::parse(password).is_err());
+        }
+    }
+
+    proptest::proptest! {
+        // Hashing is deliberately slow, so fewer cases
+        #![proptest_config(proptest::prelude::ProptestConfig::with_cases(8))]
+
+        #[test]
+        fn unicode_password_meeting_the_rules_is_accepted(
+            password in "[a-z]{3,10}[A-Z]{3,10}[0-9]{3,10}[%!ßж]{3,10}"
+        ) {
+            let password = SecretString::from(password);
+            proptest::prop_assert!(domain::PasswordHash::parse(password).is_ok());
+        }
+    }
 }
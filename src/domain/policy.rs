@@ -0,0 +1,77 @@
+//-- ./src/domain/policy.rs
+
+// #![allow(unused)] // For beginning only.
+
+//! Policy domain
+//!
+//! The policies users must accept before using the service, each with a
+//! configured version (see `configuration.policies`). Acceptances are stored
+//! against the user by policy name and version.
+//! ---
+
+use crate::prelude::*;
+
+/// Policies a user can be required to accept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Policy {
+    /// The terms of service
+    TermsOfService,
+    /// The privacy policy
+    PrivacyPolicy,
+}
+
+impl Policy {
+    /// All the policies, in the order they are listed
+    pub const ALL: [Policy; 2] = [Policy::TermsOfService, Policy::PrivacyPolicy];
+
+    /// Convert Policy to its stored name
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Policy::TermsOfService => "terms_of_service",
+            Policy::PrivacyPolicy => "privacy_policy",
+        }
+    }
+}
+
+impl std::fmt::Display for Policy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+impl std::str::FromStr for Policy {
+    type Err = AuthenticationError;
+
+    fn from_str(input: &str) -> Result<Policy, Self::Err> {
+        Policy::ALL
+            .into_iter()
+            .find(|policy| policy.to_str() == input)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Policy::ALL.iter().map(Policy::to_str).collect();
+                AuthenticationError::ValidationError(format!(
+                    "{input} is not a policy, use one of {}",
+                    names.join(", ")
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Bring module into test scope
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn policies_round_trip_their_names() -> Result<()> {
+        for policy in Policy::ALL {
+            assert_eq!(policy.to_str().parse::<Policy>()?, policy);
+        }
+        assert!("cookies".parse::<Policy>().is_err());
+
+        Ok(())
+    }
+}
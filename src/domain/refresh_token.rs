@@ -13,10 +13,11 @@ use core::time;
 use jsonwebtoken::{encode, EncodingKey, Header};
 use secrecy::{ExposeSecret, SecretString};
 use serde::Deserialize;
-use std::collections::{self, HashMap};
 use tonic::{metadata::MetadataValue, Status};
 use uuid::Uuid;
 
+use crate::configuration::{RefreshCookieConfiguration, RefreshTokenConfiguration};
+use crate::utils::{self, Clock, SystemClock};
 use crate::{database, domain::jwt_token::TokenType, prelude::*};
 
 use super::TokenClaim;
@@ -24,10 +25,6 @@ use super::TokenClaim;
 // TODO: Sanitise before parsing
 // TODO: Write an into method from a token claim
 
-/// What paths within the domain should the browser send the cookie back to the server.
-/// Set to root, so it will be sent for all paths in the domain set within the cookie
-pub static COOKIE_PATH: &str = "/";
-
 /// Refresh Token for authorising a new Access Token
 // #[derive(serde::Deserialize, Debug, Clone, PartialEq)]
 #[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
@@ -74,9 +71,23 @@ impl RefreshToken {
         duration: &time::Duration,
         user: &database::Users,
     ) -> Result<Self, AuthenticationError> {
-        // Build the Access Token Claim
+        Self::new_with_clock(secret, issuer, duration, user, &SystemClock)
+    }
+
+    /// # New Refresh Token at the Clock Time
+    ///
+    /// The same as `RefreshToken::new`, issued at the time of `clock` instead
+    /// of the system time
+    pub fn new_with_clock(
+        secret: &SecretString,
+        issuer: &SecretString,
+        duration: &time::Duration,
+        user: &database::Users,
+        clock: &dyn Clock,
+    ) -> Result<Self, AuthenticationError> {
+        // Build the Refresh Token Claim
         let token_claim =
-            TokenClaim::new(issuer, duration, user, &TokenType::Refresh);
+            TokenClaim::new_with_clock(issuer, duration, user, &TokenType::Refresh, clock);
 
         // Encode the Token Claim into a URL-safe hash encryption
         let token = encode(
@@ -134,95 +145,99 @@ impl RefreshToken {
     ///
     /// ## Parameters
     ///
-    /// - `domain<&str>` - The domain of the cookie
+    /// - `cookie_config<&RefreshCookieConfiguration>` - The cookie name and attributes
+    /// - `domain<&str>` - The domain of the cookie, unless `cookie_config` overrides it
     /// - `duration<&time::Duration>` - How long is the cookie validate for
     ///
     /// ## References
     /// - https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Headers/Set-Cookie
+    /// - https://developer.mozilla.org/en-US/docs/Web/Privacy/Guides/Privacy_sandbox/Partitioned_cookies
     /// - https://github.com/Houndie/open-dance-registration/blob/main/src/api/authentication.rs
     /// - https://github.com/dlunch/account/blob/main/server/src/handlers/auth.rs
     /// - https://docs.rs/cookie/latest/cookie/struct.CookieBuilder.html
     ///
     #[tracing::instrument(name = "Build a Refresh Token cookie from: ")]
-    pub fn build_cookie(&self, domain: &str, duration: &time::Duration) -> Cookie {
+    pub fn build_cookie(
+        &self,
+        cookie_config: &RefreshCookieConfiguration,
+        domain: &str,
+        duration: &time::Duration,
+    ) -> Cookie<'static> {
         let duration = cookie::time::Duration::new(duration.as_secs() as i64, 0);
+        let domain = cookie_config.domain.as_deref().unwrap_or(domain);
 
-        let refresh_cookie = Cookie::build(("refresh_token", self.to_string()))
+        let mut refresh_cookie = Cookie::build((cookie_config.name.clone(), self.to_string()))
             // Set the domain of the cookie
             .domain(domain.to_owned())
-            // Do not use http
-            // .domain("localhost") 
             // Indicates the path that must exist in the requested URL for the browser to send the Cookie header.
-            .path(COOKIE_PATH)
+            .path(cookie_config.path.clone())
             // Indicates the number of seconds until the cookie expires.
             .max_age(duration)
             // Forbids JavaScript from accessing the cookie
             .http_only(true)
             // Indicates that the cookie is sent to the server only when a request is made with the https or localhost
-            .secure(false)
-            // .same_site(cookie::SameSite::None)
-            .build();
+            .secure(cookie_config.secure)
+            // Keep a separate cookie per top level site when embedded cross-site (CHIPS)
+            .partitioned(cookie_config.partitioned);
 
-        refresh_cookie
+        // Without a SameSite attribute the browser picks, usually Lax
+        if let Some(same_site) = cookie_config.same_site {
+            refresh_cookie = refresh_cookie.same_site(same_site.into());
+        }
+
+        refresh_cookie.build()
     }
 
     /// # Extract Token Form Header
     ///
-    /// Extract the Refresh token string from the tonic request header
-    // TODO: Use Cookie parse instead of manually parsing the cookie string
+    /// Extract the Refresh token string from the tonic request header. The
+    /// refresh token cookie is checked first, then, when configured, the
+    /// `authorization: Bearer` metadata and the custom metadata key, for
+    /// clients that can't keep cookies.
+    ///
+    /// ## Parameters
+    ///
+    /// - `cookie_name<&str>` - The name of the refresh token cookie
+    /// - `sources<&RefreshTokenConfiguration>` - The metadata also accepted
+    /// - `request_metadata<&tonic::metadata::MetadataMap>` - The request metadata
     #[tracing::instrument(
         name = "Extract Refresh Token from http header: ",
-        skip(token_secret)
+        skip(request_metadata)
     )]
     pub fn from_header(
-        token_secret: &SecretString,
+        cookie_name: &str,
+        sources: &RefreshTokenConfiguration,
         request_metadata: &tonic::metadata::MetadataMap,
     ) -> Result<Self, AuthenticationError> {
-        // Collect all cookies from the request metadata into a vector
-        let cookies = request_metadata
-            .get_all("cookie")
-            .into_iter()
-            .collect::<Vec<_>>();
-        tracing::debug!("Cookies vector: {cookies:#?}");
-
-        // Create hashmap for storing the header cookies in
-        let mut cookies_map: HashMap<&str, &str> = HashMap::new();
-
-        // Inetrate over cookies in the header, adding them to the cookie has map
-        for cookie in cookies {
-            // Convert the cookie to a string
-            let cookie = cookie.to_str().map_err(|_| {
-                tracing::error!("Error converting cookie to Ascii string.");
-                AuthenticationError::AuthenticationError(
-                    "Error converting cookie Ascii to string".to_string(),
-                )
-            })?;
-
-            // Split the cookie string into key-value pairs
-            let parts: Vec<&str> = cookie.split("=").collect();
-
-            // Ensure that the cookie has a key and a value
-            if parts.len() != 2 {
-                tracing::error!("Key or value missing from cookie string");
-                AuthenticationError::AuthenticationError(
-                    "Key or value missing from cookie string".to_string(),
-                );
-            }
-
-            // Insert the key-value pair into the cookie map
-            cookies_map.insert(parts[0], parts[1]);
+        let cookie_jar = utils::metadata::get_cookie_jar(request_metadata)?;
+        if let Some(cookie) = cookie_jar.get(cookie_name) {
+            return Ok(Self(cookie.value_trimmed().to_string()));
         }
 
-        let refresh_token = cookies_map.get("refresh_token").ok_or(
-            AuthenticationError::AuthenticationError(
-                "No refresh token in cookie map".to_string(),
-            ),
-        )?;
+        let metadata = |key: &str| {
+            request_metadata
+                .get(key)
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        };
+
+        let bearer = metadata("authorization")
+            .filter(|_| sources.accept_bearer)
+            .and_then(|authorization| authorization.split_once(' '))
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer"))
+            .map(|(_, token)| token.trim());
+        if let Some(token) = bearer {
+            return Ok(Self(token.to_string()));
+        }
 
-        // Build a new refresh token from the refresh token string
-        let refresh_token = RefreshToken(refresh_token.to_string());
+        if let Some(token) = sources.metadata_key.as_deref().and_then(metadata) {
+            return Ok(Self(token.to_string()));
+        }
 
-        Ok(refresh_token)
+        Err(AuthenticationError::AuthenticationError(
+            "No refresh token in the request metadata".to_string(),
+        ))
     }
 }
 
@@ -316,17 +331,87 @@ mod tests {
         let domain = DomainSuffix().fake::<String>();
 
         // Build the refresh token cookie
-        let cookie = refresh_token.build_cookie(&domain, &random_duration);
+        let cookie_config = RefreshCookieConfiguration::default();
+        let cookie = refresh_token.build_cookie(&cookie_config, &domain, &random_duration);
 
         // Convert to a cookie duration for assertion
         let random_duration: cookie::time::Duration =
             cookie::time::Duration::new(random_duration.as_secs() as i64, 0);
 
+        assert_eq!(cookie.name(), "refresh_token");
         assert_eq!(cookie.domain(), Some(domain.as_str()));
-        assert_eq!(cookie.path(), Some(COOKIE_PATH));
+        assert_eq!(cookie.path(), Some("/"));
         assert_eq!(cookie.max_age(), Some(random_duration));
         assert_eq!(cookie.http_only(), Some(true));
         assert_eq!(cookie.secure(), Some(false));
+        assert_eq!(cookie.same_site(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn build_refresh_cookie_with_configured_attributes() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let random_user = database::Users::mock_data()?;
+        let random_secret = SecretString::from(Alphanumeric.sample_string(&mut rand::rng(), 60));
+        let random_issuer = SecretString::from(CompanyName().fake::<String>());
+        let duration = std::time::Duration::from_secs(60 * 60);
+        let refresh_token =
+            RefreshToken::new(&random_secret, &random_issuer, &duration, &random_user)?;
+        let cookie_config = RefreshCookieConfiguration {
+            name: "__Host-session".to_string(),
+            path: "/auth".to_string(),
+            domain: Some("auth.example.com".to_string()),
+            same_site: Some(crate::configuration::CookieSameSite::None),
+            secure: true,
+            partitioned: true,
+        };
+
+        //-- Execute Function (Act)
+        let cookie = refresh_token.build_cookie(&cookie_config, "localhost", &duration);
+
+        //-- Checks (Assertions)
+        assert_eq!(cookie.name(), "__Host-session");
+        assert_eq!(cookie.value(), refresh_token.as_ref());
+        assert_eq!(cookie.domain(), Some("auth.example.com"));
+        assert_eq!(cookie.path(), Some("/auth"));
+        assert_eq!(cookie.same_site(), Some(cookie::SameSite::None));
+        assert_eq!(cookie.secure(), Some(true));
+        assert!(cookie.to_string().contains("; Partitioned"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn refresh_token_is_read_from_the_configured_metadata() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let metadata = |key: &str, value: &str| -> Result<tonic::metadata::MetadataMap> {
+            let mut headers = http::HeaderMap::new();
+            headers.insert(http::HeaderName::from_bytes(key.as_bytes())?, value.parse()?);
+            Ok(tonic::metadata::MetadataMap::from_headers(headers))
+        };
+        let cookie = metadata("cookie", "theme=dark; refresh_token=from-cookie")?;
+        let bearer = metadata("authorization", "Bearer from-bearer")?;
+        let custom = metadata("x-refresh-token", "from-custom")?;
+        let cookies_only = RefreshTokenConfiguration::default();
+        let sources = RefreshTokenConfiguration {
+            accept_bearer: true,
+            metadata_key: Some("x-refresh-token".to_string()),
+        };
+
+        //-- Execute Function (Act)
+        let read = |sources: &RefreshTokenConfiguration, metadata| {
+            RefreshToken::from_header("refresh_token", sources, metadata)
+                .map(|token| token.to_string())
+                .ok()
+        };
+
+        //-- Checks (Assertions)
+        assert_eq!(read(&cookies_only, &cookie), Some("from-cookie".to_string()));
+        assert_eq!(read(&cookies_only, &bearer), None);
+        assert_eq!(read(&cookies_only, &custom), None);
+        assert_eq!(read(&sources, &bearer), Some("from-bearer".to_string()));
+        assert_eq!(read(&sources, &custom), Some("from-custom".to_string()));
 
         Ok(())
     }
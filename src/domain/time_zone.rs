@@ -0,0 +1,96 @@
+//-- ./src/domain/time_zone.rs
+
+// #![allow(unused)] // For beginning only.
+
+//! Time zone domain parsing
+//!
+//! Parse a string into an IANA time zone name (e.g. `UTC`,
+//! `Australia/Brisbane`, `America/Argentina/Buenos_Aires`), used to show times
+//! in the user's time zone. Only the shape of the name is checked, clients
+//! offer the names from their own time zone database.
+//! ---
+
+use crate::prelude::*;
+
+/// The time zone used when a user has not chosen one
+pub const DEFAULT_TIME_ZONE: &str = "UTC";
+
+/// Longest time zone name accepted
+const MAX_TIME_ZONE_LENGTH: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize, derive_more::From)]
+pub struct TimeZone(String);
+
+impl Default for TimeZone {
+    fn default() -> Self {
+        Self(DEFAULT_TIME_ZONE.to_string())
+    }
+}
+
+impl TimeZone {
+    /// Returns a Result of TimeZone if the input looks like an IANA time zone
+    /// name, one to three `/` separated parts of letters, digits, `_`, `-` and
+    /// `+`, each starting with a letter, e.g. `Etc/GMT+10`.
+    pub fn parse(time_zone: impl Into<String>) -> Result<TimeZone, AuthenticationError> {
+        let time_zone = time_zone.into().trim().to_string();
+
+        let parts: Vec<&str> = time_zone.split('/').collect();
+        let parts_are_valid = parts.iter().all(|part| {
+            part.starts_with(|c: char| c.is_ascii_alphabetic())
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+'))
+        });
+
+        if time_zone.len() > MAX_TIME_ZONE_LENGTH || parts.len() > 3 || !parts_are_valid {
+            return Err(AuthenticationError::ValidationError(format!(
+                "time zone is not an IANA time zone name: {time_zone}"
+            )));
+        }
+
+        Ok(Self(time_zone))
+    }
+}
+
+impl AsRef<str> for TimeZone {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for TimeZone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Bring module into test scope
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn time_zone_names_are_accepted() -> Result<()> {
+        for name in ["UTC", "Australia/Brisbane", "America/Argentina/Buenos_Aires", "Etc/GMT+10"] {
+            assert_eq!(TimeZone::parse(name)?.as_ref(), name);
+        }
+        assert_eq!(TimeZone::parse(" Europe/Paris ")?.as_ref(), "Europe/Paris");
+        assert_eq!(TimeZone::default().as_ref(), DEFAULT_TIME_ZONE);
+
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_time_zones_are_rejected() {
+        assert!(TimeZone::parse("").is_err());
+        assert!(TimeZone::parse("Australia/").is_err());
+        assert!(TimeZone::parse("../etc/passwd").is_err());
+        assert!(TimeZone::parse("+10:00").is_err());
+        assert!(TimeZone::parse("A/B/C/D").is_err());
+        assert!(TimeZone::parse("A".repeat(65)).is_err());
+    }
+}
@@ -30,6 +30,7 @@ use crate::{
         self,
         tokens::{self, TokenType},
     },
+    utils::{Clock, SystemClock},
     AuthenticationError,
 };
 
@@ -168,6 +169,18 @@ impl TokenClaimNew {
         duration: &chrono::Duration,
         user: &database::Users,
         token_type: &tokens::TokenType,
+    ) -> Self {
+        Self::new_with_clock(issuer, duration, user, token_type, &SystemClock)
+    }
+
+    /// The same as `TokenClaimNew::new`, issued at the time of `clock` instead
+    /// of the system time
+    pub fn new_with_clock(
+        issuer: &SecretString,
+        duration: &chrono::Duration,
+        user: &database::Users,
+        token_type: &tokens::TokenType,
+        clock: &dyn Clock,
     ) -> Self {
         // Get the user id from the user instance passed in
         let subject = user.id;
@@ -175,8 +188,9 @@ impl TokenClaimNew {
         // TODO: consider making this a configuration setting
         let audience = "authentication_service".to_string();
 
-        // Get System Time now, truncated to seconds for JWT compatibility
-        let now = chrono::Utc::now()
+        // Get the clock time now, truncated to seconds for JWT compatibility
+        let now = clock
+            .now()
             .with_nanosecond(0)
             .expect("Failed to truncate nanoseconds from current time");
 
@@ -5,14 +5,114 @@
 //! Username domain parsing
 //!
 //! Parse string into a name, checking for validation as we go.
+//!
+//! Names are checked against the `UserNamePolicy` installed at startup from
+//! the `user_names` configuration: length bounds, the characters allowed,
+//! reserved names such as "admin" and an optional profanity list. Without one
+//! the default policy only rejects blank, overlong and forbidden characters.
 //! ---
 
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+
 use serde::{Deserialize, Serialize};
 use sqlx::Decode;
 use unicode_segmentation::UnicodeSegmentation;
 
 use crate::prelude::*;
 
+/// Characters that are never allowed in a name
+const FORBIDDEN_CHARACTERS: [char; 9] = ['/', '(', ')', '"', '<', '>', '\\', '{', '}'];
+
+/// The policy `UserName::parse` checks names against, see `UserNamePolicy::install`
+static INSTALLED_POLICY: RwLock<Option<Arc<UserNamePolicy>>> = RwLock::new(None);
+
+/// The characters a name can be made of
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UserNameCharacters {
+    /// Any character except the forbidden ones
+    #[default]
+    Any,
+    /// Letters and digits in any script, with their accents, spaces and `'-.`
+    Letters,
+    /// ASCII letters and digits, spaces and `'-._`
+    Ascii,
+}
+
+impl UserNameCharacters {
+    /// Is the grapheme allowed in a name
+    fn allows(&self, grapheme: &str) -> bool {
+        match self {
+            UserNameCharacters::Any => true,
+            // Accents are combining marks in the same grapheme as their letter
+            UserNameCharacters::Letters => grapheme.chars().next().is_some_and(|c| {
+                c.is_alphanumeric() || matches!(c, ' ' | '\'' | '-' | '.')
+            }),
+            UserNameCharacters::Ascii => grapheme.chars().all(|c| {
+                c.is_ascii_alphanumeric() || matches!(c, ' ' | '\'' | '-' | '.' | '_')
+            }),
+        }
+    }
+}
+
+/// What a deployment allows in a name
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserNamePolicy {
+    /// The fewest graphemes in a name, not counting surrounding whitespace
+    pub min_length: usize,
+    /// The most graphemes in a name
+    pub max_length: usize,
+    /// The characters a name can be made of
+    pub characters: UserNameCharacters,
+    /// Lower case names that can't be used, e.g. "admin"
+    pub reserved: HashSet<String>,
+    /// Lower case words that can't appear in a name
+    pub profanity: HashSet<String>,
+}
+
+impl Default for UserNamePolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 1,
+            max_length: 256,
+            characters: UserNameCharacters::default(),
+            reserved: HashSet::new(),
+            profanity: HashSet::new(),
+        }
+    }
+}
+
+impl UserNamePolicy {
+    /// Check every name parsed with `UserName::parse` against this policy,
+    /// replacing the one installed before
+    pub fn install(self) {
+        let mut installed = INSTALLED_POLICY
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *installed = Some(Arc::new(self));
+    }
+
+    /// The installed policy, or the default when none is installed
+    pub fn installed() -> Arc<UserNamePolicy> {
+        INSTALLED_POLICY
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+            .unwrap_or_default()
+    }
+
+    /// Is the name reserved, or does it contain a word on the profanity list
+    fn is_not_allowed(&self, name: &str) -> bool {
+        let name = name.trim().to_lowercase();
+
+        self.reserved.contains(&name)
+            || name
+                .split(|c: char| !c.is_alphanumeric())
+                .any(|word| self.profanity.contains(word))
+    }
+}
+
 // #[derive(
 //     Debug, Clone, PartialEq, Serialize, Deserialize, Decode, derive_more::From,
 // )]
@@ -40,7 +140,17 @@ impl std::fmt::Display for UserName {
 }
 
 impl UserName {
+    /// Returns a Result of UserName if the name meets the installed
+    /// `UserNamePolicy`
     pub fn parse(name: impl Into<String>) -> Result<UserName, AuthenticationError> {
+        Self::parse_with(name, &UserNamePolicy::installed())
+    }
+
+    /// Returns a Result of UserName if the name meets the policy
+    pub fn parse_with(
+        name: impl Into<String>,
+        policy: &UserNamePolicy,
+    ) -> Result<UserName, AuthenticationError> {
         let name: String = name.into();
 
         // `.trim()` returns a view over the input `name` without trailing whitespace-like
@@ -54,16 +164,26 @@ impl UserName {
         // `graphemes` returns an iterator over the graphemes in the input `name`.
         // `true` specifies that we want to use the extended grapheme definition set,
         // the recommended one.
-        let is_too_long = name.graphemes(true).count() > 256;
+        let is_too_long = name.graphemes(true).count() > policy.max_length;
+        let is_too_short = name.trim().graphemes(true).count() < policy.min_length;
 
         // Iterate over all characters in the input `name` to check if any of them matches
         // one of the characters in the forbidden array.
-        let forbidden_characters = ['/', '(', ')', '"', '<', '>', '\\', '{', '}'];
         let contains_forbidden_characters =
-            name.chars().any(|g| forbidden_characters.contains(&g));
-
-        if is_empty_or_whitespace || is_too_long || contains_forbidden_characters {
+            name.chars().any(|g| FORBIDDEN_CHARACTERS.contains(&g));
+        let contains_disallowed_characters = !name
+            .graphemes(true)
+            .all(|grapheme| policy.characters.allows(grapheme));
+
+        if is_empty_or_whitespace
+            || is_too_long
+            || is_too_short
+            || contains_forbidden_characters
+            || contains_disallowed_characters
+        {
             Err(AuthenticationError::UserNameFormatInvalid(name))
+        } else if policy.is_not_allowed(&name) {
+            Err(AuthenticationError::UserNameNotAllowed(name))
         } else {
             Ok(Self(name))
         }
@@ -86,7 +206,9 @@ mod tests {
     use fake::faker::name::en::Name;
     use fake::Fake;
 
-    use super::{AuthenticationError, UserName};
+    use std::collections::HashSet;
+
+    use super::{AuthenticationError, UserName, UserNameCharacters, UserNamePolicy};
 
     // Override with more flexible error
     pub type Result<T> = core::result::Result<T, Error>;
@@ -166,4 +288,85 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn the_policy_bounds_the_length_and_characters() -> Result<()> {
+        let policy = UserNamePolicy {
+            min_length: 3,
+            max_length: 10,
+            characters: UserNameCharacters::Letters,
+            ..UserNamePolicy::default()
+        };
+
+        assert_ok!(UserName::parse_with("José O'Neil", &UserNamePolicy::default()));
+        assert_ok!(UserName::parse_with("Zoë-Ann", &policy));
+        assert_ok!(UserName::parse_with("Иван", &policy));
+        assert_err!(UserName::parse_with(" Jo ", &policy));
+        assert_err!(UserName::parse_with("Jane Smithson", &policy));
+        assert_err!(UserName::parse_with("Jane 🙂", &policy));
+        assert_err!(UserName::parse_with("Jane_Doe", &policy));
+
+        let ascii = UserNamePolicy {
+            characters: UserNameCharacters::Ascii,
+            ..UserNamePolicy::default()
+        };
+        assert_ok!(UserName::parse_with("jane_doe-42", &ascii));
+        assert_err!(UserName::parse_with("Zoë", &ascii));
+
+        Ok(())
+    }
+
+    #[test]
+    fn reserved_names_and_profanity_are_not_allowed() -> Result<()> {
+        let policy = UserNamePolicy {
+            reserved: HashSet::from(["admin".to_string()]),
+            profanity: HashSet::from(["darn".to_string()]),
+            ..UserNamePolicy::default()
+        };
+
+        assert!(matches!(
+            UserName::parse_with(" Admin ", &policy),
+            Err(AuthenticationError::UserNameNotAllowed { .. })
+        ));
+        assert!(matches!(
+            UserName::parse_with("Darn-it Smith", &policy),
+            Err(AuthenticationError::UserNameNotAllowed { .. })
+        ));
+        assert_ok!(UserName::parse_with("Admin Smith", &policy));
+        assert_ok!(UserName::parse_with("Darnell", &policy));
+
+        Ok(())
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn any_string_parses_without_panicking(name in ".*") {
+            if let Ok(parsed) = UserName::parse(name.clone()) {
+                proptest::prop_assert_eq!(parsed.as_ref(), name.as_str());
+                proptest::prop_assert!(!name.trim().is_empty());
+            }
+        }
+
+        #[test]
+        fn unicode_name_is_accepted(name in "\\p{L}[\\p{L}\\p{M} '-]{0,200}") {
+            proptest::prop_assert!(UserName::parse(name).is_ok());
+        }
+
+        #[test]
+        fn name_with_forbidden_character_is_rejected(
+            name in "\\p{L}{0,20}[/()\"<>\\\\{}]\\p{L}{0,20}"
+        ) {
+            proptest::prop_assert!(UserName::parse(name).is_err());
+        }
+
+        #[test]
+        fn overlong_name_is_rejected(name in "[a-zA-Zéøßж]{257,1000}") {
+            proptest::prop_assert!(UserName::parse(name).is_err());
+        }
+
+        #[test]
+        fn whitespace_only_name_is_rejected(name in "\\s*") {
+            proptest::prop_assert!(UserName::parse(name).is_err());
+        }
+    }
 }
@@ -32,6 +32,33 @@ pub enum UserRole {
 }
 
 impl UserRole {
+    /// Permission to use the admin service
+    pub const ADMIN_ACCESS: &'static str = "admin:access";
+
+    /// Permission to use the users service
+    pub const USERS_ACCESS: &'static str = "users:access";
+
+    /// Permission to use the sessions service
+    pub const SESSIONS_ACCESS: &'static str = "sessions:access";
+
+    /// Permission to impersonate other users
+    pub const USERS_IMPERSONATE: &'static str = "users:impersonate";
+
+    /// The permissions granted to the role, added to access tokens as the
+    /// `perms` claim
+    pub fn permissions(&self) -> &'static [&'static str] {
+        match self {
+            UserRole::Admin => &[
+                Self::ADMIN_ACCESS,
+                Self::USERS_ACCESS,
+                Self::SESSIONS_ACCESS,
+                Self::USERS_IMPERSONATE,
+            ],
+            UserRole::User => &[Self::USERS_ACCESS, Self::SESSIONS_ACCESS],
+            UserRole::Guest => &[],
+        }
+    }
+
     /// Convert UserRole to a string reference
     pub fn to_str(&self) -> &str {
         match self {
@@ -0,0 +1,68 @@
+//-- ./src/email/console.rs
+
+//! Email client that prints messages to the console instead of delivering them.
+//!
+//! Used in development and demo mode so flows that send email can be tried
+//! without an SMTP server.
+//! ---
+
+use crate::email::{EmailClient, EmailMessage};
+use crate::prelude::*;
+
+/// Prints emails to standard out
+#[derive(Debug, Clone, Default)]
+pub struct ConsoleEmailClient;
+
+impl ConsoleEmailClient {
+    /// Format an email message the way it is printed to the console
+    pub fn render(message: &EmailMessage) -> String {
+        format!(
+            "\n----------- EMAIL ----------- \nTo: {}\nSubject: {}\n\n{}\n-----------------------------",
+            message.to.as_ref(),
+            message.subject,
+            message.body_text
+        )
+    }
+}
+
+#[tonic::async_trait]
+impl EmailClient for ConsoleEmailClient {
+    #[tracing::instrument(name = "Console email: ", skip(self, message), fields(to = %message.to.as_ref()))]
+    async fn send(&self, message: &EmailMessage) -> Result<(), AuthenticationError> {
+        println!("{}", Self::render(message));
+
+        Ok(())
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[tokio::test]
+    async fn console_email_renders_and_sends() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let message = EmailMessage {
+            to: domain::EmailAddress::parse("someone@example.com")?,
+            subject: "Welcome".to_string(),
+            body_text: "Hello there".to_string(),
+        };
+
+        //-- Execute Function (Act)
+        let rendered = ConsoleEmailClient::render(&message);
+        ConsoleEmailClient.send(&message).await?;
+
+        //-- Checks (Assertions)
+        assert!(rendered.contains("To: someone@example.com"));
+        assert!(rendered.contains("Subject: Welcome"));
+        assert!(rendered.contains("Hello there"));
+
+        Ok(())
+    }
+}
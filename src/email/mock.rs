@@ -0,0 +1,227 @@
+//-- ./src/email/mock.rs
+
+//! Email client that keeps sent messages in memory instead of delivering them.
+//!
+//! Injected into the server in tests with `startup::TonicServer::with_email_client`,
+//! so tests can assert on the emails a flow sends and follow the links and
+//! codes in them. Clones share the same captured messages.
+//! ---
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::domain;
+use crate::email::{EmailClient, EmailMessage};
+use crate::prelude::*;
+
+/// How often `wait_for_sent` checks for new messages
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Records emails in memory
+#[derive(Debug, Clone, Default)]
+pub struct MockEmailClient {
+    sent: Arc<Mutex<Vec<EmailMessage>>>,
+}
+
+impl MockEmailClient {
+    /// Create a client with no captured messages
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every message sent, oldest first
+    pub fn sent(&self) -> Vec<EmailMessage> {
+        self.sent.lock().unwrap().clone()
+    }
+
+    /// Every message sent to the address, oldest first
+    pub fn sent_to(&self, to: &domain::EmailAddress) -> Vec<EmailMessage> {
+        self.sent
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|message| &message.to == to)
+            .cloned()
+            .collect()
+    }
+
+    /// The last message sent
+    pub fn last_message(&self) -> Option<EmailMessage> {
+        self.sent.lock().unwrap().last().cloned()
+    }
+
+    /// The last message sent to the address
+    pub fn last_message_to(
+        &self,
+        to: &domain::EmailAddress,
+    ) -> Option<EmailMessage> {
+        self.sent_to(to).pop()
+    }
+
+    /// The first link in the last message containing one, such as the link in
+    /// a verification email
+    pub fn last_verification_link(&self) -> Option<String> {
+        self.sent
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find_map(|message| first_link(&message.body_text))
+    }
+
+    /// The email change code in the last email change message sent to the address
+    pub fn last_email_change_token(
+        &self,
+        to: &domain::EmailAddress,
+    ) -> Option<String> {
+        self.sent_to(to)
+            .iter()
+            .rev()
+            .find_map(|message| first_word_starting(&message.body_text, "act_"))
+    }
+
+    /// The token in the link of the last magic link message sent to the address
+    pub fn last_magic_link_token(&self, to: &domain::EmailAddress) -> Option<String> {
+        self.sent_to(to).iter().rev().find_map(|message| {
+            first_link(&message.body_text)?
+                .split_once("token=")
+                .map(|(_, token)| token.split('&').next().unwrap_or(token).to_string())
+        })
+    }
+
+    /// The temporary password in the last temporary password message sent to
+    /// the address, the line of dash separated groups
+    pub fn last_temporary_password(&self, to: &domain::EmailAddress) -> Option<String> {
+        self.sent_to(to).iter().rev().find_map(|message| {
+            message
+                .body_text
+                .lines()
+                .map(str::trim)
+                .find(|line| {
+                    line.split('-').count() == 4
+                        && line.split('-').all(|group| {
+                            group.len() == 5 && group.chars().all(|c| c.is_ascii_alphanumeric())
+                        })
+                })
+                .map(str::to_string)
+        })
+    }
+
+    /// Forget the captured messages
+    pub fn clear(&self) {
+        self.sent.lock().unwrap().clear();
+    }
+
+    /// Wait until at least `count` messages have been sent, as emails are sent
+    /// from the outbox in the background. Returns the messages, or an error when
+    /// the timeout passes first.
+    pub async fn wait_for_sent(
+        &self,
+        count: usize,
+        timeout: Duration,
+    ) -> Result<Vec<EmailMessage>, AuthenticationError> {
+        let wait = async {
+            loop {
+                let sent = self.sent();
+                if sent.len() >= count {
+                    return sent;
+                }
+                tokio::time::sleep(WAIT_POLL_INTERVAL).await;
+            }
+        };
+
+        tokio::time::timeout(timeout, wait).await.map_err(|_| {
+            AuthenticationError::Generic(format!(
+                "Only {} of {count} emails were sent within {timeout:?}",
+                self.sent().len()
+            ))
+        })
+    }
+}
+
+/// The first http or https link in the text
+fn first_link(text: &str) -> Option<String> {
+    first_word_starting(text, "https://")
+        .or_else(|| first_word_starting(text, "http://"))
+}
+
+/// The first whitespace separated word in the text starting with the prefix
+fn first_word_starting(text: &str, prefix: &str) -> Option<String> {
+    text.split_whitespace()
+        .find(|word| word.starts_with(prefix))
+        .map(str::to_string)
+}
+
+#[tonic::async_trait]
+impl EmailClient for MockEmailClient {
+    #[tracing::instrument(name = "Mock email: ", skip(self, message), fields(to = %message.to.as_ref()))]
+    async fn send(&self, message: &EmailMessage) -> Result<(), AuthenticationError> {
+        self.sent.lock().unwrap().push(message.clone());
+
+        Ok(())
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    fn email_message(to: &str, body_text: &str) -> Result<EmailMessage> {
+        Ok(EmailMessage {
+            to: domain::EmailAddress::parse(to)?,
+            subject: "Subject".to_string(),
+            body_text: body_text.to_string(),
+        })
+    }
+
+    #[tokio::test]
+    async fn sent_messages_are_captured() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let client = MockEmailClient::new();
+        let verification = email_message(
+            "first@example.com",
+            "Please verify:\n\nhttps://example.com/verify?token=abc\n\nThanks",
+        )?;
+        let email_change =
+            email_message("second@example.com", "Use the code below:\n\nact_123\n")?;
+
+        //-- Execute Function (Act)
+        client.clone().send(&verification).await?;
+        client.send(&email_change).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(
+            client.sent(),
+            vec![verification.clone(), email_change.clone()]
+        );
+        assert_eq!(client.last_message(), Some(email_change.clone()));
+        assert_eq!(
+            client.last_magic_link_token(&verification.to).as_deref(),
+            Some("abc")
+        );
+        assert_eq!(client.last_message_to(&verification.to), Some(verification));
+        assert_eq!(
+            client.last_verification_link().as_deref(),
+            Some("https://example.com/verify?token=abc")
+        );
+        assert_eq!(
+            client.last_email_change_token(&email_change.to).as_deref(),
+            Some("act_123")
+        );
+        client.wait_for_sent(2, Duration::from_millis(10)).await?;
+        assert!(client
+            .wait_for_sent(3, Duration::from_millis(10))
+            .await
+            .is_err());
+
+        client.clear();
+        assert!(client.sent().is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+//-- ./src/email/mod.rs
+
+// #![allow(unused)] // For development only
+
+//! # Email Module
+//!
+//! Outgoing email for the authentication service. Senders depend on the
+//! `EmailClient` trait so the delivery transport can be swapped per
+//! environment.
+//!
+//! ## Clients
+//! - **ConsoleEmailClient**: Prints emails to the console, for development and demo mode
+//! - **SmtpEmailClient**: Delivers emails through an SMTP relay
+//! - **MockEmailClient**: Keeps emails in memory, for tests to assert on
+//!
+//! ## Templates
+//! - **EmailTemplates**: Renders localised email messages from Tera templates
+//! ---
+
+use std::sync::Arc;
+
+use crate::configuration::{EmailConfiguration, EmailTransport};
+use crate::domain;
+use crate::prelude::*;
+
+mod console;
+mod mock;
+mod smtp;
+mod templates;
+
+pub use console::ConsoleEmailClient;
+pub use mock::MockEmailClient;
+pub use smtp::SmtpEmailClient;
+pub use templates::{EmailTemplate, EmailTemplates};
+
+/// An outgoing email message
+///
+/// # Fields
+/// - `to`: Recipient email address
+/// - `subject`: Email subject line
+/// - `body_text`: Plain text email body
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct EmailMessage {
+    pub to: domain::EmailAddress,
+    pub subject: String,
+    pub body_text: String,
+}
+
+/// Delivers email messages
+#[tonic::async_trait]
+pub trait EmailClient: Send + Sync {
+    /// Send an email message
+    async fn send(&self, message: &EmailMessage) -> Result<(), AuthenticationError>;
+}
+
+/// Build the email client for the configured transport
+pub fn client_from_config(
+    config: &EmailConfiguration,
+) -> Result<Arc<dyn EmailClient>, AuthenticationError> {
+    match config.transport {
+        EmailTransport::Console => Ok(Arc::new(ConsoleEmailClient)),
+        EmailTransport::Smtp => Ok(Arc::new(SmtpEmailClient::new(config)?)),
+    }
+}
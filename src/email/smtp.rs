@@ -0,0 +1,119 @@
+//-- ./src/email/smtp.rs
+
+//! Email client that delivers messages through an SMTP relay using lettre.
+//!
+//! The connection uses STARTTLS on the configured port, authenticating when a
+//! username and password are configured.
+//! ---
+
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use secrecy::ExposeSecret;
+
+use crate::configuration::EmailConfiguration;
+use crate::email::{EmailClient, EmailMessage};
+use crate::prelude::*;
+
+/// Delivers emails through an SMTP relay
+#[derive(Clone)]
+pub struct SmtpEmailClient {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    sender: Mailbox,
+}
+
+impl SmtpEmailClient {
+    /// Build a new SMTP client from the email configuration
+    pub fn new(config: &EmailConfiguration) -> Result<Self, AuthenticationError> {
+        let host = config.smtp_host.as_deref().ok_or_else(|| {
+            AuthenticationError::ValidationError(
+                "email.smtp_host is required when email.transport is smtp".to_string(),
+            )
+        })?;
+
+        let sender: Mailbox = config.sender.parse().map_err(|e| {
+            AuthenticationError::ValidationError(format!(
+                "email.sender is not a valid address: {e}"
+            ))
+        })?;
+
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+            .map_err(|e| AuthenticationError::EmailDelivery(e.to_string()))?
+            .port(config.smtp_port);
+
+        if let (Some(username), Some(password)) =
+            (&config.smtp_username, &config.smtp_password)
+        {
+            builder = builder.credentials(Credentials::new(
+                username.clone(),
+                password.expose_secret().to_string(),
+            ));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            sender,
+        })
+    }
+
+    /// Connect to the relay and check it responds, without sending an email
+    pub async fn test_connection(&self) -> Result<(), AuthenticationError> {
+        match self.transport.test_connection().await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(AuthenticationError::EmailDelivery(
+                "SMTP relay did not accept the connection".to_string(),
+            )),
+            Err(e) => Err(AuthenticationError::EmailDelivery(e.to_string())),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl EmailClient for SmtpEmailClient {
+    #[tracing::instrument(name = "SMTP email: ", skip(self, message), fields(to = %message.to.as_ref()))]
+    async fn send(&self, message: &EmailMessage) -> Result<(), AuthenticationError> {
+        let to: Mailbox = message
+            .to
+            .as_ref()
+            .parse()
+            .map_err(|e| AuthenticationError::EmailDelivery(format!("{e}")))?;
+
+        let email = Message::builder()
+            .from(self.sender.clone())
+            .to(to)
+            .subject(message.subject.clone())
+            .body(message.body_text.clone())
+            .map_err(|e| AuthenticationError::EmailDelivery(e.to_string()))?;
+
+        self.transport
+            .send(email)
+            .await
+            .map_err(|e| AuthenticationError::EmailDelivery(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_smtp_host_is_an_error() {
+        let config = EmailConfiguration::default();
+
+        assert!(SmtpEmailClient::new(&config).is_err());
+    }
+
+    #[test]
+    fn invalid_sender_is_an_error() {
+        let config = EmailConfiguration {
+            smtp_host: Some("smtp.example.com".to_string()),
+            sender: "not an address".to_string(),
+            ..Default::default()
+        };
+
+        assert!(SmtpEmailClient::new(&config).is_err());
+    }
+}
@@ -0,0 +1,355 @@
+//-- ./src/email/templates.rs
+
+//! Localised email templates.
+//!
+//! Each email is a pair of [Tera](https://keats.github.io/tera/) templates
+//! named `<locale>/<template>.subject` and `<locale>/<template>.txt`. English
+//! and French templates are built in, and `email.template_directory` can add
+//! locales or replace the built in templates. The user's locale is tried
+//! first, then its language (e.g. `pt-BR` then `pt`), then `en`.
+//! ---
+
+use strum::{Display, EnumIter, IntoEnumIterator};
+use tera::{Context, Tera};
+
+use crate::configuration::EmailConfiguration;
+use crate::domain;
+use crate::email::EmailMessage;
+use crate::prelude::*;
+
+/// The emails sent by the service
+#[derive(Debug, Clone, Copy, PartialEq, Display, EnumIter)]
+#[strum(serialize_all = "snake_case")]
+pub enum EmailTemplate {
+    /// Verify a new email address, with `verification_url` and `expires_in_hours`
+    Verification,
+    /// Reset a forgotten password, with `reset_url` and `expires_in_minutes`
+    PasswordReset,
+    /// Tell the user about account activity, with `event`, `occurred_on` and an optional `ip_address`
+    SecurityAlert,
+    /// Confirm an email address change, with `token`, `new_email`, `is_new_address` and `expires_in_hours`
+    EmailChange,
+    /// Log in without a password, with `login_url` and `expires_in_minutes`
+    MagicLink,
+    /// Send an imported user their first login password, with `temporary_password`
+    TemporaryPassword,
+}
+
+/// Built in templates, as (name, template) pairs
+const BUILT_IN_TEMPLATES: [(&str, &str); 24] = [
+    (
+        "en/verification.subject",
+        include_str!("../../templates/email/en/verification.subject"),
+    ),
+    (
+        "en/verification.txt",
+        include_str!("../../templates/email/en/verification.txt"),
+    ),
+    (
+        "en/password_reset.subject",
+        include_str!("../../templates/email/en/password_reset.subject"),
+    ),
+    (
+        "en/password_reset.txt",
+        include_str!("../../templates/email/en/password_reset.txt"),
+    ),
+    (
+        "en/security_alert.subject",
+        include_str!("../../templates/email/en/security_alert.subject"),
+    ),
+    (
+        "en/security_alert.txt",
+        include_str!("../../templates/email/en/security_alert.txt"),
+    ),
+    (
+        "en/email_change.subject",
+        include_str!("../../templates/email/en/email_change.subject"),
+    ),
+    (
+        "en/email_change.txt",
+        include_str!("../../templates/email/en/email_change.txt"),
+    ),
+    (
+        "en/magic_link.subject",
+        include_str!("../../templates/email/en/magic_link.subject"),
+    ),
+    (
+        "en/magic_link.txt",
+        include_str!("../../templates/email/en/magic_link.txt"),
+    ),
+    (
+        "en/temporary_password.subject",
+        include_str!("../../templates/email/en/temporary_password.subject"),
+    ),
+    (
+        "en/temporary_password.txt",
+        include_str!("../../templates/email/en/temporary_password.txt"),
+    ),
+    (
+        "fr/verification.subject",
+        include_str!("../../templates/email/fr/verification.subject"),
+    ),
+    (
+        "fr/verification.txt",
+        include_str!("../../templates/email/fr/verification.txt"),
+    ),
+    (
+        "fr/password_reset.subject",
+        include_str!("../../templates/email/fr/password_reset.subject"),
+    ),
+    (
+        "fr/password_reset.txt",
+        include_str!("../../templates/email/fr/password_reset.txt"),
+    ),
+    (
+        "fr/security_alert.subject",
+        include_str!("../../templates/email/fr/security_alert.subject"),
+    ),
+    (
+        "fr/security_alert.txt",
+        include_str!("../../templates/email/fr/security_alert.txt"),
+    ),
+    (
+        "fr/email_change.subject",
+        include_str!("../../templates/email/fr/email_change.subject"),
+    ),
+    (
+        "fr/email_change.txt",
+        include_str!("../../templates/email/fr/email_change.txt"),
+    ),
+    (
+        "fr/magic_link.subject",
+        include_str!("../../templates/email/fr/magic_link.subject"),
+    ),
+    (
+        "fr/magic_link.txt",
+        include_str!("../../templates/email/fr/magic_link.txt"),
+    ),
+    (
+        "fr/temporary_password.subject",
+        include_str!("../../templates/email/fr/temporary_password.subject"),
+    ),
+    (
+        "fr/temporary_password.txt",
+        include_str!("../../templates/email/fr/temporary_password.txt"),
+    ),
+];
+
+/// Renders emails from the built in and custom templates
+#[derive(Debug, Clone)]
+pub struct EmailTemplates {
+    tera: Tera,
+}
+
+impl EmailTemplates {
+    /// Load the built in templates, then the custom templates from
+    /// `email.template_directory` over the top of them
+    pub fn new(config: &EmailConfiguration) -> Result<Self, AuthenticationError> {
+        let mut built_in = Tera::default();
+        built_in.add_raw_templates(BUILT_IN_TEMPLATES)?;
+
+        let tera = match &config.template_directory {
+            Some(directory) => {
+                let glob = format!("{}/**/*", directory.trim_end_matches('/'));
+                let mut custom = Tera::new(&glob)?;
+                // Custom templates win, built in templates fill the gaps
+                custom.extend(&built_in)?;
+                custom
+            }
+            None => built_in,
+        };
+
+        let templates = Self { tera };
+        templates.check_defaults()?;
+
+        Ok(templates)
+    }
+
+    /// Every email needs a default locale template to fall back to
+    fn check_defaults(&self) -> Result<(), AuthenticationError> {
+        for template in EmailTemplate::iter() {
+            for extension in ["subject", "txt"] {
+                let name =
+                    format!("{}/{template}.{extension}", domain::DEFAULT_LOCALE);
+                if !self.has_template(&name) {
+                    return Err(AuthenticationError::ConfigurationMissing(format!(
+                        "email template {name}"
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn has_template(&self, name: &str) -> bool {
+        self.tera
+            .get_template_names()
+            .any(|template| template == name)
+    }
+
+    /// The locale the template is rendered in, the first fallback that has it
+    pub fn resolve_locale<'a>(
+        &self,
+        template: EmailTemplate,
+        locale: &'a domain::Locale,
+    ) -> &'a str {
+        locale
+            .fallbacks()
+            .into_iter()
+            .find(|candidate| {
+                self.has_template(&format!("{candidate}/{template}.txt"))
+            })
+            .unwrap_or(domain::DEFAULT_LOCALE)
+    }
+
+    /// Render an email for the recipient in their locale
+    ///
+    /// ## Parameters
+    ///
+    /// - `template: EmailTemplate` - The email to render
+    /// - `to: &domain::EmailAddress` - The recipient
+    /// - `locale: &domain::Locale` - The recipient's locale
+    /// - `context: &Context` - The template variables
+    pub fn render(
+        &self,
+        template: EmailTemplate,
+        to: &domain::EmailAddress,
+        locale: &domain::Locale,
+        context: &Context,
+    ) -> Result<EmailMessage, AuthenticationError> {
+        let resolved = self.resolve_locale(template, locale);
+
+        let subject = self
+            .tera
+            .render(&format!("{resolved}/{template}.subject"), context)?;
+        let body_text = self
+            .tera
+            .render(&format!("{resolved}/{template}.txt"), context)?;
+
+        Ok(EmailMessage {
+            to: to.to_owned(),
+            subject: subject.trim().to_string(),
+            body_text,
+        })
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    fn reset_context() -> Context {
+        let mut context = Context::new();
+        context.insert("name", "Ada");
+        context.insert("reset_url", "https://example.com/reset?token=abc");
+        context.insert("expires_in_minutes", &30);
+        context
+    }
+
+    #[test]
+    fn renders_in_user_locale_with_fallback() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let templates = EmailTemplates::new(&EmailConfiguration::default())?;
+        let to = domain::EmailAddress::parse("ada@example.com")?;
+
+        //-- Execute Function (Act)
+        let french = templates.render(
+            EmailTemplate::PasswordReset,
+            &to,
+            &domain::Locale::parse("fr-CA")?,
+            &reset_context(),
+        )?;
+        let german = templates.render(
+            EmailTemplate::PasswordReset,
+            &to,
+            &domain::Locale::parse("de")?,
+            &reset_context(),
+        )?;
+
+        //-- Checks (Assertions)
+        assert_eq!(french.subject, "Réinitialisez votre mot de passe");
+        assert!(french
+            .body_text
+            .contains("https://example.com/reset?token=abc"));
+        assert_eq!(german.subject, "Reset your password");
+        assert!(german.body_text.contains("30 minutes"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn forced_password_resets_are_worded_for_the_admin() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let templates = EmailTemplates::new(&EmailConfiguration::default())?;
+        let to = domain::EmailAddress::parse("ada@example.com")?;
+        let mut context = reset_context();
+        context.insert("forced", &true);
+
+        //-- Execute Function (Act)
+        let forced = templates.render(
+            EmailTemplate::PasswordReset,
+            &to,
+            &domain::Locale::default(),
+            &context,
+        )?;
+
+        //-- Checks (Assertions)
+        assert!(forced.body_text.contains("An administrator has reset your password"));
+        assert!(!forced.body_text.contains("ignore this email"));
+        assert!(forced.body_text.contains("https://example.com/reset?token=abc"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn custom_templates_replace_built_in() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let directory = std::env::temp_dir()
+            .join(format!("email-templates-{}", uuid::Uuid::now_v7()));
+        std::fs::create_dir_all(directory.join("en"))?;
+        std::fs::create_dir_all(directory.join("de"))?;
+        std::fs::write(directory.join("en/password_reset.subject"), "Custom reset")?;
+        std::fs::write(
+            directory.join("de/password_reset.subject"),
+            "Passwort zurücksetzen",
+        )?;
+        std::fs::write(directory.join("de/password_reset.txt"), "Hallo {{ name }}")?;
+
+        let config = EmailConfiguration {
+            template_directory: Some(directory.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let templates = EmailTemplates::new(&config)?;
+        let to = domain::EmailAddress::parse("ada@example.com")?;
+
+        //-- Execute Function (Act)
+        let english = templates.render(
+            EmailTemplate::PasswordReset,
+            &to,
+            &domain::Locale::default(),
+            &reset_context(),
+        )?;
+        let german = templates.render(
+            EmailTemplate::PasswordReset,
+            &to,
+            &domain::Locale::parse("de")?,
+            &reset_context(),
+        )?;
+
+        //-- Checks (Assertions)
+        assert_eq!(english.subject, "Custom reset");
+        assert!(english.body_text.contains("reset your password"));
+        assert_eq!(german.subject, "Passwort zurücksetzen");
+        assert_eq!(german.body_text, "Hallo Ada");
+
+        std::fs::remove_dir_all(directory)?;
+
+        Ok(())
+    }
+}
@@ -11,6 +11,27 @@
 //! * [How to Handle Errors in Rust: A Comprehensive Guide](https://dev.to/nathan20/how-to-handle-errors-in-rust-a-comprehensive-guide-1cco)
 //! * [Rust Error Types Explained: Building Robust Error Handling](https://marketsplash.com/rust-error-types/)
 
+use tonic_types::{ErrorDetails, StatusExt};
+
+use crate::domain::EmailDomainRejection;
+use crate::rpc::proto::ErrorCode;
+use crate::services::validation::FieldViolation;
+
+/// Metadata listing the policies a user must accept, as `policy=version` pairs
+/// separated by commas
+pub static POLICY_ACCEPTANCE_HEADER: &str = "x-policy-acceptance-required";
+
+/// Metadata with the reason code a registration's email domain was refused,
+/// e.g. `EMAIL_DOMAIN_BLOCKED`
+pub static EMAIL_DOMAIN_REJECTED_HEADER: &str = "x-email-domain-rejected";
+
+/// Metadata with the stable machine readable code of an error, e.g.
+/// `LOGIN_THROTTLED`, see `error_messages` for the localised messages
+pub static ERROR_CODE_HEADER: &str = "x-error-code";
+
+/// Metadata with the request field an error is about, e.g. `email`
+pub static ERROR_FIELD_HEADER: &str = "x-error-field";
+
 /// Static errors types
 #[derive(thiserror::Error, Debug)]
 pub enum AuthenticationError {
@@ -32,6 +53,10 @@ pub enum AuthenticationError {
     #[error("Name format is invalid: {0}")]
     UserNameFormatInvalid(String),
 
+    /// The name is reserved or contains a word on the profanity list
+    #[error("Name is not allowed: {0}")]
+    UserNameNotAllowed(String),
+
     #[error("Password does not meet minimum requirements")]
     PasswordFormatInvalid,
 
@@ -68,6 +93,66 @@ pub enum AuthenticationError {
         message: String,
     },
 
+    /// Email could not be built or delivered
+    #[error("Email delivery error: {0}")]
+    EmailDelivery(String),
+
+    /// Event could not be published to the event bus
+    #[error("Event bus error: {0}")]
+    EventBus(String),
+
+    /// The request needs a valid CAPTCHA token
+    #[error("CAPTCHA required: {0}")]
+    CaptchaRequired(String),
+
+    /// Too many failed logins, carries the seconds until logins are allowed again
+    #[error("Too many failed logins, retry after {0} seconds")]
+    LoginThrottled(u64),
+
+    /// The user must accept the policies first, carries each `policy=version`
+    #[error("Policy acceptance required: {}", .0.join(", "))]
+    PolicyAcceptanceRequired(Vec<String>),
+
+    /// An admin forced a password reset, the user must complete it to log in
+    #[error("Password change required")]
+    PasswordChangeRequired,
+
+    /// Too many passwords waiting to be hashed, see `services::PasswordHasher`
+    #[error("Password hashing queue is full")]
+    PasswordHashQueueFull,
+
+    /// A query took longer than its budget, see `database::timeouts`
+    #[error("Database query timed out: {0}")]
+    QueryTimeout(&'static str),
+
+    /// The database circuit is open, see `database::CircuitBreaker`
+    #[error("Database is unavailable")]
+    DatabaseUnavailable,
+
+    /// The LDAP directory could not be reached or searched
+    #[error("Directory error: {0}")]
+    Directory(String),
+
+    /// Registration was refused for the email address domain
+    #[error("Email domain rejected: {0}")]
+    EmailDomainRejected(EmailDomainRejection),
+
+    /// Request fields are missing or malformed, see `services::validation`
+    #[error("Invalid request: {}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    InvalidRequest(Vec<FieldViolation>),
+
+    /// An uploaded avatar is too large or not a supported image
+    #[error("Invalid image: {0}")]
+    InvalidImage(String),
+
+    /// The avatar storage backend could not be reached
+    #[error("Avatar storage error: {0}")]
+    AvatarStorage(String),
+
+    /// Required configuration keys are not set in any configuration layer
+    #[error("Missing configuration keys: {0}")]
+    ConfigurationMissing(String),
+
     //-- External errors
     /// Derive IO errors
     #[error(transparent)]
@@ -108,20 +193,221 @@ pub enum AuthenticationError {
     #[error("json web token: {0}")]
     JsonWebToken(#[from] jsonwebtoken::errors::Error),
 
+    #[error("email template: {0}")]
+    Template(#[from] tera::Error),
+
+    #[error("webhook request: {0}")]
+    Webhook(#[from] reqwest::Error),
+
     // Tonic Reflections errors
     #[error(transparent)]
     TonicReflection(#[from] tonic_reflection::server::Error),
 
     #[error(transparent)]
     Chrono(#[from] chrono::ParseError),
+
+    // Configuration file watcher errors
+    #[error(transparent)]
+    Notify(#[from] notify::Error),
+}
+
+impl AuthenticationError {
+    /// The stable code of the error, sent to clients in an `ErrorDetail` so
+    /// they can branch on it without parsing the message
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            AuthenticationError::AuthenticationError(_) => ErrorCode::AuthenticationFailed,
+            AuthenticationError::CaptchaRequired(_) => ErrorCode::CaptchaRequired,
+            AuthenticationError::LoginThrottled(_) => ErrorCode::LoginThrottled,
+            AuthenticationError::PolicyAcceptanceRequired(_) => {
+                ErrorCode::PolicyAcceptanceRequired
+            }
+            AuthenticationError::PasswordChangeRequired => ErrorCode::PasswordChangeRequired,
+            AuthenticationError::PasswordHashQueueFull => ErrorCode::ServerAtCapacity,
+            AuthenticationError::Directory(_) => ErrorCode::DirectoryUnavailable,
+            AuthenticationError::DatabaseUnavailable => ErrorCode::DatabaseUnavailable,
+            AuthenticationError::QueryTimeout(_) => ErrorCode::DeadlineExceeded,
+            AuthenticationError::EmailIsEmpty | AuthenticationError::EmailFormatInvalid(_) => {
+                ErrorCode::EmailInvalid
+            }
+            AuthenticationError::UserNameFormatInvalid(_) => ErrorCode::UserNameInvalid,
+            AuthenticationError::UserNameNotAllowed(_) => ErrorCode::UserNameNotAllowed,
+            AuthenticationError::PasswordFormatInvalid => ErrorCode::PasswordInvalid,
+            AuthenticationError::UserRole => ErrorCode::UserRoleInvalid,
+            AuthenticationError::InvalidToken(_) => ErrorCode::TokenInvalid,
+            AuthenticationError::TokenExpired => ErrorCode::TokenExpired,
+            AuthenticationError::ValidationError(_) | AuthenticationError::InvalidRequest(_) => {
+                ErrorCode::ValidationFailed
+            }
+            AuthenticationError::ConstraintViolation { .. } => ErrorCode::ConstraintViolation,
+            AuthenticationError::EmailDomainRejected(rejection) => match rejection {
+                EmailDomainRejection::NotAllowed => ErrorCode::EmailDomainNotAllowed,
+                EmailDomainRejection::Blocked => ErrorCode::EmailDomainBlocked,
+                EmailDomainRejection::Disposable => ErrorCode::EmailDomainDisposable,
+            },
+            AuthenticationError::InvalidImage(_) => ErrorCode::InvalidImage,
+            AuthenticationError::AvatarStorage(_) => ErrorCode::AvatarStorageUnavailable,
+            // Failures inside the service, the details stay in the logs
+            AuthenticationError::Generic(_)
+            | AuthenticationError::Static(_)
+            | AuthenticationError::PasswordParseError
+            | AuthenticationError::DatabaseError(_)
+            | AuthenticationError::EmailDelivery(_)
+            | AuthenticationError::EventBus(_)
+            | AuthenticationError::ConfigurationMissing(_)
+            | AuthenticationError::IO(_)
+            | AuthenticationError::Config(_)
+            | AuthenticationError::TonicTransport(_)
+            | AuthenticationError::AddressParse(_)
+            | AuthenticationError::LogError(_)
+            | AuthenticationError::TracingError(_)
+            | AuthenticationError::SqlxMigration(_)
+            | AuthenticationError::Sqlx(_)
+            | AuthenticationError::Uuid(_)
+            | AuthenticationError::Json(_)
+            | AuthenticationError::JsonWebToken(_)
+            | AuthenticationError::Template(_)
+            | AuthenticationError::Webhook(_)
+            | AuthenticationError::TonicReflection(_)
+            | AuthenticationError::Chrono(_)
+            | AuthenticationError::Notify(_) => ErrorCode::Internal,
+        }
+    }
+
+    /// The stable code as text, e.g. `LOGIN_THROTTLED`, sent in the
+    /// `x-error-code` metadata and the key of the error's localised message
+    pub fn code(&self) -> &'static str {
+        error_code_name(self.error_code())
+    }
+
+    /// The request field the error is about, if it is about one
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            AuthenticationError::EmailIsEmpty
+            | AuthenticationError::EmailFormatInvalid(_)
+            | AuthenticationError::EmailDomainRejected(_) => Some("email"),
+            AuthenticationError::UserNameFormatInvalid(_)
+            | AuthenticationError::UserNameNotAllowed(_) => Some("name"),
+            AuthenticationError::PasswordFormatInvalid => Some("password"),
+            AuthenticationError::CaptchaRequired(_) => Some("captcha_token"),
+            AuthenticationError::InvalidImage(_) => Some("chunk"),
+            AuthenticationError::ConstraintViolation { field, .. } => Some(field.as_str()),
+            // The first, every field is listed in the status details
+            AuthenticationError::InvalidRequest(violations) => {
+                violations.first().map(|violation| violation.field.as_str())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The text of an error code, its proto name without the `ERROR_CODE_`
+/// prefix, e.g. `LOGIN_THROTTLED`
+pub fn error_code_name(code: ErrorCode) -> &'static str {
+    let name = code.as_str_name();
+    name.strip_prefix("ERROR_CODE_").unwrap_or(name)
+}
+
+/// The error code of its text, `UNSPECIFIED` when it is not a code
+pub fn error_code_from_name(name: &str) -> ErrorCode {
+    ErrorCode::from_str_name(&format!("ERROR_CODE_{name}")).unwrap_or(ErrorCode::Unspecified)
 }
 
 impl From<AuthenticationError> for tonic::Status {
     fn from(authentication_error: AuthenticationError) -> tonic::Status {
-        match authentication_error {
+        let code = authentication_error.code();
+        let field = authentication_error.field().map(str::to_string);
+
+        // The details of internal errors stay in the logs and error reports
+        if authentication_error.error_code() == ErrorCode::Internal {
+            crate::error_reporting::capture_error(&authentication_error);
+        }
+
+        // Count the failures to reach the database towards opening the circuit
+        if let AuthenticationError::Sqlx(error) = &authentication_error {
+            if crate::database::is_connection_error(error) {
+                crate::database::circuit_breaker().record_failure();
+            }
+        }
+
+        let mut status = match authentication_error {
             AuthenticationError::AuthenticationError(m) => {
                 tonic::Status::unauthenticated(m)
             }
+            AuthenticationError::CaptchaRequired(m) => {
+                tonic::Status::failed_precondition(m)
+            }
+            AuthenticationError::LoginThrottled(retry_after) => {
+                let mut status = tonic::Status::resource_exhausted(
+                    "Too many failed logins, try again later",
+                );
+                // Hint when to retry, copied to the Retry-After header by the gateway
+                status.metadata_mut().insert(
+                    "retry-after",
+                    tonic::metadata::MetadataValue::from(retry_after),
+                );
+                status
+            }
+            AuthenticationError::PolicyAcceptanceRequired(policies) => {
+                let mut status =
+                    tonic::Status::failed_precondition("PolicyAcceptanceRequired");
+                // The policies to accept, so clients can show them before AcceptPolicy
+                if let Ok(value) = tonic::metadata::MetadataValue::try_from(policies.join(","))
+                {
+                    status.metadata_mut().insert(POLICY_ACCEPTANCE_HEADER, value);
+                }
+                status
+            }
+            AuthenticationError::PasswordChangeRequired => {
+                tonic::Status::failed_precondition("PasswordChangeRequired")
+            }
+            AuthenticationError::PasswordHashQueueFull => tonic::Status::unavailable(
+                "Server is at capacity, retry with a backoff",
+            ),
+            AuthenticationError::Directory(_) => {
+                tonic::Status::unavailable("Directory is unavailable")
+            }
+            AuthenticationError::DatabaseUnavailable => {
+                tonic::Status::unavailable("Database is unavailable, retry with a backoff")
+            }
+            AuthenticationError::QueryTimeout(_) => {
+                tonic::Status::deadline_exceeded("Database query took too long")
+            }
+            AuthenticationError::UserNameNotAllowed(_) => {
+                tonic::Status::invalid_argument("Name is not allowed")
+            }
+            AuthenticationError::EmailDomainRejected(rejection) => {
+                let mut status = tonic::Status::invalid_argument(rejection.to_string());
+                // The reason code, so clients can explain why without parsing the message
+                status.metadata_mut().insert(
+                    EMAIL_DOMAIN_REJECTED_HEADER,
+                    tonic::metadata::MetadataValue::from_static(rejection.code()),
+                );
+                status
+            }
+            AuthenticationError::InvalidRequest(ref violations) => {
+                // Every invalid field, so clients can show them all at once
+                let details = ErrorDetails::with_bad_request(
+                    violations
+                        .iter()
+                        .map(|violation| {
+                            tonic_types::FieldViolation::new(
+                                &violation.field,
+                                &violation.description,
+                            )
+                        })
+                        .collect::<Vec<_>>(),
+                );
+                tonic::Status::with_error_details(
+                    tonic::Code::InvalidArgument,
+                    authentication_error.to_string(),
+                    details,
+                )
+            }
+            AuthenticationError::InvalidImage(m) => tonic::Status::invalid_argument(m),
+            AuthenticationError::AvatarStorage(_) => {
+                tonic::Status::unavailable("Avatar storage is unavailable")
+            }
             // BackendError::EmailFormatInvalid(_) => {
             //     Status::invalid_argument(format!("{:?}", backend_error))
             // }
@@ -141,6 +427,18 @@ impl From<AuthenticationError> for tonic::Status {
             // BackendError::HashingError => tonic::Status::unavailable(format!("{:?}", backend_error)),
             // _ => tonic::Status::unknown(format!("{:?}", backend_error)),
             _ => tonic::Status::internal("Internal server error"),
+        };
+
+        // The stable code, so the message can be localised for the client
+        status.metadata_mut().insert(
+            ERROR_CODE_HEADER,
+            tonic::metadata::MetadataValue::from_static(code),
+        );
+        if let Some(value) =
+            field.and_then(|field| tonic::metadata::MetadataValue::try_from(field).ok())
+        {
+            status.metadata_mut().insert(ERROR_FIELD_HEADER, value);
         }
+        status
     }
 }
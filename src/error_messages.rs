@@ -0,0 +1,258 @@
+//-- ./src/error_messages.rs
+
+// #![allow(unused)] // For development only
+
+//! # Error Messages
+//!
+//! Localised messages for the errors returned to clients, keyed by a stable
+//! code such as `LOGIN_THROTTLED` or `EMAIL_DOMAIN_BLOCKED`, the proto
+//! `ErrorCode` names without their `ERROR_CODE_` prefix, see
+//! `AuthenticationError::error_code`. Errors without their own code use the
+//! name of their gRPC status code, e.g. `NOT_FOUND`.
+//!
+//! Each locale is a JSON object of `code: message` pairs named
+//! `<locale>.json`. English and French are built in, from `templates/errors`,
+//! and `error_messages.translation_directory` can add locales or replace built
+//! in messages. A message missing from a locale falls back to its language
+//! (e.g. `pt-BR` then `pt`), then `en`.
+//!
+//! `middleware::ErrorMessagesLayer` picks the locale from the request's
+//! `accept-language` metadata and adds the code and message to the status
+//! details.
+//! ---
+
+use std::collections::HashMap;
+
+use crate::configuration::ErrorMessagesConfiguration;
+use crate::domain;
+use crate::prelude::*;
+
+/// Built in translations, as (locale, JSON messages) pairs
+const BUILT_IN_TRANSLATIONS: [(&str, &str); 2] = [
+    ("en", include_str!("../templates/errors/en.json")),
+    ("fr", include_str!("../templates/errors/fr.json")),
+];
+
+/// The error messages of each locale, keyed by error code
+#[derive(Debug, Clone)]
+pub struct ErrorMessages {
+    locales: HashMap<String, HashMap<String, String>>,
+    help_url: Option<String>,
+}
+
+impl ErrorMessages {
+    /// Load the built in translations, then the translation files from
+    /// `error_messages.translation_directory` over the top of them
+    pub fn new(config: &ErrorMessagesConfiguration) -> Result<Self, AuthenticationError> {
+        let mut messages = Self {
+            locales: HashMap::new(),
+            help_url: config.help_url.clone(),
+        };
+
+        for (locale, translations) in BUILT_IN_TRANSLATIONS {
+            messages.add(domain::Locale::parse(locale)?, serde_json::from_str(translations)?);
+        }
+
+        if let Some(directory) = &config.translation_directory {
+            for entry in std::fs::read_dir(directory)? {
+                let path = entry?.path();
+                if path.extension().is_none_or(|extension| extension != "json") {
+                    continue;
+                }
+
+                let locale = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .unwrap_or_default();
+                let locale = domain::Locale::parse(locale)?;
+                let translations = std::fs::read_to_string(&path)?;
+                // Custom messages win, built in messages fill the gaps
+                messages.add(locale, serde_json::from_str(&translations)?);
+            }
+        }
+
+        Ok(messages)
+    }
+
+    fn add(&mut self, locale: domain::Locale, translations: HashMap<String, String>) {
+        self.locales
+            .entry(locale.to_string())
+            .or_default()
+            .extend(translations);
+    }
+
+    /// The locale to answer in, the first language in an `accept-language`
+    /// header, by preference, that has messages. `en` when none do.
+    ///
+    /// ## Parameters
+    ///
+    /// - `accept_language: &str` - The header, e.g. `fr-CA, fr;q=0.9, en;q=0.8`
+    pub fn negotiate(&self, accept_language: &str) -> domain::Locale {
+        let mut languages: Vec<(f32, &str)> = accept_language
+            .split(',')
+            .filter_map(|language| {
+                let mut parameters = language.split(';');
+                let tag = parameters.next()?.trim();
+                let quality = match parameters.find_map(|p| p.trim().strip_prefix("q=")) {
+                    Some(quality) => quality.trim().parse().ok()?,
+                    None => 1.0,
+                };
+                (quality > 0.0).then_some((quality, tag))
+            })
+            .collect();
+        // Stable, so languages of equal preference keep the header's order
+        languages.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        languages
+            .into_iter()
+            .filter_map(|(_, tag)| domain::Locale::parse(tag).ok())
+            .find_map(|locale| self.supported(&locale))
+            .unwrap_or_default()
+    }
+
+    /// The locale, or the closest of its parents (e.g. `pt` for `pt-BR`), that
+    /// has messages
+    fn supported(&self, locale: &domain::Locale) -> Option<domain::Locale> {
+        std::iter::successors(Some(locale.as_ref()), |tag| {
+            tag.rsplit_once('-').map(|(parent, _)| parent)
+        })
+        .find(|tag| self.locales.contains_key(*tag))
+        .map(|tag| domain::Locale::from(tag.to_string()))
+    }
+
+    /// The link to help with an error code, from `error_messages.help_url`
+    pub fn help_url(&self, code: &str) -> Option<String> {
+        self.help_url
+            .as_ref()
+            .map(|help_url| help_url.replace("{code}", code))
+    }
+
+    /// The message for an error code in the locale, or the first of its
+    /// fallbacks that has it, as (locale, message)
+    pub fn message(&self, locale: &domain::Locale, code: &str) -> Option<(&str, &str)> {
+        locale.fallbacks().into_iter().find_map(|candidate| {
+            let (locale, messages) = self.locales.get_key_value(candidate)?;
+            messages
+                .get(code)
+                .map(|message| (locale.as_str(), message.as_str()))
+        })
+    }
+}
+
+/// The error code of a gRPC status code, for errors without their own code
+pub fn status_code_name(code: tonic::Code) -> &'static str {
+    match code {
+        tonic::Code::Ok => "OK",
+        tonic::Code::Cancelled => "CANCELLED",
+        tonic::Code::Unknown => "UNKNOWN",
+        tonic::Code::InvalidArgument => "INVALID_ARGUMENT",
+        tonic::Code::DeadlineExceeded => "DEADLINE_EXCEEDED",
+        tonic::Code::NotFound => "NOT_FOUND",
+        tonic::Code::AlreadyExists => "ALREADY_EXISTS",
+        tonic::Code::PermissionDenied => "PERMISSION_DENIED",
+        tonic::Code::ResourceExhausted => "RESOURCE_EXHAUSTED",
+        tonic::Code::FailedPrecondition => "FAILED_PRECONDITION",
+        tonic::Code::Aborted => "ABORTED",
+        tonic::Code::OutOfRange => "OUT_OF_RANGE",
+        tonic::Code::Unimplemented => "UNIMPLEMENTED",
+        tonic::Code::Internal => "INTERNAL",
+        tonic::Code::Unavailable => "UNAVAILABLE",
+        tonic::Code::DataLoss => "DATA_LOSS",
+        tonic::Code::Unauthenticated => "UNAUTHENTICATED",
+    }
+}
+
+/// The gRPC status code of an error code name, e.g. `UNAVAILABLE`
+pub fn status_code_from_name(name: &str) -> Option<tonic::Code> {
+    (0..=16)
+        .map(tonic::Code::from_i32)
+        .find(|code| status_code_name(*code) == name)
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{error_code_from_name, error_code_name};
+    use crate::rpc::proto::ErrorCode;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    fn built_in() -> Result<ErrorMessages> {
+        Ok(ErrorMessages::new(&ErrorMessagesConfiguration::default())?)
+    }
+
+    #[test]
+    fn every_error_code_has_a_built_in_message() -> Result<()> {
+        let messages = built_in()?;
+        // Every proto error code, skipping UNSPECIFIED
+        let codes = (1..=u8::MAX as i32)
+            .filter_map(|value| ErrorCode::try_from(value).ok())
+            .map(error_code_name);
+
+        for code in codes {
+            for locale in ["en", "fr"] {
+                let (resolved, _) = messages
+                    .message(&domain::Locale::parse(locale)?, code)
+                    .ok_or(format!("{code} has no message"))?;
+                assert_eq!(resolved, locale, "{code} is not translated to {locale}");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn status_codes_are_error_codes() {
+        for value in 1..=16 {
+            let code = tonic::Code::from_i32(value);
+            assert_ne!(
+                error_code_from_name(status_code_name(code)),
+                ErrorCode::Unspecified,
+                "{code:?} is not an error code"
+            );
+            assert_eq!(status_code_from_name(status_code_name(code)), Some(code));
+        }
+    }
+
+    #[test]
+    fn the_preferred_supported_language_is_chosen() -> Result<()> {
+        let messages = built_in()?;
+
+        assert_eq!(messages.negotiate("fr-CA, en;q=0.8").as_ref(), "fr");
+        assert_eq!(messages.negotiate("de, en;q=0.5, fr;q=0.9").as_ref(), "fr");
+        assert_eq!(messages.negotiate("de, fr;q=0").as_ref(), "en");
+        assert_eq!(messages.negotiate("*").as_ref(), "en");
+        assert_eq!(messages.negotiate("").as_ref(), "en");
+
+        Ok(())
+    }
+
+    #[test]
+    fn missing_messages_fall_back_to_english() -> Result<()> {
+        let directory = std::env::temp_dir().join(uuid::Uuid::now_v7().to_string());
+        std::fs::create_dir(&directory)?;
+        std::fs::write(
+            directory.join("pt-BR.json"),
+            r#"{ "NOT_FOUND": "Não encontrado" }"#,
+        )?;
+        std::fs::write(directory.join("README.md"), "Not a translation")?;
+        let messages = ErrorMessages::new(&ErrorMessagesConfiguration {
+            translation_directory: Some(directory.to_string_lossy().to_string()),
+            ..Default::default()
+        })?;
+        let locale = messages.negotiate("pt-BR");
+
+        assert_eq!(locale.as_ref(), "pt-BR");
+        assert_eq!(messages.message(&locale, "NOT_FOUND"), Some(("pt-BR", "Não encontrado")));
+        assert_eq!(
+            messages.message(&locale, "LOGIN_THROTTLED"),
+            Some(("en", "Too many failed logins, try again later"))
+        );
+        assert_eq!(messages.message(&locale, "NOT_A_CODE"), None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+//-- ./src/error_reporting.rs
+
+// #![allow(unused)] // For development only
+
+//! # Error Reporting
+//!
+//! Reports internal errors, such as database failures, and panics to Sentry,
+//! so they are noticed without reading the logs. Only built with the `sentry`
+//! feature, and only reported when `error_reporting.dsn` is set, otherwise
+//! reporting does nothing.
+//!
+//! Errors are reported with the release, `authentication_service@<version>+<git
+//! sha>`, and the environment. `middleware::ErrorReportingLayer` gives each
+//! request its own scope tagged with the gRPC path and request id, so an
+//! error reported while serving a request has the request's context. Users'
+//! IP addresses and other personal information are not sent.
+//! ---
+
+use crate::build_info;
+use crate::configuration::ErrorReportingConfiguration;
+use crate::prelude::*;
+
+/// Keeps error reporting running, flushing queued reports when dropped
+#[derive(Default)]
+pub struct ErrorReporting {
+    #[cfg(feature = "sentry")]
+    _guard: Option<sentry::ClientInitGuard>,
+}
+
+/// The release errors are reported under, e.g.
+/// `authentication_service@0.1.2+1a2b3c4`
+pub fn release() -> String {
+    format!(
+        "authentication_service@{}+{}",
+        build_info::VERSION,
+        build_info::GIT_SHA
+    )
+}
+
+/// Start reporting errors to the `error_reporting.dsn`, before tracing starts
+/// so panics while starting are reported
+pub fn init(config: &ErrorReportingConfiguration) -> Result<ErrorReporting, AuthenticationError> {
+    #[cfg(feature = "sentry")]
+    {
+        use secrecy::ExposeSecret;
+
+        let Some(dsn) = &config.dsn else {
+            return Ok(ErrorReporting::default());
+        };
+        let dsn = dsn.expose_secret().parse().map_err(|e| {
+            AuthenticationError::ValidationError(format!("Invalid error_reporting.dsn: {e}"))
+        })?;
+        let environment = config
+            .environment
+            .clone()
+            .or_else(|| std::env::var("APP_ENVIRONMENT").ok())
+            .unwrap_or_else(|| "development".to_string());
+
+        let guard = sentry::init(sentry::ClientOptions {
+            dsn: Some(dsn),
+            release: Some(release().into()),
+            environment: Some(environment.into()),
+            sample_rate: config.sample_rate,
+            send_default_pii: false,
+            ..Default::default()
+        });
+
+        Ok(ErrorReporting {
+            _guard: Some(guard),
+        })
+    }
+
+    #[cfg(not(feature = "sentry"))]
+    {
+        if config.dsn.is_some() {
+            tracing::warn!("error_reporting.dsn is set, but the `sentry` feature is off");
+        }
+        Ok(ErrorReporting::default())
+    }
+}
+
+/// Report an internal error, with the scope of the request being served
+pub fn capture_error(error: &AuthenticationError) {
+    #[cfg(feature = "sentry")]
+    sentry::capture_error(error);
+
+    #[cfg(not(feature = "sentry"))]
+    let _ = error;
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reporting_is_off_without_a_dsn() -> Result<(), AuthenticationError> {
+        //-- Setup and Fixtures (Arrange)
+        let config = ErrorReportingConfiguration::default();
+
+        //-- Execute Function (Act)
+        let _reporting = init(&config)?;
+        capture_error(&AuthenticationError::Static("Not reported"));
+
+        //-- Checks (Assertions)
+        assert!(release().starts_with(&format!("authentication_service@{}+", build_info::VERSION)));
+
+        Ok(())
+    }
+}
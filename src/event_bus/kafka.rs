@@ -0,0 +1,84 @@
+//-- ./src/event_bus/kafka.rs
+
+//! Event publisher for Kafka topics using rdkafka.
+//!
+//! Messages are keyed by user id, so each user's events stay in order on one
+//! partition, and carry an `event-id` header for de-duplication.
+//! ---
+
+use std::time::Duration;
+
+use rdkafka::message::{Header, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::ClientConfig;
+
+use crate::configuration::EventBusConfiguration;
+use crate::event_bus::EventPublisher;
+use crate::events::AuthEvent;
+use crate::prelude::*;
+
+/// Header carrying the event id
+static EVENT_ID_HEADER: &str = "event-id";
+
+/// How long to wait for the brokers to acknowledge a message
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Publishes authentication events to Kafka topics
+#[derive(Clone)]
+pub struct KafkaEventPublisher {
+    producer: FutureProducer,
+}
+
+impl KafkaEventPublisher {
+    /// Build a producer for the bootstrap servers in the event bus configuration
+    pub fn new(config: &EventBusConfiguration) -> Result<Self, AuthenticationError> {
+        let brokers = config.url.as_deref().ok_or_else(|| {
+            AuthenticationError::ValidationError(
+                "event_bus.url is required when event_bus.transport is kafka"
+                    .to_string(),
+            )
+        })?;
+
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set(
+                "message.timeout.ms",
+                DELIVERY_TIMEOUT.as_millis().to_string(),
+            )
+            .create()
+            .map_err(|e| AuthenticationError::EventBus(e.to_string()))?;
+
+        Ok(Self { producer })
+    }
+}
+
+#[tonic::async_trait]
+impl EventPublisher for KafkaEventPublisher {
+    #[tracing::instrument(name = "Kafka publish: ", skip(self, event, payload), fields(event_id = %event.id))]
+    async fn publish(
+        &self,
+        subject: &str,
+        event: &AuthEvent,
+        payload: &str,
+    ) -> Result<(), AuthenticationError> {
+        // Global events, such as revoking every session, have no user
+        let key = event.user_id.unwrap_or(event.id).to_string();
+        let event_id = event.id.to_string();
+        let headers = OwnedHeaders::new().insert(Header {
+            key: EVENT_ID_HEADER,
+            value: Some(&event_id),
+        });
+
+        let record = FutureRecord::to(subject)
+            .key(&key)
+            .payload(payload)
+            .headers(headers);
+
+        self.producer
+            .send(record, DELIVERY_TIMEOUT)
+            .await
+            .map_err(|(e, _message)| AuthenticationError::EventBus(e.to_string()))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+//-- ./src/event_bus/mod.rs
+
+// #![allow(unused)] // For development only
+
+//! # Event Bus Module
+//!
+//! Publish authentication events to Kafka or NATS, so other microservices can
+//! consume identity changes (registrations, verifications, logins, ...)
+//! without polling.
+//!
+//! Events are published from the transactional outbox (see `services::outbox`)
+//! to `<event_bus.subject_prefix>.<event type>`, e.g. `auth.user.login`, using
+//! the webhook event types and JSON payload (see `services::webhooks`).
+//! Delivery is at least once, so consumers should de-duplicate on the event
+//! `id`.
+//!
+//! ## Publishers
+//! - **NatsEventPublisher**: Publishes to NATS subjects, behind the `nats` feature
+//! - **KafkaEventPublisher**: Publishes to Kafka topics keyed by user id, behind
+//!   the `kafka` feature
+//! ---
+
+use std::sync::Arc;
+
+use crate::configuration::{EventBusConfiguration, EventBusTransport};
+use crate::events::AuthEvent;
+use crate::prelude::*;
+use crate::services::webhooks;
+
+#[cfg(feature = "kafka")]
+mod kafka;
+#[cfg(feature = "nats")]
+mod nats;
+
+#[cfg(feature = "kafka")]
+pub use kafka::KafkaEventPublisher;
+#[cfg(feature = "nats")]
+pub use nats::NatsEventPublisher;
+
+/// Publishes authentication events to an event bus
+#[tonic::async_trait]
+pub trait EventPublisher: Send + Sync {
+    /// Publish an event's JSON payload to the subject (or topic)
+    async fn publish(
+        &self,
+        subject: &str,
+        event: &AuthEvent,
+        payload: &str,
+    ) -> Result<(), AuthenticationError>;
+}
+
+/// The subject (or topic) an event is published to
+pub fn subject(prefix: &str, event: &AuthEvent) -> String {
+    format!("{prefix}.{}", webhooks::event_type(event.kind))
+}
+
+/// Publish an event with the given publisher, using the configured prefix
+pub async fn publish(
+    publisher: &dyn EventPublisher,
+    config: &EventBusConfiguration,
+    event: &AuthEvent,
+) -> Result<(), AuthenticationError> {
+    let subject = subject(&config.subject_prefix, event);
+    let payload = webhooks::payload(event)?;
+
+    publisher.publish(&subject, event, &payload).await
+}
+
+/// Build the event publisher for the configured transport, `None` when events
+/// are not published
+pub async fn publisher_from_config(
+    config: &EventBusConfiguration,
+) -> Result<Option<Arc<dyn EventPublisher>>, AuthenticationError> {
+    match config.transport {
+        EventBusTransport::None => Ok(None),
+        #[cfg(feature = "nats")]
+        EventBusTransport::Nats => {
+            Ok(Some(Arc::new(NatsEventPublisher::connect(config).await?)))
+        }
+        #[cfg(feature = "kafka")]
+        EventBusTransport::Kafka => {
+            Ok(Some(Arc::new(KafkaEventPublisher::new(config)?)))
+        }
+        #[allow(unreachable_patterns)]
+        transport => Err(AuthenticationError::ValidationError(format!(
+            "event_bus.transport {transport} requires the `{transport}` feature"
+        ))),
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::events::AuthEventKind;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn subjects_use_the_prefix_and_event_type() {
+        let event = AuthEvent::new(AuthEventKind::Verification);
+
+        assert_eq!(subject("auth", &event), "auth.user.verified");
+        assert_eq!(
+            subject("identity.prod", &AuthEvent::new(AuthEventKind::Login)),
+            "identity.prod.user.login"
+        );
+    }
+
+    #[tokio::test]
+    async fn no_publisher_without_a_transport() -> Result<()> {
+        let publisher =
+            publisher_from_config(&EventBusConfiguration::default()).await?;
+
+        assert!(publisher.is_none());
+
+        Ok(())
+    }
+}
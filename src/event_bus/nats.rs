@@ -0,0 +1,75 @@
+//-- ./src/event_bus/nats.rs
+
+//! Event publisher for NATS subjects using async-nats.
+//!
+//! Each message carries a `Nats-Msg-Id` header set to the event id, so
+//! JetStream streams de-duplicate events published more than once.
+//! ---
+
+use async_nats::HeaderMap;
+
+use crate::configuration::EventBusConfiguration;
+use crate::event_bus::EventPublisher;
+use crate::events::AuthEvent;
+use crate::prelude::*;
+
+/// Header JetStream uses to de-duplicate messages
+static MESSAGE_ID_HEADER: &str = "Nats-Msg-Id";
+
+/// Publishes authentication events to NATS subjects
+#[derive(Debug, Clone)]
+pub struct NatsEventPublisher {
+    client: async_nats::Client,
+}
+
+impl NatsEventPublisher {
+    /// Connect to the NATS server in the event bus configuration
+    pub async fn connect(
+        config: &EventBusConfiguration,
+    ) -> Result<Self, AuthenticationError> {
+        let url = config.url.as_deref().ok_or_else(|| {
+            AuthenticationError::ValidationError(
+                "event_bus.url is required when event_bus.transport is nats"
+                    .to_string(),
+            )
+        })?;
+
+        let client = async_nats::connect(url)
+            .await
+            .map_err(|e| AuthenticationError::EventBus(e.to_string()))?;
+
+        Ok(Self { client })
+    }
+}
+
+#[tonic::async_trait]
+impl EventPublisher for NatsEventPublisher {
+    #[tracing::instrument(name = "NATS publish: ", skip(self, event, payload), fields(event_id = %event.id))]
+    async fn publish(
+        &self,
+        subject: &str,
+        event: &AuthEvent,
+        payload: &str,
+    ) -> Result<(), AuthenticationError> {
+        let mut headers = HeaderMap::new();
+        headers.insert(MESSAGE_ID_HEADER, event.id.to_string().as_str());
+
+        self.client
+            .publish_with_headers(
+                subject.to_string(),
+                headers,
+                payload.to_string().into(),
+            )
+            .await
+            .map_err(|e| AuthenticationError::EventBus(e.to_string()))?;
+
+        // Wait for the server to receive the message, so the outbox entry is
+        // only marked processed once it is published
+        self.client
+            .flush()
+            .await
+            .map_err(|e| AuthenticationError::EventBus(e.to_string()))?;
+
+        Ok(())
+    }
+}
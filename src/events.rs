@@ -0,0 +1,257 @@
+//-- ./src/events.rs
+
+// #![allow(unused)] // For development only
+
+//! # Authentication Events
+//!
+//! Real time feed of authentication events (registrations, verifications,
+//! logins, logouts, session revocations, password changes and admin
+//! impersonations), used by the admin `WatchAuthEvents` stream so SIEM tooling
+//! can subscribe. The same
+//! events are queued in the outbox for the webhooks (see `services::webhooks`)
+//! and event bus (see `event_bus`).
+//!
+//! Events are published on a tokio broadcast channel. Publishing never blocks
+//! the authentication flows: if nobody is subscribed the event is dropped, and
+//! a subscriber that falls too far behind skips the events it missed.
+//! ---
+
+use chrono::{DateTime, Utc};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::prelude::*;
+use crate::rpc::proto::AuthEventResponse;
+
+/// Default number of events buffered for slow subscribers
+pub const DEFAULT_EVENT_CAPACITY: usize = 1024;
+
+/// The kind of authentication event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthEventKind {
+    Registration,
+    Verification,
+    Login,
+    Logout,
+    Revocation,
+    PasswordChange,
+    Impersonation,
+}
+
+impl AuthEventKind {
+    /// Convert AuthEventKind to a string reference
+    pub fn to_str(&self) -> &str {
+        match self {
+            AuthEventKind::Registration => "registration",
+            AuthEventKind::Verification => "verification",
+            AuthEventKind::Login => "login",
+            AuthEventKind::Logout => "logout",
+            AuthEventKind::Revocation => "revocation",
+            AuthEventKind::PasswordChange => "password_change",
+            AuthEventKind::Impersonation => "impersonation",
+        }
+    }
+}
+
+impl std::fmt::Display for AuthEventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+impl std::str::FromStr for AuthEventKind {
+    type Err = AuthenticationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "registration" => Ok(AuthEventKind::Registration),
+            "verification" => Ok(AuthEventKind::Verification),
+            "login" => Ok(AuthEventKind::Login),
+            "logout" => Ok(AuthEventKind::Logout),
+            "revocation" => Ok(AuthEventKind::Revocation),
+            "password_change" => Ok(AuthEventKind::PasswordChange),
+            "impersonation" => Ok(AuthEventKind::Impersonation),
+            other => Err(AuthenticationError::ValidationError(format!(
+                "{other} is not a valid authentication event kind"
+            ))),
+        }
+    }
+}
+
+/// A single authentication event
+///
+/// # Fields
+/// - `id`: Unique event id (Uuid v7, so ids sort by time)
+/// - `kind`: Registration, verification, login, logout, revocation, password change
+///   or impersonation
+/// - `user_id`: The user the event relates to, `None` for global revocations
+/// - `session_id`: The session the event relates to, when there is a single one
+/// - `ip_address`: The client IPv4 address, stored the same way as `login_ip`
+/// - `sessions_affected`: How many sessions changed state
+/// - `occurred_at`: When the event happened
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct AuthEvent {
+    pub id: Uuid,
+    pub kind: AuthEventKind,
+    pub user_id: Option<Uuid>,
+    pub session_id: Option<Uuid>,
+    pub ip_address: Option<i32>,
+    pub sessions_affected: u64,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl AuthEvent {
+    /// Create a new event of the given kind, timestamped now
+    pub fn new(kind: AuthEventKind) -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            kind,
+            user_id: None,
+            session_id: None,
+            ip_address: None,
+            sessions_affected: 0,
+            occurred_at: Utc::now(),
+        }
+    }
+
+    /// Set the user the event relates to
+    pub fn user(mut self, user_id: Uuid) -> Self {
+        self.user_id = Some(user_id);
+        self
+    }
+
+    /// Set the session the event relates to
+    pub fn session(mut self, session_id: Uuid) -> Self {
+        self.session_id = Some(session_id);
+        self
+    }
+
+    /// Set the client ip address
+    pub fn ip_address(mut self, ip_address: Option<i32>) -> Self {
+        self.ip_address = ip_address;
+        self
+    }
+
+    /// Set how many sessions were affected
+    pub fn sessions_affected(mut self, sessions_affected: u64) -> Self {
+        self.sessions_affected = sessions_affected;
+        self
+    }
+}
+
+impl From<AuthEvent> for AuthEventResponse {
+    /// Convert from an AuthEvent to proto::AuthEventResponse
+    fn from(value: AuthEvent) -> Self {
+        Self {
+            id: value.id.to_string(),
+            kind: value.kind.to_string(),
+            user_id: value.user_id.map(|id| id.to_string()),
+            session_id: value.session_id.map(|id| id.to_string()),
+            ip_address: value.ip_address,
+            sessions_affected: value.sessions_affected,
+            occurred_at: value.occurred_at.to_string(),
+        }
+    }
+}
+
+/// Broadcast channel for authentication events, cheap to clone into each service
+#[derive(Debug, Clone)]
+pub struct AuthEvents {
+    sender: broadcast::Sender<AuthEvent>,
+}
+
+impl Default for AuthEvents {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_CAPACITY)
+    }
+}
+
+impl AuthEvents {
+    /// Create a new event channel buffering `capacity` events per subscriber
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _receiver) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// Publish an event to all current subscribers.
+    ///
+    /// Returns the number of subscribers the event was sent to. Having no
+    /// subscribers is not an error.
+    pub fn publish(&self, event: AuthEvent) -> usize {
+        tracing::debug!("Publishing authentication event: {event:?}");
+        self.sender.send(event).unwrap_or(0)
+    }
+
+    /// Subscribe to events published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<AuthEvent> {
+        self.sender.subscribe()
+    }
+
+    /// The number of active subscribers
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn event_kind_round_trips_through_strings() -> Result<()> {
+        for kind in [
+            AuthEventKind::Registration,
+            AuthEventKind::Verification,
+            AuthEventKind::Login,
+            AuthEventKind::Logout,
+            AuthEventKind::Revocation,
+            AuthEventKind::PasswordChange,
+            AuthEventKind::Impersonation,
+        ] {
+            assert_eq!(kind.to_string().parse::<AuthEventKind>()?, kind);
+        }
+        assert!("password".parse::<AuthEventKind>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn publish_without_subscribers_is_dropped() {
+        let events = AuthEvents::default();
+
+        assert_eq!(events.publish(AuthEvent::new(AuthEventKind::Login)), 0);
+    }
+
+    #[tokio::test]
+    async fn subscribers_receive_published_events() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let events = AuthEvents::new(8);
+        let mut first = events.subscribe();
+        let mut second = events.subscribe();
+        let user_id = Uuid::now_v7();
+
+        //-- Execute Function (Act)
+        let sent = events.publish(
+            AuthEvent::new(AuthEventKind::Logout)
+                .user(user_id)
+                .sessions_affected(2),
+        );
+
+        //-- Checks (Assertions)
+        assert_eq!(sent, 2);
+        for receiver in [&mut first, &mut second] {
+            let event = receiver.recv().await?;
+            assert_eq!(event.kind, AuthEventKind::Logout);
+            assert_eq!(event.user_id, Some(user_id));
+            assert_eq!(event.sessions_affected, 2);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,420 @@
+//-- ./src/http/authentication.rs
+
+// #![allow(unused)] // For development only
+
+//! # Authentication Gateway Handlers
+//!
+//! Each handler wraps the JSON body in a Tonic request, copying the HTTP
+//! headers into the request metadata and the client address into the request
+//! extensions, then calls the gRPC `AuthenticationService`. The response
+//! metadata (e.g. `set-cookie`) is copied back into the HTTP response headers.
+//! ---
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::{ConnectInfo, State};
+use axum::http::{header, HeaderMap};
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::{Form, Json};
+use tonic::metadata::MetadataMap;
+use tonic::transport::server::TcpConnectInfo;
+
+use crate::http::HttpError;
+use crate::rpc::proto::authentication_service_server::AuthenticationService as Authentication;
+use crate::rpc::proto::{
+    BeginPasskeyLoginRequest, ClientCredentialsRequest, CompleteMagicLinkRequest, CompleteSamlLoginRequest, Empty,
+    FinishPasskeyLoginRequest, LoginRequest, RegisterRequest, RequestMagicLinkRequest,
+    RequestPasswordResetRequest, ResetPasswordRequest, StartDeviceAuthorizationRequest,
+    TokenFromDeviceCodeRequest,
+};
+use crate::services::AuthenticationService;
+
+/// Shared gateway state
+type ServiceState = State<Arc<AuthenticationService>>;
+
+/// Build a Tonic request as if it had arrived over gRPC
+fn tonic_request<T>(
+    message: T,
+    headers: HeaderMap,
+    remote_address: SocketAddr,
+) -> tonic::Request<T> {
+    let mut request = tonic::Request::new(message);
+    *request.metadata_mut() = MetadataMap::from_headers(headers);
+    request.extensions_mut().insert(TcpConnectInfo {
+        local_addr: None,
+        remote_addr: Some(remote_address),
+    });
+    request
+}
+
+/// Convert a Tonic response into a JSON response, keeping its headers
+fn json_response<T: serde::Serialize>(
+    result: Result<tonic::Response<T>, tonic::Status>,
+) -> Result<Response, HttpError> {
+    let (metadata, message, _extensions) = result?.into_parts();
+    Ok((metadata.into_headers(), Json(message)).into_response())
+}
+
+/// `POST /login`
+pub async fn login(
+    State(service): ServiceState,
+    ConnectInfo(remote_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(message): Json<LoginRequest>,
+) -> Result<Response, HttpError> {
+    let request = tonic_request(message, headers, remote_address);
+    json_response(service.login(request).await)
+}
+
+/// `POST /refresh`, using the refresh token cookie
+pub async fn refresh(
+    State(service): ServiceState,
+    ConnectInfo(remote_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+) -> Result<Response, HttpError> {
+    let request = tonic_request(Empty {}, headers, remote_address);
+    json_response(service.refresh(request).await)
+}
+
+/// `POST /logout`, using the refresh token cookie
+pub async fn logout(
+    State(service): ServiceState,
+    ConnectInfo(remote_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+) -> Result<Response, HttpError> {
+    let request = tonic_request(Empty {}, headers, remote_address);
+    json_response(service.logout(request).await)
+}
+
+/// `POST /logout-others`, using the refresh token cookie
+pub async fn logout_other_sessions(
+    State(service): ServiceState,
+    ConnectInfo(remote_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+) -> Result<Response, HttpError> {
+    let request = tonic_request(Empty {}, headers, remote_address);
+    json_response(service.logout_other_sessions(request).await)
+}
+
+/// `POST /register`
+pub async fn register(
+    State(service): ServiceState,
+    ConnectInfo(remote_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(message): Json<RegisterRequest>,
+) -> Result<Response, HttpError> {
+    let request = tonic_request(message, headers, remote_address);
+    json_response(service.register(request).await)
+}
+
+/// `POST /password-reset`
+pub async fn reset_password(
+    State(service): ServiceState,
+    ConnectInfo(remote_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(message): Json<ResetPasswordRequest>,
+) -> Result<Response, HttpError> {
+    let request = tonic_request(message, headers, remote_address);
+    json_response(service.reset_password(request).await)
+}
+
+/// `POST /password-reset/request`
+pub async fn request_password_reset(
+    State(service): ServiceState,
+    ConnectInfo(remote_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(message): Json<RequestPasswordResetRequest>,
+) -> Result<Response, HttpError> {
+    let request = tonic_request(message, headers, remote_address);
+    json_response(service.request_password_reset(request).await)
+}
+
+/// `POST /magic-link`
+pub async fn request_magic_link(
+    State(service): ServiceState,
+    ConnectInfo(remote_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(message): Json<RequestMagicLinkRequest>,
+) -> Result<Response, HttpError> {
+    let request = tonic_request(message, headers, remote_address);
+    json_response(service.request_magic_link(request).await)
+}
+
+/// `POST /magic-link/complete`
+pub async fn complete_magic_link(
+    State(service): ServiceState,
+    ConnectInfo(remote_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(message): Json<CompleteMagicLinkRequest>,
+) -> Result<Response, HttpError> {
+    let request = tonic_request(message, headers, remote_address);
+    json_response(service.complete_magic_link(request).await)
+}
+
+/// `POST /passkey-login`
+pub async fn begin_passkey_login(
+    State(service): ServiceState,
+    ConnectInfo(remote_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(message): Json<BeginPasskeyLoginRequest>,
+) -> Result<Response, HttpError> {
+    let request = tonic_request(message, headers, remote_address);
+    json_response(service.begin_passkey_login(request).await)
+}
+
+/// `POST /passkey-login/complete`
+pub async fn finish_passkey_login(
+    State(service): ServiceState,
+    ConnectInfo(remote_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(message): Json<FinishPasskeyLoginRequest>,
+) -> Result<Response, HttpError> {
+    let request = tonic_request(message, headers, remote_address);
+    json_response(service.finish_passkey_login(request).await)
+}
+
+/// `POST /device/code`
+pub async fn start_device_authorization(
+    State(service): ServiceState,
+    ConnectInfo(remote_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(message): Json<StartDeviceAuthorizationRequest>,
+) -> Result<Response, HttpError> {
+    let request = tonic_request(message, headers, remote_address);
+    json_response(service.start_device_authorization(request).await)
+}
+
+/// `POST /device/token`, polled by the device until the user approves it
+pub async fn token_from_device_code(
+    State(service): ServiceState,
+    ConnectInfo(remote_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(message): Json<TokenFromDeviceCodeRequest>,
+) -> Result<Response, HttpError> {
+    let request = tonic_request(message, headers, remote_address);
+    json_response(service.token_from_device_code(request).await)
+}
+
+/// The OAuth token request form, only the client credentials grant is supported
+#[derive(serde::Deserialize)]
+pub struct TokenForm {
+    grant_type: String,
+    client_id: String,
+    client_secret: String,
+    scope: Option<String>,
+}
+
+/// `POST /oauth/token`, the OAuth client credentials grant for API clients
+pub async fn oauth_token(
+    State(service): ServiceState,
+    ConnectInfo(remote_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Form(form): Form<TokenForm>,
+) -> Result<Response, HttpError> {
+    if form.grant_type != "client_credentials" {
+        return Err(tonic::Status::invalid_argument("unsupported_grant_type").into());
+    }
+
+    let message = ClientCredentialsRequest {
+        client_id: form.client_id,
+        client_secret: form.client_secret,
+        scope: form.scope,
+    };
+    let request = tonic_request(message, headers, remote_address);
+    json_response(service.client_credentials(request).await)
+}
+
+/// The identity provider's form post to the assertion consumer service
+#[derive(serde::Deserialize)]
+pub struct SamlAcsForm {
+    #[serde(rename = "SAMLResponse")]
+    saml_response: String,
+}
+
+/// `GET /saml/metadata`, the service provider metadata XML
+pub async fn saml_metadata(
+    State(service): ServiceState,
+    ConnectInfo(remote_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+) -> Result<Response, HttpError> {
+    let request = tonic_request(Empty {}, headers, remote_address);
+    let xml = service.get_saml_metadata(request).await?.into_inner().xml;
+    Ok(([(header::CONTENT_TYPE, "application/samlmetadata+xml")], xml).into_response())
+}
+
+/// `GET /saml/login`, redirecting the browser to the identity provider
+pub async fn begin_saml_login(
+    State(service): ServiceState,
+    ConnectInfo(remote_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+) -> Result<Response, HttpError> {
+    let request = tonic_request(Empty {}, headers, remote_address);
+    let redirect_url = service.begin_saml_login(request).await?.into_inner().redirect_url;
+    Ok(Redirect::to(&redirect_url).into_response())
+}
+
+/// `POST /saml/acs`, the identity provider's response. The refresh token
+/// cookie is set and the browser is sent to `saml.redirect_url`, where the
+/// application gets an access token from `/refresh`.
+pub async fn complete_saml_login(
+    State(service): ServiceState,
+    ConnectInfo(remote_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Form(form): Form<SamlAcsForm>,
+) -> Result<Response, HttpError> {
+    let message = CompleteSamlLoginRequest {
+        saml_response: form.saml_response,
+        remember_me: false,
+        organization_id: None,
+    };
+    let request = tonic_request(message, headers, remote_address);
+    let (metadata, _message, _extensions) =
+        service.complete_saml_login(request).await?.into_parts();
+    Ok((
+        metadata.into_headers(),
+        Redirect::to(&service.saml_redirect_url()),
+    )
+        .into_response())
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::body::Body;
+    use axum::extract::connect_info::MockConnectInfo;
+    use axum::http::{header, Request, StatusCode};
+    use secrecy::SecretString;
+    use sqlx::{Pool, Postgres};
+    use tower::ServiceExt;
+
+    use crate::configuration::Configuration;
+    use crate::events::AuthEvents;
+    use crate::http::error::ErrorBody;
+    use crate::middleware::{ApiKeyStore, TokenDenylist};
+    use crate::services::CaptchaGuard;
+    use crate::rpc::proto::LoginResponse;
+    use crate::{database, domain};
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    const PASSWORD: &str = "Gateway-Passw0rd";
+
+    /// Gateway router with a mock client address
+    fn gateway(database: Pool<Postgres>) -> Result<axum::Router> {
+        let config = Configuration::parse()?.into_shared();
+        let router = crate::http::router(
+            Arc::new(database),
+            config,
+            AuthEvents::default(),
+            TokenDenylist::default(),
+            CaptchaGuard::new()?,
+            ApiKeyStore::default(),
+        )
+        .layer(MockConnectInfo(SocketAddr::from(([127, 0, 0, 1], 8082))));
+        Ok(router)
+    }
+
+    /// Insert an active user with a known password
+    async fn insert_user(database: &Pool<Postgres>) -> Result<database::Users> {
+        let mut user = database::Users::mock_data()?;
+        user.password_hash = domain::PasswordHash::parse(SecretString::from(PASSWORD))?;
+        user.is_active = true;
+        Ok(user.insert(database).await?)
+    }
+
+    fn login_request(email: &str, password: &str) -> Result<Request<Body>> {
+        let body = serde_json::json!({ "email": email, "password": password });
+        Ok(Request::post("/login")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))?)
+    }
+
+    #[sqlx::test]
+    async fn login_returns_access_token_and_refresh_cookie(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = insert_user(&database).await?;
+        let router = gateway(database)?;
+
+        //-- Execute Function (Act)
+        let response = router
+            .oneshot(login_request(user.email.as_ref(), PASSWORD)?)
+            .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(response.status(), StatusCode::OK);
+        let cookie = response
+            .headers()
+            .get(header::SET_COOKIE)
+            .ok_or("missing refresh cookie")?
+            .to_str()?;
+        assert!(cookie.starts_with("refresh_token="));
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+        let login: LoginResponse = serde_json::from_slice(&body)?;
+        assert!(!login.access_token.is_empty());
+        assert_eq!(login.user.ok_or("missing user")?.id, user.id.to_string());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn login_with_wrong_password_is_unauthorized(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = insert_user(&database).await?;
+        let router = gateway(database)?;
+
+        //-- Execute Function (Act)
+        let response = router
+            .oneshot(login_request(user.email.as_ref(), "Wrong-Passw0rd")?)
+            .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+        let error: ErrorBody = serde_json::from_slice(&body)?;
+        assert_eq!(error.code, "Unauthenticated");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn refresh_without_cookie_is_unauthorized(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let router = gateway(database)?;
+
+        //-- Execute Function (Act)
+        let response = router
+            .oneshot(Request::post("/refresh").body(Body::empty())?)
+            .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn saml_login_is_not_implemented_by_default(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let router = gateway(database)?;
+
+        //-- Execute Function (Act)
+        let response = router
+            .oneshot(Request::get("/saml/login").body(Body::empty())?)
+            .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(response.status(), StatusCode::NOT_IMPLEMENTED);
+
+        Ok(())
+    }
+}
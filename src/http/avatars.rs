@@ -0,0 +1,75 @@
+//-- ./src/http/avatars.rs
+
+// #![allow(unused)] // For development only
+
+//! # Avatar Files
+//!
+//! Serve the avatars stored by the `local` backend, at the signed URLs
+//! returned in `UserResponse.avatar_url`.
+//!
+//! | Route                                                       | Action         |
+//! |-------------------------------------------------------------|----------------|
+//! | `GET /avatars/{user_id}/{hash}?expires=...&signature=...`   | Get the avatar |
+//!
+//! URLs that are unsigned, tampered with or expired are forbidden.
+//! ---
+
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use chrono::Utc;
+
+use crate::avatars::{self, LocalAvatarStorage};
+
+/// The signature query added by `LocalAvatarStorage::signed_url`
+#[derive(Debug, serde::Deserialize)]
+pub struct SignedQuery {
+    expires: i64,
+    signature: String,
+}
+
+/// Build the avatar router, nested under `/avatars` by the gateway
+pub fn router(storage: LocalAvatarStorage) -> Router {
+    Router::new()
+        .route("/{user_id}/{hash}", get(read_avatar))
+        .with_state(Arc::new(storage))
+}
+
+/// `GET /avatars/{user_id}/{hash}`
+async fn read_avatar(
+    State(storage): State<Arc<LocalAvatarStorage>>,
+    Path((user_id, hash)): Path<(String, String)>,
+    Query(query): Query<SignedQuery>,
+) -> Response {
+    let key = format!("{user_id}/{hash}");
+    if !avatars::is_valid_key(&key) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    if !storage.verify(&key, query.expires, &query.signature) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+
+    match storage.read(&key).await {
+        Ok(Some(image)) => {
+            // A new image gets a new key, so it can be cached until the URL expires
+            let max_age = query.expires.saturating_sub(Utc::now().timestamp()).max(0);
+            (
+                [
+                    (header::CONTENT_TYPE, avatars::CONTENT_TYPE.to_string()),
+                    (header::CACHE_CONTROL, format!("private, max-age={max_age}")),
+                ],
+                image,
+            )
+                .into_response()
+        }
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            tracing::error!("Unable to read avatar {key}: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//-- ./src/http/error.rs
+
+// #![allow(unused)] // For development only
+
+//! # Gateway Errors
+//!
+//! Translate the gRPC `Status` returned by the services into an HTTP status code
+//! and a JSON error body. A `retry-after` hint in the status metadata is
+//! returned as the `Retry-After` header, the `x-ratelimit-*` metadata as the
+//! same headers, and a reason code, such as why a
+//! registration's email domain was refused, as the body's `reason`.
+//! ---
+
+use axum::http::{header::RETRY_AFTER, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use tonic::Code;
+
+use crate::error::EMAIL_DOMAIN_REJECTED_HEADER;
+use crate::utils::rate_limit::RATE_LIMIT_METADATA;
+
+/// JSON error body returned by the gateway
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct ErrorBody {
+    pub code: String,
+    pub message: String,
+    /// Machine readable reason for the error, when there is one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// A service `Status` returned over HTTP
+#[derive(Debug)]
+pub struct HttpError(pub tonic::Status);
+
+impl From<tonic::Status> for HttpError {
+    fn from(status: tonic::Status) -> Self {
+        Self(status)
+    }
+}
+
+/// Map a gRPC status code to the equivalent HTTP status code
+pub fn http_status(code: Code) -> StatusCode {
+    match code {
+        Code::Ok => StatusCode::OK,
+        Code::InvalidArgument | Code::OutOfRange => StatusCode::BAD_REQUEST,
+        Code::FailedPrecondition => StatusCode::PRECONDITION_FAILED,
+        Code::Unauthenticated => StatusCode::UNAUTHORIZED,
+        Code::PermissionDenied => StatusCode::FORBIDDEN,
+        Code::NotFound => StatusCode::NOT_FOUND,
+        Code::AlreadyExists | Code::Aborted => StatusCode::CONFLICT,
+        Code::ResourceExhausted => StatusCode::TOO_MANY_REQUESTS,
+        Code::Unimplemented => StatusCode::NOT_IMPLEMENTED,
+        Code::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        Code::DeadlineExceeded => StatusCode::GATEWAY_TIMEOUT,
+        Code::Cancelled
+        | Code::Unknown
+        | Code::Internal
+        | Code::DataLoss => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+impl IntoResponse for HttpError {
+    fn into_response(self) -> Response {
+        let body = ErrorBody {
+            code: format!("{:?}", self.0.code()),
+            message: self.0.message().to_string(),
+            reason: self
+                .0
+                .metadata()
+                .get(EMAIL_DOMAIN_REJECTED_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string),
+        };
+
+        let mut response = (http_status(self.0.code()), Json(body)).into_response();
+
+        // Pass on when to retry, e.g. for throttled logins
+        if let Some(retry_after) = self
+            .0
+            .metadata()
+            .get("retry-after")
+            .and_then(|value| HeaderValue::from_bytes(value.as_bytes()).ok())
+        {
+            response.headers_mut().insert(RETRY_AFTER, retry_after);
+        }
+
+        // And how much of the rate limit is left
+        for key in RATE_LIMIT_METADATA {
+            if let Some(value) = self
+                .0
+                .metadata()
+                .get(key)
+                .and_then(|value| HeaderValue::from_bytes(value.as_bytes()).ok())
+            {
+                response.headers_mut().insert(key, value);
+            }
+        }
+
+        response
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_codes_map_to_http() {
+        assert_eq!(http_status(Code::Unauthenticated), StatusCode::UNAUTHORIZED);
+        assert_eq!(http_status(Code::InvalidArgument), StatusCode::BAD_REQUEST);
+        assert_eq!(http_status(Code::ResourceExhausted), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(http_status(Code::Internal), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn retry_after_metadata_becomes_a_header() {
+        let status: tonic::Status =
+            crate::prelude::AuthenticationError::LoginThrottled(30).into();
+
+        let response = HttpError(status).into_response();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[RETRY_AFTER], "30");
+    }
+
+    #[tokio::test]
+    async fn reason_metadata_is_added_to_the_body() -> Result<(), Box<dyn std::error::Error>> {
+        let status: tonic::Status = crate::prelude::AuthenticationError::EmailDomainRejected(
+            crate::domain::EmailDomainRejection::Disposable,
+        )
+        .into();
+
+        let response = HttpError(status).into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+        let body: ErrorBody = serde_json::from_slice(&body)?;
+
+        assert_eq!(body.code, "InvalidArgument");
+        assert_eq!(body.reason.as_deref(), Some("EMAIL_DOMAIN_DISPOSABLE"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+//-- ./src/http/mod.rs
+
+// #![allow(unused)] // For development only
+
+//! # REST/JSON Gateway
+//!
+//! Optional axum HTTP gateway for clients that cannot speak gRPC. Each route
+//! translates the JSON request into the matching gRPC request and calls the same
+//! `AuthenticationService`, so domain validation, sessions and events are shared
+//! with the gRPC endpoints.
+//!
+//! | Route                          | gRPC method                                      |
+//! |--------------------------------|--------------------------------------------------|
+//! | `POST /login`                  | `AuthenticationService/Login`                    |
+//! | `POST /refresh`                | `AuthenticationService/Refresh`                  |
+//! | `POST /logout`                 | `AuthenticationService/Logout`                   |
+//! | `POST /logout-others`          | `AuthenticationService/LogoutOtherSessions`      |
+//! | `POST /register`               | `AuthenticationService/Register`                 |
+//! | `POST /password-reset`         | `AuthenticationService/ResetPassword`            |
+//! | `POST /password-reset/request` | `AuthenticationService/RequestPasswordReset`     |
+//! | `POST /magic-link`             | `AuthenticationService/RequestMagicLink`         |
+//! | `POST /magic-link/complete`    | `AuthenticationService/CompleteMagicLink`        |
+//! | `POST /passkey-login`          | `AuthenticationService/BeginPasskeyLogin`        |
+//! | `POST /passkey-login/complete` | `AuthenticationService/FinishPasskeyLogin`       |
+//! | `GET /saml/metadata`           | `AuthenticationService/GetSamlMetadata`          |
+//! | `GET /saml/login`              | `AuthenticationService/BeginSamlLogin`           |
+//! | `POST /saml/acs`               | `AuthenticationService/CompleteSamlLogin`        |
+//! | `POST /device/code`            | `AuthenticationService/StartDeviceAuthorization` |
+//! | `POST /device/token`           | `AuthenticationService/TokenFromDeviceCode`      |
+//! | `POST /oauth/token`            | `AuthenticationService/ClientCredentials`        |
+//!
+//! Cookies are passed through both ways, so the refresh token cookie works the
+//! same as it does over gRPC-Web. Enable the gateway with `http.enabled`.
+//!
+//! The SAML routes are for the browser: `/saml/login` redirects to the
+//! identity provider, which posts its response back to `/saml/acs`. That sets
+//! the refresh token cookie and redirects to `saml.redirect_url`.
+//!
+//! `/oauth/token` takes a form encoded OAuth token request, as most OAuth
+//! client libraries send, and only supports the `client_credentials` grant.
+//!
+//! The gateway also serves SCIM 2.0 user provisioning under `/scim/v2`, see
+//! `scim`, and the avatars stored by the `local` backend under `/avatars`, see
+//! `avatars`.
+//! ---
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::routing::{get, post};
+use axum::Router;
+use sqlx::{Pool, Postgres};
+use tokio::net::TcpListener;
+use tower_http::catch_panic::CatchPanicLayer;
+use tower_http::trace::TraceLayer;
+
+use crate::avatars::LocalAvatarStorage;
+use crate::configuration::{AvatarStorageBackend, SharedConfiguration};
+use crate::events::AuthEvents;
+use crate::middleware::{ApiKeyStore, TokenDenylist};
+use crate::prelude::*;
+use crate::services::{AuthenticationService, CaptchaGuard, PasswordHasher};
+
+mod authentication;
+mod avatars;
+mod error;
+mod scim;
+
+pub use error::HttpError;
+
+/// Build the gateway router.
+///
+/// # Parameters
+/// * `database` - The sqlx database pool.
+/// * `config` - The shared runtime configuration.
+/// * `events` - Authentication event broadcaster, shared with the gRPC services.
+/// * `denylist` - Denied access tokens, shared with the gRPC services.
+/// * `captcha` - CAPTCHA checks and failed login counts, shared with the gRPC services.
+/// * `api_keys` - Usable service account API keys, authenticating SCIM requests.
+pub fn router(
+    database: Arc<Pool<Postgres>>,
+    config: SharedConfiguration,
+    events: AuthEvents,
+    denylist: TokenDenylist,
+    captcha: CaptchaGuard,
+    api_keys: ApiKeyStore,
+) -> Router {
+    // Snapshot of the configuration for settings that are fixed at startup
+    let startup_config = config.load_full();
+
+    // The gateway hashes passwords in its own queue, sized like the gRPC one
+    let password_hasher =
+        PasswordHasher::new(startup_config.load_shedding.max_queued_password_hashes);
+    let scim_state = scim::ScimState::new(
+        database.clone(),
+        api_keys,
+        events.clone(),
+        password_hasher.clone(),
+    );
+    let authentication_service = Arc::new(
+        AuthenticationService::new(database, config, events, denylist, captcha)
+            .with_password_hasher(password_hasher),
+    );
+
+    let mut router = Router::new()
+        .route("/login", post(authentication::login))
+        .route("/refresh", post(authentication::refresh))
+        .route("/logout", post(authentication::logout))
+        .route("/logout-others", post(authentication::logout_other_sessions))
+        .route("/register", post(authentication::register))
+        .route("/password-reset", post(authentication::reset_password))
+        .route("/password-reset/request", post(authentication::request_password_reset))
+        .route("/magic-link", post(authentication::request_magic_link))
+        .route("/magic-link/complete", post(authentication::complete_magic_link))
+        .route("/passkey-login", post(authentication::begin_passkey_login))
+        .route("/passkey-login/complete", post(authentication::finish_passkey_login))
+        .route("/saml/metadata", get(authentication::saml_metadata))
+        .route("/saml/login", get(authentication::begin_saml_login))
+        .route("/saml/acs", post(authentication::complete_saml_login))
+        .route("/device/code", post(authentication::start_device_authorization))
+        .route("/device/token", post(authentication::token_from_device_code))
+        .route("/oauth/token", post(authentication::oauth_token))
+        .with_state(authentication_service)
+        .nest("/scim/v2", scim::router(scim_state));
+
+    // Serve the avatars stored by the local backend
+    if startup_config.avatars.backend == AvatarStorageBackend::Local {
+        match LocalAvatarStorage::new(
+            &startup_config.avatars,
+            &startup_config.application.token_secret,
+        ) {
+            Ok(storage) => router = router.nest("/avatars", avatars::router(storage)),
+            Err(e) => tracing::error!("Avatars are not served by the gateway: {e}"),
+        }
+    }
+
+    router
+        .layer(TraceLayer::new_for_http())
+        // Endpoints not yet implemented by the service return 500, not a dropped connection
+        .layer(CatchPanicLayer::new())
+}
+
+/// Serve the gateway router until the listener closes.
+pub async fn serve(listener: TcpListener, router: Router) -> Result<(), AuthenticationError> {
+    tracing::info!("HTTP gateway started at '{}'", listener.local_addr()?);
+
+    axum::serve(
+        listener,
+        router.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
+
+    Ok(())
+}
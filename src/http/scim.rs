@@ -0,0 +1,874 @@
+//-- ./src/http/scim.rs
+
+// #![allow(unused)] // For development only
+
+//! # SCIM 2.0 Provisioning
+//!
+//! A [SCIM 2.0](https://datatracker.ietf.org/doc/html/rfc7644) `/Users`
+//! resource, so identity providers such as Okta and Azure AD can provision
+//! users. SCIM users are mapped onto the `Users` model:
+//!
+//! | SCIM attribute                  | User field  |
+//! |---------------------------------|-------------|
+//! | `id`                            | `id`        |
+//! | `userName`                      | `email`     |
+//! | `displayName`, `name.formatted` | `name`      |
+//! | `active`                        | `is_active` |
+//!
+//! | Route                        | Action                                 |
+//! |------------------------------|----------------------------------------|
+//! | `GET /scim/v2/Users`         | List users, filtered by `userName eq`  |
+//! | `POST /scim/v2/Users`        | Provision a user                       |
+//! | `GET /scim/v2/Users/{id}`    | Get a user                             |
+//! | `PATCH /scim/v2/Users/{id}`  | Update or deactivate a user            |
+//! | `DELETE /scim/v2/Users/{id}` | Soft delete a user                     |
+//!
+//! Requests authenticate with an admin service account API key as a bearer
+//! token, `Authorization: Bearer ams_...`. Provisioned users are verified, as
+//! the identity provider owns their email. Without a `password` they log in
+//! with a magic link or set a password with a reset. Users that are
+//! deactivated or deleted have their sessions revoked. `userName` can't be
+//! changed by a patch, email changes need the confirmation of both addresses.
+//! ---
+
+use std::sync::Arc;
+
+use axum::extract::{FromRequestParts, Path, Query, State};
+use axum::http::{header, request::Parts, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use secrecy::SecretString;
+use serde_json::Value;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::events::{AuthEvent, AuthEventKind, AuthEvents};
+use crate::http::error::http_status;
+use crate::middleware::ApiKeyStore;
+use crate::prelude::*;
+use crate::services::PasswordHasher;
+use crate::{database, domain};
+
+/// SCIM core user resource schema
+const USER_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:User";
+
+/// SCIM list response message schema
+const LIST_RESPONSE_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:ListResponse";
+
+/// SCIM error message schema
+const ERROR_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:Error";
+
+/// Media type of SCIM responses
+const SCIM_CONTENT_TYPE: &str = "application/scim+json";
+
+/// Most users returned in one list page
+const MAX_PAGE_SIZE: usize = 100;
+
+/// Shared SCIM state
+#[derive(Clone)]
+pub struct ScimState {
+    database: Arc<Pool<Postgres>>,
+    api_keys: ApiKeyStore,
+    events: AuthEvents,
+    password_hasher: PasswordHasher,
+}
+
+impl ScimState {
+    pub fn new(
+        database: Arc<Pool<Postgres>>,
+        api_keys: ApiKeyStore,
+        events: AuthEvents,
+        password_hasher: PasswordHasher,
+    ) -> Self {
+        Self {
+            database,
+            api_keys,
+            events,
+            password_hasher,
+        }
+    }
+}
+
+/// Build the SCIM router, nested under `/scim/v2` by the gateway
+pub fn router(state: ScimState) -> Router {
+    Router::new()
+        .route("/Users", get(list_users).post(create_user))
+        .route(
+            "/Users/{id}",
+            get(read_user).patch(update_user).delete(delete_user),
+        )
+        .with_state(state)
+}
+
+//-- Errors
+
+/// SCIM error body, see RFC 7644 section 3.12
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct ScimErrorBody {
+    pub schemas: Vec<String>,
+    pub status: String,
+    #[serde(rename = "scimType", default, skip_serializing_if = "Option::is_none")]
+    pub scim_type: Option<String>,
+    pub detail: String,
+}
+
+/// An error returned to the identity provider
+#[derive(Debug)]
+pub struct ScimError {
+    status: StatusCode,
+    scim_type: Option<&'static str>,
+    detail: String,
+}
+
+impl ScimError {
+    fn new(status: StatusCode, detail: impl Into<String>) -> Self {
+        Self {
+            status,
+            scim_type: None,
+            detail: detail.into(),
+        }
+    }
+
+    /// A bad request with the SCIM error type, such as `invalidValue`
+    fn bad_request(scim_type: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            scim_type: Some(scim_type),
+            ..Self::new(StatusCode::BAD_REQUEST, detail)
+        }
+    }
+
+    fn unauthorized() -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, "Authentication Failed!")
+    }
+
+    fn not_found() -> Self {
+        Self::new(StatusCode::NOT_FOUND, "User not found")
+    }
+
+    fn user_name_taken() -> Self {
+        Self {
+            scim_type: Some("uniqueness"),
+            ..Self::new(StatusCode::CONFLICT, "userName is already in use")
+        }
+    }
+}
+
+impl From<tonic::Status> for ScimError {
+    fn from(status: tonic::Status) -> Self {
+        Self::new(http_status(status.code()), status.message())
+    }
+}
+
+impl From<AuthenticationError> for ScimError {
+    fn from(error: AuthenticationError) -> Self {
+        tonic::Status::from(error).into()
+    }
+}
+
+impl IntoResponse for ScimError {
+    fn into_response(self) -> Response {
+        let body = ScimErrorBody {
+            schemas: vec![ERROR_SCHEMA.to_string()],
+            status: self.status.as_u16().to_string(),
+            scim_type: self.scim_type.map(str::to_string),
+            detail: self.detail,
+        };
+
+        scim_response(self.status, body)
+    }
+}
+
+/// A JSON response with the SCIM media type
+fn scim_response<T: serde::Serialize>(status: StatusCode, body: T) -> Response {
+    let mut response = (status, Json(body)).into_response();
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(SCIM_CONTENT_TYPE),
+    );
+    response
+}
+
+//-- Authentication
+
+/// An admin service account, authenticated by the bearer API key
+#[derive(Debug)]
+pub struct ScimClient {
+    pub api_key_id: Uuid,
+}
+
+impl FromRequestParts<ScimState> for ScimClient {
+    type Rejection = ScimError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &ScimState,
+    ) -> Result<Self, Self::Rejection> {
+        let token = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(ScimError::unauthorized)?;
+
+        let api_key =
+            domain::ApiKey::parse(token.trim()).map_err(|_| ScimError::unauthorized())?;
+        let identity = state
+            .api_keys
+            .authenticate(&api_key)
+            .ok_or_else(ScimError::unauthorized)?;
+
+        if identity.role != domain::UserRole::Admin {
+            tracing::error!("SCIM request with a non admin API key: {}", identity.id);
+            return Err(ScimError::new(
+                StatusCode::FORBIDDEN,
+                "SCIM provisioning needs an admin API key",
+            ));
+        }
+
+        Ok(Self {
+            api_key_id: identity.id,
+        })
+    }
+}
+
+//-- Resources
+
+/// SCIM user name
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimName {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub formatted: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub given_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family_name: Option<String>,
+}
+
+/// SCIM user email
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct ScimEmail {
+    pub value: String,
+    #[serde(default)]
+    pub primary: bool,
+}
+
+/// SCIM resource metadata
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimMeta {
+    pub resource_type: String,
+    pub created: String,
+    pub location: String,
+}
+
+/// SCIM user resource
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUser {
+    pub schemas: Vec<String>,
+    pub id: String,
+    pub user_name: String,
+    pub display_name: String,
+    pub name: ScimName,
+    pub emails: Vec<ScimEmail>,
+    pub active: bool,
+    pub meta: ScimMeta,
+}
+
+impl From<database::Users> for ScimUser {
+    fn from(user: database::Users) -> Self {
+        Self {
+            schemas: vec![USER_SCHEMA.to_string()],
+            id: user.id.to_string(),
+            user_name: user.email.to_string(),
+            display_name: user.name.to_string(),
+            name: ScimName {
+                formatted: Some(user.name.to_string()),
+                ..ScimName::default()
+            },
+            emails: vec![ScimEmail {
+                value: user.email.to_string(),
+                primary: true,
+            }],
+            active: user.is_active,
+            meta: ScimMeta {
+                resource_type: "User".to_string(),
+                created: user.created_on.to_rfc3339(),
+                location: format!("/scim/v2/Users/{}", user.id),
+            },
+        }
+    }
+}
+
+/// SCIM list response
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimListResponse {
+    pub schemas: Vec<String>,
+    pub total_results: u64,
+    pub start_index: usize,
+    pub items_per_page: usize,
+    #[serde(rename = "Resources")]
+    pub resources: Vec<ScimUser>,
+}
+
+impl ScimListResponse {
+    fn new(users: Vec<database::Users>, total_results: u64, start_index: usize) -> Self {
+        Self {
+            schemas: vec![LIST_RESPONSE_SCHEMA.to_string()],
+            total_results,
+            start_index,
+            items_per_page: users.len(),
+            resources: users.into_iter().map(ScimUser::from).collect(),
+        }
+    }
+}
+
+/// Users are active unless the identity provider says otherwise
+fn active_by_default() -> bool {
+    true
+}
+
+/// `POST /Users` body, unknown attributes are ignored
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateScimUser {
+    pub user_name: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub name: Option<ScimName>,
+    #[serde(default = "active_by_default")]
+    #[serde(deserialize_with = "serde_aux::field_attributes::deserialize_bool_from_anything")]
+    pub active: bool,
+    #[serde(default)]
+    pub password: Option<SecretString>,
+}
+
+impl CreateScimUser {
+    /// The user's name, from the first of `displayName`, `name.formatted`,
+    /// `name.givenName name.familyName` and `userName` that is set
+    fn user_name(&self) -> String {
+        let name = self.name.as_ref();
+        let given_and_family = name
+            .map(|name| {
+                [name.given_name.as_deref(), name.family_name.as_deref()]
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .filter(|name| !name.is_empty());
+
+        self.display_name
+            .clone()
+            .or_else(|| name.and_then(|name| name.formatted.clone()))
+            .or(given_and_family)
+            .unwrap_or_else(|| self.user_name.clone())
+    }
+}
+
+/// A `PATCH /Users/{id}` operation
+#[derive(Debug, serde::Deserialize)]
+pub struct ScimPatchOperation {
+    pub op: String,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub value: Value,
+}
+
+/// `PATCH /Users/{id}` body
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ScimPatch {
+    pub operations: Vec<ScimPatchOperation>,
+}
+
+/// `GET /Users` query
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimListQuery {
+    #[serde(default)]
+    pub filter: Option<String>,
+    #[serde(default)]
+    pub start_index: Option<usize>,
+    #[serde(default)]
+    pub count: Option<usize>,
+}
+
+//-- Mapping
+
+/// Parse a `userName eq "..."` filter, the only filter identity providers
+/// need to find a user before provisioning them
+fn user_name_filter(filter: &str) -> Result<String, ScimError> {
+    let invalid = || {
+        ScimError::bad_request("invalidFilter", "Only userName eq \"...\" filters are supported")
+    };
+
+    let mut parts = filter.trim().splitn(3, ' ');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(attribute), Some(operator), Some(value))
+            if attribute.eq_ignore_ascii_case("userName")
+                && operator.eq_ignore_ascii_case("eq") =>
+        {
+            value
+                .trim()
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .map(str::to_string)
+                .ok_or_else(invalid)
+        }
+        _ => Err(invalid()),
+    }
+}
+
+fn parse_email(user_name: &str) -> Result<domain::EmailAddress, ScimError> {
+    domain::EmailAddress::parse(user_name)
+        .map_err(|_| ScimError::bad_request("invalidValue", "userName must be an email address"))
+}
+
+fn parse_name(name: &str) -> Result<domain::UserName, ScimError> {
+    domain::UserName::parse(name)
+        .map_err(|_| ScimError::bad_request("invalidValue", "Invalid displayName"))
+}
+
+/// Apply one attribute of a patch to the user. Attributes that are not
+/// mapped onto the user, such as `externalId`, are ignored.
+fn apply_patch_value(
+    user: &mut database::Users,
+    path: &str,
+    value: &Value,
+) -> Result<(), ScimError> {
+    let string_value = || {
+        value
+            .as_str()
+            .ok_or_else(|| ScimError::bad_request("invalidValue", format!("{path} must be a string")))
+    };
+
+    match path.to_ascii_lowercase().as_str() {
+        "active" => {
+            user.is_active = match value {
+                Value::Bool(active) => *active,
+                // Azure AD sends booleans as "True" and "False"
+                Value::String(active) => active.eq_ignore_ascii_case("true"),
+                _ => {
+                    return Err(ScimError::bad_request(
+                        "invalidValue",
+                        "active must be a boolean",
+                    ))
+                }
+            }
+        }
+        // Changing the email goes through the email change confirmation, a patch
+        // repeating the current userName is fine
+        "username" => {
+            if parse_email(string_value()?)? != user.email {
+                return Err(ScimError::bad_request(
+                    "mutability",
+                    "userName can't be changed by a patch",
+                ));
+            }
+        }
+        "displayname" | "name.formatted" => user.name = parse_name(string_value()?)?,
+        // Without a path, the value holds the attributes to replace
+        "" => {
+            let attributes = value.as_object().ok_or_else(|| {
+                ScimError::bad_request("invalidValue", "A patch without a path needs an object value")
+            })?;
+            for (attribute, value) in attributes {
+                match value.as_object() {
+                    Some(sub_attributes) => {
+                        for (sub_attribute, value) in sub_attributes {
+                            apply_patch_value(user, &format!("{attribute}.{sub_attribute}"), value)?;
+                        }
+                    }
+                    None => apply_patch_value(user, attribute, value)?,
+                }
+            }
+        }
+        _ => tracing::debug!("Ignoring SCIM patch of unmapped attribute: {path}"),
+    }
+
+    Ok(())
+}
+
+//-- Handlers
+
+/// Find the user by SCIM id, `404` if it is unknown or deleted
+async fn find_user(id: &str, database: &Pool<Postgres>) -> Result<database::Users, ScimError> {
+    let id = Uuid::try_parse(id).map_err(|_| ScimError::not_found())?;
+
+    database::Users::from_user_id(&id, database)
+        .await
+        .map_err(|_| ScimError::not_found())
+}
+
+/// `GET /Users`, a page of users or the user matching a `userName` filter
+#[tracing::instrument(name = "SCIM List Users Request: ", skip(state))]
+async fn list_users(
+    State(state): State<ScimState>,
+    client: ScimClient,
+    Query(query): Query<ScimListQuery>,
+) -> Result<Response, ScimError> {
+    let database = state.database.as_ref();
+    let start_index = query.start_index.unwrap_or(1).max(1);
+
+    let list = match query.filter {
+        Some(filter) => {
+            let user_name = user_name_filter(&filter)?;
+            let user = match domain::EmailAddress::parse(user_name) {
+                Ok(email) => database::Users::from_user_email(&email, database).await.ok(),
+                Err(_) => None,
+            };
+            let users: Vec<_> = user.into_iter().collect();
+            let total_results = users.len() as u64;
+            ScimListResponse::new(users, total_results, 1)
+        }
+        None => {
+            let count = query.count.unwrap_or(MAX_PAGE_SIZE).min(MAX_PAGE_SIZE);
+            let users = database::Users::index(&count, &(start_index - 1), database).await?;
+            let total_results = database::Users::count(database).await?;
+            ScimListResponse::new(users, total_results, start_index)
+        }
+    };
+
+    Ok(scim_response(StatusCode::OK, list))
+}
+
+/// `GET /Users/{id}`
+#[tracing::instrument(name = "SCIM Read User Request: ", skip(state))]
+async fn read_user(
+    State(state): State<ScimState>,
+    client: ScimClient,
+    Path(id): Path<String>,
+) -> Result<Response, ScimError> {
+    let user = find_user(&id, state.database.as_ref()).await?;
+
+    Ok(scim_response(StatusCode::OK, ScimUser::from(user)))
+}
+
+/// `POST /Users`, provision a verified user
+#[tracing::instrument(name = "SCIM Create User Request: ", skip(state, message))]
+async fn create_user(
+    State(state): State<ScimState>,
+    client: ScimClient,
+    Json(message): Json<CreateScimUser>,
+) -> Result<Response, ScimError> {
+    let email = parse_email(&message.user_name)?;
+    let name = parse_name(&message.user_name())?;
+
+    if database::Users::exists_by_email(&email, state.database.as_ref()).await? {
+        return Err(ScimError::user_name_taken());
+    }
+
+    let password_hash = match message.password {
+        Some(password) => state.password_hasher.hash(password).await.map_err(|_| {
+            ScimError::bad_request("invalidValue", "Password does not meet the password policy")
+        })?,
+        None => domain::PasswordHash::dummy(),
+    };
+
+    let user = database::Users {
+        id: Uuid::now_v7(),
+        email,
+        name,
+        password_hash,
+        role: domain::UserRole::User,
+        is_active: message.active,
+        is_verified: true,
+        created_on: chrono::Utc::now(),
+        locale: domain::Locale::default(),
+    };
+
+    // Insert the user and queue the registration event in one transaction
+    let mut transaction = state.database.begin().await.map_err(AuthenticationError::from)?;
+    let user = user.insert(&mut *transaction).await?;
+    let event = AuthEvent::new(AuthEventKind::Registration).user(user.id);
+    database::Outbox::new(event.clone())
+        .insert(&mut *transaction)
+        .await?;
+    transaction.commit().await.map_err(AuthenticationError::from)?;
+    state.events.publish(event);
+
+    tracing::info!("SCIM user {} provisioned by API key {}", user.id, client.api_key_id);
+
+    let location = format!("/scim/v2/Users/{}", user.id);
+    let mut response = scim_response(StatusCode::CREATED, ScimUser::from(user));
+    if let Ok(location) = HeaderValue::from_str(&location) {
+        response.headers_mut().insert(header::LOCATION, location);
+    }
+
+    Ok(response)
+}
+
+/// `PATCH /Users/{id}`, update the user's attributes. Deactivating the user
+/// revokes their sessions.
+#[tracing::instrument(name = "SCIM Update User Request: ", skip(state, message))]
+async fn update_user(
+    State(state): State<ScimState>,
+    client: ScimClient,
+    Path(id): Path<String>,
+    Json(message): Json<ScimPatch>,
+) -> Result<Response, ScimError> {
+    let current = find_user(&id, state.database.as_ref()).await?;
+
+    let mut user = current.clone();
+    for operation in &message.operations {
+        if !["add", "replace"].contains(&operation.op.to_ascii_lowercase().as_str()) {
+            return Err(ScimError::bad_request(
+                "invalidSyntax",
+                "Only add and replace operations are supported",
+            ));
+        }
+        apply_patch_value(&mut user, operation.path.as_deref().unwrap_or_default(), &operation.value)?;
+    }
+
+    // Update the user and revoke the sessions of a deactivated user together
+    let mut transaction = state.database.begin().await.map_err(AuthenticationError::from)?;
+    let user = user.update(&mut *transaction).await?;
+    if current.is_active && !user.is_active {
+        database::Sessions::revoke_user_id(&user.id, &mut *transaction).await?;
+    }
+    transaction.commit().await.map_err(AuthenticationError::from)?;
+
+    tracing::info!("SCIM user {} updated by API key {}", user.id, client.api_key_id);
+
+    Ok(scim_response(StatusCode::OK, ScimUser::from(user)))
+}
+
+/// `DELETE /Users/{id}`, soft delete the user and revoke their sessions
+#[tracing::instrument(name = "SCIM Delete User Request: ", skip(state))]
+async fn delete_user(
+    State(state): State<ScimState>,
+    client: ScimClient,
+    Path(id): Path<String>,
+) -> Result<Response, ScimError> {
+    let user = find_user(&id, state.database.as_ref()).await?;
+
+    let mut transaction = state.database.begin().await.map_err(AuthenticationError::from)?;
+    user.delete(&mut *transaction).await?;
+    database::Sessions::revoke_user_id(&user.id, &mut *transaction).await?;
+    transaction.commit().await.map_err(AuthenticationError::from)?;
+
+    tracing::info!("SCIM user {} deleted by API key {}", user.id, client.api_key_id);
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    /// SCIM router with an API key for the role
+    fn scim(database: Pool<Postgres>, role: &domain::UserRole) -> (Router, domain::ApiKey) {
+        let api_key = domain::ApiKey::generate();
+        let api_keys = ApiKeyStore::default();
+        api_keys.insert(&database::ApiKeys::new("identity provider", role, &api_key, None));
+
+        let state = ScimState::new(
+            Arc::new(database),
+            api_keys,
+            AuthEvents::default(),
+            PasswordHasher::default(),
+        );
+        (router(state), api_key)
+    }
+
+    fn scim_request(
+        method: &str,
+        uri: &str,
+        api_key: &domain::ApiKey,
+        body: Option<Value>,
+    ) -> Result<Request<Body>> {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::AUTHORIZATION, format!("Bearer {}", api_key.expose()))
+            .header(header::CONTENT_TYPE, SCIM_CONTENT_TYPE);
+        let body = body.map(|body| Body::from(body.to_string())).unwrap_or_default();
+        Ok(request.body(body)?)
+    }
+
+    async fn json_body<T: serde::de::DeserializeOwned>(response: Response) -> Result<T> {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    #[test]
+    fn user_name_filters_are_parsed() -> Result<()> {
+        assert_eq!(
+            user_name_filter(r#"userName eq "jane@example.com""#)?,
+            "jane@example.com"
+        );
+        assert_eq!(user_name_filter(r#"username EQ "a b@example.com""#)?, "a b@example.com");
+        assert!(user_name_filter(r#"displayName eq "Jane""#).is_err());
+        assert!(user_name_filter(r#"userName sw "jane""#).is_err());
+        assert!(user_name_filter("userName eq jane@example.com").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn patch_without_path_replaces_attributes() -> Result<()> {
+        let mut user = database::Users::mock_data()?;
+        user.is_active = true;
+
+        apply_patch_value(
+            &mut user,
+            "",
+            &serde_json::json!({ "active": "False", "name": { "formatted": "Jane Doe" }, "externalId": "123" }),
+        )?;
+
+        assert!(!user.is_active);
+        assert_eq!(user.name.as_ref(), "Jane Doe");
+
+        Ok(())
+    }
+
+    #[test]
+    fn patch_can_not_change_the_user_name() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let mut user = database::Users::mock_data()?;
+        let email = user.email.clone();
+        let other_email = domain::EmailAddress::mock_data()?;
+
+        //-- Execute Function (Act)
+        let unchanged = apply_patch_value(
+            &mut user,
+            "userName",
+            &serde_json::json!(email.as_ref()),
+        );
+        let changed = apply_patch_value(
+            &mut user,
+            "",
+            &serde_json::json!({ "userName": other_email.as_ref() }),
+        );
+
+        //-- Checks (Assertions)
+        assert!(unchanged.is_ok());
+        let error = changed.expect_err("changing userName should be refused");
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        assert_eq!(error.scim_type, Some("mutability"));
+        assert_eq!(user.email, email);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn requests_need_an_admin_api_key(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let (admin_router, _admin_key) = scim(database.clone(), &domain::UserRole::Admin);
+        let (user_router, user_key) = scim(database, &domain::UserRole::User);
+
+        //-- Execute Function (Act)
+        let anonymous = admin_router
+            .oneshot(Request::get("/Users").body(Body::empty())?)
+            .await?;
+        let forbidden = user_router
+            .oneshot(scim_request("GET", "/Users", &user_key, None)?)
+            .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(anonymous.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(forbidden.status(), StatusCode::FORBIDDEN);
+        let error: ScimErrorBody = json_body(forbidden).await?;
+        assert_eq!(error.schemas, vec![ERROR_SCHEMA.to_string()]);
+        assert_eq!(error.status, "403");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn provisioned_users_can_be_found_deactivated_and_deleted(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let (router, api_key) = scim(database.clone(), &domain::UserRole::Admin);
+        let email = domain::EmailAddress::mock_data()?;
+        let create = serde_json::json!({
+            "schemas": [USER_SCHEMA],
+            "userName": email.as_ref(),
+            "name": { "givenName": "Jane", "familyName": "Doe" },
+            "active": true,
+        });
+
+        //-- Execute Function (Act)
+        let created = router
+            .clone()
+            .oneshot(scim_request("POST", "/Users", &api_key, Some(create.clone()))?)
+            .await?;
+        assert_eq!(created.status(), StatusCode::CREATED);
+        assert_eq!(created.headers()[header::CONTENT_TYPE], SCIM_CONTENT_TYPE);
+        let created: ScimUser = json_body(created).await?;
+
+        let duplicate = router
+            .clone()
+            .oneshot(scim_request("POST", "/Users", &api_key, Some(create))?)
+            .await?;
+
+        let filter = format!("/Users?filter=userName%20eq%20%22{}%22", email.as_ref());
+        let found: ScimListResponse = json_body(
+            router
+                .clone()
+                .oneshot(scim_request("GET", &filter, &api_key, None)?)
+                .await?,
+        )
+        .await?;
+
+        let patch = serde_json::json!({
+            "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
+            "Operations": [{ "op": "Replace", "path": "active", "value": false }],
+        });
+        let patched: ScimUser = json_body(
+            router
+                .clone()
+                .oneshot(scim_request(
+                    "PATCH",
+                    &format!("/Users/{}", created.id),
+                    &api_key,
+                    Some(patch),
+                )?)
+                .await?,
+        )
+        .await?;
+
+        let deleted = router
+            .clone()
+            .oneshot(scim_request("DELETE", &format!("/Users/{}", created.id), &api_key, None)?)
+            .await?;
+        let missing = router
+            .oneshot(scim_request("GET", &format!("/Users/{}", created.id), &api_key, None)?)
+            .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(created.user_name, email.as_ref());
+        assert_eq!(created.display_name, "Jane Doe");
+        assert!(created.active);
+
+        assert_eq!(duplicate.status(), StatusCode::CONFLICT);
+        let error: ScimErrorBody = json_body(duplicate).await?;
+        assert_eq!(error.scim_type.as_deref(), Some("uniqueness"));
+
+        assert_eq!(found.total_results, 1);
+        assert_eq!(found.resources[0].id, created.id);
+
+        assert!(!patched.active);
+
+        assert_eq!(deleted.status(), StatusCode::NO_CONTENT);
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+}
@@ -4,15 +4,32 @@
 
 pub use error::AuthenticationError;
 
+pub mod avatars;
+pub mod build_info;
+pub mod check_config;
+pub mod cli;
+#[cfg(feature = "client")]
+pub mod client;
 pub mod configuration;
 pub mod database;
+#[cfg(feature = "demo")]
+pub mod demo;
 pub mod domain;
+pub mod email;
 mod error;
+pub mod error_messages;
+pub mod error_reporting;
+pub mod event_bus;
+pub mod events;
+pub mod http;
 pub mod middleware;
 pub mod prelude;
+pub mod readiness;
+pub mod repository;
 pub mod router;
 pub mod rpc;
 pub mod services;
 pub mod startup;
 pub mod telemetry;
 pub mod utils;
+pub mod warm_up;
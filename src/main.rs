@@ -3,10 +3,20 @@
 // #![allow(unused)] // For beginning only.
 
 // For intellisense
+mod build_info;
+mod check_config;
+mod cli;
 mod configuration;
 mod database;
+#[cfg(feature = "demo")]
+mod demo;
 mod domain;
+mod email;
 mod error;
+mod error_reporting;
+mod event_bus;
+mod events;
+mod http;
 mod middleware;
 mod prelude;
 mod router;
@@ -15,9 +25,9 @@ mod services;
 mod startup;
 mod telemetry;
 mod utils;
+mod warm_up;
 
-// use configuration::Configuration;
-
+use clap::Parser;
 use configuration::Configuration;
 
 use crate::prelude::*;
@@ -25,16 +35,60 @@ use crate::prelude::*;
 /// Binary entry point
 #[tokio::main]
 async fn main() -> Result<(), AuthenticationError> {
+    let cli = cli::Cli::parse();
+
+    // Run self-contained with an embedded database and seeded accounts
+    if cli.demo {
+        #[cfg(feature = "demo")]
+        return demo::run().await;
+
+        #[cfg(not(feature = "demo"))]
+        return Err(AuthenticationError::Static(
+            "Demo mode is not available, rebuild with `--features demo`",
+        ));
+    }
+
+    // Validate configuration and connectivity, then exit
+    if cli.check_config {
+        return check_config::run().await;
+    }
+
     // Parse configuration files
     let config = Configuration::parse()?;
+    config.validate()?;
+
+    // Check every name parsed from here on against the configured policy
+    config.user_names.policy()?.install();
+
+    // Run admin commands and exit
+    match cli.command {
+        None | Some(cli::Command::Serve) => {}
+        Some(command) => return command.run(&config).await,
+    }
+
+    // Report internal errors and panics, kept alive until the server stops
+    let _error_reporting = error_reporting::init(&config.error_reporting)?;
 
     // Start tracing
     let log_level = config.application.log_level;
-    let _telemetry = telemetry::init(log_level)?;
+    let telemetry = telemetry::init(log_level, &config.telemetry)?;
+    tracing::debug!("{}", config.redacted());
 
     let database = database::init_pool(&config.database).await?;
 
+    let hot_reload = config.application.hot_reload;
     let tonic_server = startup::TonicServer::build(config, database).await?;
+
+    // Reload non-critical settings when the configuration files change
+    let _config_watcher = if hot_reload {
+        Some(configuration::reload::watch(
+            tonic_server.config.clone(),
+            Some(telemetry.log_level_handle()),
+        )?)
+    } else {
+        None
+    };
+
     let _tonic_server = tonic_server.run().await;
 
     Ok(())
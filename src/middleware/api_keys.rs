@@ -0,0 +1,185 @@
+//-- ./src/middleware/api_keys.rs
+
+// #![allow(unused)] // For development only
+
+//! # API Key Store
+//!
+//! In memory index of the usable API keys by key hash, so the synchronous
+//! authorisation layer can authenticate `x-api-key` requests without a
+//! database round trip. It is loaded at startup, updated as keys are created
+//! and revoked, and reloaded from the table every `REFRESH_INTERVAL`, picking
+//! up keys created or revoked by other replicas.
+//! ---
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::prelude::*;
+use crate::{database, domain};
+
+/// How often the API keys are reloaded from the database
+const REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// An authenticated API key, added to the request extensions by the authorisation layer
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiKeyIdentity {
+    pub id: Uuid,
+    pub role: domain::UserRole,
+    pub expires_on: Option<DateTime<Utc>>,
+}
+
+impl From<&database::ApiKeys> for ApiKeyIdentity {
+    fn from(api_key: &database::ApiKeys) -> Self {
+        Self {
+            id: api_key.id,
+            role: api_key.role.clone(),
+            expires_on: api_key.expires_on,
+        }
+    }
+}
+
+/// Shared index of usable API keys, cheap to clone into each service
+#[derive(Debug, Clone, Default)]
+pub struct ApiKeyStore {
+    keys: Arc<RwLock<HashMap<String, ApiKeyIdentity>>>,
+}
+
+impl ApiKeyStore {
+    /// Load the usable API keys from the database
+    pub async fn load(database: &Pool<Postgres>) -> Result<Self, AuthenticationError> {
+        let store = Self::default();
+        store.refresh(database).await?;
+
+        Ok(store)
+    }
+
+    /// Replace the keys with the usable keys in the database, dropping those
+    /// revoked by other replicas since the last refresh
+    pub async fn refresh(
+        &self,
+        database: &Pool<Postgres>,
+    ) -> Result<(), AuthenticationError> {
+        let usable: HashMap<String, ApiKeyIdentity> =
+            database::ApiKeys::index_usable(database)
+                .await?
+                .iter()
+                .filter(|api_key| api_key.is_usable())
+                .map(|api_key| {
+                    (api_key.key_hash.clone(), ApiKeyIdentity::from(api_key))
+                })
+                .collect();
+
+        *self.keys.write().unwrap_or_else(|e| e.into_inner()) = usable;
+
+        Ok(())
+    }
+
+    /// Reload the API keys from the database in the background
+    pub fn spawn(self, database: Pool<Postgres>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+            // The first tick completes straight away, the keys were just loaded
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.refresh(&database).await {
+                    tracing::error!("Unable to refresh the API keys: {e}");
+                }
+            }
+        });
+    }
+
+    /// Add an API key, ignored if it is revoked or expired
+    pub fn insert(&self, api_key: &database::ApiKeys) {
+        if !api_key.is_usable() {
+            return;
+        }
+
+        let mut keys = self.keys.write().unwrap_or_else(|e| e.into_inner());
+        keys.insert(api_key.key_hash.clone(), ApiKeyIdentity::from(api_key));
+    }
+
+    /// Remove an API key by id
+    pub fn remove(&self, id: &Uuid) {
+        let mut keys = self.keys.write().unwrap_or_else(|e| e.into_inner());
+        keys.retain(|_, identity| identity.id != *id);
+    }
+
+    /// Look up the presented API key, returning `None` if it is unknown or expired
+    pub fn authenticate(&self, api_key: &domain::ApiKey) -> Option<ApiKeyIdentity> {
+        let keys = self.keys.read().unwrap_or_else(|e| e.into_inner());
+
+        keys.get(&api_key.hash())
+            .filter(|identity| identity.expires_on.is_none_or(|expires_on| expires_on > Utc::now()))
+            .cloned()
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn authenticates_usable_keys_until_removed() {
+        //-- Setup and Fixtures (Arrange)
+        let store = ApiKeyStore::default();
+        let (record, api_key) = database::ApiKeys::mock_data();
+        let (mut expired, expired_key) = database::ApiKeys::mock_data();
+        expired.expires_on = Some(Utc::now() - Duration::days(1));
+
+        //-- Execute Function (Act)
+        store.insert(&record);
+        store.insert(&expired);
+
+        //-- Checks (Assertions)
+        let identity = store.authenticate(&api_key).expect("key is usable");
+        assert_eq!(identity.id, record.id);
+        assert_eq!(identity.role, record.role);
+        assert!(store.authenticate(&expired_key).is_none());
+        assert!(store.authenticate(&domain::ApiKey::generate()).is_none());
+
+        store.remove(&record.id);
+        assert!(store.authenticate(&api_key).is_none());
+    }
+
+    #[sqlx::test]
+    async fn keys_changed_after_loading_are_refreshed(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let (revoked, revoked_key) = database::ApiKeys::mock_data();
+        let revoked = revoked.insert(&database).await?;
+        let store = ApiKeyStore::load(&database).await?;
+
+        // Revoked and created by another replica, straight in the table
+        database::ApiKeys::revoke_by_id(&revoked.id, &database).await?;
+        let (created, created_key) = database::ApiKeys::mock_data();
+        created.insert(&database).await?;
+        let authenticated_before_refresh = (
+            store.authenticate(&revoked_key).is_some(),
+            store.authenticate(&created_key).is_some(),
+        );
+
+        //-- Execute Function (Act)
+        store.refresh(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(authenticated_before_refresh, (true, false));
+        assert!(store.authenticate(&revoked_key).is_none());
+        assert!(store.authenticate(&created_key).is_some());
+
+        Ok(())
+    }
+}
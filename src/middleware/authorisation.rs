@@ -2,51 +2,110 @@
 
 // #![allow(unused)] // For beginning only.
 
-/// # Access Token Interceptor
+/// # Authorisation Layer
 ///
-/// This interceptor is used to validate the access token in the request
-/// metadata. It checks if the access token is present and valid.
-/// If the access token is not present or invalid, it returns an error.
+/// This layer enforces the `MethodPolicies` policy of each request. Public
+/// methods pass straight through, the others need a valid access token in the
+/// request metadata, with the role or permission the policy asks for. If the
+/// access token is not present, invalid or denied it returns `UNAUTHENTICATED`,
+/// and when the caller is not allowed, or the method has no policy,
+/// `PERMISSION_DENIED`.
+///
+/// Service accounts can present an API key in the `x-api-key` metadata instead,
+/// which is authenticated against the API key store and scoped to the key role.
+///
+/// Users who have not accepted the configured policy versions are turned away
+/// with `PolicyAcceptanceRequired`, except when accepting them or reading
+/// their own profile.
+///
+/// The authenticated `ApiKeyIdentity`, or the access token `AuthContext` and
+/// `TokenClaim`, is added to the request extensions for the services.
+/// Requests with an access token are counted against its session in
+/// `SessionActivity`.
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
 use secrecy::SecretString;
+use tonic::metadata::MetadataMap;
+use tower::BoxError;
+use tower::Service;
+use tower_layer::Layer;
 
 use crate::{domain, prelude::*};
-use std::str::FromStr;
+
+use super::{
+    ApiKeyStore, MethodPolicies, MethodPolicy, PolicyAcceptanceStore, SessionActivity,
+    TokenDenylist,
+};
+
+/// Methods users can call before accepting the configured policies
+const POLICY_EXEMPT_METHODS: [&str; 2] = ["AcceptPolicy", "GetMe"];
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
 
 #[derive(Clone)]
-pub struct AuthorisationInterceptor {
+pub struct AuthorisationLayer {
     pub(crate) token_secret: SecretString,
     pub(crate) issuer: SecretString,
-    pub(crate) allowable_roles: Vec<domain::UserRole>,
+    pub(crate) audiences: Vec<String>,
+    pub(crate) method_policies: MethodPolicies,
+    pub(crate) denylist: TokenDenylist,
+    pub(crate) api_keys: ApiKeyStore,
+    pub(crate) policies: PolicyAcceptanceStore,
+    pub(crate) activity: SessionActivity,
 }
 
-impl tonic::service::Interceptor for AuthorisationInterceptor {
-    /// Intercept the request and check if the access token is valid
-    #[tracing::instrument(
-        name = "Authorisation Interceptor: ", 
-        skip(self),
-        fields(
-            header = ?request.metadata(),
-        )
-    )]
-    fn call(
-        &mut self,
-        request: tonic::Request<()>,
-    ) -> Result<tonic::Request<()>, tonic::Status> {
-        // Get the metadata from the tonic request
-        let metadata: &tonic::metadata::MetadataMap = request.metadata();
+impl AuthorisationLayer {
+    /// Check the request metadata against the policy of the method path,
+    /// adding the caller to the request extensions
+    #[tracing::instrument(name = "Authorisation Layer: ", skip(self, metadata, extensions))]
+    fn authorise(
+        &self,
+        path: &str,
+        metadata: &MetadataMap,
+        extensions: &mut http::Extensions,
+    ) -> Result<(), tonic::Status> {
+        // Deny methods without a policy, startup checks every served method has one
+        let policy = self.method_policies.get(path).ok_or_else(|| {
+            tracing::error!("No authorisation policy for {path}!");
+            tonic::Status::permission_denied("Authorisation Failed!")
+        })?;
+        if policy == &MethodPolicy::Public {
+            return Ok(());
+        }
+
+        // Service accounts authenticate with an API key instead of an access token
+        if let Some(api_key) = domain::ApiKey::from_header(metadata)? {
+            let identity = self.api_keys.authenticate(&api_key).ok_or_else(|| {
+                tracing::error!("API key is unknown, revoked or expired!");
+                tonic::Status::unauthenticated("Authentication Failed!")
+            })?;
+
+            let permissions = identity.role.permissions();
+            if !policy.allows(&identity.role, |permission| permissions.contains(&permission)) {
+                tracing::error!("API key role is not authorised!");
+                return Err(tonic::Status::permission_denied("Authorisation Failed!"));
+            }
+
+            tracing::info!("API key authenticated: {}", identity.id);
+            extensions.insert(identity);
+
+            return Ok(());
+        }
 
         // Get the access token from the header metadata
         let access_token_bearer = domain::AccessToken::parse_header(metadata)?;
 
-        println!("Access Token: {:?}", access_token_bearer);
-
         // Using the Token Secret decode the Access Token string into a Token Claim.
-        // This validates the token expiration, not before and Issuer.
+        // This validates the token expiration, not before, Issuer and Audience.
         // TODO: Map out domains and refactor tokens
-        let access_token_claim = domain::TokenClaim::parse(
+        let access_token_claim = domain::TokenClaim::parse_with_audiences(
             &access_token_bearer.to_string(),
             &self.token_secret,
             &self.issuer,
+            &self.audiences,
         )
         .map_err(|_| {
             tracing::error!("Access Token is invalid! Unable to parse token claim.");
@@ -60,26 +119,97 @@ impl tonic::service::Interceptor for AuthorisationInterceptor {
             access_token_claim.sub
         );
 
-        // Parse Token Claim user role into domain user role
-        let role = domain::UserRole::from_str(access_token_claim.jur.as_str())
-            .map_err(|_| {
-                tracing::error!("Access Token user role is invalid!");
-                // Return error
-                AuthenticationError::AuthenticationError(
-                    "Authentication Failed! No valid auth token.".to_string(),
-                )
-            })?;
+        // Reject access tokens denied before they expired, e.g. after a password change
+        if self.denylist.is_denied(&access_token_claim.jti) {
+            tracing::error!("Access Token is denied: {}", access_token_claim.jti);
+            return Err(tonic::Status::unauthenticated("Authentication Failed!"));
+        }
+
+        // The caller, parsed once for the policy checks and the services
+        let auth_context = domain::AuthContext::from_claim(&access_token_claim)?;
 
-        // Check if the access token user role is in the list of allowable roles
+        // Check the access token role or permissions satisfy the method policy
         // If not, return an error
-        if !self.allowable_roles.contains(&role) {
+        if !policy.allows(&auth_context.role, |permission| {
+            access_token_claim.has_permission(permission)
+        }) {
             tracing::error!("Access Token user role is not authorised!");
-            // Return error
-            return Err(tonic::Status::unauthenticated("Authentication Failed!"));
+            // The caller is authenticated, but not allowed to call the method
+            return Err(tonic::Status::permission_denied("Authorisation Failed!"));
+        }
+
+        // Hold back users until they accept the configured policy versions
+        let method = path.rsplit('/').next().unwrap_or_default();
+        if !POLICY_EXEMPT_METHODS.contains(&method) {
+            let user_id = auth_context.user_id;
+            let outstanding = self.policies.outstanding(&user_id);
+            if !outstanding.is_empty() {
+                tracing::info!("User {user_id} must accept policies: {outstanding:?}");
+                return Err(AuthenticationError::PolicyAcceptanceRequired(outstanding).into());
+            }
         }
 
         tracing::info!("Authorization request header validated.");
 
-        Ok(request)
+        // Count the request against the session the access token was issued for
+        self.activity.record_access_token(&access_token_claim.jti);
+
+        // Services read the caller from the context, and the claim to scope
+        // queries, e.g. to the token organization
+        extensions.insert(auth_context);
+        extensions.insert(access_token_claim);
+
+        Ok(())
+    }
+}
+
+impl<S> Layer<S> for AuthorisationLayer {
+    type Service = Authorisation<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Authorisation {
+            inner,
+            layer: Arc::new(self.clone()),
+        }
+    }
+}
+
+/// Service created by [`AuthorisationLayer`]
+#[derive(Clone)]
+pub struct Authorisation<S> {
+    inner: S,
+    layer: Arc<AuthorisationLayer>,
+}
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for Authorisation<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>>,
+    S::Error: Into<BoxError>,
+    S::Future: Send + 'static,
+    ResBody: Default + Send + 'static,
+{
+    type Response = http::Response<ResBody>;
+    type Error = BoxError;
+    type Future = BoxFuture<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
     }
-}
\ No newline at end of file
+
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        // Check the metadata as tonic does, then hand the headers back
+        let (mut parts, body) = request.into_parts();
+        let metadata = MetadataMap::from_headers(std::mem::take(&mut parts.headers));
+        let authorised = self
+            .layer
+            .authorise(parts.uri.path(), &metadata, &mut parts.extensions);
+        parts.headers = metadata.into_headers();
+
+        if let Err(status) = authorised {
+            return Box::pin(std::future::ready(Ok(status.into_http())));
+        }
+
+        let future = self.inner.call(http::Request::from_parts(parts, body));
+        Box::pin(async move { future.await.map_err(Into::into) })
+    }
+}
@@ -0,0 +1,169 @@
+//-- ./src/middleware/catch_panic.rs
+
+// #![allow(unused)] // For development only
+
+//! # Catch Panic
+//!
+//! A panic in a handler would otherwise tear down the connection, failing
+//! every call on it. [`catch_panic_layer`] catches the panic and answers the
+//! request with an `Internal` status carrying a correlation id, in the message
+//! and the `x-correlation-id` metadata, that finds the panic in the logs.
+//!
+//! The backtrace is captured where the panic happens, by a panic hook that
+//! then calls the hook it replaced, so the Sentry panic integration still
+//! reports it, see `crate::error_reporting`. Panics are counted by the
+//! `auth.panics` metric.
+//! ---
+
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::RefCell;
+use std::sync::Once;
+
+use opentelemetry::metrics::Counter;
+use tower_http::catch_panic::{CatchPanicLayer, ResponseForPanic};
+
+use crate::error::ERROR_CODE_HEADER;
+
+/// Metadata with the id of a panic in the logs
+pub static CORRELATION_ID_HEADER: &str = "x-correlation-id";
+
+thread_local! {
+    /// The backtrace of the last panic on the thread, taken by the handler
+    static PANIC_BACKTRACE: RefCell<Option<Backtrace>> = const { RefCell::new(None) };
+}
+
+static PANIC_HOOK: Once = Once::new();
+
+/// Keep the backtrace of each panic for [`PanicResponse`] to log, then call
+/// the previous hook
+fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            PANIC_BACKTRACE.with(|backtrace| {
+                *backtrace.borrow_mut() = Some(Backtrace::force_capture());
+            });
+            previous(info);
+        }));
+    });
+}
+
+/// The message a panic was raised with
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>")
+}
+
+/// Answer panicked requests with an `Internal` status
+#[derive(Clone)]
+pub struct PanicResponse {
+    panics: Counter<u64>,
+}
+
+impl PanicResponse {
+    pub fn new() -> Self {
+        install_panic_hook();
+        let meter = opentelemetry::global::meter("authentication_service");
+
+        Self {
+            panics: meter
+                .u64_counter("auth.panics")
+                .with_description("Requests that panicked")
+                .build(),
+        }
+    }
+}
+
+impl Default for PanicResponse {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResponseForPanic for PanicResponse {
+    type ResponseBody = tonic::body::Body;
+
+    fn response_for_panic(
+        &mut self,
+        panic: Box<dyn Any + Send + 'static>,
+    ) -> http::Response<Self::ResponseBody> {
+        let correlation_id = uuid::Uuid::now_v7();
+        let backtrace = PANIC_BACKTRACE
+            .with(|backtrace| backtrace.borrow_mut().take())
+            .map(|backtrace| backtrace.to_string())
+            .unwrap_or_default();
+        tracing::error!(
+            %correlation_id,
+            "Request panicked: {}\n{backtrace}",
+            panic_message(panic.as_ref())
+        );
+        self.panics.add(1, &[]);
+
+        let mut status = tonic::Status::internal(format!(
+            "Internal error, correlation id {correlation_id}"
+        ));
+        if let Ok(value) = correlation_id.to_string().parse() {
+            status.metadata_mut().insert(CORRELATION_ID_HEADER, value);
+        }
+        if let Ok(value) = "INTERNAL".parse() {
+            status.metadata_mut().insert(ERROR_CODE_HEADER, value);
+        }
+
+        status.into_http()
+    }
+}
+
+/// Catch panics below the layer, answering with an `Internal` status
+pub fn catch_panic_layer() -> CatchPanicLayer<PanicResponse> {
+    CatchPanicLayer::custom(PanicResponse::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use tower::{ServiceBuilder, ServiceExt};
+
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[tokio::test]
+    async fn panics_are_answered_with_an_internal_status() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let service = ServiceBuilder::new()
+            .layer(catch_panic_layer())
+            .service(tower::service_fn(|_request: http::Request<()>| async move {
+                if true {
+                    panic!("Handler bug");
+                }
+                Ok::<_, Infallible>(http::Response::new(tonic::body::Body::empty()))
+            }));
+        let request = http::Request::builder()
+            .uri("/authentication.v1.UsersService/GetMe")
+            .body(())?;
+
+        //-- Execute Function (Act)
+        let response = service.oneshot(request).await?;
+
+        //-- Checks (Assertions)
+        let status = tonic::Status::from_header_map(response.headers())
+            .expect("the response has a status");
+        let correlation_id = status
+            .metadata()
+            .get(CORRELATION_ID_HEADER)
+            .expect("the status has a correlation id")
+            .to_str()?;
+        assert_eq!(status.code(), tonic::Code::Internal);
+        assert!(status.message().contains(correlation_id));
+        assert!(!status.message().contains("Handler bug"));
+
+        Ok(())
+    }
+}
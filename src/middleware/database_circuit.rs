@@ -0,0 +1,160 @@
+//-- ./src/middleware/database_circuit.rs
+
+// #![allow(unused)] // For development only
+
+//! # Database Circuit
+//!
+//! Answers requests with `Unavailable` straight away while the database
+//! circuit breaker is open, rather than each waiting for a connection attempt
+//! to time out, see `database::CircuitBreaker`. The health, reflection and
+//! utilities services don't need the database, e.g. to report readiness, so
+//! they are always served.
+//! ---
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tower::Service;
+use tower_layer::Layer;
+
+use crate::database::{self, CircuitBreaker};
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// Services served while the circuit is open, as path prefixes
+const EXEMPT_PREFIXES: [&str; 2] = ["/grpc.health.", "/grpc.reflection."];
+
+/// Services served while the circuit is open, of any API version
+const EXEMPT_SERVICES: [&str; 1] = ["UtilitiesService"];
+
+/// Does the gRPC path need the database
+fn needs_database(path: &str) -> bool {
+    if EXEMPT_PREFIXES.iter().any(|prefix| path.starts_with(prefix)) {
+        return false;
+    }
+
+    let service = path
+        .trim_start_matches('/')
+        .split('/')
+        .next()
+        .and_then(|service| service.rsplit('.').next())
+        .unwrap_or_default();
+    !EXEMPT_SERVICES.contains(&service)
+}
+
+/// Fail requests needing the database fast while its circuit is open
+#[derive(Debug, Clone, Copy)]
+pub struct DatabaseCircuitLayer {
+    breaker: &'static CircuitBreaker,
+}
+
+impl DatabaseCircuitLayer {
+    /// Check the shared circuit breaker, see `database::circuit_breaker`
+    pub fn new() -> Self {
+        Self {
+            breaker: database::circuit_breaker(),
+        }
+    }
+}
+
+impl Default for DatabaseCircuitLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Layer<S> for DatabaseCircuitLayer {
+    type Service = DatabaseCircuit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        DatabaseCircuit {
+            inner,
+            breaker: self.breaker,
+        }
+    }
+}
+
+/// Service created by [`DatabaseCircuitLayer`]
+#[derive(Debug, Clone)]
+pub struct DatabaseCircuit<S> {
+    inner: S,
+    breaker: &'static CircuitBreaker,
+}
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for DatabaseCircuit<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>>,
+    S::Future: Send + 'static,
+    ResBody: Default,
+{
+    type Response = http::Response<ResBody>;
+    type Error = S::Error;
+    type Future = BoxFuture<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        if needs_database(request.uri().path()) {
+            if let Err(error) = self.breaker.allow() {
+                let status = tonic::Status::from(error);
+                return Box::pin(async move { Ok(status.into_http()) });
+            }
+        }
+
+        Box::pin(self.inner.call(request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use tower::{ServiceBuilder, ServiceExt};
+
+    use super::*;
+    use crate::configuration::CircuitBreakerConfiguration;
+
+    #[test]
+    fn health_and_utilities_do_not_need_the_database() {
+        assert!(needs_database("/authentication.v1.AuthenticationService/Login"));
+        assert!(!needs_database("/grpc.health.v1.Health/Check"));
+        assert!(!needs_database("/grpc.reflection.v1.ServerReflection/ServerReflectionInfo"));
+        assert!(!needs_database("/authentication.v1.UtilitiesService/Ping"));
+    }
+
+    #[tokio::test]
+    async fn requests_are_rejected_while_the_circuit_is_open() -> Result<(), Infallible> {
+        //-- Setup and Fixtures (Arrange)
+        let breaker = Box::leak(Box::new(CircuitBreaker::new(&CircuitBreakerConfiguration {
+            enabled: true,
+            failure_threshold: 1,
+            open_seconds: 60,
+        })));
+        breaker.record_failure();
+        let service = ServiceBuilder::new()
+            .layer(DatabaseCircuitLayer { breaker })
+            .service(tower::service_fn(|_request: http::Request<()>| async move {
+                Ok::<_, Infallible>(http::Response::new(()))
+            }));
+        let request = |path: &str| http::Request::builder().uri(path).body(()).unwrap();
+
+        //-- Execute Function (Act)
+        let login = service
+            .clone()
+            .oneshot(request("/authentication.v1.AuthenticationService/Login"))
+            .await?;
+        let ping = service
+            .oneshot(request("/authentication.v1.UtilitiesService/Ping"))
+            .await?;
+
+        //-- Checks (Assertions)
+        let status = tonic::Status::from_header_map(login.headers());
+        assert_eq!(status.map(|status| status.code()), Some(tonic::Code::Unavailable));
+        assert!(tonic::Status::from_header_map(ping.headers()).is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+//-- ./src/middleware/denylist.rs
+
+// #![allow(unused)] // For development only
+
+//! # Access Token Denylist
+//!
+//! In memory copy of the `access_token_denylist` table, so the synchronous
+//! authorisation layer can reject denied access tokens without a
+//! database round trip. It is loaded at startup, updated as tokens are
+//! denied and refreshed from the table every `REFRESH_INTERVAL`, picking up
+//! tokens denied by other replicas. Entries are dropped once the access token
+//! has expired, since the token claim is rejected from then on anyway.
+//! ---
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+
+use crate::database;
+use crate::prelude::*;
+
+/// How often the denylist is refreshed from the database, shorter than any
+/// access token lifetime, which is configured in whole minutes
+const REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Shared set of denied access token ids (jti), cheap to clone into each service
+#[derive(Debug, Clone, Default)]
+pub struct TokenDenylist {
+    entries: Arc<RwLock<HashMap<String, DateTime<Utc>>>>,
+}
+
+impl TokenDenylist {
+    /// Load the unexpired denylist entries from the database
+    pub async fn load(database: &Pool<Postgres>) -> Result<Self, AuthenticationError> {
+        let denylist = Self::default();
+        denylist.refresh(database).await?;
+
+        Ok(denylist)
+    }
+
+    /// Add the unexpired entries in the database, including those denied by
+    /// other replicas since the last refresh
+    pub async fn refresh(
+        &self,
+        database: &Pool<Postgres>,
+    ) -> Result<(), AuthenticationError> {
+        let denied =
+            database::AccessTokenDenylist::index_unexpired(database).await?;
+
+        let now = Utc::now();
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|_, expires_on| *expires_on > now);
+        entries.extend(
+            denied
+                .into_iter()
+                .filter(|entry| entry.expires_on > now)
+                .map(|entry| (entry.jti, entry.expires_on)),
+        );
+
+        Ok(())
+    }
+
+    /// Refresh the denylist from the database in the background
+    pub fn spawn(self, database: Pool<Postgres>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+            // The first tick completes straight away, the denylist was just loaded
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.refresh(&database).await {
+                    tracing::error!("Unable to refresh the access token denylist: {e}");
+                }
+            }
+        });
+    }
+
+    /// Deny an access token id until it expires
+    pub fn deny(&self, jti: &str, expires_on: DateTime<Utc>) {
+        let now = Utc::now();
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+
+        // Drop entries for tokens that have expired since they were denied
+        entries.retain(|_, expires_on| *expires_on > now);
+
+        if expires_on > now {
+            entries.insert(jti.to_string(), expires_on);
+        }
+    }
+
+    /// Is the access token id denied
+    pub fn is_denied(&self, jti: &str) -> bool {
+        let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+
+        entries
+            .get(jti)
+            .is_some_and(|expires_on| *expires_on > Utc::now())
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use uuid::Uuid;
+
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn denied_until_expiry() {
+        //-- Setup and Fixtures (Arrange)
+        let denylist = TokenDenylist::default();
+
+        //-- Execute Function (Act)
+        denylist.deny("current", Utc::now() + Duration::minutes(5));
+        denylist.deny("expired", Utc::now() - Duration::minutes(5));
+
+        //-- Checks (Assertions)
+        assert!(denylist.is_denied("current"));
+        assert!(!denylist.is_denied("expired"));
+        assert!(!denylist.is_denied("unknown"));
+
+        // Clones share the same entries
+        let clone = denylist.clone();
+        clone.deny("shared", Utc::now() + Duration::minutes(5));
+        assert!(denylist.is_denied("shared"));
+    }
+
+    #[sqlx::test]
+    async fn tokens_denied_after_loading_are_refreshed(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let random_user = database::Users::mock_data()?.insert(&database).await?;
+        let denylist = TokenDenylist::load(&database).await?;
+
+        // Denied by another replica, straight into the table
+        let jti = Uuid::now_v7().to_string();
+        database::AccessTokenDenylist::new(
+            &jti,
+            &random_user.id,
+            &(Utc::now() + Duration::minutes(5)),
+        )
+        .insert(&database)
+        .await?;
+        let denied_before_refresh = denylist.is_denied(&jti);
+
+        //-- Execute Function (Act)
+        denylist.refresh(&database).await?;
+
+        //-- Checks (Assertions)
+        assert!(!denied_before_refresh);
+        assert!(denylist.is_denied(&jti));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,291 @@
+//-- ./src/middleware/error_messages.rs
+
+// #![allow(unused)] // For development only
+
+//! # Error Messages
+//!
+//! A tower layer that adds a stable code and a localised message to the
+//! details of every error status, so clients can show the error in the user's
+//! language and act on it without parsing the English `grpc-message`.
+//!
+//! The details, in `grpc-status-details-bin`, are:
+//! 1. **`authentication.v1.ErrorDetail`**: the `ErrorCode`, the request field
+//!    the error is about and a link to help with it.
+//! 2. **`google.rpc.ErrorInfo`**: the code as text in its `reason`.
+//! 3. **`google.rpc.LocalizedMessage`**: the message in the best language of
+//!    the request's `accept-language` metadata, see
+//!    `error_messages::ErrorMessages`.
+//!
+//! The code and field are the `x-error-code` and `x-error-field` metadata set
+//! by `AuthenticationError`. Errors without a code use the name of their gRPC
+//! status code.
+//!
+//! Only errors returned before the response body, as unary calls return them,
+//! are localised. An error ending a stream is sent as-is.
+//! ---
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use http::header::ACCEPT_LANGUAGE;
+use http::HeaderMap;
+use prost::bytes::Bytes;
+use prost::Message;
+use tonic_types::StatusExt;
+use tower::Service;
+use tower_layer::Layer;
+
+use crate::domain;
+use crate::error::{error_code_from_name, ERROR_CODE_HEADER, ERROR_FIELD_HEADER};
+use crate::error_messages::{status_code_name, ErrorMessages};
+use crate::rpc::proto::ErrorDetail;
+
+/// The `ErrorInfo` domain of the errors returned by the service
+const ERROR_DOMAIN: &str = "authentication_service";
+
+/// The `Any` type URL of an `ErrorDetail` in the status details
+pub const ERROR_DETAIL_TYPE_URL: &str = "type.googleapis.com/authentication.v1.ErrorDetail";
+
+/// `google.rpc.Status`, the message encoded in `grpc-status-details-bin`
+#[derive(Clone, PartialEq, Message)]
+struct RpcStatus {
+    #[prost(int32, tag = "1")]
+    code: i32,
+    #[prost(string, tag = "2")]
+    message: String,
+    #[prost(message, repeated, tag = "3")]
+    details: Vec<prost_types::Any>,
+}
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// Add the error code and localised message to the details of error statuses
+#[derive(Debug, Clone)]
+pub struct ErrorMessagesLayer {
+    messages: Arc<ErrorMessages>,
+}
+
+impl ErrorMessagesLayer {
+    /// Localise errors with the loaded translations
+    pub fn new(messages: ErrorMessages) -> Self {
+        Self {
+            messages: Arc::new(messages),
+        }
+    }
+}
+
+impl<S> Layer<S> for ErrorMessagesLayer {
+    type Service = LocalisedErrors<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        LocalisedErrors {
+            inner,
+            messages: Arc::clone(&self.messages),
+        }
+    }
+}
+
+/// Service created by [`ErrorMessagesLayer`]
+#[derive(Debug, Clone)]
+pub struct LocalisedErrors<S> {
+    inner: S,
+    messages: Arc<ErrorMessages>,
+}
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for LocalisedErrors<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = http::Response<ResBody>;
+    type Error = S::Error;
+    type Future = BoxFuture<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        let locale = request
+            .headers()
+            .get(ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .map(|accept_language| self.messages.negotiate(accept_language))
+            .unwrap_or_default();
+        let messages = Arc::clone(&self.messages);
+        let future = self.inner.call(request);
+
+        Box::pin(async move {
+            let mut response = future.await?;
+            localise(&messages, &locale, response.headers_mut());
+            Ok(response)
+        })
+    }
+}
+
+/// Replace the error status in the response headers, if there is one, with
+/// the same status plus the error details
+fn localise(messages: &ErrorMessages, locale: &domain::Locale, headers: &mut HeaderMap) {
+    let Some(status) = tonic::Status::from_header_map(headers) else {
+        return;
+    };
+    if status.code() == tonic::Code::Ok {
+        return;
+    }
+
+    let metadata = |key: &str| {
+        status
+            .metadata()
+            .get(key)
+            .and_then(|value| value.to_str().ok())
+    };
+    let code = metadata(ERROR_CODE_HEADER).unwrap_or_else(|| status_code_name(status.code()));
+
+    // Keep the details already on the status, e.g. a BadRequest
+    let mut details = status.get_error_details();
+    details.set_error_info(code, ERROR_DOMAIN, HashMap::new());
+    if let Some((locale, message)) = messages.message(locale, code) {
+        details.set_localized_message(locale, message);
+    }
+    let localised = tonic::Status::with_error_details(status.code(), status.message(), details);
+
+    // Add the ErrorDetail to the google.rpc.Status encoded by tonic-types
+    let error_detail = ErrorDetail {
+        code: error_code_from_name(code).into(),
+        field: metadata(ERROR_FIELD_HEADER).unwrap_or_default().to_string(),
+        help_url: messages.help_url(code).unwrap_or_default(),
+    };
+    let mut rpc_status = RpcStatus::decode(localised.details()).unwrap_or_default();
+    rpc_status.details.push(prost_types::Any {
+        type_url: ERROR_DETAIL_TYPE_URL.to_string(),
+        value: error_detail.encode_to_vec(),
+    });
+    let localised = tonic::Status::with_details(
+        status.code(),
+        status.message(),
+        Bytes::from(rpc_status.encode_to_vec()),
+    );
+
+    if let Err(error) = localised.add_header(headers) {
+        tracing::warn!("Error details could not be added to the response: {error}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use tower::{ServiceBuilder, ServiceExt};
+
+    use super::*;
+    use crate::configuration::ErrorMessagesConfiguration;
+    use crate::prelude::AuthenticationError;
+    use crate::rpc::proto::ErrorCode;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    /// Answer every request with the status, as a unary error response
+    async fn respond(
+        status: tonic::Status,
+        accept_language: &str,
+    ) -> Result<tonic::Status> {
+        let messages = ErrorMessages::new(&ErrorMessagesConfiguration {
+            help_url: Some("https://docs.example.com/errors#{code}".to_string()),
+            ..Default::default()
+        })?;
+        let service = ServiceBuilder::new()
+            .layer(ErrorMessagesLayer::new(messages))
+            .service(tower::service_fn(move |_request: http::Request<()>| {
+                let response = status.clone().into_http::<()>();
+                async move { Ok::<_, Infallible>(response) }
+            }));
+        let request = http::Request::builder()
+            .uri("/authentication.v1.AuthenticationService/Login")
+            .header(ACCEPT_LANGUAGE, accept_language)
+            .body(())?;
+
+        let response = service.oneshot(request).await?;
+
+        Ok(tonic::Status::from_header_map(response.headers()).ok_or("no status")?)
+    }
+
+    /// The `ErrorDetail` in the status details
+    fn error_detail(status: &tonic::Status) -> Result<ErrorDetail> {
+        let any = RpcStatus::decode(status.details())?
+            .details
+            .into_iter()
+            .find(|any| any.type_url == ERROR_DETAIL_TYPE_URL)
+            .ok_or("no error detail")?;
+
+        Ok(ErrorDetail::decode(any.value.as_slice())?)
+    }
+
+    #[tokio::test]
+    async fn errors_get_a_code_and_a_localised_message() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let status: tonic::Status = AuthenticationError::LoginThrottled(30).into();
+
+        //-- Execute Function (Act)
+        let status = respond(status, "fr-CA, en;q=0.5").await?;
+
+        //-- Checks (Assertions)
+        let error_info = status.get_details_error_info().ok_or("no error info")?;
+        let localized = status.get_details_localized_message().ok_or("no message")?;
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+        assert_eq!(status.message(), "Too many failed logins, try again later");
+        assert_eq!(error_info.reason, "LOGIN_THROTTLED");
+        assert_eq!(error_info.domain, ERROR_DOMAIN);
+        assert_eq!(localized.locale, "fr");
+        assert_eq!(localized.message, "Trop de connexions échouées, réessayez plus tard");
+        assert_eq!(status.metadata().get("retry-after").ok_or("no retry-after")?, "30");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn errors_get_an_error_detail() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let status: tonic::Status = AuthenticationError::EmailDomainRejected(
+            crate::domain::EmailDomainRejection::Blocked,
+        )
+        .into();
+
+        //-- Execute Function (Act)
+        let status = respond(status, "en").await?;
+
+        //-- Checks (Assertions)
+        let error_detail = error_detail(&status)?;
+        assert_eq!(error_detail.code(), ErrorCode::EmailDomainBlocked);
+        assert_eq!(error_detail.field, "email");
+        assert_eq!(error_detail.help_url, "https://docs.example.com/errors#EMAIL_DOMAIN_BLOCKED");
+        assert!(status.get_details_error_info().is_some());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn errors_without_a_code_use_the_status_code() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let status = tonic::Status::not_found("User not found");
+
+        //-- Execute Function (Act)
+        let status = respond(status, "de").await?;
+
+        //-- Checks (Assertions)
+        let error_info = status.get_details_error_info().ok_or("no error info")?;
+        let localized = status.get_details_localized_message().ok_or("no message")?;
+        assert_eq!(status.message(), "User not found");
+        assert_eq!(error_info.reason, "NOT_FOUND");
+        assert_eq!(localized.locale, "en");
+        assert_eq!(localized.message, "Not found");
+        assert_eq!(error_detail(&status)?.code(), ErrorCode::NotFound);
+        assert_eq!(error_detail(&status)?.field, "");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+//-- ./src/middleware/error_reporting.rs
+
+// #![allow(unused)] // For development only
+
+//! # Error Reporting
+//!
+//! Gives each request its own error reporting scope, tagged with the gRPC
+//! path, request id and user agent, so the internal errors and panics of a
+//! request are reported with its context, see `crate::error_reporting`. Only
+//! built with the `sentry` feature, without it the layer passes every request
+//! straight through.
+//! ---
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tower::Service;
+use tower_layer::Layer;
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// The request metadata reported with errors, as (tag, metadata) pairs
+const REPORTED_METADATA: [(&str, &str); 2] =
+    [("request_id", "x-request-id"), ("user_agent", "user-agent")];
+
+/// The tags of the errors reported while serving a request
+fn request_tags<ReqBody>(request: &http::Request<ReqBody>) -> Vec<(&'static str, String)> {
+    let path = ("grpc.path", request.uri().path().to_string());
+    let metadata = REPORTED_METADATA.iter().filter_map(|(tag, key)| {
+        request
+            .headers()
+            .get(*key)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| (*tag, value.to_string()))
+    });
+
+    std::iter::once(path).chain(metadata).collect()
+}
+
+/// Report the errors of each request in its own scope
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ErrorReportingLayer;
+
+impl<S> Layer<S> for ErrorReportingLayer {
+    type Service = ErrorReporting<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ErrorReporting { inner }
+    }
+}
+
+/// Service created by [`ErrorReportingLayer`]
+#[derive(Debug, Clone)]
+pub struct ErrorReporting<S> {
+    inner: S,
+}
+
+impl<S, ReqBody> Service<http::Request<ReqBody>> for ErrorReporting<S>
+where
+    S: Service<http::Request<ReqBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    #[cfg(feature = "sentry")]
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        use std::sync::Arc;
+
+        use sentry::{Hub, SentryFutureExt};
+
+        let hub = Arc::new(Hub::new_from_top(Hub::current()));
+        let tags = request_tags(&request);
+        hub.configure_scope(|scope| {
+            for (tag, value) in tags {
+                scope.set_tag(tag, value);
+            }
+        });
+
+        // Errors reported while calling and polling the inner service use the
+        // request's scope
+        let future = Hub::run(Arc::clone(&hub), || self.inner.call(request));
+        Box::pin(future.bind_hub(hub))
+    }
+
+    #[cfg(not(feature = "sentry"))]
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        Box::pin(self.inner.call(request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use tower::{ServiceBuilder, ServiceExt};
+
+    use super::*;
+
+    #[test]
+    fn requests_are_tagged_with_their_path_and_metadata() {
+        //-- Setup and Fixtures (Arrange)
+        let request = http::Request::builder()
+            .uri("/authentication.v1.UsersService/GetMe")
+            .header("x-request-id", "3c4a7b1e")
+            .body(())
+            .unwrap();
+
+        //-- Execute Function (Act)
+        let tags = request_tags(&request);
+
+        //-- Checks (Assertions)
+        assert_eq!(
+            tags,
+            vec![
+                ("grpc.path", "/authentication.v1.UsersService/GetMe".to_string()),
+                ("request_id", "3c4a7b1e".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn requests_are_passed_through() -> Result<(), Infallible> {
+        //-- Setup and Fixtures (Arrange)
+        let service = ServiceBuilder::new()
+            .layer(ErrorReportingLayer)
+            .service(tower::service_fn(|request: http::Request<()>| async move {
+                Ok::<_, Infallible>(request.uri().path().to_string())
+            }));
+        let request = http::Request::builder()
+            .uri("/authentication.v1.UsersService/GetMe")
+            .body(())
+            .unwrap();
+
+        //-- Execute Function (Act)
+        let path = service.oneshot(request).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(path, "/authentication.v1.UsersService/GetMe");
+
+        Ok(())
+    }
+}
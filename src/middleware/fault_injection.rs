@@ -0,0 +1,318 @@
+//-- ./src/middleware/fault_injection.rs
+
+// #![allow(unused)] // For development only
+
+//! # Fault Injection
+//!
+//! A tower layer that makes a percentage of requests fail the way they do in
+//! production, so clients and their retries can be tested without external
+//! tooling. Each rule in `fault_injection.rules` matches a gRPC path and can:
+//! 1. **Delay** requests by `latency_milliseconds` before they are served
+//! 2. **Fail** requests with the `error_code` status instead of serving them
+//! 3. **Drop** responses, serving the request then resetting the stream as if
+//!    the response was lost
+//!
+//! The first matching rule applies, and each fault is rolled independently.
+//! Only built with the `fault_injection` feature, without it the layer never
+//! has rules and passes every request straight through.
+//! ---
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tower::BoxError;
+use tower::Service;
+use tower_layer::Layer;
+
+use crate::configuration::FaultInjectionConfiguration;
+use crate::error_messages::status_code_from_name;
+use crate::prelude::*;
+
+/// Message of the errors injected by a rule
+const INJECTED_ERROR_MESSAGE: &str = "Fault injected for resilience testing";
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// The error a dropped response fails with, resetting the stream
+#[derive(Debug)]
+pub struct DroppedResponse;
+
+impl std::fmt::Display for DroppedResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Response dropped by fault injection")
+    }
+}
+
+impl std::error::Error for DroppedResponse {}
+
+/// The faults injected into the requests to a path
+#[derive(Debug, Clone)]
+struct FaultRule {
+    path: String,
+    latency_percent: f64,
+    latency: Duration,
+    error_percent: f64,
+    error_code: tonic::Code,
+    drop_percent: f64,
+}
+
+impl FaultRule {
+    /// Does the rule apply to the gRPC path
+    fn matches(&self, path: &str) -> bool {
+        match self.path.strip_suffix('*') {
+            Some(prefix) => path.starts_with(prefix),
+            None => self.path == path,
+        }
+    }
+}
+
+/// Roll for a fault injected into `percent` of requests
+fn roll(percent: f64) -> bool {
+    percent > 0.0 && rand::random::<f64>() * 100.0 < percent
+}
+
+/// Inject the configured faults into matching requests
+#[derive(Debug, Clone, Default)]
+pub struct FaultInjectionLayer {
+    rules: Arc<[FaultRule]>,
+}
+
+impl FaultInjectionLayer {
+    /// Inject the faults of `fault_injection.rules`, none when it isn't
+    /// enabled or the `fault_injection` feature is off
+    pub fn new(config: &FaultInjectionConfiguration) -> Result<Self, AuthenticationError> {
+        if !config.enabled || !cfg!(feature = "fault_injection") {
+            return Ok(Self::default());
+        }
+
+        let rules = config
+            .rules
+            .iter()
+            .map(|rule| {
+                let error_code = status_code_from_name(&rule.error_code).ok_or_else(|| {
+                    AuthenticationError::ValidationError(format!(
+                        "Unknown fault injection error code {}",
+                        rule.error_code
+                    ))
+                })?;
+                Ok(FaultRule {
+                    path: rule.path.clone(),
+                    latency_percent: rule.latency_percent,
+                    latency: Duration::from_millis(rule.latency_milliseconds),
+                    error_percent: rule.error_percent,
+                    error_code,
+                    drop_percent: rule.drop_percent,
+                })
+            })
+            .collect::<Result<_, AuthenticationError>>()?;
+        tracing::warn!("Fault injection is enabled, requests will fail on purpose");
+
+        Ok(Self { rules })
+    }
+}
+
+impl<S> Layer<S> for FaultInjectionLayer {
+    type Service = FaultInjection<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        FaultInjection {
+            inner,
+            rules: Arc::clone(&self.rules),
+        }
+    }
+}
+
+/// Service created by [`FaultInjectionLayer`]
+#[derive(Debug, Clone)]
+pub struct FaultInjection<S> {
+    inner: S,
+    rules: Arc<[FaultRule]>,
+}
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for FaultInjection<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>> + Clone + Send + 'static,
+    S::Error: Into<BoxError>,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: Default,
+{
+    type Response = http::Response<ResBody>;
+    type Error = BoxError;
+    type Future = BoxFuture<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        let path = request.uri().path();
+        let Some(rule) = self.rules.iter().find(|rule| rule.matches(path)).cloned() else {
+            let future = self.inner.call(request);
+            return Box::pin(async move { future.await.map_err(Into::into) });
+        };
+
+        // The delay happens after poll_ready, so call a ready clone and leave
+        // this service ready for the next request
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let path = path.to_string();
+
+        Box::pin(async move {
+            if roll(rule.latency_percent) {
+                tracing::debug!("Delaying {path} by {:?}", rule.latency);
+                tokio::time::sleep(rule.latency).await;
+            }
+            if roll(rule.error_percent) {
+                tracing::debug!("Failing {path} with {:?}", rule.error_code);
+                return Ok(tonic::Status::new(rule.error_code, INJECTED_ERROR_MESSAGE).into_http());
+            }
+
+            let response = inner.call(request).await.map_err(Into::into)?;
+            if roll(rule.drop_percent) {
+                tracing::debug!("Dropping the response to {path}");
+                return Err(DroppedResponse.into());
+            }
+
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use tower::{ServiceBuilder, ServiceExt};
+
+    use super::*;
+    use crate::configuration::FaultRuleConfiguration;
+
+    const LOGIN_PATH: &str = "/authentication.v1.AuthenticationService/Login";
+
+    fn request(path: &str) -> http::Request<()> {
+        http::Request::builder().uri(path).body(()).unwrap()
+    }
+
+    fn status_code(response: &http::Response<tonic::body::Body>) -> Option<tonic::Code> {
+        tonic::Status::from_header_map(response.headers()).map(|status| status.code())
+    }
+
+    /// A rule for `path` with every fault off
+    fn rule(path: &str) -> FaultRuleConfiguration {
+        FaultRuleConfiguration {
+            path: path.to_string(),
+            latency_percent: 0.0,
+            latency_milliseconds: 0,
+            error_percent: 0.0,
+            error_code: "UNAVAILABLE".to_string(),
+            drop_percent: 0.0,
+        }
+    }
+
+    fn layer(rules: Vec<FaultRuleConfiguration>) -> Result<FaultInjectionLayer, BoxError> {
+        Ok(FaultInjectionLayer::new(&FaultInjectionConfiguration {
+            enabled: true,
+            rules,
+        })?)
+    }
+
+    fn service(
+        layer: FaultInjectionLayer,
+    ) -> impl Service<
+        http::Request<()>,
+        Response = http::Response<tonic::body::Body>,
+        Error = BoxError,
+        Future = impl Send,
+    > + Clone {
+        ServiceBuilder::new()
+            .layer(layer)
+            .service(tower::service_fn(|_request: http::Request<()>| async {
+                Ok::<_, Infallible>(http::Response::new(tonic::body::Body::default()))
+            }))
+    }
+
+    #[test]
+    fn rules_match_a_path_or_a_prefix() {
+        let rule = |path: &str| FaultRule {
+            path: path.to_string(),
+            latency_percent: 0.0,
+            latency: Duration::ZERO,
+            error_percent: 0.0,
+            error_code: tonic::Code::Unavailable,
+            drop_percent: 0.0,
+        };
+
+        assert!(rule(LOGIN_PATH).matches(LOGIN_PATH));
+        assert!(!rule(LOGIN_PATH).matches("/authentication.v1.AuthenticationService/Register"));
+        assert!(rule("/authentication.v1.AuthenticationService/*").matches(LOGIN_PATH));
+        assert!(!rule("/authentication.v1.UsersService/*").matches(LOGIN_PATH));
+        assert!(rule("*").matches(LOGIN_PATH));
+    }
+
+    #[tokio::test]
+    async fn matching_requests_fail_with_the_error_code() -> Result<(), BoxError> {
+        //-- Setup and Fixtures (Arrange)
+        let service = service(layer(vec![FaultRuleConfiguration {
+            error_percent: 100.0,
+            error_code: "DEADLINE_EXCEEDED".to_string(),
+            ..rule(LOGIN_PATH)
+        }])?);
+
+        //-- Execute Function (Act)
+        let failed = service.clone().oneshot(request(LOGIN_PATH)).await?;
+        let served = service.oneshot(request("/authentication.v1.UsersService/GetMe")).await?;
+
+        //-- Checks (Assertions)
+        if cfg!(feature = "fault_injection") {
+            assert_eq!(status_code(&failed), Some(tonic::Code::DeadlineExceeded));
+        } else {
+            assert_eq!(status_code(&failed), None);
+        }
+        assert_eq!(status_code(&served), None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn dropped_responses_are_errors() -> Result<(), BoxError> {
+        //-- Setup and Fixtures (Arrange)
+        let service = service(layer(vec![FaultRuleConfiguration {
+            drop_percent: 100.0,
+            ..rule("*")
+        }])?);
+
+        //-- Execute Function (Act)
+        let result = service.oneshot(request(LOGIN_PATH)).await;
+
+        //-- Checks (Assertions)
+        let dropped = result.err().is_some_and(|error| error.is::<DroppedResponse>());
+        assert_eq!(dropped, cfg!(feature = "fault_injection"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn matching_requests_are_delayed() -> Result<(), BoxError> {
+        //-- Setup and Fixtures (Arrange)
+        let service = service(layer(vec![FaultRuleConfiguration {
+            latency_percent: 100.0,
+            latency_milliseconds: 200,
+            ..rule(LOGIN_PATH)
+        }])?);
+        let started = std::time::Instant::now();
+
+        //-- Execute Function (Act)
+        let response = service.oneshot(request(LOGIN_PATH)).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(status_code(&response), None);
+        let delayed = started.elapsed() >= Duration::from_millis(200);
+        assert_eq!(delayed, cfg!(feature = "fault_injection"));
+
+        Ok(())
+    }
+}
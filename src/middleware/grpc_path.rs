@@ -0,0 +1,98 @@
+//-- ./src/middleware/grpc_path.rs
+
+// #![allow(unused)] // For development only
+
+//! # gRPC Path
+//!
+//! Tonic interceptors only see the request metadata and extensions, not the
+//! URI, so they can't tell which method is being called. [`GrpcPathLayer`]
+//! copies the request path, e.g. `/authentication.v1.UsersService/GetMe`, into the request
+//! extensions as a [`GrpcPath`] for the interceptors to read.
+//! ---
+
+use std::task::{Context, Poll};
+
+use tower::Service;
+use tower_layer::Layer;
+
+/// The path of the gRPC method being called
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrpcPath(pub String);
+
+impl GrpcPath {
+    /// The method name, the last segment of the path
+    pub fn method(&self) -> &str {
+        self.0.rsplit('/').next().unwrap_or_default()
+    }
+}
+
+/// Add the request path to the request extensions as a [`GrpcPath`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GrpcPathLayer;
+
+impl<S> Layer<S> for GrpcPathLayer {
+    type Service = GrpcPathService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GrpcPathService { inner }
+    }
+}
+
+/// Service created by [`GrpcPathLayer`]
+#[derive(Debug, Clone)]
+pub struct GrpcPathService<S> {
+    inner: S,
+}
+
+impl<S, ReqBody> Service<http::Request<ReqBody>> for GrpcPathService<S>
+where
+    S: Service<http::Request<ReqBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: http::Request<ReqBody>) -> Self::Future {
+        let path = GrpcPath(request.uri().path().to_string());
+        request.extensions_mut().insert(path);
+
+        self.inner.call(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use tower::{ServiceBuilder, ServiceExt};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn the_path_is_added_to_the_extensions() -> Result<(), Infallible> {
+        //-- Setup and Fixtures (Arrange)
+        let service = ServiceBuilder::new()
+            .layer(GrpcPathLayer)
+            .service(tower::service_fn(|request: http::Request<()>| async move {
+                Ok::<_, Infallible>(request.extensions().get::<GrpcPath>().cloned())
+            }));
+        let request = http::Request::builder()
+            .uri("/authentication.v1.UsersService/GetMe")
+            .body(())
+            .unwrap();
+
+        //-- Execute Function (Act)
+        let path = service.oneshot(request).await?;
+
+        //-- Checks (Assertions)
+        let path = path.expect("path is in the extensions");
+        assert_eq!(path.0, "/authentication.v1.UsersService/GetMe");
+        assert_eq!(path.method(), "GetMe");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,247 @@
+//-- ./src/middleware/load_shed.rs
+
+// #![allow(unused)] // For development only
+
+//! # Load Shedding
+//!
+//! Tower layers that turn away requests once the server is at capacity, so an
+//! overload is answered straight away with `Unavailable` instead of queueing
+//! requests without bound.
+//!
+//! The router stacks tower's `LoadShed` over a `GlobalConcurrencyLimit` for
+//! all requests, then an [`ExpensiveRequestLimitLayer`] with a lower limit for
+//! the endpoints that hash passwords. [`UnavailableLayer`] sits outside both
+//! and answers their `Overloaded` errors with a gRPC `Unavailable` status,
+//! which clients can retry with a backoff.
+//! ---
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use tokio::sync::Semaphore;
+use tower::load_shed::error::Overloaded;
+use tower::BoxError;
+use tower::Service;
+use tower_layer::Layer;
+
+/// Message returned to clients when a request is shed
+const OVERLOADED_MESSAGE: &str = "Server is at capacity, retry with a backoff";
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// Answer `Overloaded` errors from the layers below with a gRPC `Unavailable` status
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UnavailableLayer;
+
+impl<S> Layer<S> for UnavailableLayer {
+    type Service = Unavailable<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Unavailable { inner }
+    }
+}
+
+/// Service created by [`UnavailableLayer`]
+#[derive(Debug, Clone)]
+pub struct Unavailable<S> {
+    inner: S,
+}
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for Unavailable<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>>,
+    S::Error: Into<BoxError>,
+    S::Future: Send + 'static,
+    ResBody: Default,
+{
+    type Response = http::Response<ResBody>;
+    type Error = BoxError;
+    type Future = BoxFuture<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        let future = self.inner.call(request);
+
+        Box::pin(async move {
+            match future.await.map_err(Into::into) {
+                Err(error) if error.is::<Overloaded>() => {
+                    tracing::warn!("Shedding request, the server is at capacity");
+                    Ok(tonic::Status::unavailable(OVERLOADED_MESSAGE).into_http())
+                }
+                result => result,
+            }
+        })
+    }
+}
+
+/// Limit the requests in flight to a set of expensive gRPC paths, failing any
+/// over the limit with `Overloaded` rather than waiting for a permit
+#[derive(Debug, Clone)]
+pub struct ExpensiveRequestLimitLayer {
+    permits: Arc<Semaphore>,
+    paths: Arc<[String]>,
+}
+
+impl ExpensiveRequestLimitLayer {
+    /// Allow `max_concurrent` requests in flight across all of `paths`, e.g.
+    /// `/authentication.v1.AuthenticationService/Login`
+    pub fn new<P>(max_concurrent: usize, paths: impl IntoIterator<Item = P>) -> Self
+    where
+        P: Into<String>,
+    {
+        Self {
+            permits: Arc::new(Semaphore::new(max_concurrent)),
+            paths: paths.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl<S> Layer<S> for ExpensiveRequestLimitLayer {
+    type Service = ExpensiveRequestLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ExpensiveRequestLimit {
+            inner,
+            permits: Arc::clone(&self.permits),
+            paths: Arc::clone(&self.paths),
+        }
+    }
+}
+
+/// Service created by [`ExpensiveRequestLimitLayer`]
+#[derive(Debug, Clone)]
+pub struct ExpensiveRequestLimit<S> {
+    inner: S,
+    permits: Arc<Semaphore>,
+    paths: Arc<[String]>,
+}
+
+impl<S, ReqBody> Service<http::Request<ReqBody>> for ExpensiveRequestLimit<S>
+where
+    S: Service<http::Request<ReqBody>>,
+    S::Error: Into<BoxError>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = BoxError;
+    type Future = BoxFuture<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        let path = request.uri().path();
+
+        // Only expensive paths need a permit, which is held until the response is ready
+        let permit = if self.paths.iter().any(|expensive| expensive == path) {
+            match Arc::clone(&self.permits).try_acquire_owned() {
+                Ok(permit) => Some(permit),
+                Err(_) => return Box::pin(async { Err(Overloaded::new().into()) }),
+            }
+        } else {
+            None
+        };
+
+        let future = self.inner.call(request);
+
+        Box::pin(async move {
+            let response = future.await.map_err(Into::into);
+            drop(permit);
+            response
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use tower::{ServiceBuilder, ServiceExt};
+
+    use super::*;
+
+    const LOGIN_PATH: &str = "/authentication.v1.AuthenticationService/Login";
+
+    fn request(path: &str) -> http::Request<()> {
+        http::Request::builder().uri(path).body(()).unwrap()
+    }
+
+    fn status_code(
+        response: &http::Response<tonic::body::Body>,
+    ) -> Option<tonic::Code> {
+        tonic::Status::from_header_map(response.headers())
+            .map(|status| status.code())
+    }
+
+    #[tokio::test]
+    async fn expensive_requests_over_the_limit_are_unavailable(
+    ) -> Result<(), BoxError> {
+        //-- Setup and Fixtures (Arrange)
+        let limit = ExpensiveRequestLimitLayer::new(1, [LOGIN_PATH]);
+        let service = ServiceBuilder::new()
+            .layer(UnavailableLayer)
+            .layer(limit.clone())
+            .service(tower::service_fn(|_request: http::Request<()>| async {
+                Ok::<_, Infallible>(
+                    http::Response::new(tonic::body::Body::default()),
+                )
+            }));
+
+        // Hold the only permit, as an in flight login would
+        let permit = Arc::clone(&limit.permits).try_acquire_owned()?;
+
+        //-- Execute Function (Act)
+        let shed = service.clone().oneshot(request(LOGIN_PATH)).await?;
+        let cheap = service
+            .clone()
+            .oneshot(request("/authentication.v1.UsersService/Read"))
+            .await?;
+        drop(permit);
+        let served = service.clone().oneshot(request(LOGIN_PATH)).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(status_code(&shed), Some(tonic::Code::Unavailable));
+        assert_eq!(status_code(&cheap), None);
+        assert_eq!(status_code(&served), None);
+        assert_eq!(limit.permits.available_permits(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn load_shed_overload_is_unavailable() -> Result<(), BoxError> {
+        //-- Setup and Fixtures (Arrange)
+        let permits = Arc::new(Semaphore::new(1));
+        let service = ServiceBuilder::new()
+            .layer(UnavailableLayer)
+            .load_shed()
+            .layer(tower::limit::GlobalConcurrencyLimitLayer::with_semaphore(
+                Arc::clone(&permits),
+            ))
+            .service(tower::service_fn(|_request: http::Request<()>| async {
+                Ok::<_, Infallible>(
+                    http::Response::new(tonic::body::Body::default()),
+                )
+            }));
+
+        // Hold the only permit, as an in flight request would
+        let permit = Arc::clone(&permits).try_acquire_owned()?;
+
+        //-- Execute Function (Act)
+        let shed = service.clone().oneshot(request(LOGIN_PATH)).await?;
+        drop(permit);
+        let served = service.clone().oneshot(request(LOGIN_PATH)).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(status_code(&shed), Some(tonic::Code::Unavailable));
+        assert_eq!(status_code(&served), None);
+
+        Ok(())
+    }
+}
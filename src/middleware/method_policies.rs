@@ -0,0 +1,332 @@
+//-- ./src/middleware/method_policies.rs
+
+// #![allow(unused)] // For development only
+
+//! # Method Policies
+//!
+//! The authorisation policy of every gRPC method, in one map keyed by the
+//! method path, e.g. `/authentication.v1.UsersService/GetMe`. A method is one of:
+//! - **Public**: anyone can call it, the service authenticates the caller
+//!   itself when it needs to, e.g. with the refresh token cookie
+//! - **Authenticated**: any valid access token or API key
+//! - **Role**: an access token or API key with one of the roles
+//! - **Scope**: an access token granting the permission, or an API key whose
+//!   role grants it, see `UserRole::permissions`
+//!
+//! `AuthorisationLayer` enforces the policy of each request and denies paths
+//! without one. `MethodPolicies::check` fails startup when a method in the
+//! proto descriptors has no policy, so a new RPC can't be served until it is
+//! given one.
+//! ---
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use prost::Message;
+
+use crate::domain::UserRole;
+use crate::prelude::*;
+use crate::rpc::proto::{
+    admin_service_server::SERVICE_NAME as ADMIN_SERVICE_NAME,
+    authentication_service_server::SERVICE_NAME as AUTHENTICATION_SERVICE_NAME,
+    sessions_service_server::SERVICE_NAME as SESSIONS_SERVICE_NAME,
+    users_service_server::SERVICE_NAME as USERS_SERVICE_NAME,
+    utilities_service_server::SERVICE_NAME as UTILITIES_SERVICE_NAME,
+};
+use crate::rpc::API_VERSIONS;
+
+/// The gRPC health service, added to every router by `startup`
+const HEALTH_SERVICE_NAME: &str = "grpc.health.v1.Health";
+
+/// The gRPC reflection services, see `rpc::spec_service`
+const REFLECTION_SERVICE_NAMES: [&str; 2] = [
+    "grpc.reflection.v1.ServerReflection",
+    "grpc.reflection.v1alpha.ServerReflection",
+];
+
+/// Who may call a gRPC method
+#[derive(Debug, Clone, PartialEq)]
+pub enum MethodPolicy {
+    /// Anyone, without credentials
+    Public,
+
+    /// Any valid access token or API key
+    Authenticated,
+
+    /// An access token or API key with one of the roles
+    Role(&'static [UserRole]),
+
+    /// An access token or API key granting the permission
+    Scope(&'static str),
+}
+
+impl MethodPolicy {
+    /// Does the policy allow a caller with the role, checking permissions with
+    /// `has_permission`
+    pub fn allows(&self, role: &UserRole, has_permission: impl Fn(&str) -> bool) -> bool {
+        match self {
+            MethodPolicy::Public | MethodPolicy::Authenticated => true,
+            MethodPolicy::Role(roles) => roles.contains(role),
+            MethodPolicy::Scope(permission) => has_permission(permission),
+        }
+    }
+}
+
+/// The policy of each service method, as (service, [(method, policy)])
+fn service_policies() -> Vec<(&'static str, Vec<(&'static str, MethodPolicy)>)> {
+    use MethodPolicy::*;
+
+    vec![
+        (
+            UTILITIES_SERVICE_NAME,
+            vec![
+                ("Ping", Public),
+                ("ReadinessReport", Public),
+                ("ServerInfo", Public),
+                ("ServerTime", Public),
+                ("Echo", Public),
+                ("SetLogFilter", Role(&[UserRole::Admin])),
+            ],
+        ),
+        // Authentication methods take passwords, refresh tokens or one time
+        // codes, and check them themselves
+        (
+            AUTHENTICATION_SERVICE_NAME,
+            vec![
+                ("Login", Public),
+                ("Refresh", Public),
+                ("UpdatePassword", Public),
+                ("ResetPassword", Public),
+                ("RequestPasswordReset", Public),
+                ("Register", Public),
+                ("Logout", Public),
+                ("LogoutOtherSessions", Public),
+                ("ConfirmEmailChange", Public),
+                ("RequestMagicLink", Public),
+                ("CompleteMagicLink", Public),
+                ("GetSamlMetadata", Public),
+                ("BeginSamlLogin", Public),
+                ("CompleteSamlLogin", Public),
+                ("StartDeviceAuthorization", Public),
+                ("TokenFromDeviceCode", Public),
+                ("ClientCredentials", Public),
+                ("BeginPasskeyLogin", Public),
+                ("FinishPasskeyLogin", Public),
+            ],
+        ),
+        // Managing other users is for admins, the rest are the caller's own
+        (
+            USERS_SERVICE_NAME,
+            [
+                "GetMe",
+                "SetAvatar",
+                "AcceptPolicy",
+                "GetPreferences",
+                "UpdatePreferences",
+                "ListMyLoginHistory",
+                "BeginPasskeyRegistration",
+                "FinishPasskeyRegistration",
+                "ApproveDeviceAuthorization",
+                "ListMyGrants",
+                "RevokeGrant",
+                "BeginIdentityLink",
+                "ListMyIdentities",
+                "UnlinkIdentity",
+            ]
+            .into_iter()
+            .map(|method| (method, Scope(UserRole::USERS_ACCESS)))
+            .chain(
+                ["Create", "Read", "Index", "SearchUsers", "Update", "Delete"]
+                    .map(|method| (method, Role(&[UserRole::Admin]))),
+            )
+            .collect(),
+        ),
+        (
+            SESSIONS_SERVICE_NAME,
+            [
+                "Read",
+                "Index",
+                "Revoke",
+                "RevokeUser",
+                "RevokeAll",
+                "Delete",
+                "DeleteUser",
+                "DeleteAll",
+            ]
+            .into_iter()
+            .map(|method| (method, Scope(UserRole::SESSIONS_ACCESS)))
+            .collect(),
+        ),
+        (
+            ADMIN_SERVICE_NAME,
+            [
+                "ImportUsers",
+                "ExportUsers",
+                "WatchAuthEvents",
+                "CreateApiKey",
+                "ListApiKeys",
+                "RevokeApiKey",
+                "RegisterClient",
+                "ListClients",
+                "UpdateClient",
+                "RevokeClient",
+                "CreateOrganization",
+                "SetEmailDomainRule",
+                "ListEmailDomainRules",
+                "DeleteEmailDomainRule",
+                "AddOrganizationMember",
+                "CreateWebhookEndpoint",
+                "ListWebhookEndpoints",
+                "DeleteWebhookEndpoint",
+                "ListWebhookDeliveries",
+                "RequestEmailChange",
+                "ListImpersonations",
+                "RevokeImpersonation",
+                "DeleteUser",
+                "RestoreUser",
+                "MergeUsers",
+                "ForcePasswordReset",
+                "GetSecurityReport",
+            ]
+            .into_iter()
+            .map(|method| (method, Role(&[UserRole::Admin])))
+            .chain([("ImpersonateUser", Scope(UserRole::USERS_IMPERSONATE))])
+            .collect(),
+        ),
+        (HEALTH_SERVICE_NAME, vec![("Check", Public), ("Watch", Public)]),
+    ]
+    .into_iter()
+    .chain(
+        REFLECTION_SERVICE_NAMES
+            .map(|service| (service, vec![("ServerReflectionInfo", Public)])),
+    )
+    .collect()
+}
+
+/// The authorisation policy of every gRPC method, keyed by path
+#[derive(Debug, Clone)]
+pub struct MethodPolicies {
+    policies: Arc<HashMap<String, MethodPolicy>>,
+}
+
+impl Default for MethodPolicies {
+    fn default() -> Self {
+        let policies = service_policies()
+            .into_iter()
+            .flat_map(|(service, methods)| {
+                methods
+                    .into_iter()
+                    .map(move |(method, policy)| (format!("/{service}/{method}"), policy))
+            })
+            .collect();
+
+        Self {
+            policies: Arc::new(policies),
+        }
+    }
+}
+
+impl MethodPolicies {
+    /// The policy of a method path, `None` when it has no policy
+    pub fn get(&self, path: &str) -> Option<&MethodPolicy> {
+        self.policies.get(path)
+    }
+
+    /// Check every method in the proto descriptors of each API version has a
+    /// policy, listing the paths of those that don't
+    pub fn check(&self) -> Result<(), AuthenticationError> {
+        let mut files = Vec::new();
+        for version in API_VERSIONS {
+            let descriptors = prost_types::FileDescriptorSet::decode(version.file_descriptor_set)
+                .map_err(|e| {
+                    AuthenticationError::Generic(format!(
+                        "Invalid {} file descriptors: {e}",
+                        version.package
+                    ))
+                })?;
+            files.extend(descriptors.file);
+        }
+
+        let paths: HashSet<String> = files
+            .iter()
+            .flat_map(|file| {
+                file.service.iter().flat_map(move |service| {
+                    service.method.iter().map(move |method| {
+                        format!(
+                            "/{}.{}/{}",
+                            file.package(),
+                            service.name(),
+                            method.name()
+                        )
+                    })
+                })
+            })
+            .collect();
+
+        let mut missing: Vec<&String> = paths
+            .iter()
+            .filter(|path| !self.policies.contains_key(*path))
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        missing.sort();
+        Err(AuthenticationError::ValidationError(format!(
+            "Methods without an authorisation policy: {}",
+            missing
+                .iter()
+                .map(|path| path.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )))
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn every_method_has_a_policy() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let policies = MethodPolicies::default();
+
+        //-- Execute Function (Act)
+        policies.check()?;
+
+        //-- Checks (Assertions)
+        assert_eq!(
+            policies.get(&format!("/{AUTHENTICATION_SERVICE_NAME}/Login")),
+            Some(&MethodPolicy::Public)
+        );
+        assert_eq!(
+            policies.get(&format!("/{USERS_SERVICE_NAME}/GetMe")),
+            Some(&MethodPolicy::Scope(UserRole::USERS_ACCESS))
+        );
+        assert_eq!(
+            policies.get(&format!("/{USERS_SERVICE_NAME}/SearchUsers")),
+            Some(&MethodPolicy::Role(&[UserRole::Admin]))
+        );
+        assert_eq!(policies.get(&format!("/{USERS_SERVICE_NAME}/NotAMethod")), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn policies_allow_roles_and_permissions() {
+        let user = UserRole::User;
+        let user_permissions = |permission: &str| user.permissions().contains(&permission);
+
+        assert!(MethodPolicy::Authenticated.allows(&UserRole::Guest, |_| false));
+        assert!(MethodPolicy::Role(&[UserRole::Admin, UserRole::User]).allows(&user, user_permissions));
+        assert!(!MethodPolicy::Role(&[UserRole::Admin]).allows(&user, user_permissions));
+        assert!(MethodPolicy::Scope(UserRole::SESSIONS_ACCESS).allows(&user, user_permissions));
+        assert!(!MethodPolicy::Scope(UserRole::ADMIN_ACCESS).allows(&user, user_permissions));
+    }
+}
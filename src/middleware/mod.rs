@@ -1,5 +1,33 @@
 // #![allow(unused)] // For beginning only.
 
+mod api_keys;
 mod authorisation;
+mod catch_panic;
+mod database_circuit;
+mod denylist;
+mod error_messages;
+mod error_reporting;
+mod fault_injection;
+mod grpc_path;
+mod load_shed;
+mod method_policies;
+mod package_alias;
+mod policy_acceptances;
+mod session_activity;
 
-pub use authorisation::AuthorisationInterceptor;
+pub use api_keys::{ApiKeyIdentity, ApiKeyStore};
+pub use authorisation::{Authorisation, AuthorisationLayer};
+pub use catch_panic::{catch_panic_layer, PanicResponse, CORRELATION_ID_HEADER};
+pub use database_circuit::{DatabaseCircuit, DatabaseCircuitLayer};
+pub use denylist::TokenDenylist;
+pub use error_messages::{ErrorMessagesLayer, LocalisedErrors, ERROR_DETAIL_TYPE_URL};
+pub use error_reporting::{ErrorReporting, ErrorReportingLayer};
+pub use fault_injection::{DroppedResponse, FaultInjection, FaultInjectionLayer};
+pub use grpc_path::{GrpcPath, GrpcPathLayer, GrpcPathService};
+pub use load_shed::{
+    ExpensiveRequestLimit, ExpensiveRequestLimitLayer, Unavailable, UnavailableLayer,
+};
+pub use method_policies::{MethodPolicies, MethodPolicy};
+pub use package_alias::{PackageAliasLayer, PackageAliases};
+pub use policy_acceptances::PolicyAcceptanceStore;
+pub use session_activity::SessionActivity;
@@ -0,0 +1,178 @@
+//-- ./src/middleware/package_alias.rs
+
+// #![allow(unused)] // For development only
+
+//! # Package Aliases
+//!
+//! Serves the methods of old proto packages from the package that replaced
+//! them, so clients generated from an old package keep working while they
+//! are regenerated. Each alias in `rpc::PACKAGE_ALIASES` rewrites request
+//! paths from the old package to the current one, e.g.
+//! `/authentication.UsersService/GetMe` to
+//! `/authentication.v1.UsersService/GetMe`, before the layers that match
+//! paths.
+//!
+//! An alias only works while the old package's messages are wire compatible
+//! with the current package. A breaking change goes in a new package instead,
+//! served next to the old one, see `rpc::API_VERSIONS`. Reflection only lists
+//! the served packages, never their aliases.
+//! ---
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use tower::Service;
+use tower_layer::Layer;
+
+use crate::rpc::{PackageAlias, PACKAGE_ALIASES};
+
+/// The aliased path of a request, as (alias, current path). `None` for paths
+/// not in an aliased package.
+fn current_path<'a>(
+    aliases: &'a [PackageAlias],
+    path: &str,
+) -> Option<(&'a PackageAlias, String)> {
+    aliases.iter().find_map(|alias| {
+        let service_method = path.strip_prefix(&format!("/{}.", alias.from))?;
+        // Services in the aliased package have no further package segment,
+        // e.g. `v1.UsersService` is in a newer package
+        let (service, _method) = service_method.split_once('/')?;
+        if service.contains('.') {
+            return None;
+        }
+
+        Some((alias, format!("/{}.{service_method}", alias.to)))
+    })
+}
+
+/// Rewrite the paths of aliased packages to the packages that replaced them
+#[derive(Debug, Clone)]
+pub struct PackageAliasLayer {
+    aliases: &'static [PackageAlias],
+    logged: Arc<Mutex<HashSet<&'static str>>>,
+}
+
+impl Default for PackageAliasLayer {
+    fn default() -> Self {
+        Self {
+            aliases: PACKAGE_ALIASES,
+            logged: Arc::default(),
+        }
+    }
+}
+
+impl<S> Layer<S> for PackageAliasLayer {
+    type Service = PackageAliases<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        PackageAliases {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service created by [`PackageAliasLayer`]
+#[derive(Debug, Clone)]
+pub struct PackageAliases<S> {
+    inner: S,
+    layer: PackageAliasLayer,
+}
+
+impl<S, ReqBody> Service<http::Request<ReqBody>> for PackageAliases<S>
+where
+    S: Service<http::Request<ReqBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: http::Request<ReqBody>) -> Self::Future {
+        if let Some((alias, path)) = current_path(self.layer.aliases, request.uri().path()) {
+            // Log the first call to each deprecated package
+            let mut logged = self.layer.logged.lock().unwrap_or_else(|e| e.into_inner());
+            if logged.insert(alias.from) {
+                tracing::warn!(
+                    "Client called {} in the deprecated {} package, regenerate it from {}",
+                    request.uri().path(),
+                    alias.from,
+                    alias.to
+                );
+            }
+            drop(logged);
+
+            let mut parts = request.uri().clone().into_parts();
+            if let Ok(path_and_query) = path.parse() {
+                parts.path_and_query = Some(path_and_query);
+            }
+            if let Ok(uri) = http::Uri::from_parts(parts) {
+                *request.uri_mut() = uri;
+            }
+        }
+
+        self.inner.call(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use tower::{ServiceBuilder, ServiceExt};
+
+    use super::*;
+
+    #[test]
+    fn only_aliased_paths_are_rewritten() {
+        let aliases = [
+            PackageAlias {
+                from: "authentication",
+                to: "authentication.v2",
+            },
+            PackageAlias {
+                from: "authentication.v1",
+                to: "authentication.v2",
+            },
+        ];
+        let current = |path: &str| current_path(&aliases, path).map(|(_, path)| path);
+
+        assert_eq!(
+            current("/authentication.UsersService/GetMe").as_deref(),
+            Some("/authentication.v2.UsersService/GetMe")
+        );
+        assert_eq!(
+            current("/authentication.v1.UsersService/GetMe").as_deref(),
+            Some("/authentication.v2.UsersService/GetMe")
+        );
+        assert_eq!(current("/authentication.v2.UsersService/GetMe"), None);
+        assert_eq!(current("/grpc.health.v1.Health/Check"), None);
+        assert_eq!(current("/authentication.UsersService"), None);
+    }
+
+    #[tokio::test]
+    async fn legacy_requests_reach_the_current_path() -> Result<(), Infallible> {
+        //-- Setup and Fixtures (Arrange)
+        let service = ServiceBuilder::new()
+            .layer(PackageAliasLayer::default())
+            .service(tower::service_fn(|request: http::Request<()>| async move {
+                Ok::<_, Infallible>(request.uri().to_string())
+            }));
+        let request = http::Request::builder()
+            .uri("http://localhost/authentication.AuthenticationService/Login")
+            .body(())
+            .unwrap();
+
+        //-- Execute Function (Act)
+        let uri = service.oneshot(request).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(uri, "http://localhost/authentication.v1.AuthenticationService/Login");
+
+        Ok(())
+    }
+}
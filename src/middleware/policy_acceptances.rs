@@ -0,0 +1,125 @@
+//-- ./src/middleware/policy_acceptances.rs
+
+// #![allow(unused)] // For development only
+
+//! # Policy Acceptance Store
+//!
+//! In memory set of the policy versions each user has accepted, so the
+//! synchronous authorisation layer can hold back users who have not
+//! accepted the configured terms of service and privacy policy versions
+//! without a database round trip. It is loaded at startup with the
+//! acceptances of the configured versions and updated as users accept.
+//! Changing a version on reload requires users to accept it again.
+//! ---
+
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::configuration::{PoliciesConfiguration, SharedConfiguration};
+use crate::prelude::*;
+use crate::{database, domain};
+
+/// Shared set of accepted policy versions, cheap to clone into each service
+#[derive(Debug, Clone)]
+pub struct PolicyAcceptanceStore {
+    config: SharedConfiguration,
+    accepted: Arc<RwLock<HashSet<(Uuid, domain::Policy, String)>>>,
+}
+
+impl PolicyAcceptanceStore {
+    /// Create an empty store, reading the required versions from `config`
+    pub fn new(config: SharedConfiguration) -> Self {
+        Self {
+            config,
+            accepted: Arc::default(),
+        }
+    }
+
+    /// Load the acceptances of the configured policy versions from the database
+    pub async fn load(
+        database: &Pool<Postgres>,
+        config: SharedConfiguration,
+    ) -> Result<Self, AuthenticationError> {
+        let store = Self::new(config);
+
+        let policies = store.config.load_full().policies.clone();
+        for (policy, version) in policies.required() {
+            for acceptance in
+                database::PolicyAcceptances::index_version(policy.to_str(), version, database)
+                    .await?
+            {
+                store.accept(&acceptance.user_id, policy, &acceptance.version);
+            }
+        }
+
+        Ok(store)
+    }
+
+    /// Record that the user accepted the policy version
+    pub fn accept(&self, user_id: &Uuid, policy: domain::Policy, version: &str) {
+        let mut accepted = self.accepted.write().unwrap_or_else(|e| e.into_inner());
+        accepted.insert((user_id.to_owned(), policy, version.to_string()));
+    }
+
+    /// The configured policy versions the user has not accepted, as
+    /// `policy=version` pairs
+    pub fn outstanding(&self, user_id: &Uuid) -> Vec<String> {
+        self.outstanding_for(user_id, &self.config.load().policies)
+    }
+
+    /// The policy versions in `policies` the user has not accepted
+    fn outstanding_for(&self, user_id: &Uuid, policies: &PoliciesConfiguration) -> Vec<String> {
+        let accepted = self.accepted.read().unwrap_or_else(|e| e.into_inner());
+
+        policies
+            .required()
+            .into_iter()
+            .filter(|(policy, version)| {
+                !accepted.contains(&(user_id.to_owned(), *policy, version.to_string()))
+            })
+            .map(|(policy, version)| format!("{policy}={version}"))
+            .collect()
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use crate::configuration::Configuration;
+
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn only_unaccepted_versions_are_outstanding() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let store = PolicyAcceptanceStore::new(Configuration::parse()?.into_shared());
+        let user_id = Uuid::now_v7();
+        let policies = PoliciesConfiguration {
+            terms_of_service_version: Some("2".to_string()),
+            privacy_policy_version: Some("1".to_string()),
+        };
+
+        //-- Execute Function (Act)
+        store.accept(&user_id, domain::Policy::TermsOfService, "1");
+        store.accept(&user_id, domain::Policy::PrivacyPolicy, "1");
+        let outstanding = store.outstanding_for(&user_id, &policies);
+
+        //-- Checks (Assertions)
+        assert_eq!(outstanding, vec!["terms_of_service=2"]);
+        assert!(store
+            .outstanding_for(&user_id, &PoliciesConfiguration::default())
+            .is_empty());
+        // Clones share the same acceptances
+        store.clone().accept(&user_id, domain::Policy::TermsOfService, "2");
+        assert!(store.outstanding_for(&user_id, &policies).is_empty());
+
+        Ok(())
+    }
+}
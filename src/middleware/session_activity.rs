@@ -0,0 +1,146 @@
+//-- ./src/middleware/session_activity.rs
+
+// #![allow(unused)] // For development only
+
+//! # Session Activity
+//!
+//! Counts the requests made with each session, so session listings can show
+//! when a session was last used and how much. The synchronous authorisation
+//! layer records the access tokens it accepts and the authentication
+//! service records refreshed sessions, both in memory. The counts are written
+//! to the `sessions` table every `FLUSH_INTERVAL`, so a busy session is written
+//! at most once a minute instead of on every request.
+//!
+//! Activity not yet written is lost if the server stops.
+//! ---
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, SubsecRound, Utc};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::database;
+
+/// How often the recorded activity is written to the database
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// What a session was used with
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum SessionUse {
+    /// The session's refresh token, by session id
+    Session(Uuid),
+
+    /// An access token issued for the session, by jti
+    AccessToken(String),
+}
+
+/// Requests made with a session since the activity was last written
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Activity {
+    last_used_at: DateTime<Utc>,
+    requests: i64,
+}
+
+/// Shared record of session activity, cheap to clone into each service
+#[derive(Debug, Clone, Default)]
+pub struct SessionActivity {
+    pending: Arc<Mutex<HashMap<SessionUse, Activity>>>,
+}
+
+impl SessionActivity {
+    fn record(&self, session_use: SessionUse) {
+        let now = Utc::now().round_subsecs(0);
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+
+        let activity = pending.entry(session_use).or_insert(Activity {
+            last_used_at: now,
+            requests: 0,
+        });
+        activity.last_used_at = now;
+        activity.requests += 1;
+    }
+
+    /// Record a request made with a session's refresh token
+    pub fn record_session(&self, session_id: &Uuid) {
+        self.record(SessionUse::Session(session_id.to_owned()));
+    }
+
+    /// Record a request made with an access token, by its jti
+    pub fn record_access_token(&self, jti: &str) {
+        self.record(SessionUse::AccessToken(jti.to_string()));
+    }
+
+    /// Take the activity recorded since the last flush
+    fn take(&self) -> HashMap<SessionUse, Activity> {
+        std::mem::take(&mut *self.pending.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Write the recorded activity to the sessions it was recorded for
+    pub async fn flush(&self, database: &Pool<Postgres>) {
+        for (session_use, activity) in self.take() {
+            let Activity {
+                last_used_at,
+                requests,
+            } = activity;
+            let result = match &session_use {
+                SessionUse::Session(id) => {
+                    database::Sessions::record_activity(id, &last_used_at, requests, database)
+                        .await
+                }
+                SessionUse::AccessToken(jti) => {
+                    database::Sessions::record_activity_by_access_token_id(
+                        jti,
+                        &last_used_at,
+                        requests,
+                        database,
+                    )
+                    .await
+                }
+            };
+
+            if let Err(e) = result {
+                tracing::error!("Unable to record the activity of {session_use:?}: {e}");
+            }
+        }
+    }
+
+    /// Write the recorded activity to the database in the background
+    pub fn spawn(self, database: Pool<Postgres>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+            loop {
+                interval.tick().await;
+                self.flush(&database).await;
+            }
+        });
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_are_counted_until_taken() {
+        //-- Setup and Fixtures (Arrange)
+        let activity = SessionActivity::default();
+        let session_id = Uuid::now_v7();
+
+        //-- Execute Function (Act)
+        activity.record_session(&session_id);
+        activity.record_session(&session_id);
+        // Clones share the same activity
+        activity.clone().record_access_token("jti");
+        let taken = activity.take();
+
+        //-- Checks (Assertions)
+        assert_eq!(taken.len(), 2);
+        assert_eq!(taken[&SessionUse::Session(session_id)].requests, 2);
+        assert_eq!(taken[&SessionUse::AccessToken("jti".to_string())].requests, 1);
+        assert!(activity.take().is_empty());
+    }
+}
@@ -0,0 +1,214 @@
+//-- ./src/readiness.rs
+
+// #![allow(unused)] // For development only
+
+//! # Startup Readiness
+//!
+//! Checks the service's dependencies after boot, so the gRPC health service
+//! only reports `SERVING` once requests can succeed. The checks are:
+//! - **database**: the pool answers a query
+//! - **migrations**: every embedded migration has been applied
+//! - **token signing**: an access token signed with `application.token_secret`
+//!   parses back with the configured issuer and audiences
+//! - **email**: the SMTP relay accepts a connection, only when
+//!   `readiness.check_email` is set
+//!
+//! `startup` runs the checks after the warm-up, and again every
+//! `readiness.retry_interval_seconds` until they pass. The latest report is
+//! kept in a shared `Readiness`, returned by the `ReadinessReport` RPC for
+//! debugging a server that never reports healthy.
+//! ---
+
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use secrecy::ExposeSecret;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::check_config::{check_email, CheckResult};
+use crate::configuration::Configuration;
+use crate::database;
+use crate::domain::TokenClaim;
+use crate::prelude::*;
+
+/// How long the throwaway access token signed by the token check is valid for
+const TOKEN_CHECK_DURATION: Duration = Duration::from_secs(60);
+
+/// The outcome of one run of the readiness checks
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadinessReport {
+    /// Every check run, in order
+    pub checks: Vec<CheckResult>,
+
+    /// When the checks finished
+    pub checked_at: DateTime<Utc>,
+}
+
+impl ReadinessReport {
+    /// Did every check pass
+    pub fn is_ready(&self) -> bool {
+        self.checks.iter().all(|check| check.outcome.is_ok())
+    }
+}
+
+/// The latest readiness report, shared by startup and the utilities service
+#[derive(Debug, Clone, Default)]
+pub struct Readiness {
+    report: Arc<RwLock<Option<ReadinessReport>>>,
+}
+
+impl Readiness {
+    /// The latest report, `None` until the first checks finish
+    pub fn report(&self) -> Option<ReadinessReport> {
+        self.report
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Replace the latest report
+    pub fn set(&self, report: ReadinessReport) {
+        *self.report.write().unwrap_or_else(|e| e.into_inner()) = Some(report);
+    }
+}
+
+/// Check the database answers a query
+async fn check_database(database: &Pool<Postgres>) -> Result<String, String> {
+    let _row: (i32,) = sqlx::query_as("SELECT 1")
+        .fetch_one(database)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok("answering queries".to_string())
+}
+
+/// Check there are no pending migrations
+async fn check_migrations(database: &Pool<Postgres>) -> Result<String, String> {
+    let migrations = database::migration_status(database)
+        .await
+        .map_err(|e| e.to_string())?;
+    let pending = migrations
+        .iter()
+        .filter(|migration| !migration.applied)
+        .count();
+
+    if pending > 0 {
+        return Err(format!("{pending} pending migrations"));
+    }
+
+    Ok(format!("{} migrations applied", migrations.len()))
+}
+
+/// Check an access token signed with the token secret parses back
+fn check_token_signing(config: &Configuration) -> Result<String, String> {
+    let application = &config.application;
+    if application.token_secret.expose_secret().is_empty() {
+        return Err("application.token_secret is empty".to_string());
+    }
+
+    let issuer = application.get_issuer();
+    let audiences = &application.token_audiences;
+    let claim = TokenClaim::new_for_client(&issuer, &TOKEN_CHECK_DURATION, &Uuid::nil(), &[])
+        .with_audiences(audiences);
+    let token = encode(
+        &Header::default(),
+        &claim,
+        &EncodingKey::from_secret(application.token_secret.expose_secret().as_bytes()),
+    )
+    .map_err(|e| e.to_string())?;
+    TokenClaim::parse_with_audiences(&token, &application.token_secret, &issuer, audiences)
+        .map_err(|e| e.to_string())?;
+
+    Ok("access tokens sign and verify".to_string())
+}
+
+/// Run the readiness checks.
+///
+/// # Parameters
+/// * `database` - The sqlx database pool the services use.
+/// * `config` - The current configuration.
+///
+/// # Returns
+/// * `ReadinessReport` - The outcome of each check.
+#[tracing::instrument(name = "Startup readiness checks: ", skip_all)]
+pub async fn check(database: &Pool<Postgres>, config: &Configuration) -> ReadinessReport {
+    let mut checks = vec![
+        CheckResult {
+            name: "database",
+            outcome: check_database(database).await,
+        },
+        CheckResult {
+            name: "migrations",
+            outcome: check_migrations(database).await,
+        },
+        CheckResult {
+            name: "token signing",
+            outcome: check_token_signing(config),
+        },
+    ];
+    if config.readiness.check_email {
+        checks.push(CheckResult {
+            name: "email",
+            outcome: check_email(config).await,
+        });
+    }
+
+    let report = ReadinessReport {
+        checks,
+        checked_at: Utc::now(),
+    };
+    for check in report.checks.iter().filter(|check| check.outcome.is_err()) {
+        tracing::warn!("Readiness check failed {check}");
+    }
+
+    report
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use secrecy::SecretString;
+
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn a_migrated_database_is_ready(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let config = Configuration::parse()?;
+        let readiness = Readiness::default();
+
+        //-- Execute Function (Act)
+        let report = check(&database, &config).await;
+        readiness.set(report.clone());
+
+        //-- Checks (Assertions)
+        let names: Vec<&str> = report.checks.iter().map(|check| check.name).collect();
+        assert_eq!(names, ["database", "migrations", "token signing"]);
+        assert!(report.is_ready(), "{:?}", report.checks);
+        assert_eq!(readiness.report(), Some(report));
+
+        Ok(())
+    }
+
+    #[test]
+    fn an_empty_token_secret_is_not_ready() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let mut config = Configuration::parse()?;
+        config.application.token_secret = SecretString::from("");
+
+        //-- Execute Function (Act)
+        let outcome = check_token_signing(&config);
+
+        //-- Checks (Assertions)
+        assert_eq!(outcome, Err("application.token_secret is empty".to_string()));
+
+        Ok(())
+    }
+}
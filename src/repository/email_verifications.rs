@@ -0,0 +1,47 @@
+//-- ./src/repository/email_verifications.rs
+
+// #![allow(unused)] // For development only
+
+//! Email verification token storage trait, matching the
+//! `database::EmailVerifications` model.
+
+use uuid::Uuid;
+
+use crate::prelude::*;
+use crate::{database, domain};
+
+/// Stores, reads and deletes email verification tokens
+#[tonic::async_trait]
+pub trait EmailVerificationRepository: Send + Sync {
+    /// Store a new email verification, returning the stored record
+    async fn insert_email_verification(
+        &self,
+        email_verification: &database::EmailVerifications,
+    ) -> Result<database::EmailVerifications, AuthenticationError>;
+
+    /// The email verification with the token, or an error when there is none
+    async fn from_token(
+        &self,
+        token: &domain::EmailVerificationToken,
+    ) -> Result<database::EmailVerifications, AuthenticationError>;
+
+    /// A page of a user's email verifications ordered by id
+    async fn index_from_user_id(
+        &self,
+        user_id: &Uuid,
+        limit: &usize,
+        offset: &usize,
+    ) -> Result<Vec<database::EmailVerifications>, AuthenticationError>;
+
+    /// Delete the email verification with the token, returning the rows deleted
+    async fn delete_by_token(
+        &self,
+        token: &domain::EmailVerificationToken,
+    ) -> Result<u64, AuthenticationError>;
+
+    /// Delete every email verification of a user, returning the rows deleted
+    async fn delete_all_user_id(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<u64, AuthenticationError>;
+}
@@ -0,0 +1,457 @@
+//-- ./src/repository/memory.rs
+
+// #![allow(unused)] // For development only
+
+//! In-memory repositories, for fast tests without Postgres.
+//!
+//! Rows are kept in `DashMap`s keyed by id, so one repository can be shared
+//! between services and test tasks, and are queried the same way as the
+//! Postgres models, with the same ordering and paging. The `Users` model does
+//! not carry `deleted_at`, so only live users should be inserted. Nothing is
+//! persisted.
+
+use chrono::{DateTime, Utc};
+use dashmap::{DashMap, DashSet};
+use uuid::Uuid;
+
+use crate::prelude::*;
+use crate::repository::{
+    EmailVerificationRepository, SessionRepository, UserRepository,
+};
+use crate::{database, domain};
+
+/// Repositories backed by memory
+#[derive(Default)]
+pub struct MemoryRepository {
+    users: DashMap<Uuid, database::Users>,
+    organization_members: DashSet<(Uuid, Uuid)>,
+    sessions: DashMap<Uuid, database::Sessions>,
+    email_verifications: DashMap<Uuid, database::EmailVerifications>,
+}
+
+impl MemoryRepository {
+    /// Create an empty repository
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a user, replacing any user with the same id
+    pub fn insert_user(&self, user: database::Users) {
+        self.users.insert(user.id, user);
+    }
+
+    /// Add a user to an organization
+    pub fn insert_organization_member(
+        &self,
+        organization_id: &Uuid,
+        user_id: &Uuid,
+    ) {
+        self.organization_members
+            .insert((*organization_id, *user_id));
+    }
+
+    /// Add a session, replacing any session with the same id
+    pub fn insert_session(&self, session: database::Sessions) {
+        self.sessions.insert(session.id, session);
+    }
+}
+
+/// The same error Postgres gives when `fetch_one` finds no row
+fn not_found() -> AuthenticationError {
+    sqlx::Error::RowNotFound.into()
+}
+
+/// Clone the rows matching the predicate, ordered by the key
+fn select<T, K, P, O>(rows: &DashMap<Uuid, T>, predicate: P, order: O) -> Vec<T>
+where
+    T: Clone,
+    K: Ord,
+    P: Fn(&T) -> bool,
+    O: Fn(&T) -> K,
+{
+    let mut selected: Vec<T> = rows
+        .iter()
+        .filter(|entry| predicate(entry.value()))
+        .map(|entry| entry.value().clone())
+        .collect();
+    selected.sort_by_key(order);
+
+    selected
+}
+
+/// Page rows already in order
+fn page<T>(rows: Vec<T>, limit: &usize, offset: &usize) -> Vec<T> {
+    rows.into_iter().skip(*offset).take(*limit).collect()
+}
+
+/// Remove the rows matching the predicate, returning how many were removed
+fn remove<T, P>(rows: &DashMap<Uuid, T>, predicate: P) -> u64
+where
+    P: Fn(&T) -> bool,
+{
+    let mut removed = 0;
+    rows.retain(|_, row| {
+        let matched = predicate(row);
+        if matched {
+            removed += 1;
+        }
+        !matched
+    });
+
+    removed
+}
+
+/// Does a user match every filter that is set
+fn matches_filter(
+    user: &database::Users,
+    filter: &database::UsersSearchFilter,
+) -> bool {
+    let email = filter
+        .email
+        .as_deref()
+        .map(str::trim)
+        .filter(|email| !email.is_empty())
+        .map(str::to_lowercase);
+
+    email.is_none_or(|email| user.email.as_ref().to_lowercase().contains(&email))
+        && filter.role.as_ref().is_none_or(|role| &user.role == role)
+        && filter
+            .is_active
+            .is_none_or(|is_active| user.is_active == is_active)
+        && filter
+            .is_verified
+            .is_none_or(|is_verified| user.is_verified == is_verified)
+        && filter
+            .created_after
+            .is_none_or(|created_after| user.created_on >= created_after)
+        && filter
+            .created_before
+            .is_none_or(|created_before| user.created_on < created_before)
+}
+
+#[tonic::async_trait]
+impl UserRepository for MemoryRepository {
+    async fn from_user_id(
+        &self,
+        id: &Uuid,
+    ) -> Result<database::Users, AuthenticationError> {
+        self.users
+            .get(id)
+            .map(|user| user.value().clone())
+            .ok_or_else(not_found)
+    }
+
+    async fn index(
+        &self,
+        limit: &usize,
+        offset: &usize,
+    ) -> Result<Vec<database::Users>, AuthenticationError> {
+        let users = select(&self.users, |_| true, |user| user.id);
+
+        Ok(page(users, limit, offset))
+    }
+
+    async fn index_organization(
+        &self,
+        organization_id: &Uuid,
+        limit: &usize,
+        offset: &usize,
+    ) -> Result<Vec<database::Users>, AuthenticationError> {
+        let users = select(
+            &self.users,
+            |user| {
+                self.organization_members
+                    .contains(&(*organization_id, user.id))
+            },
+            |user| user.id,
+        );
+
+        Ok(page(users, limit, offset))
+    }
+
+    async fn search(
+        &self,
+        filter: &database::UsersSearchFilter,
+        limit: &usize,
+        cursor_created_on: Option<DateTime<Utc>>,
+        cursor_id: Option<Uuid>,
+    ) -> Result<Vec<database::Users>, AuthenticationError> {
+        let cursor =
+            match (cursor_created_on, cursor_id) {
+                (Some(created_on), Some(id)) => Some((created_on, id)),
+                (None, None) => None,
+                _ => return Err(AuthenticationError::ValidationError(
+                    "Both cursor_created_on and cursor_id must be provided together"
+                        .to_string(),
+                )),
+            };
+
+        let users = select(
+            &self.users,
+            |user| {
+                matches_filter(user, filter)
+                    && filter.organization_id.is_none_or(|organization_id| {
+                        self.organization_members
+                            .contains(&(organization_id, user.id))
+                    })
+                    && cursor
+                        .is_none_or(|cursor| (user.created_on, user.id) > cursor)
+            },
+            |user| (user.created_on, user.id),
+        );
+
+        Ok(page(users, limit, &0))
+    }
+}
+
+#[tonic::async_trait]
+impl SessionRepository for MemoryRepository {
+    async fn from_id(
+        &self,
+        id: &Uuid,
+    ) -> Result<database::Sessions, AuthenticationError> {
+        self.sessions
+            .get(id)
+            .map(|session| session.value().clone())
+            .ok_or_else(not_found)
+    }
+
+    async fn index(
+        &self,
+        limit: &usize,
+        offset: &usize,
+    ) -> Result<Vec<database::Sessions>, AuthenticationError> {
+        let sessions = select(&self.sessions, |_| true, |session| session.id);
+
+        Ok(page(sessions, limit, offset))
+    }
+
+    async fn index_organization(
+        &self,
+        organization_id: &Uuid,
+        limit: &usize,
+        offset: &usize,
+    ) -> Result<Vec<database::Sessions>, AuthenticationError> {
+        let sessions = select(
+            &self.sessions,
+            |session| session.organization_id.as_ref() == Some(organization_id),
+            |session| session.id,
+        );
+
+        Ok(page(sessions, limit, offset))
+    }
+
+    async fn delete_by_id(&self, id: &Uuid) -> Result<u64, AuthenticationError> {
+        Ok(self.sessions.remove(id).map_or(0, |_| 1))
+    }
+
+    async fn delete_all_user(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<u64, AuthenticationError> {
+        Ok(remove(&self.sessions, |session| {
+            &session.user_id == user_id
+        }))
+    }
+
+    async fn delete_all_user_organization(
+        &self,
+        user_id: &Uuid,
+        organization_id: &Uuid,
+    ) -> Result<u64, AuthenticationError> {
+        Ok(remove(&self.sessions, |session| {
+            &session.user_id == user_id
+                && session.organization_id.as_ref() == Some(organization_id)
+        }))
+    }
+
+    async fn delete_all(&self) -> Result<u64, AuthenticationError> {
+        Ok(remove(&self.sessions, |_| true))
+    }
+
+    async fn delete_all_organization(
+        &self,
+        organization_id: &Uuid,
+    ) -> Result<u64, AuthenticationError> {
+        Ok(remove(&self.sessions, |session| {
+            session.organization_id.as_ref() == Some(organization_id)
+        }))
+    }
+}
+
+#[tonic::async_trait]
+impl EmailVerificationRepository for MemoryRepository {
+    async fn insert_email_verification(
+        &self,
+        email_verification: &database::EmailVerifications,
+    ) -> Result<database::EmailVerifications, AuthenticationError> {
+        self.email_verifications.insert(
+            email_verification.id.into_uuid(),
+            email_verification.clone(),
+        );
+
+        Ok(email_verification.clone())
+    }
+
+    async fn from_token(
+        &self,
+        token: &domain::EmailVerificationToken,
+    ) -> Result<database::EmailVerifications, AuthenticationError> {
+        // The Postgres model reports a missing token as a database error
+        self.email_verifications
+            .iter()
+            .find(|entry| entry.token.as_ref() == token.as_ref())
+            .map(|entry| entry.value().clone())
+            .ok_or_else(|| {
+                AuthenticationError::DatabaseError(
+                    sqlx::Error::RowNotFound.to_string(),
+                )
+            })
+    }
+
+    async fn index_from_user_id(
+        &self,
+        user_id: &Uuid,
+        limit: &usize,
+        offset: &usize,
+    ) -> Result<Vec<database::EmailVerifications>, AuthenticationError> {
+        let email_verifications = select(
+            &self.email_verifications,
+            |email_verification| &email_verification.user_id == user_id,
+            |email_verification| email_verification.id.into_uuid(),
+        );
+
+        Ok(page(email_verifications, limit, offset))
+    }
+
+    async fn delete_by_token(
+        &self,
+        token: &domain::EmailVerificationToken,
+    ) -> Result<u64, AuthenticationError> {
+        Ok(remove(&self.email_verifications, |email_verification| {
+            email_verification.token.as_ref() == token.as_ref()
+        }))
+    }
+
+    async fn delete_all_user_id(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<u64, AuthenticationError> {
+        Ok(remove(&self.email_verifications, |email_verification| {
+            &email_verification.user_id == user_id
+        }))
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use fake::{faker::company::en::CompanyName, Fake, Faker};
+    use secrecy::SecretString;
+
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[tokio::test]
+    async fn search_filters_and_pages_by_cursor() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let repository = MemoryRepository::new();
+        let mut users = Vec::new();
+        for seconds in 0..3 {
+            let mut user = database::Users::mock_data()?;
+            user.role = domain::UserRole::Guest;
+            user.created_on = Utc::now() + chrono::Duration::seconds(seconds);
+            repository.insert_user(user.clone());
+            users.push(user);
+        }
+        let mut admin = database::Users::mock_data()?;
+        admin.role = domain::UserRole::Admin;
+        repository.insert_user(admin);
+        let filter = database::UsersSearchFilter {
+            role: Some(domain::UserRole::Guest),
+            ..Default::default()
+        };
+
+        //-- Execute Function (Act)
+        let first_page = repository.search(&filter, &2, None, None).await?;
+        let last = first_page.last().ok_or("empty first page")?;
+        let second_page = repository
+            .search(&filter, &2, Some(last.created_on), Some(last.id))
+            .await?;
+        let partial_cursor =
+            repository.search(&filter, &2, Some(Utc::now()), None).await;
+
+        //-- Checks (Assertions)
+        assert_eq!(first_page, users[..2]);
+        assert_eq!(second_page, users[2..]);
+        assert!(partial_cursor.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sessions_are_read_and_deleted() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let repository = MemoryRepository::new();
+        let user = database::Users::mock_data()?;
+        let other_user = database::Users::mock_data()?;
+        let session = database::Sessions::mock_data(&user).await?;
+        repository.insert_session(session.clone());
+        repository.insert_session(database::Sessions::mock_data(&user).await?);
+        repository.insert_session(database::Sessions::mock_data(&other_user).await?);
+
+        //-- Execute Function (Act)
+        let read = repository.from_id(&session.id).await?;
+        let deleted = repository.delete_all_user(&user.id).await?;
+        let missing = repository.from_id(&session.id).await;
+        let remaining = SessionRepository::index(&repository, &10, &0).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(read, session);
+        assert_eq!(deleted, 2);
+        assert!(missing.is_err());
+        assert_eq!(remaining.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn email_verifications_are_read_by_token_and_deleted() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let repository = MemoryRepository::new();
+        let user = database::Users::mock_data()?;
+        let issuer =
+            SecretString::new(CompanyName().fake::<String>().into_boxed_str());
+        let secret = SecretString::new(Faker.fake::<String>().into_boxed_str());
+        let duration = chrono::Duration::hours(24);
+        let claim = domain::TokenClaimNew::new(
+            &issuer,
+            &duration,
+            &user,
+            &domain::TokenType::EmailVerification,
+        );
+        let token = domain::EmailVerificationToken::try_from_claim(claim, &secret)?;
+        let email_verification =
+            database::EmailVerifications::new(&user, &token, &duration);
+        repository
+            .insert_email_verification(&email_verification)
+            .await?;
+
+        //-- Execute Function (Act)
+        let read = repository.from_token(&token).await?;
+        let index = repository.index_from_user_id(&user.id, &10, &0).await?;
+        let deleted = repository.delete_by_token(&token).await?;
+        let missing = repository.from_token(&token).await;
+
+        //-- Checks (Assertions)
+        assert_eq!(read, email_verification);
+        assert_eq!(index, vec![email_verification]);
+        assert_eq!(deleted, 1);
+        assert!(missing.is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+//-- ./src/repository/mod.rs
+
+// #![allow(unused)] // For development only
+
+//! # Repositories
+//!
+//! Storage traits the services depend on, so a service can be backed by
+//! Postgres in production and by memory in fast unit tests.
+//!
+//! `PostgresRepository` delegates to the `database` models. `MemoryRepository`
+//! keeps rows in `DashMap`s, for tests and future backends to compare against.
+//!
+//! Writes that must commit with an outbox message in the same transaction
+//! still use the database pool directly, as a transaction cannot span a
+//! trait object.
+//!
+//! # Contents
+//! - `UserRepository` - reading users
+//! - `SessionRepository` - reading and deleting sessions
+//! - `EmailVerificationRepository` - storing, reading and deleting email
+//!   verification tokens
+//! - `PostgresRepository` - the Postgres implementation
+//! - `MemoryRepository` - the in-memory implementation
+//! ---
+
+mod email_verifications;
+mod memory;
+mod postgres;
+mod sessions;
+mod users;
+
+pub use email_verifications::EmailVerificationRepository;
+pub use memory::MemoryRepository;
+pub use postgres::PostgresRepository;
+pub use sessions::SessionRepository;
+pub use users::UserRepository;
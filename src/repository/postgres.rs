@@ -0,0 +1,232 @@
+//-- ./src/repository/postgres.rs
+
+// #![allow(unused)] // For development only
+
+//! Postgres repositories, delegating to the `database` models.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::prelude::*;
+use crate::repository::{
+    EmailVerificationRepository, SessionRepository, UserRepository,
+};
+use crate::{database, domain};
+
+/// Repositories backed by the Postgres pool, cheap to clone into each service
+#[derive(Clone)]
+pub struct PostgresRepository {
+    database: Arc<Pool<Postgres>>,
+}
+
+impl PostgresRepository {
+    /// Create a repository reading from the database pool
+    pub fn new(database: Arc<Pool<Postgres>>) -> Self {
+        Self { database }
+    }
+}
+
+#[tonic::async_trait]
+impl UserRepository for PostgresRepository {
+    async fn from_user_id(
+        &self,
+        id: &Uuid,
+    ) -> Result<database::Users, AuthenticationError> {
+        database::Users::from_user_id(id, &self.database).await
+    }
+
+    async fn index(
+        &self,
+        limit: &usize,
+        offset: &usize,
+    ) -> Result<Vec<database::Users>, AuthenticationError> {
+        database::Users::index(limit, offset, &self.database).await
+    }
+
+    async fn index_organization(
+        &self,
+        organization_id: &Uuid,
+        limit: &usize,
+        offset: &usize,
+    ) -> Result<Vec<database::Users>, AuthenticationError> {
+        database::Users::index_organization(
+            organization_id,
+            limit,
+            offset,
+            &self.database,
+        )
+        .await
+    }
+
+    async fn search(
+        &self,
+        filter: &database::UsersSearchFilter,
+        limit: &usize,
+        cursor_created_on: Option<DateTime<Utc>>,
+        cursor_id: Option<Uuid>,
+    ) -> Result<Vec<database::Users>, AuthenticationError> {
+        database::Users::search(
+            filter,
+            limit,
+            cursor_created_on,
+            cursor_id,
+            &self.database,
+        )
+        .await
+    }
+}
+
+#[tonic::async_trait]
+impl SessionRepository for PostgresRepository {
+    async fn from_id(
+        &self,
+        id: &Uuid,
+    ) -> Result<database::Sessions, AuthenticationError> {
+        database::Sessions::from_id(id, &self.database).await
+    }
+
+    async fn index(
+        &self,
+        limit: &usize,
+        offset: &usize,
+    ) -> Result<Vec<database::Sessions>, AuthenticationError> {
+        database::Sessions::index(limit, offset, &self.database).await
+    }
+
+    async fn index_organization(
+        &self,
+        organization_id: &Uuid,
+        limit: &usize,
+        offset: &usize,
+    ) -> Result<Vec<database::Sessions>, AuthenticationError> {
+        database::Sessions::index_organization(
+            organization_id,
+            limit,
+            offset,
+            &self.database,
+        )
+        .await
+    }
+
+    async fn delete_by_id(&self, id: &Uuid) -> Result<u64, AuthenticationError> {
+        database::Sessions::delete_by_id(id, &self.database).await
+    }
+
+    async fn delete_all_user(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<u64, AuthenticationError> {
+        database::Sessions::delete_all_user(user_id, &self.database).await
+    }
+
+    async fn delete_all_user_organization(
+        &self,
+        user_id: &Uuid,
+        organization_id: &Uuid,
+    ) -> Result<u64, AuthenticationError> {
+        database::Sessions::delete_all_user_organization(
+            user_id,
+            organization_id,
+            &self.database,
+        )
+        .await
+    }
+
+    async fn delete_all(&self) -> Result<u64, AuthenticationError> {
+        database::Sessions::delete_all(&self.database).await
+    }
+
+    async fn delete_all_organization(
+        &self,
+        organization_id: &Uuid,
+    ) -> Result<u64, AuthenticationError> {
+        database::Sessions::delete_all_organization(organization_id, &self.database)
+            .await
+    }
+}
+
+#[tonic::async_trait]
+impl EmailVerificationRepository for PostgresRepository {
+    async fn insert_email_verification(
+        &self,
+        email_verification: &database::EmailVerifications,
+    ) -> Result<database::EmailVerifications, AuthenticationError> {
+        email_verification.insert(&self.database).await
+    }
+
+    async fn from_token(
+        &self,
+        token: &domain::EmailVerificationToken,
+    ) -> Result<database::EmailVerifications, AuthenticationError> {
+        database::EmailVerifications::from_token(token, &self.database).await
+    }
+
+    async fn index_from_user_id(
+        &self,
+        user_id: &Uuid,
+        limit: &usize,
+        offset: &usize,
+    ) -> Result<Vec<database::EmailVerifications>, AuthenticationError> {
+        database::EmailVerifications::index_from_user_id(
+            user_id,
+            limit,
+            offset,
+            &self.database,
+        )
+        .await
+    }
+
+    async fn delete_by_token(
+        &self,
+        token: &domain::EmailVerificationToken,
+    ) -> Result<u64, AuthenticationError> {
+        database::EmailVerifications::delete_by_token(token, &self.database).await
+    }
+
+    async fn delete_all_user_id(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<u64, AuthenticationError> {
+        database::EmailVerifications::delete_all_user_id(user_id, &self.database)
+            .await
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn delegates_to_the_database_models(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?.insert(&database).await?;
+        let session = database::Sessions::mock_data(&user)
+            .await?
+            .insert(&database)
+            .await?;
+        let repository = PostgresRepository::new(Arc::new(database));
+
+        //-- Execute Function (Act)
+        let read_user = UserRepository::from_user_id(&repository, &user.id).await?;
+        let read_session =
+            SessionRepository::from_id(&repository, &session.id).await?;
+        let deleted = repository.delete_all_user(&user.id).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(read_user, user);
+        assert_eq!(read_session.id, session.id);
+        assert_eq!(deleted, 1);
+
+        Ok(())
+    }
+}
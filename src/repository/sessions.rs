@@ -0,0 +1,63 @@
+//-- ./src/repository/sessions.rs
+
+// #![allow(unused)] // For development only
+
+//! Session storage trait, matching the `database::Sessions` reads and
+//! deletes the services use.
+
+use uuid::Uuid;
+
+use crate::database;
+use crate::prelude::*;
+
+/// Reads and deletes sessions in storage
+#[tonic::async_trait]
+pub trait SessionRepository: Send + Sync {
+    /// The session with the id, or a not found error
+    async fn from_id(
+        &self,
+        id: &Uuid,
+    ) -> Result<database::Sessions, AuthenticationError>;
+
+    /// A page of sessions ordered by id
+    async fn index(
+        &self,
+        limit: &usize,
+        offset: &usize,
+    ) -> Result<Vec<database::Sessions>, AuthenticationError>;
+
+    /// A page of the sessions scoped to an organization, ordered by id
+    async fn index_organization(
+        &self,
+        organization_id: &Uuid,
+        limit: &usize,
+        offset: &usize,
+    ) -> Result<Vec<database::Sessions>, AuthenticationError>;
+
+    /// Delete the session with the id, returning the rows deleted
+    async fn delete_by_id(&self, id: &Uuid) -> Result<u64, AuthenticationError>;
+
+    /// Delete every session of a user, returning the rows deleted
+    async fn delete_all_user(
+        &self,
+        user_id: &Uuid,
+    ) -> Result<u64, AuthenticationError>;
+
+    /// Delete a user's sessions scoped to an organization, returning the
+    /// rows deleted
+    async fn delete_all_user_organization(
+        &self,
+        user_id: &Uuid,
+        organization_id: &Uuid,
+    ) -> Result<u64, AuthenticationError>;
+
+    /// Delete every session, returning the rows deleted
+    async fn delete_all(&self) -> Result<u64, AuthenticationError>;
+
+    /// Delete every session scoped to an organization, returning the rows
+    /// deleted
+    async fn delete_all_organization(
+        &self,
+        organization_id: &Uuid,
+    ) -> Result<u64, AuthenticationError>;
+}
@@ -0,0 +1,45 @@
+//-- ./src/repository/users.rs
+
+// #![allow(unused)] // For development only
+
+//! User storage trait, matching the `database::Users` reads the services use.
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::database;
+use crate::prelude::*;
+
+/// Reads users from storage. Soft deleted users are never returned.
+#[tonic::async_trait]
+pub trait UserRepository: Send + Sync {
+    /// The user with the id, or a not found error
+    async fn from_user_id(
+        &self,
+        id: &Uuid,
+    ) -> Result<database::Users, AuthenticationError>;
+
+    /// A page of users ordered by id
+    async fn index(
+        &self,
+        limit: &usize,
+        offset: &usize,
+    ) -> Result<Vec<database::Users>, AuthenticationError>;
+
+    /// A page of an organization's members ordered by id
+    async fn index_organization(
+        &self,
+        organization_id: &Uuid,
+        limit: &usize,
+        offset: &usize,
+    ) -> Result<Vec<database::Users>, AuthenticationError>;
+
+    /// Users matching the filter, ordered by `(created_on, id)` after the cursor
+    async fn search(
+        &self,
+        filter: &database::UsersSearchFilter,
+        limit: &usize,
+        cursor_created_on: Option<DateTime<Utc>>,
+        cursor_id: Option<Uuid>,
+    ) -> Result<Vec<database::Users>, AuthenticationError>;
+}
@@ -6,7 +6,7 @@
 //!
 //! This module configures and builds the gRPC server for the authentication service using Tonic.
 //!
-//! It sets up all RPC endpoints, middleware (including authorization), and CORS for gRPC-Web support.
+//! It sets up all RPC endpoints, middleware (including authorization, see `middleware::MethodPolicies`), and CORS for gRPC-Web support.
 //! TLS/HTTPS is supported and can be enabled via configuration, with certificate and key paths configurable.
 //!
 //! ## Local Development: TLS Certificates
@@ -33,6 +33,18 @@
 //!
 //! - Set `use_tls = true` in your configuration to enable TLS.
 //! - Set `tls_certificate` and `tls_private_key` to the paths of your certificate and key files.
+//! - Set `load_shedding.max_concurrent_requests` and `load_shedding.max_concurrent_expensive_requests`
+//!   to cap the requests in flight. Requests over either limit fail with `Unavailable` straight away.
+//! - Set `grpc.max_decoding_message_size`, `grpc.request_timeout_seconds` and the `grpc` keepalive
+//!   settings to tune the server. The TCP keepalive is set on the listener, see `startup`.
+//! - Set `compression.accept` and `compression.send` to the encodings the services compress
+//!   messages with, and `compression.services.<service>` to override them for one service.
+//! - Set `application.services` to the services served on the main address, and `listeners` to
+//!   serve services on more addresses or Unix domain sockets. Each listener gets its own router,
+//!   so the `load_shedding` limits apply to each listener separately.
+//! - Set `internal.enabled` to move the admin services to a router on `internal.address`.
+//! - Build with the `fault_injection` feature and set `fault_injection.rules` to delay, fail or
+//!   drop a percentage of requests, for testing clients against failures.
 //!
 //! ## Actions and Fixes
 //!
@@ -50,19 +62,29 @@ use core::time;
 use http::HeaderName;
 use sqlx::Pool;
 use sqlx::Postgres;
+use tokio::sync::Semaphore;
 use tonic::transport as tonic_transport;
+use tower::limit::GlobalConcurrencyLimitLayer;
+use tower::load_shed::LoadShedLayer;
+use tower_http::catch_panic::CatchPanicLayer;
 use tower_http::cors;
 
-use crate::configuration::Configuration;
-use crate::domain;
+use crate::avatars;
+use crate::configuration::SharedConfiguration;
+use crate::error_messages;
+use crate::events;
 use crate::middleware;
 use crate::prelude::*;
+use crate::readiness;
 use crate::rpc;
+use crate::rpc::proto::admin_service_server::AdminServiceServer as AdminServer;
 use crate::rpc::proto::authentication_service_server::AuthenticationServiceServer as AuthenticationServer;
+use crate::rpc::proto::authentication_service_server::SERVICE_NAME as AUTHENTICATION_SERVICE_NAME;
 use crate::rpc::proto::sessions_service_server::SessionsServiceServer as SessionsServer;
 use crate::rpc::proto::users_service_server::UsersServiceServer as UsersServer;
 use crate::rpc::proto::utilities_service_server::UtilitiesServiceServer as UtilitiesServer;
 use crate::services;
+use crate::telemetry;
 
 //-- Constants
 // Default max age for CORS preflight requests
@@ -84,19 +106,76 @@ const DEFAULT_EXPOSED_HEADERS: [&str; 3] =
 // The browser will not send these headers by default
 // unless they are specified in the CORS request.
 // The gRPC-web client will use these headers to send the request.
-const DEFAULT_ALLOW_HEADERS: [&str; 5] = [
+const DEFAULT_ALLOW_HEADERS: [&str; 7] = [
     "x-grpc-web",
     "content-type",
     "x-user-agent",
     "grpc-timeout",
     "authorization",
+    "x-api-key",
+    "accept-language",
 ];
 
+// Authentication methods that hash passwords with argon2, these get a lower
+// concurrency limit so a burst of logins can't starve the other endpoints
+const EXPENSIVE_AUTHENTICATION_METHODS: [&str; 3] = ["Login", "Register", "UpdatePassword"];
+
+/// Enable the configured compression encodings on a generated service server
+macro_rules! with_compression {
+    ($server:expr, $compression:expr, $service:literal) => {{
+        let (accept, send) = $compression.encodings($service);
+        let server = accept
+            .iter()
+            .fold($server, |server, encoding| server.accept_compressed((*encoding).into()));
+        send.iter()
+            .fold(server, |server, encoding| server.send_compressed((*encoding).into()))
+    }};
+}
+
 // Use a type alias for the gRPC router for cleaner code and easier reference
-type GrpcRouter = tonic_transport::server::Router<
+pub type GrpcRouter = tonic_transport::server::Router<
     tower_layer::Stack<
-        tonic_web::GrpcWebLayer,
-        tower_layer::Stack<cors::CorsLayer, tower_layer::Identity>,
+        middleware::AuthorisationLayer,
+        tower_layer::Stack<
+            middleware::GrpcPathLayer,
+            tower_layer::Stack<
+                middleware::DatabaseCircuitLayer,
+                tower_layer::Stack<
+                    middleware::ExpensiveRequestLimitLayer,
+                    tower_layer::Stack<
+                        GlobalConcurrencyLimitLayer,
+                        tower_layer::Stack<
+                            LoadShedLayer,
+                            tower_layer::Stack<
+                                middleware::UnavailableLayer,
+                                tower_layer::Stack<
+                                    middleware::FaultInjectionLayer,
+                                    tower_layer::Stack<
+                                        CatchPanicLayer<middleware::PanicResponse>,
+                                        tower_layer::Stack<
+                                            middleware::ErrorMessagesLayer,
+                                            tower_layer::Stack<
+                                                middleware::ErrorReportingLayer,
+                                                tower_layer::Stack<
+                                                    middleware::PackageAliasLayer,
+                                                    tower_layer::Stack<
+                                                        tonic_web::GrpcWebLayer,
+                                                        tower_layer::Stack<
+                                                            cors::CorsLayer,
+                                                            tower_layer::Identity,
+                                                        >,
+                                                    >,
+                                                >,
+                                            >,
+                                        >,
+                                    >,
+                                >,
+                            >,
+                        >,
+                    >,
+                >,
+            >,
+        >,
     >,
 >;
 
@@ -105,28 +184,58 @@ type GrpcRouter = tonic_transport::server::Router<
 /// RPC module containing endpoint configurations
 ///
 /// `database: Pool<Postgres>` - The database connection pool
-/// `config: Configuration` - The application configuration
+/// `shared_config: SharedConfiguration` - The shared runtime application configuration
+/// `auth_events: AuthEvents` - Authentication event broadcaster, shared with the HTTP gateway
+/// `denylist: TokenDenylist` - Denied access tokens, shared with the HTTP gateway
+/// `api_keys: ApiKeyStore` - Usable service account API keys
+/// `policies: PolicyAcceptanceStore` - Accepted policy versions, checked by the authorisation layer
+/// `captcha: CaptchaGuard` - CAPTCHA checks, shared with the HTTP gateway
+/// `readiness: Readiness` - The startup readiness checks, reported by the utilities service
+/// `activity: SessionActivity` - Requests made with each session, recorded by the authorisation layer
+/// `services: &[String]` - The services to serve, see `configuration::GRPC_SERVICES`
 ///
 /// ## References
 ///
 pub fn get_router(
     database: Pool<Postgres>,
-    config: Configuration,
+    shared_config: SharedConfiguration,
+    auth_events: events::AuthEvents,
+    denylist: middleware::TokenDenylist,
+    api_keys: middleware::ApiKeyStore,
+    policies: middleware::PolicyAcceptanceStore,
+    captcha: services::CaptchaGuard,
+    readiness: readiness::Readiness,
+    activity: middleware::SessionActivity,
+    services: &[String],
 ) -> Result<GrpcRouter, AuthenticationError> {
-    // Wraps our database pool and config in an Atomic Reference Counted (ARC).
+    // Wraps our database pool in an Atomic Reference Counted (ARC).
     // Each instance of the backend will get a pointer to the pool instead of getting a raw copy.
     let database = Arc::new(database);
-    let config = Arc::new(config);
+
+    // Snapshot of the configuration for settings that are fixed at startup,
+    // services load the shared configuration on each request instead.
+    let config = shared_config.load_full();
 
     // Get the token secret and issuer from the config
     let token_secret = config.application.token_secret.clone();
     let issuer = config.application.get_issuer();
+    let audiences = config.application.token_audiences.clone();
+
+    // Every served method must have an authorisation policy
+    let method_policies = middleware::MethodPolicies::default();
+    method_policies.check()?;
 
-    // Create the interceptor
-    // let access_token_interceptor = middleware::AccessTokenInterceptor {
-    //     token_secret,
-    //     issuer,
-    // };
+    // Authorise each request against the policy of its method
+    let authorisation_layer = middleware::AuthorisationLayer {
+        token_secret,
+        issuer,
+        audiences,
+        method_policies,
+        denylist: denylist.clone(),
+        api_keys: api_keys.clone(),
+        policies: policies.clone(),
+        activity: activity.clone(),
+    };
 
     // Build CORS layer
     let cors_layer = tower_http::cors::CorsLayer::new()
@@ -148,52 +257,127 @@ pub fn get_router(
                 .collect::<Vec<HeaderName>>(),
         );
 
+    // Concurrency limits, shed load rather than queueing requests. When disabled
+    // the limits are effectively unbounded.
+    let (max_concurrent_requests, max_concurrent_expensive_requests) =
+        if config.load_shedding.enabled {
+            (
+                config.load_shedding.max_concurrent_requests,
+                config.load_shedding.max_concurrent_expensive_requests,
+            )
+        } else {
+            (Semaphore::MAX_PERMITS, Semaphore::MAX_PERMITS)
+        };
+    let expensive_limit_layer = middleware::ExpensiveRequestLimitLayer::new(
+        max_concurrent_expensive_requests,
+        EXPENSIVE_AUTHENTICATION_METHODS
+            .iter()
+            .map(|method| format!("/{AUTHENTICATION_SERVICE_NAME}/{method}")),
+    );
+
+    // Error codes and localised messages are added to every error status
+    let error_messages_layer = middleware::ErrorMessagesLayer::new(
+        error_messages::ErrorMessages::new(&config.error_messages)?,
+    );
+
+    // Requests larger than this fail with ResourceExhausted, set on each service
+    let max_decoding_message_size = config.grpc.max_decoding_message_size;
+
+    // Faults injected for resilience testing, none unless enabled
+    let fault_injection_layer = middleware::FaultInjectionLayer::new(&config.fault_injection)?;
+
+    // Password hashing runs on the blocking thread pool, with one queue shared
+    // by all the services
+    let password_hasher =
+        services::PasswordHasher::new(config.load_shedding.max_queued_password_hashes);
+
     //-- Build the Utilities Service
     // Create a new UtilitiesService instance
-    let utilities_service = services::UtilitiesService::new(Arc::clone(&config));
+    let utilities_service =
+        services::UtilitiesService::new(Arc::clone(&shared_config)).with_readiness(readiness);
 
     // Wrap the UtilitiesService in the UtilitiesServiceServer
-    let utilities_server = UtilitiesServer::new(utilities_service);
+    let utilities_server = with_compression!(
+        UtilitiesServer::new(utilities_service).max_decoding_message_size(max_decoding_message_size),
+        config.compression,
+        "utilities"
+    );
 
     //-- Build the Authentication Service
     // Create a new AuthenticationService instance
     let authentication_service = services::AuthenticationService::new(
         Arc::clone(&database),
-        Arc::clone(&config),
-    );
+        Arc::clone(&shared_config),
+        auth_events.clone(),
+        denylist.clone(),
+        captcha,
+    )
+    .with_password_hasher(password_hasher.clone())
+    .with_session_activity(activity.clone());
 
     // Wrap the AuthenticationService in the AuthenticationServiceServer
-    let authentication_server = AuthenticationServer::new(authentication_service);
+    let authentication_server = with_compression!(
+        AuthenticationServer::new(authentication_service)
+            .max_decoding_message_size(max_decoding_message_size),
+        config.compression,
+        "authentication"
+    );
 
     //-- Build the Users Service
     // Create a new UsersService instance
-    let users_service =
-        services::UsersService::new(Arc::clone(&database), Arc::clone(&config));
+    let users_service = services::UsersService::new(
+        Arc::clone(&database),
+        Arc::clone(&shared_config),
+        auth_events.clone(),
+    )
+    .with_password_hasher(password_hasher.clone())
+    .with_policy_acceptances(policies.clone())
+    .with_avatar_storage(avatars::storage_from_config(
+        &config.avatars,
+        &config.application.token_secret,
+    )?);
 
     // Wrap the UsersService in the UsersServiceServer
     // let users_server = UsersServer::new(users_service); // <-- For testing with no access token
-    let users_server = UsersServer::with_interceptor(
-        users_service,
-        middleware::AuthorisationInterceptor {
-            token_secret: token_secret.clone(),
-            issuer: issuer.clone(),
-            allowable_roles: vec![domain::UserRole::Admin, domain::UserRole::User],
-        },
+    let users_server = with_compression!(
+        UsersServer::new(users_service).max_decoding_message_size(max_decoding_message_size),
+        config.compression,
+        "users"
     );
 
     //-- Build the Sessions Service
     // Create a new SessionsService instance
-    let sessions_service =
-        services::SessionsService::new(Arc::clone(&database), Arc::clone(&config));
+    let sessions_service = services::SessionsService::new(
+        Arc::clone(&database),
+        Arc::clone(&shared_config),
+        auth_events.clone(),
+    );
 
     // Wrap the SessionsService in the SessionsServiceServer
-    let sessions_server = SessionsServer::with_interceptor(
-        sessions_service,
-        middleware::AuthorisationInterceptor {
-            token_secret: token_secret.clone(),
-            issuer: issuer.clone(),
-            allowable_roles: vec![domain::UserRole::Admin, domain::UserRole::User],
-        },
+    let sessions_server = with_compression!(
+        SessionsServer::new(sessions_service)
+            .max_decoding_message_size(max_decoding_message_size),
+        config.compression,
+        "sessions"
+    );
+
+    //-- Build the Admin Service
+    // Create a new AdminService instance
+    let admin_service = services::AdminService::new(
+        Arc::clone(&database),
+        Arc::clone(&shared_config),
+        auth_events.clone(),
+        api_keys.clone(),
+        denylist.clone(),
+    )
+    .with_password_hasher(password_hasher);
+
+    // Wrap the AdminService in the AdminServiceServer, only admins may use it,
+    // see `middleware::MethodPolicies`
+    let admin_server = with_compression!(
+        AdminServer::new(admin_service).max_decoding_message_size(max_decoding_message_size),
+        config.compression,
+        "admin"
     );
 
     //-- Build the Tonic Router
@@ -202,10 +386,35 @@ pub fn get_router(
     // the server. It allows us to add services, middlewares, and other configurations.
     // The server builder is used to create the Tonic server
     let mut server_builder = tonic_transport::Server::builder()
-        .trace_fn(|_| tracing::info_span!("Tonic"))
+        .trace_fn(telemetry::grpc_span)
         .accept_http1(true)
+        // Cancel calls that run too long, clients can ask for less with grpc-timeout
+        .timeout(config.grpc.request_timeout())
+        .http2_keepalive_interval(config.grpc.http2_keepalive_interval())
+        .http2_keepalive_timeout(Some(config.grpc.http2_keepalive_timeout()))
         .layer(cors_layer)
-        .layer(tonic_web::GrpcWebLayer::new());
+        .layer(tonic_web::GrpcWebLayer::new())
+        // Serve the deprecated package paths, before the layers that match paths
+        .layer(middleware::PackageAliasLayer::default())
+        // Report the internal errors and panics of each request with its context
+        .layer(middleware::ErrorReportingLayer)
+        // Localise the errors of every layer below, including shed requests
+        .layer(error_messages_layer)
+        // Answer panicked requests with Internal, rather than dropping the connection
+        .layer(middleware::catch_panic_layer())
+        // Delay, fail or drop requests on purpose, only with the feature on
+        .layer(fault_injection_layer)
+        // Answer shed requests with Unavailable, then shed requests over the limits
+        .layer(middleware::UnavailableLayer)
+        .layer(LoadShedLayer::new())
+        .layer(GlobalConcurrencyLimitLayer::new(max_concurrent_requests))
+        .layer(expensive_limit_layer)
+        // Answer Unavailable straight away while the database circuit is open
+        .layer(middleware::DatabaseCircuitLayer::new())
+        // Let the layers below see which method is called
+        .layer(middleware::GrpcPathLayer)
+        // Enforce the authorisation policy of the method
+        .layer(authorisation_layer);
 
     // If the application is configured to use TLS, we need to load the TLS identity
     // and configure the server to use TLS.
@@ -248,12 +457,16 @@ pub fn get_router(
 
     // Add the services to the server builder. The services are added to the server
     // builder, which will be used to create the Tonic server.
+    // Only the services served on this listener are added
+    let serves = |service: &str| services.iter().any(|served| served == service);
     let router = server_builder
         .add_service(rpc::spec_service()?)
-        .add_service(utilities_server)
-        .add_service(authentication_server)
-        .add_service(users_server)
-        .add_service(sessions_server);
+        .add_service(rpc::spec_service_v1alpha()?)
+        .add_optional_service(serves("utilities").then_some(utilities_server))
+        .add_optional_service(serves("authentication").then_some(authentication_server))
+        .add_optional_service(serves("users").then_some(users_server))
+        .add_optional_service(serves("sessions").then_some(sessions_server))
+        .add_optional_service(serves("admin").then_some(admin_server));
 
     Ok(router)
 }
@@ -3,10 +3,30 @@
 //! # RPC Protocol Buffers
 //! 
 //! This module contains the RPC protocol buffers for the authentication service.
+//!
+//! ## API Versions
+//!
+//! Each API version is a proto package, e.g. `authentication.v1`, laid out as
+//! Buf expects in `proto/authentication/authentication/v1/`. Every package in
+//! `API_VERSIONS` is listed by reflection, over both the `v1` and `v1alpha`
+//! reflection protocols, and checked for method policies at startup.
+//!
+//! Compatible changes are made in the current package. A breaking change is
+//! made in a new package, e.g. `authentication.v2`, served next to the old
+//! one so old clients keep working:
+//! 1. Compile the new package in `build.rs` to its own descriptor set and
+//!    include it as another module, e.g. `proto_v2`
+//! 2. Add it to `API_VERSIONS` and give its methods policies in
+//!    `middleware::MethodPolicies`
+//! 3. Implement the new services, and serve the old package's services by
+//!    converting their messages to the new ones
+//! 4. Once clients have moved, alias the old package to the new one in
+//!    `PACKAGE_ALIASES` for any methods that are still wire compatible, or
+//!    remove it
 
 #![allow(unused)] // For development only
 
-use tonic_reflection::server::v1::{ ServerReflection, ServerReflectionServer};
+use tonic_reflection::server::{v1, v1alpha};
 
 use crate::prelude::*;
 
@@ -16,23 +36,113 @@ use crate::prelude::*;
 /// The `proto` module contains the generated code from the Protobuf files.
 /// The `spec_service` function returns a reflection server to allow reading the proto definition at runtime.
 pub mod proto {
-    // The string specified here must match the proto package name
-    tonic::include_proto!("authentication");
+    // The string specified here must match the proto package name, see
+    // `PACKAGE_ALIASES` for the old `authentication` package
+    tonic::include_proto!("authentication.v1");
 
     #[allow(dead_code)]
     pub const FILE_DESCRIPTOR_SET: &[u8] =
         tonic::include_file_descriptor_set!("authentication_descriptor");
 }
 
+/// A served API version
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ApiVersion {
+    /// The proto package, e.g. `authentication.v1`
+    pub package: &'static str,
+
+    /// The encoded file descriptor set of the package
+    pub file_descriptor_set: &'static [u8],
+}
+
+/// The API versions served, oldest first
+pub const API_VERSIONS: &[ApiVersion] = &[ApiVersion {
+    package: "authentication.v1",
+    file_descriptor_set: proto::FILE_DESCRIPTOR_SET,
+}];
+
+/// An old proto package served as the package that replaced it, see
+/// `middleware::PackageAliasLayer`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PackageAlias {
+    /// The deprecated package, e.g. `authentication`
+    pub from: &'static str,
+
+    /// The package serving it, e.g. `authentication.v1`
+    pub to: &'static str,
+}
+
+/// Deprecated packages still served, by the package that replaced them
+pub const PACKAGE_ALIASES: &[PackageAlias] = &[PackageAlias {
+    from: "authentication",
+    to: "authentication.v1",
+}];
+
 // spec_service returns reflection server to allow reading proto definition at runtime.
-pub fn spec_service() -> Result<ServerReflectionServer<impl ServerReflection>, AuthenticationError> {
+pub fn spec_service(
+) -> Result<v1::ServerReflectionServer<impl v1::ServerReflection>, AuthenticationError> {
     // Create the reflection service
     // This service allows us to read the proto definition at runtime
     // and is used by gRPC-Web to generate the client code.
-    let reflection_service = tonic_reflection::server::Builder::configure()
-        .register_encoded_file_descriptor_set(proto::FILE_DESCRIPTOR_SET)
+    let reflection_service = API_VERSIONS
+        .iter()
+        .fold(tonic_reflection::server::Builder::configure(), |builder, version| {
+            builder.register_encoded_file_descriptor_set(version.file_descriptor_set)
+        })
         .build_v1()?;
 
     Ok(reflection_service)
 }
 
+// spec_service_v1alpha returns the same reflection server for clients that
+// only speak the older v1alpha reflection protocol, e.g. older grpcurl
+pub fn spec_service_v1alpha(
+) -> Result<v1alpha::ServerReflectionServer<impl v1alpha::ServerReflection>, AuthenticationError>
+{
+    let reflection_service = API_VERSIONS
+        .iter()
+        .fold(tonic_reflection::server::Builder::configure(), |builder, version| {
+            builder.register_encoded_file_descriptor_set(version.file_descriptor_set)
+        })
+        .build_v1alpha()?;
+
+    Ok(reflection_service)
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn api_versions_describe_their_packages() -> Result<()> {
+        for version in API_VERSIONS {
+            let descriptors = prost_types::FileDescriptorSet::decode(version.file_descriptor_set)?;
+            assert!(
+                descriptors
+                    .file
+                    .iter()
+                    .any(|file| file.package() == version.package && !file.service.is_empty()),
+                "{} has no services",
+                version.package
+            );
+        }
+
+        for alias in PACKAGE_ALIASES {
+            assert!(
+                API_VERSIONS.iter().any(|version| version.package == alias.to),
+                "{} is aliased to {}, which is not served",
+                alias.from,
+                alias.to
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,1878 @@
+//-- ./src/services/admin.rs
+
+//! RPC service for admin only endpoints
+//!
+//! Contains bulk user management endpoints used when migrating users between
+//! environments:
+//! - `import_users`: Client streaming of user records, validated and inserted per row
+//! - `export_users`: Server streaming of user rows (without password hashes) as CSV or ndjson
+//! - `watch_auth_events`: Server streaming of login, logout and revocation events
+//!
+//! And service account API key management:
+//! - `create_api_key`: Create a role scoped API key, the key is only returned once
+//! - `list_api_keys`: Page through API keys, without the keys themselves
+//! - `revoke_api_key`: Revoke an API key so it can no longer authenticate
+//!
+//! And OAuth client registration:
+//! - `register_client`: Register a confidential or public client, a confidential client's secret is only returned once
+//! - `list_clients`: Page through clients, without their secrets
+//! - `update_client`: Change a client's name, redirect URIs, grant types and scopes
+//! - `revoke_client`: Revoke a client so it can no longer get access tokens
+//!
+//! And organization (tenant) management:
+//! - `create_organization`: Create an organization with a unique slug
+//! - `add_organization_member`: Add a user to an organization, or change their role in it
+//!
+//! And webhook endpoint management (see `services::webhooks`):
+//! - `create_webhook_endpoint`: Create an endpoint, the signing secret is only returned once
+//! - `list_webhook_endpoints`: Page through webhook endpoints, without their secrets
+//! - `delete_webhook_endpoint`: Delete an endpoint and its delivery history
+//! - `list_webhook_deliveries`: Page through an endpoint's delivery attempts
+//!
+//! And registration email domain rules (see `services::email_domains`):
+//! - `set_email_domain_rule`: Allow or block a domain, and its subdomains, from registering
+//! - `list_email_domain_rules`: List the domain rules, in domain order
+//! - `delete_email_domain_rule`: Remove a domain's rule
+//!
+//! And user email changes:
+//! - `request_email_change`: Start an email change, confirmed from both the old and new addresses
+//!
+//! And user impersonation, for support (see `configuration.impersonation`):
+//! - `impersonate_user`: Issue a short lived access token marked with the admin, recording why
+//! - `list_impersonations`: Page through the impersonation audit trail
+//! - `revoke_impersonation`: Deny an impersonation access token before it expires
+//!
+//! And forced password resets:
+//! - `force_password_reset`: Make a user change their password, revoking their
+//!   sessions and emailing them a reset link
+//!
+//! And incident response:
+//! - `get_security_report`: Summarise recent failed logins by IP address and
+//!   account, with the logins locked out now
+//!
+//! And user deletion:
+//! - `delete_user`: Soft delete a user and revoke their sessions
+//! - `restore_user`: Restore a soft deleted user
+//! - `merge_users`: Move a duplicate user's sessions, tokens and audit records
+//!   to the account kept, then soft delete the duplicate
+//! ---
+
+// #![allow(unused)] // For development only
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use secrecy::ExposeSecret;
+use sqlx::{Pool, Postgres};
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::{BroadcastStream, ReceiverStream};
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status, Streaming};
+use uuid::Uuid;
+
+use crate::configuration::{Configuration, SharedConfiguration};
+use crate::database::timeouts::{with_timeout, QueryBudget};
+use crate::email::{EmailTemplate, EmailTemplates};
+use crate::events::{AuthEvent, AuthEventKind, AuthEvents};
+use crate::middleware::{ApiKeyStore, TokenDenylist};
+use crate::prelude::*;
+use crate::rpc::proto::admin_service_server::AdminService as Admin;
+use crate::rpc::proto::{
+    AddOrganizationMemberRequest, ApiKeyIndexRequest, ApiKeyIndexResponse, ApiKeyResponse,
+    AuthEventResponse, ClientIndexRequest, ClientIndexResponse, ClientResponse, CreateApiKeyRequest,
+    CreateApiKeyResponse, CreateOrganizationRequest, CreateUserRequest,
+    CreateWebhookEndpointRequest, CreateWebhookEndpointResponse, DeleteEmailDomainRuleRequest,
+    DeleteEmailDomainRuleResponse, DeleteUserRequest, DeleteUserResponse,
+    DeleteWebhookEndpointRequest, DeleteWebhookEndpointResponse, EmailDomainRuleResponse, Empty,
+    ExportUsersRequest, ExportUsersResponse, ForcePasswordResetRequest,
+    ForcePasswordResetResponse, ImpersonateUserRequest, ImpersonateUserResponse,
+    ImpersonationIndexRequest, ImpersonationIndexResponse, ImpersonationResponse, ImportUserFailure,
+    ImportUsersResponse, ListEmailDomainRulesResponse, MergeUsersRequest, MergeUsersResponse,
+    OrganizationMemberResponse, OrganizationResponse, RegisterClientRequest,
+    RegisterClientResponse, RequestEmailChangeRequest, RequestEmailChangeResponse,
+    FailedLoginsByAccountResponse, FailedLoginsByIpResponse, LockedLoginResponse,
+    RestoreUserRequest, RevokeApiKeyRequest, RevokeApiKeyResponse, RevokeClientRequest,
+    RevokeClientResponse, RevokeImpersonationRequest, RevokeImpersonationResponse,
+    SecurityReportRequest, SecurityReportResponse, SetEmailDomainRuleRequest, UpdateClientRequest, UserResponse, WatchAuthEventsRequest, WebhookDeliveryIndexRequest,
+    WebhookDeliveryIndexResponse, WebhookDeliveryResponse, WebhookEndpointIndexRequest,
+    WebhookEndpointIndexResponse, WebhookEndpointResponse,
+};
+use crate::services::webhooks::WEBHOOK_EVENT_TYPES;
+use crate::services::{password_reset, validation, PasswordHasher};
+use crate::{database, domain};
+
+/// How many export lines can be buffered before the database reads wait
+const EXPORT_CHANNEL_SIZE: usize = 128;
+
+/// How long both addresses have to confirm an email change
+const EMAIL_CHANGE_EXPIRY_HOURS: u64 = 24;
+
+/// Admin service containing a database pool
+pub struct AdminService {
+    database: Arc<Pool<Postgres>>,
+    config: SharedConfiguration,
+    events: AuthEvents,
+    api_keys: ApiKeyStore,
+    denylist: TokenDenylist,
+    password_hasher: PasswordHasher,
+}
+
+impl AdminService {
+    /// Create a new AdminService passing in the Arc for the Sqlx database pool
+    pub fn new(
+        database: Arc<Pool<Postgres>>,
+        config: SharedConfiguration,
+        events: AuthEvents,
+        api_keys: ApiKeyStore,
+        denylist: TokenDenylist,
+    ) -> Self {
+        Self {
+            database,
+            config,
+            events,
+            api_keys,
+            denylist,
+            password_hasher: PasswordHasher::default(),
+        }
+    }
+
+    /// Share a password hasher, and its queue, with the other services
+    pub fn with_password_hasher(mut self, password_hasher: PasswordHasher) -> Self {
+        self.password_hasher = password_hasher;
+        self
+    }
+
+    /// Shorthand for reference to database pool
+    fn database_ref(&self) -> &Pool<Postgres> {
+        &self.database
+    }
+
+    /// Shorthand for a snapshot of the configuration
+    fn config_ref(&self) -> Arc<Configuration> {
+        self.config.load_full()
+    }
+}
+
+/// Supported user export formats
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Ndjson,
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = AuthenticationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "" | "csv" => Ok(Self::Csv),
+            "ndjson" | "jsonl" => Ok(Self::Ndjson),
+            other => Err(AuthenticationError::ValidationError(format!(
+                "{other} is not a supported export format. Use either `csv` or `ndjson`."
+            ))),
+        }
+    }
+}
+
+/// A user row as exported, the password hash is never included
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ExportUserRow {
+    pub id: Uuid,
+    pub email: String,
+    pub name: String,
+    pub role: String,
+    pub is_active: bool,
+    pub is_verified: bool,
+    pub created_on: DateTime<Utc>,
+}
+
+impl From<database::Users> for ExportUserRow {
+    fn from(value: database::Users) -> Self {
+        Self {
+            id: value.id,
+            email: value.email.to_string(),
+            name: value.name.to_string(),
+            role: value.role.to_string(),
+            is_active: value.is_active,
+            is_verified: value.is_verified,
+            created_on: value.created_on,
+        }
+    }
+}
+
+/// CSV header line for user exports
+pub const EXPORT_CSV_HEADER: &str = "id,email,name,role,is_active,is_verified,created_on";
+
+/// Quote a CSV field when it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+impl ExportUserRow {
+    /// Format the row as a single export line (without the trailing new line)
+    pub fn to_line(&self, format: ExportFormat) -> Result<String, AuthenticationError> {
+        match format {
+            ExportFormat::Csv => Ok([
+                self.id.to_string(),
+                csv_field(&self.email),
+                csv_field(&self.name),
+                self.role.clone(),
+                self.is_active.to_string(),
+                self.is_verified.to_string(),
+                self.created_on.to_rfc3339(),
+            ]
+            .join(",")),
+            ExportFormat::Ndjson => Ok(serde_json::to_string(self)?),
+        }
+    }
+}
+
+impl From<database::ApiKeys> for ApiKeyResponse {
+    /// Convert from database::ApiKeys to proto::ApiKeyResponse, the key hash is never included
+    fn from(value: database::ApiKeys) -> Self {
+        Self {
+            id: value.id.to_string(),
+            name: value.name,
+            key_prefix: value.key_prefix,
+            role: value.role.to_string(),
+            created_on: value.created_on.to_string(),
+            expires_on: value.expires_on.map(|expires_on| expires_on.to_string()),
+            revoked_on: value.revoked_on.map(|revoked_on| revoked_on.to_string()),
+        }
+    }
+}
+
+impl From<database::Clients> for ClientResponse {
+    /// Convert from database::Clients to proto::ClientResponse, the secret hash is never included
+    fn from(value: database::Clients) -> Self {
+        Self {
+            client_id: value.id.to_string(),
+            name: value.name,
+            client_type: value.client_type.to_string(),
+            secret_prefix: value.secret_prefix,
+            redirect_uris: value.redirect_uris,
+            grant_types: value.grant_types,
+            scopes: value.scopes,
+            created_on: value.created_on.to_string(),
+            revoked_on: value.revoked_on.map(|revoked_on| revoked_on.to_string()),
+        }
+    }
+}
+
+/// Is the redirect URI an absolute URI without a fragment, using https
+/// unless it is a loopback address for native apps (RFC 8252). Private-use
+/// schemes, e.g. `com.example.app:/callback`, are allowed for mobile apps.
+fn is_valid_redirect_uri(uri: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(uri) else {
+        return false;
+    };
+    if url.fragment().is_some() {
+        return false;
+    }
+
+    match url.scheme() {
+        "https" => url.host().is_some(),
+        "http" => matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "[::1]")),
+        scheme => scheme.contains('.'),
+    }
+}
+
+/// Check a client registration, returning the trimmed name and parsed grant
+/// types. A client must be allowed at least one grant, clients using a
+/// redirect grant need a redirect URI, and public clients can't use the
+/// client credentials grant as they have no secret.
+fn validate_client_registration<'a>(
+    name: &'a str,
+    client_type: database::ClientType,
+    redirect_uris: &[String],
+    grant_types: &[String],
+    scopes: &[String],
+) -> Result<(&'a str, Vec<domain::GrantType>), Status> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(Status::invalid_argument("Client name is required"));
+    }
+
+    let grant_types = grant_types
+        .iter()
+        .map(|grant_type| grant_type.parse::<domain::GrantType>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| Status::invalid_argument(e.to_string()))?;
+    if grant_types.is_empty() {
+        return Err(Status::invalid_argument("At least one grant type is required"));
+    }
+    if client_type == database::ClientType::Public
+        && grant_types.contains(&domain::GrantType::ClientCredentials)
+    {
+        return Err(Status::invalid_argument(
+            "Public clients can't use the client_credentials grant",
+        ));
+    }
+
+    if let Some(uri) = redirect_uris.iter().find(|uri| !is_valid_redirect_uri(uri)) {
+        return Err(Status::invalid_argument(format!("Invalid redirect URI: {uri:?}")));
+    }
+    if domain::GrantType::needs_redirect_uri(&grant_types) && redirect_uris.is_empty() {
+        return Err(Status::invalid_argument(
+            "The authorization_code grant needs at least one redirect URI",
+        ));
+    }
+
+    if let Some(scope) = scopes.iter().find(|scope| !domain::is_valid_scope(scope)) {
+        return Err(Status::invalid_argument(format!("Invalid scope: {scope:?}")));
+    }
+
+    Ok((name, grant_types))
+}
+
+impl From<database::Impersonations> for ImpersonationResponse {
+    /// Convert from database::Impersonations to proto::ImpersonationResponse
+    fn from(value: database::Impersonations) -> Self {
+        Self {
+            id: value.id.to_string(),
+            admin_id: value.admin_id.to_string(),
+            user_id: value.user_id.to_string(),
+            reason: value.reason,
+            created_on: value.created_on.to_string(),
+            expires_on: value.expires_on.to_string(),
+            revoked_on: value.revoked_on.map(|revoked_on| revoked_on.to_string()),
+            revoked_by: value.revoked_by.map(|revoked_by| revoked_by.to_string()),
+        }
+    }
+}
+
+/// Check the reason given for an impersonation, returning it trimmed
+fn validate_impersonation_reason(reason: &str, min_length: usize) -> Result<&str, Status> {
+    let reason = reason.trim();
+    if reason.chars().count() < min_length {
+        return Err(Status::invalid_argument(format!(
+            "A reason of at least {min_length} characters is required"
+        )));
+    }
+
+    Ok(reason)
+}
+
+impl From<database::WebhookEndpoints> for WebhookEndpointResponse {
+    /// Convert from database::WebhookEndpoints to proto::WebhookEndpointResponse, the secret is never included
+    fn from(value: database::WebhookEndpoints) -> Self {
+        Self {
+            id: value.id.to_string(),
+            url: value.url,
+            event_types: value.event_types,
+            is_active: value.is_active,
+            created_on: value.created_on.to_string(),
+        }
+    }
+}
+
+impl From<database::WebhookDeliveries> for WebhookDeliveryResponse {
+    /// Convert from database::WebhookDeliveries to proto::WebhookDeliveryResponse
+    fn from(value: database::WebhookDeliveries) -> Self {
+        Self {
+            id: value.id.to_string(),
+            endpoint_id: value.endpoint_id.to_string(),
+            event_id: value.event_id.to_string(),
+            event_type: value.event_type,
+            status: value.status.to_string(),
+            attempts: value.attempts,
+            next_attempt_at: value.next_attempt_at.to_string(),
+            last_attempt_at: value.last_attempt_at.map(|attempt| attempt.to_string()),
+            last_response_status: value.last_response_status,
+            last_error: value.last_error,
+            created_on: value.created_on.to_string(),
+        }
+    }
+}
+
+impl From<database::Organizations> for OrganizationResponse {
+    /// Convert from database::Organizations to proto::OrganizationResponse
+    fn from(value: database::Organizations) -> Self {
+        Self {
+            id: value.id.to_string(),
+            name: value.name,
+            slug: value.slug,
+            created_on: value.created_on.to_string(),
+        }
+    }
+}
+
+impl From<database::EmailDomains> for EmailDomainRuleResponse {
+    /// Convert from database::EmailDomains to proto::EmailDomainRuleResponse
+    fn from(value: database::EmailDomains) -> Self {
+        Self {
+            domain: value.domain,
+            rule: value.rule.to_string(),
+            created_on: value.created_on.to_string(),
+        }
+    }
+}
+
+impl From<database::OrganizationMembers> for OrganizationMemberResponse {
+    /// Convert from database::OrganizationMembers to proto::OrganizationMemberResponse
+    fn from(value: database::OrganizationMembers) -> Self {
+        Self {
+            organization_id: value.organization_id.to_string(),
+            user_id: value.user_id.to_string(),
+            role: value.role.to_string(),
+            created_on: value.created_on.to_string(),
+        }
+    }
+}
+
+impl From<database::LoginFailuresByIp> for FailedLoginsByIpResponse {
+    /// Convert from database::LoginFailuresByIp to proto::FailedLoginsByIpResponse
+    fn from(value: database::LoginFailuresByIp) -> Self {
+        Self {
+            ip_address: value.ip_address,
+            failed: value.failed as u64,
+            throttled: value.throttled as u64,
+            accounts: value.emails as u64,
+            last_attempt_on: value.last_attempt_on.to_string(),
+            is_throttled: false,
+        }
+    }
+}
+
+impl From<database::LoginFailuresByEmail> for FailedLoginsByAccountResponse {
+    /// Convert from database::LoginFailuresByEmail to proto::FailedLoginsByAccountResponse
+    fn from(value: database::LoginFailuresByEmail) -> Self {
+        Self {
+            email: value.email,
+            user_id: value.user_id.map(|user_id| user_id.to_string()),
+            failed: value.failed as u64,
+            throttled: value.throttled as u64,
+            ip_addresses: value.ip_addresses as u64,
+            last_attempt_on: value.last_attempt_on.to_string(),
+            is_locked: false,
+        }
+    }
+}
+
+impl From<database::LoginThrottles> for LockedLoginResponse {
+    /// Convert from database::LoginThrottles to proto::LockedLoginResponse
+    fn from(value: database::LoginThrottles) -> Self {
+        Self {
+            ip_address: value.ip_address,
+            email: value.email,
+            failures: value.failures,
+            locked_until: value
+                .locked_until
+                .map(|locked_until| locked_until.to_string())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl Admin for AdminService {
+    /// Handle client streaming requests to import users into the database.
+    ///
+    /// Each streamed record is validated and inserted on its own, so one bad
+    /// row does not abort the import. The response summarises the rows that
+    /// failed and why.
+    ///
+    /// Records with `temporary_password` set are given a random password,
+    /// emailed to the user, that only works for their first login.
+    #[tracing::instrument(name = "Import Users Request: ", skip(self, request))]
+    async fn import_users(
+        &self,
+        request: Request<Streaming<CreateUserRequest>>,
+    ) -> Result<Response<ImportUsersResponse>, Status> {
+        let mut stream = request.into_inner();
+
+        let config = self.config_ref();
+        let templates = EmailTemplates::new(&config.email)?;
+
+        let mut imported: u64 = 0;
+        let mut failures: Vec<ImportUserFailure> = Vec::new();
+        let mut row: u64 = 0;
+
+        while let Some(record) = stream.next().await {
+            row += 1;
+            let mut record = record?;
+            let email = record.email.clone();
+
+            // A temporary password replaces the record's password
+            let temporary_password = record
+                .temporary_password
+                .then(domain::PasswordHash::temporary_password);
+            if let Some(temporary_password) = &temporary_password {
+                record.password = temporary_password.expose_secret().to_string();
+            }
+
+            // Validate the record using the same conversion as the create
+            // endpoint, a full hashing queue fails the import rather than the row
+            let user: database::Users = match self
+                .password_hasher
+                .spawn(move || record.try_into())
+                .await
+            {
+                Ok(user) => user,
+                Err(e @ AuthenticationError::PasswordHashQueueFull) => return Err(e.into()),
+                Err(e) => {
+                    tracing::warn!("Import row {row} failed validation: {e}");
+                    failures.push(ImportUserFailure {
+                        row,
+                        email,
+                        reason: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            // Insert the user and queue the registration event, and any
+            // temporary password email, in one transaction. Duplicate emails
+            // fail on the unique constraint.
+            let event = AuthEvent::new(AuthEventKind::Registration).user(user.id);
+            let inserted = async {
+                let temporary_password_email = match &temporary_password {
+                    Some(temporary_password) => {
+                        let mut context = tera::Context::new();
+                        context.insert("name", user.name.as_ref());
+                        context.insert("temporary_password", temporary_password.expose_secret());
+                        Some(templates.render(
+                            EmailTemplate::TemporaryPassword,
+                            &user.email,
+                            &user.locale,
+                            &context,
+                        )?)
+                    }
+                    None => None,
+                };
+
+                let mut transaction = self.database.begin().await?;
+                let user = user.insert(&mut *transaction).await?;
+                if let Some(email) = temporary_password_email {
+                    database::Users::set_password_state(
+                        &user.id,
+                        database::PasswordState::Temporary,
+                        &mut *transaction,
+                    )
+                    .await?;
+                    database::Outbox::new(email)
+                        .insert(&mut *transaction)
+                        .await?;
+                }
+                database::Outbox::new(event.clone())
+                    .insert(&mut *transaction)
+                    .await?;
+                transaction.commit().await?;
+                Ok::<_, AuthenticationError>(user)
+            }
+            .await;
+
+            match inserted {
+                Ok(_user) => {
+                    imported += 1;
+                    self.events.publish(event);
+                }
+                Err(e) => {
+                    tracing::warn!("Import row {row} failed to insert: {e}");
+                    failures.push(ImportUserFailure {
+                        row,
+                        email,
+                        reason: e.to_string(),
+                    });
+                }
+            }
+        }
+
+        tracing::info!(
+            "User import finished, {imported} imported and {} failed",
+            failures.len()
+        );
+
+        let response_message = ImportUsersResponse {
+            imported,
+            failed: failures.len() as u64,
+            failures,
+        };
+
+        Ok(Response::new(response_message))
+    }
+
+    type ExportUsersStream = ReceiverStream<Result<ExportUsersResponse, Status>>;
+
+    /// Handle server streaming requests to export all users, one line per message.
+    ///
+    /// Users are streamed from the database as the client reads them, so the
+    /// export is never held in memory.
+    #[tracing::instrument(name = "Export Users Request: ", skip(self, request))]
+    async fn export_users(
+        &self,
+        request: Request<ExportUsersRequest>,
+    ) -> Result<Response<Self::ExportUsersStream>, Status> {
+        let request_message = request.into_inner();
+
+        let format: ExportFormat = request_message
+            .format
+            .parse()
+            .map_err(|_| Status::invalid_argument("Invalid export format"))?;
+
+        let database = Arc::clone(&self.database);
+        let (sender, receiver) = tokio::sync::mpsc::channel(EXPORT_CHANNEL_SIZE);
+
+        tokio::spawn(async move {
+            if format == ExportFormat::Csv {
+                let header = ExportUsersResponse {
+                    line: EXPORT_CSV_HEADER.to_string(),
+                };
+                if sender.send(Ok(header)).await.is_err() {
+                    return;
+                }
+            }
+
+            // Rows are read as the channel drains, never buffered in memory
+            let users = database::Users::fetch_stream(&database);
+            tokio::pin!(users);
+
+            // The export can take as long as the client reads, but each row
+            // has the interactive budget
+            loop {
+                let user = with_timeout(
+                    QueryBudget::Interactive,
+                    "users.fetch_stream",
+                    async { users.next().await.transpose() },
+                )
+                .await;
+                let line = match user {
+                    Ok(None) => return,
+                    Ok(Some(user)) => ExportUserRow::from(user)
+                        .to_line(format)
+                        .map_err(Status::from)
+                        .map(|line| ExportUsersResponse { line }),
+                    Err(e) => {
+                        tracing::error!("User export failed reading the database: {e}");
+                        let _ = sender.send(Err(e.into())).await;
+                        return;
+                    }
+                };
+
+                // The client has gone away, so stop reading the database
+                if sender.send(line).await.is_err() {
+                    return;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+
+    type WatchAuthEventsStream =
+        std::pin::Pin<Box<dyn Stream<Item = Result<AuthEventResponse, Status>> + Send>>;
+
+    /// Handle server streaming requests to watch authentication events in real time.
+    ///
+    /// Only events published after the subscription are sent. An empty `kinds`
+    /// filter streams every event. Subscribers that fall behind skip the
+    /// events they missed rather than slowing down logins.
+    #[tracing::instrument(name = "Watch Auth Events Request: ", skip(self, request))]
+    async fn watch_auth_events(
+        &self,
+        request: Request<WatchAuthEventsRequest>,
+    ) -> Result<Response<Self::WatchAuthEventsStream>, Status> {
+        let request_message = request.into_inner();
+
+        let kinds = request_message
+            .kinds
+            .iter()
+            .map(|kind| kind.parse::<AuthEventKind>())
+            .collect::<Result<Vec<AuthEventKind>, AuthenticationError>>()
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        let stream = BroadcastStream::new(self.events.subscribe()).filter_map(
+            move |event| match event {
+                Ok(event) if kinds.is_empty() || kinds.contains(&event.kind) => {
+                    Some(Ok(AuthEventResponse::from(event)))
+                }
+                Ok(_) => None,
+                Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                    tracing::warn!("Auth event subscriber lagged, {skipped} events skipped");
+                    None
+                }
+            },
+        );
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    /// Create a service account API key scoped to a role.
+    ///
+    /// The key is only returned in this response, the database keeps its hash.
+    #[tracing::instrument(name = "Create API Key Request: ", skip(self, request))]
+    async fn create_api_key(
+        &self,
+        request: Request<CreateApiKeyRequest>,
+    ) -> Result<Response<CreateApiKeyResponse>, Status> {
+        let request_message = request.into_inner();
+
+        let name = request_message.name.trim();
+        if name.is_empty() {
+            return Err(Status::invalid_argument("API key name is required"));
+        }
+
+        let role = request_message
+            .role
+            .parse::<domain::UserRole>()
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        let expires_on = request_message
+            .expires_in_days
+            .map(|days| Utc::now() + Duration::days(days.into()));
+
+        // Generate the key and keep only its hash
+        let api_key = domain::ApiKey::generate();
+        let record = database::ApiKeys::new(name, &role, &api_key, expires_on)
+            .insert(self.database_ref())
+            .await?;
+
+        // The key can be used straight away
+        self.api_keys.insert(&record);
+        tracing::info!("API key created: {}", record.id);
+
+        let response_message = CreateApiKeyResponse {
+            api_key: Some(record.into()),
+            secret: api_key.expose().to_string(),
+        };
+
+        Ok(Response::new(response_message))
+    }
+
+    /// Page through API keys, newest first. The keys themselves are never returned.
+    #[tracing::instrument(name = "List API Keys Request: ", skip(self, request))]
+    async fn list_api_keys(
+        &self,
+        request: Request<ApiKeyIndexRequest>,
+    ) -> Result<Response<ApiKeyIndexResponse>, Status> {
+        validation::validate(&request)?;
+
+        let request_message = request.into_inner();
+
+        let offset: usize = request_message
+            .offset
+            .try_into()
+            .map_err(|_| Status::invalid_argument("Invalid offset value"))?;
+
+        let limit: usize = request_message
+            .limit
+            .try_into()
+            .map_err(|_| Status::invalid_argument("Invalid limit value"))?;
+
+        let database_records =
+            database::ApiKeys::index(&limit, &offset, self.database_ref()).await?;
+
+        let response_message = ApiKeyIndexResponse {
+            api_keys: database_records.into_iter().map(|api_key| api_key.into()).collect(),
+        };
+
+        Ok(Response::new(response_message))
+    }
+
+    /// Revoke an API key, so requests presenting it are rejected.
+    #[tracing::instrument(name = "Revoke API Key Request: ", skip(self, request))]
+    async fn revoke_api_key(
+        &self,
+        request: Request<RevokeApiKeyRequest>,
+    ) -> Result<Response<RevokeApiKeyResponse>, Status> {
+        validation::validate(&request)?;
+
+        let request_message = request.into_inner();
+
+        let id = Uuid::parse_str(&request_message.id)
+            .map_err(|_| Status::invalid_argument("Invalid API key id"))?;
+
+        let rows_affected = database::ApiKeys::revoke_by_id(&id, self.database_ref()).await?;
+
+        // Stop the key authenticating, even if it was already revoked
+        self.api_keys.remove(&id);
+
+        if rows_affected == 0 {
+            return Err(Status::not_found("API key not found or already revoked"));
+        }
+        tracing::info!("API key revoked: {id}");
+
+        let response_message = RevokeApiKeyResponse {
+            success: true,
+            message: "API key revoked".to_string(),
+        };
+
+        Ok(Response::new(response_message))
+    }
+
+    /// Register an OAuth client, either `confidential` or `public`, with its
+    /// redirect URIs, allowed grant types and scopes.
+    ///
+    /// A confidential client's secret is only returned in this response, the
+    /// database keeps its hash. Public clients have no secret.
+    #[tracing::instrument(name = "Register Client Request: ", skip(self, request))]
+    async fn register_client(
+        &self,
+        request: Request<RegisterClientRequest>,
+    ) -> Result<Response<RegisterClientResponse>, Status> {
+        let request_message = request.into_inner();
+
+        let client_type = match request_message.client_type.as_str() {
+            "confidential" => database::ClientType::Confidential,
+            "public" => database::ClientType::Public,
+            _ => {
+                return Err(Status::invalid_argument(
+                    "Client type must be confidential or public",
+                ))
+            }
+        };
+
+        let (name, grant_types) = validate_client_registration(
+            &request_message.name,
+            client_type,
+            &request_message.redirect_uris,
+            &request_message.grant_types,
+            &request_message.scopes,
+        )?;
+
+        // Generate a secret for confidential clients and keep only its hash
+        let (client, secret) = match client_type {
+            database::ClientType::Confidential => {
+                let secret = domain::ClientSecret::generate();
+                let client = database::Clients::new_confidential(
+                    name,
+                    &secret,
+                    &request_message.redirect_uris,
+                    &grant_types,
+                    &request_message.scopes,
+                );
+                (client, Some(secret))
+            }
+            database::ClientType::Public => {
+                let client = database::Clients::new_public(
+                    name,
+                    &request_message.redirect_uris,
+                    &grant_types,
+                    &request_message.scopes,
+                );
+                (client, None)
+            }
+        };
+        let record = client.insert(self.database_ref()).await?;
+        tracing::info!("OAuth client registered: {} ({})", record.id, record.client_type);
+
+        let response_message = RegisterClientResponse {
+            client: Some(record.into()),
+            client_secret: secret.map(|secret| secret.expose().to_string()),
+        };
+
+        Ok(Response::new(response_message))
+    }
+
+    /// Page through OAuth clients, newest first. The secrets are never returned.
+    #[tracing::instrument(name = "List Clients Request: ", skip(self, request))]
+    async fn list_clients(
+        &self,
+        request: Request<ClientIndexRequest>,
+    ) -> Result<Response<ClientIndexResponse>, Status> {
+        validation::validate(&request)?;
+
+        let request_message = request.into_inner();
+
+        let offset: usize = request_message
+            .offset
+            .try_into()
+            .map_err(|_| Status::invalid_argument("Invalid offset value"))?;
+
+        let limit: usize = request_message
+            .limit
+            .try_into()
+            .map_err(|_| Status::invalid_argument("Invalid limit value"))?;
+
+        let database_records =
+            database::Clients::index(&limit, &offset, self.database_ref()).await?;
+
+        let response_message = ClientIndexResponse {
+            clients: database_records.into_iter().map(|client| client.into()).collect(),
+        };
+
+        Ok(Response::new(response_message))
+    }
+
+    /// Change an OAuth client's name, redirect URIs, grant types and scopes.
+    /// The client type and secret can't be changed, register a new client
+    /// instead. Tokens already issued keep their scopes until they expire.
+    #[tracing::instrument(name = "Update Client Request: ", skip(self, request))]
+    async fn update_client(
+        &self,
+        request: Request<UpdateClientRequest>,
+    ) -> Result<Response<ClientResponse>, Status> {
+        validation::validate(&request)?;
+
+        let request_message = request.into_inner();
+
+        let id = Uuid::parse_str(&request_message.client_id)
+            .map_err(|_| Status::invalid_argument("Invalid client id"))?;
+
+        let mut client = database::Clients::from_id(&id, self.database_ref())
+            .await
+            .map_err(|_| Status::not_found("Client not found"))?;
+        if !client.is_usable() {
+            return Err(Status::failed_precondition("Client is revoked"));
+        }
+
+        let (name, grant_types) = validate_client_registration(
+            &request_message.name,
+            client.client_type,
+            &request_message.redirect_uris,
+            &request_message.grant_types,
+            &request_message.scopes,
+        )?;
+
+        client.name = name.to_string();
+        client.redirect_uris = request_message.redirect_uris;
+        client.grant_types = grant_types.iter().map(|grant| grant.to_string()).collect();
+        client.scopes = request_message.scopes;
+
+        let record = client.update(self.database_ref()).await?;
+        tracing::info!("OAuth client updated: {}", record.id);
+
+        Ok(Response::new(record.into()))
+    }
+
+    /// Revoke an OAuth client, so it can no longer get access tokens. Tokens
+    /// already issued last until they expire.
+    #[tracing::instrument(name = "Revoke Client Request: ", skip(self, request))]
+    async fn revoke_client(
+        &self,
+        request: Request<RevokeClientRequest>,
+    ) -> Result<Response<RevokeClientResponse>, Status> {
+        validation::validate(&request)?;
+
+        let request_message = request.into_inner();
+
+        let id = Uuid::parse_str(&request_message.client_id)
+            .map_err(|_| Status::invalid_argument("Invalid client id"))?;
+
+        let rows_affected = database::Clients::revoke_by_id(&id, self.database_ref()).await?;
+
+        if rows_affected == 0 {
+            return Err(Status::not_found("Client not found or already revoked"));
+        }
+        tracing::info!("OAuth client revoked: {id}");
+
+        let response_message = RevokeClientResponse {
+            success: true,
+            message: "Client revoked".to_string(),
+        };
+
+        Ok(Response::new(response_message))
+    }
+
+    /// Create an organization (tenant). The slug must be unique.
+    #[tracing::instrument(name = "Create Organization Request: ", skip(self, request))]
+    async fn create_organization(
+        &self,
+        request: Request<CreateOrganizationRequest>,
+    ) -> Result<Response<OrganizationResponse>, Status> {
+        let request_message = request.into_inner();
+
+        let organization =
+            database::Organizations::new(&request_message.name, &request_message.slug)
+                .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        let organization = organization
+            .insert(self.database_ref())
+            .await
+            .map_err(|_| Status::already_exists("Organization slug is already in use"))?;
+        tracing::info!("Organization created: {}", organization.id);
+
+        Ok(Response::new(organization.into()))
+    }
+
+    /// Allow or block an email domain, and its subdomains, from registering,
+    /// replacing the domain's rule if it has one. Once any domain is allowed,
+    /// only allowed domains can register.
+    #[tracing::instrument(name = "Set Email Domain Rule Request: ", skip(self, request))]
+    async fn set_email_domain_rule(
+        &self,
+        request: Request<SetEmailDomainRuleRequest>,
+    ) -> Result<Response<EmailDomainRuleResponse>, Status> {
+        let request_message = request.into_inner();
+
+        let email_domain = domain::EmailDomain::parse(&request_message.domain)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let rule = request_message
+            .rule
+            .parse::<database::EmailDomainRule>()
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        let email_domain = database::EmailDomains::new(&email_domain, rule)
+            .upsert(self.database_ref())
+            .await?;
+        tracing::info!("Email domain {} rule set: {}", email_domain.domain, email_domain.rule);
+
+        Ok(Response::new(email_domain.into()))
+    }
+
+    /// List the email domain rules set by admins, in domain order. The
+    /// domains in the `email_domains` configuration are not included.
+    #[tracing::instrument(name = "List Email Domain Rules Request: ", skip(self, _request))]
+    async fn list_email_domain_rules(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<ListEmailDomainRulesResponse>, Status> {
+        let rules = database::EmailDomains::index(self.database_ref())
+            .await?
+            .into_iter()
+            .map(EmailDomainRuleResponse::from)
+            .collect();
+
+        Ok(Response::new(ListEmailDomainRulesResponse { rules }))
+    }
+
+    /// Remove an email domain's rule. Rules for its subdomains are kept.
+    #[tracing::instrument(name = "Delete Email Domain Rule Request: ", skip(self, request))]
+    async fn delete_email_domain_rule(
+        &self,
+        request: Request<DeleteEmailDomainRuleRequest>,
+    ) -> Result<Response<DeleteEmailDomainRuleResponse>, Status> {
+        let request_message = request.into_inner();
+
+        let email_domain = domain::EmailDomain::parse(&request_message.domain)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        let rows_affected =
+            database::EmailDomains::delete(&email_domain, self.database_ref()).await?;
+        if rows_affected == 0 {
+            return Err(Status::not_found("Email domain has no rule"));
+        }
+        tracing::info!("Email domain rule deleted: {email_domain}");
+
+        Ok(Response::new(DeleteEmailDomainRuleResponse { rows_affected }))
+    }
+
+    /// Add a user to an organization with a role, updating the role if they are
+    /// already a member.
+    #[tracing::instrument(name = "Add Organization Member Request: ", skip(self, request))]
+    async fn add_organization_member(
+        &self,
+        request: Request<AddOrganizationMemberRequest>,
+    ) -> Result<Response<OrganizationMemberResponse>, Status> {
+        validation::validate(&request)?;
+
+        let request_message = request.into_inner();
+
+        let organization_id = Uuid::parse_str(&request_message.organization_id)
+            .map_err(|_| Status::invalid_argument("Invalid organization id"))?;
+
+        let user_id = Uuid::parse_str(&request_message.user_id)
+            .map_err(|_| Status::invalid_argument("Invalid user id"))?;
+
+        let role = request_message
+            .role
+            .parse::<domain::UserRole>()
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        let member = database::OrganizationMembers::new(&organization_id, &user_id, &role)
+            .insert(self.database_ref())
+            .await
+            .map_err(|_| Status::not_found("Organization or user not found"))?;
+        tracing::info!("User {user_id} added to organization {organization_id} as {role}");
+
+        Ok(Response::new(member.into()))
+    }
+
+    /// Create a webhook endpoint subscribed to some (or, if none are given, all)
+    /// webhook event types.
+    ///
+    /// The signing secret is only returned in this response.
+    #[tracing::instrument(name = "Create Webhook Endpoint Request: ", skip(self, request))]
+    async fn create_webhook_endpoint(
+        &self,
+        request: Request<CreateWebhookEndpointRequest>,
+    ) -> Result<Response<CreateWebhookEndpointResponse>, Status> {
+        let request_message = request.into_inner();
+
+        let url = request_message.url.trim();
+        let is_http = reqwest::Url::parse(url)
+            .is_ok_and(|url| matches!(url.scheme(), "http" | "https"));
+        if !is_http {
+            return Err(Status::invalid_argument("Webhook url must be an http(s) url"));
+        }
+
+        if let Some(unknown) = request_message
+            .event_types
+            .iter()
+            .find(|event_type| !WEBHOOK_EVENT_TYPES.contains(&event_type.as_str()))
+        {
+            return Err(Status::invalid_argument(format!(
+                "{unknown} is not a webhook event type, use one of {}",
+                WEBHOOK_EVENT_TYPES.join(", ")
+            )));
+        }
+
+        let endpoint = database::WebhookEndpoints::new(url, &request_message.event_types)
+            .insert(self.database_ref())
+            .await?;
+        tracing::info!("Webhook endpoint created: {}", endpoint.id);
+
+        let secret = endpoint.secret.clone();
+        let response_message = CreateWebhookEndpointResponse {
+            endpoint: Some(endpoint.into()),
+            secret,
+        };
+
+        Ok(Response::new(response_message))
+    }
+
+    /// Page through webhook endpoints, newest first. Secrets are never returned.
+    #[tracing::instrument(name = "List Webhook Endpoints Request: ", skip(self, request))]
+    async fn list_webhook_endpoints(
+        &self,
+        request: Request<WebhookEndpointIndexRequest>,
+    ) -> Result<Response<WebhookEndpointIndexResponse>, Status> {
+        validation::validate(&request)?;
+
+        let request_message = request.into_inner();
+
+        let offset: usize = request_message
+            .offset
+            .try_into()
+            .map_err(|_| Status::invalid_argument("Invalid offset value"))?;
+
+        let limit: usize = request_message
+            .limit
+            .try_into()
+            .map_err(|_| Status::invalid_argument("Invalid limit value"))?;
+
+        let database_records =
+            database::WebhookEndpoints::index(&limit, &offset, self.database_ref()).await?;
+
+        let response_message = WebhookEndpointIndexResponse {
+            endpoints: database_records.into_iter().map(|endpoint| endpoint.into()).collect(),
+        };
+
+        Ok(Response::new(response_message))
+    }
+
+    /// Delete a webhook endpoint. Queued deliveries to it are dropped.
+    #[tracing::instrument(name = "Delete Webhook Endpoint Request: ", skip(self, request))]
+    async fn delete_webhook_endpoint(
+        &self,
+        request: Request<DeleteWebhookEndpointRequest>,
+    ) -> Result<Response<DeleteWebhookEndpointResponse>, Status> {
+        validation::validate(&request)?;
+
+        let request_message = request.into_inner();
+
+        let id = Uuid::parse_str(&request_message.id)
+            .map_err(|_| Status::invalid_argument("Invalid webhook endpoint id"))?;
+
+        let rows_affected =
+            database::WebhookEndpoints::delete_by_id(&id, self.database_ref()).await?;
+
+        if rows_affected == 0 {
+            return Err(Status::not_found("Webhook endpoint not found"));
+        }
+        tracing::info!("Webhook endpoint deleted: {id}");
+
+        let response_message = DeleteWebhookEndpointResponse {
+            success: true,
+            message: "Webhook endpoint deleted".to_string(),
+        };
+
+        Ok(Response::new(response_message))
+    }
+
+    /// Page through the delivery attempts for a webhook endpoint, newest first.
+    #[tracing::instrument(name = "List Webhook Deliveries Request: ", skip(self, request))]
+    async fn list_webhook_deliveries(
+        &self,
+        request: Request<WebhookDeliveryIndexRequest>,
+    ) -> Result<Response<WebhookDeliveryIndexResponse>, Status> {
+        validation::validate(&request)?;
+
+        let request_message = request.into_inner();
+
+        let endpoint_id = Uuid::parse_str(&request_message.endpoint_id)
+            .map_err(|_| Status::invalid_argument("Invalid webhook endpoint id"))?;
+
+        let offset: usize = request_message
+            .offset
+            .try_into()
+            .map_err(|_| Status::invalid_argument("Invalid offset value"))?;
+
+        let limit: usize = request_message
+            .limit
+            .try_into()
+            .map_err(|_| Status::invalid_argument("Invalid limit value"))?;
+
+        let database_records = database::WebhookDeliveries::index_endpoint(
+            &endpoint_id,
+            &limit,
+            &offset,
+            self.database_ref(),
+        )
+        .await?;
+
+        let response_message = WebhookDeliveryIndexResponse {
+            deliveries: database_records.into_iter().map(|delivery| delivery.into()).collect(),
+        };
+
+        Ok(Response::new(response_message))
+    }
+
+    /// Start changing a user's email address.
+    ///
+    /// A token is emailed to both the current and new addresses, and the email
+    /// is only changed once both are confirmed (see `confirm_email_change` in
+    /// the authentication service). Any earlier pending change for the user is
+    /// replaced.
+    #[tracing::instrument(name = "Request Email Change Request: ", skip(self, request))]
+    async fn request_email_change(
+        &self,
+        request: Request<RequestEmailChangeRequest>,
+    ) -> Result<Response<RequestEmailChangeResponse>, Status> {
+        validation::validate(&request)?;
+
+        // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
+        let (_request_metadata, request_extensions, request_message) =
+            request.into_parts();
+
+        let user_id = Uuid::parse_str(&request_message.user_id)
+            .map_err(|_| Status::invalid_argument("Invalid user id"))?;
+
+        let new_email = domain::EmailAddress::parse(&request_message.new_email)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        let user = database::Users::from_user_id(&user_id, self.database_ref())
+            .await
+            .map_err(|_| Status::not_found("User not found"))?;
+
+        if user.email == new_email {
+            return Err(Status::invalid_argument(
+                "New email is the same as the current email",
+            ));
+        }
+
+        if database::Users::exists_by_email(&new_email, self.database_ref())
+            .await?
+        {
+            return Err(Status::already_exists("Email is already in use"));
+        }
+
+        // Admins using an access token are recorded, API keys have no user
+        let requested_by = request_extensions
+            .get::<domain::AuthContext>()
+            .map(|auth_context| auth_context.user_id);
+
+        let config = self.config_ref();
+        let templates = EmailTemplates::new(&config.email)?;
+
+        let token_secret = &config.application.token_secret;
+        let old_token =
+            domain::ActionToken::generate(domain::ActionPurpose::EmailChange, token_secret);
+        let new_token =
+            domain::ActionToken::generate(domain::ActionPurpose::EmailChange, token_secret);
+        let email_change = database::EmailChanges::new(
+            &user.id,
+            &new_email,
+            &old_token,
+            &new_token,
+            requested_by,
+            &std::time::Duration::from_secs(EMAIL_CHANGE_EXPIRY_HOURS * 60 * 60),
+        );
+
+        // Render a confirmation email for each address
+        let mut emails = Vec::with_capacity(2);
+        for (to, token, is_new_address) in [
+            (&user.email, &old_token, false),
+            (&new_email, &new_token, true),
+        ] {
+            let mut context = tera::Context::new();
+            context.insert("name", user.name.as_ref());
+            context.insert("token", token.expose());
+            context.insert("new_email", new_email.as_ref());
+            context.insert("is_new_address", &is_new_address);
+            context.insert("expires_in_hours", &EMAIL_CHANGE_EXPIRY_HOURS);
+            emails.push(templates.render(
+                EmailTemplate::EmailChange,
+                to,
+                &user.locale,
+                &context,
+            )?);
+        }
+
+        // Replace any pending change and queue both emails in one transaction
+        let mut transaction = self.database.begin().await?;
+        database::EmailChanges::delete_pending_for_user(&user.id, &mut *transaction)
+            .await?;
+        let email_change = email_change.insert(&mut *transaction).await?;
+        for email in emails {
+            database::Outbox::new(email)
+                .insert(&mut *transaction)
+                .await?;
+        }
+        transaction.commit().await?;
+
+        tracing::info!("Email change requested for user {}", user.id);
+
+        let response_message = RequestEmailChangeResponse {
+            id: email_change.id.to_string(),
+            expires_on: email_change.expires_on.to_rfc3339(),
+        };
+
+        Ok(Response::new(response_message))
+    }
+
+    /// Issue a short lived access token for a user, so an admin can see what
+    /// they see. The token is the user's, marked with the admin in its `act`
+    /// claim, and has no refresh token.
+    ///
+    /// The admin must use their own access token and give a reason. Admins
+    /// can't be impersonated. Each impersonation is recorded, and announced
+    /// as a `user.impersonated` event, in the same transaction.
+    #[tracing::instrument(name = "Impersonate User Request: ", skip(self, request))]
+    async fn impersonate_user(
+        &self,
+        request: Request<ImpersonateUserRequest>,
+    ) -> Result<Response<ImpersonateUserResponse>, Status> {
+        validation::validate(&request)?;
+
+        // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
+        let (_request_metadata, request_extensions, request_message) =
+            request.into_parts();
+
+        let config = self.config_ref();
+        if !config.impersonation.enabled {
+            return Err(Status::unimplemented("Impersonation is not enabled"));
+        }
+
+        // API keys have no user to hold to account, so an admin access token is required
+        let admin_claim = request_extensions
+            .get::<domain::TokenClaim>()
+            .ok_or_else(|| {
+                Status::permission_denied("Impersonation needs an admin access token")
+            })?;
+        if !admin_claim.has_permission(domain::UserRole::USERS_IMPERSONATE)
+            || admin_claim.impersonator_id()?.is_some()
+        {
+            return Err(Status::permission_denied("Not allowed to impersonate users"));
+        }
+        let admin_id = domain::AuthContext::from_extensions(&request_extensions)?.user_id;
+
+        let user_id = Uuid::parse_str(&request_message.user_id)
+            .map_err(|_| Status::invalid_argument("Invalid user id"))?;
+        if user_id == admin_id {
+            return Err(Status::invalid_argument("Admins can't impersonate themselves"));
+        }
+
+        let reason = validate_impersonation_reason(
+            &request_message.reason,
+            config.impersonation.min_reason_length,
+        )?;
+
+        let user = database::Users::from_user_id(&user_id, self.database_ref())
+            .await
+            .map_err(|_| Status::not_found("User not found"))?;
+        if user.role == domain::UserRole::Admin {
+            return Err(Status::permission_denied("Admins can't be impersonated"));
+        }
+        if !user.is_active {
+            return Err(Status::failed_precondition("User is not active"));
+        }
+
+        let duration = std::time::Duration::from_secs(
+            config.impersonation.access_token_duration_minutes * 60,
+        );
+        let token_secret = &config.application.token_secret;
+        let access_token = domain::AccessToken::new_impersonation(
+            token_secret,
+            &config.application.get_issuer(),
+            &duration,
+            &user,
+            &admin_id,
+            &config.application.token_audiences,
+        )?;
+
+        // Read back the token id, so the token can be denied on revoke
+        let access_token_claim = domain::TokenClaim::parse_with_audiences(
+            access_token.as_ref(),
+            token_secret,
+            &config.application.get_issuer(),
+            &config.application.token_audiences,
+        )?;
+        let access_token_id = Uuid::parse_str(&access_token_claim.jti)
+            .map_err(|_| Status::internal("Invalid access token id"))?;
+        let expires_on = DateTime::from_timestamp(access_token_claim.exp as i64, 0)
+            .ok_or_else(|| Status::internal("Invalid access token expiry"))?;
+
+        // Record the impersonation and queue its event in one transaction
+        let impersonation = database::Impersonations::new(
+            &admin_id,
+            &user.id,
+            reason,
+            &access_token_id,
+            &expires_on,
+        );
+        let event = AuthEvent::new(AuthEventKind::Impersonation).user(user.id);
+        let mut transaction = self.database.begin().await?;
+        let impersonation = impersonation.insert(&mut *transaction).await?;
+        database::Outbox::new(event.clone())
+            .insert(&mut *transaction)
+            .await?;
+        transaction.commit().await?;
+        self.events.publish(event);
+
+        tracing::warn!(
+            "Admin {admin_id} is impersonating user {} until {}: {reason}",
+            user.id,
+            impersonation.expires_on,
+        );
+
+        let response_message = ImpersonateUserResponse {
+            impersonation: Some(impersonation.into()),
+            access_token: access_token.to_string(),
+        };
+
+        Ok(Response::new(response_message))
+    }
+
+    /// Page through the impersonation audit trail, newest first.
+    #[tracing::instrument(name = "List Impersonations Request: ", skip(self, request))]
+    async fn list_impersonations(
+        &self,
+        request: Request<ImpersonationIndexRequest>,
+    ) -> Result<Response<ImpersonationIndexResponse>, Status> {
+        validation::validate(&request)?;
+
+        let request_message = request.into_inner();
+
+        let offset: usize = request_message
+            .offset
+            .try_into()
+            .map_err(|_| Status::invalid_argument("Invalid offset value"))?;
+
+        let limit: usize = request_message
+            .limit
+            .try_into()
+            .map_err(|_| Status::invalid_argument("Invalid limit value"))?;
+
+        let database_records =
+            database::Impersonations::index(&limit, &offset, self.database_ref()).await?;
+
+        let response_message = ImpersonationIndexResponse {
+            impersonations: database_records
+                .into_iter()
+                .map(|impersonation| impersonation.into())
+                .collect(),
+        };
+
+        Ok(Response::new(response_message))
+    }
+
+    /// Revoke an impersonation, denying its access token on every instance
+    /// before it expires.
+    #[tracing::instrument(name = "Revoke Impersonation Request: ", skip(self, request))]
+    async fn revoke_impersonation(
+        &self,
+        request: Request<RevokeImpersonationRequest>,
+    ) -> Result<Response<RevokeImpersonationResponse>, Status> {
+        validation::validate(&request)?;
+
+        // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
+        let (_request_metadata, request_extensions, request_message) =
+            request.into_parts();
+
+        let id = Uuid::parse_str(&request_message.impersonation_id)
+            .map_err(|_| Status::invalid_argument("Invalid impersonation id"))?;
+
+        // Admins using an access token are recorded, API keys have no user
+        let revoked_by = request_extensions
+            .get::<domain::AuthContext>()
+            .map(|auth_context| auth_context.user_id);
+
+        let impersonation =
+            database::Impersonations::revoke(&id, revoked_by.as_ref(), self.database_ref())
+                .await?
+                .ok_or_else(|| {
+                    Status::not_found("Impersonation not found or already revoked")
+                })?;
+
+        // Deny the token, the denylist is reloaded from the database on start up
+        let entry = database::AccessTokenDenylist::new(
+            &impersonation.access_token_id.to_string(),
+            &impersonation.user_id,
+            &impersonation.expires_on,
+        );
+        entry.insert(self.database_ref()).await?;
+        self.denylist.deny(&entry.jti, entry.expires_on);
+
+        tracing::warn!(
+            "Impersonation of user {} by admin {} revoked",
+            impersonation.user_id,
+            impersonation.admin_id,
+        );
+
+        let response_message = RevokeImpersonationResponse {
+            success: true,
+            message: "Impersonation revoked".to_string(),
+        };
+
+        Ok(Response::new(response_message))
+    }
+
+    /// Make a user change their password. Their sessions are revoked, their
+    /// logins fail with `PASSWORD_CHANGE_REQUIRED` and they are emailed a
+    /// reset link. Completing the reset lets them log in again.
+    #[tracing::instrument(name = "Admin Force Password Reset Request: ", skip(self, request))]
+    async fn force_password_reset(
+        &self,
+        request: Request<ForcePasswordResetRequest>,
+    ) -> Result<Response<ForcePasswordResetResponse>, Status> {
+        validation::validate(&request)?;
+
+        let request_message = request.into_inner();
+
+        let config = self.config_ref();
+
+        let id = Uuid::parse_str(&request_message.user_id)
+            .map_err(|_| Status::invalid_argument("Invalid user id"))?;
+
+        let user = database::Users::from_user_id(&id, self.database_ref())
+            .await
+            .map_err(|_| Status::not_found("User not found"))?;
+
+        // Deny the access tokens of the sessions being revoked, the denylist
+        // is reloaded from the database on start up
+        let access_token_expires_on = Utc::now()
+            + Duration::minutes(config.application.access_token_duration_minutes as i64);
+        let denied = database::AccessTokenDenylist::insert_for_sessions(
+            &id,
+            None,
+            &access_token_expires_on,
+            self.database_ref(),
+        )
+        .await?;
+        for entry in &denied {
+            self.denylist.deny(&entry.jti, entry.expires_on);
+        }
+
+        // Flag the user, revoke their sessions, email the link and queue the
+        // event in one transaction
+        let mut transaction = self.database.begin().await?;
+        database::Users::set_password_state(
+            &id,
+            database::PasswordState::ChangeRequired,
+            &mut *transaction,
+        )
+        .await?;
+        let sessions_revoked =
+            database::Sessions::revoke_user_id(&id, &mut *transaction).await? as u64;
+        password_reset::issue_password_reset(&mut *transaction, &config, &user, true).await?;
+
+        let event = AuthEvent::new(AuthEventKind::Revocation)
+            .user(id)
+            .sessions_affected(sessions_revoked);
+        database::Outbox::new(event.clone())
+            .insert(&mut *transaction)
+            .await?;
+        transaction.commit().await?;
+        self.events.publish(event);
+
+        tracing::warn!("Password reset forced for user: {id}");
+
+        let response_message = ForcePasswordResetResponse {
+            success: true,
+            sessions_revoked,
+        };
+
+        Ok(Response::new(response_message))
+    }
+
+    /// Soft delete a user and revoke their sessions. The user can no longer
+    /// log in, and can be restored until purged.
+    #[tracing::instrument(name = "Admin Delete User Request: ", skip(self, request))]
+    async fn delete_user(
+        &self,
+        request: Request<DeleteUserRequest>,
+    ) -> Result<Response<DeleteUserResponse>, Status> {
+        validation::validate(&request)?;
+
+        let request_message = request.into_inner();
+
+        let id = Uuid::parse_str(&request_message.id)
+            .map_err(|_| Status::invalid_argument("Invalid user id"))?;
+
+        let user = database::Users::from_user_id(&id, self.database_ref())
+            .await
+            .map_err(|_| Status::not_found("User not found"))?;
+
+        // Delete the user and revoke their sessions in one transaction
+        let mut transaction = self.database.begin().await?;
+        let rows_affected = user.delete(&mut *transaction).await?;
+        database::Sessions::revoke_user_id(&id, &mut *transaction).await?;
+        transaction.commit().await?;
+
+        tracing::info!("User deleted: {id}");
+
+        Ok(Response::new(DeleteUserResponse { rows_affected }))
+    }
+
+    /// Restore a soft deleted user. Their sessions stay revoked, so they need
+    /// to log in again.
+    #[tracing::instrument(name = "Admin Restore User Request: ", skip(self, request))]
+    async fn restore_user(
+        &self,
+        request: Request<RestoreUserRequest>,
+    ) -> Result<Response<UserResponse>, Status> {
+        validation::validate(&request)?;
+
+        let request_message = request.into_inner();
+
+        let id = Uuid::parse_str(&request_message.id)
+            .map_err(|_| Status::invalid_argument("Invalid user id"))?;
+
+        let user = database::Users::restore(&id, self.database_ref())
+            .await
+            .map_err(|_| Status::not_found("Deleted user not found"))?;
+
+        tracing::info!("User restored: {id}");
+
+        Ok(Response::new(user.into()))
+    }
+
+    /// Merge a duplicate user into the account that is kept. Their sessions,
+    /// passkeys, linked identities and audit records are moved in one
+    /// statement, so a merge is never half done, and the duplicate is soft
+    /// deleted. Records the kept account already has, like a grant to the
+    /// same client, stay with the duplicate.
+    #[tracing::instrument(name = "Admin Merge Users Request: ", skip(self, request))]
+    async fn merge_users(
+        &self,
+        request: Request<MergeUsersRequest>,
+    ) -> Result<Response<MergeUsersResponse>, Status> {
+        validation::validate(&request)?;
+
+        let request_message = request.into_inner();
+
+        let source_id = Uuid::parse_str(&request_message.source_user_id)
+            .map_err(|_| Status::invalid_argument("Invalid source user id"))?;
+        let target_id = Uuid::parse_str(&request_message.target_user_id)
+            .map_err(|_| Status::invalid_argument("Invalid target user id"))?;
+        if source_id == target_id {
+            return Err(Status::invalid_argument("A user can't be merged into themselves"));
+        }
+
+        let source = database::Users::from_user_id(&source_id, self.database_ref())
+            .await
+            .map_err(|_| Status::not_found("Source user not found"))?;
+        let target = database::Users::from_user_id(&target_id, self.database_ref())
+            .await
+            .map_err(|_| Status::not_found("Target user not found"))?;
+
+        let merge = source.merge_into(&target.id, self.database_ref()).await?;
+
+        tracing::info!("User {source_id} merged into: {target_id}");
+
+        let response_message = MergeUsersResponse {
+            user: Some(target.into()),
+            sessions_moved: merge.sessions,
+            passkeys_moved: merge.passkeys,
+            identities_moved: merge.identities,
+            grants_moved: merge.grants,
+            logins_moved: merge.logins,
+            impersonations_moved: merge.impersonations,
+        };
+
+        Ok(Response::new(response_message))
+    }
+
+    /// Summarise the failed and throttled logins within the last
+    /// `window_minutes`, grouped by IP address and by account, most first.
+    /// The IP address and email pairs locked out now are listed, and flagged
+    /// in the groups, to support incident response.
+    #[tracing::instrument(name = "Admin Get Security Report Request: ", skip(self, request))]
+    async fn get_security_report(
+        &self,
+        request: Request<SecurityReportRequest>,
+    ) -> Result<Response<SecurityReportResponse>, Status> {
+        validation::validate(&request)?;
+
+        let request_message = request.into_inner();
+
+        let limit: usize = request_message
+            .limit
+            .try_into()
+            .map_err(|_| Status::invalid_argument("Invalid limit value"))?;
+
+        let generated_on = Utc::now();
+        let window_started_on =
+            generated_on - Duration::minutes(request_message.window_minutes as i64);
+
+        let by_ip_address =
+            database::Logins::failures_by_ip(&window_started_on, &limit, self.database_ref())
+                .await?;
+        let by_account =
+            database::Logins::failures_by_email(&window_started_on, &limit, self.database_ref())
+                .await?;
+        let locked = database::LoginThrottles::index_locked(&limit, self.database_ref()).await?;
+
+        // Throttles are keyed by the lower case email, as the accounts are grouped
+        let throttled_ip_addresses: HashSet<&str> =
+            locked.iter().map(|throttle| throttle.ip_address.as_str()).collect();
+        let locked_emails: HashSet<&str> =
+            locked.iter().map(|throttle| throttle.email.as_str()).collect();
+
+        let by_ip_address = by_ip_address
+            .into_iter()
+            .map(|failures| FailedLoginsByIpResponse {
+                is_throttled: throttled_ip_addresses.contains(failures.ip_address.as_str()),
+                ..failures.into()
+            })
+            .collect();
+        let by_account = by_account
+            .into_iter()
+            .map(|failures| FailedLoginsByAccountResponse {
+                is_locked: locked_emails.contains(failures.email.as_str()),
+                ..failures.into()
+            })
+            .collect();
+
+        let response_message = SecurityReportResponse {
+            window_started_on: window_started_on.to_string(),
+            generated_on: generated_on.to_string(),
+            by_ip_address,
+            by_account,
+            locked: locked.into_iter().map(|throttle| throttle.into()).collect(),
+        };
+
+        Ok(Response::new(response_message))
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    fn export_row() -> ExportUserRow {
+        ExportUserRow {
+            id: Uuid::nil(),
+            email: "someone@example.com".to_string(),
+            name: "Smith, \"Jo\"".to_string(),
+            role: "user".to_string(),
+            is_active: true,
+            is_verified: false,
+            created_on: DateTime::<Utc>::UNIX_EPOCH,
+        }
+    }
+
+    #[test]
+    fn export_format_parses_known_formats() -> Result<()> {
+        assert_eq!("".parse::<ExportFormat>()?, ExportFormat::Csv);
+        assert_eq!("CSV".parse::<ExportFormat>()?, ExportFormat::Csv);
+        assert_eq!("ndjson".parse::<ExportFormat>()?, ExportFormat::Ndjson);
+        assert!("xml".parse::<ExportFormat>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn csv_line_quotes_special_characters() -> Result<()> {
+        let line = export_row().to_line(ExportFormat::Csv)?;
+
+        assert_eq!(
+            line,
+            "00000000-0000-0000-0000-000000000000,someone@example.com,\"Smith, \"\"Jo\"\"\",user,true,false,1970-01-01T00:00:00+00:00"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn ndjson_line_has_no_password_hash() -> Result<()> {
+        let line = export_row().to_line(ExportFormat::Ndjson)?;
+        let value: serde_json::Value = serde_json::from_str(&line)?;
+
+        assert_eq!(value["email"], "someone@example.com");
+        assert!(value.get("password_hash").is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn redirect_uris_must_be_https_loopback_or_private_use() {
+        assert!(is_valid_redirect_uri("https://app.example.com/callback"));
+        assert!(is_valid_redirect_uri("http://127.0.0.1:8400/callback"));
+        assert!(is_valid_redirect_uri("com.example.app:/callback"));
+        assert!(!is_valid_redirect_uri("http://app.example.com/callback"));
+        assert!(!is_valid_redirect_uri("https://app.example.com/callback#token"));
+        assert!(!is_valid_redirect_uri("/callback"));
+        assert!(!is_valid_redirect_uri("javascript:alert(1)"));
+    }
+
+    #[test]
+    fn client_registrations_are_checked() -> Result<()> {
+        let scopes = vec!["profile".to_string()];
+        let redirect_uris = vec!["https://app.example.com/callback".to_string()];
+        let code = vec!["authorization_code".to_string()];
+        let client_credentials = vec!["client_credentials".to_string()];
+
+        let (name, grant_types) = validate_client_registration(
+            " Web App ",
+            database::ClientType::Public,
+            &redirect_uris,
+            &code,
+            &scopes,
+        )?;
+        assert_eq!(name, "Web App");
+        assert_eq!(grant_types, vec![domain::GrantType::AuthorizationCode]);
+
+        // Public clients have no secret for client credentials
+        assert!(validate_client_registration(
+            "Web App",
+            database::ClientType::Public,
+            &[],
+            &client_credentials,
+            &scopes,
+        )
+        .is_err());
+        // Authorization code needs somewhere to redirect to
+        assert!(validate_client_registration(
+            "Web App",
+            database::ClientType::Confidential,
+            &[],
+            &code,
+            &scopes,
+        )
+        .is_err());
+        assert!(validate_client_registration(
+            "Service",
+            database::ClientType::Confidential,
+            &[],
+            &["password".to_string()],
+            &scopes,
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn impersonation_reasons_must_meet_the_minimum_length() -> Result<()> {
+        assert_eq!(
+            validate_impersonation_reason("  Support ticket 42  ", 10)?,
+            "Support ticket 42"
+        );
+        assert!(validate_impersonation_reason("  debug     ", 10).is_err());
+        assert!(validate_impersonation_reason("", 1).is_err());
+
+        Ok(())
+    }
+}
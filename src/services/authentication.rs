@@ -10,12 +10,25 @@
 //!
 //! The implementation of the AuthenticationService struct contains the following
 //! services:
-//! - `authentication`: Authenticate a user using their email and password
+//! - `authentication`: Authenticate a user using their email and password, or
+//!   their LDAP directory password when `ldap` is enabled
 //! - `refresh`: Get a new Access Token using the Refresh Token that has a longer life
 //! - `update_password`: Update my password using the original password and new password
 //! - `reset_password`: Reset my password using the original password and new password
-//! - `register`: Register a new user
+//! - `register`: Register a new user, when their email domain can register
 //! - `logout`: Revoke all Sessions for the user in the database
+//! - `logout_other_sessions`: Revoke all of the user's Sessions except the current one
+//! - `confirm_email_change`: Confirm an admin requested email change from the old or new address
+//! - `request_magic_link`: Email a single-use login link, when magic links are enabled
+//! - `complete_magic_link`: Log in with the token from a magic link
+//! - `begin_passkey_login`: Issue a WebAuthn challenge for the user's passkeys
+//! - `finish_passkey_login`: Log in with a passkey, as the only or second factor
+//! - `get_saml_metadata`: The SAML service provider metadata XML
+//! - `begin_saml_login`: Create a SAML request, returning the identity provider url
+//! - `complete_saml_login`: Log in with the identity provider's SAML response
+//! - `start_device_authorization`: Issue a device code and user code for a CLI or IoT client
+//! - `token_from_device_code`: Log a device in once the user approves its user code
+//! - `client_credentials`: Issue a scoped access token to a registered confidential client
 //!
 
 use std::net::IpAddr;
@@ -23,29 +36,89 @@ use std::sync::Arc;
 use std::time;
 
 use http::header::{HeaderMap, SET_COOKIE};
-use secrecy::SecretString;
+use secrecy::{ExposeSecret, SecretString};
 use sqlx::{Pool, Postgres};
 use tonic::metadata::MetadataMap;
 use tonic::{Request, Response, Status};
 use uuid::Uuid;
 
-use crate::configuration::Configuration;
+use crate::configuration::{Configuration, PasskeyMode, RegistrationMode, SharedConfiguration};
+use crate::email::{EmailTemplate, EmailTemplates};
+use crate::events::{AuthEvent, AuthEventKind, AuthEvents};
+use crate::middleware::{SessionActivity, TokenDenylist};
+use crate::services::{
+    password_reset, validation, CaptchaGuard, DeviceAuthorization, EmailDomainGuard, LdapLogin,
+    LoginThrottle, Passkeys, PasswordHasher, PasswordResetLimiter, SamlLogin,
+};
 use crate::rpc::proto::authentication_service_server::AuthenticationService as Authentication;
 use crate::rpc::proto::{
-    Empty, LoginRequest, LoginResponse, LogoutResponse, RefreshResponse,
-    RegisterRequest, RegisterResponse, ResetPasswordRequest, ResetPasswordResponse,
-    UpdatePasswordRequest, UpdatePasswordResponse, UserResponse,
+    BeginPasskeyLoginRequest, BeginPasskeyLoginResponse, BeginSamlLoginResponse,
+    ClientCredentialsRequest, ClientCredentialsResponse, CompleteMagicLinkRequest,
+    CompleteSamlLoginRequest, ConfirmEmailChangeRequest, ConfirmEmailChangeResponse, Empty, FinishPasskeyLoginRequest, LoginRequest, LoginResponse,
+    LogoutOtherSessionsResponse, LogoutResponse, RefreshResponse, RegisterRequest,
+    RegisterResponse, RequestMagicLinkRequest, RequestMagicLinkResponse,
+    RequestPasswordResetRequest, RequestPasswordResetResponse, ResetPasswordRequest,
+    ResetPasswordResponse, SamlMetadataResponse, StartDeviceAuthorizationRequest,
+    StartDeviceAuthorizationResponse, TokenFromDeviceCodeRequest, UpdatePasswordRequest,
+    UpdatePasswordResponse, UserResponse,
 };
 use crate::{database, domain};
 use crate::{prelude::*, utils};
 
+/// Register response message, the same whether or not the email was already registered
+const REGISTER_RESPONSE_MESSAGE: &str = "Registration received, check your email to continue";
+
+/// Magic link response message, the same whether or not the email has an account
+const MAGIC_LINK_RESPONSE_MESSAGE: &str =
+    "If the email has an account a login link has been sent to it";
+
+/// Password reset response message, the same whether or not the email has an account
+const PASSWORD_RESET_RESPONSE_MESSAGE: &str =
+    "If the email has an account a password reset link has been sent to it";
+
 /// Authentication service containing a database pool
 pub struct AuthenticationService {
     /// Database Arc reference
     database: Arc<Pool<Postgres>>,
 
-    /// Configuration Arc reference
-    config: Arc<Configuration>,
+    /// Shared runtime configuration, reloaded when the configuration files change
+    config: SharedConfiguration,
+
+    /// Authentication event broadcaster
+    events: AuthEvents,
+
+    /// Access tokens denied before they expire
+    denylist: TokenDenylist,
+
+    /// CAPTCHA checks on register and login
+    captcha: CaptchaGuard,
+
+    /// Email domain allow and block lists on register
+    email_domains: EmailDomainGuard,
+
+    /// Failed login counts and lock outs per IP address and email
+    login_throttle: LoginThrottle,
+
+    /// Runs password hashing off the async runtime
+    password_hasher: PasswordHasher,
+
+    /// Password reset request counts per email and IP address
+    password_reset_limiter: PasswordResetLimiter,
+
+    /// Requests made with each session, counted on refresh
+    activity: SessionActivity,
+
+    /// WebAuthn passkey login ceremonies
+    passkeys: Passkeys,
+
+    /// Password login against an LDAP directory
+    ldap: LdapLogin,
+
+    /// SAML single sign on with the configured identity provider
+    saml: SamlLogin,
+
+    /// OAuth device authorization grant for CLI and IoT clients
+    device_authorization: DeviceAuthorization,
 }
 
 impl AuthenticationService {
@@ -56,10 +129,53 @@ impl AuthenticationService {
     /// ## Parameters
     ///
     /// - `database: Arc<Pool<Postgres>>` - Arc reference to the database pool
-    /// - `Arc<Configuration>)`: Arc reference to the configuration
+    /// - `config: SharedConfiguration` - Shared runtime configuration
+    /// - `events: AuthEvents` - Broadcaster for login and logout events
+    /// - `denylist: TokenDenylist` - Access tokens denied when sessions are revoked
+    /// - `captcha: CaptchaGuard` - CAPTCHA checks and failed login counts
     ///
-    pub fn new(database: Arc<Pool<Postgres>>, config: Arc<Configuration>) -> Self {
-        Self { database, config }
+    pub fn new(
+        database: Arc<Pool<Postgres>>,
+        config: SharedConfiguration,
+        events: AuthEvents,
+        denylist: TokenDenylist,
+        captcha: CaptchaGuard,
+    ) -> Self {
+        let login_throttle = LoginThrottle::new(Arc::clone(&database));
+        let email_domains = EmailDomainGuard::new(Arc::clone(&database));
+        let passkeys = Passkeys::new(Arc::clone(&database));
+        let ldap = LdapLogin::new(Arc::clone(&database), events.clone());
+        let saml = SamlLogin::new(Arc::clone(&database), events.clone());
+        let device_authorization = DeviceAuthorization::new(Arc::clone(&database));
+
+        Self {
+            database,
+            config,
+            events,
+            denylist,
+            captcha,
+            email_domains,
+            login_throttle,
+            password_hasher: PasswordHasher::default(),
+            password_reset_limiter: PasswordResetLimiter::default(),
+            activity: SessionActivity::default(),
+            passkeys,
+            ldap,
+            saml,
+            device_authorization,
+        }
+    }
+
+    /// Share a password hasher, and its queue, with the other services
+    pub fn with_password_hasher(mut self, password_hasher: PasswordHasher) -> Self {
+        self.password_hasher = password_hasher;
+        self
+    }
+
+    /// Count refreshes in the session activity written by startup
+    pub fn with_session_activity(mut self, activity: SessionActivity) -> Self {
+        self.activity = activity;
+        self
     }
 
     /// # Authentication Database Pool Reference
@@ -72,50 +188,47 @@ impl AuthenticationService {
 
     /// # Authentication Configuration Reference
     ///
-    /// This function is a shorthand reference to the Authentication Service
-    /// configuration.
-    fn config_ref(&self) -> &Configuration {
-        &self.config
+    /// This function loads a snapshot of the Authentication Service
+    /// configuration. Hold the snapshot for the whole request so a reload part
+    /// way through does not mix settings.
+    fn config_ref(&self) -> Arc<Configuration> {
+        self.config.load_full()
     }
-}
 
-#[tonic::async_trait]
-impl Authentication for AuthenticationService {
-    /// # Authentication Service
-    ///
-    /// Authenticate a user using their email and password
+    /// # Client IP Address
     ///
-    /// This function takes a tonic AuthenticationRequest, confirms the user is in
-    /// the database, confirms the store password hash matches the password.
-    /// Domain types are used to sanitise the email and password before checking
-    /// the database.
-    /// Once the password is verified the user is check to if they are active and
-    /// verified. Following this a access token is generated and a session instance
-    /// is saved to the database.
-    /// The access token and refresh token from the sessions instance is sent
-    /// in response. With the refresh token being sent as a httponly cookie header
-    #[tracing::instrument(name = "Authenticate Request: ", skip_all, fields(
-        src_address=%request.remote_addr().unwrap(),
-    ))]
-    async fn login(
-        &self,
-        request: Request<LoginRequest>,
-    ) -> Result<Response<LoginResponse>, Status> {
-        let socket_address = request.remote_addr().unwrap();
+    /// The ip address of the client, read from the forwarding metadata when
+    /// the request came through a trusted proxy, see `utils::client_ip`.
+    /// Requests over a Unix domain socket are from the loopback address. It
+    /// is recorded on the request span as `client_ip`.
+    fn client_ip<T>(&self, request: &Request<T>) -> IpAddr {
+        let client_ip =
+            utils::client_ip::client_ip(request, &self.config.load().trusted_proxies);
 
-        // Break the request up into its three parts: 1. Metadata, 2. Extensions & 3. Message
-        let (_request_metadata, _request_extensions, request_message) =
-            request.into_parts();
+        tracing::Span::current().record("client_ip", tracing::field::display(client_ip));
 
-        //-- 1. Verify the user email and password
-        ////////////////////////////////////////////////////////////////////////
+        client_ip
+    }
+
+    /// Where the browser is sent after a SAML login, see `saml.redirect_url`
+    pub fn saml_redirect_url(&self) -> String {
+        self.config_ref().saml.redirect_url.clone()
+    }
 
+    /// # Verify Login Credentials
+    ///
+    /// Check the email and password belong to an active user, returning the user.
+    async fn verify_credentials(
+        &self,
+        email: &str,
+        password: &SecretString,
+    ) -> Result<database::Users, Status> {
         // Parse the request email string into an EmailAddress
-        let request_email = domain::EmailAddress::parse(&request_message.email)
+        let request_email = domain::EmailAddress::parse(email)
             .map_err(|_| {
                 tracing::error!(
                     "Error parsing authentication request email address: {}",
-                    request_message.email
+                    email
                 );
                 AuthenticationError::AuthenticationError(
                     "Authentication failed!".to_string(),
@@ -124,31 +237,39 @@ impl Authentication for AuthenticationService {
         tracing::debug!("Request email: {}", request_email.as_ref());
 
         // Get the user from the database using the request email, so we can verify the password hash
-        let user =
-            database::Users::from_user_email(&request_email, self.database_ref())
-                .await
-                .map_err(|_| {
-                    tracing::error!(
-                        "User email not found in database: {}",
-                        request_email.as_ref()
-                    );
-                    AuthenticationError::AuthenticationError(
-                        "Authentication Failed!".to_string(),
-                    )
-                })?;
+        let user = match database::Users::from_user_email(&request_email, self.database_ref())
+            .await
+        {
+            Ok(user) => user,
+            Err(_) => {
+                tracing::error!(
+                    "User email not found in database: {}",
+                    request_email.as_ref()
+                );
+                // Verify against a dummy hash anyway, so an unknown email takes as
+                // long as a wrong password and can't be found by timing the response
+                self.password_hasher
+                    .verify(domain::PasswordHash::dummy(), password.clone())
+                    .await?;
+                return Err(Status::unauthenticated("Authentication Failed!"));
+            }
+        };
         tracing::debug!("User retrieved from the database: {}", user.id);
 
-        // Wrap request password in a Secret type to limit accidental exposure
-        let password = SecretString::from(request_message.password);
-
         // Verify the password hash using the password secret.
         // This will return a boolean indicating if the password is valid.
-        let is_password_valid =
-            user.password_hash.verify_password(&password).map_err(|_| {
-                tracing::error!("Password verification failed.");
-                AuthenticationError::AuthenticationError(
-                    "Authentication Failed!".to_string(),
-                )
+        let is_password_valid = self
+            .password_hasher
+            .verify(user.password_hash.clone(), password.clone())
+            .await
+            .map_err(|e| match e {
+                AuthenticationError::PasswordHashQueueFull => e,
+                _ => {
+                    tracing::error!("Password verification failed.");
+                    AuthenticationError::AuthenticationError(
+                        "Authentication Failed!".to_string(),
+                    )
+                }
             })?;
         tracing::debug!("Password verified: {}", is_password_valid);
 
@@ -165,26 +286,471 @@ impl Authentication for AuthenticationService {
         }
         tracing::debug!("User is active in the database: {}", user.id);
 
-        //-- 2. Generate new Access and Refresh Tokens
+        Ok(user)
+    }
+
+    /// # Check A Login Is Not Throttled
+    ///
+    /// Reject the login while the ip address and email are locked out after
+    /// repeated failures, adding the throttled attempt to the login history.
+    async fn check_login_throttle(
+        &self,
+        config: &Configuration,
+        email: &str,
+        login_ip: IpAddr,
+        user_agent: Option<&str>,
+    ) -> Result<(), Status> {
+        if let Err(e) = self
+            .login_throttle
+            .check(&config.login_throttle, login_ip, email)
+            .await
+        {
+            if matches!(e, AuthenticationError::LoginThrottled(_)) {
+                self.record_login(
+                    email,
+                    &login_ip.to_string(),
+                    user_agent,
+                    database::LoginOutcome::Throttled,
+                )
+                .await;
+            }
+            return Err(e.into());
+        }
+
+        Ok(())
+    }
+
+    /// # Record A Failed Login
+    ///
+    /// Count the failure towards the CAPTCHA and lock out thresholds, and add
+    /// it to the login history.
+    async fn record_failed_login(
+        &self,
+        config: &Configuration,
+        email: &str,
+        login_ip: IpAddr,
+        user_agent: Option<&str>,
+    ) {
+        self.captcha.record_failure(&config.captcha, login_ip);
+        if let Err(e) = self
+            .login_throttle
+            .record_failure(&config.login_throttle, login_ip, email)
+            .await
+        {
+            tracing::error!("Unable to record failed login: {e}");
+        }
+        self.record_login(
+            email,
+            &login_ip.to_string(),
+            user_agent,
+            database::LoginOutcome::Failed,
+        )
+        .await;
+    }
+
+    /// # Log In With A Password
+    ///
+    /// Verify the CAPTCHA, email and password, then start a session, see
+    /// `login`.
+    async fn password_login(
+        &self,
+        config: &Configuration,
+        request_message: LoginRequest,
+        login_ip: IpAddr,
+        user_agent: Option<&str>,
+    ) -> Result<Response<LoginResponse>, Status> {
+        //-- 1. Verify the CAPTCHA, email and password
+        ////////////////////////////////////////////////////////////////////////
+
+        // Wrap request password in a Secret type to limit accidental exposure
+        let password = SecretString::from(request_message.password);
+
+        let user = self
+            .verify_password_login(
+                config,
+                &request_message.email,
+                &password,
+                request_message.password_new.map(SecretString::from),
+                request_message.captcha_token.as_deref(),
+                login_ip,
+                user_agent,
+            )
+            .await?;
+
+        // Users with a passkey need it as well when passkeys are a second factor
+        if config.passkeys.enabled
+            && config.passkeys.mode == PasskeyMode::SecondFactor
+            && database::WebauthnCredentials::exists_for_user(&user.id, self.database_ref())
+                .await?
+        {
+            tracing::info!("Passkey required to log in: {}", user.id);
+            return Err(Status::failed_precondition(
+                "A passkey is required, log in with BeginPasskeyLogin",
+            ));
+        }
+
+        //-- 2. Start a new session, scoped to an organization (tenant) if one
+        // was requested, the user must be a member of it
+        ////////////////////////////////////////////////////////////////////////
+        let organization_id = self
+            .login_organization(&user, request_message.organization_id.as_deref())
+            .await?;
+
+        self.start_session(
+            config,
+            user,
+            organization_id,
+            None,
+            request_message.remember_me,
+            login_ip,
+            user_agent,
+        )
+        .await
+    }
+
+    /// # Verify A Password Login
+    ///
+    /// Check the login is not throttled, the CAPTCHA when one is needed, then
+    /// the email and password. Failures are counted and added to the login
+    /// history, a success clears the failures. A temporary password is
+    /// replaced with `password_new`, and fails with `PasswordChangeRequired`
+    /// without one.
+    #[allow(clippy::too_many_arguments)]
+    async fn verify_password_login(
+        &self,
+        config: &Configuration,
+        email: &str,
+        password: &SecretString,
+        password_new: Option<SecretString>,
+        captcha_token: Option<&str>,
+        login_ip: IpAddr,
+        user_agent: Option<&str>,
+    ) -> Result<database::Users, Status> {
+        self.check_login_throttle(config, email, login_ip, user_agent)
+            .await?;
+
+        // A CAPTCHA is needed when configured, or after repeated failed logins
+        // from the ip address
+        if let Err(e) = self
+            .captcha
+            .check(&config.captcha, captcha_token, login_ip)
+            .await
+        {
+            self.record_login(
+                email,
+                &login_ip.to_string(),
+                user_agent,
+                database::LoginOutcome::Failed,
+            )
+            .await;
+            return Err(e.into());
+        }
+
+        let user = match self.verify_credentials(email, password).await {
+            Ok(user) => user,
+            // Shed while hashing is at capacity, this is not a failed login
+            Err(status) if status.code() == tonic::Code::Unavailable => return Err(status),
+            // Then try the directory, when LDAP is enabled
+            Err(status) => match self.ldap.login(&config.ldap, email, password).await? {
+                Some(user) if user.is_active => user,
+                _ => {
+                    self.record_failed_login(config, email, login_ip, user_agent)
+                        .await;
+                    return Err(status);
+                }
+            },
+        };
+        self.captcha.clear_failures(login_ip);
+        self.login_throttle.clear(login_ip, email).await?;
+
+        // Only said after the password is checked, so it doesn't reveal the
+        // password state to anyone without the password
+        match database::Users::password_state(&user.id, self.database_ref()).await? {
+            database::PasswordState::Set => Ok(user),
+            database::PasswordState::Temporary => match password_new {
+                Some(password_new) => {
+                    self.replace_temporary_password(user, password, password_new)
+                        .await
+                }
+                None => {
+                    tracing::info!("Temporary password must be changed: {}", user.id);
+                    Err(AuthenticationError::PasswordChangeRequired.into())
+                }
+            },
+            database::PasswordState::ChangeRequired => {
+                tracing::info!("Password change required for user: {}", user.id);
+                Err(AuthenticationError::PasswordChangeRequired.into())
+            }
+        }
+    }
+
+    /// # Replace A Temporary Password
+    ///
+    /// Set the password the user chose at their first login, so the temporary
+    /// password can't be used again.
+    async fn replace_temporary_password(
+        &self,
+        mut user: database::Users,
+        temporary_password: &SecretString,
+        password_new: SecretString,
+    ) -> Result<database::Users, Status> {
+        if password_new.expose_secret() == temporary_password.expose_secret() {
+            return Err(Status::invalid_argument(
+                "The new password must be different to the temporary password",
+            ));
+        }
+        user.password_hash = self.password_hasher.hash(password_new).await?;
+
+        // Update the password and queue the event in one transaction
+        let mut transaction = self.database.begin().await?;
+        let user = user.update(&mut *transaction).await?;
+        database::Users::set_password_state(
+            &user.id,
+            database::PasswordState::Set,
+            &mut *transaction,
+        )
+        .await?;
+        let event = AuthEvent::new(AuthEventKind::PasswordChange).user(user.id);
+        database::Outbox::new(event.clone())
+            .insert(&mut *transaction)
+            .await?;
+        transaction.commit().await?;
+        self.events.publish(event);
+
+        tracing::info!("Temporary password replaced for user: {}", user.id);
+
+        Ok(user)
+    }
+
+    /// # Register With An Existing Email
+    ///
+    /// In `strict` mode queue a security alert to the owner, unless they opted
+    /// out, so the caller can't tell the email is taken. In `friendly` mode say
+    /// it is taken.
+    async fn existing_registration(
+        &self,
+        config: &Configuration,
+        existing: &database::Users,
+        ip: IpAddr,
+    ) -> Result<(), Status> {
+        if config.application.registration_mode == RegistrationMode::Friendly {
+            return Err(Status::already_exists("Email is already registered"));
+        }
+        tracing::warn!("Register attempted for an existing user: {}", existing.id);
+
+        // Respect the owner's opt out, the caller sees the same response either way
+        let preferences =
+            database::UserPreferences::for_user(&existing.id, self.database_ref()).await?;
+        if preferences.is_opted_out(domain::Notification::SecurityAlert) {
+            return Ok(());
+        }
+
+        let templates = EmailTemplates::new(&config.email)?;
+        let mut context = tera::Context::new();
+        context.insert("name", existing.name.as_ref());
+        context.insert("event", "registration attempt with your email");
+        context.insert("occurred_on", &chrono::Utc::now().to_rfc2822());
+        context.insert("ip_address", &ip.to_string());
+        let email = templates.render(
+            EmailTemplate::SecurityAlert,
+            &existing.email,
+            &existing.locale,
+            &context,
+        )?;
+
+        database::Outbox::new(email).insert(self.database_ref()).await?;
+
+        Ok(())
+    }
+
+    /// # Insert A Registered User
+    ///
+    /// Insert the user and queue the registration event in one transaction.
+    /// Returns false when a concurrent registration took the email first.
+    async fn insert_registration(
+        &self,
+        config: &Configuration,
+        user: &database::Users,
+    ) -> Result<bool, Status> {
+        let mut transaction = self.database.begin().await?;
+
+        let user = match user.insert(&mut *transaction).await {
+            Ok(user) => user,
+            Err(AuthenticationError::Sqlx(sqlx::Error::Database(e)))
+                if e.is_unique_violation() =>
+            {
+                return match config.application.registration_mode {
+                    RegistrationMode::Strict => Ok(false),
+                    RegistrationMode::Friendly => {
+                        Err(Status::already_exists("Email is already registered"))
+                    }
+                };
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        // Queue the registration for the webhooks, then publish it once saved
+        let event = AuthEvent::new(AuthEventKind::Registration).user(user.id);
+        database::Outbox::new(event.clone())
+            .insert(&mut *transaction)
+            .await?;
+        transaction.commit().await?;
+        self.events.publish(event);
+
+        Ok(true)
+    }
+
+    /// # Send A Magic Link
+    ///
+    /// Issue a magic link token for the user and queue the email with the
+    /// link in one transaction.
+    async fn send_magic_link(
+        &self,
+        config: &Configuration,
+        user: &database::Users,
+    ) -> Result<(), Status> {
+        let token = domain::ActionToken::generate(
+            domain::ActionPurpose::MagicLink,
+            &config.application.token_secret,
+        );
+        let action_token = database::ActionTokens::new(
+            &user.id,
+            &token,
+            &time::Duration::from_secs(config.magic_link.expiry_minutes * 60),
+        );
+
+        let templates = EmailTemplates::new(&config.email)?;
+        let mut context = tera::Context::new();
+        context.insert("name", user.name.as_ref());
+        context.insert("login_url", &config.magic_link.link(token.expose()));
+        context.insert("expires_in_minutes", &config.magic_link.expiry_minutes);
+        let email =
+            templates.render(EmailTemplate::MagicLink, &user.email, &user.locale, &context)?;
+
+        let mut transaction = self.database.begin().await?;
+        action_token.insert(&mut *transaction).await?;
+        database::Outbox::new(email)
+            .insert(&mut *transaction)
+            .await?;
+        transaction.commit().await?;
+
+        tracing::info!("Magic link sent to user {}", user.id);
+
+        Ok(())
+    }
+
+    /// # Send A Password Reset
+    ///
+    /// Issue a password reset for the user and queue the email with the link
+    /// in one transaction.
+    async fn send_password_reset(
+        &self,
+        config: &Configuration,
+        user: &database::Users,
+    ) -> Result<(), Status> {
+        let mut transaction = self.database.begin().await?;
+        password_reset::issue_password_reset(&mut *transaction, config, user, false).await?;
+        transaction.commit().await?;
+
+        tracing::info!("Password reset sent to user {}", user.id);
+
+        Ok(())
+    }
+
+    /// # Scope A Login To An Organization
+    ///
+    /// Check the user is a member of the requested organization (tenant),
+    /// returning its id. No, or an empty, organization id is not scoped.
+    async fn login_organization(
+        &self,
+        user: &database::Users,
+        organization_id: Option<&str>,
+    ) -> Result<Option<Uuid>, Status> {
+        let organization_id = match organization_id {
+            None | Some("") => None,
+            Some(organization_id) => {
+                let organization_id = Uuid::try_parse(organization_id).map_err(|_| {
+                    tracing::error!("Unable to parse organization id: {organization_id}");
+                    Status::unauthenticated("Authentication Failed!")
+                })?;
+
+                let member = database::OrganizationMembers::from_ids(
+                    &organization_id,
+                    &user.id,
+                    self.database_ref(),
+                )
+                .await
+                .map_err(|_| {
+                    tracing::error!(
+                        "User {} is not a member of organization {organization_id}",
+                        user.id
+                    );
+                    Status::unauthenticated("Authentication Failed!")
+                })?;
+                tracing::debug!("User is an organization {} member", member.role);
+
+                Some(organization_id)
+            }
+        };
+
+        Ok(organization_id)
+    }
+
+    /// # Start A Login Session
+    ///
+    /// Issue new access and refresh tokens, replace the user's sessions with a
+    /// new one and record the login, answering a successful login with the
+    /// tokens. Shared by every way of logging in.
+    async fn start_session(
+        &self,
+        config: &Configuration,
+        user: database::Users,
+        organization_id: Option<Uuid>,
+        client_id: Option<Uuid>,
+        remember_me: bool,
+        login_ip: IpAddr,
+        user_agent: Option<&str>,
+    ) -> Result<Response<LoginResponse>, Status> {
+        // Kept with the login in the user's login history
+        let ip_address = login_ip.to_string();
+
+        //-- 1. Generate new Access and Refresh Tokens
         ////////////////////////////////////////////////////////////////////////
 
         // Get the token secret from the config (it is wrapped in a Secret type
         // to help limit leaks)
-        let token_secret = self.config.application.token_secret.clone();
+        let token_secret = config.application.token_secret.clone();
 
         // Get the JWT issuer from the config (it is wrapped in a Secret type
         // to help limit leaks)
-        let jwt_issuer = self.config.application.get_issuer();
+        let jwt_issuer = config.application.get_issuer();
+
+        // Get the refresh token duration from the config, longer if the user
+        // asked to be remembered
+        let rt_minutes = if remember_me {
+            config.application.remember_me_duration_minutes
+        } else {
+            config.application.refresh_token_duration_minutes
+        };
+        let rt_duration: time::Duration = time::Duration::new(rt_minutes * 60, 0);
 
-        // Get the refresh token duration from the config
-        let rt_duration: time::Duration = time::Duration::new(
-            self.config.application.refresh_token_duration_minutes * 60,
+        // Sliding sessions can be extended up to the absolute lifetime, so the
+        // refresh token and cookie need to last that long. The session expiry
+        // in the database enforces the sliding window.
+        let absolute_duration: time::Duration = time::Duration::new(
+            config.application.absolute_session_lifetime_minutes * 60,
             0,
         );
+        let sliding_expiration = config.application.sliding_expiration;
+        let token_duration = if sliding_expiration {
+            rt_duration.max(absolute_duration)
+        } else {
+            rt_duration
+        };
 
         // Get the refresh token duration from the config
         let at_duration: time::Duration = time::Duration::new(
-            self.config.application.access_token_duration_minutes * 60,
+            config.application.access_token_duration_minutes * 60,
             0,
         );
 
@@ -192,29 +758,32 @@ impl Authentication for AuthenticationService {
         let refresh_token = domain::RefreshToken::new(
             &token_secret,
             &jwt_issuer,
-            &rt_duration,
+            &token_duration,
             &user,
         )?;
 
         // Build a new Access Token
-        let access_token = domain::AccessToken::new(
+        let access_token = domain::AccessToken::new_scoped(
             &token_secret,
             &jwt_issuer,
             &at_duration,
             &user,
+            organization_id.as_ref(),
+            &config.application.token_audiences,
         )?;
 
-        //-- 3. Revoke all user associated sessions and add a new user session
+        //-- 2. Revoke all user associated sessions and add a new user session
         ////////////////////////////////////////////////////////////////////////
 
+        // Revoke the old sessions, add the new one and queue the login event in
+        // one transaction, so the event is only sent if the login is saved
+        let mut transaction = self.database.begin().await?;
+
         // Revoke (make inactive) all sessions associated with the user id
         let _revoke_number =
-            database::Sessions::revoke_user_id(&user.id, self.database_ref())
+            database::Sessions::revoke_user_id(&user.id, &mut *transaction)
                 .await?;
 
-        // Get the ip address from the request socket
-        let login_ip = socket_address.ip();
-
         // IpAddress is an enum with two types, so we need to handle both IP cases
         let login_ip = match login_ip {
             IpAddr::V4(ipv4) => {
@@ -224,15 +793,57 @@ impl Authentication for AuthenticationService {
             IpAddr::V6(_) => None,
         };
 
+        // Record the access token id on the session, so it can be denied if the
+        // session is revoked before the token expires
+        let access_token_id = domain::TokenClaim::parse_with_audiences(
+            access_token.as_ref(),
+            &token_secret,
+            &jwt_issuer,
+            &config.application.token_audiences,
+        )?
+        .jti;
+
         // Create a new session instance
-        let new_session =
-            database::Sessions::new(&user, &login_ip, &rt_duration, &refresh_token)?;
+        let mut new_session =
+            database::Sessions::new(&user, &login_ip, &rt_duration, &refresh_token)?
+                .with_access_token_id(&access_token_id);
+        if sliding_expiration {
+            new_session = new_session.with_absolute_lifetime(&absolute_duration);
+        }
+        if let Some(organization_id) = &organization_id {
+            new_session = new_session.with_organization_id(organization_id);
+        }
+        if let Some(client_id) = &client_id {
+            new_session = new_session.with_client_id(client_id);
+        }
 
         // Insert the session into the database
-        let session = new_session.insert(self.database_ref()).await?;
+        let session = new_session.insert(&mut *transaction).await?;
         tracing::debug!("Session added to the database: {}", session.id);
 
-        //-- 4. Send the Tonic response
+        // Add the successful login to the user's login history
+        database::Logins::new(
+            user.email.as_ref(),
+            &ip_address,
+            user_agent,
+            database::LoginOutcome::Success,
+        )
+        .insert(&mut *transaction)
+        .await?;
+
+        // Queue the login event for the webhooks, then let any event subscribers
+        // know about the login once it is saved
+        let event = AuthEvent::new(AuthEventKind::Login)
+            .user(user.id)
+            .session(session.id)
+            .ip_address(login_ip);
+        database::Outbox::new(event.clone())
+            .insert(&mut *transaction)
+            .await?;
+        transaction.commit().await?;
+        self.events.publish(event);
+
+        //-- 3. Send the Tonic response
         ////////////////////////////////////////////////////////////////////////
 
         // Cast user instance into a UserResponse instance
@@ -243,17 +854,21 @@ impl Authentication for AuthenticationService {
         let response_message = LoginResponse {
             access_token: access_token.to_string(),
             user: Some(user_response_message),
+            refresh_token_expires_on: session.expires_on.to_rfc3339(),
         };
 
         // Create a new mutable Tonic response. It is mutable because we need to add the set-cookie header
         let mut response = Response::new(response_message);
 
         // Set the domain for the cookie
-        let domain = &self.config.application.get_domain();
+        let domain = &config.application.get_domain();
 
         // Build the refresh cookie
-        let refresh_cookie =
-            session.refresh_token.build_cookie(domain, &rt_duration);
+        let refresh_cookie = session.refresh_token.build_cookie(
+            &config.refresh_cookie,
+            domain,
+            &token_duration,
+        );
 
         // Create a new http header map
         let mut http_header = HeaderMap::new();
@@ -265,12 +880,109 @@ impl Authentication for AuthenticationService {
         // Add the http header to the rpc response
         *response.metadata_mut() = MetadataMap::from_headers(http_header);
 
-        tracing::info!("The response is: {:#?}", response);
-
         // Send Response
         Ok(response)
     }
 
+    /// # Record Login Attempt
+    ///
+    /// Add an unsuccessful login attempt to the user's login history. Failing
+    /// to record it is logged, so the original error is still returned.
+    async fn record_login(
+        &self,
+        email: &str,
+        ip_address: &str,
+        user_agent: Option<&str>,
+        outcome: database::LoginOutcome,
+    ) {
+        if let Err(e) = database::Logins::new(email, ip_address, user_agent, outcome)
+            .insert(self.database_ref())
+            .await
+        {
+            tracing::error!("Unable to record login attempt: {e}");
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl Authentication for AuthenticationService {
+    /// # Authentication Service
+    ///
+    /// Authenticate a user using their email and password
+    ///
+    /// This function takes a tonic AuthenticationRequest, confirms the user is in
+    /// the database, confirms the store password hash matches the password.
+    /// Domain types are used to sanitise the email and password before checking
+    /// the database.
+    /// Once the password is verified the user is check to if they are active and
+    /// verified. Following this a access token is generated and a session instance
+    /// is saved to the database.
+    /// The access token and refresh token from the sessions instance is sent
+    /// in response. With the refresh token being sent as a httponly cookie header
+    ///
+    /// The session lasts `refresh_token_duration_minutes`, or
+    /// `remember_me_duration_minutes` when the request sets `remember_me`. The
+    /// chosen expiry is returned in `refresh_token_expires_on` (RFC 3339).
+    ///
+    /// When the request sets `organization_id` the user must be a member of the
+    /// organization. The access token carries it in the `org` claim and the
+    /// session keeps it, so refreshed access tokens stay scoped to it.
+    ///
+    /// Every attempt, successful, failed or throttled, is added to the login
+    /// history with the ip address and `user-agent`.
+    ///
+    /// With `passkeys.mode` set to `second_factor`, users who have registered
+    /// a passkey are refused with `FAILED_PRECONDITION` and must log in with
+    /// `BeginPasskeyLogin` and `FinishPasskeyLogin` instead.
+    ///
+    /// With `ldap` enabled, a password that doesn't match a local user is
+    /// checked against the directory, and users are provisioned on their first
+    /// directory login.
+    ///
+    /// With `login_throttle` enabled, responses and errors carry the
+    /// `x-ratelimit-*` metadata of the ip address and email.
+    #[tracing::instrument(name = "Authenticate Request: ", skip_all, fields(
+        client_ip=tracing::field::Empty,
+    ))]
+    async fn login(
+        &self,
+        request: Request<LoginRequest>,
+    ) -> Result<Response<LoginResponse>, Status> {
+        validation::validate(&request)?;
+
+        let login_ip = self.client_ip(&request);
+
+        // Break the request up into its three parts: 1. Metadata, 2. Extensions & 3. Message
+        let (request_metadata, _request_extensions, request_message) =
+            request.into_parts();
+
+        // Load the current configuration, it can change at runtime
+        let config = self.config_ref();
+
+        // Kept with each login attempt in the user's login history
+        let user_agent = request_metadata
+            .get("user-agent")
+            .and_then(|value| value.to_str().ok());
+
+        let email = request_message.email.clone();
+        let result = self
+            .password_login(&config, request_message, login_ip, user_agent)
+            .await;
+
+        // Tell the client how many attempts are left, so it can back off
+        // before it is locked out
+        let rate_limit = self
+            .login_throttle
+            .rate_limit(&config.login_throttle, login_ip, &email)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!("Unable to read the login rate limit: {e}");
+                None
+            });
+
+        utils::rate_limit::with_rate_limit(result, rate_limit)
+    }
+
     /// # Refresh Service
     ///
     /// Get a new Access Token using the Refresh Token that has a longer life.
@@ -293,43 +1005,36 @@ impl Authentication for AuthenticationService {
         let (request_metadata, _request_extensions, _request_message) =
             request.into_parts();
 
+        // Load the current configuration, it can change at runtime
+        let config = self.config_ref();
+
         //-- 1. Check the Refresh Token is Valid
         ////////////////////////////////////////////////////////////////////////
         
         tracing::debug!("Check the refresh token is valid");
 
-        let cookie_jar = utils::metadata::get_cookie_jar(&request_metadata)?;
-        tracing::debug!("Cookies jar collected: {:#?}", cookie_jar);
+        // Get the refresh token from the cookie, or the metadata configured for
+        // clients without cookies
+        let refresh_token_string = domain::RefreshToken::from_header(
+            &config.refresh_cookie.name,
+            &config.refresh_token,
+            &request_metadata,
+        )
+        .map_err(|_| {
+            tracing::error!("Refresh token not found in the request header.");
+            tonic::Status::unauthenticated("Authentication Failed!")
+        })?
+        .to_string();
 
-        // Initiate the access token string
-        let refresh_token_string: String;
-
-        // Get the access token cookie from the request metadata and return the
-        // token string without the extra cookie metadata
-        match cookie_jar.get("refresh_token") {
-            Some(cookie) => {
-                refresh_token_string = cookie.value_trimmed().to_string();
-            }
-            None => {
-                tracing::error!(
-                    "Refresh token cookie not found in the request header."
-                );
-                return Err(tonic::Status::unauthenticated(
-                    "Authentication Failed!",
-                ));
-            }
-        };
-        tracing::debug!("Access token string: {}", refresh_token_string);
-
-        // Get the Token Secret from config and wrap it in a Secret to help limit leaks
-        let token_secret = &self.config_ref().application.token_secret;
+        // Get the Token Secret from config and wrap it in a Secret to help limit leaks
+        let token_secret = &config.application.token_secret;
 
         // Set the JWT issuer as the ip address of the server
-        let issuer = &self.config.application.get_issuer();
+        let issuer = &config.application.get_issuer();
 
         // Using the Token Secret decode the token into a Token Claim
         // This also validates the token expiration, not before and Issuer
-        let refresh_token_claim = domain::TokenClaim::parse(
+        domain::TokenClaim::parse(
             &refresh_token_string,
             token_secret,
             issuer,
@@ -362,23 +1067,52 @@ impl Authentication for AuthenticationService {
         // Check if the session is active
         if session.is_active == false {
             tracing::error!("Session is not active");
-            AuthenticationError::AuthenticationError(
-                "Authentication Failed!".to_string(),
-            );
+            return Err(Status::unauthenticated("Authentication Failed!"));
         }
         tracing::info!("Session is active.");
 
-        // Get user id from the refresh token claim
-        let user_id = Uuid::try_parse(&refresh_token_claim.sub).map_err(|_| {
-            tracing::error!("Unable to parse Uuid");
-            AuthenticationError::AuthenticationError(
-                "Authentication Failed!".to_string(),
-            )
-        })?;
+        // Check the session has not expired, sliding sessions can expire before
+        // their refresh token does
+        if session.expires_on <= chrono::Utc::now() {
+            tracing::error!("Session expired on: {}", session.expires_on);
+            return Err(Status::unauthenticated("Authentication Failed!"));
+        }
 
-        // Check user id in the token claim is in the database
+        // Check the session has been used within the idle timeout, separate
+        // from its expiry
+        if let Some(idle_timeout) = config.application.idle_session_timeout() {
+            if session.is_idle(&idle_timeout) {
+                tracing::error!("Session idle since: {}", session.last_active_at());
+                return Err(Status::unauthenticated("Authentication Failed!"));
+            }
+        }
+
+        // The session's user, not the refresh token claim, as a merge moves
+        // sessions to the account that is kept
         let user =
-            database::Users::from_user_id(&user_id, self.database_ref()).await?;
+            database::Users::from_user_id(&session.user_id, self.database_ref()).await?;
+
+        // Sessions started for an OAuth client only refresh while the user's
+        // grant to the client is valid
+        if let Some(client_id) = &session.client_id {
+            let grant =
+                database::Grants::active_for(&user.id, client_id, self.database_ref()).await?;
+            if grant.is_none() {
+                tracing::error!("Grant to client {client_id} revoked by user: {}", user.id);
+                return Err(Status::unauthenticated("Authentication Failed!"));
+            }
+        }
+
+        // Record the refresh, sliding the session expiry when it is a sliding session
+        let sliding_window: time::Duration = time::Duration::new(
+            config.application.refresh_token_duration_minutes * 60,
+            0,
+        );
+        let session = session
+            .slide_expiry(&sliding_window, self.database_ref())
+            .await?;
+        tracing::debug!("Session expires on: {}", session.expires_on);
+        self.activity.record_session(&session.id);
 
         //-- 3. Generate new (Refreshed) Access Token
         ////////////////////////////////////////////////////////////////////////
@@ -386,25 +1120,39 @@ impl Authentication for AuthenticationService {
         tracing::debug!("Refresh the access token.");
 
         // Wrap the Token Secret string in a Secret type to limit accidental exposure
-        let token_secret = self.config.application.token_secret.clone();
+        let token_secret = config.application.token_secret.clone();
 
         // Set the JWT issuer as the ip address of the server
-        let jwt_issuer = &self.config.application.get_issuer();
+        let jwt_issuer = &config.application.get_issuer();
 
         // Get the refresh token duration from the config
         let at_duration: time::Duration = time::Duration::new(
-            self.config.application.access_token_duration_minutes * 60,
+            config.application.access_token_duration_minutes * 60,
             0,
         );
 
-        // Build a new Access Token
-        let access_token = domain::AccessToken::new(
+        // Build a new Access Token, scoped to the same organization as the session
+        let access_token = domain::AccessToken::new_scoped(
             &token_secret,
             &jwt_issuer,
             &at_duration,
             &user,
+            session.organization_id.as_ref(),
+            &config.application.token_audiences,
         )?;
-        tracing::debug!("Generated new Access Token: {}", access_token);
+        tracing::debug!("Generated new Access Token");
+
+        // Record the new access token id on the session
+        let access_token_id = domain::TokenClaim::parse_with_audiences(
+            access_token.as_ref(),
+            &token_secret,
+            jwt_issuer,
+            &config.application.token_audiences,
+        )?
+        .jti;
+        let _session = session
+            .update_access_token_id(&access_token_id, self.database_ref())
+            .await?;
 
         //-- 4. Send the Tonic Refresh Response
         ////////////////////////////////////////////////////////////////////////
@@ -424,8 +1172,6 @@ impl Authentication for AuthenticationService {
         // Create a new mutable Tonic response. It is mutable because we need to add the set-cookie header
         let response = Response::new(response_message);
 
-        tracing::debug!("The response is: {:#?}", response);
-
         // Send Response
         Ok(response)
     }
@@ -437,6 +1183,9 @@ impl Authentication for AuthenticationService {
     /// This service takes a UpdatePasswordRequest with the original password and new password.
     /// The original password is verified against the password hash in the database.
     /// If the original password is valid, the new password is hashed and updated in the database.
+    /// The user's other sessions are then revoked and their outstanding access tokens denied.
+    /// The session the request was made from is kept unless
+    /// `application.password_change_keeps_session` is false.
     /// The function then sends a response message with a success boolean and message.
     #[tracing::instrument(name = "Update Password Request: ", skip(self, request))]
     async fn update_password(
@@ -446,6 +1195,9 @@ impl Authentication for AuthenticationService {
         //-- 0. Break the request up into its parts
         let (request_metadata, _extensions, request_message) = request.into_parts();
 
+        // Load the current configuration, it can change at runtime
+        let config = self.config_ref();
+
         //-- 1. Check the Access Token is Valid
         ////////////////////////////////////////////////////////////////////////
 
@@ -473,41 +1225,47 @@ impl Authentication for AuthenticationService {
                 ));
             }
         };
-        tracing::debug!("Access token string: {}", access_token_string);
 
         // Get the Token Secret from config and wrap it in a Secret to help limit leaks
-        let token_secret = &self.config_ref().application.token_secret;
+        let token_secret = &config.application.token_secret;
         let token_secret = token_secret.to_owned();
 
         // Set the JWT issuer as the ip address of the server
-        let issuer = &self.config.application.get_issuer();
+        let issuer = &config.application.get_issuer();
 
         // Using the Token Secret decode the Access Token string into a Token Claim.
-        // This validates the token expiration, not before and Issuer.
-        let access_token_claim =
-            domain::TokenClaim::parse(&access_token_string, &token_secret, &issuer)
-                .map_err(|_| {
-                    tracing::error!(
-                        "Access Token is invalid! Unable to parse token claim."
-                    );
-                    // Return error
-                    AuthenticationError::AuthenticationError(
-                        "Authentication Failed!".to_string(),
-                    )
-                })?;
+        // This validates the token expiration, not before, Issuer and Audience.
+        let access_token_claim = domain::TokenClaim::parse_with_audiences(
+            &access_token_string,
+            &token_secret,
+            &issuer,
+            &config.application.token_audiences,
+        )
+        .map_err(|_| {
+            tracing::error!(
+                "Access Token is invalid! Unable to parse token claim."
+            );
+            // Return error
+            AuthenticationError::AuthenticationError(
+                "Authentication Failed!".to_string(),
+            )
+        })?;
         tracing::debug!("Access Token verified: {}", access_token_claim.jti);
 
+        // Reject access tokens denied before they expired
+        if self.denylist.is_denied(&access_token_claim.jti) {
+            tracing::error!("Access Token is denied: {}", access_token_claim.jti);
+            return Err(Status::unauthenticated("Authentication Failed!"));
+        }
+
         //-- 2. Get user from database and check status
         ////////////////////////////////////////////////////////////////////////
 
-        // We can only change our own password so use the user_id in the access token
-        // Parse token claim user_id string into a UUID
-        let user_id: Uuid = access_token_claim.sub.parse().map_err(|_| {
-            tracing::error!("Unable to parse user id to UUID!");
-            return AuthenticationError::AuthenticationError(
-                "Authentication Failed!".to_string(),
-            );
-        })?;
+        // We can only change our own password so use the user_id in the access
+        // token. The authentication service is not intercepted, so the caller
+        // is read from the access token cookie here.
+        let auth_context = domain::AuthContext::from_claim(&access_token_claim)?;
+        let user_id = auth_context.user_id;
 
         // Get the user from the database using the token claim user_id, so we
         // can verify status and password hash
@@ -538,7 +1296,12 @@ impl Authentication for AuthenticationService {
         //-- Verify existing/original password
         let original_password =
             SecretString::from(request_message.password_original);
-        if user.password_hash.verify_password(&original_password)? == false {
+        if self
+            .password_hasher
+            .verify(user.password_hash.clone(), original_password)
+            .await?
+            == false
+        {
             tracing::error!("Original password is incorrect");
             return Err(Status::unauthenticated("Authentication Failed!"));
         }
@@ -551,16 +1314,97 @@ impl Authentication for AuthenticationService {
         let new_password = SecretString::from(request_message.password_new);
 
         // Parse the new password string into a PasswordHash
-        let new_password_hash = domain::PasswordHash::parse(new_password)?;
+        let new_password_hash = self.password_hasher.hash(new_password).await?;
 
         // Update the user instance with the new password hash
         user.password_hash = new_password_hash;
 
+        // Update the password, revoke the sessions and queue the event in one
+        // transaction, so the event is only sent if the change is saved
+        let mut transaction = self.database.begin().await?;
+
         // Update the user in the database
-        let _user = user.update(&self.database_ref()).await?;
+        let _user = user.update(&mut *transaction).await?;
         tracing::debug!("Users password updated in the database: {}", user.id);
 
-        //-- 4. Send the Tonic response
+        //-- 4. Revoke other sessions and deny their access tokens
+        /////////////////////////////////////////////////////////////////////////
+
+        // The session the access token was issued for, if it is still active
+        let current_session = database::Sessions::from_access_token_id(
+            &access_token_claim.jti,
+            self.database_ref(),
+        )
+        .await
+        .ok()
+        .filter(|session| session.is_active && session.user_id == user.id);
+
+        let kept_session_id = match config.application.password_change_keeps_session {
+            true => current_session.as_ref().map(|session| session.id),
+            false => None,
+        };
+
+        // Denied access tokens can be forgotten once they would have expired
+        let access_token_expires_on = chrono::Utc::now()
+            + time::Duration::new(config.application.access_token_duration_minutes * 60, 0);
+
+        // Deny the access tokens of the sessions being revoked
+        let mut denied = database::AccessTokenDenylist::insert_for_sessions(
+            &user.id,
+            kept_session_id.as_ref(),
+            &access_token_expires_on,
+            &mut *transaction,
+        )
+        .await?;
+
+        // The presented token may predate the current session's last refresh,
+        // so deny it too when the current session is not kept
+        if kept_session_id.is_none() {
+            let entry = database::AccessTokenDenylist::new(
+                &access_token_claim.jti,
+                &user.id,
+                &access_token_expires_on,
+            );
+            entry.insert(&mut *transaction).await?;
+            denied.push(entry);
+        }
+        tracing::debug!("Access tokens denied: {}", denied.len());
+
+        // Revoke the sessions
+        let sessions_revoked = match kept_session_id {
+            Some(session_id) => {
+                database::Sessions::revoke_all_except(
+                    &user.id,
+                    &session_id,
+                    &mut *transaction,
+                )
+                .await?
+            }
+            None => {
+                database::Sessions::revoke_user_id(&user.id, &mut *transaction).await?
+            }
+        } as u64;
+        tracing::debug!("Sessions revoked after password change: {sessions_revoked}");
+
+        // Record the password change for audit subscribers
+        let mut event = AuthEvent::new(AuthEventKind::PasswordChange)
+            .user(user.id)
+            .sessions_affected(sessions_revoked);
+        if let Some(session_id) = kept_session_id {
+            event = event.session(session_id);
+        }
+        database::Outbox::new(event.clone())
+            .insert(&mut *transaction)
+            .await?;
+        transaction.commit().await?;
+        self.events.publish(event);
+
+        // Only deny the access tokens in memory once the denylist rows are saved
+        for entry in &denied {
+            self.denylist.deny(&entry.jti, entry.expires_on);
+        }
+
+        //-- 5. Send the Tonic response
         ////////////////////////////////////////////////////////////////////////
 
         // Build GRPC response message
@@ -575,27 +1419,280 @@ impl Authentication for AuthenticationService {
 
     /// # Reset My Password Service
     ///
+    /// Set a new password with the token from a password reset email. The
+    /// token can only be used once, and the user's other unused resets,
+    /// sessions and access tokens are revoked. Completing a reset clears a
+    /// password change forced by an admin.
     #[tracing::instrument(name = "Reset Password Request: ", skip(self, request))]
     async fn reset_password(
         &self,
         request: Request<ResetPasswordRequest>,
     ) -> Result<Response<ResetPasswordResponse>, Status> {
+        validation::validate(&request)?;
+
+        //-- 0. Break the request up into its parts
+        let (_metadata, _extensions, request_message) = request.into_parts();
+
+        let config = self.config_ref();
+
+        //-- 1. Check the token and new password
+        ////////////////////////////////////////////////////////////////////////
+
+        let token = domain::ActionToken::parse(
+            &request_message.token,
+            domain::ActionPurpose::PasswordReset,
+            &config.application.token_secret,
+        )
+        .map_err(|_| Status::unauthenticated("Authentication Failed!"))?;
+
+        // Hash the new password before the token is used, so a password that
+        // fails validation can be retried with the same link
+        let new_password = SecretString::from(request_message.password_new);
+        let new_password_hash = self.password_hasher.hash(new_password).await?;
+
+        //-- 2. Use the token and update the password
+        ////////////////////////////////////////////////////////////////////////
+
+        // Use the token, update the password, revoke the sessions and queue
+        // the event in one transaction, so a failed reset leaves the token
+        // usable
+        let mut transaction = self.database.begin().await?;
+
+        let reset = database::PasswordResets::consume(&token, &mut *transaction)
+            .await?
+            .ok_or_else(|| {
+                tracing::error!("Password reset token is unknown, used or expired");
+                Status::unauthenticated("Authentication Failed!")
+            })?;
+
+        let mut user = database::Users::from_user_id(&reset.user_id, self.database_ref())
+            .await
+            .map_err(|_| Status::unauthenticated("Authentication Failed!"))?;
+
+        if !user.is_active {
+            tracing::error!("User is not active: {}", user.id);
+            return Err(Status::unauthenticated("Authentication Failed!"));
+        }
+
+        user.password_hash = new_password_hash;
+        let user = user.update(&mut *transaction).await?;
+        database::Users::set_password_state(&user.id, database::PasswordState::Set, &mut *transaction)
+            .await?;
+        database::PasswordResets::invalidate_unused_for_user(&user.id, &mut *transaction)
+            .await?;
+        tracing::debug!("Users password reset in the database: {}", user.id);
+
+        //-- 3. Revoke the sessions and deny their access tokens
+        ////////////////////////////////////////////////////////////////////////
+
+        // Denied access tokens can be forgotten once they would have expired
+        let access_token_expires_on = chrono::Utc::now()
+            + time::Duration::new(config.application.access_token_duration_minutes * 60, 0);
+
+        let denied = database::AccessTokenDenylist::insert_for_sessions(
+            &user.id,
+            None,
+            &access_token_expires_on,
+            &mut *transaction,
+        )
+        .await?;
+
+        let sessions_revoked =
+            database::Sessions::revoke_user_id(&user.id, &mut *transaction).await? as u64;
+        tracing::debug!("Sessions revoked after password reset: {sessions_revoked}");
+
+        // Record the password change for audit subscribers
+        let event = AuthEvent::new(AuthEventKind::PasswordChange)
+            .user(user.id)
+            .sessions_affected(sessions_revoked);
+        database::Outbox::new(event.clone())
+            .insert(&mut *transaction)
+            .await?;
+        transaction.commit().await?;
+        self.events.publish(event);
+
+        // Only deny the access tokens in memory once the denylist rows are saved
+        for entry in &denied {
+            self.denylist.deny(&entry.jti, entry.expires_on);
+        }
+
+        //-- 4. Send the Tonic response
+        ////////////////////////////////////////////////////////////////////////
+
+        let response_message = ResetPasswordResponse {
+            success: true,
+            message: "Password reset successfully".to_string(),
+        };
+
+        Ok(Response::new(response_message))
+    }
+
+    /// # Request Password Reset Service
+    ///
+    /// Email a password reset link to an active user. The response is the same
+    /// whether or not the email has an account, or the request was over the
+    /// per email and IP address limits, so it can't be used to find accounts.
+    #[tracing::instrument(name = "Request Password Reset Request: ", skip_all, fields(
+        client_ip=tracing::field::Empty,
+    ))]
+    async fn request_password_reset(
+        &self,
+        request: Request<RequestPasswordResetRequest>,
+    ) -> Result<Response<RequestPasswordResetResponse>, Status> {
+        validation::validate(&request)?;
+
+        let client_ip = self.client_ip(&request);
+
         //-- 0. Break the request up into its parts
-        let (_metadata, _extensions, _request_message) = request.into_parts();
+        let (_metadata, _extensions, request_message) = request.into_parts();
+
+        let config = self.config_ref();
+
+        self.captcha
+            .check(
+                &config.captcha,
+                request_message.captcha_token.as_deref(),
+                client_ip,
+            )
+            .await?;
 
-        unimplemented!()
+        //-- 1. Send a reset link to active users, within the limits
+        ////////////////////////////////////////////////////////////////////////
+
+        let email = domain::EmailAddress::parse(&request_message.email)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        if self.password_reset_limiter.allow(
+            &config.password_reset,
+            client_ip,
+            email.as_ref(),
+        ) {
+            match database::Users::from_user_email(&email, self.database_ref()).await {
+                Ok(user) if user.is_active => self.send_password_reset(&config, &user).await?,
+                Ok(user) => {
+                    tracing::warn!("Password reset requested for inactive user: {}", user.id)
+                }
+                Err(_) => tracing::warn!("Password reset requested for unknown email"),
+            }
+        }
+
+        //-- 2. Send response, the same whether or not a link was sent
+        ////////////////////////////////////////////////////////////////////////
+
+        let response_message = RequestPasswordResetResponse {
+            success: true,
+            message: PASSWORD_RESET_RESPONSE_MESSAGE.to_string(),
+        };
+
+        // Tell the client how many requests are left, so it can back off
+        let rate_limit = self.password_reset_limiter.rate_limit(
+            &config.password_reset,
+            client_ip,
+            email.as_ref(),
+        );
+
+        utils::rate_limit::with_rate_limit(Ok(Response::new(response_message)), rate_limit)
     }
 
     /// # Register a User Service
-    #[tracing::instrument(name = "Register User Request: ", skip(self, request))]
+    ///
+    /// A CAPTCHA token is checked first, when one is needed. Emails from a
+    /// domain that can't register fail with `INVALID_ARGUMENT` and a reason
+    /// code, see `services::email_domains`.
+    ///
+    /// With `application.registration_mode` set to `strict`, registering an
+    /// email that already has an account returns the same response as a new
+    /// registration, and the owner is emailed a security alert instead. With
+    /// `friendly` it fails with `ALREADY_EXISTS`.
+    #[tracing::instrument(name = "Register User Request: ", skip(self, request), fields(
+        client_ip=tracing::field::Empty,
+    ))]
     async fn register(
         &self,
         request: Request<RegisterRequest>,
     ) -> Result<Response<RegisterResponse>, Status> {
+        validation::validate(&request)?;
+
+        let client_ip = self.client_ip(&request);
+
         //-- 0. Break the request up into its parts
-        let (_metadata, _extensions, _request_message) = request.into_parts();
+        let (_metadata, _extensions, request_message) = request.into_parts();
 
-        unimplemented!()
+        let config = self.config_ref();
+
+        self.captcha
+            .check(
+                &config.captcha,
+                request_message.captcha_token.as_deref(),
+                client_ip,
+            )
+            .await?;
+
+        //-- 1. Validate the request
+        /////////////////////////////////////////////////////////////////////////
+
+        let email = domain::EmailAddress::parse(&request_message.email)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let name = domain::UserName::parse(&request_message.name)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let locale = request_message
+            .locale
+            .map(domain::Locale::parse)
+            .transpose()
+            .map_err(|e| Status::invalid_argument(e.to_string()))?
+            .unwrap_or_default();
+
+        // Refuse email domains that are blocked, not allowed or disposable
+        self.email_domains.check(&config.email_domains, &email).await?;
+
+        // Hash the password before checking the email, so both outcomes take
+        // about as long
+        let password = SecretString::from(request_message.password);
+        let password_hash = self.password_hasher.hash(password).await.map_err(|e| match e {
+            AuthenticationError::PasswordFormatInvalid => {
+                Status::invalid_argument(e.to_string())
+            }
+            _ => e.into(),
+        })?;
+
+        //-- 2. Register the user, unless the email is taken
+        /////////////////////////////////////////////////////////////////////////
+
+        let user = database::Users {
+            id: Uuid::now_v7(),
+            email,
+            name,
+            password_hash,
+            role: domain::UserRole::User,
+            is_active: true,
+            is_verified: false,
+            created_on: chrono::Utc::now(),
+            locale,
+        };
+
+        let registered = match database::Users::from_user_email(&user.email, self.database_ref())
+            .await
+        {
+            Ok(existing) => {
+                self.existing_registration(&config, &existing, client_ip)
+                    .await?;
+                false
+            }
+            Err(_) => self.insert_registration(&config, &user).await?,
+        };
+        if registered {
+            tracing::info!("User registered: {}", user.id);
+        }
+
+        //-- 3. Send response, the same whether or not the email was taken
+        /////////////////////////////////////////////////////////////////////////
+
+        let response_message = RegisterResponse {
+            success: true,
+            message: REGISTER_RESPONSE_MESSAGE.to_string(),
+        };
+
+        Ok(Response::new(response_message))
     }
 
     /// # Logout Service
@@ -615,18 +1712,25 @@ impl Authentication for AuthenticationService {
         let (request_metadata, _request_extensions, _request_message) =
             request.into_parts();
 
+        // Load the current configuration, it can change at runtime
+        let config = self.config_ref();
+
         //-- 1. Check the Refresh Token is Valid
         ////////////////////////////////////////////////////////////////////////
 
         // Get the Token Secret from config and wrap it in a Secret to help limit leaks
-        let token_secret = &self.config_ref().application.token_secret;
+        let token_secret = &config.application.token_secret;
 
         // Set the JWT issuer as the ip address of the server
-        let issuer = &self.config.application.get_issuer();
+        let issuer = &config.application.get_issuer();
 
         // Get the refresh token from the request header (metadata)
         let refresh_token: domain::RefreshToken =
-            domain::RefreshToken::from_header(token_secret, &request_metadata)?;
+            domain::RefreshToken::from_header(
+                &config.refresh_cookie.name,
+                &config.refresh_token,
+                &request_metadata,
+            )?;
 
         // Using the Token Secret decode the token into a Token Claim
         // This also validates the token expiration, not before and Issuer
@@ -682,14 +1786,28 @@ impl Authentication for AuthenticationService {
         //-- 3. Revoke associated session
         ////////////////////////////////////////////////////////////////////////
 
+        // Revoke the sessions and queue the logout event in one transaction
+        let mut transaction = self.database.begin().await?;
+
         // Revoke (make inactive) all sessions associated with the user id
-        let rows_revoked = session.revoke_associated(&self.database.as_ref()).await? as i64;
+        let rows_revoked = session.revoke_associated(&mut *transaction).await? as i64;
 
         if rows_revoked == 0 {
             tracing::error!("No sessions revoked");
             return Err(Status::unauthenticated("Authentication Failed!"));
         }
 
+        // Queue the logout event, then let any event subscribers know once saved
+        let event = AuthEvent::new(AuthEventKind::Logout)
+            .user(user_id)
+            .session(session.id)
+            .sessions_affected(rows_revoked as u64);
+        database::Outbox::new(event.clone())
+            .insert(&mut *transaction)
+            .await?;
+        transaction.commit().await?;
+        self.events.publish(event);
+
         //-- 4. Send the Tonic response
         ////////////////////////////////////////////////////////////////////////
 
@@ -717,9 +1835,759 @@ impl Authentication for AuthenticationService {
         // Add the http header to the rpc response
         *response.metadata_mut() = MetadataMap::from_headers(http_header);
 
-        tracing::info!("The response is: {:#?}", response);
-
         // Send Response
         Ok(response)
     }
+
+    /// # Logout Other Sessions Service
+    ///
+    /// Revoke all of the user's Sessions except the current one
+    ///
+    /// This function validates the Refresh Token sent in the request header and
+    /// finds the session it belongs to. All other sessions for the user are then
+    /// revoked, so the caller stays logged in. Use after a password change or a
+    /// suspected compromise.
+    #[tracing::instrument(name = "Log Out Other Sessions Request: ", skip(self, request))]
+    async fn logout_other_sessions(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<LogoutOtherSessionsResponse>, Status> {
+        // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
+        let (request_metadata, _request_extensions, _request_message) =
+            request.into_parts();
+
+        // Load the current configuration, it can change at runtime
+        let config = self.config_ref();
+
+        //-- 1. Check the Refresh Token is Valid
+        ////////////////////////////////////////////////////////////////////////
+
+        let token_secret = &config.application.token_secret;
+        let issuer = &config.application.get_issuer();
+
+        // Get the refresh token from the request header (metadata)
+        let refresh_token: domain::RefreshToken =
+            domain::RefreshToken::from_header(
+                &config.refresh_cookie.name,
+                &config.refresh_token,
+                &request_metadata,
+            )?;
+
+        // Decode the token, validating the expiration, not before and issuer
+        let refresh_token_claim = domain::TokenClaim::parse(
+            &refresh_token.to_string(),
+            token_secret,
+            issuer,
+        )
+        .map_err(|_| {
+            tracing::error!("Refresh Token is invalid!");
+            AuthenticationError::AuthenticationError(
+                "Authentication Failed!".to_string(),
+            )
+        })?;
+
+        //-- 2. Check the Session is Valid
+        ////////////////////////////////////////////////////////////////////////
+
+        let session = database::Sessions::from_token(
+            &refresh_token.to_string(),
+            self.database_ref(),
+        )
+        .await
+        .map_err(|_| {
+            tracing::error!("Refresh token not in sessions database");
+            AuthenticationError::AuthenticationError(
+                "Authentication Failed!".to_string(),
+            )
+        })?;
+
+        // A revoked or expired session cannot revoke the others
+        if session.is_active == false || session.expires_on <= chrono::Utc::now() {
+            tracing::error!("Session is not active: {}", session.id);
+            return Err(Status::unauthenticated("Authentication Failed!"));
+        }
+
+        // The token and session must belong to the same user
+        if refresh_token_claim.sub != session.user_id.to_string() {
+            tracing::error!("Refresh token subject does not match the session user");
+            return Err(Status::unauthenticated("Authentication Failed!"));
+        }
+
+        //-- 3. Revoke the other sessions
+        ////////////////////////////////////////////////////////////////////////
+
+        // Revoke the sessions and queue the revocation event in one transaction
+        let mut transaction = self.database.begin().await?;
+
+        let sessions_revoked = database::Sessions::revoke_all_except(
+            &session.user_id,
+            &session.id,
+            &mut *transaction,
+        )
+        .await? as u64;
+
+        // Queue the revocation event, then let any event subscribers know once saved
+        let event = AuthEvent::new(AuthEventKind::Revocation)
+            .user(session.user_id)
+            .session(session.id)
+            .sessions_affected(sessions_revoked);
+        database::Outbox::new(event.clone())
+            .insert(&mut *transaction)
+            .await?;
+        transaction.commit().await?;
+        self.events.publish(event);
+
+        //-- 4. Send the Tonic response
+        ////////////////////////////////////////////////////////////////////////
+
+        let response_message = LogoutOtherSessionsResponse {
+            success: true,
+            message: format!("Logged out of {sessions_revoked} other sessions"),
+            sessions_revoked,
+        };
+
+        Ok(Response::new(response_message))
+    }
+
+    /// # Confirm Email Change Service
+    ///
+    /// Confirm an admin requested email change using the token sent to either
+    /// the old or the new address. The user's email is only changed once the
+    /// tokens from both addresses have been confirmed.
+    #[tracing::instrument(name = "Confirm Email Change Request: ", skip(self, request))]
+    async fn confirm_email_change(
+        &self,
+        request: Request<ConfirmEmailChangeRequest>,
+    ) -> Result<Response<ConfirmEmailChangeResponse>, Status> {
+        //-- 0. Break the request up into its parts
+        let (_metadata, _extensions, request_message) = request.into_parts();
+
+        //-- 1. Find the pending change the token was sent for
+        ////////////////////////////////////////////////////////////////////////
+
+        let token = domain::ActionToken::parse(
+            &request_message.token,
+            domain::ActionPurpose::EmailChange,
+            &self.config_ref().application.token_secret,
+        )
+        .map_err(|_| Status::invalid_argument("Invalid email change token"))?;
+        let token_hash = token.hash();
+
+        // Confirm the change and update the email in one transaction, the
+        // change row stays locked until it commits
+        let mut transaction = self.database.begin().await?;
+
+        let email_change =
+            database::EmailChanges::from_token_hash(&token_hash, &mut *transaction)
+                .await?
+                .ok_or_else(|| Status::not_found("Email change not found"))?;
+
+        if email_change.is_expired() {
+            tracing::error!("Email change has expired: {}", email_change.id);
+            return Err(Status::failed_precondition("Email change has expired"));
+        }
+
+        //-- 2. Confirm this address, changing the email once both are confirmed
+        ////////////////////////////////////////////////////////////////////////
+
+        let email_change = email_change.confirm(&token_hash, &mut *transaction).await?;
+
+        let completed = email_change.is_confirmed();
+        if completed {
+            let mut user =
+                database::Users::from_user_id(&email_change.user_id, self.database_ref())
+                    .await?;
+            user.email = email_change.new_email.clone();
+            user.update(&mut *transaction)
+                .await
+                .map_err(|_| Status::already_exists("Email is already in use"))?;
+            email_change.complete(&mut *transaction).await?;
+            tracing::info!("Email changed for user {}", user.id);
+        }
+
+        transaction.commit().await?;
+
+        //-- 3. Send the Tonic response
+        ////////////////////////////////////////////////////////////////////////
+
+        let message = if completed {
+            "Email address changed"
+        } else {
+            "Email change confirmed, waiting for the other address to confirm"
+        };
+
+        let response_message = ConfirmEmailChangeResponse {
+            completed,
+            message: message.to_string(),
+        };
+
+        Ok(Response::new(response_message))
+    }
+
+    /// # Request Magic Link Service
+    ///
+    /// Email a single-use login link to the user, when `magic_link.enabled`.
+    /// The response is the same whether or not the email has an account, and
+    /// inactive users are not sent a link. A CAPTCHA token is checked first,
+    /// when one is needed.
+    #[tracing::instrument(name = "Request Magic Link Request: ", skip(self, request), fields(
+        client_ip=tracing::field::Empty,
+    ))]
+    async fn request_magic_link(
+        &self,
+        request: Request<RequestMagicLinkRequest>,
+    ) -> Result<Response<RequestMagicLinkResponse>, Status> {
+        validation::validate(&request)?;
+
+        let client_ip = self.client_ip(&request);
+
+        //-- 0. Break the request up into its parts
+        let (_metadata, _extensions, request_message) = request.into_parts();
+
+        let config = self.config_ref();
+        if !config.magic_link.enabled {
+            return Err(Status::unimplemented("Magic link login is not enabled"));
+        }
+
+        self.captcha
+            .check(
+                &config.captcha,
+                request_message.captcha_token.as_deref(),
+                client_ip,
+            )
+            .await?;
+
+        //-- 1. Send a link to active users
+        ////////////////////////////////////////////////////////////////////////
+
+        let email = domain::EmailAddress::parse(&request_message.email)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        match database::Users::from_user_email(&email, self.database_ref()).await {
+            Ok(user) if user.is_active => self.send_magic_link(&config, &user).await?,
+            Ok(user) => tracing::warn!("Magic link requested for inactive user: {}", user.id),
+            Err(_) => tracing::warn!("Magic link requested for unknown email"),
+        }
+
+        //-- 2. Send response, the same whether or not a link was sent
+        ////////////////////////////////////////////////////////////////////////
+
+        let response_message = RequestMagicLinkResponse {
+            success: true,
+            message: MAGIC_LINK_RESPONSE_MESSAGE.to_string(),
+        };
+
+        Ok(Response::new(response_message))
+    }
+
+    /// # Complete Magic Link Service
+    ///
+    /// Exchange the token from a magic link for access and refresh tokens. The
+    /// token is consumed, so the link only works once. The session is started
+    /// the same as a password login, including `remember_me`, organization
+    /// scoping and the login history.
+    #[tracing::instrument(name = "Complete Magic Link Request: ", skip_all, fields(
+        client_ip=tracing::field::Empty,
+    ))]
+    async fn complete_magic_link(
+        &self,
+        request: Request<CompleteMagicLinkRequest>,
+    ) -> Result<Response<LoginResponse>, Status> {
+        let login_ip = self.client_ip(&request);
+
+        //-- 0. Break the request up into its parts
+        let (request_metadata, _request_extensions, request_message) =
+            request.into_parts();
+
+        let config = self.config_ref();
+        if !config.magic_link.enabled {
+            return Err(Status::unimplemented("Magic link login is not enabled"));
+        }
+
+        let user_agent = request_metadata
+            .get("user-agent")
+            .and_then(|value| value.to_str().ok());
+
+        //-- 1. Consume the token and find its user
+        ////////////////////////////////////////////////////////////////////////
+
+        let token = domain::ActionToken::parse(
+            &request_message.token,
+            domain::ActionPurpose::MagicLink,
+            &config.application.token_secret,
+        )
+        .map_err(|_| Status::unauthenticated("Authentication Failed!"))?;
+
+        let action_token = database::ActionTokens::consume(&token, self.database_ref())
+            .await?
+            .ok_or_else(|| {
+                tracing::error!("Magic link token is unknown, used or expired");
+                Status::unauthenticated("Authentication Failed!")
+            })?;
+
+        let user = database::Users::from_user_id(&action_token.user_id, self.database_ref())
+            .await
+            .map_err(|_| Status::unauthenticated("Authentication Failed!"))?;
+
+        if !user.is_active {
+            tracing::error!("User is not active: {}", user.id);
+            self.record_login(
+                user.email.as_ref(),
+                &login_ip.to_string(),
+                user_agent,
+                database::LoginOutcome::Failed,
+            )
+            .await;
+            return Err(Status::unauthenticated("Authentication Failed!"));
+        }
+
+        //-- 2. Start a new session, the same as a password login
+        ////////////////////////////////////////////////////////////////////////
+
+        let organization_id = self
+            .login_organization(&user, request_message.organization_id.as_deref())
+            .await?;
+
+        self.start_session(
+            &config,
+            user,
+            organization_id,
+            None,
+            request_message.remember_me,
+            login_ip,
+            user_agent,
+        )
+        .await
+    }
+
+    /// # SAML Metadata Service
+    ///
+    /// The service provider metadata XML, for registering this service with
+    /// the identity provider.
+    #[tracing::instrument(name = "SAML Metadata Request: ", skip_all)]
+    async fn get_saml_metadata(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<SamlMetadataResponse>, Status> {
+        let config = self.config_ref();
+        let xml = self.saml.metadata(&config.saml)?;
+
+        Ok(Response::new(SamlMetadataResponse { xml }))
+    }
+
+    /// # Begin SAML Login Service
+    ///
+    /// Create a SAML AuthnRequest, returning the identity provider url to
+    /// redirect the browser to.
+    #[tracing::instrument(name = "Begin SAML Login Request: ", skip_all)]
+    async fn begin_saml_login(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<BeginSamlLoginResponse>, Status> {
+        let config = self.config_ref();
+        let redirect_url = self.saml.begin(&config.saml, None).await?;
+
+        Ok(Response::new(BeginSamlLoginResponse { redirect_url }))
+    }
+
+    /// # Complete SAML Login Service
+    ///
+    /// Exchange the identity provider's SAML response for access and refresh
+    /// tokens. The assertion is validated against the configured certificate
+    /// and the request it answers, provisioning the user on their first login.
+    /// The session is started the same as a password login.
+    #[tracing::instrument(name = "Complete SAML Login Request: ", skip_all, fields(
+        client_ip=tracing::field::Empty,
+    ))]
+    async fn complete_saml_login(
+        &self,
+        request: Request<CompleteSamlLoginRequest>,
+    ) -> Result<Response<LoginResponse>, Status> {
+        let login_ip = self.client_ip(&request);
+
+        //-- 0. Break the request up into its parts
+        let (request_metadata, _request_extensions, request_message) =
+            request.into_parts();
+
+        let config = self.config_ref();
+        let user_agent = request_metadata
+            .get("user-agent")
+            .and_then(|value| value.to_str().ok());
+
+        //-- 1. Validate the assertion and find its user
+        ////////////////////////////////////////////////////////////////////////
+
+        let user = self
+            .saml
+            .complete(&config.saml, &request_message.saml_response)
+            .await?;
+
+        if !user.is_active {
+            tracing::error!("User is not active: {}", user.id);
+            self.record_login(
+                user.email.as_ref(),
+                &login_ip.to_string(),
+                user_agent,
+                database::LoginOutcome::Failed,
+            )
+            .await;
+            return Err(Status::unauthenticated("Authentication Failed!"));
+        }
+
+        //-- 2. Start a new session, the same as a password login
+        ////////////////////////////////////////////////////////////////////////
+
+        let organization_id = self
+            .login_organization(&user, request_message.organization_id.as_deref())
+            .await?;
+
+        self.start_session(
+            &config,
+            user,
+            organization_id,
+            None,
+            request_message.remember_me,
+            login_ip,
+            user_agent,
+        )
+        .await
+    }
+
+    /// # Start Device Authorization Service
+    ///
+    /// Issue a device code and user code for a client that can't show a login
+    /// page. The device shows the user code and verification uri, then polls
+    /// `TokenFromDeviceCode` with the device code. With a `client_id` the
+    /// device logs in for the registered client, and approving it grants the
+    /// client access.
+    #[tracing::instrument(name = "Start Device Authorization Request: ", skip_all)]
+    async fn start_device_authorization(
+        &self,
+        request: Request<StartDeviceAuthorizationRequest>,
+    ) -> Result<Response<StartDeviceAuthorizationResponse>, Status> {
+        let request_message = request.into_inner();
+        let config = self.config_ref();
+        let device_config = &config.device_authorization;
+
+        let client_name = request_message
+            .client_name
+            .as_deref()
+            .map(str::trim)
+            .filter(|name| !name.is_empty());
+        let grant = self
+            .device_authorization
+            .start(device_config, client_name, request_message.client_id.as_deref())
+            .await?;
+
+        Ok(Response::new(StartDeviceAuthorizationResponse {
+            device_code: grant.device_code.expose().to_string(),
+            user_code: grant.user_code.as_ref().to_string(),
+            verification_uri: device_config.verification_uri.clone(),
+            verification_uri_complete: device_config
+                .verification_uri_complete(grant.user_code.as_ref()),
+            expires_in: device_config.expiry_minutes * 60,
+            interval: device_config.interval_seconds,
+        }))
+    }
+
+    /// # Token From Device Code Service
+    ///
+    /// Poll for the device's tokens. Until the user approves the device the
+    /// poll fails with an RFC 8628 error code as its message:
+    /// `authorization_pending`, `slow_down`, `access_denied` or
+    /// `expired_token`. Once approved the session is started the same as a
+    /// password login.
+    #[tracing::instrument(name = "Token From Device Code Request: ", skip_all, fields(
+        client_ip=tracing::field::Empty,
+    ))]
+    async fn token_from_device_code(
+        &self,
+        request: Request<TokenFromDeviceCodeRequest>,
+    ) -> Result<Response<LoginResponse>, Status> {
+        let login_ip = self.client_ip(&request);
+
+        //-- 0. Break the request up into its parts
+        let (request_metadata, _request_extensions, request_message) =
+            request.into_parts();
+
+        let config = self.config_ref();
+        let user_agent = request_metadata
+            .get("user-agent")
+            .and_then(|value| value.to_str().ok());
+
+        //-- 1. Check the user approved the device
+        ////////////////////////////////////////////////////////////////////////
+
+        let device_login = self
+            .device_authorization
+            .poll(&config.device_authorization, &request_message.device_code)
+            .await?;
+
+        let user = database::Users::from_user_id(&device_login.user_id, self.database_ref())
+            .await
+            .map_err(|_| Status::unauthenticated("Authentication Failed!"))?;
+
+        if !user.is_active {
+            tracing::error!("User is not active: {}", user.id);
+            self.record_login(
+                user.email.as_ref(),
+                &login_ip.to_string(),
+                user_agent,
+                database::LoginOutcome::Failed,
+            )
+            .await;
+            return Err(Status::unauthenticated("Authentication Failed!"));
+        }
+
+        //-- 2. Start a new session, the same as a password login
+        ////////////////////////////////////////////////////////////////////////
+
+        let organization_id = self
+            .login_organization(&user, request_message.organization_id.as_deref())
+            .await?;
+
+        self.start_session(
+            &config,
+            user,
+            organization_id,
+            device_login.client_id,
+            false,
+            login_ip,
+            user_agent,
+        )
+        .await
+    }
+
+    /// # Client Credentials Service
+    ///
+    /// Issue a short lived access token to a registered confidential client
+    /// allowed the `client_credentials` grant, for service to service calls.
+    /// The token has the requested scopes, or all of the client's scopes when
+    /// none are asked for, and no user role so it can't call user services.
+    /// No refresh token is issued, the client asks again once it expires.
+    ///
+    /// Errors carry the OAuth error code as their message: `invalid_client`
+    /// or `invalid_scope`.
+    #[tracing::instrument(name = "Client Credentials Request: ", skip_all)]
+    async fn client_credentials(
+        &self,
+        request: Request<ClientCredentialsRequest>,
+    ) -> Result<Response<ClientCredentialsResponse>, Status> {
+        let request_message = request.into_inner();
+
+        let config = self.config_ref();
+        if !config.client_credentials.enabled {
+            return Err(Status::unimplemented("Client credentials are not enabled"));
+        }
+
+        //-- 1. Check the client id and secret
+        ////////////////////////////////////////////////////////////////////////
+
+        let invalid_client = || Status::unauthenticated("invalid_client");
+
+        let client_id =
+            Uuid::parse_str(&request_message.client_id).map_err(|_| invalid_client())?;
+        let client_secret = domain::ClientSecret::parse(&request_message.client_secret)
+            .map_err(|_| invalid_client())?;
+
+        let client = database::Clients::from_id(&client_id, self.database_ref())
+            .await
+            .map_err(|_| invalid_client())?;
+
+        if !client.is_usable()
+            || !client.secret_matches(&client_secret)
+            || !client.allows_grant(domain::GrantType::ClientCredentials)
+        {
+            tracing::error!("Client credentials rejected for: {client_id}");
+            return Err(invalid_client());
+        }
+
+        //-- 2. Check the requested scopes were granted to the client
+        ////////////////////////////////////////////////////////////////////////
+
+        let scopes: Vec<String> = match request_message.scope.as_deref() {
+            Some(scope) if !scope.trim().is_empty() => {
+                scope.split_whitespace().map(str::to_string).collect()
+            }
+            _ => client.scopes.clone(),
+        };
+
+        if scopes.iter().any(|scope| !client.scopes.contains(scope)) {
+            return Err(Status::invalid_argument("invalid_scope"));
+        }
+
+        //-- 3. Issue the access token
+        ////////////////////////////////////////////////////////////////////////
+
+        let duration_seconds = config.client_credentials.access_token_duration_minutes * 60;
+        let access_token = domain::AccessToken::new_for_client(
+            &config.application.token_secret,
+            &config.application.get_issuer(),
+            &time::Duration::from_secs(duration_seconds),
+            &client,
+            &scopes,
+            &config.application.token_audiences,
+        )?;
+        tracing::info!("Client credentials token issued to: {client_id}");
+
+        Ok(Response::new(ClientCredentialsResponse {
+            access_token: access_token.to_string(),
+            token_type: "Bearer".to_string(),
+            expires_in: duration_seconds,
+            scope: scopes.join(" "),
+        }))
+    }
+
+    /// # Begin Passkey Login Service
+    ///
+    /// Issue a WebAuthn challenge for the user's passkeys, returning the
+    /// options for `navigator.credentials.get()` as JSON. With `passkeys.mode`
+    /// set to `second_factor` the password must be sent and is verified the
+    /// same as `Login`, including the CAPTCHA and lock outs.
+    #[tracing::instrument(name = "Begin Passkey Login Request: ", skip_all, fields(
+        client_ip=tracing::field::Empty,
+    ))]
+    async fn begin_passkey_login(
+        &self,
+        request: Request<BeginPasskeyLoginRequest>,
+    ) -> Result<Response<BeginPasskeyLoginResponse>, Status> {
+        let login_ip = self.client_ip(&request);
+
+        //-- 0. Break the request up into its parts
+        let (request_metadata, _request_extensions, request_message) =
+            request.into_parts();
+
+        let config = self.config_ref();
+        if !config.passkeys.enabled {
+            return Err(Status::unimplemented("Passkeys are not enabled"));
+        }
+
+        let user_agent = request_metadata
+            .get("user-agent")
+            .and_then(|value| value.to_str().ok());
+
+        //-- 1. Find the user, verifying the password for a second factor
+        ////////////////////////////////////////////////////////////////////////
+
+        let user = match config.passkeys.mode {
+            PasskeyMode::SecondFactor => {
+                let password =
+                    SecretString::from(request_message.password.unwrap_or_default());
+                self.verify_password_login(
+                    &config,
+                    &request_message.email,
+                    &password,
+                    None,
+                    request_message.captcha_token.as_deref(),
+                    login_ip,
+                    user_agent,
+                )
+                .await?
+            }
+            PasskeyMode::Primary => {
+                self.check_login_throttle(&config, &request_message.email, login_ip, user_agent)
+                    .await?;
+
+                let email = domain::EmailAddress::parse(&request_message.email)
+                    .map_err(|_| Status::unauthenticated("Authentication Failed!"))?;
+                match database::Users::from_user_email(&email, self.database_ref()).await {
+                    Ok(user) if user.is_active => user,
+                    _ => {
+                        tracing::error!("Passkey login for an unknown or inactive user");
+                        self.record_failed_login(
+                            &config,
+                            &request_message.email,
+                            login_ip,
+                            user_agent,
+                        )
+                        .await;
+                        return Err(Status::unauthenticated("Authentication Failed!"));
+                    }
+                }
+            }
+        };
+
+        //-- 2. Issue the challenge
+        ////////////////////////////////////////////////////////////////////////
+
+        let challenge = self
+            .passkeys
+            .begin_authentication(&config.passkeys, &user)
+            .await?;
+
+        Ok(Response::new(BeginPasskeyLoginResponse {
+            challenge_id: challenge.challenge_id.to_string(),
+            options: challenge.options,
+        }))
+    }
+
+    /// # Finish Passkey Login Service
+    ///
+    /// Check the credential signed by the browser answers the challenge from
+    /// `BeginPasskeyLogin`, then start a session the same as a password login,
+    /// including `remember_me`, organization scoping and the login history.
+    #[tracing::instrument(name = "Finish Passkey Login Request: ", skip_all, fields(
+        client_ip=tracing::field::Empty,
+    ))]
+    async fn finish_passkey_login(
+        &self,
+        request: Request<FinishPasskeyLoginRequest>,
+    ) -> Result<Response<LoginResponse>, Status> {
+        let login_ip = self.client_ip(&request);
+
+        //-- 0. Break the request up into its parts
+        let (request_metadata, _request_extensions, request_message) =
+            request.into_parts();
+
+        let config = self.config_ref();
+
+        let user_agent = request_metadata
+            .get("user-agent")
+            .and_then(|value| value.to_str().ok());
+
+        //-- 1. Verify the passkey and find its user
+        ////////////////////////////////////////////////////////////////////////
+
+        let user_id = self
+            .passkeys
+            .finish_authentication(
+                &config.passkeys,
+                &request_message.challenge_id,
+                &request_message.credential,
+            )
+            .await?;
+
+        let user = database::Users::from_user_id(&user_id, self.database_ref())
+            .await
+            .map_err(|_| Status::unauthenticated("Authentication Failed!"))?;
+
+        if !user.is_active {
+            tracing::error!("User is not active: {}", user.id);
+            self.record_failed_login(&config, user.email.as_ref(), login_ip, user_agent)
+                .await;
+            return Err(Status::unauthenticated("Authentication Failed!"));
+        }
+        self.captcha.clear_failures(login_ip);
+        self.login_throttle
+            .clear(login_ip, user.email.as_ref())
+            .await?;
+
+        //-- 2. Start a new session, the same as a password login
+        ////////////////////////////////////////////////////////////////////////
+
+        let organization_id = self
+            .login_organization(&user, request_message.organization_id.as_deref())
+            .await?;
+
+        self.start_session(
+            &config,
+            user,
+            organization_id,
+            None,
+            request_message.remember_me,
+            login_ip,
+            user_agent,
+        )
+        .await
+    }
 }
@@ -0,0 +1,325 @@
+//-- ./src/services/captcha.rs
+
+// #![allow(unused)] // For development only
+
+//! # CAPTCHA
+//!
+//! Optional CAPTCHA verification for the register and login RPCs, to slow down
+//! scripted sign ups and password guessing.
+//!
+//! A CAPTCHA token is needed when `captcha.required` is set, or once an IP
+//! address has failed to log in `captcha.failure_threshold` times within
+//! `captcha.failure_window_seconds`. Requests without a valid token are
+//! rejected with `FAILED_PRECONDITION`, so the client knows to show a CAPTCHA
+//! and send its token in `captcha_token`.
+//!
+//! ## Providers
+//! - **hCaptcha**: `captcha.provider: hcaptcha`
+//! - **reCAPTCHA**: `captcha.provider: recaptcha`
+//!
+//! Both are checked with the provider's `siteverify` endpoint, see
+//! `SiteVerifyCaptcha`. Failed logins are counted in memory, per instance.
+//! ---
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use secrecy::ExposeSecret;
+
+use crate::configuration::{CaptchaConfiguration, CaptchaProvider};
+use crate::prelude::*;
+
+/// hCaptcha token verification endpoint
+const HCAPTCHA_VERIFY_URL: &str = "https://api.hcaptcha.com/siteverify";
+
+/// reCAPTCHA token verification endpoint
+const RECAPTCHA_VERIFY_URL: &str = "https://www.google.com/recaptcha/api/siteverify";
+
+/// How long to wait for the provider to verify a token
+const VERIFY_TIMEOUT_SECONDS: u64 = 10;
+
+/// Checks a CAPTCHA token with the provider
+#[tonic::async_trait]
+pub trait CaptchaVerifier: Send + Sync {
+    /// Is the token a solved CAPTCHA
+    async fn verify(
+        &self,
+        config: &CaptchaConfiguration,
+        token: &str,
+        remote_ip: IpAddr,
+    ) -> Result<bool, AuthenticationError>;
+}
+
+/// The `siteverify` response, shared by hCaptcha and reCAPTCHA
+#[derive(Debug, serde::Deserialize)]
+struct SiteVerifyResponse {
+    success: bool,
+    #[serde(default, rename = "error-codes")]
+    error_codes: Vec<String>,
+}
+
+/// Verifies tokens with the hCaptcha or reCAPTCHA `siteverify` endpoint
+pub struct SiteVerifyCaptcha {
+    client: reqwest::Client,
+}
+
+impl SiteVerifyCaptcha {
+    /// Create a new verifier with its own HTTP client
+    pub fn new() -> Result<Self, AuthenticationError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(VERIFY_TIMEOUT_SECONDS))
+            .build()?;
+
+        Ok(Self { client })
+    }
+}
+
+#[tonic::async_trait]
+impl CaptchaVerifier for SiteVerifyCaptcha {
+    async fn verify(
+        &self,
+        config: &CaptchaConfiguration,
+        token: &str,
+        remote_ip: IpAddr,
+    ) -> Result<bool, AuthenticationError> {
+        let url = match config.provider {
+            CaptchaProvider::None => return Ok(true),
+            CaptchaProvider::Hcaptcha => HCAPTCHA_VERIFY_URL,
+            CaptchaProvider::Recaptcha => RECAPTCHA_VERIFY_URL,
+        };
+
+        let secret = config.secret.as_ref().ok_or_else(|| {
+            AuthenticationError::ConfigurationMissing("captcha.secret".to_string())
+        })?;
+        let remote_ip = remote_ip.to_string();
+
+        let response = self
+            .client
+            .post(url)
+            .form(&[
+                ("secret", secret.expose_secret()),
+                ("response", token),
+                ("remoteip", remote_ip.as_str()),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let response: SiteVerifyResponse = serde_json::from_str(&response)?;
+
+        if !response.success {
+            tracing::debug!("CAPTCHA rejected: {:?}", response.error_codes);
+        }
+
+        Ok(response.success)
+    }
+}
+
+/// Failed logins from an IP address within the current window
+#[derive(Debug, Clone, Copy)]
+struct FailureCount {
+    count: u32,
+    window_started: DateTime<Utc>,
+}
+
+/// Decides when a CAPTCHA is needed and checks it, cheap to clone into each service
+#[derive(Clone)]
+pub struct CaptchaGuard {
+    verifier: Arc<dyn CaptchaVerifier>,
+    failures: Arc<RwLock<HashMap<IpAddr, FailureCount>>>,
+}
+
+impl CaptchaGuard {
+    /// Create a guard verifying tokens with the provider `siteverify` endpoint
+    pub fn new() -> Result<Self, AuthenticationError> {
+        Ok(Self::with_verifier(Arc::new(SiteVerifyCaptcha::new()?)))
+    }
+
+    /// Create a guard with a different verifier, e.g. in tests
+    pub fn with_verifier(verifier: Arc<dyn CaptchaVerifier>) -> Self {
+        Self {
+            verifier,
+            failures: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Does a request from the IP address need a CAPTCHA
+    pub fn is_required(&self, config: &CaptchaConfiguration, ip: IpAddr) -> bool {
+        if config.provider == CaptchaProvider::None {
+            return false;
+        }
+        if config.required {
+            return true;
+        }
+        if config.failure_threshold == 0 {
+            return false;
+        }
+
+        let failures = self.failures.read().unwrap_or_else(|e| e.into_inner());
+        failures.get(&ip).is_some_and(|failure| {
+            is_current(failure, config) && failure.count >= config.failure_threshold
+        })
+    }
+
+    /// Check the request CAPTCHA token, if the IP address needs one
+    ///
+    /// ## Parameters
+    ///
+    /// - `config: &CaptchaConfiguration` - The current CAPTCHA configuration
+    /// - `token: Option<&str>` - The request `captcha_token`
+    /// - `ip: IpAddr` - The address the request came from
+    pub async fn check(
+        &self,
+        config: &CaptchaConfiguration,
+        token: Option<&str>,
+        ip: IpAddr,
+    ) -> Result<(), AuthenticationError> {
+        if !self.is_required(config, ip) {
+            return Ok(());
+        }
+
+        let token = token
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .ok_or_else(|| {
+                tracing::info!("CAPTCHA required for {ip}");
+                AuthenticationError::CaptchaRequired(
+                    "CAPTCHA token is required".to_string(),
+                )
+            })?;
+
+        if !self.verifier.verify(config, token, ip).await? {
+            tracing::info!("Invalid CAPTCHA token from {ip}");
+            return Err(AuthenticationError::CaptchaRequired(
+                "CAPTCHA token is invalid".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Count a failed login from the IP address
+    pub fn record_failure(&self, config: &CaptchaConfiguration, ip: IpAddr) {
+        let now = Utc::now();
+        let mut failures = self.failures.write().unwrap_or_else(|e| e.into_inner());
+
+        // Drop the counts for windows that have ended
+        failures.retain(|_, failure| is_current(failure, config));
+
+        let failure = failures.entry(ip).or_insert(FailureCount {
+            count: 0,
+            window_started: now,
+        });
+        failure.count = failure.count.saturating_add(1);
+    }
+
+    /// Forget the failed logins from the IP address, after a successful login
+    pub fn clear_failures(&self, ip: IpAddr) {
+        let mut failures = self.failures.write().unwrap_or_else(|e| e.into_inner());
+        failures.remove(&ip);
+    }
+}
+
+/// Is the failure count's window still open
+fn is_current(failure: &FailureCount, config: &CaptchaConfiguration) -> bool {
+    let window = chrono::Duration::seconds(config.failure_window_seconds as i64);
+    failure.window_started + window > Utc::now()
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use secrecy::SecretString;
+
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    /// Accepts a single known token
+    struct StaticCaptchaVerifier;
+
+    #[tonic::async_trait]
+    impl CaptchaVerifier for StaticCaptchaVerifier {
+        async fn verify(
+            &self,
+            _config: &CaptchaConfiguration,
+            token: &str,
+            _remote_ip: IpAddr,
+        ) -> Result<bool, AuthenticationError> {
+            Ok(token == "solved")
+        }
+    }
+
+    fn captcha_config(required: bool) -> CaptchaConfiguration {
+        CaptchaConfiguration {
+            provider: CaptchaProvider::Hcaptcha,
+            secret: Some(SecretString::from("secret")),
+            required,
+            failure_threshold: 2,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn required_captcha_needs_a_valid_token() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let guard = CaptchaGuard::with_verifier(Arc::new(StaticCaptchaVerifier));
+        let config = captcha_config(true);
+        let ip: IpAddr = "203.0.113.7".parse()?;
+
+        //-- Execute Function (Act)
+        let missing = guard.check(&config, None, ip).await;
+        let invalid = guard.check(&config, Some("guess"), ip).await;
+        let solved = guard.check(&config, Some("solved"), ip).await;
+
+        //-- Checks (Assertions)
+        assert!(matches!(
+            missing,
+            Err(AuthenticationError::CaptchaRequired(_))
+        ));
+        assert!(matches!(
+            invalid,
+            Err(AuthenticationError::CaptchaRequired(_))
+        ));
+        assert!(solved.is_ok());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn captcha_is_required_after_repeated_failures() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let guard = CaptchaGuard::with_verifier(Arc::new(StaticCaptchaVerifier));
+        let config = captcha_config(false);
+        let ip: IpAddr = "203.0.113.7".parse()?;
+        let other_ip: IpAddr = "203.0.113.8".parse()?;
+
+        //-- Execute Function (Act)
+        let before = guard.check(&config, None, ip).await;
+        guard.record_failure(&config, ip);
+        guard.record_failure(&config, ip);
+        let after = guard.check(&config, None, ip).await;
+        let other = guard.check(&config, None, other_ip).await;
+        guard.clear_failures(ip);
+        let cleared = guard.check(&config, None, ip).await;
+
+        //-- Checks (Assertions)
+        assert!(before.is_ok());
+        assert!(after.is_err());
+        assert!(other.is_ok());
+        assert!(cleared.is_ok());
+
+        // Without a provider a CAPTCHA is never asked for
+        guard.record_failure(&config, ip);
+        guard.record_failure(&config, ip);
+        assert!(!guard.is_required(&CaptchaConfiguration::default(), ip));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,403 @@
+//-- ./src/services/device_authorization.rs
+
+// #![allow(unused)] // For development only
+
+//! # Device Authorization
+//!
+//! The OAuth device authorization grant (RFC 8628), for CLI and IoT clients
+//! that can't show a login page:
+//!
+//! 1. **Start**: the device gets a secret device code and a short user code,
+//!    and shows the user code with `device_authorization.verification_uri`.
+//! 2. **Approve**: the user, logged in on another device, enters the user code
+//!    and approves or denies the device.
+//! 3. **Poll**: the device polls with its device code, every
+//!    `device_authorization.interval_seconds`, until the user answers. Once
+//!    approved the grant is consumed and the device logs in as the user.
+//!
+//! Poll errors carry the RFC 8628 error codes as their message, e.g.
+//! `authorization_pending` or `slow_down`. Grants are kept in `device_codes`.
+//!
+//! A device can start the authorization for a registered OAuth client allowed
+//! the device code grant. Approving it then records the user's grant to the
+//! client, see `database::Grants`, and the device only gets tokens while the
+//! grant is valid.
+//! ---
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use sqlx::{Pool, Postgres};
+use tonic::Status;
+use uuid::Uuid;
+
+use crate::configuration::DeviceAuthorizationConfiguration;
+use crate::{database, domain};
+
+/// A device authorization that has started, the device shows the user code
+/// and polls with the device code
+#[derive(Debug, Clone)]
+pub struct DeviceGrant {
+    pub device_code: domain::DeviceCode,
+    pub user_code: domain::UserCode,
+}
+
+/// The user (and client, if any) an approved device logs in as
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceLogin {
+    pub user_id: Uuid,
+    pub client_id: Option<Uuid>,
+}
+
+/// Runs the device authorization grant
+#[derive(Clone)]
+pub struct DeviceAuthorization {
+    database: Arc<Pool<Postgres>>,
+}
+
+impl DeviceAuthorization {
+    /// Create the device authorization, keeping grants in the database
+    pub fn new(database: Arc<Pool<Postgres>>) -> Self {
+        Self { database }
+    }
+
+    /// Device authorization that is not enabled answers `UNIMPLEMENTED`
+    fn check_enabled(config: &DeviceAuthorizationConfiguration) -> Result<(), Status> {
+        if !config.enabled {
+            return Err(Status::unimplemented("Device authorization is not enabled"));
+        }
+        Ok(())
+    }
+
+    /// # Start Device Authorization
+    ///
+    /// Issue a new device code and user code, pending until the user answers
+    /// or `device_authorization.expiry_minutes` pass. With a client id the
+    /// client must be registered and allowed the device code grant, failing
+    /// with `invalid_client`.
+    pub async fn start(
+        &self,
+        config: &DeviceAuthorizationConfiguration,
+        client_name: Option<&str>,
+        client_id: Option<&str>,
+    ) -> Result<DeviceGrant, Status> {
+        Self::check_enabled(config)?;
+
+        let client = match client_id {
+            Some(client_id) => Some(self.device_client(client_id).await?),
+            None => None,
+        };
+
+        let device_code = domain::DeviceCode::generate();
+        let user_code = domain::UserCode::generate();
+
+        let mut device = database::DeviceCodes::new(
+            &device_code,
+            &user_code,
+            client_name,
+            &Duration::from_secs(config.expiry_minutes * 60),
+        );
+        if let Some(client) = &client {
+            device = device.with_client(client);
+        }
+        device.insert(self.database.as_ref()).await?;
+
+        Ok(DeviceGrant {
+            device_code,
+            user_code,
+        })
+    }
+
+    /// The registered client a device authorization is started for
+    async fn device_client(&self, client_id: &str) -> Result<database::Clients, Status> {
+        let invalid_client = || Status::invalid_argument("invalid_client");
+
+        let client_id = Uuid::parse_str(client_id).map_err(|_| invalid_client())?;
+        let client = database::Clients::from_id(&client_id, self.database.as_ref())
+            .await
+            .map_err(|_| invalid_client())?;
+
+        if !client.is_usable() || !client.allows_grant(domain::GrantType::DeviceCode) {
+            return Err(invalid_client());
+        }
+
+        Ok(client)
+    }
+
+    /// # Answer Device Authorization
+    ///
+    /// Approve or deny the device showing the user code, as the user. Unknown,
+    /// expired and already answered codes are not found. Approving a device
+    /// started for a client grants the client the client's scopes.
+    pub async fn answer(
+        &self,
+        config: &DeviceAuthorizationConfiguration,
+        user_code: &str,
+        user_id: &Uuid,
+        approve: bool,
+    ) -> Result<database::DeviceCodes, Status> {
+        Self::check_enabled(config)?;
+
+        let user_code = domain::UserCode::parse(user_code)
+            .map_err(|_| Status::invalid_argument("Invalid user code"))?;
+        let status = if approve {
+            database::DeviceCodeStatus::Approved
+        } else {
+            database::DeviceCodeStatus::Denied
+        };
+
+        let device =
+            database::DeviceCodes::answer(&user_code, user_id, status, self.database.as_ref())
+                .await?
+                .ok_or_else(|| Status::not_found("Unknown or expired user code"))?;
+
+        if let (true, Some(client_id)) = (approve, &device.client_id) {
+            let client = database::Clients::from_id(client_id, self.database.as_ref()).await?;
+            let grant = database::Grants::new(user_id, client_id, &client.scopes)
+                .insert(self.database.as_ref())
+                .await?;
+            tracing::info!("User {user_id} granted client {client_id} access: {}", grant.id);
+        }
+
+        Ok(device)
+    }
+
+    /// # Poll Device Authorization
+    ///
+    /// Check whether the user has answered, returning the user (and client)
+    /// the device logs in as once approved. The grant is consumed, so tokens
+    /// are only issued once. A device started for a client is denied if the
+    /// user has since revoked their grant to the client.
+    pub async fn poll(
+        &self,
+        config: &DeviceAuthorizationConfiguration,
+        device_code: &str,
+    ) -> Result<DeviceLogin, Status> {
+        Self::check_enabled(config)?;
+
+        let expired = || Status::unauthenticated("expired_token");
+
+        let device_code = domain::DeviceCode::parse(device_code).map_err(|_| expired())?;
+        let grant = database::DeviceCodes::from_device_code(&device_code, self.database.as_ref())
+            .await?
+            .ok_or_else(expired)?;
+
+        if grant.is_expired() {
+            grant.delete(self.database.as_ref()).await?;
+            return Err(expired());
+        }
+
+        // Devices polling faster than the interval are told to slow down
+        let interval = chrono::Duration::seconds(config.interval_seconds as i64);
+        let polled_too_soon = grant
+            .last_polled_on
+            .is_some_and(|last_polled_on| Utc::now() < last_polled_on + interval);
+        grant.record_poll(self.database.as_ref()).await?;
+        if polled_too_soon {
+            return Err(Status::resource_exhausted("slow_down"));
+        }
+
+        match grant.status {
+            database::DeviceCodeStatus::Pending => {
+                Err(Status::failed_precondition("authorization_pending"))
+            }
+            database::DeviceCodeStatus::Denied => {
+                grant.delete(self.database.as_ref()).await?;
+                Err(Status::permission_denied("access_denied"))
+            }
+            database::DeviceCodeStatus::Approved => {
+                let grant = grant.consume(self.database.as_ref()).await?.ok_or_else(expired)?;
+                let user_id = grant.user_id.ok_or_else(expired)?;
+
+                if let Some(client_id) = &grant.client_id {
+                    database::Grants::active_for(&user_id, client_id, self.database.as_ref())
+                        .await?
+                        .ok_or_else(|| Status::permission_denied("access_denied"))?;
+                }
+
+                Ok(DeviceLogin {
+                    user_id,
+                    client_id: grant.client_id,
+                })
+            }
+        }
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    /// The RFC 8628 error code of a poll, empty when it succeeded
+    fn poll_error(poll: core::result::Result<DeviceLogin, Status>) -> String {
+        poll.err().map(|e| e.message().to_string()).unwrap_or_default()
+    }
+
+    fn enabled() -> DeviceAuthorizationConfiguration {
+        DeviceAuthorizationConfiguration {
+            enabled: true,
+            interval_seconds: 0,
+            ..DeviceAuthorizationConfiguration::default()
+        }
+    }
+
+    #[sqlx::test]
+    async fn approved_device_gets_the_user_once(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let device_authorization = DeviceAuthorization::new(Arc::new(database));
+        let grant = device_authorization
+            .start(&enabled(), Some("CLI"), None)
+            .await?;
+
+        //-- Execute Function (Act)
+        let pending = device_authorization
+            .poll(&enabled(), grant.device_code.expose())
+            .await;
+        device_authorization
+            .answer(&enabled(), grant.user_code.as_ref(), &user.id, true)
+            .await?;
+        let approved = device_authorization
+            .poll(&enabled(), grant.device_code.expose())
+            .await?;
+        let used = device_authorization
+            .poll(&enabled(), grant.device_code.expose())
+            .await;
+
+        //-- Checks (Assertions)
+        assert_eq!(poll_error(pending), "authorization_pending");
+        assert_eq!(
+            approved,
+            DeviceLogin {
+                user_id: user.id,
+                client_id: None
+            }
+        );
+        assert_eq!(poll_error(used), "expired_token");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn denied_devices_and_fast_polls_are_rejected(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let device_authorization = DeviceAuthorization::new(Arc::new(database));
+        let config = DeviceAuthorizationConfiguration {
+            enabled: true,
+            ..DeviceAuthorizationConfiguration::default()
+        };
+        let grant = device_authorization.start(&config, None, None).await?;
+
+        //-- Execute Function (Act)
+        let first = device_authorization
+            .poll(&config, grant.device_code.expose())
+            .await;
+        let too_soon = device_authorization
+            .poll(&config, grant.device_code.expose())
+            .await;
+        device_authorization
+            .answer(&config, grant.user_code.as_ref(), &user.id, false)
+            .await?;
+        let denied = device_authorization
+            .poll(&enabled(), grant.device_code.expose())
+            .await;
+
+        //-- Checks (Assertions)
+        assert_eq!(poll_error(first), "authorization_pending");
+        assert_eq!(poll_error(too_soon), "slow_down");
+        assert_eq!(poll_error(denied), "access_denied");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn device_authorization_is_not_implemented_by_default(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let device_authorization = DeviceAuthorization::new(Arc::new(database));
+
+        //-- Execute Function (Act)
+        let started = device_authorization
+            .start(&DeviceAuthorizationConfiguration::default(), None, None)
+            .await;
+
+        //-- Checks (Assertions)
+        assert_eq!(
+            started.map(|_| ()).map_err(|e| e.code()),
+            Err(tonic::Code::Unimplemented)
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn client_devices_need_a_valid_grant(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let user = database::Users::mock_data()?;
+        user.insert(&database).await?;
+        let (client, _secret) = database::Clients::mock_data();
+        client.insert(&database).await?;
+        let device_client = database::Clients::new_public(
+            "Mock CLI",
+            &[],
+            &[domain::GrantType::DeviceCode],
+            &["users:read".to_string()],
+        )
+        .insert(&database)
+        .await?;
+        let device_authorization = DeviceAuthorization::new(Arc::new(database.clone()));
+
+        //-- Execute Function (Act)
+        // The mock client is only allowed the client credentials grant
+        let not_allowed = device_authorization
+            .start(&enabled(), None, Some(&client.id.to_string()))
+            .await;
+
+        let approved = device_authorization
+            .start(&enabled(), None, Some(&device_client.id.to_string()))
+            .await?;
+        device_authorization
+            .answer(&enabled(), approved.user_code.as_ref(), &user.id, true)
+            .await?;
+        let login = device_authorization
+            .poll(&enabled(), approved.device_code.expose())
+            .await?;
+
+        let revoked = device_authorization
+            .start(&enabled(), None, Some(&device_client.id.to_string()))
+            .await?;
+        device_authorization
+            .answer(&enabled(), revoked.user_code.as_ref(), &user.id, true)
+            .await?;
+        let grant = database::Grants::active_for(&user.id, &device_client.id, &database)
+            .await?
+            .ok_or("grant not recorded")?;
+        database::Grants::revoke(&grant.id, &user.id, &database).await?;
+        let after_revoke = device_authorization
+            .poll(&enabled(), revoked.device_code.expose())
+            .await;
+
+        //-- Checks (Assertions)
+        assert_eq!(
+            not_allowed.map(|_| ()).map_err(|e| e.message().to_string()),
+            Err("invalid_client".to_string())
+        );
+        assert_eq!(login.client_id, Some(device_client.id));
+        assert_eq!(grant.scopes, device_client.scopes);
+        assert_eq!(poll_error(after_revoke), "access_denied");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+//-- ./src/services/email_domains.rs
+
+// #![allow(unused)] // For development only
+
+//! # Email Domains
+//!
+//! Allow and block lists for the email domains that can register. A domain
+//! also covers its subdomains, so blocking `example.com` blocks
+//! `mail.example.com`.
+//!
+//! Domains are listed in the `email_domains` configuration and by admins with
+//! `SetEmailDomainRule`, kept in the `email_domains` table. A registration is
+//! refused when its domain is:
+//! 1. **Blocked**: blocked in either list, even when also allowed.
+//! 2. **Not allowed**: some domains are allowed, and this is not one of them.
+//! 3. **Disposable**: `email_domains.block_disposable` is set and the domain
+//!    is in `DISPOSABLE_EMAIL_DOMAINS` or `email_domains.disposable`, unless
+//!    it is allowed.
+//!
+//! Refused registrations fail with `INVALID_ARGUMENT` and the reason code in
+//! the `x-email-domain-rejected` metadata, see `domain::EmailDomainRejection`.
+//! ---
+
+use std::sync::Arc;
+
+use sqlx::{Pool, Postgres};
+
+use crate::configuration::EmailDomainsConfiguration;
+use crate::database::{self, EmailDomainRule};
+use crate::domain::{self, EmailDomainRejection};
+use crate::prelude::*;
+
+/// Well known domains that give out disposable addresses
+pub const DISPOSABLE_EMAIL_DOMAINS: &[&str] = &[
+    "10minutemail.com",
+    "discard.email",
+    "dispostable.com",
+    "emailondeck.com",
+    "fakeinbox.com",
+    "getnada.com",
+    "guerrillamail.com",
+    "guerrillamail.net",
+    "mailinator.com",
+    "maildrop.cc",
+    "mailnesia.com",
+    "mintemail.com",
+    "mohmal.com",
+    "sharklasers.com",
+    "spamgourmet.com",
+    "temp-mail.org",
+    "tempail.com",
+    "tempmail.com",
+    "throwawaymail.com",
+    "trashmail.com",
+    "yopmail.com",
+];
+
+/// Checks the email domain of registrations, cheap to clone into each service
+#[derive(Clone)]
+pub struct EmailDomainGuard {
+    database: Arc<Pool<Postgres>>,
+}
+
+impl EmailDomainGuard {
+    /// Create a guard reading the admin's domain rules from the database
+    pub fn new(database: Arc<Pool<Postgres>>) -> Self {
+        Self { database }
+    }
+
+    /// Check the email's domain can register, returning
+    /// `EmailDomainRejected` with the reason when it can't
+    pub async fn check(
+        &self,
+        config: &EmailDomainsConfiguration,
+        email: &domain::EmailAddress,
+    ) -> Result<(), AuthenticationError> {
+        let domain = domain::EmailDomain::of(email)?;
+
+        let rules = database::EmailDomains::covering(&domain, &self.database).await?;
+        let is_allowed = rules.iter().any(|rule| rule.rule == EmailDomainRule::Allow);
+        let any_allowed = is_allowed
+            || !config.allowed.is_empty()
+            || database::EmailDomains::any_allowed(&self.database).await?;
+        let rules = DomainRules {
+            is_blocked: rules.iter().any(|rule| rule.rule == EmailDomainRule::Block),
+            is_allowed,
+            any_allowed,
+        };
+
+        match rejection(config, &domain, rules) {
+            Some(rejection) => {
+                tracing::info!("Registration from {domain} refused: {}", rejection.code());
+                Err(AuthenticationError::EmailDomainRejected(rejection))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// What the admin's rules in the database say about a domain
+#[derive(Debug, Clone, Copy, Default)]
+struct DomainRules {
+    is_blocked: bool,
+    is_allowed: bool,
+    any_allowed: bool,
+}
+
+/// Why the domain can't register, if it can't
+fn rejection(
+    config: &EmailDomainsConfiguration,
+    domain: &domain::EmailDomain,
+    rules: DomainRules,
+) -> Option<EmailDomainRejection> {
+    let is_listed = |domains: &[String]| {
+        domains
+            .iter()
+            .filter_map(|listed| domain::EmailDomain::parse(listed.as_str()).ok())
+            .any(|listed| domain.is_within(listed.as_ref()))
+    };
+
+    if rules.is_blocked || is_listed(&config.blocked) {
+        return Some(EmailDomainRejection::Blocked);
+    }
+
+    if rules.is_allowed || is_listed(&config.allowed) {
+        return None;
+    }
+
+    if rules.any_allowed || !config.allowed.is_empty() {
+        return Some(EmailDomainRejection::NotAllowed);
+    }
+
+    let is_disposable = DISPOSABLE_EMAIL_DOMAINS
+        .iter()
+        .any(|disposable| domain.is_within(disposable))
+        || is_listed(&config.disposable);
+    if config.block_disposable && is_disposable {
+        return Some(EmailDomainRejection::Disposable);
+    }
+
+    None
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    fn email_domain(domain: &str) -> Result<domain::EmailDomain> {
+        Ok(domain::EmailDomain::parse(domain)?)
+    }
+
+    #[test]
+    fn any_domain_registers_by_default() -> Result<()> {
+        let config = EmailDomainsConfiguration::default();
+        let rules = DomainRules::default();
+
+        assert_eq!(rejection(&config, &email_domain("example.com")?, rules), None);
+        assert_eq!(rejection(&config, &email_domain("mailinator.com")?, rules), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn blocked_domains_win_over_allowed_domains() -> Result<()> {
+        let config = EmailDomainsConfiguration {
+            allowed: vec!["example.com".to_string()],
+            blocked: vec!["@Test.Example.com".to_string()],
+            ..Default::default()
+        };
+        let rules = DomainRules::default();
+
+        assert_eq!(rejection(&config, &email_domain("mail.example.com")?, rules), None);
+        assert_eq!(
+            rejection(&config, &email_domain("a.test.example.com")?, rules),
+            Some(EmailDomainRejection::Blocked)
+        );
+        assert_eq!(
+            rejection(&config, &email_domain("example.org")?, rules),
+            Some(EmailDomainRejection::NotAllowed)
+        );
+        assert_eq!(
+            rejection(
+                &config,
+                &email_domain("example.com")?,
+                DomainRules { is_blocked: true, ..rules }
+            ),
+            Some(EmailDomainRejection::Blocked)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn rules_in_the_database_limit_registration() -> Result<()> {
+        let config = EmailDomainsConfiguration::default();
+        let only_others_allowed = DomainRules { any_allowed: true, ..Default::default() };
+        let allowed = DomainRules { is_allowed: true, any_allowed: true, ..Default::default() };
+
+        assert_eq!(
+            rejection(&config, &email_domain("example.com")?, only_others_allowed),
+            Some(EmailDomainRejection::NotAllowed)
+        );
+        assert_eq!(rejection(&config, &email_domain("example.com")?, allowed), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn disposable_domains_are_blocked_unless_allowed() -> Result<()> {
+        let config = EmailDomainsConfiguration {
+            block_disposable: true,
+            disposable: vec!["burner.example".to_string()],
+            ..Default::default()
+        };
+        let rules = DomainRules::default();
+
+        assert_eq!(
+            rejection(&config, &email_domain("mailinator.com")?, rules),
+            Some(EmailDomainRejection::Disposable)
+        );
+        assert_eq!(
+            rejection(&config, &email_domain("x.burner.example")?, rules),
+            Some(EmailDomainRejection::Disposable)
+        );
+        assert_eq!(rejection(&config, &email_domain("example.com")?, rules), None);
+        assert_eq!(
+            rejection(
+                &config,
+                &email_domain("yopmail.com")?,
+                DomainRules { is_allowed: true, any_allowed: true, ..rules }
+            ),
+            None
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn admin_rules_are_checked(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let guard = EmailDomainGuard::new(Arc::new(database.clone()));
+        let config = EmailDomainsConfiguration::default();
+        let blocked = database::EmailDomains::mock_data(EmailDomainRule::Block)?
+            .upsert(&database)
+            .await?;
+        let blocked_email = domain::EmailAddress::parse(format!("jane@{}", blocked.domain))?;
+        let other_email = domain::EmailAddress::parse("jane@example.org")?;
+
+        //-- Execute Function (Act)
+        let refused = guard.check(&config, &blocked_email).await;
+        let accepted = guard.check(&config, &other_email).await;
+
+        //-- Checks (Assertions)
+        assert!(matches!(
+            refused,
+            Err(AuthenticationError::EmailDomainRejected(EmailDomainRejection::Blocked))
+        ));
+        assert!(accepted.is_ok());
+
+        Ok(())
+    }
+}
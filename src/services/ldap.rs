@@ -0,0 +1,385 @@
+//-- ./src/services/ldap.rs
+
+// #![allow(unused)] // For development only
+
+//! # LDAP
+//!
+//! Optional password login against an LDAP or Active Directory server,
+//! alongside local passwords. When a login doesn't match a local user and
+//! password, and `ldap.enabled` is set:
+//!
+//! 1. Bind as the `ldap.bind_dn` service account (or anonymously) and search
+//!    `ldap.base_dn` with `ldap.search_filter` for the user's entry.
+//! 2. Bind as the user's entry with the login password.
+//! 3. Return the local user with the email, provisioning a shadow user on
+//!    their first login, see `provisioning`.
+//!
+//! The directory is reached through `ldap3`, behind the `ldap` feature.
+//! ---
+
+use std::sync::Arc;
+
+use secrecy::{ExposeSecret, SecretString};
+use sqlx::{Pool, Postgres};
+
+use crate::configuration::LdapConfiguration;
+use crate::events::AuthEvents;
+use crate::prelude::*;
+use crate::services::provisioning::provision_shadow_user;
+use crate::{database, domain};
+
+/// A user entry found in the directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct DirectoryUser {
+    /// The entry's `ldap.email_attribute`
+    pub email: Option<String>,
+    /// The entry's `ldap.name_attribute`
+    pub name: Option<String>,
+}
+
+/// Checks passwords against a directory
+#[tonic::async_trait]
+pub trait Directory: Send + Sync {
+    /// Check the password of the directory user found by the email, returning
+    /// their entry, or `None` when the user is unknown or the password is wrong
+    async fn authenticate(
+        &self,
+        config: &LdapConfiguration,
+        email: &str,
+        password: &SecretString,
+    ) -> Result<Option<DirectoryUser>, AuthenticationError>;
+}
+
+/// Escape a value for an LDAP search filter, see RFC 4515
+pub fn escape_filter_value(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '\\' => "\\5c".to_string(),
+            '*' => "\\2a".to_string(),
+            '(' => "\\28".to_string(),
+            ')' => "\\29".to_string(),
+            '\0' => "\\00".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// The search filter for the login email
+pub fn search_filter(config: &LdapConfiguration, email: &str) -> String {
+    config
+        .search_filter
+        .replace("{email}", &escape_filter_value(email))
+}
+
+#[cfg(feature = "ldap")]
+pub use ldap3_directory::LdapDirectory;
+
+#[cfg(feature = "ldap")]
+mod ldap3_directory {
+    use std::time::Duration;
+
+    use ldap3::{LdapConnAsync, LdapConnSettings, LdapError, Scope, SearchEntry};
+    use secrecy::{ExposeSecret, SecretString};
+
+    use super::{search_filter, Directory, DirectoryUser};
+    use crate::configuration::LdapConfiguration;
+    use crate::prelude::*;
+
+    /// LDAP result code for a wrong password
+    const INVALID_CREDENTIALS: u32 = 49;
+
+    impl From<LdapError> for AuthenticationError {
+        fn from(error: LdapError) -> Self {
+            AuthenticationError::Directory(error.to_string())
+        }
+    }
+
+    /// Checks passwords against the configured LDAP server
+    pub struct LdapDirectory;
+
+    #[tonic::async_trait]
+    impl Directory for LdapDirectory {
+        async fn authenticate(
+            &self,
+            config: &LdapConfiguration,
+            email: &str,
+            password: &SecretString,
+        ) -> Result<Option<DirectoryUser>, AuthenticationError> {
+            let timeout = Duration::from_secs(config.timeout_seconds);
+            let settings = LdapConnSettings::new().set_conn_timeout(timeout);
+            let (connection, mut ldap) = LdapConnAsync::with_settings(settings, &config.url).await?;
+            ldap3::drive!(connection);
+
+            // Find the user's entry as the service account
+            if let Some(bind_dn) = &config.bind_dn {
+                let bind_password = config
+                    .bind_password
+                    .as_ref()
+                    .map(|password| password.expose_secret().to_string())
+                    .unwrap_or_default();
+                ldap.with_timeout(timeout)
+                    .simple_bind(bind_dn, &bind_password)
+                    .await?
+                    .success()?;
+            }
+            let (entries, _result) = ldap
+                .with_timeout(timeout)
+                .search(
+                    &config.base_dn,
+                    Scope::Subtree,
+                    &search_filter(config, email),
+                    vec![config.email_attribute.as_str(), config.name_attribute.as_str()],
+                )
+                .await?
+                .success()?;
+
+            // An unknown or ambiguous email is not a directory user
+            let [entry] = entries.as_slice() else {
+                tracing::debug!("Directory entries found for {email}: {}", entries.len());
+                let _ = ldap.unbind().await;
+                return Ok(None);
+            };
+            let entry = SearchEntry::construct(entry.clone());
+
+            // Then check the password by binding as the user
+            let bind = ldap
+                .with_timeout(timeout)
+                .simple_bind(&entry.dn, password.expose_secret())
+                .await?;
+            let _ = ldap.unbind().await;
+            if bind.rc == INVALID_CREDENTIALS {
+                tracing::debug!("Directory password rejected for {}", entry.dn);
+                return Ok(None);
+            }
+            bind.success()?;
+
+            let attribute = |name: &str| entry.attrs.get(name).and_then(|values| values.first()).cloned();
+
+            Ok(Some(DirectoryUser {
+                email: attribute(&config.email_attribute),
+                name: attribute(&config.name_attribute),
+            }))
+        }
+    }
+}
+
+/// Stands in for the directory when the `ldap` feature is not built, the
+/// configuration is rejected before it is used
+#[cfg(not(feature = "ldap"))]
+struct UnavailableDirectory;
+
+#[cfg(not(feature = "ldap"))]
+#[tonic::async_trait]
+impl Directory for UnavailableDirectory {
+    async fn authenticate(
+        &self,
+        _config: &LdapConfiguration,
+        _email: &str,
+        _password: &SecretString,
+    ) -> Result<Option<DirectoryUser>, AuthenticationError> {
+        Err(AuthenticationError::Directory(
+            "ldap.enabled requires the `ldap` feature".to_string(),
+        ))
+    }
+}
+
+/// Logs users in with their directory password, cheap to clone into each service
+#[derive(Clone)]
+pub struct LdapLogin {
+    database: Arc<Pool<Postgres>>,
+    events: AuthEvents,
+    directory: Arc<dyn Directory>,
+}
+
+impl LdapLogin {
+    /// Create an LDAP login checking passwords against the configured server
+    pub fn new(database: Arc<Pool<Postgres>>, events: AuthEvents) -> Self {
+        #[cfg(feature = "ldap")]
+        let directory = Arc::new(LdapDirectory);
+        #[cfg(not(feature = "ldap"))]
+        let directory = Arc::new(UnavailableDirectory);
+
+        Self::with_directory(database, events, directory)
+    }
+
+    /// Create an LDAP login with a different directory, e.g. in tests
+    pub fn with_directory(
+        database: Arc<Pool<Postgres>>,
+        events: AuthEvents,
+        directory: Arc<dyn Directory>,
+    ) -> Self {
+        Self {
+            database,
+            events,
+            directory,
+        }
+    }
+
+    /// # LDAP Login
+    ///
+    /// Check the password with the directory, returning the local user and
+    /// provisioning them on their first login. Returns `None` when LDAP is not
+    /// enabled, or the directory rejects the email and password.
+    ///
+    /// ## Parameters
+    ///
+    /// - `config: &LdapConfiguration` - The current LDAP configuration
+    /// - `email: &str` - The login email
+    /// - `password: &SecretString` - The login password
+    pub async fn login(
+        &self,
+        config: &LdapConfiguration,
+        email: &str,
+        password: &SecretString,
+    ) -> Result<Option<database::Users>, AuthenticationError> {
+        // An empty password is an anonymous bind, which many servers accept
+        if !config.enabled || password.expose_secret().is_empty() {
+            return Ok(None);
+        }
+
+        let Some(directory_user) = self.directory.authenticate(config, email, password).await?
+        else {
+            return Ok(None);
+        };
+
+        let email = domain::EmailAddress::parse(
+            directory_user.email.as_deref().unwrap_or(email),
+        )?;
+
+        match database::Users::from_user_email(&email, &self.database).await {
+            Ok(user) => Ok(Some(user)),
+            Err(AuthenticationError::Sqlx(sqlx::Error::RowNotFound)) => {
+                provision_shadow_user(
+                    &self.database,
+                    &self.events,
+                    email,
+                    directory_user.name.as_deref(),
+                )
+                .await
+                .map(Some)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    const PASSWORD: &str = "Directory-Passw0rd";
+
+    /// A directory with one user
+    struct StaticDirectory(DirectoryUser);
+
+    #[tonic::async_trait]
+    impl Directory for StaticDirectory {
+        async fn authenticate(
+            &self,
+            _config: &LdapConfiguration,
+            _email: &str,
+            password: &SecretString,
+        ) -> Result<Option<DirectoryUser>, AuthenticationError> {
+            Ok((password.expose_secret() == PASSWORD).then(|| self.0.clone()))
+        }
+    }
+
+    fn ldap_login(database: Pool<Postgres>, email: &str) -> LdapLogin {
+        LdapLogin::with_directory(
+            Arc::new(database),
+            AuthEvents::default(),
+            Arc::new(StaticDirectory(DirectoryUser {
+                email: Some(email.to_string()),
+                name: Some("Directory User".to_string()),
+            })),
+        )
+    }
+
+    fn enabled() -> LdapConfiguration {
+        LdapConfiguration {
+            enabled: true,
+            base_dn: "ou=people,dc=example,dc=com".to_string(),
+            ..LdapConfiguration::default()
+        }
+    }
+
+    #[test]
+    fn search_filter_escapes_the_email() {
+        let config = enabled();
+
+        assert_eq!(
+            search_filter(&config, "jane@example.com"),
+            "(mail=jane@example.com)"
+        );
+        assert_eq!(
+            search_filter(&config, "*)(uid=*"),
+            "(mail=\\2a\\29\\28uid=\\2a)"
+        );
+    }
+
+    #[sqlx::test]
+    async fn first_login_provisions_a_verified_user(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let email = domain::EmailAddress::mock_data()?;
+        let ldap = ldap_login(database.clone(), email.as_ref());
+        let password = SecretString::from(PASSWORD);
+
+        //-- Execute Function (Act)
+        let first = ldap
+            .login(&enabled(), email.as_ref(), &password)
+            .await?
+            .ok_or("directory login failed")?;
+        let second = ldap
+            .login(&enabled(), email.as_ref(), &password)
+            .await?
+            .ok_or("directory login failed")?;
+
+        //-- Checks (Assertions)
+        assert_eq!(first.id, second.id);
+        assert_eq!(first.email, email);
+        assert_eq!(first.name.as_ref(), "Directory User");
+        assert!(first.is_active);
+        assert!(first.is_verified);
+        // Shadow users can't log in with a local password
+        assert_eq!(first.password_hash, domain::PasswordHash::dummy());
+        assert_eq!(database::Users::count(&database).await?, 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn rejected_or_disabled_logins_return_none(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let email = domain::EmailAddress::mock_data()?;
+        let ldap = ldap_login(database.clone(), email.as_ref());
+
+        //-- Execute Function (Act)
+        let wrong_password = ldap
+            .login(&enabled(), email.as_ref(), &SecretString::from("Wrong-Passw0rd"))
+            .await?;
+        let empty_password = ldap
+            .login(&enabled(), email.as_ref(), &SecretString::from(""))
+            .await?;
+        let disabled = ldap
+            .login(
+                &LdapConfiguration::default(),
+                email.as_ref(),
+                &SecretString::from(PASSWORD),
+            )
+            .await?;
+
+        //-- Checks (Assertions)
+        assert!(wrong_password.is_none());
+        assert!(empty_password.is_none());
+        assert!(disabled.is_none());
+        assert_eq!(database::Users::count(&database).await?, 0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,351 @@
+//-- ./src/services/login_throttle.rs
+
+// #![allow(unused)] // For development only
+
+//! # Login Throttle
+//!
+//! Adaptive throttling of failed logins, to blunt credential stuffing and
+//! password guessing.
+//!
+//! Failed logins are counted per IP address and email in the
+//! `login_throttles` table, so every instance sees the same counts. After
+//! `login_throttle.free_attempts` failures the pair is locked out, starting
+//! at `login_throttle.base_delay_seconds` and doubling with each further
+//! failure up to `login_throttle.max_delay_seconds`. Locked out logins are
+//! rejected with `RESOURCE_EXHAUSTED` and a `retry-after` metadata hint in
+//! seconds. A successful login, or `login_throttle.reset_after_seconds`
+//! without a failure, starts the count again. Login responses carry the
+//! `x-ratelimit-*` metadata (see `utils::rate_limit`), so clients can back off
+//! before they are locked out.
+//!
+//! ## Metrics
+//! - `auth.login.failures` - failed logins
+//! - `auth.login.throttled` - logins rejected while locked out
+//! ---
+
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use chrono::Utc;
+use opentelemetry::metrics::Counter;
+use sqlx::{Pool, Postgres};
+
+use crate::configuration::LoginThrottleConfiguration;
+use crate::database;
+use crate::prelude::*;
+use crate::utils::backoff::retry_delay;
+use crate::utils::rate_limit::RateLimit;
+
+/// Counts and locks out failed logins, cheap to clone into each service
+#[derive(Clone)]
+pub struct LoginThrottle {
+    database: Arc<Pool<Postgres>>,
+    failures: Counter<u64>,
+    throttled: Counter<u64>,
+}
+
+impl LoginThrottle {
+    /// Create a throttle recording failed logins in the database
+    pub fn new(database: Arc<Pool<Postgres>>) -> Self {
+        let meter = opentelemetry::global::meter("authentication_service");
+
+        Self {
+            database,
+            failures: meter
+                .u64_counter("auth.login.failures")
+                .with_description("Failed logins")
+                .build(),
+            throttled: meter
+                .u64_counter("auth.login.throttled")
+                .with_description("Logins rejected while locked out")
+                .build(),
+        }
+    }
+
+    /// Reject the login while the IP address and email are locked out
+    ///
+    /// ## Parameters
+    ///
+    /// - `config: &LoginThrottleConfiguration` - The current throttle configuration
+    /// - `ip: IpAddr` - The address the login came from
+    /// - `email: &str` - The email the login is for
+    pub async fn check(
+        &self,
+        config: &LoginThrottleConfiguration,
+        ip: IpAddr,
+        email: &str,
+    ) -> Result<(), AuthenticationError> {
+        if !config.enabled {
+            return Ok(());
+        }
+
+        let throttle = database::LoginThrottles::from_key(
+            &ip.to_string(),
+            &throttle_email(email),
+            &self.database,
+        )
+        .await?;
+
+        if let Some(retry_after) =
+            throttle.and_then(|throttle| throttle.retry_after())
+        {
+            // Round up, so the client does not retry a moment too early
+            let retry_after =
+                (retry_after.num_milliseconds() as u64).div_ceil(1_000);
+            tracing::info!("Login from {ip} throttled for {retry_after} seconds");
+            self.throttled.add(1, &[]);
+            return Err(AuthenticationError::LoginThrottled(retry_after));
+        }
+
+        Ok(())
+    }
+
+    /// The rate limit state of the IP address and email, `None` when the
+    /// throttle is disabled. The limit is the free attempts, and the reset
+    /// is when the lock out ends or, when not locked out, the failures are
+    /// forgotten.
+    pub async fn rate_limit(
+        &self,
+        config: &LoginThrottleConfiguration,
+        ip: IpAddr,
+        email: &str,
+    ) -> Result<Option<RateLimit>, AuthenticationError> {
+        if !config.enabled {
+            return Ok(None);
+        }
+
+        let throttle = database::LoginThrottles::from_key(
+            &ip.to_string(),
+            &throttle_email(email),
+            &self.database,
+        )
+        .await?;
+
+        let rate_limit = match throttle {
+            None => RateLimit {
+                limit: config.free_attempts,
+                remaining: config.free_attempts,
+                reset_seconds: 0,
+            },
+            Some(throttle) => {
+                let (remaining, reset) = match throttle.retry_after() {
+                    Some(retry_after) => (0, retry_after),
+                    None => (
+                        config
+                            .free_attempts
+                            .saturating_sub(throttle.failures.max(0) as u32),
+                        throttle.last_failure_at
+                            + chrono::Duration::seconds(config.reset_after_seconds as i64)
+                            - Utc::now(),
+                    ),
+                };
+                RateLimit {
+                    limit: config.free_attempts,
+                    remaining,
+                    // Round up, so the client does not retry a moment too early
+                    reset_seconds: (reset.num_milliseconds().max(0) as u64).div_ceil(1_000),
+                }
+            }
+        };
+
+        Ok(Some(rate_limit))
+    }
+
+    /// Count a failed login, locking out the IP address and email once the
+    /// free attempts are used up
+    pub async fn record_failure(
+        &self,
+        config: &LoginThrottleConfiguration,
+        ip: IpAddr,
+        email: &str,
+    ) -> Result<(), AuthenticationError> {
+        self.failures.add(1, &[]);
+
+        if !config.enabled {
+            return Ok(());
+        }
+
+        let throttle = database::LoginThrottles::record_failure(
+            &ip.to_string(),
+            &throttle_email(email),
+            &config.reset_after_seconds,
+            &self.database,
+        )
+        .await?;
+
+        let over_limit = throttle.failures - config.free_attempts as i32;
+        if over_limit > 0 {
+            let delay = retry_delay(config.base_delay_seconds, over_limit)
+                .min(chrono::Duration::seconds(config.max_delay_seconds as i64));
+            throttle
+                .lock_until(&(Utc::now() + delay), &self.database)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Forget the failed logins for the IP address and email, after a
+    /// successful login
+    pub async fn clear(
+        &self,
+        ip: IpAddr,
+        email: &str,
+    ) -> Result<(), AuthenticationError> {
+        database::LoginThrottles::delete_key(
+            &ip.to_string(),
+            &throttle_email(email),
+            &self.database,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Emails are compared case insensitively, so `A@example.com` and
+/// `a@example.com` share a count
+fn throttle_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn logins_are_locked_out_after_the_free_attempts(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let throttle = LoginThrottle::new(Arc::new(database));
+        let config = LoginThrottleConfiguration {
+            free_attempts: 2,
+            base_delay_seconds: 60,
+            ..Default::default()
+        };
+        let ip: IpAddr = "203.0.113.7".parse()?;
+
+        //-- Execute Function (Act)
+        throttle
+            .record_failure(&config, ip, "A@example.com")
+            .await?;
+        throttle
+            .record_failure(&config, ip, "a@example.com")
+            .await?;
+        let free = throttle.check(&config, ip, "a@example.com").await;
+        throttle
+            .record_failure(&config, ip, "a@example.com")
+            .await?;
+        let locked = throttle.check(&config, ip, "a@example.com").await;
+        let other_email = throttle.check(&config, ip, "b@example.com").await;
+        throttle.clear(ip, "a@example.com").await?;
+        let cleared = throttle.check(&config, ip, "a@example.com").await;
+
+        //-- Checks (Assertions)
+        assert!(free.is_ok());
+        assert!(matches!(
+            locked,
+            Err(AuthenticationError::LoginThrottled(seconds)) if seconds > 0 && seconds <= 60
+        ));
+        assert!(other_email.is_ok());
+        assert!(cleared.is_ok());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn lock_outs_double_up_to_the_maximum(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let throttle = LoginThrottle::new(Arc::new(database));
+        let config = LoginThrottleConfiguration {
+            free_attempts: 0,
+            base_delay_seconds: 100,
+            max_delay_seconds: 300,
+            ..Default::default()
+        };
+        let ip: IpAddr = "203.0.113.7".parse()?;
+        let mut retry_afters = Vec::new();
+
+        //-- Execute Function (Act)
+        for _ in 0..4 {
+            throttle
+                .record_failure(&config, ip, "a@example.com")
+                .await?;
+            if let Err(AuthenticationError::LoginThrottled(seconds)) =
+                throttle.check(&config, ip, "a@example.com").await
+            {
+                retry_afters.push(seconds);
+            }
+        }
+
+        //-- Checks (Assertions)
+        assert_eq!(retry_afters, vec![100, 200, 300, 300]);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn rate_limits_count_down_the_free_attempts(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let throttle = LoginThrottle::new(Arc::new(database));
+        let config = LoginThrottleConfiguration {
+            free_attempts: 2,
+            base_delay_seconds: 60,
+            reset_after_seconds: 900,
+            ..Default::default()
+        };
+        let ip: IpAddr = "203.0.113.7".parse()?;
+
+        //-- Execute Function (Act)
+        let before = throttle.rate_limit(&config, ip, "a@example.com").await?;
+        throttle
+            .record_failure(&config, ip, "a@example.com")
+            .await?;
+        let failed = throttle.rate_limit(&config, ip, "a@example.com").await?;
+        for _ in 0..2 {
+            throttle
+                .record_failure(&config, ip, "a@example.com")
+                .await?;
+        }
+        let locked = throttle.rate_limit(&config, ip, "a@example.com").await?;
+        let disabled = throttle
+            .rate_limit(
+                &LoginThrottleConfiguration {
+                    enabled: false,
+                    ..config.clone()
+                },
+                ip,
+                "a@example.com",
+            )
+            .await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(
+            before,
+            Some(RateLimit {
+                limit: 2,
+                remaining: 2,
+                reset_seconds: 0
+            })
+        );
+        let failed = failed.ok_or("no rate limit")?;
+        assert_eq!((failed.limit, failed.remaining), (2, 1));
+        assert!(failed.reset_seconds > 0 && failed.reset_seconds <= 900);
+        let locked = locked.ok_or("no rate limit")?;
+        assert_eq!(locked.remaining, 0);
+        assert!(locked.reset_seconds > 0 && locked.reset_seconds <= 60);
+        assert_eq!(disabled, None);
+
+        Ok(())
+    }
+}
@@ -6,10 +6,25 @@
 /// It is the main entry point for all service-related functionality.
 ///
 /// ## Services
+/// - **AdminService**: Admin only endpoints such as bulk user import and export.
 /// - **AuthenticationService**: Handles user authentication and authorization.
+/// - **CaptchaGuard**: Checks CAPTCHA tokens on register and login when needed.
+/// - **EmailDomainGuard**: Checks the email domain of registrations against the allow and block lists.
+/// - **DeviceAuthorization**: Runs the OAuth device authorization grant for CLI and IoT clients.
+/// - **LdapLogin**: Checks passwords against an LDAP directory, provisioning users.
+/// - **LoginThrottle**: Locks out repeated failed logins per IP address and email.
+/// - **password_reset**: Issues the emailed password reset links.
+/// - **PasswordResetLimiter**: Limits password reset requests per email and IP address.
+/// - **PasswordHasher**: Runs argon2 password hashing on the blocking thread pool.
+/// - **Passkeys**: Runs WebAuthn passkey registration and login ceremonies.
+/// - **SamlLogin**: Runs SAML 2.0 single sign on as the service provider.
+/// - **OutboxDispatcher**: Processes the emails and events queued in the outbox.
 /// - **SessionsService**: Manages user sessions and session-related data.
 /// - **UsersService**: Manages user data and user-related operations.
 /// - **UtilitiesService**: Provides utility functions and helpers.
+/// - **validation**: Checks request fields before the handlers act on them.
+/// - **VerificationReminders**: Reminds unverified users, deactivating them after a deadline.
+/// - **WebhookDispatcher**: Delivers authentication events to webhook endpoints.
 ///
 /// ## References
 /// - [govinda-attal/app-a](https://github.com/govinda-attal/app-a/tree/2-grpc-server)
@@ -17,12 +32,41 @@
 // #![allow(unused)] // For beginning only.
 
 // Flatten module exports
+pub use admin::AdminService;
 pub use authentication::AuthenticationService;
+pub use captcha::CaptchaGuard;
+pub use device_authorization::DeviceAuthorization;
+pub use email_domains::EmailDomainGuard;
+pub use ldap::LdapLogin;
+pub use login_throttle::LoginThrottle;
+pub use outbox::OutboxDispatcher;
+pub use passkeys::Passkeys;
+pub use password_hasher::PasswordHasher;
+pub use password_reset_limiter::PasswordResetLimiter;
+pub use saml::SamlLogin;
 pub use sessions::SessionsService;
 pub use users::UsersService;
 pub use utilities::UtilitiesService;
+pub use verification_reminders::VerificationReminders;
+pub use webhooks::WebhookDispatcher;
 
+mod admin;
 mod authentication;
+pub mod captcha;
+pub mod device_authorization;
+pub mod email_domains;
+pub mod ldap;
+pub mod login_throttle;
+pub mod outbox;
+pub mod passkeys;
+pub mod password_hasher;
+pub mod password_reset;
+pub mod password_reset_limiter;
+pub mod provisioning;
+pub mod saml;
+pub mod validation;
+pub mod verification_reminders;
 mod sessions;
 mod users;
 mod utilities;
+pub mod webhooks;
@@ -0,0 +1,357 @@
+//-- ./src/services/outbox.rs
+
+// #![allow(unused)] // For development only
+
+//! # Outbox
+//!
+//! Process the side effects queued in the transactional outbox, so emails and
+//! webhook events are not lost if the process stops mid-request.
+//!
+//! Services write an `Outbox` entry in the same transaction as the change that
+//! triggers it, so the entry exists if, and only if, the change was saved. The
+//! `OutboxDispatcher` polls the `outbox` table and processes each due entry:
+//!
+//! | Outbox message | Processed by                                       |
+//! |----------------|----------------------------------------------------|
+//! | `Email`        | Sent with the configured `EmailClient`             |
+//! | `Webhook`      | Published to the event bus, when one is configured |
+//! |                | (`event_bus`), then queued for the subscribed      |
+//! |                | endpoints (`webhooks`)                             |
+//!
+//! Processed entries are marked `processed`. Failed attempts are retried with
+//! an exponential backoff, starting at `outbox.retry_base_seconds`, until
+//! `outbox.max_attempts` is reached and the entry is marked `failed`.
+//! ---
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use sqlx::{Pool, Postgres};
+
+use crate::configuration::SharedConfiguration;
+use crate::database::{Outbox, OutboxMessage, OutboxStatus};
+use crate::email::EmailClient;
+use crate::event_bus::{self, EventPublisher};
+use crate::events::AuthEvent;
+use crate::prelude::*;
+use crate::services::WebhookDispatcher;
+use crate::utils::backoff;
+
+/// How many due entries are claimed per poll
+const CLAIM_BATCH_SIZE: usize = 100;
+
+/// How long a claimed entry is held before another dispatcher can retry it
+const CLAIM_LEASE_SECONDS: u64 = 300;
+
+/// Processes the entries queued in the transactional outbox
+#[derive(Clone)]
+pub struct OutboxDispatcher {
+    database: Pool<Postgres>,
+    config: SharedConfiguration,
+    email: Arc<dyn EmailClient>,
+    webhooks: WebhookDispatcher,
+    event_bus: Option<Arc<dyn EventPublisher>>,
+}
+
+impl OutboxDispatcher {
+    /// Create a new dispatcher sending emails with `email` and queueing webhook
+    /// events with `webhooks`
+    pub fn new(
+        database: Pool<Postgres>,
+        config: SharedConfiguration,
+        email: Arc<dyn EmailClient>,
+        webhooks: WebhookDispatcher,
+    ) -> Self {
+        Self {
+            database,
+            config,
+            email,
+            webhooks,
+            event_bus: None,
+        }
+    }
+
+    /// Send emails with another client, such as a `MockEmailClient` in tests
+    pub fn with_email_client(mut self, email: Arc<dyn EmailClient>) -> Self {
+        self.email = email;
+        self
+    }
+
+    /// Also publish authentication events to the event bus
+    pub fn with_event_bus(mut self, publisher: Arc<dyn EventPublisher>) -> Self {
+        self.event_bus = Some(publisher);
+        self
+    }
+
+    /// Process every entry that is due, returning how many were attempted
+    pub async fn process_due(&self) -> Result<usize, AuthenticationError> {
+        let entries = Outbox::claim_due(
+            &CLAIM_BATCH_SIZE,
+            &CLAIM_LEASE_SECONDS,
+            &self.database,
+        )
+        .await?;
+        let attempted = entries.len();
+
+        for entry in entries {
+            if let Err(e) = self.process(&entry).await {
+                tracing::error!(
+                    "Outbox entry {} could not be processed: {e}",
+                    entry.id
+                );
+            }
+        }
+
+        Ok(attempted)
+    }
+
+    /// Carry out an entry's side effect and record the outcome
+    #[tracing::instrument(name = "Process outbox entry: ", skip(self, entry), fields(id = %entry.id, kind = %entry.kind))]
+    async fn process(&self, entry: &Outbox) -> Result<Outbox, AuthenticationError> {
+        // Load the current configuration, retries can change at runtime
+        let config = self.config.load_full();
+
+        let outcome = match entry.message() {
+            OutboxMessage::Email(message) => self.email.send(message).await,
+            OutboxMessage::Webhook(event) => self.dispatch_event(event).await,
+        };
+        let error = outcome.err().map(|e| e.to_string());
+
+        let attempts = entry.attempts + 1;
+        let (status, next_attempt_at) = match &error {
+            None => (OutboxStatus::Processed, Utc::now()),
+            Some(_) if attempts >= config.outbox.max_attempts as i32 => {
+                (OutboxStatus::Failed, Utc::now())
+            }
+            Some(_) => (
+                OutboxStatus::Pending,
+                Utc::now()
+                    + backoff::retry_delay(
+                        config.outbox.retry_base_seconds,
+                        attempts,
+                    ),
+            ),
+        };
+
+        if let Some(error) = &error {
+            tracing::warn!(
+                "Outbox {} attempt {attempts} failed, now {status}: {error}",
+                entry.kind
+            );
+        }
+
+        entry
+            .record_attempt(
+                status,
+                error.as_deref(),
+                next_attempt_at,
+                &self.database,
+            )
+            .await
+    }
+
+    /// Publish an authentication event to the event bus, if configured, and
+    /// queue it for the webhook endpoints
+    async fn dispatch_event(
+        &self,
+        event: &AuthEvent,
+    ) -> Result<(), AuthenticationError> {
+        if let Some(publisher) = &self.event_bus {
+            let config = self.config.load();
+            event_bus::publish(publisher.as_ref(), &config.event_bus, event).await?;
+        }
+
+        self.webhooks.enqueue(event).await?;
+
+        Ok(())
+    }
+
+    /// Process the outbox in the background
+    pub fn spawn(self) {
+        let poll_interval =
+            Duration::from_secs(self.config.load().outbox.poll_interval_seconds);
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(poll_interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.process_due().await {
+                    tracing::error!("Unable to process the outbox: {e}");
+                }
+            }
+        });
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Mutex;
+
+    use uuid::Uuid;
+
+    use crate::configuration::Configuration;
+    use crate::database;
+    use crate::domain;
+    use crate::email::EmailMessage;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    /// Email client recording the messages sent, or failing every send
+    #[derive(Default)]
+    struct RecordingEmailClient {
+        fail: bool,
+        sent: Mutex<Vec<EmailMessage>>,
+    }
+
+    #[tonic::async_trait]
+    impl EmailClient for RecordingEmailClient {
+        async fn send(
+            &self,
+            message: &EmailMessage,
+        ) -> core::result::Result<(), AuthenticationError> {
+            if self.fail {
+                return Err(AuthenticationError::Generic(
+                    "relay refused".to_string(),
+                ));
+            }
+            self.sent.lock().unwrap().push(message.clone());
+            Ok(())
+        }
+    }
+
+    /// Event publisher recording the subjects published to
+    #[derive(Default)]
+    struct RecordingEventPublisher {
+        published: Mutex<Vec<(String, Uuid)>>,
+    }
+
+    #[tonic::async_trait]
+    impl EventPublisher for RecordingEventPublisher {
+        async fn publish(
+            &self,
+            subject: &str,
+            event: &AuthEvent,
+            _payload: &str,
+        ) -> core::result::Result<(), AuthenticationError> {
+            self.published
+                .lock()
+                .unwrap()
+                .push((subject.to_string(), event.id));
+            Ok(())
+        }
+    }
+
+    fn dispatcher(
+        database: Pool<Postgres>,
+        email: Arc<RecordingEmailClient>,
+    ) -> Result<OutboxDispatcher> {
+        let config = Configuration::parse()?.into_shared();
+        let webhooks = WebhookDispatcher::new(database.clone(), config.clone())?;
+        Ok(OutboxDispatcher::new(database, config, email, webhooks))
+    }
+
+    fn email_message() -> Result<EmailMessage> {
+        Ok(EmailMessage {
+            to: domain::EmailAddress::parse("someone@example.com")?,
+            subject: "Welcome".to_string(),
+            body_text: "Hello there".to_string(),
+        })
+    }
+
+    #[sqlx::test]
+    async fn emails_and_webhook_events_are_processed(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let email = Arc::new(RecordingEmailClient::default());
+        let dispatcher = dispatcher(database.clone(), Arc::clone(&email))?;
+        let endpoint = database::WebhookEndpoints::mock_data()
+            .insert(&database)
+            .await?;
+        Outbox::new(email_message()?).insert(&database).await?;
+        Outbox::mock_data().insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let first = dispatcher.process_due().await?;
+        let second = dispatcher.process_due().await?;
+
+        //-- Checks (Assertions)
+        assert_eq!((first, second), (2, 0));
+        assert_eq!(*email.sent.lock().unwrap(), vec![email_message()?]);
+
+        let deliveries = database::WebhookDeliveries::index_endpoint(
+            &endpoint.id,
+            &10,
+            &0,
+            &database,
+        )
+        .await?;
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].event_type, "user.login");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn failed_entries_are_retried_later(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let email = Arc::new(RecordingEmailClient {
+            fail: true,
+            ..Default::default()
+        });
+        let dispatcher = dispatcher(database.clone(), email)?;
+        let entry = Outbox::new(email_message()?).insert(&database).await?;
+
+        //-- Execute Function (Act)
+        let claimed = Outbox::claim_due(&10, &60, &database).await?;
+        let processed = dispatcher.process(&claimed[0]).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(processed.id, entry.id);
+        assert_eq!(processed.status, OutboxStatus::Pending);
+        assert_eq!(processed.attempts, 1);
+        assert!(processed
+            .last_error
+            .as_deref()
+            .is_some_and(|e| e.contains("relay refused")));
+        assert!(processed.next_attempt_at > Utc::now());
+        assert_eq!(dispatcher.process_due().await?, 0);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn events_are_published_to_the_event_bus(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let publisher = Arc::new(RecordingEventPublisher::default());
+        let dispatcher =
+            dispatcher(database.clone(), Arc::new(RecordingEmailClient::default()))?
+                .with_event_bus(publisher.clone());
+        let entry = Outbox::mock_data().insert(&database).await?;
+        let OutboxMessage::Webhook(event) = entry.message() else {
+            return Err("mock outbox entry is not an event".into());
+        };
+
+        //-- Execute Function (Act)
+        let attempted = dispatcher.process_due().await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(attempted, 1);
+        assert_eq!(
+            *publisher.published.lock().unwrap(),
+            vec![("auth.user.login".to_string(), event.id)]
+        );
+
+        Ok(())
+    }
+}
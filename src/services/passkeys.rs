@@ -0,0 +1,288 @@
+//-- ./src/services/passkeys.rs
+
+// #![allow(unused)] // For development only
+
+//! # Passkeys
+//!
+//! WebAuthn passkey registration and login, using
+//! [webauthn-rs](https://docs.rs/webauthn-rs). Each ceremony has two steps:
+//!
+//! 1. **Begin**: the options for `navigator.credentials.create()` or
+//!    `navigator.credentials.get()` are returned as JSON, with a challenge id.
+//!    The ceremony state is kept in `passkey_challenges`.
+//! 2. **Finish**: the client sends back the challenge id and the credential
+//!    JSON from the browser. The challenge is consumed, so it can only be
+//!    answered once, within `PASSKEY_CHALLENGE_EXPIRY_MINUTES`.
+//!
+//! Registered passkeys are kept in `webauthn_credentials`, and their sign
+//! count is updated after each login. The relying party is configured in
+//! `passkeys`, and is read on each request so it can be hot reloaded.
+//! ---
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use sqlx::{Pool, Postgres};
+use tonic::Status;
+use uuid::Uuid;
+use webauthn_rs::prelude::{
+    Passkey, PasskeyAuthentication, PasskeyRegistration, PublicKeyCredential,
+    RegisterPublicKeyCredential, Url,
+};
+use webauthn_rs::{Webauthn, WebauthnBuilder};
+
+use crate::configuration::PasskeysConfiguration;
+use crate::database;
+use crate::prelude::*;
+
+/// How long a passkey registration or login can take to finish
+const PASSKEY_CHALLENGE_EXPIRY_MINUTES: u64 = 5;
+
+/// A passkey ceremony that has begun, the client answers the options then
+/// finishes it with the challenge id
+#[derive(Debug, Clone)]
+pub struct PasskeyChallenge {
+    /// Sent back when finishing the ceremony
+    pub challenge_id: Uuid,
+    /// The WebAuthn options for the browser, as JSON
+    pub options: String,
+}
+
+/// Runs passkey registration and login ceremonies
+#[derive(Clone)]
+pub struct Passkeys {
+    database: Arc<Pool<Postgres>>,
+}
+
+impl Passkeys {
+    /// Create the passkey ceremonies, keeping their state in the database
+    pub fn new(database: Arc<Pool<Postgres>>) -> Self {
+        Self { database }
+    }
+
+    /// Build the relying party from the configuration. Passkeys that are not
+    /// enabled answer `UNIMPLEMENTED`.
+    fn webauthn(config: &PasskeysConfiguration) -> Result<Webauthn, Status> {
+        if !config.enabled {
+            return Err(Status::unimplemented("Passkeys are not enabled"));
+        }
+
+        let origin = Url::parse(&config.relying_party_origin).map_err(|e| {
+            tracing::error!("Invalid passkeys.relying_party_origin: {e}");
+            Status::internal("Internal server error")
+        })?;
+        let webauthn = WebauthnBuilder::new(&config.relying_party_id, &origin)
+            .and_then(|builder| builder.rp_name(&config.relying_party_name).build())
+            .map_err(|e| {
+                tracing::error!("Unable to build the passkey relying party: {e}");
+                Status::internal("Internal server error")
+            })?;
+
+        Ok(webauthn)
+    }
+
+    /// Keep the ceremony state until the ceremony is finished
+    async fn insert_challenge(
+        &self,
+        user_id: &Uuid,
+        ceremony: database::PasskeyCeremony,
+        state: &impl serde::Serialize,
+        options: &impl serde::Serialize,
+    ) -> Result<PasskeyChallenge, AuthenticationError> {
+        let challenge = database::PasskeyChallenges::new(
+            user_id,
+            ceremony,
+            state,
+            &Duration::from_secs(PASSKEY_CHALLENGE_EXPIRY_MINUTES * 60),
+        )?
+        .insert(self.database.as_ref())
+        .await?;
+
+        Ok(PasskeyChallenge {
+            challenge_id: challenge.id,
+            options: serde_json::to_string(options)?,
+        })
+    }
+
+    /// Consume the challenge for the ceremony, unknown, used and expired
+    /// challenges are unauthenticated
+    async fn consume_challenge(
+        &self,
+        challenge_id: &str,
+        ceremony: database::PasskeyCeremony,
+    ) -> Result<database::PasskeyChallenges, Status> {
+        let challenge_id = Uuid::try_parse(challenge_id)
+            .map_err(|_| Status::invalid_argument("Invalid challenge id"))?;
+
+        database::PasskeyChallenges::consume(&challenge_id, ceremony, self.database.as_ref())
+            .await?
+            .ok_or_else(|| {
+                tracing::error!("Passkey challenge is unknown or expired: {challenge_id}");
+                Status::unauthenticated("Authentication Failed!")
+            })
+    }
+
+    /// # Begin Passkey Registration
+    ///
+    /// Issue the options for creating a new passkey for the user. Passkeys the
+    /// user already has are excluded, so an authenticator isn't registered twice.
+    pub async fn begin_registration(
+        &self,
+        config: &PasskeysConfiguration,
+        user: &database::Users,
+    ) -> Result<PasskeyChallenge, Status> {
+        let webauthn = Self::webauthn(config)?;
+
+        let existing =
+            database::WebauthnCredentials::index_for_user(&user.id, self.database.as_ref())
+                .await?
+                .iter()
+                .map(|credential| Ok(credential.passkey()?.cred_id().clone()))
+                .collect::<Result<Vec<_>, AuthenticationError>>()?;
+
+        let (options, state) = webauthn
+            .start_passkey_registration(
+                user.id,
+                user.email.as_ref(),
+                user.name.as_ref(),
+                Some(existing),
+            )
+            .map_err(|e| {
+                tracing::error!("Unable to begin passkey registration: {e}");
+                Status::internal("Internal server error")
+            })?;
+
+        Ok(self
+            .insert_challenge(
+                &user.id,
+                database::PasskeyCeremony::Registration,
+                &state,
+                &options,
+            )
+            .await?)
+    }
+
+    /// # Finish Passkey Registration
+    ///
+    /// Check the credential created by the browser answers the user's
+    /// registration challenge, then save the new passkey.
+    pub async fn finish_registration(
+        &self,
+        config: &PasskeysConfiguration,
+        user_id: &Uuid,
+        challenge_id: &str,
+        credential: &str,
+        name: &str,
+    ) -> Result<database::WebauthnCredentials, Status> {
+        let webauthn = Self::webauthn(config)?;
+
+        let credential: RegisterPublicKeyCredential = serde_json::from_str(credential)
+            .map_err(|_| Status::invalid_argument("Invalid passkey credential"))?;
+
+        let challenge = self
+            .consume_challenge(challenge_id, database::PasskeyCeremony::Registration)
+            .await?;
+        if &challenge.user_id != user_id {
+            tracing::error!("Passkey challenge was issued to another user: {}", challenge.id);
+            return Err(Status::unauthenticated("Authentication Failed!"));
+        }
+        let state: PasskeyRegistration = challenge.state()?;
+
+        let passkey = webauthn
+            .finish_passkey_registration(&credential, &state)
+            .map_err(|e| {
+                tracing::error!("Passkey registration failed: {e}");
+                Status::invalid_argument("Passkey registration failed")
+            })?;
+
+        database::WebauthnCredentials::new(user_id, name, &passkey)?
+            .insert(self.database.as_ref())
+            .await
+            .map_err(|_| Status::already_exists("Passkey is already registered"))
+    }
+
+    /// # Begin Passkey Login
+    ///
+    /// Issue the options for logging in with one of the user's passkeys. Users
+    /// without a passkey are unauthenticated.
+    pub async fn begin_authentication(
+        &self,
+        config: &PasskeysConfiguration,
+        user: &database::Users,
+    ) -> Result<PasskeyChallenge, Status> {
+        let webauthn = Self::webauthn(config)?;
+
+        let passkeys =
+            database::WebauthnCredentials::index_for_user(&user.id, self.database.as_ref())
+                .await?
+                .iter()
+                .map(database::WebauthnCredentials::passkey)
+                .collect::<Result<Vec<Passkey>, AuthenticationError>>()?;
+        if passkeys.is_empty() {
+            tracing::error!("User has no passkeys: {}", user.id);
+            return Err(Status::unauthenticated("Authentication Failed!"));
+        }
+
+        let (options, state) = webauthn.start_passkey_authentication(&passkeys).map_err(|e| {
+            tracing::error!("Unable to begin passkey login: {e}");
+            Status::internal("Internal server error")
+        })?;
+
+        Ok(self
+            .insert_challenge(
+                &user.id,
+                database::PasskeyCeremony::Authentication,
+                &state,
+                &options,
+            )
+            .await?)
+    }
+
+    /// # Finish Passkey Login
+    ///
+    /// Check the credential signed by the browser answers the login challenge,
+    /// update the passkey's sign count and return the id of the user logging in.
+    pub async fn finish_authentication(
+        &self,
+        config: &PasskeysConfiguration,
+        challenge_id: &str,
+        credential: &str,
+    ) -> Result<Uuid, Status> {
+        let webauthn = Self::webauthn(config)?;
+
+        let credential: PublicKeyCredential = serde_json::from_str(credential)
+            .map_err(|_| Status::invalid_argument("Invalid passkey credential"))?;
+
+        let challenge = self
+            .consume_challenge(challenge_id, database::PasskeyCeremony::Authentication)
+            .await?;
+        let state: PasskeyAuthentication = challenge.state()?;
+
+        let result = webauthn
+            .finish_passkey_authentication(&credential, &state)
+            .map_err(|e| {
+                tracing::error!("Passkey login failed: {e}");
+                Status::unauthenticated("Authentication Failed!")
+            })?;
+
+        // Keep the new sign count, so a cloned authenticator can be detected
+        let credential_id =
+            database::WebauthnCredentials::encode_credential_id(result.cred_id());
+        let credentials = database::WebauthnCredentials::index_for_user(
+            &challenge.user_id,
+            self.database.as_ref(),
+        )
+        .await?;
+        if let Some(credential) = credentials
+            .iter()
+            .find(|credential| credential.credential_id == credential_id)
+        {
+            let mut passkey = credential.passkey()?;
+            passkey.update_credential(&result);
+            let passkey = serde_json::to_string(&passkey).map_err(AuthenticationError::from)?;
+            credential.record_use(&passkey, self.database.as_ref()).await?;
+        }
+
+        Ok(challenge.user_id)
+    }
+}
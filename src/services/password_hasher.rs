@@ -0,0 +1,189 @@
+//-- ./src/services/password_hasher.rs
+
+// #![allow(unused)] // For development only
+
+//! # Password Hasher
+//!
+//! Argon2 hashing and verification take tens of milliseconds of CPU, which
+//! would stall the async runtime if run on it. The password hasher runs them
+//! on tokio's blocking thread pool instead.
+//!
+//! The number of hashes waiting for or running on the pool is bounded by
+//! `load_shedding.max_queued_password_hashes`. Hashes over the bound are
+//! rejected with `UNAVAILABLE` straight away rather than queued.
+//!
+//! ## Metrics
+//! - `auth.password_hash.queue_depth` - hashes waiting for or running on the pool
+//! ---
+
+use std::sync::Arc;
+
+use opentelemetry::metrics::UpDownCounter;
+use secrecy::SecretString;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::configuration::LoadSheddingConfiguration;
+use crate::domain;
+use crate::prelude::*;
+
+/// Runs password hashing on the blocking thread pool, cheap to clone into each service
+#[derive(Clone)]
+pub struct PasswordHasher {
+    queue: Arc<Semaphore>,
+    queue_depth: UpDownCounter<i64>,
+}
+
+impl Default for PasswordHasher {
+    fn default() -> Self {
+        Self::new(LoadSheddingConfiguration::default().max_queued_password_hashes)
+    }
+}
+
+/// A place in the hashing queue, released when the hash has finished
+struct QueueSlot {
+    _permit: OwnedSemaphorePermit,
+    queue_depth: UpDownCounter<i64>,
+}
+
+impl Drop for QueueSlot {
+    fn drop(&mut self) {
+        self.queue_depth.add(-1, &[]);
+    }
+}
+
+impl PasswordHasher {
+    /// Create a hasher allowing `max_queued` hashes on the pool at once
+    pub fn new(max_queued: usize) -> Self {
+        let meter = opentelemetry::global::meter("authentication_service");
+
+        Self {
+            queue: Arc::new(Semaphore::new(max_queued)),
+            queue_depth: meter
+                .i64_up_down_counter("auth.password_hash.queue_depth")
+                .with_description(
+                    "Password hashes waiting for or running on the blocking pool",
+                )
+                .build(),
+        }
+    }
+
+    /// Validate and hash a new password, see `domain::PasswordHash::parse`
+    pub async fn hash(
+        &self,
+        password: SecretString,
+    ) -> Result<domain::PasswordHash, AuthenticationError> {
+        self.spawn(move || domain::PasswordHash::parse(password))
+            .await
+    }
+
+    /// Verify a password against a hash, see `domain::PasswordHash::verify_password`
+    pub async fn verify(
+        &self,
+        password_hash: domain::PasswordHash,
+        password: SecretString,
+    ) -> Result<bool, AuthenticationError> {
+        self.spawn(move || password_hash.verify_password(&password))
+            .await
+    }
+
+    /// Run a task that hashes passwords, such as a request conversion, on the
+    /// blocking thread pool
+    ///
+    /// Returns `PasswordHashQueueFull` when the queue is at its bound. The
+    /// queue slot is held until the task finishes, even if the caller stops
+    /// waiting for it.
+    pub async fn spawn<T, F>(&self, task: F) -> Result<T, AuthenticationError>
+    where
+        F: FnOnce() -> Result<T, AuthenticationError> + Send + 'static,
+        T: Send + 'static,
+    {
+        let permit = Arc::clone(&self.queue).try_acquire_owned().map_err(|_| {
+            tracing::warn!("Password hashing queue is full");
+            AuthenticationError::PasswordHashQueueFull
+        })?;
+        self.queue_depth.add(1, &[]);
+        let slot = QueueSlot {
+            _permit: permit,
+            queue_depth: self.queue_depth.clone(),
+        };
+
+        tokio::task::spawn_blocking(move || {
+            let _slot = slot;
+            task()
+        })
+        .await
+        .map_err(|e| {
+            AuthenticationError::Generic(format!(
+                "Password hashing task failed: {e}"
+            ))
+        })?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSWORD: &str = "aB1%aB1%aB1%aB1%";
+
+    #[tokio::test]
+    async fn hashes_and_verifies_on_the_blocking_pool(
+    ) -> Result<(), AuthenticationError> {
+        //-- Setup and Fixtures (Arrange)
+        let hasher = PasswordHasher::new(1);
+
+        //-- Execute Function (Act)
+        let password_hash = hasher.hash(SecretString::from(PASSWORD)).await?;
+        let verified = hasher
+            .verify(password_hash.clone(), SecretString::from(PASSWORD))
+            .await?;
+        let rejected = hasher
+            .verify(password_hash, SecretString::from("wrong-password"))
+            .await?;
+
+        //-- Checks (Assertions)
+        assert!(verified);
+        assert!(!rejected);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn hashes_over_the_bound_are_rejected() -> Result<(), AuthenticationError>
+    {
+        //-- Setup and Fixtures (Arrange)
+        let hasher = PasswordHasher::new(1);
+        let (release, released) = std::sync::mpsc::channel::<()>();
+
+        // Occupy the only slot until released
+        let blocked = {
+            let hasher = hasher.clone();
+            tokio::spawn(async move {
+                hasher
+                    .spawn(move || {
+                        released.recv().ok();
+                        Ok(())
+                    })
+                    .await
+            })
+        };
+        while hasher.queue.available_permits() > 0 {
+            tokio::task::yield_now().await;
+        }
+
+        //-- Execute Function (Act)
+        let rejected = hasher.hash(SecretString::from(PASSWORD)).await;
+        release.send(()).ok();
+        blocked.await.expect("blocked task panicked")?;
+        let accepted = hasher.hash(SecretString::from(PASSWORD)).await;
+
+        //-- Checks (Assertions)
+        assert!(matches!(
+            rejected,
+            Err(AuthenticationError::PasswordHashQueueFull)
+        ));
+        assert!(accepted.is_ok());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+//-- ./src/services/password_reset.rs
+
+// #![allow(unused)] // For development only
+
+//! # Password Reset
+//!
+//! Password resets are issued when a user asks for one with
+//! `RequestPasswordReset`, or when an admin forces one with
+//! `ForcePasswordReset`. Both email a single-use link to `password_reset.link_url`,
+//! and only the latest link a user was sent can be used. `ResetPassword`
+//! exchanges the link's token for a new password.
+//! ---
+
+use sqlx::PgConnection;
+
+use crate::configuration::Configuration;
+use crate::email::{EmailTemplate, EmailTemplates};
+use crate::prelude::*;
+use crate::{database, domain};
+
+/// # Issue A Password Reset
+///
+/// Invalidate the user's earlier unused resets, insert a new reset and queue
+/// the email with its link. Run it in a transaction, so the reset is only
+/// saved with its email.
+///
+/// ## Parameters
+///
+/// - `transaction: &mut PgConnection` - The transaction to issue the reset in
+/// - `config: &Configuration` - The current configuration
+/// - `user: &database::Users` - The user the link is emailed to
+/// - `forced: bool` - An admin forced the reset, changing the email wording
+pub async fn issue_password_reset(
+    transaction: &mut PgConnection,
+    config: &Configuration,
+    user: &database::Users,
+    forced: bool,
+) -> Result<database::PasswordResets, AuthenticationError> {
+    let token = domain::ActionToken::generate(
+        domain::ActionPurpose::PasswordReset,
+        &config.application.token_secret,
+    );
+    let password_reset = database::PasswordResets::new(
+        &user.id,
+        &token,
+        &std::time::Duration::from_secs(config.password_reset.expiry_minutes * 60),
+    );
+
+    let templates = EmailTemplates::new(&config.email)?;
+    let mut context = tera::Context::new();
+    context.insert("name", user.name.as_ref());
+    context.insert("reset_url", &config.password_reset.link(token.expose()));
+    context.insert("expires_in_minutes", &config.password_reset.expiry_minutes);
+    context.insert("forced", &forced);
+    let email = templates.render(
+        EmailTemplate::PasswordReset,
+        &user.email,
+        &user.locale,
+        &context,
+    )?;
+
+    database::PasswordResets::invalidate_unused_for_user(&user.id, &mut *transaction).await?;
+    let password_reset = password_reset.insert(&mut *transaction).await?;
+    database::Outbox::new(email)
+        .insert(&mut *transaction)
+        .await?;
+
+    Ok(password_reset)
+}
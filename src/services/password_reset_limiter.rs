@@ -0,0 +1,254 @@
+//-- ./src/services/password_reset_limiter.rs
+
+// #![allow(unused)] // For development only
+
+//! # Password Reset Limiter
+//!
+//! Limits how often password resets are requested, so `RequestPasswordReset`
+//! can't be used to flood a user's inbox or probe many emails from one
+//! address.
+//!
+//! Requests are counted per email and per IP address within
+//! `password_reset.window_seconds`. Once either count reaches
+//! `password_reset.max_requests_per_email` or `max_requests_per_ip` further
+//! requests are ignored until the window ends. The caller gets the same
+//! response either way, so the limit doesn't reveal which emails have an
+//! account. Requests are counted in memory, per instance. Responses carry the
+//! `x-ratelimit-*` metadata of whichever limit is closest (see
+//! `utils::rate_limit`), which is the same whether or not the email has an
+//! account.
+//!
+//! ## Metrics
+//! - `auth.password_reset.limited` - reset requests ignored over the limits
+//! ---
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, RwLock};
+
+use chrono::{DateTime, Utc};
+use opentelemetry::metrics::Counter;
+
+use crate::configuration::PasswordResetConfiguration;
+use crate::utils::rate_limit::RateLimit;
+
+/// What password reset requests are counted by
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum RequestKey {
+    Email(String),
+    Ip(IpAddr),
+}
+
+/// Password reset requests within the current window
+#[derive(Debug, Clone, Copy)]
+struct RequestCount {
+    count: u32,
+    window_started: DateTime<Utc>,
+}
+
+/// Counts password reset requests, cheap to clone into each service
+#[derive(Clone)]
+pub struct PasswordResetLimiter {
+    requests: Arc<RwLock<HashMap<RequestKey, RequestCount>>>,
+    limited: Counter<u64>,
+}
+
+impl Default for PasswordResetLimiter {
+    fn default() -> Self {
+        let meter = opentelemetry::global::meter("authentication_service");
+
+        Self {
+            requests: Arc::new(RwLock::new(HashMap::new())),
+            limited: meter
+                .u64_counter("auth.password_reset.limited")
+                .with_description("Password reset requests ignored over the limits")
+                .build(),
+        }
+    }
+}
+
+impl PasswordResetLimiter {
+    /// Count a password reset request, returning false when the email or IP
+    /// address is over its limit and the request should be ignored
+    ///
+    /// ## Parameters
+    ///
+    /// - `config: &PasswordResetConfiguration` - The current password reset configuration
+    /// - `ip: IpAddr` - The address the request came from
+    /// - `email: &str` - The email the reset is for
+    pub fn allow(&self, config: &PasswordResetConfiguration, ip: IpAddr, email: &str) -> bool {
+        let now = Utc::now();
+        let window = chrono::Duration::seconds(config.window_seconds as i64);
+        let mut requests = self.requests.write().unwrap_or_else(|e| e.into_inner());
+
+        // Drop the counts for windows that have ended
+        requests.retain(|_, request| request.window_started + window > now);
+
+        // Count both keys, even when the first is already over its limit
+        let mut allowed = true;
+        for (key, limit) in [
+            (RequestKey::Email(limiter_email(email)), config.max_requests_per_email),
+            (RequestKey::Ip(ip), config.max_requests_per_ip),
+        ] {
+            let request = requests.entry(key).or_insert(RequestCount {
+                count: 0,
+                window_started: now,
+            });
+            request.count = request.count.saturating_add(1);
+            allowed &= limit == 0 || request.count <= limit;
+        }
+
+        if !allowed {
+            tracing::info!("Password reset request from {ip} over the limit");
+            self.limited.add(1, &[]);
+        }
+
+        allowed
+    }
+
+    /// The rate limit state of the email or IP address, whichever has fewer
+    /// requests remaining, `None` when neither is limited
+    ///
+    /// ## Parameters
+    ///
+    /// - `config: &PasswordResetConfiguration` - The current password reset configuration
+    /// - `ip: IpAddr` - The address the request came from
+    /// - `email: &str` - The email the reset is for
+    pub fn rate_limit(
+        &self,
+        config: &PasswordResetConfiguration,
+        ip: IpAddr,
+        email: &str,
+    ) -> Option<RateLimit> {
+        let now = Utc::now();
+        let window = chrono::Duration::seconds(config.window_seconds as i64);
+        let requests = self.requests.read().unwrap_or_else(|e| e.into_inner());
+
+        [
+            (RequestKey::Email(limiter_email(email)), config.max_requests_per_email),
+            (RequestKey::Ip(ip), config.max_requests_per_ip),
+        ]
+        .into_iter()
+        .filter(|(_, limit)| *limit > 0)
+        .map(|(key, limit)| match requests.get(&key) {
+            Some(request) if request.window_started + window > now => RateLimit {
+                limit,
+                remaining: limit.saturating_sub(request.count),
+                // Round up, so the client does not retry a moment too early
+                reset_seconds: ((request.window_started + window - now)
+                    .num_milliseconds()
+                    .max(0) as u64)
+                    .div_ceil(1_000),
+            },
+            _ => RateLimit {
+                limit,
+                remaining: limit,
+                reset_seconds: 0,
+            },
+        })
+        .min_by_key(|rate_limit| rate_limit.remaining)
+    }
+}
+
+/// Emails are compared case insensitively, so `A@example.com` and
+/// `a@example.com` share a count
+fn limiter_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    fn config(max_requests_per_email: u32, max_requests_per_ip: u32) -> PasswordResetConfiguration {
+        PasswordResetConfiguration {
+            max_requests_per_email,
+            max_requests_per_ip,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn requests_are_limited_per_email() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let limiter = PasswordResetLimiter::default();
+        let config = config(2, 0);
+        let ip: IpAddr = "203.0.113.7".parse()?;
+        let other_ip: IpAddr = "203.0.113.8".parse()?;
+
+        //-- Execute Function (Act)
+        let first = limiter.allow(&config, ip, "user@example.com");
+        let second = limiter.allow(&config, other_ip, "USER@example.com ");
+        let third = limiter.allow(&config, ip, "user@example.com");
+        let other_email = limiter.allow(&config, ip, "other@example.com");
+
+        //-- Checks (Assertions)
+        assert!(first);
+        assert!(second);
+        assert!(!third);
+        assert!(other_email);
+
+        Ok(())
+    }
+
+    #[test]
+    fn requests_are_limited_per_ip_address() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let limiter = PasswordResetLimiter::default();
+        let config = config(0, 2);
+        let ip: IpAddr = "203.0.113.7".parse()?;
+        let other_ip: IpAddr = "203.0.113.8".parse()?;
+
+        //-- Execute Function (Act)
+        let allowed: Vec<bool> = ["a@example.com", "b@example.com", "c@example.com"]
+            .into_iter()
+            .map(|email| limiter.allow(&config, ip, email))
+            .collect();
+        let other = limiter.allow(&config, other_ip, "d@example.com");
+
+        //-- Checks (Assertions)
+        assert_eq!(allowed, vec![true, true, false]);
+        assert!(other);
+
+        Ok(())
+    }
+
+    #[test]
+    fn rate_limits_report_the_closest_limit() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let limiter = PasswordResetLimiter::default();
+        let unlimited_config = config(0, 0);
+        let config = PasswordResetConfiguration {
+            window_seconds: 3_600,
+            ..config(3, 10)
+        };
+        let ip: IpAddr = "203.0.113.7".parse()?;
+
+        //-- Execute Function (Act)
+        let before = limiter.rate_limit(&config, ip, "a@example.com");
+        limiter.allow(&config, ip, "a@example.com");
+        let after = limiter.rate_limit(&config, ip, "a@example.com");
+        let unlimited = limiter.rate_limit(&unlimited_config, ip, "a@example.com");
+
+        //-- Checks (Assertions)
+        assert_eq!(
+            before,
+            Some(RateLimit {
+                limit: 3,
+                remaining: 3,
+                reset_seconds: 0
+            })
+        );
+        let after = after.ok_or("no rate limit")?;
+        assert_eq!((after.limit, after.remaining), (3, 2));
+        assert!(after.reset_seconds > 0 && after.reset_seconds <= 3_600);
+        assert_eq!(unlimited, None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+//-- ./src/services/provisioning.rs
+
+// #![allow(unused)] // For development only
+
+//! # External User Provisioning
+//!
+//! Users whose identity is owned by an LDAP directory or a SAML identity
+//! provider are provisioned as local shadow users on their first login. Shadow
+//! users are verified, as the directory owns their email, and have no local
+//! password.
+//! ---
+
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::events::{AuthEvent, AuthEventKind, AuthEvents};
+use crate::prelude::*;
+use crate::{database, domain};
+
+/// # Provision A Shadow User
+///
+/// Insert a verified user with no local password and queue the registration
+/// event. A user provisioned concurrently with the same email is returned
+/// instead.
+///
+/// ## Parameters
+///
+/// - `database: &Pool<Postgres>` - The database pool
+/// - `events: &AuthEvents` - Broadcaster for the registration event
+/// - `email: domain::EmailAddress` - The email from the directory
+/// - `name: Option<&str>` - The name from the directory, the start of the email
+///   is used when it is missing or invalid
+pub async fn provision_shadow_user(
+    database: &Pool<Postgres>,
+    events: &AuthEvents,
+    email: domain::EmailAddress,
+    name: Option<&str>,
+) -> Result<database::Users, AuthenticationError> {
+    let local_part = email.as_ref().split('@').next().unwrap_or_default();
+    let name = name
+        .and_then(|name| domain::UserName::parse(name).ok())
+        .map_or_else(|| domain::UserName::parse(local_part), Ok)?;
+
+    let user = database::Users {
+        id: Uuid::now_v7(),
+        email,
+        name,
+        password_hash: domain::PasswordHash::dummy(),
+        role: domain::UserRole::User,
+        is_active: true,
+        is_verified: true,
+        created_on: chrono::Utc::now(),
+        locale: domain::Locale::default(),
+    };
+
+    let mut transaction = database.begin().await?;
+    let user = match user.insert(&mut *transaction).await {
+        Ok(user) => user,
+        // A concurrent first login provisioned the user
+        Err(AuthenticationError::Sqlx(sqlx::Error::Database(e))) if e.is_unique_violation() => {
+            return database::Users::from_user_email(&user.email, database).await;
+        }
+        Err(e) => return Err(e),
+    };
+    let event = AuthEvent::new(AuthEventKind::Registration).user(user.id);
+    database::Outbox::new(event.clone())
+        .insert(&mut *transaction)
+        .await?;
+    transaction.commit().await?;
+    events.publish(event);
+
+    tracing::info!("Shadow user provisioned: {}", user.id);
+
+    Ok(user)
+}
@@ -0,0 +1,468 @@
+//-- ./src/services/saml.rs
+
+// #![allow(unused)] // For development only
+
+//! # SAML
+//!
+//! SAML 2.0 single sign on, with this service as the service provider, using
+//! [samael](https://docs.rs/samael). The identity provider is configured in
+//! `saml` with its entity id, single sign on url and signing certificate.
+//!
+//! 1. **Metadata**: the service provider metadata XML, registered with the
+//!    identity provider.
+//! 2. **Begin**: an AuthnRequest is created and its id kept in
+//!    `saml_requests`, returning the identity provider url to redirect to.
+//! 3. **Complete**: the identity provider's response is checked against its
+//!    certificate and the request it answers, which is consumed so the
+//!    response can't be replayed. The assertion's email and name attributes
+//!    are mapped onto a local user, provisioned on their first login when
+//!    `saml.provision_users` is set. A NameID linked in `federated_identities`
+//!    logs in as the user it is linked to, whatever its email.
+//! 4. **Link**: a request begun for a logged in user links the identity in
+//!    its response to that user instead of logging in.
+//!
+//! Identity provider initiated logins are not accepted. samael is behind the
+//! `saml` feature.
+//! ---
+
+use std::sync::Arc;
+
+use sqlx::{Pool, Postgres};
+
+use crate::configuration::SamlConfiguration;
+use crate::events::AuthEvents;
+
+/// How long the identity provider has to answer a request
+#[cfg_attr(not(feature = "saml"), allow(dead_code))]
+const SAML_REQUEST_EXPIRY_MINUTES: u64 = 10;
+
+/// Escape a value for an XML attribute or text
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// The base64 DER body of the certificate, without any PEM armour or whitespace
+fn certificate_body(certificate: &str) -> String {
+    certificate
+        .lines()
+        .filter(|line| !line.starts_with("-----"))
+        .flat_map(|line| line.split_whitespace())
+        .collect()
+}
+
+/// The identity provider metadata, built from the configuration so only its
+/// entity id, single sign on url and certificate need to be configured
+#[cfg_attr(not(feature = "saml"), allow(dead_code))]
+fn idp_metadata_xml(config: &SamlConfiguration) -> String {
+    format!(
+        r#"<md:EntityDescriptor xmlns:md="urn:oasis:names:tc:SAML:2.0:metadata" entityID="{entity_id}">
+  <md:IDPSSODescriptor protocolSupportEnumeration="urn:oasis:names:tc:SAML:2.0:protocol">
+    <md:KeyDescriptor use="signing">
+      <ds:KeyInfo xmlns:ds="http://www.w3.org/2000/09/xmldsig#">
+        <ds:X509Data>
+          <ds:X509Certificate>{certificate}</ds:X509Certificate>
+        </ds:X509Data>
+      </ds:KeyInfo>
+    </md:KeyDescriptor>
+    <md:SingleSignOnService Binding="urn:oasis:names:tc:SAML:2.0:bindings:HTTP-Redirect" Location="{sso_url}"/>
+  </md:IDPSSODescriptor>
+</md:EntityDescriptor>"#,
+        entity_id = xml_escape(&config.idp_entity_id),
+        certificate = certificate_body(&config.idp_certificate),
+        sso_url = xml_escape(&config.idp_sso_url),
+    )
+}
+
+/// The user identified by an assertion
+#[derive(Debug, Clone, PartialEq)]
+pub struct SamlIdentity {
+    pub subject: Option<String>,
+    pub email: String,
+    pub name: Option<String>,
+}
+
+/// Map the assertion's NameID and attributes onto the user's email and name.
+/// Attributes are matched on their name or friendly name.
+#[cfg_attr(not(feature = "saml"), allow(dead_code))]
+fn map_identity(
+    config: &SamlConfiguration,
+    name_id: Option<&str>,
+    attributes: &[(Vec<String>, Vec<String>)],
+) -> Option<SamlIdentity> {
+    let attribute = |wanted: &str| {
+        attributes
+            .iter()
+            .find(|(names, _)| names.iter().any(|name| name.eq_ignore_ascii_case(wanted)))
+            .and_then(|(_, values)| values.first())
+            .cloned()
+    };
+
+    let email = attribute(&config.email_attribute).or_else(|| name_id.map(str::to_string))?;
+
+    Some(SamlIdentity {
+        subject: name_id.map(str::to_string),
+        email,
+        name: attribute(&config.name_attribute),
+    })
+}
+
+/// Runs SAML single sign on with the configured identity provider
+#[derive(Clone)]
+#[cfg_attr(not(feature = "saml"), allow(dead_code))]
+pub struct SamlLogin {
+    database: Arc<Pool<Postgres>>,
+    events: AuthEvents,
+}
+
+impl SamlLogin {
+    /// Create the SAML login, keeping its requests in the database
+    pub fn new(database: Arc<Pool<Postgres>>, events: AuthEvents) -> Self {
+        Self { database, events }
+    }
+}
+
+#[cfg(feature = "saml")]
+mod samael_login {
+    use std::time::Duration;
+
+    use base64::Engine;
+    use samael::metadata::EntityDescriptor;
+    use samael::schema::{Assertion, Response};
+    use samael::service_provider::{ServiceProvider, ServiceProviderBuilder};
+    use tonic::Status;
+    use uuid::Uuid;
+
+    use super::{idp_metadata_xml, map_identity, SamlLogin, SAML_REQUEST_EXPIRY_MINUTES};
+    use crate::configuration::SamlConfiguration;
+    use crate::prelude::*;
+    use crate::services::provisioning::provision_shadow_user;
+    use crate::{database, domain};
+
+    impl SamlLogin {
+        /// Build the service provider from the configuration. SAML that is
+        /// not enabled answers `UNIMPLEMENTED`.
+        fn service_provider(config: &SamlConfiguration) -> Result<ServiceProvider, Status> {
+            if !config.enabled {
+                return Err(Status::unimplemented("SAML login is not enabled"));
+            }
+
+            let idp_metadata: EntityDescriptor = samael::metadata::de::from_str(
+                &idp_metadata_xml(config),
+            )
+            .map_err(|e| {
+                tracing::error!("Invalid SAML identity provider configuration: {e}");
+                Status::internal("Internal server error")
+            })?;
+
+            ServiceProviderBuilder::default()
+                .entity_id(config.entity_id.clone())
+                .acs_url(config.acs_url.clone())
+                .idp_metadata(idp_metadata)
+                .allow_idp_initiated(false)
+                .build()
+                .map_err(|e| {
+                    tracing::error!("Unable to build the SAML service provider: {e}");
+                    Status::internal("Internal server error")
+                })
+        }
+
+        /// # SAML Metadata
+        ///
+        /// The service provider metadata XML, for registering this service
+        /// with the identity provider.
+        pub fn metadata(&self, config: &SamlConfiguration) -> Result<String, Status> {
+            let metadata = Self::service_provider(config)?.metadata().map_err(|e| {
+                tracing::error!("Unable to build the SAML metadata: {e}");
+                Status::internal("Internal server error")
+            })?;
+
+            metadata.to_xml().map_err(|e| {
+                tracing::error!("Unable to serialise the SAML metadata: {e}");
+                Status::internal("Internal server error")
+            })
+        }
+
+        /// # Begin SAML Login
+        ///
+        /// Create an AuthnRequest, keeping its id until the identity provider
+        /// answers, and return the identity provider url to send the browser to.
+        /// With a `link_user_id` the identity in the response is linked to
+        /// that user.
+        pub async fn begin(
+            &self,
+            config: &SamlConfiguration,
+            link_user_id: Option<&Uuid>,
+        ) -> Result<String, Status> {
+            let service_provider = Self::service_provider(config)?;
+
+            let request = service_provider
+                .make_authentication_request(&config.idp_sso_url)
+                .map_err(|e| {
+                    tracing::error!("Unable to create the SAML request: {e}");
+                    Status::internal("Internal server error")
+                })?;
+
+            let saml_request = database::SamlRequests::new(
+                &request.id,
+                &Duration::from_secs(SAML_REQUEST_EXPIRY_MINUTES * 60),
+            );
+            let saml_request = match link_user_id {
+                Some(user_id) => saml_request.link_user(user_id),
+                None => saml_request,
+            };
+            saml_request.insert(self.database.as_ref()).await?;
+
+            let redirect_url = request
+                .redirect("")
+                .ok()
+                .flatten()
+                .ok_or_else(|| {
+                    tracing::error!("Unable to build the SAML redirect url");
+                    Status::internal("Internal server error")
+                })?;
+
+            Ok(redirect_url.to_string())
+        }
+
+        /// # Complete SAML Login
+        ///
+        /// Check the identity provider's base64 encoded response answers one
+        /// of our requests and is signed by the identity provider, then return
+        /// the user it identifies, or the user it was linked to.
+        pub async fn complete(
+            &self,
+            config: &SamlConfiguration,
+            saml_response: &str,
+        ) -> Result<database::Users, Status> {
+            let service_provider = Self::service_provider(config)?;
+
+            // Find the request the response answers, consuming it so the
+            // response can only be used once
+            let request_id = in_response_to(saml_response).ok_or_else(|| {
+                tracing::error!("SAML response does not answer a request");
+                Status::unauthenticated("Authentication Failed!")
+            })?;
+            let saml_request = database::SamlRequests::consume(&request_id, self.database.as_ref())
+                .await?
+                .ok_or_else(|| {
+                    tracing::error!("SAML request is unknown, answered or expired: {request_id}");
+                    Status::unauthenticated("Authentication Failed!")
+                })?;
+
+            let assertion = service_provider
+                .parse_base64_response(saml_response, Some(&[request_id.as_str()]))
+                .map_err(|e| {
+                    tracing::error!("SAML response rejected: {e}");
+                    Status::unauthenticated("Authentication Failed!")
+                })?;
+
+            let identity = identity(config, &assertion).ok_or_else(|| {
+                tracing::error!("SAML assertion has no email");
+                Status::unauthenticated("Authentication Failed!")
+            })?;
+            let email = domain::EmailAddress::parse(&identity.email).map_err(|_| {
+                tracing::error!("SAML assertion email is invalid: {}", identity.email);
+                Status::unauthenticated("Authentication Failed!")
+            })?;
+
+            // Identities are keyed on the NameID, falling back to the email
+            // for identity providers that don't send one
+            let provider = config.idp_entity_id.as_str();
+            let subject = identity.subject.as_deref().unwrap_or(email.as_ref());
+            let linked = database::FederatedIdentities::from_subject(
+                provider,
+                subject,
+                self.database.as_ref(),
+            )
+            .await?;
+
+            if let Some(user_id) = saml_request.link_user_id {
+                return self.link(user_id, linked, provider, subject, &email).await;
+            }
+
+            if let Some(linked) = linked {
+                let user =
+                    database::Users::from_user_id(&linked.user_id, self.database.as_ref()).await?;
+                return Ok(user);
+            }
+
+            match database::Users::from_user_email(&email, self.database.as_ref()).await {
+                Ok(user) => Ok(user),
+                Err(AuthenticationError::Sqlx(sqlx::Error::RowNotFound))
+                    if config.provision_users =>
+                {
+                    Ok(provision_shadow_user(
+                        self.database.as_ref(),
+                        &self.events,
+                        email,
+                        identity.name.as_deref(),
+                    )
+                    .await?)
+                }
+                Err(AuthenticationError::Sqlx(sqlx::Error::RowNotFound)) => {
+                    tracing::error!("SAML user is not provisioned: {}", email.as_ref());
+                    Err(Status::unauthenticated("Authentication Failed!"))
+                }
+                Err(e) => Err(e.into()),
+            }
+        }
+
+        /// Link the identity to the user the request was begun for, unless
+        /// it is already linked to someone else
+        async fn link(
+            &self,
+            user_id: Uuid,
+            linked: Option<database::FederatedIdentities>,
+            provider: &str,
+            subject: &str,
+            email: &domain::EmailAddress,
+        ) -> Result<database::Users, Status> {
+            match linked {
+                Some(linked) if linked.user_id != user_id => {
+                    tracing::error!("SAML identity is linked to another user: {subject}");
+                    return Err(Status::already_exists(
+                        "Identity is linked to another account",
+                    ));
+                }
+                Some(_) => {}
+                None => {
+                    database::FederatedIdentities::new(&user_id, provider, subject, email.as_ref())
+                        .insert(self.database.as_ref())
+                        .await?;
+                    tracing::info!("SAML identity linked to user: {user_id}");
+                }
+            }
+
+            Ok(database::Users::from_user_id(&user_id, self.database.as_ref()).await?)
+        }
+    }
+
+    /// The InResponseTo of the response, read before it is verified so the
+    /// request can be looked up
+    fn in_response_to(saml_response: &str) -> Option<String> {
+        let xml = base64::engine::general_purpose::STANDARD
+            .decode(saml_response.trim())
+            .ok()?;
+        let response: Response = String::from_utf8(xml).ok()?.parse().ok()?;
+
+        response.in_response_to
+    }
+
+    /// The user identified by the assertion's NameID and attributes
+    fn identity(config: &SamlConfiguration, assertion: &Assertion) -> Option<super::SamlIdentity> {
+        let name_id = assertion
+            .subject
+            .as_ref()
+            .and_then(|subject| subject.name_id.as_ref())
+            .map(|name_id| name_id.value.as_str());
+
+        let attributes: Vec<(Vec<String>, Vec<String>)> = assertion
+            .attribute_statements
+            .iter()
+            .flatten()
+            .flat_map(|statement| &statement.attributes)
+            .map(|attribute| {
+                let names = [&attribute.name, &attribute.friendly_name]
+                    .into_iter()
+                    .flatten()
+                    .cloned()
+                    .collect();
+                let values = attribute
+                    .values
+                    .iter()
+                    .filter_map(|value| value.value.clone())
+                    .collect();
+                (names, values)
+            })
+            .collect();
+
+        map_identity(config, name_id, &attributes)
+    }
+}
+
+/// Without the `saml` feature SAML login is unimplemented, the configuration
+/// is rejected before it is used
+#[cfg(not(feature = "saml"))]
+impl SamlLogin {
+    pub fn metadata(&self, _config: &SamlConfiguration) -> Result<String, tonic::Status> {
+        Err(tonic::Status::unimplemented("SAML login requires the `saml` feature"))
+    }
+
+    pub async fn begin(
+        &self,
+        _config: &SamlConfiguration,
+        _link_user_id: Option<&uuid::Uuid>,
+    ) -> Result<String, tonic::Status> {
+        Err(tonic::Status::unimplemented("SAML login requires the `saml` feature"))
+    }
+
+    pub async fn complete(
+        &self,
+        _config: &SamlConfiguration,
+        _saml_response: &str,
+    ) -> Result<crate::database::Users, tonic::Status> {
+        Err(tonic::Status::unimplemented("SAML login requires the `saml` feature"))
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attributes(pairs: &[(&str, &str)]) -> Vec<(Vec<String>, Vec<String>)> {
+        pairs
+            .iter()
+            .map(|(name, value)| (vec![name.to_string()], vec![value.to_string()]))
+            .collect()
+    }
+
+    #[test]
+    fn identity_uses_attributes_then_the_name_id() {
+        let config = SamlConfiguration::default();
+
+        let identity = map_identity(
+            &config,
+            Some("name-id@example.com"),
+            &attributes(&[("Email", "jane@example.com"), ("displayName", "Jane Doe")]),
+        );
+        assert_eq!(
+            identity,
+            Some(SamlIdentity {
+                subject: Some("name-id@example.com".to_string()),
+                email: "jane@example.com".to_string(),
+                name: Some("Jane Doe".to_string()),
+            })
+        );
+
+        let identity = map_identity(&config, Some("name-id@example.com"), &[]);
+        assert_eq!(
+            identity.map(|identity| identity.email).as_deref(),
+            Some("name-id@example.com")
+        );
+
+        assert!(map_identity(&config, None, &[]).is_none());
+    }
+
+    #[test]
+    fn idp_metadata_is_built_from_the_configuration() {
+        let config = SamlConfiguration {
+            idp_entity_id: "https://idp.example.com/?a=1&b=2".to_string(),
+            idp_sso_url: "https://idp.example.com/sso".to_string(),
+            idp_certificate: "-----BEGIN CERTIFICATE-----\nMIIC\nabcd\n-----END CERTIFICATE-----\n"
+                .to_string(),
+            ..SamlConfiguration::default()
+        };
+
+        let metadata = idp_metadata_xml(&config);
+
+        assert!(metadata.contains(r#"entityID="https://idp.example.com/?a=1&amp;b=2""#));
+        assert!(metadata.contains("<ds:X509Certificate>MIICabcd</ds:X509Certificate>"));
+        assert!(metadata.contains(r#"Location="https://idp.example.com/sso""#));
+    }
+}
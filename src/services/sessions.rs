@@ -13,9 +13,10 @@ use sqlx::{Pool, Postgres};
 use tonic::{Request, Response, Status};
 use uuid::Uuid;
 
-use crate::configuration::Configuration;
-use crate::database;
+use crate::configuration::{Configuration, SharedConfiguration};
+use crate::events::{AuthEvent, AuthEventKind, AuthEvents};
 use crate::prelude::AuthenticationError;
+use crate::repository::{PostgresRepository, SessionRepository};
 use crate::rpc::proto::sessions_service_server::SessionsService as Sessions;
 use crate::rpc::proto::{
     Empty, SessionsDeleteRequest, SessionsDeleteResponse, SessionsDeleteUserRequest,
@@ -23,30 +24,76 @@ use crate::rpc::proto::{
     SessionsResponse, SessionsRevokeRequest, SessionsRevokeResponse,
     SessionsRevokeUserRequest,
 };
+use crate::services::validation;
+use crate::{database, utils};
 
 /// User service containing a database pool
 // #[derive(Debug)]
 pub struct SessionsService {
     database: Arc<Pool<Postgres>>,
     #[allow(dead_code)]
-    config: Arc<Configuration>,
+    config: SharedConfiguration,
+    events: AuthEvents,
+    sessions: Arc<dyn SessionRepository>,
 }
 
 impl SessionsService {
     /// Create a new UserService passing in the Arc for the Sqlx database pool
-    pub fn new(database: Arc<Pool<Postgres>>, config: Arc<Configuration>) -> Self {
-        Self { database, config }
+    pub fn new(
+        database: Arc<Pool<Postgres>>,
+        config: SharedConfiguration,
+        events: AuthEvents,
+    ) -> Self {
+        let sessions = Arc::new(PostgresRepository::new(Arc::clone(&database)));
+
+        Self {
+            database,
+            config,
+            events,
+            sessions,
+        }
+    }
+
+    /// Read and delete sessions in another repository, such as `MemoryRepository` in tests
+    pub fn with_sessions_repository(
+        mut self,
+        sessions: Arc<dyn SessionRepository>,
+    ) -> Self {
+        self.sessions = sessions;
+        self
+    }
+
+    /// Tokens scoped to an organization can only reach that organization's
+    /// sessions, other sessions are not found
+    async fn check_organization(
+        &self,
+        request_extensions: &tonic::Extensions,
+        id: &Uuid,
+    ) -> Result<(), Status> {
+        let Some(organization_id) = utils::tenant::organization_id(request_extensions)?
+        else {
+            return Ok(());
+        };
+
+        let database_record = self.sessions.from_id(id).await?;
+        if database_record.organization_id != Some(organization_id) {
+            tracing::error!("Session is not in the token organization: {id}");
+            return Err(Status::not_found("Session not found"));
+        }
+
+        Ok(())
     }
 
     /// Shorthand for reference to database pool
+    #[allow(dead_code)]
     fn database_ref(&self) -> &Pool<Postgres> {
         &self.database
     }
 
     /// Shorthand for reference to application configuration instance
     #[allow(dead_code)]
-    fn config_ref(&self) -> &Configuration {
-        &self.config
+    fn config_ref(&self) -> Arc<Configuration> {
+        self.config.load_full()
     }
 }
 
@@ -66,6 +113,9 @@ impl From<database::Sessions> for SessionsResponse {
             Some(value.logged_out_at.unwrap().to_string())
         };
         let logout_ip = value.logout_ip;
+        let organization_id = value.organization_id.map(|id| id.to_string());
+        let last_used_at = value.last_used_at.map(|last_used_at| last_used_at.to_string());
+        let request_count = value.request_count;
 
         Self {
             id,
@@ -77,6 +127,9 @@ impl From<database::Sessions> for SessionsResponse {
             is_active,
             logged_out_at,
             logout_ip,
+            organization_id,
+            last_used_at,
+            request_count,
         }
     }
 }
@@ -89,8 +142,10 @@ impl Sessions for SessionsService {
         &self,
         request: Request<SessionsReadRequest>,
     ) -> Result<Response<SessionsResponse>, Status> {
+        validation::validate(&request)?;
+
         // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
-        let (_request_metadata, _request_extensions, request_message) =
+        let (_request_metadata, request_extensions, request_message) =
             request.into_parts();
 
         // Parse the request message string into a Uuid
@@ -101,8 +156,14 @@ impl Sessions for SessionsService {
             );
         })?;
 
-        let database_record =
-            database::Sessions::from_id(&id, self.database_ref()).await?;
+        let database_record = self.sessions.from_id(&id).await?;
+
+        // Tokens scoped to an organization can only read that organization's sessions
+        let organization_id = utils::tenant::organization_id(&request_extensions)?;
+        if organization_id.is_some() && database_record.organization_id != organization_id {
+            tracing::error!("Session is not in the token organization: {id}");
+            return Err(Status::not_found("Session not found"));
+        }
 
         // Convert the database record into a LoginsResponse message
         let response_message: SessionsResponse = database_record.into();
@@ -117,10 +178,15 @@ impl Sessions for SessionsService {
         &self,
         request: Request<SessionsIndexRequest>,
     ) -> Result<Response<SessionsIndexResponse>, Status> {
+        validation::validate(&request)?;
+
         // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
-        let (_request_metadata, _request_extensions, request_message) =
+        let (_request_metadata, request_extensions, request_message) =
             request.into_parts();
 
+        // Tokens scoped to an organization only see that organization's sessions
+        let organization_id = utils::tenant::organization_id(&request_extensions)?;
+
         // Offset, where to start the records from
         let offset: usize = request_message.offset.try_into().map_err(|_| {
             Status::invalid_argument("Offset must be a non-negative integer within usize range")
@@ -132,8 +198,14 @@ impl Sessions for SessionsService {
         })?;
 
         // Query the database
-        let database_records =
-            database::Sessions::index(&limit, &offset, self.database_ref()).await?;
+        let database_records = match organization_id {
+            Some(organization_id) => {
+                self.sessions
+                    .index_organization(&organization_id, &limit, &offset)
+                    .await?
+            }
+            None => self.sessions.index(&limit, &offset).await?,
+        };
 
         // Convert database::Users into User Response within the vector
         let sessions: Vec<SessionsResponse> = database_records
@@ -153,8 +225,10 @@ impl Sessions for SessionsService {
         &self,
         request: Request<SessionsRevokeRequest>,
     ) -> Result<Response<SessionsRevokeResponse>, Status> {
+        validation::validate(&request)?;
+
         // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
-        let (_request_metadata, _request_extensions, request_message) =
+        let (_request_metadata, request_extensions, request_message) =
             request.into_parts();
 
         // Parse the request message string into a Uuid
@@ -165,9 +239,28 @@ impl Sessions for SessionsService {
             );
         })?;
 
+        self.check_organization(&request_extensions, &id).await?;
+
+        // Revoke the session and queue the revocation event in one transaction
+        let mut transaction = self.database.begin().await?;
+
         // Revoke Session in database based on database row PK (id)
         let rows_affected =
-            database::Sessions::revoke_by_id(&id, self.database_ref()).await? as u64;
+            database::Sessions::revoke_by_id(&id, &mut *transaction).await? as u64;
+
+        let event = AuthEvent::new(AuthEventKind::Revocation)
+            .session(id)
+            .sessions_affected(rows_affected);
+        if rows_affected > 0 {
+            database::Outbox::new(event.clone())
+                .insert(&mut *transaction)
+                .await?;
+        }
+        transaction.commit().await?;
+
+        if rows_affected > 0 {
+            self.events.publish(event);
+        }
 
         // Build Session Response message
         let response_message = SessionsRevokeResponse { rows_affected };
@@ -185,8 +278,10 @@ impl Sessions for SessionsService {
         &self,
         request: Request<SessionsRevokeUserRequest>,
     ) -> Result<Response<SessionsRevokeResponse>, Status> {
+        validation::validate(&request)?;
+
         // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
-        let (_request_metadata, _request_extensions, request_message) =
+        let (_request_metadata, request_extensions, request_message) =
             request.into_parts();
 
         // Parse the request message string into a Uuid
@@ -197,10 +292,38 @@ impl Sessions for SessionsService {
             );
         })?;
 
+        // Tokens scoped to an organization only revoke that organization's sessions
+        let organization_id = utils::tenant::organization_id(&request_extensions)?;
+
+        // Revoke the sessions and queue the revocation event in one transaction
+        let mut transaction = self.database.begin().await?;
+
         // Revoke Sessions in database based on database row PK (id)
-        let rows_affected =
-            database::Sessions::revoke_user_id(&user_id, self.database_ref()).await?
-                as u64;
+        let rows_affected = match organization_id {
+            Some(organization_id) => {
+                database::Sessions::revoke_user_organization(
+                    &user_id,
+                    &organization_id,
+                    &mut *transaction,
+                )
+                .await?
+            }
+            None => database::Sessions::revoke_user_id(&user_id, &mut *transaction).await?,
+        } as u64;
+
+        let event = AuthEvent::new(AuthEventKind::Revocation)
+            .user(user_id)
+            .sessions_affected(rows_affected);
+        if rows_affected > 0 {
+            database::Outbox::new(event.clone())
+                .insert(&mut *transaction)
+                .await?;
+        }
+        transaction.commit().await?;
+
+        if rows_affected > 0 {
+            self.events.publish(event);
+        }
 
         // Build Sessions Response message
         let response_message = SessionsRevokeResponse { rows_affected };
@@ -219,12 +342,36 @@ impl Sessions for SessionsService {
         request: Request<Empty>,
     ) -> Result<Response<SessionsRevokeResponse>, Status> {
         // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
-        let (_request_metadata, _request_extensions, _request_message) =
+        let (_request_metadata, request_extensions, _request_message) =
             request.into_parts();
 
+        // Tokens scoped to an organization only revoke that organization's sessions
+        let organization_id = utils::tenant::organization_id(&request_extensions)?;
+
+        // Revoke the sessions and queue the revocation event in one transaction
+        let mut transaction = self.database.begin().await?;
+
         // Revoke (set is_active = false) all Access Tokens in the database
-        let rows_affected =
-            database::Sessions::revoke_all(self.database_ref()).await? as u64;
+        let rows_affected = match organization_id {
+            Some(organization_id) => {
+                database::Sessions::revoke_organization(&organization_id, &mut *transaction)
+                    .await?
+            }
+            None => database::Sessions::revoke_all(&mut *transaction).await?,
+        } as u64;
+
+        let event =
+            AuthEvent::new(AuthEventKind::Revocation).sessions_affected(rows_affected);
+        if rows_affected > 0 {
+            database::Outbox::new(event.clone())
+                .insert(&mut *transaction)
+                .await?;
+        }
+        transaction.commit().await?;
+
+        if rows_affected > 0 {
+            self.events.publish(event);
+        }
 
         // Build Session Response message
         let response_message = SessionsRevokeResponse { rows_affected };
@@ -239,8 +386,10 @@ impl Sessions for SessionsService {
         &self,
         request: Request<SessionsDeleteRequest>,
     ) -> Result<Response<SessionsDeleteResponse>, Status> {
+        validation::validate(&request)?;
+
         // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
-        let (_request_metadata, _request_extensions, request_message) =
+        let (_request_metadata, request_extensions, request_message) =
             request.into_parts();
 
         // Parse the request message string into a Uuid
@@ -251,9 +400,10 @@ impl Sessions for SessionsService {
             );
         })?;
 
+        self.check_organization(&request_extensions, &id).await?;
+
         // Revoke Session in database based on database row PK (id)
-        let rows_affected =
-            database::Sessions::delete_by_id(&id, self.database_ref()).await? as u64;
+        let rows_affected = self.sessions.delete_by_id(&id).await?;
 
         // Build Session Response message
         let response_message = SessionsDeleteResponse { rows_affected };
@@ -271,8 +421,10 @@ impl Sessions for SessionsService {
         &self,
         request: Request<SessionsDeleteUserRequest>,
     ) -> Result<Response<SessionsDeleteResponse>, Status> {
+        validation::validate(&request)?;
+
         // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
-        let (_request_metadata, _request_extensions, request_message) =
+        let (_request_metadata, request_extensions, request_message) =
             request.into_parts();
 
         // Parse the request message string into a Uuid
@@ -283,10 +435,18 @@ impl Sessions for SessionsService {
             );
         })?;
 
+        // Tokens scoped to an organization only delete that organization's sessions
+        let organization_id = utils::tenant::organization_id(&request_extensions)?;
+
         // Revoke Session in database based on database row PK (id)
-        let rows_affected =
-            database::Sessions::delete_all_user(&user_id, self.database_ref())
-                .await? as u64;
+        let rows_affected = match organization_id {
+            Some(organization_id) => {
+                self.sessions
+                    .delete_all_user_organization(&user_id, &organization_id)
+                    .await?
+            }
+            None => self.sessions.delete_all_user(&user_id).await?,
+        };
 
         // Build Session Response message
         let response_message = SessionsDeleteResponse { rows_affected };
@@ -305,12 +465,19 @@ impl Sessions for SessionsService {
         request: Request<Empty>,
     ) -> Result<Response<SessionsDeleteResponse>, Status> {
         // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
-        let (_request_metadata, _request_extensions, _request_message) =
+        let (_request_metadata, request_extensions, _request_message) =
             request.into_parts();
 
+        // Tokens scoped to an organization only delete that organization's sessions
+        let organization_id = utils::tenant::organization_id(&request_extensions)?;
+
         // Revoke (set is_active = false) all Access Tokens in the database
-        let rows_affected =
-            database::Sessions::delete_all(self.database_ref()).await? as u64;
+        let rows_affected = match organization_id {
+            Some(organization_id) => {
+                self.sessions.delete_all_organization(&organization_id).await?
+            }
+            None => self.sessions.delete_all().await?,
+        };
 
         // Build Session Response message
         let response_message = SessionsDeleteResponse { rows_affected };
@@ -9,34 +9,149 @@
 
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use secrecy::SecretString;
 use sqlx::{Pool, Postgres};
-use tonic::{Request, Response, Status};
+use tokio_stream::StreamExt;
+use tonic::{Request, Response, Status, Streaming};
 use uuid::Uuid;
 
-use crate::configuration::Configuration;
+use crate::avatars::{self, AvatarStorage};
+use crate::configuration::{Configuration, SharedConfiguration};
+use crate::events::{AuthEvent, AuthEventKind, AuthEvents};
+use crate::middleware::PolicyAcceptanceStore;
 use crate::prelude::AuthenticationError;
+use crate::repository::{PostgresRepository, UserRepository};
 use crate::rpc::proto::users_service_server::UsersService as Users;
+use crate::services::{validation, DeviceAuthorization, Passkeys, PasswordHasher, SamlLogin};
 use crate::rpc::proto::{
-    CreateUserRequest, DeleteUserRequest, DeleteUserResponse, ReadUserRequest,
+    AcceptPolicyRequest, AcceptPolicyResponse, ApproveDeviceAuthorizationRequest,
+    BeginIdentityLinkRequest, BeginIdentityLinkResponse, BeginPasskeyRegistrationResponse,
+    CreateUserRequest, DeleteUserRequest, DeleteUserResponse, Empty,
+    FinishPasskeyRegistrationRequest, GrantResponse, IdentityResponse, ListMyGrantsResponse,
+    ListMyIdentitiesResponse, ListMyLoginHistoryRequest, ListMyLoginHistoryResponse,
+    LoginHistoryResponse, PasskeyResponse, PreferencesResponse, ReadUserRequest,
+    RevokeGrantRequest, RevokeGrantResponse, SearchUsersRequest, SearchUsersResponse,
+    SetAvatarRequest, UnlinkIdentityRequest, UnlinkIdentityResponse, UpdatePreferencesRequest,
     UpdateUserRequest, UserIndexRequest, UserIndexResponse, UserResponse,
 };
-use crate::{database, domain};
+use crate::{database, domain, utils};
 
 /// User service containing a database pool
 // #[derive(Debug)]
 pub struct UsersService {
     database: Arc<Pool<Postgres>>,
-    #[allow(dead_code)]
-    config: Arc<Configuration>,
+    config: SharedConfiguration,
+    events: AuthEvents,
+    users: Arc<dyn UserRepository>,
+    password_hasher: PasswordHasher,
+    passkeys: Passkeys,
+    device_authorization: DeviceAuthorization,
+    policies: PolicyAcceptanceStore,
+    avatars: Option<Arc<dyn AvatarStorage>>,
+    saml: SamlLogin,
 }
 
 impl UsersService {
     /// Create a new UserService passing in the Arc for the Sqlx database pool
-    pub fn new(database: Arc<Pool<Postgres>>, config: Arc<Configuration>) -> Self {
-        Self { database, config }
+    pub fn new(
+        database: Arc<Pool<Postgres>>,
+        config: SharedConfiguration,
+        events: AuthEvents,
+    ) -> Self {
+        let users = Arc::new(PostgresRepository::new(Arc::clone(&database)));
+        let passkeys = Passkeys::new(Arc::clone(&database));
+        let device_authorization = DeviceAuthorization::new(Arc::clone(&database));
+        let policies = PolicyAcceptanceStore::new(Arc::clone(&config));
+        let saml = SamlLogin::new(Arc::clone(&database), events.clone());
+
+        Self {
+            database,
+            config,
+            events,
+            users,
+            password_hasher: PasswordHasher::default(),
+            passkeys,
+            device_authorization,
+            policies,
+            avatars: None,
+            saml,
+        }
+    }
+
+    /// Read users from another repository, such as `MemoryRepository` in tests
+    pub fn with_users_repository(mut self, users: Arc<dyn UserRepository>) -> Self {
+        self.users = users;
+        self
+    }
+
+    /// Share a password hasher, and its queue, with the other services
+    pub fn with_password_hasher(mut self, password_hasher: PasswordHasher) -> Self {
+        self.password_hasher = password_hasher;
+        self
+    }
+
+    /// Share the accepted policy versions with the authorisation layer
+    pub fn with_policy_acceptances(mut self, policies: PolicyAcceptanceStore) -> Self {
+        self.policies = policies;
+        self
+    }
+
+    /// Store uploaded avatars, uploads are rejected without a storage backend
+    pub fn with_avatar_storage(mut self, avatars: Option<Arc<dyn AvatarStorage>>) -> Self {
+        self.avatars = avatars;
+        self
+    }
+
+    /// Convert a user into a response message, with a signed URL for their
+    /// avatar if they uploaded one
+    async fn user_response(
+        &self,
+        user: database::Users,
+    ) -> Result<UserResponse, AuthenticationError> {
+        let Some(storage) = &self.avatars else {
+            return Ok(user.into());
+        };
+
+        let avatar = database::Avatars::for_user(&user.id, self.database_ref()).await?;
+        let expires_in = Duration::from_secs(self.config_ref().avatars.url_expiry_seconds);
+
+        let mut response_message: UserResponse = user.into();
+        response_message.avatar_url = avatar
+            .map(|avatar| storage.signed_url(&avatar.storage_key, expires_in))
+            .transpose()?;
+
+        Ok(response_message)
+    }
+
+    /// Tokens scoped to an organization can only reach that organization's
+    /// members, other users are not found
+    async fn check_organization(
+        &self,
+        request_extensions: &tonic::Extensions,
+        user_id: &Uuid,
+    ) -> Result<(), Status> {
+        let Some(organization_id) = utils::tenant::organization_id(request_extensions)?
+        else {
+            return Ok(());
+        };
+
+        match database::OrganizationMembers::from_ids(
+            &organization_id,
+            user_id,
+            self.database_ref(),
+        )
+        .await
+        {
+            Ok(_member) => Ok(()),
+            Err(AuthenticationError::Sqlx(sqlx::Error::RowNotFound)) => {
+                tracing::error!("User is not in the token organization: {user_id}");
+                Err(Status::not_found("User not found"))
+            }
+            Err(e) => Err(e.into()),
+        }
     }
 
     /// Shorthand for reference to database pool
@@ -45,9 +160,8 @@ impl UsersService {
         &self.database
     }
 
-    #[allow(dead_code)]
-    fn config_ref(&self) -> &Configuration {
-        &self.config
+    fn config_ref(&self) -> Arc<Configuration> {
+        self.config.load_full()
     }
 }
 
@@ -65,6 +179,11 @@ impl TryFrom<CreateUserRequest> for database::Users {
         let is_active = value.is_active;
         let is_verified = value.is_verified;
         let created_on = Utc::now();
+        let locale = value
+            .locale
+            .map(domain::Locale::parse)
+            .transpose()?
+            .unwrap_or_default();
 
         Ok(Self {
             id,
@@ -75,6 +194,7 @@ impl TryFrom<CreateUserRequest> for database::Users {
             is_active,
             is_verified,
             created_on,
+            locale,
         })
     }
 }
@@ -96,6 +216,8 @@ impl TryFrom<UpdateUserRequest> for database::Users {
         let is_verified = value.is_verified;
         // I do not get updated
         let created_on = Utc::now();
+        // I am updated separately with `update_locale`
+        let locale = domain::Locale::default();
 
         Ok(Self {
             id,
@@ -106,6 +228,46 @@ impl TryFrom<UpdateUserRequest> for database::Users {
             is_active,
             is_verified,
             created_on,
+            locale,
+        })
+    }
+}
+
+/// Parse an optional timestamp string from a request message
+fn parse_optional_datetime(
+    value: Option<String>,
+) -> Result<Option<DateTime<Utc>>, AuthenticationError> {
+    value
+        .map(|v| v.parse::<DateTime<Utc>>())
+        .transpose()
+        .map_err(AuthenticationError::from)
+}
+
+/// Convert a Search Users Request message into a database::UsersSearchFilter
+impl TryFrom<&SearchUsersRequest> for database::UsersSearchFilter {
+    type Error = AuthenticationError;
+
+    fn try_from(value: &SearchUsersRequest) -> Result<Self, Self::Error> {
+        let email = value.email.clone();
+        let role = value
+            .role
+            .as_deref()
+            .map(domain::UserRole::from_str)
+            .transpose()?;
+        let is_active = value.is_active;
+        let is_verified = value.is_verified;
+        let created_after = parse_optional_datetime(value.created_after.clone())?;
+        let created_before = parse_optional_datetime(value.created_before.clone())?;
+
+        Ok(Self {
+            email,
+            role,
+            is_active,
+            is_verified,
+            created_after,
+            created_before,
+            // Scoped from the access token by the handler, not the request
+            organization_id: None,
         })
     }
 }
@@ -120,6 +282,9 @@ impl From<database::Users> for UserResponse {
         let is_active = value.is_active;
         let is_verified = value.is_verified;
         let created_on = value.created_on.to_string();
+        let locale = value.locale.to_string();
+        // Signed separately, see `UsersService::user_response`
+        let avatar_url = None;
 
         Self {
             id,
@@ -129,10 +294,81 @@ impl From<database::Users> for UserResponse {
             is_active,
             is_verified,
             created_on,
+            locale,
+            avatar_url,
+        }
+    }
+}
+
+impl From<database::WebauthnCredentials> for PasskeyResponse {
+    fn from(value: database::WebauthnCredentials) -> Self {
+        PasskeyResponse {
+            id: value.id.to_string(),
+            name: value.name,
+            created_on: value.created_on.to_rfc3339(),
+            last_used_on: value.last_used_on.map(|used_on| used_on.to_rfc3339()),
+        }
+    }
+}
+
+impl From<database::Logins> for LoginHistoryResponse {
+    fn from(value: database::Logins) -> Self {
+        Self {
+            id: value.id.to_string(),
+            ip_address: value.ip_address,
+            user_agent: value.user_agent,
+            outcome: value.outcome.to_string(),
+            // RFC 3339, so it can be sent back as the next page cursor
+            created_on: value.created_on.to_rfc3339(),
+        }
+    }
+}
+
+impl From<database::FederatedIdentities> for IdentityResponse {
+    fn from(value: database::FederatedIdentities) -> Self {
+        Self {
+            id: value.id.to_string(),
+            provider: value.provider,
+            subject: value.subject,
+            email: value.email,
+            created_on: value.created_on.to_rfc3339(),
         }
     }
 }
 
+/// Build a grant response, named after the client the user granted access to
+fn grant_response(grant: database::Grants, client: &database::Clients) -> GrantResponse {
+    GrantResponse {
+        grant_id: grant.id.to_string(),
+        client_id: grant.client_id.to_string(),
+        client_name: client.name.clone(),
+        scopes: grant.scopes,
+        created_on: grant.created_on.to_rfc3339(),
+        updated_on: grant.updated_on.to_rfc3339(),
+        revoked_on: grant.revoked_on.map(|revoked_on| revoked_on.to_rfc3339()),
+    }
+}
+
+/// Build a preferences response, with the locale kept on the user
+fn preferences_response(
+    user: &database::Users,
+    preferences: database::UserPreferences,
+) -> PreferencesResponse {
+    PreferencesResponse {
+        locale: user.locale.to_string(),
+        timezone: preferences.timezone.to_string(),
+        notification_opt_outs: preferences.notification_opt_outs,
+        custom_json: preferences.custom.0.to_string(),
+        updated_on: preferences.updated_on.to_rfc3339(),
+    }
+}
+
+/// Get the caller's user id from the auth context added by the authorisation
+/// layer. API keys do not belong to a user.
+fn caller_user_id(request_extensions: &tonic::Extensions) -> Result<Uuid, Status> {
+    Ok(domain::AuthContext::from_extensions(request_extensions)?.user_id)
+}
+
 #[tonic::async_trait]
 impl Users for UsersService {
     /// Handle rpc requests to create a user in the database
@@ -147,11 +383,26 @@ impl Users for UsersService {
         let (_request_metadata, _request_extensions, request_message) =
             request.into_parts();
 
-        // Convert create user request message into a user instance
-        let user: database::Users = request_message.try_into()?;
+        // Convert create user request message into a user instance, hashing
+        // the password off the async runtime
+        let user: database::Users = self
+            .password_hasher
+            .spawn(move || request_message.try_into())
+            .await?;
+
+        // Insert the user and queue the registration event in one transaction
+        let mut transaction = self.database.begin().await?;
 
         // Insert user into the database
-        let database_record = user.insert(self.database_ref()).await?;
+        let database_record = user.insert(&mut *transaction).await?;
+
+        // Queue the registration for the webhooks, then publish it once saved
+        let event = AuthEvent::new(AuthEventKind::Registration).user(database_record.id);
+        database::Outbox::new(event.clone())
+            .insert(&mut *transaction)
+            .await?;
+        transaction.commit().await?;
+        self.events.publish(event);
 
         // Convert database user record into a user response message
         let response_message: UserResponse = database_record.into();
@@ -171,8 +422,10 @@ impl Users for UsersService {
         &self,
         request: Request<ReadUserRequest>,
     ) -> Result<Response<UserResponse>, Status> {
+        validation::validate(&request)?;
+
         // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
-        let (_request_metadata, _request_extensions, request_message) =
+        let (_request_metadata, request_extensions, request_message) =
             request.into_parts();
 
         let id = Uuid::parse_str(&request_message.id).map_err(|_| {
@@ -182,15 +435,327 @@ impl Users for UsersService {
             );
         })?;
 
-        let database_record =
-            database::Users::from_user_id(&id, self.database_ref()).await?;
+        self.check_organization(&request_extensions, &id).await?;
+
+        let database_record = self.users.from_user_id(&id).await?;
 
         // Convert database user record into a user response message
-        let response_message: UserResponse = database_record.into();
+        let response_message = self.user_response(database_record).await?;
+
+        Ok(Response::new(response_message))
+    }
+
+    /// Handle rpc requests for the caller's own profile, resolved from the
+    /// access token so no admin role is needed
+    #[tracing::instrument(name = "Get Me Request: ", skip(self, request))]
+    async fn get_me(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<UserResponse>, Status> {
+        // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
+        let (_request_metadata, request_extensions, _request_message) =
+            request.into_parts();
+
+        let user_id = caller_user_id(&request_extensions)?;
+
+        let database_record = self
+            .users
+            .from_user_id(&user_id)
+            .await
+            .map_err(|_| {
+                tracing::error!("User id not found in database: {user_id}");
+                Status::unauthenticated("Authentication Failed!")
+            })?;
+
+        // Convert database user record into a user response message
+        let response_message = self.user_response(database_record).await?;
 
         Ok(Response::new(response_message))
     }
 
+    /// Handle rpc requests to set the caller's avatar. The image is streamed
+    /// in chunks, checked, cropped and resized to a square PNG, then stored in
+    /// the configured backend. The caller's profile is returned with a signed
+    /// URL for the new avatar.
+    #[tracing::instrument(name = "Set Avatar Request: ", skip(self, request))]
+    async fn set_avatar(
+        &self,
+        request: Request<Streaming<SetAvatarRequest>>,
+    ) -> Result<Response<UserResponse>, Status> {
+        // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
+        let (_request_metadata, request_extensions, mut stream) = request.into_parts();
+
+        let user_id = caller_user_id(&request_extensions)?;
+        let storage = self.avatars.clone().ok_or_else(|| {
+            Status::failed_precondition("Avatar uploads are not enabled")
+        })?;
+        let config = self.config_ref();
+        let (max_upload_bytes, size_pixels) =
+            (config.avatars.max_upload_bytes, config.avatars.size_pixels);
+
+        // Collect the chunks, stopping as soon as the upload is too large
+        let mut upload: Vec<u8> = Vec::new();
+        while let Some(message) = stream.next().await {
+            let chunk = message?.chunk;
+            if upload.len() + chunk.len() > max_upload_bytes {
+                return Err(AuthenticationError::InvalidImage(format!(
+                    "Avatars can't be larger than {max_upload_bytes} bytes"
+                ))
+                .into());
+            }
+            upload.extend_from_slice(&chunk);
+        }
+        if upload.is_empty() {
+            let error = AuthenticationError::InvalidImage("No avatar was uploaded".to_string());
+            return Err(error.into());
+        }
+
+        let user = self.users.from_user_id(&user_id).await.map_err(|_| {
+            tracing::error!("User id not found in database: {user_id}");
+            Status::unauthenticated("Authentication Failed!")
+        })?;
+
+        // Decoding and resizing are CPU bound, keep them off the async runtime
+        let avatar = tokio::task::spawn_blocking(move || avatars::process(&upload, size_pixels))
+            .await
+            .map_err(|e| {
+                AuthenticationError::Generic(format!("Avatar processing task failed: {e}"))
+            })??;
+
+        // Store the image before recording it, then remove the image it replaced
+        let previous = database::Avatars::for_user(&user_id, self.database_ref()).await?;
+        let record = database::Avatars::new(&user_id, &avatar, avatars::CONTENT_TYPE);
+        storage.put(&record.storage_key, avatar).await?;
+        let record = record.upsert(self.database_ref()).await?;
+        if let Some(previous) = previous.filter(|p| p.storage_key != record.storage_key) {
+            if let Err(e) = storage.delete(&previous.storage_key).await {
+                tracing::warn!("Unable to remove replaced avatar {}: {e}", previous.storage_key);
+            }
+        }
+
+        tracing::info!("Avatar set for user {user_id}: {}", record.storage_key);
+
+        let response_message = self.user_response(user).await?;
+
+        Ok(Response::new(response_message))
+    }
+
+    /// Handle rpc requests to accept the configured version of a policy. The
+    /// acceptance is recorded with the caller's address, and the policies
+    /// still to accept are returned. Impersonating admins can't accept
+    /// policies for the user.
+    #[tracing::instrument(name = "Accept Policy Request: ", skip(self, request))]
+    async fn accept_policy(
+        &self,
+        request: Request<AcceptPolicyRequest>,
+    ) -> Result<Response<AcceptPolicyResponse>, Status> {
+        let ip_address = Some(
+            utils::client_ip::client_ip(&request, &self.config_ref().trusted_proxies)
+                .to_string(),
+        );
+
+        // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
+        let (_request_metadata, request_extensions, request_message) =
+            request.into_parts();
+
+        let user_id = caller_user_id(&request_extensions)?;
+        let is_impersonated = request_extensions
+            .get::<domain::TokenClaim>()
+            .is_some_and(|claim| claim.act.is_some());
+        if is_impersonated {
+            return Err(Status::permission_denied(
+                "Policies can't be accepted while impersonating",
+            ));
+        }
+
+        let policy = request_message
+            .policy
+            .parse::<domain::Policy>()
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        // Only the configured version can be accepted
+        let config = self.config_ref();
+        let version = config
+            .policies
+            .version(policy)
+            .ok_or_else(|| Status::failed_precondition(format!("No {policy} is configured")))?;
+        if request_message.version != version {
+            return Err(Status::failed_precondition(format!(
+                "The current {policy} version is {version}"
+            )));
+        }
+
+        let acceptance =
+            database::PolicyAcceptances::new(&user_id, policy, version, ip_address.as_deref())
+                .insert(self.database_ref())
+                .await?;
+        self.policies.accept(&user_id, policy, &acceptance.version);
+
+        tracing::info!("User {user_id} accepted {policy} version {version}");
+
+        let response_message = AcceptPolicyResponse {
+            policy: acceptance.policy,
+            version: acceptance.version,
+            accepted_on: acceptance.accepted_on.to_string(),
+            outstanding: self.policies.outstanding(&user_id),
+        };
+
+        Ok(Response::new(response_message))
+    }
+
+    /// Handle rpc requests for the caller's preferences, the defaults when
+    /// they have not set any
+    #[tracing::instrument(name = "Get Preferences Request: ", skip(self, request))]
+    async fn get_preferences(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<PreferencesResponse>, Status> {
+        // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
+        let (_request_metadata, request_extensions, _request_message) =
+            request.into_parts();
+
+        let user_id = caller_user_id(&request_extensions)?;
+
+        let user = self.users.from_user_id(&user_id).await.map_err(|_| {
+            tracing::error!("User id not found in database: {user_id}");
+            Status::unauthenticated("Authentication Failed!")
+        })?;
+        let preferences =
+            database::UserPreferences::for_user(&user_id, self.database_ref()).await?;
+
+        Ok(Response::new(preferences_response(&user, preferences)))
+    }
+
+    /// Handle rpc requests to update the caller's preferences. Only the fields
+    /// set are changed. The client specific preferences are merged, keys set
+    /// to `null` are removed.
+    #[tracing::instrument(name = "Update Preferences Request: ", skip(self, request))]
+    async fn update_preferences(
+        &self,
+        request: Request<UpdatePreferencesRequest>,
+    ) -> Result<Response<PreferencesResponse>, Status> {
+        // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
+        let (_request_metadata, request_extensions, request_message) =
+            request.into_parts();
+
+        let user_id = caller_user_id(&request_extensions)?;
+
+        // Check every field before saving any of them
+        let locale = request_message
+            .locale
+            .map(domain::Locale::parse)
+            .transpose()
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let timezone = request_message
+            .timezone
+            .map(domain::TimeZone::parse)
+            .transpose()
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let notification_opt_outs = request_message
+            .notification_opt_outs
+            .map(|opt_outs| {
+                opt_outs
+                    .notifications
+                    .iter()
+                    .map(|notification| notification.parse::<domain::Notification>())
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let custom = request_message
+            .custom_json
+            .map(|custom| serde_json::from_str::<serde_json::Value>(&custom))
+            .transpose()
+            .map_err(|_| Status::invalid_argument("custom_json is not valid JSON"))?;
+
+        let mut user = self.users.from_user_id(&user_id).await.map_err(|_| {
+            tracing::error!("User id not found in database: {user_id}");
+            Status::unauthenticated("Authentication Failed!")
+        })?;
+        let mut preferences =
+            database::UserPreferences::for_user(&user_id, self.database_ref()).await?;
+
+        if let Some(timezone) = timezone {
+            preferences.timezone = timezone;
+        }
+        if let Some(notification_opt_outs) = notification_opt_outs {
+            preferences.set_notification_opt_outs(&notification_opt_outs);
+        }
+        if let Some(custom) = custom {
+            preferences
+                .merge_custom(custom)
+                .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        }
+        let preferences = preferences.upsert(self.database_ref()).await?;
+
+        if let Some(locale) = locale {
+            user = user.update_locale(&locale, self.database_ref()).await?;
+        }
+
+        tracing::info!("Preferences updated for user {user_id}");
+
+        Ok(Response::new(preferences_response(&user, preferences)))
+    }
+
+    /// Handle rpc requests for the caller's own login history, newest first,
+    /// paged with the `created_on` and `id` of the last login in the previous page
+    #[tracing::instrument(name = "List My Login History Request: ", skip(self, request))]
+    async fn list_my_login_history(
+        &self,
+        request: Request<ListMyLoginHistoryRequest>,
+    ) -> Result<Response<ListMyLoginHistoryResponse>, Status> {
+        validation::validate(&request)?;
+
+        // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
+        let (_request_metadata, request_extensions, request_message) =
+            request.into_parts();
+
+        let user_id = caller_user_id(&request_extensions)?;
+
+        // The number of logins to be returned
+        let limit: usize = request_message
+            .limit
+            .try_into()
+            .map_err(|_| Status::invalid_argument("Invalid limit value"))?;
+
+        // Cursor, the created on and id of the last login in the previous page
+        let cursor_created_on =
+            parse_optional_datetime(request_message.cursor_created_on)
+                .map_err(|_| Status::invalid_argument("Invalid cursor value"))?;
+        let cursor_id = request_message
+            .cursor_id
+            .map(|id| Uuid::parse_str(&id))
+            .transpose()
+            .map_err(|_| Status::invalid_argument("Invalid cursor value"))?;
+
+        let database_records = database::Logins::index_user(
+            &user_id,
+            &utils::pagination::query_limit(limit),
+            cursor_created_on,
+            cursor_id,
+            self.database_ref(),
+        )
+        .await
+        .map_err(|e| match e {
+            AuthenticationError::ValidationError(m) => Status::invalid_argument(m),
+            e => e.into(),
+        })?;
+
+        // Trim the extra login and build the cursor for the next page
+        let page = utils::pagination::Page::from_records(database_records, limit, |login| {
+            (login.created_on, login.id)
+        });
+
+        let logins: Vec<LoginHistoryResponse> =
+            page.records.into_iter().map(|login| login.into()).collect();
+
+        Ok(Response::new(ListMyLoginHistoryResponse {
+            logins,
+            next_cursor: page.next_cursor,
+            has_more: page.has_more,
+        }))
+    }
+
     /// Handle rpc requests to get a user index of the database
     #[tracing::instrument(
         name = "Read User Index Request: ",
@@ -203,10 +768,15 @@ impl Users for UsersService {
         &self,
         request: Request<UserIndexRequest>,
     ) -> Result<Response<UserIndexResponse>, Status> {
+        validation::validate(&request)?;
+
         // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
-        let (_request_metadata, _request_extensions, request_message) =
+        let (_request_metadata, request_extensions, request_message) =
             request.into_parts();
-        
+
+        // Tokens scoped to an organization only see that organization's users
+        let organization_id = utils::tenant::organization_id(&request_extensions)?;
+
         // Offset, where to start the records from
         let offset: usize = request_message.offset.try_into().map_err(|_| {
             Status::invalid_argument("Invalid offset value")
@@ -218,8 +788,14 @@ impl Users for UsersService {
         })?;
 
         // Query the database
-        let database_records =
-            database::Users::index(&limit, &offset, self.database_ref()).await?;
+        let database_records = match organization_id {
+            Some(organization_id) => {
+                self.users
+                    .index_organization(&organization_id, &limit, &offset)
+                    .await?
+            }
+            None => self.users.index(&limit, &offset).await?,
+        };
 
         // Convert database::Users into User Response within the vector
         let users_response: Vec<UserResponse> = database_records
@@ -235,6 +811,68 @@ impl Users for UsersService {
         Ok(Response::new(response))
     }
 
+    /// Handle rpc requests to search users in the database
+    #[tracing::instrument(name = "Search Users Request: ", skip(self, request))]
+    async fn search_users(
+        &self,
+        request: Request<SearchUsersRequest>,
+    ) -> Result<Response<SearchUsersResponse>, Status> {
+        validation::validate(&request)?;
+
+        // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
+        let (_request_metadata, request_extensions, request_message) =
+            request.into_parts();
+
+        // Convert the request filters into a database search filter
+        let mut filter: database::UsersSearchFilter = (&request_message)
+            .try_into()
+            .map_err(|_| Status::invalid_argument("Invalid search filter"))?;
+
+        // Tokens scoped to an organization only find that organization's users
+        filter.organization_id = utils::tenant::organization_id(&request_extensions)?;
+
+        // The number of users to be returned
+        let limit: usize = request_message
+            .limit
+            .try_into()
+            .map_err(|_| Status::invalid_argument("Invalid limit value"))?;
+
+        // Cursor, the created on and id of the last user in the previous page
+        let cursor_created_on =
+            parse_optional_datetime(request_message.cursor_created_on)
+                .map_err(|_| Status::invalid_argument("Invalid cursor value"))?;
+        let cursor_id = request_message
+            .cursor_id
+            .map(|id| Uuid::parse_str(&id))
+            .transpose()
+            .map_err(|_| Status::invalid_argument("Invalid cursor value"))?;
+
+        // Query the database
+        let database_records = self
+            .users
+            .search(
+                &filter,
+                &utils::pagination::query_limit(limit),
+                cursor_created_on,
+                cursor_id,
+            )
+            .await?;
+
+        // Trim the extra user and build the cursor for the next page
+        let page = utils::pagination::Page::from_records(database_records, limit, |user| {
+            (user.created_on, user.id)
+        });
+
+        // Convert database::Users into User Response within the vector
+        let users: Vec<UserResponse> = page.records.into_iter().map(|user| user.into()).collect();
+
+        Ok(Response::new(SearchUsersResponse {
+            users,
+            next_cursor: page.next_cursor,
+            has_more: page.has_more,
+        }))
+    }
+
     /// Handle rpc requests to update a user in the database
     #[tracing::instrument(
         name = "Update User Request: ",
@@ -248,14 +886,60 @@ impl Users for UsersService {
         request: Request<UpdateUserRequest>,
     ) -> Result<Response<UserResponse>, Status> {
         // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
-        let (_request_metadata, _request_extensions, request_message) =
+        let (_request_metadata, request_extensions, request_message) =
             request.into_parts();
 
-        // Convert create user request message into a user instance
-        let user: database::Users = request_message.try_into()?;
+        // The locale is only changed when the request sets it
+        let locale = request_message
+            .locale
+            .clone()
+            .map(domain::Locale::parse)
+            .transpose()?;
+
+        // Convert update user request message into a user instance, hashing
+        // the placeholder password off the async runtime
+        let user: database::Users = self
+            .password_hasher
+            .spawn(move || request_message.try_into())
+            .await?;
+
+        self.check_organization(&request_extensions, &user.id).await?;
+
+        // Check the current record, so verifying the user can be published
+        let current = database::Users::from_user_id(&user.id, self.database_ref()).await?;
+        let was_verified = current.is_verified;
+
+        // Email changes need confirming from both addresses, see `request_email_change`
+        if user.email != current.email {
+            return Err(Status::failed_precondition(
+                "Email can not be updated directly, request an email change",
+            ));
+        }
+
+        // Update the user and queue any verification event in one transaction
+        let mut transaction = self.database.begin().await?;
 
         // Insert user into the database
-        let database_record = user.update(self.database_ref()).await?;
+        let mut database_record = user.update(&mut *transaction).await?;
+
+        let event = (!was_verified && database_record.is_verified)
+            .then(|| AuthEvent::new(AuthEventKind::Verification).user(database_record.id));
+        if let Some(event) = &event {
+            database::Outbox::new(event.clone())
+                .insert(&mut *transaction)
+                .await?;
+        }
+        transaction.commit().await?;
+
+        if let Some(event) = event {
+            self.events.publish(event);
+        }
+
+        if let Some(locale) = locale {
+            database_record = database_record
+                .update_locale(&locale, self.database_ref())
+                .await?;
+        }
 
         // Convert database user record into a user response message
         let response_message: UserResponse = database_record.into();
@@ -275,8 +959,10 @@ impl Users for UsersService {
         &self,
         request: Request<DeleteUserRequest>,
     ) -> Result<Response<DeleteUserResponse>, Status> {
+        validation::validate(&request)?;
+
         // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
-        let (_request_metadata, _request_extensions, request_message) =
+        let (_request_metadata, request_extensions, request_message) =
             request.into_parts();
 
         let id = Uuid::parse_str(&request_message.id).map_err(|_| {
@@ -286,15 +972,264 @@ impl Users for UsersService {
             );
         })?;
 
+        self.check_organization(&request_extensions, &id).await?;
+
         let database_record =
             database::Users::from_user_id(&id, self.database_ref()).await?;
 
-        let rows_affected =
-            database_record.delete(self.database_ref()).await? as u64;
+        // Soft delete the user and revoke their sessions together, an admin
+        // can restore the user with `RestoreUser`
+        let mut transaction = self.database.begin().await?;
+        let rows_affected = database_record.delete(&mut *transaction).await?;
+        database::Sessions::revoke_user_id(&id, &mut *transaction).await?;
+        transaction.commit().await?;
 
         // Convert database user record into a user response message
         let response_message = DeleteUserResponse { rows_affected };
 
         Ok(Response::new(response_message))
     }
+
+    /// Begin registering a passkey for the caller, returning the WebAuthn
+    /// options for `navigator.credentials.create()` as JSON
+    #[tracing::instrument(name = "Begin Passkey Registration Request: ", skip(self, request))]
+    async fn begin_passkey_registration(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<BeginPasskeyRegistrationResponse>, Status> {
+        // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
+        let (_request_metadata, request_extensions, _request_message) =
+            request.into_parts();
+
+        let user_id = caller_user_id(&request_extensions)?;
+        let user = database::Users::from_user_id(&user_id, self.database_ref()).await?;
+
+        let challenge = self
+            .passkeys
+            .begin_registration(&self.config_ref().passkeys, &user)
+            .await?;
+
+        Ok(Response::new(BeginPasskeyRegistrationResponse {
+            challenge_id: challenge.challenge_id.to_string(),
+            options: challenge.options,
+        }))
+    }
+
+    /// Finish registering a passkey for the caller with the credential created
+    /// by the browser
+    #[tracing::instrument(name = "Finish Passkey Registration Request: ", skip(self, request))]
+    async fn finish_passkey_registration(
+        &self,
+        request: Request<FinishPasskeyRegistrationRequest>,
+    ) -> Result<Response<PasskeyResponse>, Status> {
+        // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
+        let (_request_metadata, request_extensions, request_message) =
+            request.into_parts();
+
+        let user_id = caller_user_id(&request_extensions)?;
+
+        // Name the passkey so the user can tell them apart
+        let name = match request_message.name.as_deref().map(str::trim) {
+            None | Some("") => "Passkey",
+            Some(name) => name,
+        };
+
+        let credential = self
+            .passkeys
+            .finish_registration(
+                &self.config_ref().passkeys,
+                &user_id,
+                &request_message.challenge_id,
+                &request_message.credential,
+                name,
+            )
+            .await?;
+        tracing::info!("Passkey registered for user {user_id}: {}", credential.id);
+
+        Ok(Response::new(credential.into()))
+    }
+
+    /// Approve or deny the device showing the user code, so it logs in as the
+    /// caller with `TokenFromDeviceCode`
+    #[tracing::instrument(name = "Approve Device Authorization Request: ", skip(self, request))]
+    async fn approve_device_authorization(
+        &self,
+        request: Request<ApproveDeviceAuthorizationRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
+        let (_request_metadata, request_extensions, request_message) =
+            request.into_parts();
+
+        let user_id = caller_user_id(&request_extensions)?;
+
+        let grant = self
+            .device_authorization
+            .answer(
+                &self.config_ref().device_authorization,
+                &request_message.user_code,
+                &user_id,
+                request_message.approve,
+            )
+            .await?;
+        tracing::info!(
+            "Device authorization {} by user {user_id}: {}",
+            grant.status,
+            grant.id
+        );
+
+        Ok(Response::new(Empty {}))
+    }
+
+    /// Handle rpc requests for the OAuth clients the caller has granted
+    /// access to, most recently granted first, including revoked grants
+    #[tracing::instrument(name = "List My Grants Request: ", skip(self, request))]
+    async fn list_my_grants(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<ListMyGrantsResponse>, Status> {
+        // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
+        let (_request_metadata, request_extensions, _request_message) =
+            request.into_parts();
+
+        let user_id = caller_user_id(&request_extensions)?;
+
+        let database_records =
+            database::Grants::index_user(&user_id, self.database_ref()).await?;
+
+        // A user grants few clients, so look each one up for its name
+        let mut grants = Vec::with_capacity(database_records.len());
+        for grant in database_records {
+            let client =
+                database::Clients::from_id(&grant.client_id, self.database_ref()).await?;
+            grants.push(grant_response(grant, &client));
+        }
+
+        Ok(Response::new(ListMyGrantsResponse { grants }))
+    }
+
+    /// Handle rpc requests to revoke one of the caller's grants. The client's
+    /// sessions for the caller are revoked, so it can't refresh its tokens,
+    /// and it must be granted access again to log in.
+    #[tracing::instrument(name = "Revoke Grant Request: ", skip(self, request))]
+    async fn revoke_grant(
+        &self,
+        request: Request<RevokeGrantRequest>,
+    ) -> Result<Response<RevokeGrantResponse>, Status> {
+        validation::validate(&request)?;
+
+        // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
+        let (_request_metadata, request_extensions, request_message) =
+            request.into_parts();
+
+        let user_id = caller_user_id(&request_extensions)?;
+
+        let grant_id = Uuid::parse_str(&request_message.grant_id)
+            .map_err(|_| Status::invalid_argument("Invalid grant id"))?;
+
+        let grant = database::Grants::revoke(&grant_id, &user_id, self.database_ref())
+            .await?
+            .ok_or_else(|| Status::not_found("Grant not found or already revoked"))?;
+
+        let sessions_revoked =
+            database::Sessions::revoke_user_client(&user_id, &grant.client_id, self.database_ref())
+                .await?;
+        tracing::info!(
+            "User {user_id} revoked client {} access, sessions revoked: {sessions_revoked}",
+            grant.client_id
+        );
+
+        Ok(Response::new(RevokeGrantResponse {
+            success: true,
+            message: "Grant revoked".to_string(),
+        }))
+    }
+
+    /// Handle rpc requests to link a federated identity to the caller's
+    /// account. The browser is sent to the identity provider, and the
+    /// identity it answers with is linked once the caller has logged in there.
+    #[tracing::instrument(name = "Begin Identity Link Request: ", skip(self, request))]
+    async fn begin_identity_link(
+        &self,
+        request: Request<BeginIdentityLinkRequest>,
+    ) -> Result<Response<BeginIdentityLinkResponse>, Status> {
+        // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
+        let (_request_metadata, request_extensions, request_message) =
+            request.into_parts();
+
+        let user_id = caller_user_id(&request_extensions)?;
+        let is_impersonated = request_extensions
+            .get::<domain::TokenClaim>()
+            .is_some_and(|claim| claim.act.is_some());
+        if is_impersonated {
+            return Err(Status::permission_denied(
+                "Identities can't be linked while impersonating",
+            ));
+        }
+
+        if request_message.provider != "saml" {
+            return Err(Status::invalid_argument(format!(
+                "Unknown identity provider: {}",
+                request_message.provider
+            )));
+        }
+
+        let config = self.config_ref();
+        let redirect_url = self.saml.begin(&config.saml, Some(&user_id)).await?;
+
+        Ok(Response::new(BeginIdentityLinkResponse { redirect_url }))
+    }
+
+    /// Handle rpc requests for the federated identities linked to the
+    /// caller's account, oldest first
+    #[tracing::instrument(name = "List My Identities Request: ", skip(self, request))]
+    async fn list_my_identities(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<ListMyIdentitiesResponse>, Status> {
+        // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
+        let (_request_metadata, request_extensions, _request_message) =
+            request.into_parts();
+
+        let user_id = caller_user_id(&request_extensions)?;
+
+        let identities =
+            database::FederatedIdentities::index_user(&user_id, self.database_ref())
+                .await?
+                .into_iter()
+                .map(IdentityResponse::from)
+                .collect();
+
+        Ok(Response::new(ListMyIdentitiesResponse { identities }))
+    }
+
+    /// Handle rpc requests to unlink one of the caller's federated identities
+    #[tracing::instrument(name = "Unlink Identity Request: ", skip(self, request))]
+    async fn unlink_identity(
+        &self,
+        request: Request<UnlinkIdentityRequest>,
+    ) -> Result<Response<UnlinkIdentityResponse>, Status> {
+        validation::validate(&request)?;
+
+        // Break up the request into its three parts: 1. Metadata, 2. Extensions & 3. Message
+        let (_request_metadata, request_extensions, request_message) =
+            request.into_parts();
+
+        let user_id = caller_user_id(&request_extensions)?;
+
+        let identity_id = Uuid::parse_str(&request_message.id)
+            .map_err(|_| Status::invalid_argument("Invalid identity id"))?;
+
+        let rows_affected = database::FederatedIdentities::delete_for_user(
+            &identity_id,
+            &user_id,
+            self.database_ref(),
+        )
+        .await?;
+        if rows_affected == 0 {
+            return Err(Status::not_found("Identity not found"));
+        }
+        tracing::info!("User {user_id} unlinked identity: {identity_id}");
+
+        Ok(Response::new(UnlinkIdentityResponse { rows_affected }))
+    }
 }
@@ -1,26 +1,100 @@
 //-- ./src/rpc/utilities.rs
 
 //! Return a result containing an RPC Utilities server
+//!
+//! `ReadinessReport` returns the latest startup readiness checks, see
+//! `readiness`, for debugging a server that never reports healthy.
+//!
+//! `ServerInfo` and `ServerTime` report what is running and the server clock,
+//! for operations and SDK smoke tests. `Echo` returns the message with the
+//! request metadata the server received, for debugging clients and proxies
+//! that drop headers. Credentials in the metadata are redacted.
+//!
+//! `SetLogFilter` changes the log filter at runtime for admins, e.g. to debug
+//! one module for ten minutes before the configured log level is restored.
 
 // #![allow(unused)] // For beginning only.
 
-use std::sync::Arc;
+use std::collections::HashMap;
 
+use chrono::{DateTime, Utc};
+use tonic::metadata::KeyAndValueRef;
 use tonic::{Request, Response, Status};
 
-use crate::configuration::Configuration;
-use crate::rpc::proto::{Empty, PingResponse};
+use crate::build_info;
+use crate::configuration::SharedConfiguration;
+use crate::readiness::Readiness;
+use crate::rpc::proto::{
+    EchoRequest, EchoResponse, Empty, PingResponse, ReadinessCheck, ReadinessReportResponse,
+    ServerInfoResponse, ServerTimeResponse, SetLogFilterRequest, SetLogFilterResponse,
+};
+use crate::services::validation;
+use crate::telemetry::{self, LogLevelHandle};
 use crate::rpc::proto::utilities_service_server::UtilitiesService as Utilities;
 
+/// Metadata holding credentials, echoed back redacted
+const REDACTED_METADATA: [&str; 3] = ["authorization", "cookie", "x-api-key"];
+
+/// The value echoed back for redacted metadata
+const REDACTED: &str = "[redacted]";
+
 // #[derive(Debug, Default)]
 pub struct UtilitiesService {
-    #[allow(dead_code)]
-    config: Arc<Configuration>,
+    config: SharedConfiguration,
+    readiness: Readiness,
+    started_at: DateTime<Utc>,
+    log_level_handle: Option<LogLevelHandle>,
 }
 
 impl UtilitiesService {
-    pub fn new(config: Arc<Configuration>) -> Self {
-        Self { config }
+    pub fn new(config: SharedConfiguration) -> Self {
+        Self {
+            config,
+            readiness: Readiness::default(),
+            started_at: Utc::now(),
+            log_level_handle: telemetry::log_level_handle(),
+        }
+    }
+
+    /// The text metadata of a request, keyed by name with repeated values
+    /// joined by commas. Credentials, including a custom refresh token
+    /// `refresh_token.metadata_key`, are redacted.
+    fn reflect_metadata(&self, metadata: &tonic::metadata::MetadataMap) -> HashMap<String, String> {
+        let refresh_token_key = self.config.load().refresh_token.metadata_key.clone();
+        let is_redacted = |key: &str| {
+            REDACTED_METADATA.contains(&key)
+                || refresh_token_key
+                    .as_ref()
+                    .is_some_and(|refresh_token_key| refresh_token_key.eq_ignore_ascii_case(key))
+        };
+
+        let mut reflected: HashMap<String, String> = HashMap::new();
+        for entry in metadata.iter() {
+            // Binary metadata is skipped, it would need encoding to echo back
+            let KeyAndValueRef::Ascii(key, value) = entry else {
+                continue;
+            };
+            let value = if is_redacted(key.as_str()) {
+                REDACTED
+            } else {
+                value.to_str().unwrap_or_default()
+            };
+            reflected
+                .entry(key.to_string())
+                .and_modify(|values| {
+                    values.push_str(", ");
+                    values.push_str(value);
+                })
+                .or_insert_with(|| value.to_string());
+        }
+
+        reflected
+    }
+
+    /// Report the readiness checks run by startup
+    pub fn with_readiness(mut self, readiness: Readiness) -> Self {
+        self.readiness = readiness;
+        self
     }
 }
 
@@ -41,4 +115,197 @@ impl Utilities for UtilitiesService {
         // Send back our ping response.
         Ok(Response::new(response_message))
     }
+
+    #[tracing::instrument(
+        name = "Readiness report endpoint",
+        skip(self),
+    )]
+    async fn readiness_report(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<ReadinessReportResponse>, Status> {
+        // Not ready, with no checks, until the first checks finish
+        let response = match self.readiness.report() {
+            Some(report) => ReadinessReportResponse {
+                ready: report.is_ready(),
+                checks: report
+                    .checks
+                    .into_iter()
+                    .map(|check| {
+                        let passed = check.outcome.is_ok();
+                        ReadinessCheck {
+                            name: check.name.to_string(),
+                            passed,
+                            detail: check.outcome.unwrap_or_else(|error| error),
+                        }
+                    })
+                    .collect(),
+                checked_at: report.checked_at.to_rfc3339(),
+            },
+            None => ReadinessReportResponse::default(),
+        };
+
+        Ok(Response::new(response))
+    }
+
+    #[tracing::instrument(
+        name = "Server info endpoint",
+        skip(self),
+    )]
+    async fn server_info(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<ServerInfoResponse>, Status> {
+        let uptime = Utc::now() - self.started_at;
+        let response = ServerInfoResponse {
+            version: build_info::VERSION.to_string(),
+            git_sha: build_info::GIT_SHA.to_string(),
+            build_time: build_info::build_time().to_rfc3339(),
+            started_at: self.started_at.to_rfc3339(),
+            uptime_seconds: uptime.num_seconds(),
+            features: build_info::enabled_features()
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        };
+
+        Ok(Response::new(response))
+    }
+
+    #[tracing::instrument(
+        name = "Server time endpoint",
+        skip(self),
+    )]
+    async fn server_time(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<ServerTimeResponse>, Status> {
+        let now = Utc::now();
+        let response = ServerTimeResponse {
+            time: now.to_rfc3339(),
+            unix_milliseconds: now.timestamp_millis(),
+        };
+
+        Ok(Response::new(response))
+    }
+
+    #[tracing::instrument(
+        name = "Echo endpoint",
+        skip_all,
+    )]
+    async fn echo(
+        &self,
+        request: Request<EchoRequest>,
+    ) -> Result<Response<EchoResponse>, Status> {
+        let metadata = self.reflect_metadata(request.metadata());
+        let response = EchoResponse {
+            message: request.into_inner().message,
+            metadata,
+        };
+
+        Ok(Response::new(response))
+    }
+
+    /// Add log filter directives on top of the configured log level, for
+    /// `duration_minutes` or until the next change when zero. An empty filter
+    /// restores the configured log level. A configuration reload changing the
+    /// log level also replaces the filter.
+    #[tracing::instrument(
+        name = "Set log filter endpoint",
+        skip_all,
+    )]
+    async fn set_log_filter(
+        &self,
+        request: Request<SetLogFilterRequest>,
+    ) -> Result<Response<SetLogFilterResponse>, Status> {
+        validation::validate(&request)?;
+
+        let request_message = request.into_inner();
+        let handle = self.log_level_handle.as_ref().ok_or_else(|| {
+            tracing::error!("Log filter change requested before tracing was initiated");
+            Status::failed_precondition("Tracing is not initiated")
+        })?;
+        let log_level = self.config.load().application.log_level;
+
+        let directives = request_message.filter.trim();
+        if directives.is_empty() {
+            handle.set(log_level)?;
+            tracing::warn!("Log filter restored to {log_level}");
+
+            return Ok(Response::new(SetLogFilterResponse {
+                filter: handle.current(),
+                reverts_at: None,
+            }));
+        }
+
+        let generation = handle.set_directives(log_level, directives)?;
+        tracing::warn!(
+            "Log filter set to {directives} for {} minutes",
+            request_message.duration_minutes
+        );
+
+        // Restore the configured log level once the filter expires, unless it
+        // has been changed again since
+        let reverts_at = (request_message.duration_minutes > 0).then(|| {
+            let duration = chrono::Duration::minutes(request_message.duration_minutes as i64);
+            let handle = handle.clone();
+            let config = self.config.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(duration.to_std().unwrap_or_default()).await;
+                let log_level = config.load().application.log_level;
+                match handle.revert(generation, log_level) {
+                    Ok(true) => tracing::warn!("Log filter expired, restored to {log_level}"),
+                    Ok(false) => {}
+                    Err(e) => tracing::error!("Unable to restore the log level: {e}"),
+                }
+            });
+
+            (Utc::now() + duration).to_rfc3339()
+        });
+
+        let response = SetLogFilterResponse {
+            filter: handle.current(),
+            reverts_at,
+        };
+
+        Ok(Response::new(response))
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configuration::Configuration;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn echoed_metadata_redacts_credentials() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let mut config = Configuration::parse()?;
+        config.refresh_token.metadata_key = Some("x-refresh-token".to_string());
+        let service = UtilitiesService::new(config.into_shared());
+        let mut metadata = tonic::metadata::MetadataMap::new();
+        metadata.insert("authorization", "Bearer secret".parse()?);
+        metadata.insert("x-refresh-token", "secret".parse()?);
+        metadata.insert("x-request-id", "abc".parse()?);
+        metadata.append("x-forwarded-for", "10.0.0.1".parse()?);
+        metadata.append("x-forwarded-for", "10.0.0.2".parse()?);
+        metadata.insert_bin("trace-bin", tonic::metadata::MetadataValue::from_bytes(b"binary"));
+
+        //-- Execute Function (Act)
+        let reflected = service.reflect_metadata(&metadata);
+
+        //-- Checks (Assertions)
+        assert_eq!(reflected["authorization"], REDACTED);
+        assert_eq!(reflected["x-refresh-token"], REDACTED);
+        assert_eq!(reflected["x-request-id"], "abc");
+        assert_eq!(reflected["x-forwarded-for"], "10.0.0.1, 10.0.0.2");
+        assert!(!reflected.contains_key("trace-bin"));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,527 @@
+//-- ./src/services/validation.rs
+
+// #![allow(unused)] // For development only
+
+//! # Request Validation
+//!
+//! Checks the shape of request messages before a handler acts on them:
+//! required fields are set, ids are UUIDs, email addresses parse and page
+//! limits are in range. Every invalid field is collected so a client can fix
+//! them all at once, and the request fails with `INVALID_ARGUMENT` and a
+//! `google.rpc.BadRequest` in the status details listing each field and why,
+//! see `AuthenticationError::InvalidRequest`.
+//!
+//! Handlers call `validate(&request)?` before anything else. Rules that need
+//! configuration or the database, such as the user name policy or the email
+//! domain rules, are still checked by the handlers.
+//! ---
+
+use tonic::Request;
+use uuid::Uuid;
+
+use crate::domain;
+use crate::prelude::*;
+use crate::rpc::proto::{
+    AddOrganizationMemberRequest, ApiKeyIndexRequest, ClientIndexRequest, DeleteUserRequest,
+    DeleteWebhookEndpointRequest, ForcePasswordResetRequest, ImpersonateUserRequest,
+    ImpersonationIndexRequest, ListMyLoginHistoryRequest, LoginRequest, MergeUsersRequest,
+    ReadUserRequest, RegisterRequest, RequestEmailChangeRequest, RequestMagicLinkRequest,
+    RequestPasswordResetRequest, ResetPasswordRequest, RestoreUserRequest, RevokeApiKeyRequest,
+    RevokeClientRequest,
+    RevokeGrantRequest, RevokeImpersonationRequest,
+    SearchUsersRequest, SessionsDeleteRequest, SessionsDeleteUserRequest, SessionsIndexRequest,
+    SecurityReportRequest, SessionsReadRequest, SessionsRevokeRequest, SessionsRevokeUserRequest,
+    SetLogFilterRequest,
+    UnlinkIdentityRequest, UpdateClientRequest, UserIndexRequest, WebhookDeliveryIndexRequest,
+    WebhookEndpointIndexRequest,
+};
+
+/// The most records a page can be asked for
+pub const MAX_PAGE_SIZE: usize = 100;
+
+/// The longest window a security report can cover, 30 days
+pub const MAX_SECURITY_REPORT_WINDOW_MINUTES: u32 = 43_200;
+
+/// The longest a log filter can be set for, a day
+pub const MAX_LOG_FILTER_MINUTES: u32 = 1_440;
+
+/// A request field and why it is invalid
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldViolation {
+    pub field: String,
+    pub description: String,
+}
+
+impl std::fmt::Display for FieldViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.field, self.description)
+    }
+}
+
+/// The invalid fields of a request, collected by `ValidateRequest`
+#[derive(Debug, Default)]
+pub struct Violations(Vec<FieldViolation>);
+
+impl Violations {
+    fn add(&mut self, field: &str, description: impl Into<String>) {
+        self.0.push(FieldViolation {
+            field: field.to_string(),
+            description: description.into(),
+        });
+    }
+
+    /// The field is not empty or only whitespace
+    pub fn required(&mut self, field: &str, value: &str) -> &mut Self {
+        if value.trim().is_empty() {
+            self.add(field, "is required");
+        }
+        self
+    }
+
+    /// The field is an email address
+    pub fn email(&mut self, field: &str, value: &str) -> &mut Self {
+        if value.trim().is_empty() {
+            self.add(field, "is required");
+        } else if domain::EmailAddress::parse(value).is_err() {
+            self.add(field, "is not an email address");
+        }
+        self
+    }
+
+    /// The field is a UUID
+    pub fn uuid(&mut self, field: &str, value: &str) -> &mut Self {
+        if value.trim().is_empty() {
+            self.add(field, "is required");
+        } else if Uuid::parse_str(value).is_err() {
+            self.add(field, "is not a UUID");
+        }
+        self
+    }
+
+    /// The field is a UUID when it is set
+    pub fn optional_uuid(&mut self, field: &str, value: Option<&str>) -> &mut Self {
+        if value.is_some_and(|value| Uuid::parse_str(value).is_err()) {
+            self.add(field, "is not a UUID");
+        }
+        self
+    }
+
+    /// The field is a page size, from 1 to `MAX_PAGE_SIZE`
+    pub fn limit(&mut self, field: &str, value: impl TryInto<usize>) -> &mut Self {
+        if !value
+            .try_into()
+            .is_ok_and(|limit| (1..=MAX_PAGE_SIZE).contains(&limit))
+        {
+            self.add(field, format!("must be from 1 to {MAX_PAGE_SIZE}"));
+        }
+        self
+    }
+
+    /// The field is from `min` to `max`
+    pub fn between(&mut self, field: &str, value: u32, min: u32, max: u32) -> &mut Self {
+        if !(min..=max).contains(&value) {
+            self.add(field, format!("must be from {min} to {max}"));
+        }
+        self
+    }
+
+    /// The field is a record offset, zero or more
+    pub fn offset(&mut self, field: &str, value: impl TryInto<usize>) -> &mut Self {
+        if value.try_into().is_err() {
+            self.add(field, "must be zero or more");
+        }
+        self
+    }
+
+    /// `InvalidRequest` listing the violations, if there are any
+    pub fn into_result(self) -> Result<(), AuthenticationError> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(AuthenticationError::InvalidRequest(self.0))
+        }
+    }
+}
+
+/// A request message whose fields can be checked without the database
+pub trait ValidateRequest {
+    /// Add a violation for each invalid field
+    fn validate(&self, violations: &mut Violations);
+}
+
+/// Check a request's fields, failing with every invalid field
+pub fn validate<T: ValidateRequest>(request: &Request<T>) -> Result<(), AuthenticationError> {
+    let mut violations = Violations::default();
+    request.get_ref().validate(&mut violations);
+    violations.into_result()
+}
+
+//-- Authentication Service
+impl ValidateRequest for LoginRequest {
+    fn validate(&self, violations: &mut Violations) {
+        // Not checked as an email address, so a login can't tell them apart
+        violations
+            .required("email", &self.email)
+            .required("password", &self.password);
+    }
+}
+
+impl ValidateRequest for RegisterRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations
+            .email("email", &self.email)
+            .required("name", &self.name)
+            .required("password", &self.password);
+    }
+}
+
+impl ValidateRequest for RequestMagicLinkRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.email("email", &self.email);
+    }
+}
+
+impl ValidateRequest for RequestPasswordResetRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.email("email", &self.email);
+    }
+}
+
+impl ValidateRequest for ResetPasswordRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations
+            .required("token", &self.token)
+            .required("password_new", &self.password_new);
+    }
+}
+
+//-- Users Service
+impl ValidateRequest for ReadUserRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.uuid("id", &self.id);
+    }
+}
+
+impl ValidateRequest for UserIndexRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations
+            .limit("limit", self.limit)
+            .offset("offset", self.offset);
+    }
+}
+
+impl ValidateRequest for SearchUsersRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations
+            .limit("limit", self.limit)
+            .optional_uuid("cursor_id", self.cursor_id.as_deref());
+    }
+}
+
+impl ValidateRequest for ListMyLoginHistoryRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations
+            .limit("limit", self.limit)
+            .optional_uuid("cursor_id", self.cursor_id.as_deref());
+    }
+}
+
+impl ValidateRequest for DeleteUserRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.uuid("id", &self.id);
+    }
+}
+
+impl ValidateRequest for RevokeGrantRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.uuid("grant_id", &self.grant_id);
+    }
+}
+
+impl ValidateRequest for UnlinkIdentityRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.uuid("id", &self.id);
+    }
+}
+
+//-- Sessions Service
+impl ValidateRequest for SessionsReadRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.uuid("id", &self.id);
+    }
+}
+
+impl ValidateRequest for SessionsIndexRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations
+            .limit("limit", self.limit)
+            .offset("offset", self.offset);
+    }
+}
+
+impl ValidateRequest for SessionsRevokeRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.uuid("id", &self.id);
+    }
+}
+
+impl ValidateRequest for SessionsRevokeUserRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.uuid("user_id", &self.user_id);
+    }
+}
+
+impl ValidateRequest for SessionsDeleteRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.uuid("id", &self.id);
+    }
+}
+
+impl ValidateRequest for SessionsDeleteUserRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.uuid("user_id", &self.user_id);
+    }
+}
+
+//-- Admin Service
+impl ValidateRequest for ApiKeyIndexRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations
+            .limit("limit", self.limit)
+            .offset("offset", self.offset);
+    }
+}
+
+impl ValidateRequest for RevokeApiKeyRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.uuid("id", &self.id);
+    }
+}
+
+impl ValidateRequest for ClientIndexRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations
+            .limit("limit", self.limit)
+            .offset("offset", self.offset);
+    }
+}
+
+impl ValidateRequest for UpdateClientRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.uuid("client_id", &self.client_id);
+    }
+}
+
+impl ValidateRequest for RevokeClientRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.uuid("client_id", &self.client_id);
+    }
+}
+
+impl ValidateRequest for AddOrganizationMemberRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations
+            .uuid("organization_id", &self.organization_id)
+            .uuid("user_id", &self.user_id);
+    }
+}
+
+impl ValidateRequest for WebhookEndpointIndexRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations
+            .limit("limit", self.limit)
+            .offset("offset", self.offset);
+    }
+}
+
+impl ValidateRequest for DeleteWebhookEndpointRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.uuid("id", &self.id);
+    }
+}
+
+impl ValidateRequest for WebhookDeliveryIndexRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations
+            .uuid("endpoint_id", &self.endpoint_id)
+            .limit("limit", self.limit)
+            .offset("offset", self.offset);
+    }
+}
+
+impl ValidateRequest for RequestEmailChangeRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations
+            .uuid("user_id", &self.user_id)
+            .email("new_email", &self.new_email);
+    }
+}
+
+impl ValidateRequest for ImpersonateUserRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.uuid("user_id", &self.user_id);
+    }
+}
+
+impl ValidateRequest for ImpersonationIndexRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations
+            .limit("limit", self.limit)
+            .offset("offset", self.offset);
+    }
+}
+
+impl ValidateRequest for RevokeImpersonationRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.uuid("impersonation_id", &self.impersonation_id);
+    }
+}
+
+impl ValidateRequest for ForcePasswordResetRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.uuid("user_id", &self.user_id);
+    }
+}
+
+impl ValidateRequest for RestoreUserRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.uuid("id", &self.id);
+    }
+}
+
+impl ValidateRequest for MergeUsersRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations
+            .uuid("source_user_id", &self.source_user_id)
+            .uuid("target_user_id", &self.target_user_id);
+    }
+}
+
+impl ValidateRequest for SecurityReportRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations
+            .between(
+                "window_minutes",
+                self.window_minutes,
+                1,
+                MAX_SECURITY_REPORT_WINDOW_MINUTES,
+            )
+            .limit("limit", self.limit);
+    }
+}
+
+//-- Utilities Service
+impl ValidateRequest for SetLogFilterRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.between("duration_minutes", self.duration_minutes, 0, MAX_LOG_FILTER_MINUTES);
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use tonic_types::StatusExt;
+
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    fn violations(request: &impl ValidateRequest) -> Vec<FieldViolation> {
+        let mut violations = Violations::default();
+        request.validate(&mut violations);
+        violations.0
+    }
+
+    fn fields(violations: &[FieldViolation]) -> Vec<&str> {
+        violations
+            .iter()
+            .map(|violation| violation.field.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn every_invalid_field_is_listed() {
+        let request = RegisterRequest {
+            email: "not an email".to_string(),
+            name: " ".to_string(),
+            password: String::new(),
+            ..Default::default()
+        };
+
+        let violations = violations(&request);
+
+        assert_eq!(fields(&violations), vec!["email", "name", "password"]);
+        assert_eq!(violations[0].description, "is not an email address");
+        assert_eq!(violations[1].description, "is required");
+    }
+
+    #[test]
+    fn ids_must_be_uuids() {
+        let request = MergeUsersRequest {
+            source_user_id: Uuid::now_v7().to_string(),
+            target_user_id: "42".to_string(),
+            ..Default::default()
+        };
+
+        let violations = violations(&request);
+
+        assert_eq!(fields(&violations), vec!["target_user_id"]);
+        assert_eq!(violations[0].to_string(), "target_user_id is not a UUID");
+    }
+
+    #[test]
+    fn page_limits_must_be_in_range() {
+        let page = |limit| violations(&UserIndexRequest { limit, offset: 0 });
+
+        assert!(page(1).is_empty());
+        assert!(page(MAX_PAGE_SIZE as u64).is_empty());
+        assert_eq!(fields(&page(0)), vec!["limit"]);
+        assert_eq!(fields(&page(MAX_PAGE_SIZE as u64 + 1)), vec!["limit"]);
+    }
+
+    #[test]
+    fn security_report_windows_must_be_in_range() {
+        let report = |window_minutes| {
+            violations(&SecurityReportRequest {
+                window_minutes,
+                limit: 10,
+            })
+        };
+
+        assert!(report(60).is_empty());
+        assert!(report(MAX_SECURITY_REPORT_WINDOW_MINUTES).is_empty());
+        assert_eq!(fields(&report(0)), vec!["window_minutes"]);
+        assert_eq!(
+            fields(&report(MAX_SECURITY_REPORT_WINDOW_MINUTES + 1)),
+            vec!["window_minutes"]
+        );
+    }
+
+    #[test]
+    fn invalid_requests_fail_with_invalid_argument() -> Result<()> {
+        let request = Request::new(SessionsReadRequest {
+            id: String::new(),
+            ..Default::default()
+        });
+        let valid = Request::new(SessionsReadRequest {
+            id: Uuid::now_v7().to_string(),
+            ..Default::default()
+        });
+
+        let status: tonic::Status = validate(&request)
+            .err()
+            .ok_or("request is valid")?
+            .into();
+
+        let bad_request = status.get_details_bad_request().ok_or("no bad request")?;
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        assert_eq!(bad_request.field_violations.len(), 1);
+        assert_eq!(bad_request.field_violations[0].field, "id");
+        assert_eq!(bad_request.field_violations[0].description, "is required");
+        assert!(validate(&valid).is_ok());
+
+        Ok(())
+    }
+}
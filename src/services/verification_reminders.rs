@@ -0,0 +1,302 @@
+//-- ./src/services/verification_reminders.rs
+
+// #![allow(unused)] // For development only
+
+//! # Verification Reminders
+//!
+//! A background job reminding users who haven't verified their email, then
+//! deactivating their accounts if they never do.
+//!
+//! Each run, users unverified `verification_reminders.unverified_after_days`
+//! after registering are emailed a new verification link. Later reminders are
+//! sent `interval_days` after the last, the interval doubling after each one,
+//! until `max_reminders` have been sent. Users still unverified
+//! `deactivate_after_days` after registering are deactivated and their
+//! sessions revoked. Reminders are claimed with `FOR UPDATE SKIP LOCKED`, so
+//! several instances can run the job.
+//!
+//! ## Metrics
+//! - `auth.verification_reminders.sent` - verification reminders queued
+//! - `auth.verification_reminders.deactivated` - unverified users deactivated
+//! ---
+
+use std::time::Duration;
+
+use chrono::Utc;
+use opentelemetry::metrics::Counter;
+use sqlx::{Pool, Postgres};
+
+use crate::configuration::SharedConfiguration;
+use crate::email::{EmailTemplate, EmailTemplates};
+use crate::events::{AuthEvent, AuthEventKind, AuthEvents};
+use crate::middleware::TokenDenylist;
+use crate::prelude::*;
+use crate::{database, domain};
+
+/// Reminds unverified users and deactivates those past the deadline
+#[derive(Clone)]
+pub struct VerificationReminders {
+    database: Pool<Postgres>,
+    config: SharedConfiguration,
+    denylist: TokenDenylist,
+    events: AuthEvents,
+    sent: Counter<u64>,
+    deactivated: Counter<u64>,
+}
+
+impl VerificationReminders {
+    /// Create a new job, denying the access tokens of deactivated users in
+    /// `denylist` and publishing their revocations to `events`
+    pub fn new(
+        database: Pool<Postgres>,
+        config: SharedConfiguration,
+        denylist: TokenDenylist,
+        events: AuthEvents,
+    ) -> Self {
+        let meter = opentelemetry::global::meter("authentication_service");
+
+        Self {
+            database,
+            config,
+            denylist,
+            events,
+            sent: meter
+                .u64_counter("auth.verification_reminders.sent")
+                .with_description("Verification reminders queued")
+                .build(),
+            deactivated: meter
+                .u64_counter("auth.verification_reminders.deactivated")
+                .with_description("Unverified users deactivated")
+                .build(),
+        }
+    }
+
+    /// Remind the users that are due a reminder and deactivate those past the
+    /// deadline, returning how many were (reminded, deactivated)
+    pub async fn run_once(&self) -> Result<(usize, usize), AuthenticationError> {
+        // Load the current configuration, reminders can change at runtime
+        let config = self.config.load_full();
+        if !config.verification_reminders.enabled {
+            return Ok((0, 0));
+        }
+
+        let reminded = self.remind(&config).await?;
+        let deactivated = self.deactivate(&config).await?;
+
+        Ok((reminded, deactivated))
+    }
+
+    /// Claim the users due a reminder and queue their verification emails
+    #[tracing::instrument(name = "Send verification reminders: ", skip(self, config))]
+    async fn remind(
+        &self,
+        config: &crate::configuration::Configuration,
+    ) -> Result<usize, AuthenticationError> {
+        let reminders = &config.verification_reminders;
+        let registered_before =
+            Utc::now() - chrono::Duration::days(reminders.unverified_after_days as i64);
+        let interval_seconds =
+            chrono::Duration::days(reminders.interval_days as i64).num_seconds() as f64;
+        let expiry = chrono::Duration::hours(reminders.expiry_hours as i64);
+
+        let templates = EmailTemplates::new(&config.email)?;
+        let issuer = config.application.get_issuer();
+
+        // Claim the users and queue their emails in one transaction, so a
+        // failure leaves the reminders to be sent next run
+        let mut transaction = self.database.begin().await?;
+        let users = database::Users::claim_verification_reminders(
+            &registered_before,
+            reminders.max_reminders as i32,
+            interval_seconds,
+            reminders.batch_size as i64,
+            &mut *transaction,
+        )
+        .await?;
+
+        for user in &users {
+            let claim = domain::TokenClaimNew::new(
+                &issuer,
+                &expiry,
+                user,
+                &domain::TokenType::EmailVerification,
+            );
+            let token = domain::EmailVerificationToken::try_from_claim(
+                claim,
+                &config.application.token_secret,
+            )?;
+            database::EmailVerifications::new(user, &token, &expiry)
+                .insert(&self.database)
+                .await?;
+
+            let mut context = tera::Context::new();
+            context.insert("name", user.name.as_ref());
+            context.insert("verification_url", &reminders.link(token.as_ref()));
+            context.insert("expires_in_hours", &reminders.expiry_hours);
+            let email = templates.render(
+                EmailTemplate::Verification,
+                &user.email,
+                &user.locale,
+                &context,
+            )?;
+            database::Outbox::new(email)
+                .insert(&mut *transaction)
+                .await?;
+        }
+        transaction.commit().await?;
+
+        if !users.is_empty() {
+            tracing::info!("Verification reminders queued: {}", users.len());
+            self.sent.add(users.len() as u64, &[]);
+        }
+
+        Ok(users.len())
+    }
+
+    /// Deactivate the users still unverified after the deadline, revoking
+    /// their sessions
+    #[tracing::instrument(name = "Deactivate unverified users: ", skip(self, config))]
+    async fn deactivate(
+        &self,
+        config: &crate::configuration::Configuration,
+    ) -> Result<usize, AuthenticationError> {
+        let deactivate_after_days = config.verification_reminders.deactivate_after_days;
+        if deactivate_after_days == 0 {
+            return Ok(0);
+        }
+
+        let registered_before =
+            Utc::now() - chrono::Duration::days(deactivate_after_days as i64);
+        let ids = database::Users::deactivate_unverified(&registered_before, &self.database)
+            .await?;
+
+        let access_token_expires_on = Utc::now()
+            + chrono::Duration::minutes(config.application.access_token_duration_minutes as i64);
+        for id in &ids {
+            // Deny the access tokens of the sessions being revoked, the
+            // denylist is reloaded from the database on start up
+            let denied = database::AccessTokenDenylist::insert_for_sessions(
+                id,
+                None,
+                &access_token_expires_on,
+                &self.database,
+            )
+            .await?;
+            for entry in &denied {
+                self.denylist.deny(&entry.jti, entry.expires_on);
+            }
+
+            let mut transaction = self.database.begin().await?;
+            let sessions_revoked =
+                database::Sessions::revoke_user_id(id, &mut *transaction).await? as u64;
+            let event = AuthEvent::new(AuthEventKind::Revocation)
+                .user(*id)
+                .sessions_affected(sessions_revoked);
+            database::Outbox::new(event.clone())
+                .insert(&mut *transaction)
+                .await?;
+            transaction.commit().await?;
+            self.events.publish(event);
+
+            tracing::warn!("Unverified user deactivated: {id}");
+        }
+
+        self.deactivated.add(ids.len() as u64, &[]);
+
+        Ok(ids.len())
+    }
+
+    /// Run the job in the background
+    pub fn spawn(self) {
+        let poll_interval = Duration::from_secs(
+            self.config.load().verification_reminders.poll_interval_seconds,
+        );
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(poll_interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.run_once().await {
+                    tracing::error!("Unable to run the verification reminders: {e}");
+                }
+            }
+        });
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::Duration;
+
+    use crate::configuration::Configuration;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    fn job(database: Pool<Postgres>, enabled: bool) -> Result<VerificationReminders> {
+        let mut config = Configuration::parse()?;
+        config.verification_reminders.enabled = enabled;
+
+        Ok(VerificationReminders::new(
+            database,
+            config.into_shared(),
+            TokenDenylist::default(),
+            AuthEvents::default(),
+        ))
+    }
+
+    /// An active, unverified user registered `days` ago
+    async fn unverified_user(days: i64, database: &Pool<Postgres>) -> Result<database::Users> {
+        let mut user = database::Users::mock_data()?;
+        user.is_active = true;
+        user.is_verified = false;
+        user.created_on = Utc::now() - Duration::days(days);
+
+        Ok(user.insert(database).await?)
+    }
+
+    #[sqlx::test]
+    async fn unverified_users_are_reminded_then_deactivated(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let job = job(database.clone(), true)?;
+        let reminded = unverified_user(5, &database).await?;
+        let expired = unverified_user(40, &database).await?;
+        let _recent = unverified_user(1, &database).await?;
+
+        //-- Execute Function (Act)
+        let first = job.run_once().await?;
+        let second = job.run_once().await?;
+        let queued = database::Outbox::claim_due(&10, &300, &database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(first, (2, 1));
+        assert_eq!(second, (0, 0));
+        // Two reminders and one revocation event
+        assert_eq!(queued.len(), 3);
+        assert!(database::Users::from_user_id(&reminded.id, &database).await?.is_active);
+        assert!(!database::Users::from_user_id(&expired.id, &database).await?.is_active);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn nothing_is_sent_when_disabled(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let job = job(database.clone(), false)?;
+        unverified_user(40, &database).await?;
+
+        //-- Execute Function (Act)
+        let outcome = job.run_once().await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(outcome, (0, 0));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,433 @@
+//-- ./src/services/webhooks.rs
+
+// #![allow(unused)] // For development only
+
+//! # Webhooks
+//!
+//! Deliver authentication events to the configured webhook endpoints as signed
+//! JSON, so other systems can react to them without polling.
+//!
+//! | Authentication event   | Webhook event type  |
+//! |------------------------|---------------------|
+//! | `Registration`         | `user.registered`   |
+//! | `Verification`         | `user.verified`     |
+//! | `Login`                | `user.login`        |
+//! | `Logout`, `Revocation` | `session.revoked`   |
+//! | `PasswordChange`       | `password.changed`  |
+//! | `Impersonation`        | `user.impersonated` |
+//!
+//! Events are queued through the transactional outbox (see `outbox`), where the
+//! `WebhookDispatcher` queues a delivery for each endpoint subscribed to the
+//! event type. A worker polls the `webhook_deliveries` queue and POSTs each
+//! due delivery. Failed
+//! attempts are retried with an exponential backoff, starting at
+//! `webhooks.retry_base_seconds`, until `webhooks.max_attempts` is reached.
+//!
+//! Each request carries the headers:
+//! - `x-webhook-id`: The delivery id, the same on every attempt
+//! - `x-webhook-event`: The webhook event type
+//! - `x-webhook-timestamp`: Unix timestamp of the attempt
+//! - `x-webhook-signature`: `sha256=` followed by the hex HMAC-SHA256 of
+//!   `<timestamp>.<body>`, keyed with the endpoint secret
+//! ---
+
+use std::time::Duration;
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use sqlx::{Pool, Postgres};
+
+use crate::configuration::SharedConfiguration;
+use crate::database::{WebhookDeliveries, WebhookDeliveryStatus, WebhookEndpoints};
+use crate::events::{AuthEvent, AuthEventKind};
+use crate::prelude::*;
+use crate::utils::backoff;
+
+/// Header carrying the delivery id
+pub static WEBHOOK_ID_HEADER: &str = "x-webhook-id";
+
+/// Header carrying the webhook event type
+pub static WEBHOOK_EVENT_HEADER: &str = "x-webhook-event";
+
+/// Header carrying the unix timestamp included in the signature
+pub static WEBHOOK_TIMESTAMP_HEADER: &str = "x-webhook-timestamp";
+
+/// Header carrying the payload signature
+pub static WEBHOOK_SIGNATURE_HEADER: &str = "x-webhook-signature";
+
+/// Webhook event types endpoints can subscribe to
+pub const WEBHOOK_EVENT_TYPES: [&str; 6] = [
+    "user.registered",
+    "user.verified",
+    "user.login",
+    "session.revoked",
+    "password.changed",
+    "user.impersonated",
+];
+
+/// How many due deliveries are claimed per poll
+const CLAIM_BATCH_SIZE: usize = 50;
+
+/// How long a claimed delivery is held before another worker can retry it
+const CLAIM_LEASE_SECONDS: u64 = 300;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// The webhook event type an authentication event is delivered as
+pub fn event_type(kind: AuthEventKind) -> &'static str {
+    match kind {
+        AuthEventKind::Registration => "user.registered",
+        AuthEventKind::Verification => "user.verified",
+        AuthEventKind::Login => "user.login",
+        AuthEventKind::Logout | AuthEventKind::Revocation => "session.revoked",
+        AuthEventKind::PasswordChange => "password.changed",
+        AuthEventKind::Impersonation => "user.impersonated",
+    }
+}
+
+/// Build the JSON body delivered for an authentication event
+pub fn payload(event: &AuthEvent) -> Result<String, AuthenticationError> {
+    let body = serde_json::json!({
+        "id": event.id,
+        "type": event_type(event.kind),
+        "occurred_at": event.occurred_at.to_rfc3339(),
+        "data": {
+            "user_id": event.user_id,
+            "session_id": event.session_id,
+            "ip_address": event
+                .ip_address
+                .map(|ip| std::net::Ipv4Addr::from(ip as u32).to_string()),
+            "sessions_affected": event.sessions_affected,
+        },
+    });
+
+    Ok(serde_json::to_string(&body)?)
+}
+
+/// Sign a payload for the `x-webhook-signature` header
+pub fn sign(secret: &str, timestamp: i64, payload: &str) -> String {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(format!("{timestamp}.{payload}").as_bytes());
+
+    format!("sha256={:x}", mac.finalize().into_bytes())
+}
+
+/// Queues authentication events for the webhook endpoints and delivers them
+#[derive(Debug, Clone)]
+pub struct WebhookDispatcher {
+    database: Pool<Postgres>,
+    config: SharedConfiguration,
+    client: reqwest::Client,
+}
+
+impl WebhookDispatcher {
+    /// Create a new dispatcher, the HTTP client timeout is fixed at startup
+    pub fn new(
+        database: Pool<Postgres>,
+        config: SharedConfiguration,
+    ) -> Result<Self, AuthenticationError> {
+        let timeout =
+            Duration::from_secs(config.load().webhooks.request_timeout_seconds);
+        let client = reqwest::Client::builder().timeout(timeout).build()?;
+
+        Ok(Self {
+            database,
+            config,
+            client,
+        })
+    }
+
+    /// Queue a delivery of the event for each endpoint subscribed to it.
+    ///
+    /// Returns the number of deliveries queued.
+    #[tracing::instrument(name = "Queue webhook deliveries: ", skip(self, event), fields(event_id = %event.id))]
+    pub async fn enqueue(
+        &self,
+        event: &AuthEvent,
+    ) -> Result<usize, AuthenticationError> {
+        let event_type = event_type(event.kind);
+        let endpoints =
+            WebhookEndpoints::index_subscribed(event_type, &self.database).await?;
+        if endpoints.is_empty() {
+            return Ok(0);
+        }
+
+        let payload = payload(event)?;
+        for endpoint in &endpoints {
+            WebhookDeliveries::new(&endpoint.id, &event.id, event_type, &payload)
+                .insert(&self.database)
+                .await?;
+        }
+
+        Ok(endpoints.len())
+    }
+
+    /// Attempt every delivery that is due, returning how many were attempted
+    pub async fn deliver_due(&self) -> Result<usize, AuthenticationError> {
+        let deliveries = WebhookDeliveries::claim_due(
+            &CLAIM_BATCH_SIZE,
+            &CLAIM_LEASE_SECONDS,
+            &self.database,
+        )
+        .await?;
+        let attempted = deliveries.len();
+
+        for delivery in deliveries {
+            if let Err(e) = self.deliver(&delivery).await {
+                tracing::error!(
+                    "Webhook delivery {} could not be attempted: {e}",
+                    delivery.id
+                );
+            }
+        }
+
+        Ok(attempted)
+    }
+
+    /// POST a delivery to its endpoint and record the outcome
+    #[tracing::instrument(name = "Deliver webhook: ", skip(self, delivery), fields(id = %delivery.id))]
+    async fn deliver(
+        &self,
+        delivery: &WebhookDeliveries,
+    ) -> Result<WebhookDeliveries, AuthenticationError> {
+        // Load the current configuration, retries can change at runtime
+        let config = self.config.load_full();
+
+        let endpoint =
+            WebhookEndpoints::from_id(&delivery.endpoint_id, &self.database).await?;
+        if !endpoint.is_active {
+            return delivery
+                .record_attempt(
+                    WebhookDeliveryStatus::Failed,
+                    None,
+                    Some("endpoint is disabled"),
+                    Utc::now(),
+                    &self.database,
+                )
+                .await;
+        }
+
+        let timestamp = Utc::now().timestamp();
+        let signature = sign(&endpoint.secret, timestamp, &delivery.payload);
+
+        let response = self
+            .client
+            .post(&endpoint.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(WEBHOOK_ID_HEADER, delivery.id.to_string())
+            .header(WEBHOOK_EVENT_HEADER, &delivery.event_type)
+            .header(WEBHOOK_TIMESTAMP_HEADER, timestamp.to_string())
+            .header(WEBHOOK_SIGNATURE_HEADER, signature)
+            .body(delivery.payload.clone())
+            .send()
+            .await;
+
+        let (response_status, error) = match response {
+            Ok(response) if response.status().is_success() => {
+                (Some(response.status().as_u16() as i32), None)
+            }
+            Ok(response) => (
+                Some(response.status().as_u16() as i32),
+                Some(format!("endpoint returned {}", response.status())),
+            ),
+            Err(e) => (None, Some(e.to_string())),
+        };
+
+        let attempts = delivery.attempts + 1;
+        let (status, next_attempt_at) = match &error {
+            None => (WebhookDeliveryStatus::Delivered, Utc::now()),
+            Some(_) if attempts >= config.webhooks.max_attempts as i32 => {
+                (WebhookDeliveryStatus::Failed, Utc::now())
+            }
+            Some(_) => (
+                WebhookDeliveryStatus::Pending,
+                Utc::now()
+                    + backoff::retry_delay(
+                        config.webhooks.retry_base_seconds,
+                        attempts,
+                    ),
+            ),
+        };
+
+        match &error {
+            None => tracing::debug!("Webhook delivered to {}", endpoint.url),
+            Some(error) => tracing::warn!(
+                "Webhook attempt {attempts} to {} failed, now {status}: {error}",
+                endpoint.url
+            ),
+        }
+
+        delivery
+            .record_attempt(
+                status,
+                response_status,
+                error.as_deref(),
+                next_attempt_at,
+                &self.database,
+            )
+            .await
+    }
+
+    /// Deliver the queue in the background
+    pub fn spawn(self) {
+        let poll_interval =
+            Duration::from_secs(self.config.load().webhooks.poll_interval_seconds);
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(poll_interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.deliver_due().await {
+                    tracing::error!("Unable to deliver webhooks: {e}");
+                }
+            }
+        });
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+    use uuid::Uuid;
+
+    use crate::configuration::Configuration;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    /// Serve a webhook receiver answering with `status`, forwarding each request
+    async fn receiver(
+        status: StatusCode,
+    ) -> Result<(String, mpsc::Receiver<(HeaderMap, String)>)> {
+        let (sender, requests) = mpsc::channel(8);
+        let router = axum::Router::new().route(
+            "/hook",
+            post(move |headers: HeaderMap, body: String| {
+                let sender = sender.clone();
+                async move {
+                    let _ = sender.send((headers, body)).await;
+                    status
+                }
+            }),
+        );
+
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}/hook", listener.local_addr()?);
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        Ok((url, requests))
+    }
+
+    fn dispatcher(database: Pool<Postgres>) -> Result<WebhookDispatcher> {
+        let config = Configuration::parse()?.into_shared();
+        Ok(WebhookDispatcher::new(database, config)?)
+    }
+
+    #[test]
+    fn every_event_kind_has_a_webhook_event_type() {
+        for kind in [
+            AuthEventKind::Registration,
+            AuthEventKind::Verification,
+            AuthEventKind::Login,
+            AuthEventKind::Logout,
+            AuthEventKind::Revocation,
+            AuthEventKind::PasswordChange,
+            AuthEventKind::Impersonation,
+        ] {
+            assert!(WEBHOOK_EVENT_TYPES.contains(&event_type(kind)));
+        }
+    }
+
+    #[test]
+    fn signature_is_keyed_and_covers_the_timestamp() {
+        let signature = sign("whsec_secret", 1_700_000_000, "{}");
+
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert_eq!(signature, sign("whsec_secret", 1_700_000_000, "{}"));
+        assert_ne!(signature, sign("whsec_other", 1_700_000_000, "{}"));
+        assert_ne!(signature, sign("whsec_secret", 1_700_000_001, "{}"));
+    }
+
+    #[sqlx::test]
+    async fn subscribed_endpoints_receive_signed_events(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let (url, mut requests) = receiver(StatusCode::NO_CONTENT).await?;
+        let endpoint = WebhookEndpoints::new(&url, &["user.login".to_string()])
+            .insert(&database)
+            .await?;
+        let dispatcher = dispatcher(database.clone())?;
+        let user_id = Uuid::now_v7();
+
+        //-- Execute Function (Act)
+        let queued = dispatcher
+            .enqueue(&AuthEvent::new(AuthEventKind::Login).user(user_id))
+            .await?;
+        let ignored = dispatcher
+            .enqueue(&AuthEvent::new(AuthEventKind::PasswordChange))
+            .await?;
+        let attempted = dispatcher.deliver_due().await?;
+
+        //-- Checks (Assertions)
+        assert_eq!((queued, ignored, attempted), (1, 0, 1));
+
+        let (headers, body) = requests.recv().await.ok_or("no webhook received")?;
+        let timestamp: i64 = headers[WEBHOOK_TIMESTAMP_HEADER].to_str()?.parse()?;
+        assert_eq!(
+            headers[WEBHOOK_SIGNATURE_HEADER].to_str()?,
+            sign(&endpoint.secret, timestamp, &body)
+        );
+        assert_eq!(headers[WEBHOOK_EVENT_HEADER].to_str()?, "user.login");
+        let body: serde_json::Value = serde_json::from_str(&body)?;
+        assert_eq!(body["data"]["user_id"], user_id.to_string());
+
+        let deliveries =
+            WebhookDeliveries::index_endpoint(&endpoint.id, &10, &0, &database)
+                .await?;
+        assert_eq!(deliveries[0].status, WebhookDeliveryStatus::Delivered);
+        assert_eq!(deliveries[0].last_response_status, Some(204));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn failed_deliveries_are_retried_later(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let (url, _requests) = receiver(StatusCode::SERVICE_UNAVAILABLE).await?;
+        let endpoint = WebhookEndpoints::new(&url, &[]).insert(&database).await?;
+        let dispatcher = dispatcher(database.clone())?;
+        dispatcher
+            .enqueue(&AuthEvent::new(AuthEventKind::Registration))
+            .await?;
+
+        //-- Execute Function (Act)
+        let first = dispatcher.deliver_due().await?;
+        let second = dispatcher.deliver_due().await?;
+
+        //-- Checks (Assertions)
+        assert_eq!((first, second), (1, 0));
+
+        let deliveries =
+            WebhookDeliveries::index_endpoint(&endpoint.id, &10, &0, &database)
+                .await?;
+        assert_eq!(deliveries[0].status, WebhookDeliveryStatus::Pending);
+        assert_eq!(deliveries[0].attempts, 1);
+        assert_eq!(deliveries[0].last_response_status, Some(503));
+        assert!(deliveries[0].next_attempt_at > Utc::now());
+
+        Ok(())
+    }
+}
@@ -8,21 +8,144 @@
 //!
 //! This module has a Tonic Server instance enum for reuse in the integration
 //! test suit.
+//!
+//! The gRPC health service reports `NOT_SERVING` until the optional warm-up
+//! (see `warm_up`) has finished and the readiness checks (see `readiness`)
+//! pass, so load balancers only send traffic once the caches are warm and the
+//! dependencies are reachable.
+//!
+//! When `http.enabled` is set the REST/JSON gateway (see `http`) is served on
+//! its own port alongside the Tonic server, sharing the same event broadcaster.
+//!
+//! The outbox dispatcher (see `services::outbox`) and webhook dispatcher (see
+//! `services::webhooks`) process their queues in the background once the
+//! server runs, along with the verification reminders (see
+//! `services::verification_reminders`).
+//!
+//! Accepted connections get the TCP keepalive from `grpc.tcp_keepalive_seconds`,
+//! the other `grpc` settings are applied by `router::get_router`.
+//!
+//! Each of the `listeners` serves its own set of services on another address
+//! or a Unix domain socket, e.g. the admin service on localhost only. When
+//! `internal.enabled` is set the admin service is moved to its own router on
+//! `internal.address`, the other services stay on the main address.
 //! ---
 
-use crate::{configuration::Configuration, prelude::*, router};
+use crate::configuration::{Configuration, ListenerConfiguration, SharedConfiguration};
+use crate::{
+    database, email, event_bus, events, http, middleware, prelude::*, readiness, router, services,
+    warm_up,
+};
 
 use sqlx::{Pool, Postgres};
 use tokio::net::TcpListener;
-use tonic::transport::server::Router;
-use tonic_web::GrpcWebLayer;
-use tower_http::cors::CorsLayer;
-use tower_layer::{Stack,Identity };
+use tonic_health::server::HealthReporter;
+use tonic_health::ServingStatus;
+
+/// Health service name covering the whole server
+const SERVER_HEALTH_SERVICE: &str = "";
+
+/// Health service name reporting the database circuit, `NOT_SERVING` while
+/// it is open
+const DATABASE_HEALTH_SERVICE: &str = "database";
+
+/// A socket to serve gRPC on
+pub enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(tokio::net::UnixListener),
+}
+
+impl Listener {
+    /// Bind the listener's address or Unix domain socket
+    async fn bind(config: &ListenerConfiguration) -> Result<Self, AuthenticationError> {
+        match (&config.address, &config.unix_socket) {
+            (Some(address), _) => Ok(Self::Tcp(TcpListener::bind(address).await?)),
+            #[cfg(unix)]
+            (None, Some(path)) => {
+                use std::os::unix::fs::FileTypeExt;
+
+                // Replace the socket left behind by an earlier run, but never
+                // another kind of file
+                let metadata = std::fs::symlink_metadata(path);
+                if metadata.is_ok_and(|metadata| metadata.file_type().is_socket()) {
+                    std::fs::remove_file(path)?;
+                }
+                Ok(Self::Unix(tokio::net::UnixListener::bind(path)?))
+            }
+            _ => Err(AuthenticationError::ValidationError(format!(
+                "listeners.{} must set one of address or unix_socket",
+                config.name
+            ))),
+        }
+    }
+
+    /// Where the listener accepts connections, for logs
+    fn local_address(&self) -> String {
+        match self {
+            Self::Tcp(listener) => listener
+                .local_addr()
+                .map(|address| address.to_string())
+                .unwrap_or_default(),
+            #[cfg(unix)]
+            Self::Unix(listener) => listener
+                .local_addr()
+                .ok()
+                .and_then(|address| address.as_pathname().map(|path| path.display().to_string()))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Serve the router on the listener until it fails
+    async fn serve(
+        self,
+        router: router::GrpcRouter,
+        tcp_keepalive: Option<std::time::Duration>,
+    ) -> Result<(), AuthenticationError> {
+        match self {
+            Self::Tcp(listener) => {
+                let incoming = tonic::transport::server::TcpIncoming::from(listener)
+                    .with_keepalive(tcp_keepalive);
+                router.serve_with_incoming(incoming).await?;
+            }
+            #[cfg(unix)]
+            Self::Unix(listener) => {
+                let incoming = tokio_stream::wrappers::UnixListenerStream::new(listener);
+                router.serve_with_incoming(incoming).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// One of the `listeners`, serving its own router
+pub struct AdditionalListener {
+    pub name: String,
+    pub router: router::GrpcRouter,
+    pub listener: Listener,
+}
 
 /// Tonic Server instance enum;
 pub struct TonicServer {
-    pub router: Router<Stack<GrpcWebLayer, Stack<CorsLayer, Identity>>>,
+    pub router: router::GrpcRouter,
     pub listener: TcpListener,
+    pub listeners: Vec<AdditionalListener>,
+    pub health_reporter: HealthReporter,
+    pub config: SharedConfiguration,
+    pub http_listener: Option<TcpListener>,
+    http_router: Option<axum::Router>,
+    outbox: services::OutboxDispatcher,
+    webhooks: services::WebhookDispatcher,
+    verification_reminders: services::VerificationReminders,
+    database: Pool<Postgres>,
+    warm_up: bool,
+    warm_up_connections: usize,
+    readiness: readiness::Readiness,
+    session_activity: middleware::SessionActivity,
+    denylist: middleware::TokenDenylist,
+    api_keys: middleware::ApiKeyStore,
+    tcp_keepalive: Option<std::time::Duration>,
 }
 
 impl TonicServer {
@@ -34,15 +157,149 @@ impl TonicServer {
 
         // Get the address from the configuration
         let address = config.application.get_address();
+        let http_address = config
+            .http
+            .enabled
+            .then(|| format!("{}:{}", config.application.ip_address, config.http.port));
+        let warm_up = config.application.warm_up;
+        let warm_up_connections = config.application.warm_up_connections;
+        let tcp_keepalive = config.grpc.tcp_keepalive();
 
-        // Create the router with the database and configuration
-        let router = router::get_router(database, config)?;
+        // Share the configuration with the services so it can be reloaded
+        let config = config.into_shared();
+
+        // Build the health service, not serving until warm-up has finished
+        let (health_reporter, health_server) = tonic_health::server::health_reporter();
+        health_reporter
+            .set_service_status(SERVER_HEALTH_SERVICE, ServingStatus::NotServing)
+            .await;
+
+        // Broadcast channel for authentication events, shared by the services that
+        // publish them and the admin service that streams them
+        let auth_events = events::AuthEvents::default();
+
+        // Queue webhook events and send emails from the outbox, then deliver
+        // the webhooks
+        let webhooks = services::WebhookDispatcher::new(database.clone(), config.clone())?;
+        let mut outbox = services::OutboxDispatcher::new(
+            database.clone(),
+            config.clone(),
+            email::client_from_config(&config.load().email)?,
+            webhooks.clone(),
+        );
+
+        // Also publish the events to Kafka or NATS, when configured
+        if let Some(publisher) = event_bus::publisher_from_config(&config.load().event_bus).await? {
+            outbox = outbox.with_event_bus(publisher);
+        }
+
+        // Access tokens denied before they expire, shared by the authorisation
+        // layer that checks them and the services that deny them
+        let denylist = middleware::TokenDenylist::load(&database).await?;
+
+        // Remind unverified users, deactivating them after the deadline
+        let verification_reminders = services::VerificationReminders::new(
+            database.clone(),
+            config.clone(),
+            denylist.clone(),
+            auth_events.clone(),
+        );
+
+        // Usable service account API keys, shared by the authorisation layer and the
+        // admin service that creates and revokes them
+        let api_keys = middleware::ApiKeyStore::load(&database).await?;
+
+        // Accepted policy versions, shared by the authorisation layer that checks them
+        // and the users service that records acceptances
+        let policies = middleware::PolicyAcceptanceStore::load(&database, config.clone()).await?;
+
+        // CAPTCHA checks and failed login counts, shared by the gRPC and HTTP
+        // authentication services
+        let captcha = services::CaptchaGuard::new()?;
+
+        // The latest readiness checks, run once the server is running
+        let readiness = readiness::Readiness::default();
+
+        // Requests made with each session, recorded by the authorisation layer and
+        // the authentication service, written to the database by `run`
+        let session_activity = middleware::SessionActivity::default();
+
+        // Create a router with the database and configuration for each
+        // listener, serving its set of services
+        let build_router = |services: &[String]| {
+            router::get_router(
+                database.clone(),
+                config.clone(),
+                auth_events.clone(),
+                denylist.clone(),
+                api_keys.clone(),
+                policies.clone(),
+                captcha.clone(),
+                readiness.clone(),
+                session_activity.clone(),
+                services,
+            )
+            .map(|router| router.add_service(health_server.clone()))
+        };
+        let router = build_router(&config.load().public_services())?;
 
         // We are using listener as it will bind a random port when port setting
         // is '0'. This is important for integration test server spawn.
         let listener = TcpListener::bind(address).await?;
 
-        Ok(Self { router, listener })
+        // Bind the internal server and the additional listeners
+        let mut listeners = Vec::new();
+        for listener_config in &config.load().all_listeners() {
+            listeners.push(AdditionalListener {
+                name: listener_config.name.clone(),
+                router: build_router(&listener_config.services)?,
+                listener: Listener::bind(listener_config).await?,
+            });
+        }
+
+        // Build the optional REST/JSON gateway on its own listener
+        let (http_listener, http_router) = match http_address {
+            Some(http_address) => (
+                Some(TcpListener::bind(http_address).await?),
+                Some(http::router(
+                    std::sync::Arc::new(database.clone()),
+                    config.clone(),
+                    auth_events,
+                    denylist.clone(),
+                    captcha,
+                    api_keys.clone(),
+                )),
+            ),
+            None => (None, None),
+        };
+
+        Ok(Self {
+            router,
+            listener,
+            listeners,
+            health_reporter,
+            config,
+            http_listener,
+            http_router,
+            outbox,
+            webhooks,
+            verification_reminders,
+            database,
+            warm_up,
+            warm_up_connections,
+            readiness,
+            session_activity,
+            denylist,
+            api_keys,
+            tcp_keepalive,
+        })
+    }
+
+    /// Send the outbox emails with another client instead of the configured
+    /// transport, such as a `MockEmailClient` in tests
+    pub fn with_email_client(mut self, email: std::sync::Arc<dyn email::EmailClient>) -> Self {
+        self.outbox = self.outbox.with_email_client(email);
+        self
     }
 
     /// Run the Tonic server instance
@@ -54,8 +311,91 @@ impl TonicServer {
         );
         tracing::info!("Tonic server started at '{}'", address);
 
-        let incoming = tokio_stream::wrappers::TcpListenerStream::new(self.listener);
-        self.router.serve_with_incoming(incoming).await?;
+        // Warm up in the background while the server accepts connections, then
+        // report healthy once the readiness checks pass
+        let health_reporter = self.health_reporter.clone();
+        let database = self.database.clone();
+        let config = self.config.clone();
+        let readiness = self.readiness.clone();
+        let (warm_up, warm_up_connections) = (self.warm_up, self.warm_up_connections);
+        tokio::spawn(async move {
+            if warm_up {
+                let _report = warm_up::run(&database, warm_up_connections).await;
+            }
+            loop {
+                let config = config.load_full();
+                let report = readiness::check(&database, &config).await;
+                let ready = report.is_ready();
+                readiness.set(report);
+                if ready {
+                    break;
+                }
+                let retry_interval = config.readiness.retry_interval_seconds;
+                tracing::warn!("Tonic server is not ready, checking again in {retry_interval}s");
+                tokio::time::sleep(std::time::Duration::from_secs(retry_interval)).await;
+            }
+            health_reporter
+                .set_service_status(SERVER_HEALTH_SERVICE, ServingStatus::Serving)
+                .await;
+            tracing::info!("Tonic server is reporting healthy");
+        });
+
+        // Report the database circuit state as the database health service
+        let health_reporter = self.health_reporter.clone();
+        let mut circuit_state = database::circuit_breaker().subscribe();
+        tokio::spawn(async move {
+            loop {
+                let status = match *circuit_state.borrow_and_update() {
+                    database::CircuitState::Open => ServingStatus::NotServing,
+                    _ => ServingStatus::Serving,
+                };
+                health_reporter
+                    .set_service_status(DATABASE_HEALTH_SERVICE, status)
+                    .await;
+                if circuit_state.changed().await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        // Process the outbox, deliver webhooks and send verification reminders
+        // in the background
+        self.outbox.spawn();
+        self.webhooks.spawn();
+        self.verification_reminders.spawn();
+
+        // Write the session activity to the database in the background
+        self.session_activity.spawn(self.database.clone());
+
+        // Pick up access tokens denied by other replicas in the background
+        self.denylist.spawn(self.database.clone());
+
+        // Pick up API keys created or revoked by other replicas in the background
+        self.api_keys.spawn(self.database.clone());
+
+        // Serve the REST/JSON gateway alongside the Tonic server
+        if let (Some(http_listener), Some(http_router)) = (self.http_listener, self.http_router) {
+            tokio::spawn(async move {
+                if let Err(e) = http::serve(http_listener, http_router).await {
+                    tracing::error!("HTTP gateway stopped: {e}");
+                }
+            });
+        }
+
+        // Serve the additional listeners alongside the main one
+        for AdditionalListener { name, router, listener } in self.listeners {
+            tracing::info!("Tonic listener '{name}' started at '{}'", listener.local_address());
+            let tcp_keepalive = self.tcp_keepalive;
+            tokio::spawn(async move {
+                if let Err(e) = listener.serve(router, tcp_keepalive).await {
+                    tracing::error!("Tonic listener '{name}' stopped: {e}");
+                }
+            });
+        }
+
+        Listener::Tcp(self.listener)
+            .serve(self.router, self.tcp_keepalive)
+            .await?;
 
         Ok(())
     }
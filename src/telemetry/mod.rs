@@ -0,0 +1,522 @@
+// -- ./src/telemetry/mod.rs
+
+// #![allow(unused)] // For beginning only.
+
+//! Sets up the API log telemetry
+//!
+//! # Application Telemetry
+//!
+//! Instrumenting to collect structured, event-based diagnostic information.
+//!
+//! Tracing is made up of spans, events within those spans and subscribers that
+//! pick what spans and events to grab and and what then performs tasks on the
+//! grabbed spans and events.
+//!
+//! # References
+//!
+//! Learn more about Rust Telemetry (i.e async logging)
+//!
+//! * [Tracing Crate Documentation](https://docs.rs/tracing/latest/tracing/)
+//! * [Tracing Repo](https://github.com/tokio-rs/tracing)
+//! * [Decrusting the tracing crate](https://youtu.be/21rtHinFA40)
+//! * [Getting started with Tracing](https://tokio.rs/tokio/topics/tracing)
+//! * [Can we have easier pretty log for development?](https://github.com/LukeMathWalker/tracing-bunyan-formatter/issues/17)
+
+//! ## OpenTelemetry
+//!
+//! When `telemetry.otlp_endpoint` is configured, spans (including the Tonic
+//! request span and the instrumented database functions) are also exported to
+//! an OTLP collector such as Jaeger or Tempo. W3C `traceparent` context in the
+//! incoming gRPC metadata is continued, so the service joins the caller's trace.
+//!
+//! Metrics, such as failed and throttled logins, are exported to the same
+//! collector. Without an endpoint the global meter is a no-op.
+//!
+//! ## Database Metrics
+//! - `db.query.duration` - seconds spent in each instrumented database
+//!   function, by `db.operation` span name. Recorded from the function spans,
+//!   so only while the log level lets `info` spans through.
+//! - `db.pool.connections`, `db.pool.idle` and `db.pool.max` - pool
+//!   saturation, registered when the pool is created.
+//!
+//! Slow statements are logged by sqlx, see
+//! `database.slow_query_threshold_milliseconds`.
+//!
+//! ## Console Logs
+//!
+//! Events are written to stdout as human readable lines, or one JSON object
+//! per line with `telemetry.log_format: json` for log pipelines. Either way
+//! tokens, passwords and secrets are scrubbed and emails masked as they are
+//! written, see `redaction`. Spans exported over OTLP are scrubbed the same
+//! way. Noisy targets can be sampled, see `sampling`.
+//!
+//! The log filter can be changed at runtime with the `SetLogFilter` utilities
+//! RPC, e.g. to debug one module for ten minutes, see `LogLevelHandle`.
+
+// TODO: Add https://prometheus.io/
+// TODO: Add tracing console
+
+mod redaction;
+mod sampling;
+
+pub use redaction::{RedactingMakeWriter, RedactingSpanExporter, Redactor, REDACTED};
+pub use sampling::LogSampler;
+
+use crate::configuration::{LogFormat, TelemetryConfiguration};
+use crate::prelude::*;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
+
+use opentelemetry::{
+    metrics::{Histogram, Meter, MeterProvider as _},
+    propagation::{Extractor, TextMapPropagator},
+    trace::TracerProvider as _,
+    KeyValue,
+};
+use opentelemetry_sdk::{
+    metrics::SdkMeterProvider,
+    propagation::TraceContextPropagator,
+    trace::{Sampler, SdkTracerProvider},
+    Resource,
+};
+use tracing::{
+    level_filters::LevelFilter,
+    span::{Attributes, Id},
+    subscriber::set_global_default,
+    Subscriber,
+};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{
+    fmt::format::FmtSpan,
+    layer::{Context, SubscriberExt},
+    registry::LookupSpan,
+    reload,
+    EnvFilter,
+    Layer,
+    Registry,
+};
+
+/// Spans from the database module are timed as queries
+const DATABASE_SPAN_TARGET: &str = "authentication_service::database";
+
+/// The log level handle of the initiated telemetry, tracing being global
+static LOG_LEVEL_HANDLE: OnceLock<LogLevelHandle> = OnceLock::new();
+
+/// Handle for changing the log level after tracing has been initiated
+#[derive(Clone)]
+pub struct LogLevelHandle {
+    handle: reload::Handle<EnvFilter, Registry>,
+
+    /// Counts filter changes, so a timed filter is only reverted while it is
+    /// still the one in use
+    generation: Arc<AtomicU64>,
+}
+
+impl LogLevelHandle {
+    fn new(handle: reload::Handle<EnvFilter, Registry>) -> Self {
+        Self {
+            handle,
+            generation: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    fn reload(&self, filter: EnvFilter) -> Result<(), AuthenticationError> {
+        self.handle
+            .reload(filter)
+            .map_err(|e| AuthenticationError::Generic(format!("Unable to reload log level: {e}")))
+    }
+
+    /// Replace the default log level. A `RUST_LOG` environment filter still
+    /// takes precedence, as it does at startup.
+    pub fn set(&self, log_level: LevelFilter) -> Result<(), AuthenticationError> {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.reload(env_filter(log_level))
+    }
+
+    /// Filter with `directives` on top of the default log level, e.g.
+    /// `authentication_service::database=debug`, in place of any `RUST_LOG`
+    /// filter. Returns the generation to `revert`.
+    pub fn set_directives(
+        &self,
+        log_level: LevelFilter,
+        directives: &str,
+    ) -> Result<u64, AuthenticationError> {
+        // Later directives replace earlier ones, so a level in `directives`
+        // replaces the default
+        let filter = EnvFilter::builder()
+            .parse(format!("{log_level},{directives}"))
+            .map_err(|e| AuthenticationError::ValidationError(format!("Invalid log filter: {e}")))?;
+
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        self.reload(filter)?;
+
+        Ok(generation)
+    }
+
+    /// Restore the default log level, unless the filter has changed since
+    /// `generation`. Returns whether it was restored.
+    pub fn revert(
+        &self,
+        generation: u64,
+        log_level: LevelFilter,
+    ) -> Result<bool, AuthenticationError> {
+        if self
+            .generation
+            .compare_exchange(generation, generation + 1, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return Ok(false);
+        }
+        self.reload(env_filter(log_level))?;
+
+        Ok(true)
+    }
+
+    /// The filter in use
+    pub fn current(&self) -> String {
+        self.handle
+            .with_current(|filter| filter.to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// Handle for changing the log level, `None` until tracing is initiated
+pub fn log_level_handle() -> Option<LogLevelHandle> {
+    LOG_LEVEL_HANDLE.get().cloned()
+}
+
+/// Initiated telemetry, flushes exported spans and metrics when dropped
+pub struct Telemetry {
+    log_level_handle: LogLevelHandle,
+    tracer_provider: Option<SdkTracerProvider>,
+    meter_provider: Option<SdkMeterProvider>,
+}
+
+impl Telemetry {
+    /// Handle for changing the log level at runtime
+    pub fn log_level_handle(&self) -> LogLevelHandle {
+        self.log_level_handle.clone()
+    }
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        if let Some(tracer_provider) = self.tracer_provider.take() {
+            if let Err(e) = tracer_provider.shutdown() {
+                eprintln!("Failed to flush OpenTelemetry spans: {e}");
+            }
+        }
+        if let Some(meter_provider) = self.meter_provider.take() {
+            if let Err(e) = meter_provider.shutdown() {
+                eprintln!("Failed to flush OpenTelemetry metrics: {e}");
+            }
+        }
+    }
+}
+
+/// When a database span was created, kept in the span extensions
+struct QueryStart(Instant);
+
+/// Records how long each instrumented database function takes
+struct DatabaseMetricsLayer {
+    duration: Histogram<f64>,
+}
+
+impl DatabaseMetricsLayer {
+    fn new(meter: Meter) -> Self {
+        Self {
+            duration: meter
+                .f64_histogram("db.query.duration")
+                .with_unit("s")
+                .with_description("Time spent in database functions")
+                .build(),
+        }
+    }
+}
+
+impl<S> Layer<S> for DatabaseMetricsLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if !attrs.metadata().target().starts_with(DATABASE_SPAN_TARGET) {
+            return;
+        }
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(QueryStart(Instant::now()));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(elapsed) = span
+            .extensions()
+            .get::<QueryStart>()
+            .map(|start| start.0.elapsed())
+        else {
+            return;
+        };
+
+        // Span names end with ": " for the console output
+        let operation = span.name().trim_end_matches([':', ' ']);
+        self.duration.record(
+            elapsed.as_secs_f64(),
+            &[KeyValue::new("db.operation", operation)],
+        );
+    }
+}
+
+/// Build the event filter for the log level
+fn env_filter(log_level: LevelFilter) -> EnvFilter {
+    // Set default log level based on configuration file
+    let default_env_filter = EnvFilter::builder()
+        .with_default_directive(log_level.into())
+        .from_env_lossy();
+
+    // Try to use env runtime level, if not present use default
+    // TODO: How do we test this
+    EnvFilter::try_from_default_env().unwrap_or_else(|_| default_env_filter)
+}
+
+/// Build the OTLP span exporter pipeline
+fn tracer_provider(
+    otlp_endpoint: &str,
+    config: &TelemetryConfiguration,
+) -> Result<SdkTracerProvider, AuthenticationError> {
+    use opentelemetry_otlp::WithExportConfig;
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(otlp_endpoint)
+        .build()
+        .map_err(|e| {
+            AuthenticationError::Generic(format!("Unable to build OTLP exporter: {e}"))
+        })?;
+
+    // Follow the caller's sampling decision, otherwise sample by ratio
+    let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+        config.sampling_ratio,
+    )));
+
+    // Spans carry the same fields as the console logs, so are redacted too
+    let exporter = RedactingSpanExporter::new(exporter, Redactor::new(config));
+
+    let tracer_provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_sampler(sampler)
+        .with_resource(
+            Resource::builder()
+                .with_service_name(config.service_name.clone())
+                .build(),
+        )
+        .build();
+
+    Ok(tracer_provider)
+}
+
+/// Build the OTLP metric exporter pipeline
+fn meter_provider(
+    otlp_endpoint: &str,
+    config: &TelemetryConfiguration,
+) -> Result<SdkMeterProvider, AuthenticationError> {
+    use opentelemetry_otlp::WithExportConfig;
+
+    let exporter = opentelemetry_otlp::MetricExporter::builder()
+        .with_tonic()
+        .with_endpoint(otlp_endpoint)
+        .build()
+        .map_err(|e| {
+            AuthenticationError::Generic(format!("Unable to build OTLP metric exporter: {e}"))
+        })?;
+
+    let meter_provider = SdkMeterProvider::builder()
+        .with_periodic_exporter(exporter)
+        .with_resource(
+            Resource::builder()
+                .with_service_name(config.service_name.clone())
+                .build(),
+        )
+        .build();
+
+    Ok(meter_provider)
+}
+
+pub fn init(
+    log_level: LevelFilter,
+    config: &TelemetryConfiguration,
+) -> Result<Telemetry, AuthenticationError> {
+    //-- 1. Filter events
+    // Wrap the filter in a reload layer so the log level can change at runtime
+    let (env_filter, reload_handle) = reload::Layer::new(env_filter(log_level));
+
+    // Build event collector for console output, pretty or JSON, scrubbing
+    // sensitive values as events are written
+    let console_writer = RedactingMakeWriter::new(std::io::stdout, Redactor::new(config));
+    let sampler = LogSampler::new(&config.log_sampling);
+    let (console_collector, json_collector) = match config.log_format {
+        LogFormat::Pretty => (
+            Some(
+                tracing_subscriber::fmt::layer()
+                    .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE)
+                    .with_writer(console_writer)
+                    .with_filter(sampler),
+            ),
+            None,
+        ),
+        LogFormat::Json => (
+            None,
+            Some(
+                tracing_subscriber::fmt::layer()
+                    .json()
+                    .with_current_span(true)
+                    .with_span_list(true)
+                    .with_span_events(FmtSpan::CLOSE)
+                    .with_writer(console_writer)
+                    .with_filter(sampler),
+            ),
+        ),
+    };
+
+    // Build span exporter, if an OTLP collector is configured
+    let tracer_provider = config
+        .otlp_endpoint
+        .as_deref()
+        .map(|otlp_endpoint| tracer_provider(otlp_endpoint, config))
+        .transpose()?;
+    let meter_provider = config
+        .otlp_endpoint
+        .as_deref()
+        .map(|otlp_endpoint| meter_provider(otlp_endpoint, config))
+        .transpose()?;
+    let otel_collector = tracer_provider.as_ref().map(|tracer_provider| {
+        tracing_opentelemetry::layer()
+            .with_tracer(tracer_provider.tracer(config.service_name.clone()))
+    });
+
+    // Time database functions, if metrics are exported
+    let database_metrics = meter_provider.as_ref().map(|meter_provider| {
+        DatabaseMetricsLayer::new(meter_provider.meter("authentication_service"))
+    });
+
+    //-- 2. Build a registry of collectors
+    let registry = tracing_subscriber::registry()
+        .with(env_filter)
+        .with(console_collector)
+        .with(json_collector)
+        .with(otel_collector)
+        .with(database_metrics);
+
+    // Convert all log records into tracing events.
+    tracing_log::LogTracer::init()?;
+
+    //-- 3. Initiate tracing
+    set_global_default(registry)?;
+
+    // Read and write W3C trace context on requests
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+    if let Some(tracer_provider) = &tracer_provider {
+        opentelemetry::global::set_tracer_provider(tracer_provider.clone());
+    }
+    if let Some(meter_provider) = &meter_provider {
+        opentelemetry::global::set_meter_provider(meter_provider.clone());
+    }
+
+    let log_level_handle = LogLevelHandle::new(reload_handle);
+    let _ = LOG_LEVEL_HANDLE.set(log_level_handle.clone());
+
+    Ok(Telemetry {
+        log_level_handle,
+        tracer_provider,
+        meter_provider,
+    })
+}
+
+/// Read trace context from gRPC request metadata (HTTP/2 headers)
+struct MetadataExtractor<'a>(&'a http::HeaderMap);
+
+impl Extractor for MetadataExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
+
+/// Build the Tonic request span, continuing any trace context sent by the caller
+pub fn grpc_span<B>(request: &http::Request<B>) -> tracing::Span {
+    let span = tracing::info_span!(
+        "Tonic",
+        otel.name = request.uri().path(),
+        rpc.system = "grpc",
+    );
+
+    let parent_context = opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.extract(&MetadataExtractor(request.headers()))
+    });
+    span.set_parent(parent_context);
+
+    span
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use opentelemetry::trace::TraceContextExt;
+
+    #[test]
+    fn timed_log_filters_are_only_reverted_while_in_use() -> Result<(), AuthenticationError> {
+        //-- Setup and Fixtures (Arrange)
+        let (_layer, handle) = reload::Layer::new(env_filter(LevelFilter::INFO));
+        let handle = LogLevelHandle::new(handle);
+
+        //-- Execute Function (Act)
+        let first =
+            handle.set_directives(LevelFilter::INFO, "authentication_service::database=debug")?;
+        let second = handle.set_directives(LevelFilter::INFO, "tower_http=debug")?;
+        let stale = handle.revert(first, LevelFilter::INFO)?;
+        let current = handle.current();
+        let reverted = handle.revert(second, LevelFilter::INFO)?;
+        let invalid = handle.set_directives(LevelFilter::INFO, "tower_http=loud");
+
+        //-- Checks (Assertions)
+        assert!(!stale);
+        assert!(current.contains("tower_http=debug"));
+        assert!(reverted);
+        assert!(!handle.current().contains("tower_http=debug"));
+        assert!(invalid.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn extracts_w3c_trace_context_from_metadata() {
+        //-- Setup and Fixtures (Arrange)
+        let request = http::Request::builder()
+            .header(
+                "traceparent",
+                "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            )
+            .body(())
+            .unwrap();
+
+        //-- Execute Function (Act)
+        let context = TraceContextPropagator::new()
+            .extract(&MetadataExtractor(request.headers()));
+
+        //-- Checks (Assertions)
+        let span_context = context.span().span_context().clone();
+        assert!(span_context.is_remote());
+        assert_eq!(
+            span_context.trace_id().to_string(),
+            "4bf92f3577b34da6a3ce929d0e0e4736"
+        );
+    }
+}
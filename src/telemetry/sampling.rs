@@ -0,0 +1,127 @@
+// -- ./src/telemetry/sampling.rs
+
+// #![allow(unused)] // For beginning only.
+
+//! Samples the console log events of noisy targets
+//!
+//! Per request debug logs can overwhelm a log pipeline. `telemetry.log_sampling`
+//! keeps a fraction of the info, debug and trace events of a target and its
+//! submodules, the most specific target's rule applying. Warnings and errors
+//! are always kept, as are spans, so sampled events still have their context.
+
+use tracing::{subscriber::Interest, Event, Level, Metadata};
+use tracing_subscriber::layer::{Context, Filter};
+
+use crate::configuration::LogSampling;
+
+/// Per layer filter keeping a fraction of the events of sampled targets
+#[derive(Debug, Clone, Default)]
+pub struct LogSampler {
+    /// Most specific target first
+    rules: Vec<LogSampling>,
+}
+
+impl LogSampler {
+    pub fn new(rules: &[LogSampling]) -> Self {
+        let mut rules = rules.to_vec();
+        rules.sort_by(|a, b| b.target.len().cmp(&a.target.len()));
+
+        Self { rules }
+    }
+
+    /// The fraction of events kept from a target, `None` when it isn't sampled
+    fn ratio(&self, target: &str) -> Option<f64> {
+        self.rules
+            .iter()
+            .find(|rule| {
+                target
+                    .strip_prefix(rule.target.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            })
+            .map(|rule| rule.ratio)
+    }
+
+    /// Is an event at the level from the target kept
+    fn sample(&self, level: &Level, target: &str) -> bool {
+        if *level <= Level::WARN {
+            return true;
+        }
+
+        match self.ratio(target) {
+            Some(ratio) => rand::random::<f64>() < ratio,
+            None => true,
+        }
+    }
+}
+
+impl<S> Filter<S> for LogSampler {
+    fn enabled(&self, _metadata: &Metadata<'_>, _cx: &Context<'_, S>) -> bool {
+        true
+    }
+
+    fn callsite_enabled(&self, _metadata: &'static Metadata<'static>) -> Interest {
+        Interest::always()
+    }
+
+    fn event_enabled(&self, event: &Event<'_>, _cx: &Context<'_, S>) -> bool {
+        self.sample(event.metadata().level(), event.metadata().target())
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sampler() -> LogSampler {
+        LogSampler::new(&[
+            LogSampling {
+                target: "authentication_service".to_string(),
+                ratio: 1.0,
+            },
+            LogSampling {
+                target: "authentication_service::database".to_string(),
+                ratio: 0.0,
+            },
+        ])
+    }
+
+    #[test]
+    fn the_most_specific_target_is_sampled() {
+        //-- Setup and Fixtures (Arrange)
+        let sampler = sampler();
+
+        //-- Execute Function (Act)
+        let database = sampler.ratio("authentication_service::database::users");
+        let services = sampler.ratio("authentication_service::services");
+        let similar = sampler.ratio("authentication_service_client");
+        let other = sampler.ratio("tower_http");
+
+        //-- Checks (Assertions)
+        assert_eq!(database, Some(0.0));
+        assert_eq!(services, Some(1.0));
+        assert_eq!(similar, None);
+        assert_eq!(other, None);
+    }
+
+    #[test]
+    fn warnings_and_errors_are_always_kept() {
+        //-- Setup and Fixtures (Arrange)
+        let sampler = sampler();
+        let target = "authentication_service::database";
+
+        //-- Execute Function (Act)
+        let debug = sampler.sample(&Level::DEBUG, target);
+        let info = sampler.sample(&Level::INFO, target);
+        let warn = sampler.sample(&Level::WARN, target);
+        let error = sampler.sample(&Level::ERROR, target);
+        let unsampled = sampler.sample(&Level::DEBUG, "tower_http");
+
+        //-- Checks (Assertions)
+        assert!(!debug);
+        assert!(!info);
+        assert!(warn);
+        assert!(error);
+        assert!(unsampled);
+    }
+}
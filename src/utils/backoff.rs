@@ -0,0 +1,73 @@
+//-- ./src/utils/backoff.rs
+
+// #![allow(unused)] // For development only
+
+//! # Backoff Utilities
+//!
+//! Exponential backoff shared by the background workers that retry failed
+//! side effects (outbox entries and webhook deliveries), and the database
+//! queries retried after transient errors.
+//!
+//! Modules include:
+//!
+//! - `retry_delay(base_seconds, attempts)` - how long to wait before the next attempt
+//! - `jittered_delay(base, max, attempts)` - a random wait up to the backoff,
+//!   so retries from many requests don't arrive together
+
+use std::time::Duration;
+
+/// Longest delay between two attempts
+pub const MAX_RETRY_DELAY_SECONDS: u64 = 6 * 60 * 60;
+
+/// # Retry Delay
+///
+/// How long to wait before the next attempt, after `attempts` attempts have
+/// failed. The delay starts at `base_seconds` and doubles with each failed
+/// attempt, capped at `MAX_RETRY_DELAY_SECONDS`.
+pub fn retry_delay(base_seconds: u64, attempts: i32) -> chrono::Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 20) as u32;
+    let seconds = base_seconds
+        .saturating_mul(2u64.pow(exponent))
+        .min(MAX_RETRY_DELAY_SECONDS);
+
+    chrono::Duration::seconds(seconds as i64)
+}
+
+/// # Jittered Delay
+///
+/// A random wait before the next attempt, after `attempts` attempts have
+/// failed, between zero and a ceiling that starts at `base` and doubles with
+/// each failed attempt, capped at `max` ("full jitter").
+pub fn jittered_delay(base: Duration, max: Duration, attempts: u32) -> Duration {
+    let exponent = attempts.saturating_sub(1).min(20);
+    let ceiling = base.saturating_mul(2u32.pow(exponent)).min(max);
+
+    ceiling.mul_f64(rand::random::<f64>())
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_backs_off_exponentially() {
+        assert_eq!(retry_delay(30, 1), chrono::Duration::seconds(30));
+        assert_eq!(retry_delay(30, 3), chrono::Duration::seconds(120));
+        assert_eq!(
+            retry_delay(30, 30),
+            chrono::Duration::seconds(MAX_RETRY_DELAY_SECONDS as i64)
+        );
+    }
+
+    #[test]
+    fn jittered_delay_is_within_the_backoff() {
+        let (base, max) = (Duration::from_millis(25), Duration::from_millis(500));
+
+        for _ in 0..100 {
+            assert!(jittered_delay(base, max, 1) <= base);
+            assert!(jittered_delay(base, max, 3) <= Duration::from_millis(100));
+            assert!(jittered_delay(base, max, 30) <= max);
+        }
+    }
+}
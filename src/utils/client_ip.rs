@@ -0,0 +1,231 @@
+//-- ./src/utils/client_ip.rs
+
+// #![allow(unused)] // For development only
+
+//! # Client IP Address
+//!
+//! Behind a proxy or load balancer the peer address of a request is the
+//! proxy, and the client's address is in the `forwarded` (RFC 7239) or
+//! `x-forwarded-for` metadata. Either can be set by anyone, so they are only
+//! read when the peer is one of `trusted_proxies.cidrs`.
+//!
+//! The forwarded addresses are walked from the nearest hop, the last, while
+//! each is a trusted proxy. The first address that isn't trusted is the
+//! client, so a client can't pretend to be another address by sending its own
+//! header. `forwarded` is used when both are sent.
+//!
+//! Requests over a Unix domain socket listener have no peer address. The
+//! peer is on the same host, so it is taken to be the loopback address, and
+//! the forwarding metadata is read when loopback is a trusted proxy.
+//!
+//! Services use the client IP address for the login history, throttling and
+//! rate limits, and record it on the request span as `client_ip`.
+//! ---
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use tonic::metadata::MetadataMap;
+use tonic::Request;
+
+use crate::configuration::TrustedProxiesConfiguration;
+
+/// The peer of requests without a peer address, over a Unix domain socket
+const LOCAL_PEER: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+/// The client IP address of a request, from the loopback address when it
+/// has no peer address
+pub fn client_ip<T>(request: &Request<T>, config: &TrustedProxiesConfiguration) -> IpAddr {
+    let peer = request
+        .remote_addr()
+        .map_or(LOCAL_PEER, |peer| peer.ip());
+
+    client_ip_from(peer, request.metadata(), config)
+}
+
+/// The client IP address of a request from `peer`, taken from the forwarding
+/// metadata when the peer is a trusted proxy
+pub fn client_ip_from(
+    peer: IpAddr,
+    metadata: &MetadataMap,
+    config: &TrustedProxiesConfiguration,
+) -> IpAddr {
+    if !config.is_trusted(&peer) {
+        return peer;
+    }
+
+    let hops = forwarded_for(metadata);
+
+    // Walk back from the nearest hop while the address is a trusted proxy. An
+    // unknown or obfuscated hop ends the walk at the last address known.
+    let mut client = peer;
+    for hop in hops.iter().rev() {
+        if !config.is_trusted(&client) {
+            break;
+        }
+        match hop {
+            Some(ip) => client = *ip,
+            None => break,
+        }
+    }
+
+    client
+}
+
+/// The forwarded addresses, the client first, `None` for a hop that isn't an
+/// IP address
+fn forwarded_for(metadata: &MetadataMap) -> Vec<Option<IpAddr>> {
+    let forwarded: Vec<&str> = metadata
+        .get_all("forwarded")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .collect();
+    if !forwarded.is_empty() {
+        return forwarded
+            .iter()
+            .flat_map(|value| value.split(','))
+            .filter_map(|element| {
+                element.split(';').find_map(|pair| {
+                    let (key, value) = pair.split_once('=')?;
+                    key.trim()
+                        .eq_ignore_ascii_case("for")
+                        .then(|| parse_node(value))
+                })
+            })
+            .collect();
+    }
+
+    metadata
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(parse_node)
+        .collect()
+}
+
+/// Parse a forwarded node, an IP address with an optional port, IPv6
+/// addresses in brackets when they have a port, e.g. `"[2001:db8::1]:4711"`
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+
+    node.parse::<IpAddr>()
+        .ok()
+        .or_else(|| node.parse::<SocketAddr>().ok().map(|address| address.ip()))
+        .or_else(|| {
+            node.strip_prefix('[')
+                .and_then(|node| node.strip_suffix(']'))
+                .and_then(|node| node.parse().ok())
+        })
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    fn config(cidrs: &[&str]) -> Result<TrustedProxiesConfiguration> {
+        Ok(TrustedProxiesConfiguration {
+            cidrs: cidrs
+                .iter()
+                .map(|cidr| cidr.parse())
+                .collect::<core::result::Result<_, _>>()?,
+        })
+    }
+
+    fn metadata(headers: &[(&'static str, &'static str)]) -> MetadataMap {
+        let mut metadata = MetadataMap::new();
+        for (key, value) in headers {
+            metadata.append(*key, value.parse().unwrap());
+        }
+        metadata
+    }
+
+    #[test]
+    fn forwarding_metadata_is_ignored_from_untrusted_peers() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let config = config(&["10.0.0.0/8"])?;
+        let metadata = metadata(&[("x-forwarded-for", "198.51.100.1")]);
+        let peer: IpAddr = "203.0.113.7".parse()?;
+
+        //-- Execute Function (Act)
+        let client = client_ip_from(peer, &metadata, &config);
+        let untrusted =
+            client_ip_from(peer, &metadata, &TrustedProxiesConfiguration::default());
+
+        //-- Checks (Assertions)
+        assert_eq!(client, peer);
+        assert_eq!(untrusted, peer);
+
+        Ok(())
+    }
+
+    #[test]
+    fn the_first_untrusted_hop_is_the_client() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let config = config(&["10.0.0.0/8"])?;
+        // The client sent its own header, claiming to be 192.0.2.1
+        let metadata =
+            metadata(&[("x-forwarded-for", "192.0.2.1, 198.51.100.1, 10.0.0.2")]);
+        let peer: IpAddr = "10.0.0.1".parse()?;
+
+        //-- Execute Function (Act)
+        let client = client_ip_from(peer, &metadata, &config);
+
+        //-- Checks (Assertions)
+        assert_eq!(client, "198.51.100.1".parse::<IpAddr>()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn forwarded_is_used_before_x_forwarded_for() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let config = config(&["10.0.0.0/8"])?;
+        let metadata = metadata(&[
+            ("x-forwarded-for", "198.51.100.1"),
+            (
+                "forwarded",
+                "for=192.0.2.60;proto=https, For=\"[2001:db8:cafe::17]:4711\"",
+            ),
+        ]);
+        let obfuscated =
+            self::metadata(&[("forwarded", "for=_hidden, for=10.0.0.2")]);
+        let peer: IpAddr = "10.0.0.1".parse()?;
+
+        //-- Execute Function (Act)
+        let client = client_ip_from(peer, &metadata, &config);
+        let hidden = client_ip_from(peer, &obfuscated, &config);
+
+        //-- Checks (Assertions)
+        assert_eq!(client, "2001:db8:cafe::17".parse::<IpAddr>()?);
+        assert_eq!(hidden, "10.0.0.2".parse::<IpAddr>()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn requests_without_a_peer_are_from_loopback() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let config = config(&["127.0.0.1/32"])?;
+        let mut forwarded = Request::new(());
+        forwarded
+            .metadata_mut()
+            .insert("x-forwarded-for", "198.51.100.1".parse()?);
+
+        //-- Execute Function (Act)
+        let local = client_ip(&Request::new(()), &config);
+        let proxied = client_ip(&forwarded, &config);
+        let untrusted = client_ip(&forwarded, &TrustedProxiesConfiguration::default());
+
+        //-- Checks (Assertions)
+        assert_eq!(local, LOCAL_PEER);
+        assert_eq!(proxied, "198.51.100.1".parse::<IpAddr>()?);
+        assert_eq!(untrusted, LOCAL_PEER);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+//-- ./src/utils/clock.rs
+
+// #![allow(unused)] // For development only
+
+//! # Clock Utilities
+//!
+//! Where the current time comes from for token and expiry logic, so tests can
+//! fix and advance time instead of sleeping.
+//!
+//! Modules include:
+//!
+//! - `Clock` - the current time
+//! - `SystemClock` - the system time, used everywhere outside tests
+//! - `MockClock` - a time that only moves when a test advances it
+
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, SubsecRound, Utc};
+
+/// The current time
+pub trait Clock: Send + Sync {
+    /// The time now
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A time set by the test, only moving when advanced. Clones share the time.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    now: Arc<Mutex<DateTime<Utc>>>,
+}
+
+impl Default for MockClock {
+    /// Starts at the system time, rounded to seconds to match JWT timestamps
+    fn default() -> Self {
+        Self::new(Utc::now().round_subsecs(0))
+    }
+}
+
+impl MockClock {
+    /// A clock stopped at `now`
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(now)),
+        }
+    }
+
+    /// Move the clock forward, or back with a negative duration
+    pub fn advance(&self, duration: chrono::Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+
+    /// Stop the clock at another time
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap() = now;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mock_clock_only_moves_when_advanced() {
+        let start = Utc::now().round_subsecs(0);
+        let clock = MockClock::new(start);
+        let shared = clock.clone();
+
+        assert_eq!(clock.now(), start);
+
+        shared.advance(chrono::Duration::minutes(5));
+        assert_eq!(clock.now(), start + chrono::Duration::minutes(5));
+
+        clock.set(start);
+        assert_eq!(shared.now(), start);
+    }
+
+    #[test]
+    fn system_clock_is_the_current_time() {
+        let before = Utc::now();
+        let now = SystemClock.now();
+
+        assert!(now >= before && now <= Utc::now());
+    }
+}
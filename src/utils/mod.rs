@@ -4,7 +4,14 @@
 #[cfg(test)]
 mod mock_uuid;
 
+pub mod backoff;
+pub mod client_ip;
+pub mod clock;
 pub mod metadata;
+pub mod pagination;
+pub mod rate_limit;
+pub mod tenant;
 
+pub use clock::{Clock, MockClock, SystemClock};
 #[cfg(test)]
 pub use mock_uuid::mock_uuid;
@@ -0,0 +1,106 @@
+//-- ./src/utils/pagination.rs
+
+// #![allow(unused)] // For beginning only.
+
+//! # Pagination Utilities
+//!
+//! Keyset pagination metadata for list RPC responses.
+//!
+//! List RPCs query one row more than the requested limit. If that extra row
+//! comes back there is another page, and the cursor for it is the
+//! `created_on` and `id` of the last row returned.
+//!
+//! Modules include:
+//!
+//! - `Page::from_records(records, limit, cursor)` - trims the extra row and builds the next cursor
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use uuid::Uuid;
+
+use crate::rpc::proto::PageCursor;
+
+/// A page of records, with the cursor for the page after it
+#[derive(Debug, PartialEq)]
+pub struct Page<T> {
+    pub records: Vec<T>,
+    pub next_cursor: Option<PageCursor>,
+    pub has_more: bool,
+}
+
+impl<T> Page<T> {
+    /// Build a page from records queried with a limit of `limit + 1`
+    ///
+    /// ## Parameters
+    ///
+    /// - `records: Vec<T>` - The records, in page order
+    /// - `limit: usize` - The limit the client asked for
+    /// - `cursor: impl Fn(&T) -> (DateTime<Utc>, Uuid)` - The created on and id of a record
+    pub fn from_records(
+        mut records: Vec<T>,
+        limit: usize,
+        cursor: impl Fn(&T) -> (DateTime<Utc>, Uuid),
+    ) -> Self {
+        let has_more = records.len() > limit;
+        records.truncate(limit);
+
+        let next_cursor = if has_more {
+            records.last().map(|record| page_cursor(cursor(record)))
+        } else {
+            None
+        };
+
+        Self {
+            records,
+            next_cursor,
+            has_more,
+        }
+    }
+}
+
+/// The limit to query the database with, one more than asked for
+pub fn query_limit(limit: usize) -> usize {
+    limit.saturating_add(1)
+}
+
+fn page_cursor((created_on, id): (DateTime<Utc>, Uuid)) -> PageCursor {
+    PageCursor {
+        created_on: created_on.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        id: id.to_string(),
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records(count: usize) -> Vec<(DateTime<Utc>, Uuid)> {
+        (0..count).map(|_| (Utc::now(), Uuid::now_v7())).collect()
+    }
+
+    #[test]
+    fn extra_record_means_another_page() {
+        let records = records(4);
+        let last = records[2];
+
+        let page = Page::from_records(records, 3, |record| *record);
+
+        assert_eq!(page.records.len(), 3);
+        assert!(page.has_more);
+        let next_cursor = page.next_cursor.unwrap();
+        assert_eq!(next_cursor.id, last.1.to_string());
+        assert_eq!(
+            next_cursor.created_on.parse::<DateTime<Utc>>().unwrap(),
+            last.0
+        );
+    }
+
+    #[test]
+    fn last_page_has_no_cursor() {
+        let page = Page::from_records(records(3), 3, |record| *record);
+
+        assert_eq!(page.records.len(), 3);
+        assert!(!page.has_more);
+        assert_eq!(page.next_cursor, None);
+    }
+}
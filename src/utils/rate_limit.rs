@@ -0,0 +1,109 @@
+//-- ./src/utils/rate_limit.rs
+
+// #![allow(unused)] // For development only
+
+//! # Rate Limit Metadata
+//!
+//! The rate limit state returned to clients on throttled endpoints, so they
+//! can back off before being locked out:
+//!
+//! | Metadata                | Value                                         |
+//! |-------------------------|-----------------------------------------------|
+//! | `x-ratelimit-limit`     | Requests allowed within the window            |
+//! | `x-ratelimit-remaining` | Requests left before the limit is reached     |
+//! | `x-ratelimit-reset`     | Seconds until the count starts again          |
+//!
+//! The metadata is added to error statuses as well as responses, and the
+//! REST/JSON gateway returns it as headers.
+//! ---
+
+use tonic::metadata::{MetadataMap, MetadataValue};
+use tonic::{Response, Status};
+
+/// Requests allowed within the window
+pub const RATE_LIMIT_LIMIT: &str = "x-ratelimit-limit";
+
+/// Requests left before the limit is reached
+pub const RATE_LIMIT_REMAINING: &str = "x-ratelimit-remaining";
+
+/// Seconds until the count starts again
+pub const RATE_LIMIT_RESET: &str = "x-ratelimit-reset";
+
+/// Every rate limit metadata key
+pub const RATE_LIMIT_METADATA: [&str; 3] =
+    [RATE_LIMIT_LIMIT, RATE_LIMIT_REMAINING, RATE_LIMIT_RESET];
+
+/// The state of a rate limit, for one caller
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub limit: u32,
+    pub remaining: u32,
+    pub reset_seconds: u64,
+}
+
+impl RateLimit {
+    /// Add the rate limit to response or status metadata
+    pub fn insert_into(&self, metadata: &mut MetadataMap) {
+        metadata.insert(RATE_LIMIT_LIMIT, MetadataValue::from(self.limit));
+        metadata.insert(RATE_LIMIT_REMAINING, MetadataValue::from(self.remaining));
+        metadata.insert(RATE_LIMIT_RESET, MetadataValue::from(self.reset_seconds));
+    }
+}
+
+/// Add the rate limit, when there is one, to a response or its error status
+pub fn with_rate_limit<T>(
+    result: Result<Response<T>, Status>,
+    rate_limit: Option<RateLimit>,
+) -> Result<Response<T>, Status> {
+    let Some(rate_limit) = rate_limit else {
+        return result;
+    };
+
+    match result {
+        Ok(mut response) => {
+            rate_limit.insert_into(response.metadata_mut());
+            Ok(response)
+        }
+        Err(mut status) => {
+            rate_limit.insert_into(status.metadata_mut());
+            Err(status)
+        }
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn rate_limits_are_added_to_responses_and_statuses() -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let rate_limit = RateLimit {
+            limit: 5,
+            remaining: 2,
+            reset_seconds: 60,
+        };
+
+        //-- Execute Function (Act)
+        let response = with_rate_limit(Ok(Response::new(())), Some(rate_limit))
+            .map_err(|status| status.message().to_string())?;
+        let status = with_rate_limit::<()>(Err(Status::unauthenticated("no")), Some(rate_limit))
+            .unwrap_err();
+        let without = with_rate_limit(Ok(Response::new(())), None)
+            .map_err(|status| status.message().to_string())?;
+
+        //-- Checks (Assertions)
+        assert_eq!(response.metadata().get(RATE_LIMIT_LIMIT).ok_or("no limit")?, "5");
+        assert_eq!(response.metadata().get(RATE_LIMIT_REMAINING).ok_or("no remaining")?, "2");
+        assert_eq!(response.metadata().get(RATE_LIMIT_RESET).ok_or("no reset")?, "60");
+        assert_eq!(status.metadata().get(RATE_LIMIT_REMAINING).ok_or("no remaining")?, "2");
+        assert!(without.metadata().get(RATE_LIMIT_LIMIT).is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+//-- ./src/utils/tenant.rs
+
+// #![allow(unused)] // For beginning only.
+
+//! # Tenant Utilities
+//!
+//! Utility functions for scoping requests to an organization (tenant).
+//!
+//! Modules include:
+//!
+//! - `organization_id(extensions: &tonic::Extensions)` - returns the organization the request is scoped to
+
+use uuid::Uuid;
+
+use crate::domain;
+use crate::AuthenticationError;
+
+/// # Request Organization
+///
+/// The organization the request access token is scoped to, added to the request
+/// extensions by the authorisation layer. `None` when the token is not
+/// scoped or the request used an API key.
+pub fn organization_id(
+    extensions: &tonic::Extensions,
+) -> Result<Option<Uuid>, AuthenticationError> {
+    match extensions.get::<domain::TokenClaim>() {
+        Some(token_claim) => token_claim.organization_id(),
+        None => Ok(None),
+    }
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[test]
+    fn organization_from_token_claim() -> Result<()> {
+        let organization_id = Uuid::now_v7();
+        let mut extensions = tonic::Extensions::new();
+        assert_eq!(super::organization_id(&extensions)?, None);
+
+        extensions.insert(domain::TokenClaim::default().with_organization(&organization_id));
+        assert_eq!(super::organization_id(&extensions)?, Some(organization_id));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,153 @@
+//-- ./src/warm_up.rs
+
+// #![allow(unused)] // For development only
+
+//! # Startup Warm-up
+//!
+//! Optional warm-up run after the listener is bound but before the server
+//! reports healthy, so the first requests after a deploy do not pay for cold
+//! caches.
+//!
+//! The warm-up:
+//! - Reads the first page of users and sessions, paging hot rows into the
+//!   Postgres buffer cache
+//! - Runs the hottest login and refresh statements on several pooled
+//!   connections, so each connection has them prepared in its statement cache
+//! - Performs one throwaway Argon2 verification to page in the hashing code
+//!   and allocate its memory
+//!
+//! Failures are logged and reported but never stop the server starting.
+//! ---
+
+use std::time::{Duration, Instant};
+
+use secrecy::SecretString;
+use sqlx::{Pool, Postgres};
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+use crate::{database, domain, prelude::*};
+
+/// How many hot user and session rows to read into the buffer cache
+const HOT_ROW_LIMIT: usize = 100;
+
+/// Argon2id hash of a throwaway password, using the same parameters as
+/// `domain::PasswordHash`
+const THROWAWAY_PASSWORD_HASH: &str = "$argon2id$v=19$m=15000,t=2,p=1$HBwgCOwk9o745vPiPI/0iA$TozkH3DlprgOaWhMOU4xE1xrVGJkdUWofJujyiJ4j+U";
+
+/// Summary of a warm-up run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WarmUpReport {
+    /// Hot user rows read
+    pub users_primed: usize,
+
+    /// Hot session rows read
+    pub sessions_primed: usize,
+
+    /// Connections that ran the hot statements
+    pub connections_prepared: usize,
+
+    /// Whether the throwaway Argon2 verification ran
+    pub argon2_primed: bool,
+
+    /// How long the warm-up took
+    pub elapsed: Duration,
+}
+
+/// Run the hot login and refresh statements once.
+///
+/// The lookups use values that will not match a row, the point is for sqlx to
+/// prepare and cache each statement on the connection that runs it. Running
+/// several of these concurrently spreads them over different connections.
+async fn prepare_hot_statements(database: Pool<Postgres>) -> Result<(), AuthenticationError> {
+    let email = domain::EmailAddress::parse("warm.up@example.com")?;
+    let _ = database::Users::from_user_email(&email, &database).await;
+    let _ = database::Users::from_user_id(&Uuid::nil(), &database).await;
+    let _ = database::Sessions::from_token("warm-up", &database).await;
+    let _ = database::Sessions::from_id(&Uuid::nil(), &database).await;
+
+    Ok(())
+}
+
+/// Run the startup warm-up.
+///
+/// # Parameters
+/// * `database` - The sqlx database pool to warm.
+/// * `connections` - How many pooled connections should prepare the hot statements.
+///
+/// # Returns
+/// * `WarmUpReport` - What was warmed and how long it took.
+#[tracing::instrument(name = "Startup warm-up: ", skip(database))]
+pub async fn run(database: &Pool<Postgres>, connections: usize) -> WarmUpReport {
+    let started = Instant::now();
+    let mut report = WarmUpReport::default();
+
+    //-- 1. Page hot rows into the buffer cache
+    match database::Users::index(&HOT_ROW_LIMIT, &0, database).await {
+        Ok(users) => report.users_primed = users.len(),
+        Err(e) => tracing::warn!("Warm-up failed reading users: {e}"),
+    }
+    match database::Sessions::index(&HOT_ROW_LIMIT, &0, database).await {
+        Ok(sessions) => report.sessions_primed = sessions.len(),
+        Err(e) => tracing::warn!("Warm-up failed reading sessions: {e}"),
+    }
+
+    //-- 2. Prepare the hottest statements on several connections
+    let mut tasks = JoinSet::new();
+    for _ in 0..connections {
+        tasks.spawn(prepare_hot_statements(database.clone()));
+    }
+    while let Some(result) = tasks.join_next().await {
+        match result {
+            Ok(Ok(())) => report.connections_prepared += 1,
+            Ok(Err(e)) => tracing::warn!("Warm-up failed preparing statements: {e}"),
+            Err(e) => tracing::warn!("Warm-up statement task failed: {e}"),
+        }
+    }
+
+    //-- 3. One throwaway Argon2 verification, off the async runtime
+    let argon2 = tokio::task::spawn_blocking(|| {
+        let password_hash = domain::PasswordHash::from(THROWAWAY_PASSWORD_HASH.to_string());
+        password_hash.verify_password(&SecretString::from("warm-up"))
+    })
+    .await;
+    match argon2 {
+        Ok(Ok(_)) => report.argon2_primed = true,
+        Ok(Err(e)) => tracing::warn!("Warm-up Argon2 verification failed: {e}"),
+        Err(e) => tracing::warn!("Warm-up Argon2 task failed: {e}"),
+    }
+
+    report.elapsed = started.elapsed();
+    tracing::info!("Startup warm-up finished: {report:?}");
+
+    report
+}
+
+//-- Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Override with more flexible error
+    pub type Result<T> = core::result::Result<T, Error>;
+    pub type Error = Box<dyn std::error::Error>;
+
+    #[sqlx::test]
+    async fn warm_up_primes_rows_connections_and_argon2(
+        database: Pool<Postgres>,
+    ) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        // The migration adds a default admin user
+        let _users = database::Users::insert_n_users(5, &database).await?;
+
+        //-- Execute Function (Act)
+        let report = run(&database, 3).await;
+
+        //-- Checks (Assertions)
+        assert_eq!(report.users_primed, 6);
+        assert_eq!(report.connections_prepared, 3);
+        assert!(report.argon2_primed);
+
+        Ok(())
+    }
+}
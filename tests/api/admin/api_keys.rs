@@ -0,0 +1,79 @@
+//-- ./tests/api/admin/api_keys.rs
+
+// #![allow(unused)] // For beginning only.
+
+use sqlx::{Pool, Postgres};
+
+use authentication_service::domain;
+use authentication_service::rpc::proto::{
+    ApiKeyIndexRequest, CreateApiKeyRequest, RevokeApiKeyRequest,
+};
+
+use crate::helpers;
+
+pub type Error = Box<dyn std::error::Error>;
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[sqlx::test]
+async fn create_list_and_revoke_api_key(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    // Spawn Tonic test server, this adds the server user
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+
+    // Spawn Tonic test client
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    let request_message = CreateApiKeyRequest {
+        name: "Billing Worker".to_string(),
+        role: "user".to_string(),
+        expires_in_days: Some(30),
+    };
+
+    //-- Execute Test (Act)
+    let created = tonic_client
+        .admin()
+        .create_api_key(request_message)
+        .await?
+        .into_inner();
+
+    let api_key = created.api_key.ok_or("missing api key")?;
+
+    let listed = tonic_client
+        .admin()
+        .list_api_keys(ApiKeyIndexRequest {
+            limit: 10,
+            offset: 0,
+        })
+        .await?
+        .into_inner();
+
+    let revoked = tonic_client
+        .admin()
+        .revoke_api_key(RevokeApiKeyRequest {
+            id: api_key.id.clone(),
+        })
+        .await?
+        .into_inner();
+
+    //-- Checks (Assertions)
+    // The key is returned once and only its prefix is kept
+    let secret = domain::ApiKey::parse(&created.secret)?;
+    assert_eq!(api_key.key_prefix, secret.display_prefix());
+    assert_eq!(api_key.role, "user");
+    assert!(api_key.expires_on.is_some());
+
+    assert_eq!(listed.api_keys.len(), 1);
+    assert_eq!(listed.api_keys[0].id, api_key.id);
+
+    assert!(revoked.success);
+
+    // Revoking twice is not found
+    let status = tonic_client
+        .admin()
+        .revoke_api_key(RevokeApiKeyRequest { id: api_key.id })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::NotFound);
+
+    Ok(())
+}
@@ -0,0 +1,110 @@
+//-- ./tests/api/admin/delete_user.rs
+
+// #![allow(unused)] // For beginning only.
+
+use sqlx::{Pool, Postgres};
+use tonic::Code;
+
+use authentication_service::database;
+use authentication_service::rpc::proto::{
+    DeleteUserRequest, LoginRequest, RestoreUserRequest,
+};
+
+use crate::helpers;
+
+pub type Error = Box<dyn std::error::Error>;
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[sqlx::test]
+async fn deleted_users_can_not_log_in_until_restored(
+    database: Pool<Postgres>,
+) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password)?;
+    random_user.is_active = true;
+    random_user.is_verified = true;
+    let random_user = random_user.insert(&database).await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    let login_request = || LoginRequest {
+        email: random_user.email.to_string(),
+        password: random_password.to_string(),
+        remember_me: false,
+        organization_id: None,
+        captcha_token: None,
+        password_new: None,
+    };
+
+    //-- Execute Test (Act)
+    let deleted = tonic_client
+        .admin()
+        .delete_user(DeleteUserRequest {
+            id: random_user.id.to_string(),
+        })
+        .await?
+        .into_inner();
+    let deleted_login = tonic_client
+        .authentication()
+        .login(login_request())
+        .await
+        .unwrap_err();
+    let deleted_again = tonic_client
+        .admin()
+        .delete_user(DeleteUserRequest {
+            id: random_user.id.to_string(),
+        })
+        .await
+        .unwrap_err();
+
+    let restored = tonic_client
+        .admin()
+        .restore_user(RestoreUserRequest {
+            id: random_user.id.to_string(),
+        })
+        .await?
+        .into_inner();
+    let restored_login = tonic_client.authentication().login(login_request()).await;
+
+    //-- Checks (Assertions)
+    assert_eq!(deleted.rows_affected, 1);
+    assert_eq!(deleted_login.code(), Code::Unauthenticated);
+    assert_eq!(deleted_again.code(), Code::NotFound);
+    assert_eq!(restored.id, random_user.id.to_string());
+    assert!(restored_login.is_ok());
+    assert!(database::Users::from_user_id(&random_user.id, &database)
+        .await
+        .is_ok());
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn restoring_a_user_that_is_not_deleted_returns_not_found(
+    database: Pool<Postgres>,
+) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let random_user = helpers::mocks::users(&random_password)?
+        .insert(&database)
+        .await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    //-- Execute Test (Act)
+    let response = tonic_client
+        .admin()
+        .restore_user(RestoreUserRequest {
+            id: random_user.id.to_string(),
+        })
+        .await
+        .unwrap_err();
+
+    //-- Checks (Assertions)
+    assert_eq!(response.code(), Code::NotFound);
+
+    Ok(())
+}
@@ -0,0 +1,169 @@
+//-- ./tests/api/admin/email_change.rs
+
+// #![allow(unused)] // For beginning only.
+
+use sqlx::{Pool, Postgres};
+
+use authentication_service::database;
+use authentication_service::rpc::proto::{
+    ConfirmEmailChangeRequest, RequestEmailChangeRequest,
+};
+
+use crate::helpers;
+
+pub type Error = Box<dyn std::error::Error>;
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// The confirmation token emailed to an address, read from the outbox
+async fn emailed_token(database: &Pool<Postgres>, to: &str) -> Result<String> {
+    let (body_text,): (String,) = sqlx::query_as(
+        "SELECT payload->>'body_text' FROM outbox WHERE kind = 'email' AND payload->>'to' = $1",
+    )
+    .bind(to)
+    .fetch_one(database)
+    .await?;
+
+    let start = body_text.find("act_").ok_or("missing email change token")?;
+    let token = body_text[start..]
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_' && c != '.')
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('.');
+
+    Ok(token.to_string())
+}
+
+#[sqlx::test]
+async fn email_changes_after_both_addresses_confirm(
+    database: Pool<Postgres>,
+) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let random_user = helpers::mocks::users(&random_password)?;
+    random_user.insert(&database).await?;
+    let new_email = helpers::mocks::users(&random_password)?.email;
+
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    //-- Execute Test (Act)
+    let requested = tonic_client
+        .admin()
+        .request_email_change(RequestEmailChangeRequest {
+            user_id: random_user.id.to_string(),
+            new_email: new_email.to_string(),
+        })
+        .await?
+        .into_inner();
+
+    let old_token = emailed_token(&database, random_user.email.as_ref()).await?;
+    let new_token = emailed_token(&database, new_email.as_ref()).await?;
+
+    let first = tonic_client
+        .authentication()
+        .confirm_email_change(ConfirmEmailChangeRequest { token: old_token })
+        .await?
+        .into_inner();
+    let unchanged =
+        database::Users::from_user_id(&random_user.id, &database).await?;
+
+    let second = tonic_client
+        .authentication()
+        .confirm_email_change(ConfirmEmailChangeRequest {
+            token: new_token.clone(),
+        })
+        .await?
+        .into_inner();
+    let changed = database::Users::from_user_id(&random_user.id, &database).await?;
+
+    //-- Checks (Assertions)
+    assert!(!requested.id.is_empty());
+
+    // One confirmation is not enough to change the email
+    assert!(!first.completed);
+    assert_eq!(unchanged.email, random_user.email);
+
+    assert!(second.completed);
+    assert_eq!(changed.email, new_email);
+
+    // A completed change can not be confirmed again
+    let status = tonic_client
+        .authentication()
+        .confirm_email_change(ConfirmEmailChangeRequest { token: new_token })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::NotFound);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn email_change_codes_are_emailed_to_both_addresses(
+    database: Pool<Postgres>,
+) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let random_user = helpers::mocks::users(&random_password)?;
+    random_user.insert(&database).await?;
+    let new_email = helpers::mocks::users(&random_password)?.email;
+
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    //-- Execute Test (Act)
+    tonic_client
+        .admin()
+        .request_email_change(RequestEmailChangeRequest {
+            user_id: random_user.id.to_string(),
+            new_email: new_email.to_string(),
+        })
+        .await?;
+    tonic_server
+        .email
+        .wait_for_sent(2, std::time::Duration::from_secs(10))
+        .await?;
+
+    //-- Checks (Assertions)
+    let old_token = tonic_server
+        .email
+        .last_email_change_token(&random_user.email)
+        .ok_or("no code emailed to the old address")?;
+    let new_token = tonic_server
+        .email
+        .last_email_change_token(&new_email)
+        .ok_or("no code emailed to the new address")?;
+    assert_ne!(old_token, new_token);
+    assert_eq!(old_token, emailed_token(&database, random_user.email.as_ref()).await?);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn email_change_to_an_address_in_use_is_rejected(
+    database: Pool<Postgres>,
+) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let random_user = helpers::mocks::users(&random_password)?;
+    random_user.insert(&database).await?;
+    let other_user = helpers::mocks::users(&random_password)?;
+    other_user.insert(&database).await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    //-- Execute Test (Act)
+    let status = tonic_client
+        .admin()
+        .request_email_change(RequestEmailChangeRequest {
+            user_id: random_user.id.to_string(),
+            new_email: other_user.email.to_string(),
+        })
+        .await
+        .unwrap_err();
+
+    //-- Checks (Assertions)
+    assert_eq!(status.code(), tonic::Code::AlreadyExists);
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+//-- ./tests/api/admin/export_users.rs
+
+// #![allow(unused)] // For beginning only.
+
+use sqlx::{Pool, Postgres};
+use tokio_stream::StreamExt;
+
+use authentication_service::rpc::proto::ExportUsersRequest;
+
+use crate::helpers;
+
+pub type Error = Box<dyn std::error::Error>;
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[sqlx::test]
+async fn csv_export_streams_every_user(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let random_count = 12;
+    for _count in 0..random_count {
+        let random_password = helpers::mocks::password()?;
+        helpers::mocks::users(&random_password)?.insert(&database).await?;
+    }
+
+    // Spawn Tonic test server, this adds the server user
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+
+    // Spawn Tonic test client
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    let request_message = ExportUsersRequest {
+        format: "csv".to_string(),
+    };
+
+    //-- Execute Test (Act)
+    let mut stream = tonic_client
+        .admin()
+        .export_users(request_message)
+        .await?
+        .into_inner();
+
+    let mut lines: Vec<String> = Vec::new();
+    while let Some(message) = stream.next().await {
+        lines.push(message?.line);
+    }
+
+    //-- Checks (Assertions)
+    // Header, plus the random users, the server user and the migration admin
+    assert_eq!(lines.len(), 1 + random_count + 2);
+    assert!(lines[0].starts_with("id,email"));
+    assert!(lines.iter().all(|line| !line.contains("$argon2")));
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+//-- ./tests/api/admin/force_password_reset.rs
+
+// #![allow(unused)] // For beginning only.
+
+use sqlx::{Pool, Postgres};
+use tonic::Code;
+
+use authentication_service::database;
+use authentication_service::rpc::proto::{
+    ForcePasswordResetRequest, LoginRequest, ResetPasswordRequest,
+};
+
+use crate::helpers;
+
+pub type Error = Box<dyn std::error::Error>;
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[sqlx::test]
+async fn forced_users_can_not_log_in_until_they_reset(
+    database: Pool<Postgres>,
+) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let new_password = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password)?;
+    random_user.is_active = true;
+    random_user.is_verified = true;
+    let random_user = random_user.insert(&database).await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    let login_request = |password: &str| LoginRequest {
+        email: random_user.email.to_string(),
+        password: password.to_string(),
+        remember_me: false,
+        organization_id: None,
+        captcha_token: None,
+        password_new: None,
+    };
+    tonic_client
+        .authentication()
+        .login(login_request(&random_password))
+        .await?;
+
+    //-- Execute Test (Act)
+    let forced = tonic_client
+        .admin()
+        .force_password_reset(ForcePasswordResetRequest {
+            user_id: random_user.id.to_string(),
+        })
+        .await?
+        .into_inner();
+    let forced_login = tonic_client
+        .authentication()
+        .login(login_request(&random_password))
+        .await
+        .unwrap_err();
+
+    tonic_server
+        .email
+        .wait_for_sent(1, std::time::Duration::from_secs(10))
+        .await?;
+    let token = tonic_server
+        .email
+        .last_magic_link_token(&random_user.email)
+        .ok_or("no reset link sent")?;
+    let reset = tonic_client
+        .authentication()
+        .reset_password(ResetPasswordRequest {
+            token: token.clone(),
+            password_new: new_password.clone(),
+        })
+        .await?
+        .into_inner();
+    let reused = tonic_client
+        .authentication()
+        .reset_password(ResetPasswordRequest {
+            token,
+            password_new: new_password.clone(),
+        })
+        .await
+        .unwrap_err();
+    let reset_login = tonic_client
+        .authentication()
+        .login(login_request(&new_password))
+        .await;
+
+    //-- Checks (Assertions)
+    assert!(forced.success);
+    assert_eq!(forced.sessions_revoked, 1);
+    assert_eq!(forced_login.code(), Code::FailedPrecondition);
+    assert_eq!(forced_login.message(), "PasswordChangeRequired");
+    assert!(reset.success);
+    assert_eq!(reused.code(), Code::Unauthenticated);
+    assert!(reset_login.is_ok());
+    assert_eq!(
+        database::Users::password_state(&random_user.id, &database).await?,
+        database::PasswordState::Set
+    );
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn forcing_a_reset_for_an_unknown_user_returns_not_found(
+    database: Pool<Postgres>,
+) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    //-- Execute Test (Act)
+    let status = tonic_client
+        .admin()
+        .force_password_reset(ForcePasswordResetRequest {
+            user_id: uuid::Uuid::now_v7().to_string(),
+        })
+        .await
+        .unwrap_err();
+
+    //-- Checks (Assertions)
+    assert_eq!(status.code(), Code::NotFound);
+
+    Ok(())
+}
@@ -0,0 +1,149 @@
+//-- ./tests/api/admin/import_users.rs
+
+// #![allow(unused)] // For beginning only.
+
+use sqlx::{Pool, Postgres};
+use tonic::Code;
+
+use authentication_service::{
+    database, domain,
+    rpc::proto::{CreateUserRequest, LoginRequest},
+};
+
+use crate::helpers;
+
+pub type Error = Box<dyn std::error::Error>;
+pub type Result<T> = core::result::Result<T, Error>;
+
+fn import_record(email: &str) -> Result<CreateUserRequest> {
+    Ok(CreateUserRequest {
+        email: email.to_string(),
+        name: "Imported User".to_string(),
+        password: helpers::mocks::password()?,
+        role: "user".to_string(),
+        is_active: true,
+        is_verified: false,
+        locale: None,
+        temporary_password: false,
+    })
+}
+
+#[sqlx::test]
+async fn valid_rows_are_imported_and_invalid_rows_reported(
+    database: Pool<Postgres>,
+) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    // Spawn Tonic test server
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+
+    // Spawn Tonic test client
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    let records = vec![
+        import_record("first.import@example.com")?,
+        import_record("not-an-email")?,
+        import_record("second.import@example.com")?,
+        // Duplicate of the first row
+        import_record("first.import@example.com")?,
+    ];
+
+    //-- Execute Test (Act)
+    let response_message = tonic_client
+        .admin()
+        .import_users(tokio_stream::iter(records))
+        .await?
+        .into_inner();
+
+    //-- Checks (Assertions)
+    assert_eq!(response_message.imported, 2);
+    assert_eq!(response_message.failed, 2);
+    assert_eq!(
+        response_message
+            .failures
+            .iter()
+            .map(|f| f.row)
+            .collect::<Vec<u64>>(),
+        vec![2, 4]
+    );
+
+    let email = domain::EmailAddress::parse("second.import@example.com")?;
+    let user = database::Users::from_user_email(&email, &database).await?;
+    assert_eq!(user.name.as_ref(), "Imported User");
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn temporary_passwords_must_be_changed_at_first_login(
+    database: Pool<Postgres>,
+) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    let email = domain::EmailAddress::parse("temporary.import@example.com")?;
+    let new_password = helpers::mocks::password()?;
+    let record = CreateUserRequest {
+        password: String::new(),
+        temporary_password: true,
+        ..import_record(email.as_ref())?
+    };
+    let login_request = |password: &str, password_new: Option<&str>| LoginRequest {
+        email: email.to_string(),
+        password: password.to_string(),
+        remember_me: false,
+        organization_id: None,
+        captcha_token: None,
+        password_new: password_new.map(str::to_string),
+    };
+
+    //-- Execute Test (Act)
+    let response_message = tonic_client
+        .admin()
+        .import_users(tokio_stream::iter(vec![record]))
+        .await?
+        .into_inner();
+    tonic_server
+        .email
+        .wait_for_sent(1, std::time::Duration::from_secs(10))
+        .await?;
+    let temporary_password = tonic_server
+        .email
+        .last_temporary_password(&email)
+        .ok_or("no temporary password sent")?;
+
+    let without_new_password = tonic_client
+        .authentication()
+        .login(login_request(&temporary_password, None))
+        .await
+        .unwrap_err();
+    let first_login = tonic_client
+        .authentication()
+        .login(login_request(&temporary_password, Some(&new_password)))
+        .await;
+    let temporary_again = tonic_client
+        .authentication()
+        .login(login_request(&temporary_password, None))
+        .await
+        .unwrap_err();
+    let new_login = tonic_client
+        .authentication()
+        .login(login_request(&new_password, None))
+        .await;
+
+    //-- Checks (Assertions)
+    assert_eq!(response_message.imported, 1);
+    assert_eq!(without_new_password.code(), Code::FailedPrecondition);
+    assert_eq!(without_new_password.message(), "PasswordChangeRequired");
+    assert!(first_login.is_ok());
+    assert_eq!(temporary_again.code(), Code::Unauthenticated);
+    assert!(new_login.is_ok());
+
+    let user = database::Users::from_user_email(&email, &database).await?;
+    assert_eq!(
+        database::Users::password_state(&user.id, &database).await?,
+        database::PasswordState::Set
+    );
+
+    Ok(())
+}
@@ -0,0 +1,11 @@
+//-- ./tests/api/admin/mod.rs
+
+mod api_keys;
+mod delete_user;
+mod email_change;
+mod export_users;
+mod force_password_reset;
+mod import_users;
+mod security_report;
+mod watch_auth_events;
+mod webhooks;
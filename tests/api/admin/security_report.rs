@@ -0,0 +1,81 @@
+//-- ./tests/api/admin/security_report.rs
+
+// #![allow(unused)] // For beginning only.
+
+use sqlx::{Pool, Postgres};
+use tonic::Code;
+
+use authentication_service::rpc::proto::{LoginRequest, SecurityReportRequest};
+
+use crate::helpers;
+
+pub type Error = Box<dyn std::error::Error>;
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[sqlx::test]
+async fn failed_logins_are_reported_by_ip_address_and_account(
+    database: Pool<Postgres>,
+) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password)?;
+    random_user.is_active = true;
+    random_user.is_verified = true;
+    let random_user = random_user.insert(&database).await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    for _ in 0..2 {
+        let failed = tonic_client
+            .authentication()
+            .login(LoginRequest {
+                email: random_user.email.to_string(),
+                password: helpers::mocks::password()?,
+                remember_me: false,
+                organization_id: None,
+                captcha_token: None,
+                password_new: None,
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(failed.code(), Code::Unauthenticated);
+    }
+    sqlx::query("UPDATE login_throttles SET locked_until = NOW() + INTERVAL '60 seconds'")
+        .execute(&database)
+        .await?;
+
+    //-- Execute Test (Act)
+    let report = tonic_client
+        .admin()
+        .get_security_report(SecurityReportRequest {
+            window_minutes: 60,
+            limit: 10,
+        })
+        .await?
+        .into_inner();
+    let invalid = tonic_client
+        .admin()
+        .get_security_report(SecurityReportRequest {
+            window_minutes: 0,
+            limit: 10,
+        })
+        .await
+        .unwrap_err();
+
+    //-- Checks (Assertions)
+    let account = report
+        .by_account
+        .iter()
+        .find(|account| account.user_id == Some(random_user.id.to_string()))
+        .ok_or("account not reported")?;
+    assert_eq!(account.failed, 2);
+    assert!(account.is_locked);
+    assert_eq!(report.by_ip_address.len(), 1);
+    assert_eq!(report.by_ip_address[0].failed, 2);
+    assert!(report.by_ip_address[0].is_throttled);
+    assert_eq!(report.locked.len(), 1);
+    assert_eq!(invalid.code(), Code::InvalidArgument);
+
+    Ok(())
+}
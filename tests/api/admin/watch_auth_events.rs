@@ -0,0 +1,138 @@
+//-- ./tests/api/admin/watch_auth_events.rs
+
+// #![allow(unused)] // For beginning only.
+
+use sqlx::{Pool, Postgres};
+use tokio_stream::StreamExt;
+
+use authentication_service::domain;
+use authentication_service::rpc::proto::{
+    SessionsRevokeUserRequest, UpdateUserRequest, WatchAuthEventsRequest,
+};
+
+use crate::helpers;
+
+pub type Error = Box<dyn std::error::Error>;
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[sqlx::test]
+async fn revoking_user_sessions_streams_revocation_event(
+    database: Pool<Postgres>,
+) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    // Spawn Tonic test server
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+
+    // Spawn Tonic test client
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    // Insert a random user with a session to revoke
+    let random_password = helpers::mocks::password()?;
+    let random_user = helpers::mocks::users(&random_password)?
+        .insert(&database)
+        .await?;
+    let refresh_token = domain::RefreshToken::new(
+        &tonic_server.config.application.token_secret,
+        &tonic_server.config.application.get_issuer(),
+        &std::time::Duration::from_secs(60 * 60),
+        &random_user,
+    )?;
+    let mut random_session = helpers::mocks::sessions(&random_user, &refresh_token)?;
+    random_session.is_active = true;
+    let _random_session = random_session.insert(&database).await?;
+
+    // Subscribe before the revocation so the event is not missed
+    let mut stream = tonic_client
+        .admin()
+        .watch_auth_events(WatchAuthEventsRequest {
+            kinds: vec!["revocation".to_string()],
+        })
+        .await?
+        .into_inner();
+
+    //-- Execute Test (Act)
+    let _response = tonic_client
+        .sessions()
+        .revoke_user(SessionsRevokeUserRequest {
+            user_id: random_user.id.to_string(),
+        })
+        .await?;
+
+    let event = tokio::time::timeout(std::time::Duration::from_secs(5), stream.next())
+        .await?
+        .ok_or("Event stream closed")??;
+
+    //-- Checks (Assertions)
+    assert_eq!(event.kind, "revocation");
+    assert_eq!(event.user_id, Some(random_user.id.to_string()));
+    assert!(event.sessions_affected >= 1);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn verifying_a_user_streams_verification_event(
+    database: Pool<Postgres>,
+) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    // Insert an unverified user
+    let random_password = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password)?;
+    random_user.is_verified = false;
+    let random_user = random_user.insert(&database).await?;
+
+    let mut stream = tonic_client
+        .admin()
+        .watch_auth_events(WatchAuthEventsRequest {
+            kinds: vec!["verification".to_string()],
+        })
+        .await?
+        .into_inner();
+
+    //-- Execute Test (Act)
+    let _response = tonic_client
+        .users()
+        .update(UpdateUserRequest {
+            id: random_user.id.to_string(),
+            email: random_user.email.to_string(),
+            name: random_user.name.to_string(),
+            role: random_user.role.to_string(),
+            is_active: random_user.is_active,
+            is_verified: true,
+            locale: None,
+        })
+        .await?;
+
+    let event = tokio::time::timeout(std::time::Duration::from_secs(5), stream.next())
+        .await?
+        .ok_or("Event stream closed")??;
+
+    //-- Checks (Assertions)
+    assert_eq!(event.kind, "verification");
+    assert_eq!(event.user_id, Some(random_user.id.to_string()));
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn unknown_event_kind_is_invalid_argument(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    //-- Execute Test (Act)
+    let response = tonic_client
+        .admin()
+        .watch_auth_events(WatchAuthEventsRequest {
+            kinds: vec!["password".to_string()],
+        })
+        .await;
+
+    //-- Checks (Assertions)
+    assert_eq!(response.err().map(|s| s.code()), Some(tonic::Code::InvalidArgument));
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+//-- ./tests/api/admin/webhooks.rs
+
+// #![allow(unused)] // For beginning only.
+
+use sqlx::{Pool, Postgres};
+
+use authentication_service::rpc::proto::{
+    CreateWebhookEndpointRequest, DeleteWebhookEndpointRequest, WebhookDeliveryIndexRequest,
+    WebhookEndpointIndexRequest,
+};
+
+use crate::helpers;
+
+pub type Error = Box<dyn std::error::Error>;
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[sqlx::test]
+async fn create_list_and_delete_webhook_endpoint(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    // Spawn Tonic test server, this adds the server user
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+
+    // Spawn Tonic test client
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    let request_message = CreateWebhookEndpointRequest {
+        url: "https://hooks.example.com/authentication".to_string(),
+        event_types: vec!["user.login".to_string(), "password.changed".to_string()],
+    };
+
+    //-- Execute Test (Act)
+    let created = tonic_client
+        .admin()
+        .create_webhook_endpoint(request_message)
+        .await?
+        .into_inner();
+
+    let endpoint = created.endpoint.ok_or("missing webhook endpoint")?;
+
+    let listed = tonic_client
+        .admin()
+        .list_webhook_endpoints(WebhookEndpointIndexRequest {
+            limit: 10,
+            offset: 0,
+        })
+        .await?
+        .into_inner();
+
+    let deliveries = tonic_client
+        .admin()
+        .list_webhook_deliveries(WebhookDeliveryIndexRequest {
+            endpoint_id: endpoint.id.clone(),
+            limit: 10,
+            offset: 0,
+        })
+        .await?
+        .into_inner();
+
+    let deleted = tonic_client
+        .admin()
+        .delete_webhook_endpoint(DeleteWebhookEndpointRequest {
+            id: endpoint.id.clone(),
+        })
+        .await?
+        .into_inner();
+
+    //-- Checks (Assertions)
+    // The signing secret is returned once
+    assert!(created.secret.starts_with("whsec_"));
+    assert!(endpoint.is_active);
+    assert_eq!(endpoint.event_types, vec!["user.login", "password.changed"]);
+
+    assert_eq!(listed.endpoints.len(), 1);
+    assert_eq!(listed.endpoints[0].id, endpoint.id);
+
+    assert!(deliveries.deliveries.is_empty());
+
+    assert!(deleted.success);
+
+    // Deleting twice is not found
+    let status = tonic_client
+        .admin()
+        .delete_webhook_endpoint(DeleteWebhookEndpointRequest { id: endpoint.id })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::NotFound);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn unknown_event_types_and_urls_are_rejected(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    //-- Execute Test (Act)
+    let unknown_event = tonic_client
+        .admin()
+        .create_webhook_endpoint(CreateWebhookEndpointRequest {
+            url: "https://hooks.example.com/authentication".to_string(),
+            event_types: vec!["user.deleted".to_string()],
+        })
+        .await
+        .unwrap_err();
+
+    let bad_url = tonic_client
+        .admin()
+        .create_webhook_endpoint(CreateWebhookEndpointRequest {
+            url: "ftp://hooks.example.com".to_string(),
+            event_types: vec![],
+        })
+        .await
+        .unwrap_err();
+
+    //-- Checks (Assertions)
+    assert_eq!(unknown_event.code(), tonic::Code::InvalidArgument);
+    assert_eq!(bad_url.code(), tonic::Code::InvalidArgument);
+
+    Ok(())
+}
@@ -47,6 +47,10 @@ async fn returns_access_refresh_tokens(database: Pool<Postgres>) -> Result<()> {
     let request_message = LoginRequest {
         email: random_user.email.to_string(),
         password: random_password.to_string(),
+        remember_me: false,
+        organization_id: None,
+        captcha_token: None,
+        password_new: None,
     };
 
     // Build tonic request
@@ -114,6 +118,60 @@ async fn returns_access_refresh_tokens(database: Pool<Postgres>) -> Result<()> {
     Ok(())
 }
 
+#[sqlx::test]
+async fn remember_me_extends_session_expiry(database: Pool<Postgres>) -> Result<()> {
+    //-- 1. Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password)?;
+    random_user.is_active = true;
+    random_user.is_verified = true;
+    let _database_record = random_user.insert(&database).await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    //-- 2. Execute Test (Act)
+    let mut expiries = Vec::new();
+    for remember_me in [false, true] {
+        let request_message = LoginRequest {
+            email: random_user.email.to_string(),
+            password: random_password.to_string(),
+            remember_me,
+            organization_id: None,
+            captcha_token: None,
+            password_new: None,
+        };
+        let response_message = tonic_client
+            .authentication()
+            .login(tonic::Request::new(request_message))
+            .await?
+            .into_inner();
+        expiries.push(response_message.refresh_token_expires_on.parse::<DateTime<Utc>>()?);
+    }
+
+    //-- 3. Checks (Assertions)
+    let application = &tonic_server.config.application;
+    let expected_difference = (application.remember_me_duration_minutes
+        - application.refresh_token_duration_minutes) as i64;
+    let difference = (expiries[1] - expiries[0]).num_minutes();
+
+    // Allow a minute for the time between the two logins
+    assert!((difference - expected_difference).abs() <= 1);
+
+    // The remembered session is the active one in the database
+    let sessions =
+        database::Sessions::index_from_user_id(&random_user.id, &10, &0, &database)
+            .await?;
+    let active = sessions
+        .iter()
+        .find(|session| session.is_active)
+        .ok_or("no active session")?;
+    assert_eq!(active.expires_on, expiries[1]);
+
+    //-- 4. Return Ok
+    Ok(())
+}
+
 #[sqlx::test]
 async fn default_user_login(database: Pool<Postgres>) -> Result<()> {
     //-- 1. Setup and Fixtures (Arrange)
@@ -131,6 +189,7 @@ async fn default_user_login(database: Pool<Postgres>) -> Result<()> {
         is_verified: true,
         created_on: DateTime::parse_from_rfc3339("2019-10-17T00:00:00.000000Z")?
             .with_timezone(&Utc),
+        locale: domain::Locale::default(),
     };
 
     // Spawn Tonic test server
@@ -143,6 +202,10 @@ async fn default_user_login(database: Pool<Postgres>) -> Result<()> {
     let request_message = LoginRequest {
         email: default_user.email.to_string(),
         password: default_password,
+        remember_me: false,
+        organization_id: None,
+        captcha_token: None,
+        password_new: None,
     };
 
     // Build tonic request
@@ -231,6 +294,10 @@ async fn incorrect_password_returns_error(database: Pool<Postgres>) -> Result<()
     let request_message = LoginRequest {
         email: random_user.email.to_string(),
         password: incorrect_password,
+        remember_me: false,
+        organization_id: None,
+        captcha_token: None,
+        password_new: None,
     };
 
     // Build tonic request
@@ -277,6 +344,10 @@ async fn incorrect_email_returns_error(database: Pool<Postgres>) -> Result<()> {
     let request_message = LoginRequest {
         email: incorrect_email,
         password: random_password,
+        remember_me: false,
+        organization_id: None,
+        captcha_token: None,
+        password_new: None,
     };
 
     // Build tonic request
@@ -299,3 +370,224 @@ async fn incorrect_email_returns_error(database: Pool<Postgres>) -> Result<()> {
 
     Ok(())
 }
+
+#[sqlx::test]
+async fn organization_login_scopes_token_and_session(database: Pool<Postgres>) -> Result<()> {
+    //-- 1. Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password)?;
+    random_user.is_active = true;
+    random_user.is_verified = true;
+    let random_user = random_user.insert(&database).await?;
+
+    let organization = database::Organizations::new("Acme Corp", "acme-corp")?
+        .insert(&database)
+        .await?;
+    database::OrganizationMembers::new(
+        &organization.id,
+        &random_user.id,
+        &domain::UserRole::User,
+    )
+    .insert(&database)
+    .await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    //-- 2. Execute Test (Act)
+    let request_message = LoginRequest {
+        email: random_user.email.to_string(),
+        password: random_password.to_string(),
+        remember_me: false,
+        organization_id: Some(organization.id.to_string()),
+        captcha_token: None,
+        password_new: None,
+    };
+    let (response_metadata, response_message, _response_extensions) = tonic_client
+        .authentication()
+        .login(tonic::Request::new(request_message))
+        .await?
+        .into_parts();
+
+    //-- 3. Checks (Assertions)
+    let access_token_claim = domain::TokenClaim::parse(
+        &response_message.access_token,
+        &tonic_server.config.application.token_secret,
+        &tonic_server.config.application.get_issuer(),
+    )?;
+    assert_eq!(access_token_claim.organization_id()?, Some(organization.id));
+
+    let set_cookie = response_metadata.get("set-cookie").unwrap().to_str()?;
+    let refresh_token = Cookie::parse(set_cookie)?.value().to_string();
+    let session = database::Sessions::from_token(&refresh_token, &database).await?;
+    assert_eq!(session.organization_id, Some(organization.id));
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn organization_login_requires_membership(database: Pool<Postgres>) -> Result<()> {
+    //-- 1. Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password)?;
+    random_user.is_active = true;
+    random_user.is_verified = true;
+    let random_user = random_user.insert(&database).await?;
+
+    let organization = database::Organizations::new("Acme Corp", "acme-corp")?
+        .insert(&database)
+        .await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    //-- 2. Execute Test (Act)
+    let request_message = LoginRequest {
+        email: random_user.email.to_string(),
+        password: random_password.to_string(),
+        remember_me: false,
+        organization_id: Some(organization.id.to_string()),
+        captcha_token: None,
+        password_new: None,
+    };
+    let response = tonic_client
+        .authentication()
+        .login(tonic::Request::new(request_message))
+        .await
+        .unwrap_err();
+
+    //-- 3. Checks (Assertions)
+    assert_eq!(response.code(), Code::Unauthenticated);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn locked_out_login_returns_retry_after(database: Pool<Postgres>) -> Result<()> {
+    //-- 1. Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password)?;
+    random_user.is_active = true;
+    random_user.is_verified = true;
+    let random_user = random_user.insert(&database).await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    // Fail once so the throttle is recorded for the client address, then
+    // lock it out
+    let request_message = LoginRequest {
+        email: random_user.email.to_string(),
+        password: helpers::mocks::password()?,
+        remember_me: false,
+        organization_id: None,
+        captcha_token: None,
+        password_new: None,
+    };
+    let failed = tonic_client
+        .authentication()
+        .login(tonic::Request::new(request_message))
+        .await
+        .unwrap_err();
+    assert_eq!(failed.code(), Code::Unauthenticated);
+
+    sqlx::query("UPDATE login_throttles SET locked_until = NOW() + INTERVAL '60 seconds'")
+        .execute(&database)
+        .await?;
+
+    //-- 2. Execute Test (Act)
+    // The correct password is still rejected while locked out
+    let request_message = LoginRequest {
+        email: random_user.email.to_string(),
+        password: random_password.to_string(),
+        remember_me: false,
+        organization_id: None,
+        captcha_token: None,
+        password_new: None,
+    };
+    let response = tonic_client
+        .authentication()
+        .login(tonic::Request::new(request_message))
+        .await
+        .unwrap_err();
+
+    //-- 3. Checks (Assertions)
+    assert_eq!(response.code(), Code::ResourceExhausted);
+    let retry_after: u64 = response
+        .metadata()
+        .get("retry-after")
+        .unwrap()
+        .to_str()?
+        .parse()?;
+    assert!(retry_after > 0 && retry_after <= 60);
+    assert_eq!(
+        response.metadata().get("x-ratelimit-remaining").unwrap(),
+        "0"
+    );
+    assert!(failed.metadata().get("x-ratelimit-limit").is_some());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[sqlx::test]
+async fn login_over_unix_socket_records_loopback(database: Pool<Postgres>) -> Result<()> {
+    use authentication_service::configuration::ListenerConfiguration;
+    use authentication_service::rpc::proto::authentication_service_client::AuthenticationServiceClient;
+    use tonic::transport::{Endpoint, Uri};
+
+    //-- 1. Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password)?;
+    random_user.is_active = true;
+    random_user.is_verified = true;
+    let random_user = random_user.insert(&database).await?;
+
+    // Serve authentication on a socket, where requests have no peer address
+    let socket = std::env::temp_dir().join(format!("authentication-{}.sock", Uuid::now_v7()));
+    let unix_socket = socket.to_string_lossy().to_string();
+    let _tonic_server = helpers::TonicServer::spawn_server_with(&database, |config| {
+        config.listeners = vec![ListenerConfiguration {
+            name: "sidecar".to_string(),
+            address: None,
+            unix_socket: Some(unix_socket),
+            services: vec!["authentication".to_string()],
+        }];
+    })
+    .await?;
+
+    let socket_channel = Endpoint::try_from("http://localhost")?
+        .connect_with_connector(tower::service_fn(move |_uri: Uri| {
+            let socket = socket.clone();
+            async move {
+                let stream = tokio::net::UnixStream::connect(socket).await?;
+                Ok::<_, std::io::Error>(hyper_util::rt::TokioIo::new(stream))
+            }
+        }))
+        .await?;
+    let mut tonic_socket_client = AuthenticationServiceClient::new(socket_channel);
+
+    //-- 2. Execute Test (Act)
+    let request_message = LoginRequest {
+        email: random_user.email.to_string(),
+        password: random_password.to_string(),
+        remember_me: false,
+        organization_id: None,
+        captcha_token: None,
+        password_new: None,
+    };
+    let response_message = tonic_socket_client
+        .login(tonic::Request::new(request_message))
+        .await?
+        .into_inner();
+
+    //-- 3. Checks (Assertions)
+    assert!(!response_message.access_token.is_empty());
+
+    let logins =
+        database::Logins::index_user(&random_user.id, &10, None, None, &database).await?;
+    assert_eq!(logins.len(), 1);
+    assert_eq!(logins[0].ip_address, "127.0.0.1");
+
+    Ok(())
+}
@@ -42,6 +42,10 @@ async fn returns_logout_true(database: Pool<Postgres>) -> Result<()> {
     let auth_message = LoginRequest {
         email: random_user.email.to_string(),
         password: random_password.to_string(),
+        remember_me: false,
+        organization_id: None,
+        captcha_token: None,
+        password_new: None,
     };
 
     // Build tonic request
@@ -140,7 +144,7 @@ async fn incorrect_refresh_token_is_unauthorised(
 
     // Build the incorrect Refresh Token cookie for fail authentication
     let incorrect_refresh_cookie = incorrect_refresh_token
-        .build_cookie(&tonic_server.address, &random_duration);
+        .build_cookie(&tonic_server.config.refresh_cookie, &tonic_server.address, &random_duration);
 
     // Build tonic request message
     let request_message = Empty {};
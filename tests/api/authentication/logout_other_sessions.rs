@@ -0,0 +1,104 @@
+// #![allow(unused)] // For development only
+
+use cookie::Cookie;
+use http::header::COOKIE;
+use http::HeaderMap;
+use sqlx::{Pool, Postgres};
+use tonic::metadata::MetadataMap;
+use tonic::Request;
+
+use authentication_service::database;
+use authentication_service::rpc::proto::{Empty, LoginRequest};
+
+use crate::helpers;
+
+pub type Error = Box<dyn std::error::Error>;
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// Log in and return the refresh token value and its stripped cookie string
+async fn login(
+    tonic_client: &mut helpers::TonicClient,
+    email: &str,
+    password: &str,
+) -> Result<(String, String)> {
+    let request_message = LoginRequest {
+        email: email.to_string(),
+        password: password.to_string(),
+        remember_me: false,
+        organization_id: None,
+        captcha_token: None,
+        password_new: None,
+    };
+    let (response_metadata, _response_message, _response_extensions) = tonic_client
+        .authentication()
+        .login(Request::new(request_message))
+        .await?
+        .into_parts();
+
+    let set_cookie = response_metadata.get("set-cookie").unwrap().to_str()?;
+    let cookie = Cookie::parse(set_cookie)?;
+
+    Ok((cookie.value().to_string(), cookie.stripped().to_string()))
+}
+
+#[sqlx::test]
+async fn revokes_other_sessions_and_keeps_current(database: Pool<Postgres>) -> Result<()> {
+    //-- 1. Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password)?;
+    random_user.is_active = true;
+    random_user.is_verified = true;
+    let _database_record = random_user.insert(&database).await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    // Log in from two devices
+    let email = random_user.email.to_string();
+    let password = random_password.to_string();
+    let (current_token, current_cookie) = login(&mut tonic_client, &email, &password).await?;
+    let (other_token, _other_cookie) = login(&mut tonic_client, &email, &password).await?;
+
+    //-- 2. Execute Test (Act)
+    let mut request = Request::new(Empty {});
+    let mut http_header = HeaderMap::new();
+    http_header.insert(COOKIE, current_cookie.parse().unwrap());
+    *request.metadata_mut() = MetadataMap::from_headers(http_header);
+
+    let response_message = tonic_client
+        .authentication()
+        .logout_other_sessions(request)
+        .await?
+        .into_inner();
+
+    //-- 3. Checks (Assertions)
+    assert!(response_message.success);
+    assert_eq!(response_message.sessions_revoked, 1);
+
+    let current = database::Sessions::from_token(&current_token, &database).await?;
+    assert!(current.is_active);
+
+    let other = database::Sessions::from_token(&other_token, &database).await?;
+    assert!(!other.is_active);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn missing_refresh_token_is_unauthorised(database: Pool<Postgres>) -> Result<()> {
+    //-- 1. Setup and Fixtures (Arrange)
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    //-- 2. Execute Test (Act)
+    let response = tonic_client
+        .authentication()
+        .logout_other_sessions(Request::new(Empty {}))
+        .await
+        .unwrap_err();
+
+    //-- 3. Checks (Assertions)
+    assert_eq!(response.code(), tonic::Code::Unauthenticated);
+
+    Ok(())
+}
@@ -0,0 +1,144 @@
+// #![allow(unused)] // For development only
+
+use sqlx::{Pool, Postgres};
+use tonic::{Code, Request};
+
+use authentication_service::domain;
+use authentication_service::rpc::proto::{CompleteMagicLinkRequest, RequestMagicLinkRequest};
+
+use crate::helpers;
+
+pub type Error = Box<dyn std::error::Error>;
+pub type Result<T> = core::result::Result<T, Error>;
+
+fn request_magic_link_request(email: &str) -> RequestMagicLinkRequest {
+    RequestMagicLinkRequest {
+        email: email.to_string(),
+        captcha_token: None,
+    }
+}
+
+fn complete_magic_link_request(token: &str) -> CompleteMagicLinkRequest {
+    CompleteMagicLinkRequest {
+        token: token.to_string(),
+        remember_me: false,
+        organization_id: None,
+    }
+}
+
+#[sqlx::test]
+async fn magic_link_logs_in_once(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password)?;
+    random_user.is_active = true;
+    let random_user = random_user.insert(&database).await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server_with(&database, |config| {
+        config.magic_link.enabled = true;
+    })
+    .await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    //-- Execute Function (Act)
+    tonic_client
+        .authentication()
+        .request_magic_link(Request::new(request_magic_link_request(
+            random_user.email.as_ref(),
+        )))
+        .await?;
+    tonic_server
+        .email
+        .wait_for_sent(1, std::time::Duration::from_secs(10))
+        .await?;
+    let token = tonic_server
+        .email
+        .last_magic_link_token(&random_user.email)
+        .ok_or("no magic link emailed")?;
+
+    let response_message = tonic_client
+        .authentication()
+        .complete_magic_link(Request::new(complete_magic_link_request(&token)))
+        .await?
+        .into_inner();
+    let reused = tonic_client
+        .authentication()
+        .complete_magic_link(Request::new(complete_magic_link_request(&token)))
+        .await;
+
+    //-- Checks (Assertions)
+    let access_token_claim = domain::TokenClaim::parse(
+        &response_message.access_token,
+        &tonic_server.config.application.token_secret,
+        &tonic_server.config.application.get_issuer(),
+    )?;
+    assert_eq!(access_token_claim.sub, random_user.id.to_string());
+
+    let status = reused.expect_err("a magic link should only work once");
+    assert_eq!(status.code(), Code::Unauthenticated);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn unknown_email_gets_the_same_response(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password)?;
+    random_user.is_active = true;
+    let random_user = random_user.insert(&database).await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server_with(&database, |config| {
+        config.magic_link.enabled = true;
+    })
+    .await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    //-- Execute Function (Act)
+    let known_response = tonic_client
+        .authentication()
+        .request_magic_link(Request::new(request_magic_link_request(
+            random_user.email.as_ref(),
+        )))
+        .await?
+        .into_inner();
+    let unknown_response = tonic_client
+        .authentication()
+        .request_magic_link(Request::new(request_magic_link_request(
+            "unknown.user@example.com",
+        )))
+        .await?
+        .into_inner();
+    tonic_server
+        .email
+        .wait_for_sent(1, std::time::Duration::from_secs(10))
+        .await?;
+
+    //-- Checks (Assertions)
+    assert_eq!(known_response, unknown_response);
+    assert_eq!(tonic_server.email.sent().len(), 1);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn magic_links_are_rejected_when_disabled(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    //-- Execute Function (Act)
+    let response = tonic_client
+        .authentication()
+        .request_magic_link(Request::new(request_magic_link_request(
+            "new.user@example.com",
+        )))
+        .await;
+
+    //-- Checks (Assertions)
+    let status = response.expect_err("magic links should be disabled by default");
+    assert_eq!(status.code(), Code::Unimplemented);
+    assert!(tonic_server.email.sent().is_empty());
+
+    Ok(())
+}
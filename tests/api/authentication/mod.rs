@@ -4,5 +4,9 @@ mod login;
 mod refresh;
 mod update_password;
 mod logout;
-
+mod logout_other_sessions;
+mod register;
+mod magic_link;
+mod request_password_reset;
+mod passkeys;
 
@@ -0,0 +1,173 @@
+// #![allow(unused)] // For development only
+
+use chrono::Utc;
+use sqlx::{Pool, Postgres};
+use tonic::{Code, Request};
+use uuid::Uuid;
+
+use authentication_service::configuration::PasskeyMode;
+use authentication_service::database;
+use authentication_service::rpc::proto::{BeginPasskeyLoginRequest, Empty, LoginRequest};
+
+use crate::helpers;
+
+pub type Error = Box<dyn std::error::Error>;
+pub type Result<T> = core::result::Result<T, Error>;
+
+fn begin_passkey_login_request(email: &str, password: Option<&str>) -> BeginPasskeyLoginRequest {
+    BeginPasskeyLoginRequest {
+        email: email.to_string(),
+        password: password.map(str::to_string),
+        captcha_token: None,
+    }
+}
+
+/// A stored passkey for the user, enough to mark them as having one
+async fn insert_passkey(database: &Pool<Postgres>, user_id: &Uuid) -> Result<()> {
+    database::WebauthnCredentials {
+        id: Uuid::now_v7(),
+        user_id: user_id.to_owned(),
+        credential_id: Uuid::now_v7().simple().to_string(),
+        name: "Test passkey".to_string(),
+        passkey: "{}".to_string(),
+        created_on: Utc::now(),
+        last_used_on: None,
+    }
+    .insert(database)
+    .await?;
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn passkeys_are_rejected_when_disabled(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    //-- Execute Function (Act)
+    let login = tonic_client
+        .authentication()
+        .begin_passkey_login(Request::new(begin_passkey_login_request(
+            "new.user@example.com",
+            None,
+        )))
+        .await;
+    let registration = tonic_client
+        .users()
+        .begin_passkey_registration(Request::new(Empty {}))
+        .await;
+
+    //-- Checks (Assertions)
+    let status = login.expect_err("passkeys should be disabled by default");
+    assert_eq!(status.code(), Code::Unimplemented);
+    let status = registration.expect_err("passkeys should be disabled by default");
+    assert_eq!(status.code(), Code::Unimplemented);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn registration_returns_creation_options(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let tonic_server = helpers::TonicServer::spawn_server_with(&database, |config| {
+        config.passkeys.enabled = true;
+    })
+    .await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    //-- Execute Function (Act)
+    let response_message = tonic_client
+        .users()
+        .begin_passkey_registration(Request::new(Empty {}))
+        .await?
+        .into_inner();
+
+    //-- Checks (Assertions)
+    let options: serde_json::Value = serde_json::from_str(&response_message.options)?;
+    assert!(options["publicKey"]["challenge"].is_string());
+    assert_eq!(
+        options["publicKey"]["rp"]["id"],
+        tonic_server.config.passkeys.relying_party_id.as_str()
+    );
+    Uuid::try_parse(&response_message.challenge_id)?;
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn passkey_login_needs_a_registered_passkey(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password)?;
+    random_user.is_active = true;
+    let random_user = random_user.insert(&database).await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server_with(&database, |config| {
+        config.passkeys.enabled = true;
+    })
+    .await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    //-- Execute Function (Act)
+    let response = tonic_client
+        .authentication()
+        .begin_passkey_login(Request::new(begin_passkey_login_request(
+            random_user.email.as_ref(),
+            None,
+        )))
+        .await;
+
+    //-- Checks (Assertions)
+    let status = response.expect_err("a user without a passkey can't use one");
+    assert_eq!(status.code(), Code::Unauthenticated);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn second_factor_passkey_is_required_for_password_login(
+    database: Pool<Postgres>,
+) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password)?;
+    random_user.is_active = true;
+    let random_user = random_user.insert(&database).await?;
+    insert_passkey(&database, &random_user.id).await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server_with(&database, |config| {
+        config.passkeys.enabled = true;
+        config.passkeys.mode = PasskeyMode::SecondFactor;
+    })
+    .await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    //-- Execute Function (Act)
+    let response = tonic_client
+        .authentication()
+        .login(Request::new(LoginRequest {
+            email: random_user.email.to_string(),
+            password: random_password.to_string(),
+            remember_me: false,
+            organization_id: None,
+            captcha_token: None,
+            password_new: None,
+        }))
+        .await;
+    let wrong_password = tonic_client
+        .authentication()
+        .begin_passkey_login(Request::new(begin_passkey_login_request(
+            random_user.email.as_ref(),
+            Some("wrong-password"),
+        )))
+        .await;
+
+    //-- Checks (Assertions)
+    let status = response.expect_err("the passkey is needed as well as the password");
+    assert_eq!(status.code(), Code::FailedPrecondition);
+    let status = wrong_password.expect_err("the password is checked before the passkey");
+    assert_eq!(status.code(), Code::Unauthenticated);
+
+    Ok(())
+}
@@ -44,6 +44,10 @@ async fn returns_access_refresh_access(database: Pool<Postgres>) -> Result<()> {
     let auth_message = LoginRequest {
         email: random_user.email.to_string(),
         password: random_password.to_string(),
+        remember_me: false,
+        organization_id: None,
+        captcha_token: None,
+        password_new: None,
     };
 
     // Build tonic request
@@ -189,7 +193,7 @@ async fn incorrect_refresh_token_is_unauthorised(database: Pool<Postgres>) -> Re
     )?;
 
     // Build the incorrect Refresh Token cookie for fail authentication
-    let incorrect_refresh_cookie = incorrect_refresh_token.build_cookie(&tonic_server.address, &random_duration);
+    let incorrect_refresh_cookie = incorrect_refresh_token.build_cookie(&tonic_server.config.refresh_cookie, &tonic_server.address, &random_duration);
 
     // Build tonic request message
     let request_message = Empty {};
@@ -223,4 +227,166 @@ async fn incorrect_refresh_token_is_unauthorised(database: Pool<Postgres>) -> Re
 
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[sqlx::test]
+async fn expired_session_is_unauthorised(database: Pool<Postgres>) -> Result<()> {
+    //-- 1. Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password)?;
+    random_user.is_active = true;
+    random_user.is_verified = true;
+    let _database_record = random_user.insert(&database).await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    let auth_message = LoginRequest {
+        email: random_user.email.to_string(),
+        password: random_password.to_string(),
+        remember_me: false,
+        organization_id: None,
+        captcha_token: None,
+        password_new: None,
+    };
+    let response_metadata = tonic_client
+        .authentication()
+        .login(tonic::Request::new(auth_message))
+        .await?
+        .into_parts()
+        .0;
+    let set_cookie = response_metadata.get("set-cookie").unwrap().to_str()?;
+    let refresh_cookie = Cookie::parse(set_cookie)?.stripped().to_string();
+
+    // Expire the session in the database while the refresh token is still valid
+    sqlx::query("UPDATE sessions SET expires_on = NOW() - INTERVAL '1 minute' WHERE user_id = $1")
+        .bind(random_user.id)
+        .execute(&database)
+        .await?;
+
+    //-- 2. Execute Test (Act)
+    let mut request = Request::new(Empty {});
+    let mut http_header = HeaderMap::new();
+    http_header.insert(COOKIE, refresh_cookie.parse().unwrap());
+    *request.metadata_mut() = MetadataMap::from_headers(http_header);
+
+    let refresh_response = tonic_client
+        .authentication()
+        .refresh(request)
+        .await
+        .unwrap_err();
+
+    //-- 3. Checks (Assertions)
+    assert_eq!(refresh_response.code(), tonic::Code::Unauthenticated);
+    assert_eq!(refresh_response.message(), "Authentication Failed!");
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn idle_session_is_unauthorised(database: Pool<Postgres>) -> Result<()> {
+    //-- 1. Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password)?;
+    random_user.is_active = true;
+    random_user.is_verified = true;
+    let _database_record = random_user.insert(&database).await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server_with(&database, |config| {
+        config.application.idle_session_timeout_days = 7;
+    })
+    .await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    let auth_message = LoginRequest {
+        email: random_user.email.to_string(),
+        password: random_password.to_string(),
+        remember_me: true,
+        organization_id: None,
+        captcha_token: None,
+        password_new: None,
+    };
+    let response_metadata = tonic_client
+        .authentication()
+        .login(tonic::Request::new(auth_message))
+        .await?
+        .into_parts()
+        .0;
+    let set_cookie = response_metadata.get("set-cookie").unwrap().to_str()?;
+    let refresh_cookie = Cookie::parse(set_cookie)?.stripped().to_string();
+
+    // Leave the unexpired session unused for longer than the idle timeout
+    sqlx::query("UPDATE sessions SET logged_in_at = NOW() - INTERVAL '8 days' WHERE user_id = $1")
+        .bind(random_user.id)
+        .execute(&database)
+        .await?;
+
+    //-- 2. Execute Test (Act)
+    let mut request = Request::new(Empty {});
+    let mut http_header = HeaderMap::new();
+    http_header.insert(COOKIE, refresh_cookie.parse().unwrap());
+    *request.metadata_mut() = MetadataMap::from_headers(http_header);
+
+    let refresh_response = tonic_client
+        .authentication()
+        .refresh(request)
+        .await
+        .unwrap_err();
+
+    //-- 3. Checks (Assertions)
+    assert_eq!(refresh_response.code(), tonic::Code::Unauthenticated);
+    assert_eq!(refresh_response.message(), "Authentication Failed!");
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn refresh_token_is_accepted_as_bearer_metadata(database: Pool<Postgres>) -> Result<()> {
+    //-- 1. Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password)?;
+    random_user.is_active = true;
+    random_user.is_verified = true;
+    let _database_record = random_user.insert(&database).await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server_with(&database, |config| {
+        config.refresh_token.accept_bearer = true;
+    })
+    .await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    let auth_message = LoginRequest {
+        email: random_user.email.to_string(),
+        password: random_password.to_string(),
+        remember_me: false,
+        organization_id: None,
+        captcha_token: None,
+        password_new: None,
+    };
+    let response_metadata = tonic_client
+        .authentication()
+        .login(tonic::Request::new(auth_message))
+        .await?
+        .into_parts()
+        .0;
+    let set_cookie = response_metadata.get("set-cookie").unwrap().to_str()?;
+    let refresh_token = Cookie::parse(set_cookie)?.value().to_string();
+
+    //-- 2. Execute Test (Act)
+    // Send the refresh token as a mobile or CLI client would, without cookies
+    let mut request = Request::new(Empty {});
+    request
+        .metadata_mut()
+        .insert("authorization", format!("Bearer {refresh_token}").parse()?);
+
+    let refresh_response = tonic_client
+        .authentication()
+        .refresh(request)
+        .await?
+        .into_inner();
+
+    //-- 3. Checks (Assertions)
+    assert!(!refresh_response.access_token.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+// #![allow(unused)] // For development only
+
+use secrecy::SecretString;
+use sqlx::{Pool, Postgres};
+use tonic::Request;
+
+use authentication_service::configuration::RegistrationMode;
+use authentication_service::rpc::proto::RegisterRequest;
+use authentication_service::{database, domain};
+
+use crate::helpers;
+
+pub type Error = Box<dyn std::error::Error>;
+pub type Result<T> = core::result::Result<T, Error>;
+
+fn register_request(email: &str, password: &str) -> RegisterRequest {
+    RegisterRequest {
+        email: email.to_string(),
+        name: "Registered User".to_string(),
+        password: password.to_string(),
+        locale: None,
+        captcha_token: None,
+    }
+}
+
+#[sqlx::test]
+async fn registers_an_unverified_user(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+    let email = "new.user@example.com";
+    let password = helpers::mocks::password()?;
+
+    //-- Execute Function (Act)
+    let response_message = tonic_client
+        .authentication()
+        .register(Request::new(register_request(email, &password)))
+        .await?
+        .into_inner();
+
+    //-- Checks (Assertions)
+    assert!(response_message.success);
+
+    let user = database::Users::from_user_email(
+        &domain::EmailAddress::parse(email)?,
+        &database,
+    )
+    .await?;
+    assert_eq!(user.role, domain::UserRole::User);
+    assert!(user.is_active);
+    assert!(!user.is_verified);
+    assert!(user
+        .password_hash
+        .verify_password(&SecretString::from(password))?);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn existing_email_gets_the_same_response_and_alerts_the_owner(
+    database: Pool<Postgres>,
+) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let random_user = helpers::mocks::users(&random_password)?;
+    let random_user = random_user.insert(&database).await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+    let password = helpers::mocks::password()?;
+
+    //-- Execute Function (Act)
+    let new_response = tonic_client
+        .authentication()
+        .register(Request::new(register_request(
+            "new.user@example.com",
+            &password,
+        )))
+        .await?
+        .into_inner();
+    let existing_response = tonic_client
+        .authentication()
+        .register(Request::new(register_request(
+            random_user.email.as_ref(),
+            &password,
+        )))
+        .await?
+        .into_inner();
+    tonic_server
+        .email
+        .wait_for_sent(1, std::time::Duration::from_secs(10))
+        .await?;
+
+    //-- Checks (Assertions)
+    assert_eq!(new_response, existing_response);
+
+    // The owner is told, and their password is unchanged
+    let alerts = tonic_server.email.sent_to(&random_user.email);
+    assert_eq!(alerts.len(), 1);
+    assert!(alerts[0].body_text.contains("registration attempt"));
+
+    let user = database::Users::from_user_id(&random_user.id, &database).await?;
+    assert_eq!(user.password_hash, random_user.password_hash);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn existing_email_is_rejected_in_friendly_mode(
+    database: Pool<Postgres>,
+) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let random_user = helpers::mocks::users(&random_password)?;
+    let random_user = random_user.insert(&database).await?;
+
+    let tonic_server =
+        helpers::TonicServer::spawn_server_with(&database, |config| {
+            config.application.registration_mode = RegistrationMode::Friendly;
+        })
+        .await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    //-- Execute Function (Act)
+    let response = tonic_client
+        .authentication()
+        .register(Request::new(register_request(
+            random_user.email.as_ref(),
+            &random_password,
+        )))
+        .await;
+
+    //-- Checks (Assertions)
+    let status = response.expect_err("registering a taken email should fail");
+    assert_eq!(status.code(), tonic::Code::AlreadyExists);
+    assert!(tonic_server.email.sent_to(&random_user.email).is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,138 @@
+// #![allow(unused)] // For development only
+
+use sqlx::{Pool, Postgres};
+use tonic::Request;
+
+use authentication_service::database;
+use authentication_service::rpc::proto::RequestPasswordResetRequest;
+
+use crate::helpers;
+
+pub type Error = Box<dyn std::error::Error>;
+pub type Result<T> = core::result::Result<T, Error>;
+
+fn request_password_reset_request(email: &str) -> RequestPasswordResetRequest {
+    RequestPasswordResetRequest {
+        email: email.to_string(),
+        captcha_token: None,
+    }
+}
+
+#[sqlx::test]
+async fn only_active_users_are_sent_a_reset(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let mut active_user = helpers::mocks::users(&random_password)?;
+    active_user.is_active = true;
+    let active_user = active_user.insert(&database).await?;
+    let mut inactive_user = helpers::mocks::users(&random_password)?;
+    inactive_user.is_active = false;
+    let inactive_user = inactive_user.insert(&database).await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    //-- Execute Function (Act)
+    let mut responses = Vec::new();
+    for email in [
+        active_user.email.as_ref(),
+        inactive_user.email.as_ref(),
+        "unknown.user@example.com",
+    ] {
+        let response = tonic_client
+            .authentication()
+            .request_password_reset(Request::new(request_password_reset_request(email)))
+            .await?
+            .into_inner();
+        responses.push(response);
+    }
+    tonic_server
+        .email
+        .wait_for_sent(1, std::time::Duration::from_secs(10))
+        .await?;
+
+    //-- Checks (Assertions)
+    assert!(responses[0].success);
+    assert_eq!(responses[0], responses[1]);
+    assert_eq!(responses[0], responses[2]);
+    assert_eq!(tonic_server.email.sent().len(), 1);
+    assert!(tonic_server
+        .email
+        .last_magic_link_token(&active_user.email)
+        .is_some());
+    assert!(
+        database::PasswordResets::unused_for_user(&inactive_user.id, &database)
+            .await?
+            .is_empty()
+    );
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn a_new_reset_invalidates_the_last(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password)?;
+    random_user.is_active = true;
+    let random_user = random_user.insert(&database).await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    //-- Execute Function (Act)
+    for _ in 0..2 {
+        tonic_client
+            .authentication()
+            .request_password_reset(Request::new(request_password_reset_request(
+                random_user.email.as_ref(),
+            )))
+            .await?;
+    }
+
+    //-- Checks (Assertions)
+    let unused = database::PasswordResets::unused_for_user(&random_user.id, &database).await?;
+    assert_eq!(unused.len(), 1);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn requests_over_the_limit_are_ignored(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password)?;
+    random_user.is_active = true;
+    let random_user = random_user.insert(&database).await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server_with(&database, |config| {
+        config.password_reset.max_requests_per_email = 1;
+    })
+    .await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    //-- Execute Function (Act)
+    let mut responses = Vec::new();
+    for _ in 0..2 {
+        let response = tonic_client
+            .authentication()
+            .request_password_reset(Request::new(request_password_reset_request(
+                random_user.email.as_ref(),
+            )))
+            .await?
+            .into_inner();
+        responses.push(response);
+    }
+    tonic_server
+        .email
+        .wait_for_sent(1, std::time::Duration::from_secs(10))
+        .await?;
+
+    //-- Checks (Assertions)
+    assert_eq!(responses[0], responses[1]);
+    assert_eq!(tonic_server.email.sent().len(), 1);
+    let unused = database::PasswordResets::unused_for_user(&random_user.id, &database).await?;
+    assert_eq!(unused.len(), 1);
+
+    Ok(())
+}
@@ -4,7 +4,7 @@ use cookie::Cookie;
 use sqlx::{Pool, Postgres};
 // use uuid::Uuid;
 
-// use authentication_service::domain;
+use authentication_service::{database, domain};
 use authentication_service::rpc::proto::{LoginRequest, UpdatePasswordRequest};
 
 use crate::helpers;
@@ -32,6 +32,10 @@ async fn returns_access_refresh_access(database: Pool<Postgres>) -> Result<()> {
     let login_request_message = LoginRequest {
         email: random_user.email.to_string(),
         password: random_password_original.to_string(),
+        remember_me: false,
+        organization_id: None,
+        captcha_token: None,
+        password_new: None,
     };
     // println!("{login_request_message:#?}");
 
@@ -101,6 +105,10 @@ async fn incorrect_original_password_returns_unauthenticated(database: Pool<Post
     let login_request_message = LoginRequest {
         email: random_user.email.to_string(),
         password: random_password_original.to_string(),
+        remember_me: false,
+        organization_id: None,
+        captcha_token: None,
+        password_new: None,
     };
     // println!("{request_message:#?}");
 
@@ -172,6 +180,10 @@ async fn bad_new_password(database: Pool<Postgres>) -> Result<()> {
     let login_request_message = LoginRequest {
         email: random_user.email.to_string(),
         password: random_password_original.to_string(),
+        remember_me: false,
+        organization_id: None,
+        captcha_token: None,
+        password_new: None,
     };
     // println!("{request_message:#?}");
 
@@ -218,4 +230,88 @@ async fn bad_new_password(database: Pool<Postgres>) -> Result<()> {
     // Confirm Tonic response message
     // assert_eq!(update_password_response.message(), "Authentication Failed!");
     Ok(())
-}
\ No newline at end of file
+}
+#[sqlx::test]
+async fn revokes_other_sessions_and_denies_their_access_tokens(
+    database: Pool<Postgres>,
+) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let random_password_original = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password_original)?;
+    random_user.is_active = true;
+    random_user.is_verified = true;
+    let _database_record = random_user.insert(&database).await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    // Log in from two devices, the second is the one changing the password
+    let mut access_tokens = Vec::new();
+    for _device in 0..2 {
+        let login_request_message = LoginRequest {
+            email: random_user.email.to_string(),
+            password: random_password_original.to_string(),
+            remember_me: false,
+            organization_id: None,
+            captcha_token: None,
+            password_new: None,
+        };
+        let login_response_message = tonic_client
+            .authentication()
+            .login(tonic::Request::new(login_request_message))
+            .await?
+            .into_inner();
+        access_tokens.push(login_response_message.access_token);
+    }
+
+    // Login revokes earlier sessions, so make the first device's session active again
+    let token_secret = &tonic_server.config.application.token_secret;
+    let issuer = &tonic_server.config.application.get_issuer();
+    let other_claim = domain::TokenClaim::parse(&access_tokens[0], token_secret, issuer)?;
+    let current_claim = domain::TokenClaim::parse(&access_tokens[1], token_secret, issuer)?;
+    let other_session =
+        database::Sessions::from_access_token_id(&other_claim.jti, &database).await?;
+    sqlx::query("UPDATE sessions SET is_active = true WHERE id = $1")
+        .bind(other_session.id)
+        .execute(&database)
+        .await?;
+
+    //-- Execute Test (Act)
+    let update_password_request_message = UpdatePasswordRequest {
+        email: random_user.email.to_string(),
+        password_original: random_password_original.to_string(),
+        password_new: helpers::mocks::password()?.to_string(),
+    };
+    let access_cookie = Cookie::new("access_token", access_tokens[1].clone()).to_string();
+    let mut update_password_request = tonic::Request::new(update_password_request_message);
+    update_password_request
+        .metadata_mut()
+        .insert("cookie", access_cookie.parse().unwrap());
+
+    let response = tonic_client
+        .authentication()
+        .update_password(update_password_request)
+        .await?
+        .into_inner();
+
+    //-- Checks (Assertions)
+    assert!(response.success);
+
+    // The current session is kept, the other session is revoked
+    let current_session =
+        database::Sessions::from_access_token_id(&current_claim.jti, &database).await?;
+    assert!(current_session.is_active);
+    let other_session = database::Sessions::from_id(&other_session.id, &database).await?;
+    assert!(!other_session.is_active);
+
+    // Only the other session's access token is denied
+    let denied: Vec<String> = database::AccessTokenDenylist::index_unexpired(&database)
+        .await?
+        .into_iter()
+        .map(|entry| entry.jti)
+        .collect();
+    assert!(denied.contains(&other_claim.jti));
+    assert!(!denied.contains(&current_claim.jti));
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+//-- ./tests/api/client.rs
+
+//! Module for testing the Rust client, built with the `client` feature
+//!
+//! * Log in, keeping the access and refresh tokens
+//! * Call with the access token, refreshing it when it is rejected
+//! * Log out, forgetting the tokens
+
+// #![allow(unused)] // For beginning only.
+
+use sqlx::{Pool, Postgres};
+
+use authentication_service::client::AuthClient;
+use authentication_service::rpc::proto::{Empty, LoginRequest};
+
+use crate::helpers;
+
+pub type Error = Box<dyn std::error::Error>;
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[sqlx::test]
+async fn client_logs_in_refreshes_and_logs_out(database: Pool<Postgres>) -> Result<()> {
+	//-- Setup and Fixtures (Arrange)
+	let app = helpers::TestApp::builder(&database)
+		.with_user(helpers::UserSeed::user())
+		.build()
+		.await?;
+	let user = &app.users[0];
+	let client = AuthClient::connect(app.server.address.clone())
+		.await?
+		.with_refresh_cookie_name(app.server.config.refresh_cookie.name.clone());
+
+	//-- Execute Test (Act)
+	client
+		.login(LoginRequest {
+			email: user.user.email.to_string(),
+			password: user.password.clone(),
+			..Default::default()
+		})
+		.await?;
+	let refresh_token = client.refresh_token();
+
+	// An unusable access token is refreshed, then the call is made again
+	let client = client.with_tokens(Some("expired".to_string()), refresh_token.clone());
+	let me = client
+		.authorised(|| async { client.users().get_me(Empty {}).await })
+		.await?;
+	let refreshed_access_token = client.access_token();
+
+	client.logout().await?;
+
+	//-- Checks (Assertions)
+	assert!(refresh_token.is_some());
+	assert_eq!(me.id, user.user.id.to_string());
+	assert_ne!(refreshed_access_token.as_deref(), Some("expired"));
+	assert_eq!(client.access_token(), None);
+	assert_eq!(client.refresh_token(), None);
+
+	Ok(())
+}
@@ -0,0 +1,272 @@
+//-- ./tests/api/helpers/app.rs
+
+// #![allow(unused)] // For beginning only.
+
+//! Test application builder
+//!
+//! Spawns the Tonic test server, seeds users with a chosen role, verified and
+//! active status, mints tokens for them and connects clients that call the
+//! server as any seeded user.
+//!
+//! ```ignore
+//! let app = helpers::TestApp::builder(&database)
+//!     .with_user(helpers::UserSeed::user().unverified())
+//!     .build()
+//!     .await?;
+//! let mut client = app.client_as(&app.users[0]).await?;
+//! ```
+//! ---
+
+use std::time;
+
+use chrono::Utc;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use authentication_service::{database, domain};
+
+use crate::helpers::{mocks, TonicClient, TonicServer};
+
+pub type Error = Box<dyn std::error::Error>;
+
+/// The role and status of a user to seed. Seeds are active and verified
+/// unless changed.
+#[derive(Debug, Clone)]
+pub struct UserSeed {
+    role: domain::UserRole,
+    is_active: bool,
+    is_verified: bool,
+}
+
+impl UserSeed {
+    /// An admin user
+    pub fn admin() -> Self {
+        Self::role(domain::UserRole::Admin)
+    }
+
+    /// A standard user
+    pub fn user() -> Self {
+        Self::role(domain::UserRole::User)
+    }
+
+    /// A guest user
+    pub fn guest() -> Self {
+        Self::role(domain::UserRole::Guest)
+    }
+
+    /// A user with the role
+    pub fn role(role: domain::UserRole) -> Self {
+        Self {
+            role,
+            is_active: true,
+            is_verified: true,
+        }
+    }
+
+    /// Seed the user as not active
+    pub fn inactive(mut self) -> Self {
+        self.is_active = false;
+        self
+    }
+
+    /// Seed the user as not verified
+    pub fn unverified(mut self) -> Self {
+        self.is_verified = false;
+        self
+    }
+}
+
+/// A seeded user and the plain text password it was hashed from
+#[derive(Debug, Clone)]
+pub struct TestUser {
+    pub user: database::Users,
+    pub password: String,
+}
+
+/// Builds a `TestApp`, seeding users before the server starts
+pub struct TestAppBuilder {
+    database: Pool<Postgres>,
+    seeds: Vec<UserSeed>,
+}
+
+impl TestAppBuilder {
+    /// Seed a user
+    pub fn with_user(mut self, seed: UserSeed) -> Self {
+        self.seeds.push(seed);
+        self
+    }
+
+    /// Seed several users
+    pub fn with_users(mut self, seeds: impl IntoIterator<Item = UserSeed>) -> Self {
+        self.seeds.extend(seeds);
+        self
+    }
+
+    /// Seed the users, then spawn the server
+    pub async fn build(self) -> Result<TestApp, Error> {
+        let mut users = Vec::with_capacity(self.seeds.len());
+        for seed in &self.seeds {
+            users.push(seed_user(&self.database, seed).await?);
+        }
+
+        let server = TonicServer::spawn_server(&self.database).await?;
+
+        Ok(TestApp {
+            database: self.database,
+            server,
+            users,
+        })
+    }
+}
+
+/// A running test server, its database and the users seeded into it
+pub struct TestApp {
+    pub database: Pool<Postgres>,
+    pub server: TonicServer,
+    pub users: Vec<TestUser>,
+}
+
+impl TestApp {
+    /// Start building a test application on the test database
+    pub fn builder(database: &Pool<Postgres>) -> TestAppBuilder {
+        TestAppBuilder {
+            database: database.clone(),
+            seeds: Vec::new(),
+        }
+    }
+
+    /// Spawn a test application without seeded users
+    pub async fn spawn(database: &Pool<Postgres>) -> Result<Self, Error> {
+        Self::builder(database).build().await
+    }
+
+    /// Seed another user into the running application
+    pub async fn seed_user(&mut self, seed: UserSeed) -> Result<TestUser, Error> {
+        let user = seed_user(&self.database, &seed).await?;
+        self.users.push(user.clone());
+
+        Ok(user)
+    }
+
+    /// Mint a valid access token for the user
+    pub fn access_token(
+        &self,
+        user: &database::Users,
+    ) -> Result<domain::AccessToken, Error> {
+        let config = &self.server.config.application;
+        let duration = time::Duration::from_secs(
+            config.access_token_duration_minutes * 60,
+        );
+
+        Ok(domain::AccessToken::new(
+            &config.token_secret,
+            &config.get_issuer(),
+            &duration,
+            user,
+        )?)
+    }
+
+    /// Mint a valid refresh token for the user. It is only accepted by the
+    /// server once stored in a session, see `login_session`.
+    pub fn refresh_token(
+        &self,
+        user: &database::Users,
+    ) -> Result<domain::RefreshToken, Error> {
+        let config = &self.server.config.application;
+
+        Ok(domain::RefreshToken::new(
+            &config.token_secret,
+            &config.get_issuer(),
+            &self.refresh_token_duration(),
+            user,
+        )?)
+    }
+
+    /// Insert an active session for the user, holding a fresh refresh token
+    pub async fn login_session(
+        &self,
+        user: &database::Users,
+    ) -> Result<database::Sessions, Error> {
+        let refresh_token = self.refresh_token(user)?;
+        let mut session = mocks::sessions(user, &refresh_token)?;
+        session.logged_in_at = Utc::now();
+        session.expires_on = session.logged_in_at + self.refresh_token_duration();
+        session.is_active = true;
+        session.logged_out_at = None;
+        session.logout_ip = None;
+
+        Ok(session.insert(&self.database).await?)
+    }
+
+    /// A client calling the server as the admin `TonicServer` seeds
+    pub async fn client(&self) -> Result<TonicClient, Error> {
+        TonicClient::spawn_client(&self.server).await
+    }
+
+    /// A client calling the server as the user, with a logged in session
+    pub async fn client_as(&self, user: &TestUser) -> Result<TonicClient, Error> {
+        let access_token = self.access_token(&user.user)?;
+        let session = self.login_session(&user.user).await?;
+
+        TonicClient::spawn_client_as(
+            &self.server,
+            access_token,
+            session.refresh_token,
+        )
+        .await
+    }
+
+    /// A client calling the server as the user, with an access token scoped
+    /// to the organization
+    pub async fn client_in_organization(
+        &self,
+        user: &TestUser,
+        organization_id: &Uuid,
+    ) -> Result<TonicClient, Error> {
+        let config = &self.server.config.application;
+        let duration = time::Duration::from_secs(
+            config.access_token_duration_minutes * 60,
+        );
+        let access_token = domain::AccessToken::new_scoped(
+            &config.token_secret,
+            &config.get_issuer(),
+            &duration,
+            &user.user,
+            Some(organization_id),
+            &config.token_audiences,
+        )?;
+        let session = self.login_session(&user.user).await?;
+
+        TonicClient::spawn_client_as(
+            &self.server,
+            access_token,
+            session.refresh_token,
+        )
+        .await
+    }
+
+    fn refresh_token_duration(&self) -> time::Duration {
+        let minutes = self
+            .server
+            .config
+            .application
+            .refresh_token_duration_minutes;
+
+        time::Duration::from_secs(minutes * 60)
+    }
+}
+
+/// Insert a random user with the seed's role and status
+async fn seed_user(
+    database: &Pool<Postgres>,
+    seed: &UserSeed,
+) -> Result<TestUser, Error> {
+    let password = mocks::password()?;
+    let mut user = mocks::users(&password)?;
+    user.role = seed.role.clone();
+    user.is_active = seed.is_active;
+    user.is_verified = seed.is_verified;
+    let user = user.insert(database).await?;
+
+    Ok(TestUser { user, password })
+}
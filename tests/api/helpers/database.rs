@@ -0,0 +1,206 @@
+//-- ./tests/api/helpers/database.rs
+
+// #![allow(unused)] // For beginning only.
+
+//! Test database teardown and snapshot utilities
+//!
+//! Each `#[sqlx::test]` already runs against its own database, but scenario
+//! groups that share expensive seed data can snapshot the seeded state once and
+//! restore it between scenarios instead of re-seeding.
+//!
+//! Snapshots are stored as copies of the public tables in a per-snapshot schema
+//! (`snapshot_<label>`). This works on the live test pool, unlike
+//! `CREATE DATABASE ... TEMPLATE`, which requires no open connections to the
+//! source database.
+//!
+//! #### Reference
+//!
+//! * [PostgreSQL session_replication_role](https://www.postgresql.org/docs/current/runtime-config-client.html#GUC-SESSION-REPLICATION-ROLE)
+//! ---
+
+use sqlx::{Pool, Postgres};
+
+pub type Error = Box<dyn std::error::Error>;
+
+/// Tables that are never truncated, snapshot or restored
+const EXCLUDED_TABLES: [&str; 1] = ["_sqlx_migrations"];
+
+/// Quote a Postgres identifier so table and schema names are used literally
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+/// Get the names of all the application tables in the public schema
+pub async fn table_names(database: &Pool<Postgres>) -> Result<Vec<String>, Error> {
+    let tables: Vec<String> = sqlx::query_scalar(
+        r#"
+            SELECT tablename::TEXT
+            FROM pg_tables
+            WHERE schemaname = 'public'
+            ORDER BY tablename
+        "#,
+    )
+    .fetch_all(database)
+    .await?;
+
+    Ok(tables
+        .into_iter()
+        .filter(|table| !EXCLUDED_TABLES.contains(&table.as_str()))
+        .collect())
+}
+
+/// Remove all rows from every application table, leaving the schema and
+/// migration history in place.
+pub async fn teardown(database: &Pool<Postgres>) -> Result<(), Error> {
+    let tables = table_names(database).await?;
+    if tables.is_empty() {
+        return Ok(());
+    }
+
+    let tables = tables
+        .iter()
+        .map(|table| quote_identifier(table))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    sqlx::query(&format!("TRUNCATE TABLE {tables} RESTART IDENTITY CASCADE"))
+        .execute(database)
+        .await?;
+
+    Ok(())
+}
+
+/// A copy of the application tables that can be restored later in the test
+pub struct DatabaseSnapshot {
+    schema: String,
+    tables: Vec<String>,
+}
+
+impl DatabaseSnapshot {
+    /// Capture the current state of all application tables under `label`
+    pub async fn capture(database: &Pool<Postgres>, label: &str) -> Result<Self, Error> {
+        let schema = format!("snapshot_{label}");
+        let tables = table_names(database).await?;
+
+        let mut transaction = database.begin().await?;
+
+        sqlx::query(&format!(
+            "DROP SCHEMA IF EXISTS {} CASCADE",
+            quote_identifier(&schema)
+        ))
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query(&format!("CREATE SCHEMA {}", quote_identifier(&schema)))
+            .execute(&mut *transaction)
+            .await?;
+
+        for table in &tables {
+            sqlx::query(&format!(
+                "CREATE TABLE {}.{} AS TABLE public.{}",
+                quote_identifier(&schema),
+                quote_identifier(table),
+                quote_identifier(table),
+            ))
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        transaction.commit().await?;
+
+        Ok(Self { schema, tables })
+    }
+
+    /// Restore the application tables to the captured state. Foreign key
+    /// triggers are disabled for the restore so table order does not matter.
+    pub async fn restore(&self, database: &Pool<Postgres>) -> Result<(), Error> {
+        teardown(database).await?;
+
+        let mut transaction = database.begin().await?;
+
+        sqlx::query("SET LOCAL session_replication_role = replica")
+            .execute(&mut *transaction)
+            .await?;
+
+        for table in &self.tables {
+            sqlx::query(&format!(
+                "INSERT INTO public.{} SELECT * FROM {}.{}",
+                quote_identifier(table),
+                quote_identifier(&self.schema),
+                quote_identifier(table),
+            ))
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    /// Drop the snapshot schema
+    pub async fn discard(self, database: &Pool<Postgres>) -> Result<(), Error> {
+        sqlx::query(&format!(
+            "DROP SCHEMA IF EXISTS {} CASCADE",
+            quote_identifier(&self.schema)
+        ))
+        .execute(database)
+        .await?;
+
+        Ok(())
+    }
+}
+
+//-- Helper Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers;
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    async fn count_users(database: &Pool<Postgres>) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+            .fetch_one(database)
+            .await?;
+        Ok(count)
+    }
+
+    #[sqlx::test]
+    async fn teardown_empties_tables(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let random_password = helpers::mocks::password()?;
+        helpers::mocks::users(&random_password)?.insert(&database).await?;
+
+        //-- Execute Test (Act)
+        teardown(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(count_users(&database).await?, 0);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn restore_returns_to_captured_state(database: Pool<Postgres>) -> Result<()> {
+        //-- Setup and Fixtures (Arrange)
+        let random_password = helpers::mocks::password()?;
+        helpers::mocks::users(&random_password)?.insert(&database).await?;
+        let captured_count = count_users(&database).await?;
+        let snapshot = DatabaseSnapshot::capture(&database, "restore").await?;
+
+        // Change the state after the snapshot
+        helpers::mocks::users(&random_password)?.insert(&database).await?;
+        helpers::mocks::users(&random_password)?.insert(&database).await?;
+
+        //-- Execute Test (Act)
+        snapshot.restore(&database).await?;
+
+        //-- Checks (Assertions)
+        assert_eq!(count_users(&database).await?, captured_count);
+
+        snapshot.discard(&database).await?;
+
+        Ok(())
+    }
+}
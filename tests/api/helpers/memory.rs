@@ -0,0 +1,66 @@
+//-- ./tests/api/helpers/memory.rs
+
+// #![allow(unused)] // For beginning only.
+
+//! Services backed by the in-memory repository
+//!
+//! Reads and non-transactional deletes go through the repository traits, so
+//! tests of those rpcs can call the services directly without Postgres or a
+//! Tonic server. The services still need a database pool for transactional
+//! writes, so they get a lazy pool that never connects. Tests of rpcs that
+//! write must keep using `#[sqlx::test]` and `TonicServer`.
+//! ---
+
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use sqlx::postgres::PgPoolOptions;
+
+use authentication_service::{
+    configuration::Configuration,
+    events::AuthEvents,
+    repository::MemoryRepository,
+    services::{SessionsService, UsersService},
+};
+
+pub type Error = Box<dyn std::error::Error>;
+
+/// Users and sessions services sharing one in-memory repository
+pub struct MemoryServices {
+    pub repository: Arc<MemoryRepository>,
+    pub config: Arc<Configuration>,
+    pub users: UsersService,
+    pub sessions: SessionsService,
+}
+
+impl MemoryServices {
+    /// Build the services, seeding data through `repository`
+    pub fn spawn() -> Result<Self, Error> {
+        let config = Configuration::parse()?;
+
+        // Never connects unless a test calls an rpc that writes
+        let database = Arc::new(
+            PgPoolOptions::new().connect_lazy_with(config.database.connection()),
+        );
+        let shared_config = Arc::new(ArcSwap::from_pointee(config.clone()));
+        let events = AuthEvents::default();
+        let repository = Arc::new(MemoryRepository::new());
+
+        let users = UsersService::new(
+            Arc::clone(&database),
+            Arc::clone(&shared_config),
+            events.clone(),
+        )
+        .with_users_repository(repository.clone());
+
+        let sessions = SessionsService::new(database, shared_config, events)
+            .with_sessions_repository(repository.clone());
+
+        Ok(Self {
+            repository,
+            config: Arc::new(config),
+            users,
+            sessions,
+        })
+    }
+}
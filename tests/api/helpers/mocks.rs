@@ -74,6 +74,7 @@ pub fn users(password: &String) -> Result<database::Users, AuthenticationError>
         is_active: random_is_active,
         is_verified: random_is_verified,
         created_on: random_created_on,
+        locale: domain::Locale::default(),
     };
 
     Ok(random_user)
@@ -147,6 +148,13 @@ pub fn sessions(
         is_active: random_is_active,
         logged_out_at: random_logged_out_at,
         logout_ip: random_logout_ip,
+        absolute_expires_on: None,
+        last_refreshed_at: None,
+        access_token_id: None,
+        organization_id: None,
+        client_id: None,
+        last_used_at: None,
+        request_count: 0,
     };
 
     Ok(mock_session)
@@ -2,7 +2,11 @@
 
 // #![allow(unused)] // For beginning only.
 
+mod app;
+pub mod database;
+pub mod memory;
 pub mod mocks;
 mod spawn;
+pub use app::{TestApp, TestUser, UserSeed};
 pub use spawn::TonicClient;
 pub use spawn::TonicServer;
@@ -24,6 +24,11 @@ use tonic::transport::{Certificate, Channel, ClientTlsConfig};
 pub type AuthenticationClient =
 authentication_service::rpc::proto::authentication_service_client::AuthenticationServiceClient<Channel>;
 
+/// Convenience type alias for admin client
+pub type AdminClient =
+authentication_service::rpc::proto::admin_service_client::AdminServiceClient<
+    InterceptedService<Channel, TokenInterceptor>>;
+
 /// Convenience type alias for sessions client
 pub type SessionsClient =
 authentication_service::rpc::proto::sessions_service_client::SessionsServiceClient<
@@ -35,15 +40,28 @@ pub type UsersClient =
         InterceptedService<Channel, TokenInterceptor>,
     >;
 
+// Convenience type alias for utilities client
+pub type UtilitiesClient =
+    authentication_service::rpc::proto::utilities_service_client::UtilitiesServiceClient<
+        InterceptedService<Channel, TokenInterceptor>,
+    >;
+
 /// Tonic Client
 #[derive(Clone)]
 pub struct TonicClient {
+    admin: AdminClient,
     authentication: AuthenticationClient,
     sessions: SessionsClient,
     users: UsersClient,
+    utilities: UtilitiesClient,
 }
 
 impl TonicClient {
+    /// Returns the admin client.
+    pub fn admin(&mut self) -> &mut AdminClient {
+        &mut self.admin
+    }
+
     /// Returns the authentication client.
     pub fn authentication(&mut self) -> &mut AuthenticationClient {
         &mut self.authentication
@@ -59,9 +77,28 @@ impl TonicClient {
         &mut self.users
     }
 
+    /// Returns the utilities client.
+    pub fn utilities(&mut self) -> &mut UtilitiesClient {
+        &mut self.utilities
+    }
+
     /// Spawn a new tonic client based on the tonic server
     pub async fn spawn_client(
         server: &super::TonicServer,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::spawn_client_as(
+            server,
+            server.access_token.clone(),
+            server.refresh_token.clone(),
+        )
+        .await
+    }
+
+    /// Spawn a new tonic client sending another user's tokens
+    pub async fn spawn_client_as(
+        server: &super::TonicServer,
+        access_token: domain::AccessToken,
+        refresh_token: domain::RefreshToken,
     ) -> Result<Self, Box<dyn std::error::Error>> {
         // Build Tonic Client channel
         let uri: tonic::transport::Uri = server.address.parse()?;
@@ -91,10 +128,6 @@ impl TonicClient {
         // Add endpoint to the rpc channel
         let inner: Channel = endpoint.connect().await?;
 
-        // Get tokens
-        let access_token = server.clone().access_token;
-        let refresh_token = server.clone().refresh_token;
-
         // Get refresh token duration
         let rt_duration = time::Duration::new(
             (server.config.application.refresh_token_duration_minutes * 60)
@@ -105,7 +138,7 @@ impl TonicClient {
 
         // Build refresh token as a string
         let refresh_cookie = refresh_token
-            .build_cookie(&server.address, &rt_duration)
+            .build_cookie(&server.config.refresh_cookie, &server.address, &rt_duration)
             .to_string();
 
         // Create client token interceptor
@@ -123,10 +156,18 @@ impl TonicClient {
         // Build Users client request
         let users = authentication_service::rpc::proto::users_service_client::UsersServiceClient::with_interceptor(inner.clone(), client_interceptor.clone());
 
+        // Build Admin client request
+        let admin = authentication_service::rpc::proto::admin_service_client::AdminServiceClient::with_interceptor(inner.clone(), client_interceptor.clone());
+
+        // Build Utilities client request
+        let utilities = authentication_service::rpc::proto::utilities_service_client::UtilitiesServiceClient::with_interceptor(inner.clone(), client_interceptor.clone());
+
         let client = TonicClient {
+            admin,
             authentication,
             sessions,
             users,
+            utilities,
         };
 
         Ok(client)
@@ -10,7 +10,8 @@
 use std::sync::Arc;
 
 use authentication_service::{
-    configuration::Configuration, domain, startup, telemetry,
+    configuration::{Configuration, TelemetryConfiguration},
+    domain, email::MockEmailClient, startup, telemetry,
 };
 use once_cell::sync::Lazy;
 use sqlx::{Pool, Postgres};
@@ -26,7 +27,8 @@ pub type Error = Box<dyn std::error::Error>;
 // Lazy makes it globally available
 static TRACING: Lazy<()> = Lazy::new(|| {
     let testing_log_level = LevelFilter::ERROR; // <-- Set to ERROR for testing, change to DEBUG for more verbose output
-    let _telemetry = telemetry::init(testing_log_level);
+    let _telemetry =
+        telemetry::init(testing_log_level, &TelemetryConfiguration::default());
 });
 
 #[derive(Clone)]
@@ -35,16 +37,27 @@ pub struct TonicServer {
     pub access_token: domain::AccessToken,
     pub refresh_token: domain::RefreshToken,
     pub config: Arc<Configuration>,
+    pub email: MockEmailClient,
 }
 
 impl TonicServer {
     pub async fn spawn_server(database: &Pool<Postgres>) -> Result<Self, Error> {
+        Self::spawn_server_with(database, |_config| {}).await
+    }
+
+    /// Spawn a server as `spawn_server` does, changing the parsed configuration
+    /// with `configure` first
+    pub async fn spawn_server_with(
+        database: &Pool<Postgres>,
+        configure: impl FnOnce(&mut Configuration),
+    ) -> Result<Self, Error> {
         // Initiate tracing in integration testing
         Lazy::force(&TRACING);
 
         // Parse configuration files
         let config = {
             let mut s = Configuration::parse()?;
+            configure(&mut s);
             // Change port to `0` to avoid conflicts as the OS will assign an unused port
             s.application.port = 0;
             s
@@ -56,7 +69,7 @@ impl TonicServer {
         random_user.is_active = true;
         random_user.is_verified = true;
         random_user.role = domain::UserRole::Admin;
-        let random_user = random_user.insert(&database).await?;
+        let random_user = random_user.insert(database).await?;
         tracing::debug!("Random User: {:?}", random_user);
 
         // Get the token issuer from the configuration
@@ -83,7 +96,7 @@ impl TonicServer {
         // Generate session login to get refresh token
         let mut session = mocks::sessions(&random_user, &refresh_token)?;
         session.is_active = true;
-        let _database_session = session.insert(&database).await?;
+        let _database_session = session.insert(database).await?;
         tracing::debug!("Session: {:?}", session);
 
         // Generate access token for Tonic Client requests
@@ -101,9 +114,11 @@ impl TonicServer {
         )?;
         tracing::debug!("Access token: {}", access_token);
 
-        // Build Tonic server using main crate startup
-        let tonic_server =
-            startup::TonicServer::build(config.clone(), database.clone()).await?;
+        // Build Tonic server using main crate startup, capturing sent emails
+        let email = MockEmailClient::new();
+        let tonic_server = startup::TonicServer::build(config.clone(), database.clone())
+            .await?
+            .with_email_client(Arc::new(email.clone()));
 
         // Set tonic server address as the port is randomly selected by the TCP Listener (in startup)
         // when config sets the port to 0
@@ -130,6 +145,7 @@ impl TonicServer {
             access_token,
             refresh_token: session.refresh_token,
             config,
+            email,
         })
     }
 
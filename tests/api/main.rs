@@ -2,8 +2,12 @@
 
 // Add modules to include in integration binary
 
+mod admin;
 mod authentication;
+#[cfg(feature = "client")]
+mod client;
 pub mod helpers;
 mod sessions;
 mod users;
 mod utilities;
+mod wire_compatibility;
@@ -1,4 +1,4 @@
-
+mod read;
 // mod update;
 
 // mod delete;
\ No newline at end of file
@@ -0,0 +1,109 @@
+//-- ./tests/api/sessions/read.rs
+
+// #![allow(unused)] // For beginning only.
+
+use std::time;
+
+use authentication_service::{
+    domain,
+    rpc::proto::{
+        sessions_service_server::SessionsService as _, Empty,
+        SessionsDeleteUserRequest, SessionsIndexRequest, SessionsReadRequest,
+    },
+};
+
+use crate::helpers;
+
+pub type Error = Box<dyn std::error::Error>;
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// Build a random session for the user, using the service token settings
+fn random_session(
+    services: &helpers::memory::MemoryServices,
+    user: &authentication_service::database::Users,
+) -> Result<authentication_service::database::Sessions> {
+    let refresh_token = domain::RefreshToken::new(
+        &services.config.application.token_secret,
+        &services.config.application.get_issuer(),
+        &time::Duration::from_secs(3600),
+        user,
+    )?;
+
+    Ok(helpers::mocks::sessions(user, &refresh_token)?)
+}
+
+#[tokio::test]
+async fn id_returns_session_from_memory() -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let services = helpers::memory::MemoryServices::spawn()?;
+    let random_password = helpers::mocks::password()?;
+    let random_user = helpers::mocks::users(&random_password)?;
+    let session = random_session(&services, &random_user)?;
+    services.repository.insert_session(session.clone());
+
+    //-- Execute Test (Act)
+    let request = tonic::Request::new(SessionsReadRequest {
+        id: session.id.to_string(),
+    });
+    let response_message = services.sessions.read(request).await?.into_inner();
+
+    //-- Checks (Assertions)
+    assert_eq!(response_message.id, session.id.to_string());
+    assert_eq!(response_message.user_id, random_user.id.to_string());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn missing_id_returns_error_from_memory() -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let services = helpers::memory::MemoryServices::spawn()?;
+
+    //-- Execute Test (Act)
+    let request = tonic::Request::new(SessionsReadRequest {
+        id: uuid::Uuid::now_v7().to_string(),
+    });
+    let response = services.sessions.read(request).await;
+
+    //-- Checks (Assertions)
+    assert!(response.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn delete_user_removes_only_their_sessions_from_memory() -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let services = helpers::memory::MemoryServices::spawn()?;
+    let random_password = helpers::mocks::password()?;
+    let random_user = helpers::mocks::users(&random_password)?;
+    let other_user = helpers::mocks::users(&random_password)?;
+    for user in [&random_user, &random_user, &other_user] {
+        services
+            .repository
+            .insert_session(random_session(&services, user)?);
+    }
+
+    //-- Execute Test (Act)
+    let request = tonic::Request::new(SessionsDeleteUserRequest {
+        user_id: random_user.id.to_string(),
+    });
+    let deleted = services.sessions.delete_user(request).await?.into_inner();
+    let request = tonic::Request::new(SessionsIndexRequest {
+        limit: 10,
+        offset: 0,
+    });
+    let remaining = services.sessions.index(request).await?.into_inner();
+    let deleted_all = services
+        .sessions
+        .delete_all(tonic::Request::new(Empty {}))
+        .await?
+        .into_inner();
+
+    //-- Checks (Assertions)
+    assert_eq!(deleted.rows_affected, 2);
+    assert_eq!(remaining.sessions.len(), 1);
+    assert_eq!(deleted_all.rows_affected, 1);
+
+    Ok(())
+}
@@ -38,6 +38,8 @@ async fn returns_created_user(database: Pool<Postgres>) -> Result<()> {
         role: random_user.role.to_string(),
         is_active: random_user.is_active,
         is_verified: random_user.is_verified,
+        locale: None,
+        temporary_password: false,
     };
     // println!("{request_message:#?}");
 
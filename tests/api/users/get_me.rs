@@ -0,0 +1,168 @@
+//-- ./tests/api/users/get_me.rs
+
+// #![allow(unused)] // For beginning only.
+
+use sqlx::{Pool, Postgres};
+
+use authentication_service::domain;
+use authentication_service::rpc::proto::users_service_client::UsersServiceClient;
+use authentication_service::rpc::proto::{ApiKeyIndexRequest, Empty, LoginRequest};
+
+use crate::helpers;
+
+pub type Error = Box<dyn std::error::Error>;
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[sqlx::test]
+async fn returns_the_callers_profile(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    // Insert an active, non-admin user who can log in
+    let random_password = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password)?;
+    random_user.is_active = true;
+    random_user.is_verified = true;
+    random_user.role = domain::UserRole::User;
+    let random_user = random_user.insert(&database).await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    // Log in as the user to get their access token
+    let login_response = tonic_client
+        .authentication()
+        .login(LoginRequest {
+            email: random_user.email.to_string(),
+            password: random_password.to_string(),
+            remember_me: false,
+            organization_id: None,
+            captcha_token: None,
+            password_new: None,
+        })
+        .await?
+        .into_inner();
+
+    //-- Execute Test (Act)
+    let mut request = tonic::Request::new(Empty {});
+    request.metadata_mut().insert(
+        "authorization",
+        format!("Bearer {}", login_response.access_token).parse()?,
+    );
+    let response_message = UsersServiceClient::connect(tonic_server.address.clone())
+        .await?
+        .get_me(request)
+        .await?
+        .into_inner();
+
+    //-- Checks (Assertions)
+    assert_eq!(response_message.id, random_user.id.to_string());
+    assert_eq!(response_message.email, random_user.email.as_ref());
+    assert_eq!(response_message.name, random_user.name.as_ref());
+    assert_eq!(response_message.role, domain::UserRole::User.as_ref());
+    assert!(response_message.is_verified);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn returns_an_unverified_guests_profile(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let app = helpers::TestApp::builder(&database)
+        .with_user(helpers::UserSeed::guest().unverified())
+        .build()
+        .await?;
+    let guest = &app.users[0];
+    let mut client = app.client_as(guest).await?;
+
+    //-- Execute Test (Act)
+    let response_message = client.users().get_me(Empty {}).await?.into_inner();
+
+    //-- Checks (Assertions)
+    assert_eq!(response_message.id, guest.user.id.to_string());
+    assert_eq!(response_message.role, domain::UserRole::Guest.as_ref());
+    assert!(!response_message.is_verified);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn requires_an_access_token(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+
+    //-- Execute Test (Act)
+    let response = UsersServiceClient::connect(tonic_server.address.clone())
+        .await?
+        .get_me(Empty {})
+        .await;
+
+    //-- Checks (Assertions)
+    assert_eq!(
+        response.err().map(|status| status.code()),
+        Some(tonic::Code::Unauthenticated)
+    );
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn stays_public_when_the_internal_server_is_enabled(
+    database: Pool<Postgres>,
+) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let random_password = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password)?;
+    random_user.is_active = true;
+    random_user.is_verified = true;
+    random_user.role = domain::UserRole::User;
+    let random_user = random_user.insert(&database).await?;
+
+    // Move the default internal services off the public address
+    let tonic_server =
+        helpers::TonicServer::spawn_server_with(&database, |config| {
+            config.internal.enabled = true;
+            config.internal.address = "127.0.0.1:0".to_string();
+        })
+        .await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    let login_response = tonic_client
+        .authentication()
+        .login(LoginRequest {
+            email: random_user.email.to_string(),
+            password: random_password.to_string(),
+            remember_me: false,
+            organization_id: None,
+            captcha_token: None,
+            password_new: None,
+        })
+        .await?
+        .into_inner();
+
+    //-- Execute Test (Act)
+    let mut request = tonic::Request::new(Empty {});
+    request.metadata_mut().insert(
+        "authorization",
+        format!("Bearer {}", login_response.access_token).parse()?,
+    );
+    let response_message = UsersServiceClient::connect(tonic_server.address.clone())
+        .await?
+        .get_me(request)
+        .await?
+        .into_inner();
+    let admin_response = tonic_client
+        .admin()
+        .list_api_keys(ApiKeyIndexRequest {
+            limit: 10,
+            offset: 0,
+        })
+        .await;
+
+    //-- Checks (Assertions)
+    assert_eq!(response_message.id, random_user.id.to_string());
+    assert_eq!(
+        admin_response.err().map(|status| status.code()),
+        Some(tonic::Code::Unimplemented)
+    );
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+//-- ./tests/api/users/login_history.rs
+
+// #![allow(unused)] // For beginning only.
+
+use sqlx::{Pool, Postgres};
+
+use authentication_service::domain;
+use authentication_service::rpc::proto::users_service_client::UsersServiceClient;
+use authentication_service::rpc::proto::{ListMyLoginHistoryRequest, LoginRequest};
+
+use crate::helpers;
+
+pub type Error = Box<dyn std::error::Error>;
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[sqlx::test]
+async fn lists_the_callers_logins_newest_first(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    // Insert an active, non-admin user who can log in
+    let random_password = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password)?;
+    random_user.is_active = true;
+    random_user.is_verified = true;
+    random_user.role = domain::UserRole::User;
+    let random_user = random_user.insert(&database).await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    // Fail a login, then log in as the user to get their access token
+    let failed = tonic_client
+        .authentication()
+        .login(LoginRequest {
+            email: random_user.email.to_string(),
+            password: helpers::mocks::password()?,
+            remember_me: false,
+            organization_id: None,
+            captcha_token: None,
+            password_new: None,
+        })
+        .await;
+    assert!(failed.is_err());
+
+    let mut login_request = tonic::Request::new(LoginRequest {
+        email: random_user.email.to_string(),
+        password: random_password.to_string(),
+        remember_me: false,
+        organization_id: None,
+        captcha_token: None,
+        password_new: None,
+    });
+    login_request
+        .metadata_mut()
+        .insert("user-agent", "login-history-test".parse()?);
+    let login_response = tonic_client
+        .authentication()
+        .login(login_request)
+        .await?
+        .into_inner();
+
+    //-- Execute Test (Act)
+    let mut request = tonic::Request::new(ListMyLoginHistoryRequest {
+        limit: 1,
+        cursor_created_on: None,
+        cursor_id: None,
+    });
+    request.metadata_mut().insert(
+        "authorization",
+        format!("Bearer {}", login_response.access_token).parse()?,
+    );
+    let first_page = UsersServiceClient::connect(tonic_server.address.clone())
+        .await?
+        .list_my_login_history(request)
+        .await?
+        .into_inner();
+
+    let next_cursor = first_page.next_cursor.clone().unwrap();
+    let mut request = tonic::Request::new(ListMyLoginHistoryRequest {
+        limit: 10,
+        cursor_created_on: Some(next_cursor.created_on),
+        cursor_id: Some(next_cursor.id),
+    });
+    request.metadata_mut().insert(
+        "authorization",
+        format!("Bearer {}", login_response.access_token).parse()?,
+    );
+    let second_page = UsersServiceClient::connect(tonic_server.address.clone())
+        .await?
+        .list_my_login_history(request)
+        .await?
+        .into_inner();
+
+    //-- Checks (Assertions)
+    assert_eq!(first_page.logins.len(), 1);
+    assert!(first_page.has_more);
+    assert_eq!(first_page.logins[0].outcome, "success");
+    assert!(first_page.logins[0]
+        .user_agent
+        .as_deref()
+        .is_some_and(|user_agent| user_agent.contains("login-history-test")));
+    assert_eq!(second_page.logins.len(), 1);
+    assert_eq!(second_page.logins[0].outcome, "failed");
+    assert!(!second_page.has_more);
+    assert_eq!(second_page.next_cursor, None);
+
+    Ok(())
+}
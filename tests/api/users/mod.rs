@@ -2,5 +2,8 @@
 
 mod create;
 mod delete;
+mod get_me;
+mod login_history;
 mod read;
+mod search;
 mod update;
@@ -7,7 +7,7 @@ use sqlx::{Pool, Postgres};
 
 use authentication_service::{
     database,
-    rpc::proto::{ReadUserRequest, UserIndexRequest},
+    rpc::proto::{users_service_server::UsersService as _, ReadUserRequest, UserIndexRequest},
 };
 
 use crate::helpers;
@@ -154,3 +154,85 @@ async fn index_returns_users(pool: Pool<Postgres>) -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn id_returns_user_from_memory() -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let services = helpers::memory::MemoryServices::spawn()?;
+    let random_password = helpers::mocks::password()?;
+    let random_user = helpers::mocks::users(&random_password)?;
+    services.repository.insert_user(random_user.clone());
+
+    //-- Execute Test (Act)
+    let request = tonic::Request::new(ReadUserRequest {
+        id: random_user.id.to_string(),
+    });
+    let response_message = services.users.read(request).await?.into_inner();
+
+    //-- Checks (Assertions)
+    assert_eq!(random_user.id.to_string(), response_message.id);
+    assert_eq!(random_user.email.as_ref(), response_message.email);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn index_pages_users_from_memory() -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let services = helpers::memory::MemoryServices::spawn()?;
+    let random_count: usize = (2..30).fake::<usize>();
+    let mut test_vec: Vec<database::Users> = Vec::new();
+    for _count in 0..random_count {
+        let random_password = helpers::mocks::password()?;
+        let random_user = helpers::mocks::users(&random_password)?;
+        services.repository.insert_user(random_user.clone());
+        test_vec.push(random_user);
+    }
+    test_vec.sort_by_key(|user| user.id);
+
+    //-- Execute Test (Act)
+    let request = tonic::Request::new(UserIndexRequest {
+        limit: 2,
+        offset: 1,
+    });
+    let response_message = services.users.index(request).await?.into_inner();
+
+    //-- Checks (Assertions)
+    let ids: Vec<String> = response_message.users.into_iter().map(|user| user.id).collect();
+    let expected: Vec<String> =
+        test_vec.iter().skip(1).take(2).map(|user| user.id.to_string()).collect();
+    assert_eq!(ids, expected);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn malformed_requests_are_rejected() -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let services = helpers::memory::MemoryServices::spawn()?;
+
+    //-- Execute Test (Act)
+    let read = services
+        .users
+        .read(tonic::Request::new(ReadUserRequest {
+            id: "not-a-uuid".to_string(),
+        }))
+        .await
+        .unwrap_err();
+    let index = services
+        .users
+        .index(tonic::Request::new(UserIndexRequest {
+            limit: 1_000,
+            offset: 0,
+        }))
+        .await
+        .unwrap_err();
+
+    //-- Checks (Assertions)
+    assert_eq!(read.code(), tonic::Code::InvalidArgument);
+    assert_eq!(read.message(), "Invalid request: id is not a UUID");
+    assert_eq!(index.code(), tonic::Code::InvalidArgument);
+    assert_eq!(index.message(), "Invalid request: limit must be from 1 to 100");
+
+    Ok(())
+}
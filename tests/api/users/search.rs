@@ -0,0 +1,259 @@
+//-- ./tests/api/users/search.rs
+
+// #![allow(unused)] // For beginning only.
+
+use sqlx::{Pool, Postgres};
+
+use authentication_service::{
+    database, domain,
+    rpc::proto::{DeleteUserRequest, SearchUsersRequest, UserIndexRequest},
+};
+
+use crate::helpers;
+
+pub type Error = Box<dyn std::error::Error>;
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[sqlx::test]
+async fn email_filter_returns_matching_user(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    // Generate random user data and insert into database for testing
+    let random_password = helpers::mocks::password()?;
+    let mut random_user = helpers::mocks::users(&random_password)?;
+    random_user.email = domain::EmailAddress::parse("find.me.please@example.com")?;
+    let database_record = random_user.insert(&database).await?;
+
+    // Spawn Tonic test server
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+
+    // Spawn Tonic test client
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    let request_message = SearchUsersRequest {
+        email: Some("FIND.ME".to_string()),
+        limit: 10,
+        ..Default::default()
+    };
+
+    //-- Execute Test (Act)
+    let response_message = tonic_client
+        .users()
+        .search_users(request_message)
+        .await?
+        .into_inner();
+
+    //-- Checks (Assertions)
+    assert_eq!(response_message.users.len(), 1);
+    assert_eq!(response_message.users[0].id, database_record.id.to_string());
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn role_filter_only_returns_role(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    for _count in 0..10 {
+        let random_password = helpers::mocks::password()?;
+        let random_user = helpers::mocks::users(&random_password)?;
+        random_user.insert(&database).await?;
+    }
+
+    // Spawn Tonic test server
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+
+    // Spawn Tonic test client
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    let request_message = SearchUsersRequest {
+        role: Some("admin".to_string()),
+        limit: 100,
+        ..Default::default()
+    };
+
+    //-- Execute Test (Act)
+    let response_message = tonic_client
+        .users()
+        .search_users(request_message)
+        .await?
+        .into_inner();
+
+    //-- Checks (Assertions)
+    // The migration admin and the test server user are always admins
+    assert!(response_message.users.len() >= 2);
+    assert!(response_message.users.iter().all(|u| u.role == "admin"));
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn invalid_role_returns_invalid_argument(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    let request_message = SearchUsersRequest {
+        role: Some("superuser".to_string()),
+        limit: 10,
+        ..Default::default()
+    };
+
+    //-- Execute Test (Act)
+    let status = tonic_client
+        .users()
+        .search_users(request_message)
+        .await
+        .unwrap_err();
+
+    //-- Checks (Assertions)
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn next_cursor_pages_through_results(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    for _count in 0..3 {
+        let random_password = helpers::mocks::password()?;
+        let mut random_user = helpers::mocks::users(&random_password)?;
+        random_user.role = domain::UserRole::Guest;
+        random_user.insert(&database).await?;
+    }
+
+    // Spawn Tonic test server
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+
+    // Spawn Tonic test client
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    let request_message = SearchUsersRequest {
+        role: Some("guest".to_string()),
+        limit: 2,
+        ..Default::default()
+    };
+
+    //-- Execute Test (Act)
+    let first_page = tonic_client
+        .users()
+        .search_users(request_message)
+        .await?
+        .into_inner();
+
+    let next_cursor = first_page.next_cursor.clone().unwrap();
+    let request_message = SearchUsersRequest {
+        role: Some("guest".to_string()),
+        limit: 2,
+        cursor_created_on: Some(next_cursor.created_on),
+        cursor_id: Some(next_cursor.id),
+        ..Default::default()
+    };
+    let second_page = tonic_client
+        .users()
+        .search_users(request_message)
+        .await?
+        .into_inner();
+
+    //-- Checks (Assertions)
+    assert_eq!(first_page.users.len(), 2);
+    assert!(first_page.has_more);
+    assert_eq!(second_page.users.len(), 1);
+    assert!(!second_page.has_more);
+    assert_eq!(second_page.next_cursor, None);
+    assert!(first_page
+        .users
+        .iter()
+        .all(|user| user.id != second_page.users[0].id));
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn organization_token_only_returns_members(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let app = helpers::TestApp::builder(&database)
+        .with_users([helpers::UserSeed::admin(), helpers::UserSeed::user()])
+        .build()
+        .await?;
+    let (admin, member) = (&app.users[0], &app.users[1]);
+
+    let organization = database::Organizations::new("Acme Corp", "acme-corp")?
+        .insert(&database)
+        .await?;
+    for user in [admin, member] {
+        database::OrganizationMembers::new(
+            &organization.id,
+            &user.user.id,
+            &domain::UserRole::User,
+        )
+        .insert(&database)
+        .await?;
+    }
+
+    let mut tonic_client = app.client_in_organization(admin, &organization.id).await?;
+
+    let request_message = SearchUsersRequest {
+        limit: 100,
+        ..Default::default()
+    };
+
+    //-- Execute Test (Act)
+    let response_message = tonic_client
+        .users()
+        .search_users(request_message)
+        .await?
+        .into_inner();
+
+    //-- Checks (Assertions)
+    let mut returned_ids: Vec<String> =
+        response_message.users.into_iter().map(|user| user.id).collect();
+    returned_ids.sort();
+    let mut member_ids = vec![admin.user.id.to_string(), member.user.id.to_string()];
+    member_ids.sort();
+    assert_eq!(returned_ids, member_ids);
+
+    Ok(())
+}
+
+#[sqlx::test]
+async fn user_role_is_denied_managing_users(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let app = helpers::TestApp::builder(&database)
+        .with_users([helpers::UserSeed::user(), helpers::UserSeed::user()])
+        .build()
+        .await?;
+    let (user, other) = (&app.users[0], &app.users[1]);
+    let mut tonic_client = app.client_as(user).await?;
+
+    //-- Execute Test (Act)
+    let search = tonic_client
+        .users()
+        .search_users(SearchUsersRequest {
+            limit: 10,
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+    let index = tonic_client
+        .users()
+        .index(UserIndexRequest {
+            limit: 10,
+            offset: 0,
+        })
+        .await
+        .unwrap_err();
+    let delete = tonic_client
+        .users()
+        .delete(DeleteUserRequest {
+            id: other.user.id.to_string(),
+        })
+        .await
+        .unwrap_err();
+
+    //-- Checks (Assertions)
+    assert_eq!(search.code(), tonic::Code::PermissionDenied);
+    assert_eq!(index.code(), tonic::Code::PermissionDenied);
+    assert_eq!(delete.code(), tonic::Code::PermissionDenied);
+    assert!(database::Users::from_user_id(&other.user.id, &database).await.is_ok());
+
+    Ok(())
+}
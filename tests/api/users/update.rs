@@ -39,11 +39,13 @@ async fn user_returns_update(database: Pool<Postgres>) -> Result<()> {
     // Build rpc request message
     let request_message = UpdateUserRequest {
         id: random_user_original.id.to_string(),
-        email: random_user_update.email.to_string(),
+        // Email changes need confirming, see `admin::email_change`
+        email: random_user_original.email.to_string(),
         name: random_user_update.name.to_string(),
         role: random_user_update.role.to_string(),
         is_active: random_user_update.is_active,
         is_verified: random_user_update.is_verified,
+        locale: None,
     };
 
     // Build tonic request
@@ -61,8 +63,8 @@ async fn user_returns_update(database: Pool<Postgres>) -> Result<()> {
     // User id should equal the original id as update will not change this
     assert_eq!(random_user_original.id.to_string(), response_message.id);
 
-    // User email should be equal
-    assert_eq!(random_user_update.email.as_ref(), response_message.email);
+    // User email should be unchanged
+    assert_eq!(random_user_original.email.as_ref(), response_message.email);
 
     // Username should be equal
     assert_eq!(random_user_update.name.as_ref(), response_message.name);
@@ -84,3 +86,38 @@ async fn user_returns_update(database: Pool<Postgres>) -> Result<()> {
 
     Ok(())
 }
+
+#[sqlx::test]
+async fn email_can_not_be_updated_directly(database: Pool<Postgres>) -> Result<()> {
+    //-- Setup and Fixtures (Arrange)
+    let random_password_original = helpers::mocks::password()?;
+    let random_user_original =
+        helpers::mocks::users(&random_password_original)?;
+    let _database_record = random_user_original.insert(&database).await?;
+
+    let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+    let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+
+    let random_user_update = helpers::mocks::users(&random_password_original)?;
+    let request_message = UpdateUserRequest {
+        id: random_user_original.id.to_string(),
+        email: random_user_update.email.to_string(),
+        name: random_user_original.name.to_string(),
+        role: random_user_original.role.to_string(),
+        is_active: random_user_original.is_active,
+        is_verified: random_user_original.is_verified,
+        locale: None,
+    };
+
+    //-- Execute Test (Act)
+    let status = tonic_client
+        .users()
+        .update(tonic::Request::new(request_message))
+        .await
+        .expect_err("email changes should be rejected");
+
+    //-- Checks (Assertions)
+    assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+
+    Ok(())
+}
@@ -5,10 +5,21 @@
 //! Endpoints include
 //!
 //! * `ping`: For checking the backend server is up and running
+//! * `readiness_report`: The startup dependency checks
+//! * `server_info`, `server_time` and `echo`: For operations and SDK smoke tests
+//! * `set_log_filter`: Changes the log filter at runtime, for admins
+//! * `grpc.health.v1.Health/Check`: Reports serving once the warm-up is done
+//!
+//! Also checks responses are compressed for clients that accept it, and that
+//! each listener serves its own set of services
 
 // #![allow(unused)] // For beginning only.
 
-use authentication_service::rpc::proto::{utilities_service_client::UtilitiesServiceClient as UtilitiesClient, Empty};
+use authentication_service::rpc::proto::{utilities_service_client::UtilitiesServiceClient as UtilitiesClient, EchoRequest, Empty, SetLogFilterRequest};
+use authentication_service::configuration::ListenerConfiguration;
+use tonic::codec::CompressionEncoding;
+use tonic::transport::{Endpoint, Uri};
+use tonic_health::pb::{health_check_response::ServingStatus, health_client::HealthClient, HealthCheckRequest};
 
 use sqlx::{Pool, Postgres};
 
@@ -41,3 +52,249 @@ async fn ping_returns_pong(database: Pool<Postgres>) -> Result<()> {
 
 	Ok(())
 }
+
+#[sqlx::test]
+async fn health_reports_serving_after_warm_up(database: Pool<Postgres>) -> Result<()> {
+	//-- Setup and Fixtures (Arrange)
+	// Spawn Tonic test server
+	let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+
+	// Build Tonic health client, health checks do not need authentication
+	let mut tonic_health_client = HealthClient::new(
+		tonic_server.client_channel().await?
+	);
+
+	//-- Execute Test (Act)
+	// Warm-up runs in the background, so poll until the server reports serving
+	let mut status = ServingStatus::Unknown;
+	for _attempt in 0..50 {
+		let request = tonic::Request::new(HealthCheckRequest { service: "".to_string() });
+		status = tonic_health_client.check(request).await?.into_inner().status();
+		if status == ServingStatus::Serving {
+			break;
+		}
+		tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+	}
+
+	//-- Checks (Assertions)
+	assert_eq!(status, ServingStatus::Serving);
+
+	Ok(())
+}
+
+#[sqlx::test]
+async fn readiness_report_lists_the_passed_checks(database: Pool<Postgres>) -> Result<()> {
+	//-- Setup and Fixtures (Arrange)
+	// Spawn Tonic test server
+	let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+
+	// Build Tonic utilities client, the report does not need authentication
+	let mut tonic_utilities_client = UtilitiesClient::new(
+		tonic_server.client_channel().await?
+	);
+
+	//-- Execute Test (Act)
+	// The checks run after the warm-up, so poll until the report is ready
+	let mut response = Default::default();
+	for _attempt in 0..50 {
+		let request = tonic::Request::new(Empty {});
+		response = tonic_utilities_client.readiness_report(request).await?.into_inner();
+		if response.ready {
+			break;
+		}
+		tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+	}
+
+	//-- Checks (Assertions)
+	let names: Vec<&str> = response.checks.iter().map(|check| check.name.as_str()).collect();
+	assert!(response.ready);
+	assert_eq!(names, ["database", "migrations", "token signing"]);
+	assert!(response.checks.iter().all(|check| check.passed));
+	assert!(!response.checked_at.is_empty());
+
+	Ok(())
+}
+
+#[sqlx::test]
+async fn server_info_reports_the_build(database: Pool<Postgres>) -> Result<()> {
+	//-- Setup and Fixtures (Arrange)
+	// Spawn Tonic test server
+	let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+
+	// Build Tonic utilities client, server info does not need authentication
+	let mut tonic_utilities_client = UtilitiesClient::new(
+		tonic_server.client_channel().await?
+	);
+
+	//-- Execute Test (Act)
+	let info = tonic_utilities_client
+		.server_info(tonic::Request::new(Empty {}))
+		.await?
+		.into_inner();
+	let time = tonic_utilities_client
+		.server_time(tonic::Request::new(Empty {}))
+		.await?
+		.into_inner();
+
+	//-- Checks (Assertions)
+	assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+	assert!(!info.git_sha.is_empty());
+	assert!(info.uptime_seconds >= 0);
+	let now = chrono::Utc::now().timestamp_millis();
+	assert!((now - time.unix_milliseconds).abs() < 60_000);
+
+	Ok(())
+}
+
+#[sqlx::test]
+async fn echo_reflects_the_request_metadata(database: Pool<Postgres>) -> Result<()> {
+	//-- Setup and Fixtures (Arrange)
+	// Spawn Tonic test server
+	let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+
+	// Build Tonic utilities client
+	let mut tonic_utilities_client = UtilitiesClient::new(
+		tonic_server.client_channel().await?
+	);
+	let mut request = tonic::Request::new(EchoRequest { message: "Hello".to_string() });
+	request.metadata_mut().insert("x-request-id", "smoke-test".parse()?);
+	request.metadata_mut().insert("authorization", "Bearer secret".parse()?);
+
+	//-- Execute Test (Act)
+	let response = tonic_utilities_client.echo(request).await?.into_inner();
+
+	//-- Checks (Assertions)
+	assert_eq!(response.message, "Hello");
+	assert_eq!(response.metadata["x-request-id"], "smoke-test");
+	assert_eq!(response.metadata["authorization"], "[redacted]");
+
+	Ok(())
+}
+
+#[sqlx::test]
+async fn responses_are_compressed_when_accepted(database: Pool<Postgres>) -> Result<()> {
+	//-- Setup and Fixtures (Arrange)
+	// Spawn Tonic test server
+	let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+
+	// Build Tonic utilities client that compresses requests and accepts gzip
+	let mut tonic_utilities_client = UtilitiesClient::new(
+		tonic_server.client_channel().await?
+	)
+	.send_compressed(CompressionEncoding::Gzip)
+	.accept_compressed(CompressionEncoding::Gzip);
+
+	//-- Execute Test (Act)
+	let request_empty = tonic::Request::new(Empty {});
+	let response = tonic_utilities_client.ping(request_empty).await?;
+
+	//-- Checks (Assertions)
+	assert_eq!(
+		response.metadata().get("grpc-encoding").ok_or("not compressed")?,
+		"gzip"
+	);
+	assert_eq!(response.into_inner().message, "Pong...");
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[sqlx::test]
+async fn listeners_serve_their_own_services(database: Pool<Postgres>) -> Result<()> {
+	//-- Setup and Fixtures (Arrange)
+	// Serve authentication on the main address and utilities on a socket
+	let socket = std::env::temp_dir().join(format!("authentication-{}.sock", uuid::Uuid::now_v7()));
+	let unix_socket = socket.to_string_lossy().to_string();
+	let tonic_server = helpers::TonicServer::spawn_server_with(&database, |config| {
+		config.application.services = vec!["authentication".to_string()];
+		config.listeners = vec![ListenerConfiguration {
+			name: "sidecar".to_string(),
+			address: None,
+			unix_socket: Some(unix_socket),
+			services: vec!["utilities".to_string()],
+		}];
+	})
+	.await?;
+
+	// Build Tonic utilities clients for the main address and the socket
+	let mut tonic_public_client = UtilitiesClient::new(
+		tonic_server.client_channel().await?
+	);
+	let sidecar_channel = Endpoint::try_from("http://localhost")?
+		.connect_with_connector(tower::service_fn(move |_uri: Uri| {
+			let socket = socket.clone();
+			async move {
+				let stream = tokio::net::UnixStream::connect(socket).await?;
+				Ok::<_, std::io::Error>(hyper_util::rt::TokioIo::new(stream))
+			}
+		}))
+		.await?;
+	let mut tonic_sidecar_client = UtilitiesClient::new(sidecar_channel);
+
+	//-- Execute Test (Act)
+	let public_response = tonic_public_client.ping(tonic::Request::new(Empty {})).await;
+	let sidecar_response = tonic_sidecar_client
+		.ping(tonic::Request::new(Empty {}))
+		.await?
+		.into_inner();
+
+	//-- Checks (Assertions)
+	assert_eq!(
+		public_response.err().map(|status| status.code()),
+		Some(tonic::Code::Unimplemented)
+	);
+	assert_eq!(sidecar_response.message, "Pong...");
+
+	Ok(())
+}
+
+#[sqlx::test]
+async fn admins_can_set_the_log_filter_for_a_time(database: Pool<Postgres>) -> Result<()> {
+	//-- Setup and Fixtures (Arrange)
+	let tonic_server = helpers::TonicServer::spawn_server(&database).await?;
+	let mut tonic_client = helpers::TonicClient::spawn_client(&tonic_server).await?;
+	let mut tonic_anonymous_client = UtilitiesClient::new(
+		tonic_server.client_channel().await?
+	);
+	// Keep the tests quiet, only the database module changes
+	let request = SetLogFilterRequest {
+		filter: "error,authentication_service::database=error".to_string(),
+		duration_minutes: 10,
+	};
+
+	//-- Execute Test (Act)
+	let response = tonic_client
+		.utilities()
+		.set_log_filter(request.clone())
+		.await?
+		.into_inner();
+	let invalid = tonic_client
+		.utilities()
+		.set_log_filter(SetLogFilterRequest {
+			filter: "tower_http=loud".to_string(),
+			duration_minutes: 10,
+		})
+		.await
+		.unwrap_err();
+	let too_long = tonic_client
+		.utilities()
+		.set_log_filter(SetLogFilterRequest {
+			duration_minutes: 100_000,
+			..request.clone()
+		})
+		.await
+		.unwrap_err();
+	let anonymous = tonic_anonymous_client
+		.set_log_filter(request)
+		.await
+		.unwrap_err();
+
+	//-- Checks (Assertions)
+	assert!(response.filter.contains("authentication_service::database=error"));
+	assert!(response.reverts_at.is_some());
+	assert_eq!(invalid.code(), tonic::Code::InvalidArgument);
+	assert_eq!(too_long.code(), tonic::Code::InvalidArgument);
+	assert_eq!(anonymous.code(), tonic::Code::Unauthenticated);
+
+	Ok(())
+}
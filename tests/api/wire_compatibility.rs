@@ -0,0 +1,304 @@
+//-- ./tests/api/wire_compatibility.rs
+
+//! Wire format compatibility checks for the Protobuf definitions
+//!
+//! The compiled file descriptor set is reduced to a list of wire signatures
+//! (field numbers and types, enum values and rpc shapes) and compared against
+//! the committed baseline in `tests/api/golden/authentication_wire.txt`.
+//!
+//! * Breaking changes (removed or renumbered fields, changed types, labels or
+//!   rpc streaming) always fail.
+//! * Compatible additions fail until they are blessed into the baseline with
+//!   `BLESS_PROTO_WIRE=1 cargo test wire_compatibility`.
+//! * Baselines from before the `authentication` package became
+//!   `authentication.v1` are compared as if renamed. Renaming doesn't change
+//!   the wire format, and `middleware::PackageAliasLayer` serves the old rpc
+//!   paths.
+
+// #![allow(unused)] // For beginning only.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use prost::Message;
+use prost_types::{field_descriptor_proto, DescriptorProto, FileDescriptorSet};
+
+use authentication_service::rpc::proto::FILE_DESCRIPTOR_SET;
+
+pub type Error = Box<dyn std::error::Error>;
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// Environment variable used to bless compatible additions into the baseline
+const BLESS_ENV: &str = "BLESS_PROTO_WIRE";
+
+/// Path to the committed wire signature baseline
+fn baseline_path() -> PathBuf {
+	PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+		.join("tests/api/golden/authentication_wire.txt")
+}
+
+/// Add the wire signature of each field in a message, recursing into nested messages
+fn message_signatures(
+	prefix: &str,
+	message: &DescriptorProto,
+	signatures: &mut BTreeMap<String, String>,
+) {
+	let name = format!("{prefix}.{}", message.name());
+
+	for field in &message.field {
+		let label = match field.label() {
+			field_descriptor_proto::Label::Repeated => "repeated",
+			_ if field.proto3_optional() => "optional",
+			_ => "singular",
+		};
+		let type_name = match field.type_name() {
+			"" => String::new(),
+			type_name => format!(" {type_name}"),
+		};
+
+		// Keyed by name to catch renumbering, by number to catch reuse
+		signatures.insert(
+			format!("field {name}.{}", field.name()),
+			format!("{} {label} {:?}{type_name}", field.number(), field.r#type()),
+		);
+		signatures.insert(
+			format!("field {name}#{}", field.number()),
+			format!("{label} {:?}{type_name}", field.r#type()),
+		);
+	}
+
+	for nested in &message.nested_type {
+		// Map entries are generated by protoc and covered by the parent field
+		if nested.options.as_ref().is_some_and(|o| o.map_entry()) {
+			continue;
+		}
+		message_signatures(&name, nested, signatures);
+	}
+
+	for nested_enum in &message.enum_type {
+		for value in &nested_enum.value {
+			signatures.insert(
+				format!("enum {name}.{}.{}", nested_enum.name(), value.name()),
+				value.number().to_string(),
+			);
+		}
+	}
+}
+
+/// Reduce the compiled descriptor set to a sorted map of wire signatures
+fn wire_signatures(encoded: &[u8]) -> Result<BTreeMap<String, String>> {
+	let descriptor_set = FileDescriptorSet::decode(encoded)?;
+	let mut signatures = BTreeMap::new();
+
+	// Well known types are owned by Google, so skip them
+	for file in descriptor_set
+		.file
+		.iter()
+		.filter(|f| !f.package().starts_with("google."))
+	{
+		let package = file.package();
+
+		for message in &file.message_type {
+			message_signatures(package, message, &mut signatures);
+		}
+
+		for file_enum in &file.enum_type {
+			for value in &file_enum.value {
+				signatures.insert(
+					format!("enum {package}.{}.{}", file_enum.name(), value.name()),
+					value.number().to_string(),
+				);
+			}
+		}
+
+		for service in &file.service {
+			for method in &service.method {
+				signatures.insert(
+					format!("rpc {package}.{}.{}", service.name(), method.name()),
+					format!(
+						"{}{} -> {}{}",
+						if method.client_streaming() { "stream " } else { "" },
+						method.input_type(),
+						if method.server_streaming() { "stream " } else { "" },
+						method.output_type(),
+					),
+				);
+			}
+		}
+	}
+
+	Ok(signatures)
+}
+
+/// Parse the baseline file, one `key = signature` entry per line
+fn parse_baseline(contents: &str) -> BTreeMap<String, String> {
+	contents
+		.lines()
+		.map(str::trim)
+		.filter(|line| !line.is_empty() && !line.starts_with('#'))
+		.filter_map(|line| line.split_once(" = "))
+		.map(|(key, signature)| (key.to_string(), signature.to_string()))
+		.collect()
+}
+
+/// Rename the old `authentication` package in a baseline to `authentication.v1`
+fn rename_legacy_package(contents: &str) -> String {
+	if contents.contains("authentication.v1.") {
+		return contents.to_string();
+	}
+
+	contents
+		.replace(" authentication.", " authentication.v1.")
+		.replace(" .authentication.", " .authentication.v1.")
+}
+
+/// Render signatures in the baseline file format
+fn render_baseline(signatures: &BTreeMap<String, String>) -> String {
+	let mut contents = String::from(
+		"# Protobuf wire signatures, generated by tests/api/wire_compatibility.rs\n\
+		 # Bless compatible additions with: BLESS_PROTO_WIRE=1 cargo test wire_compatibility\n",
+	);
+	for (key, signature) in signatures {
+		contents.push_str(&format!("{key} = {signature}\n"));
+	}
+	contents
+}
+
+/// Entries in the baseline that were removed or changed
+fn breaking_changes(
+	baseline: &BTreeMap<String, String>,
+	current: &BTreeMap<String, String>,
+) -> Vec<String> {
+	baseline
+		.iter()
+		.filter_map(|(key, expected)| match current.get(key) {
+			None => Some(format!("removed: {key} = {expected}")),
+			Some(actual) if actual != expected => {
+				Some(format!("changed: {key} = {expected} -> {actual}"))
+			}
+			Some(_) => None,
+		})
+		.collect()
+}
+
+/// Entries in the current descriptor that are not in the baseline
+fn additions(
+	baseline: &BTreeMap<String, String>,
+	current: &BTreeMap<String, String>,
+) -> Vec<String> {
+	current
+		.iter()
+		.filter(|(key, _)| !baseline.contains_key(*key))
+		.map(|(key, signature)| format!("added: {key} = {signature}"))
+		.collect()
+}
+
+#[test]
+fn proto_wire_format_matches_baseline() -> Result<()> {
+	//-- Setup and Fixtures (Arrange)
+	let current = wire_signatures(FILE_DESCRIPTOR_SET)?;
+	let path = baseline_path();
+	let bless = std::env::var(BLESS_ENV).is_ok_and(|v| v == "1");
+
+	// First run after checking out the proto submodule, write the baseline
+	if !path.exists() {
+		assert!(
+			std::env::var("CI").is_err(),
+			"Missing proto wire baseline {path:?}, run `{BLESS_ENV}=1 cargo test wire_compatibility` and commit it"
+		);
+		std::fs::create_dir_all(path.parent().unwrap())?;
+		std::fs::write(&path, render_baseline(&current))?;
+		return Ok(());
+	}
+
+	let baseline = parse_baseline(&rename_legacy_package(&std::fs::read_to_string(&path)?));
+
+	//-- Execute Test (Act)
+	let breaking = breaking_changes(&baseline, &current);
+	let added = additions(&baseline, &current);
+
+	//-- Checks (Assertions)
+	// Breaking changes can never be blessed
+	assert!(
+		breaking.is_empty(),
+		"Breaking proto wire changes:\n{}",
+		breaking.join("\n")
+	);
+
+	if bless {
+		std::fs::write(&path, render_baseline(&current))?;
+		return Ok(());
+	}
+
+	assert!(
+		added.is_empty(),
+		"Unblessed proto additions, run `{BLESS_ENV}=1 cargo test wire_compatibility` to accept:\n{}",
+		added.join("\n")
+	);
+
+	Ok(())
+}
+
+#[test]
+fn renumbered_field_is_breaking() {
+	//-- Setup and Fixtures (Arrange)
+	let baseline = parse_baseline(
+		"field authentication.UserResponse.email = 2 singular TypeString\n\
+		 field authentication.UserResponse#2 = singular TypeString\n",
+	);
+	let current = parse_baseline(
+		"field authentication.UserResponse.email = 3 singular TypeString\n\
+		 field authentication.UserResponse#3 = singular TypeString\n",
+	);
+
+	//-- Execute Test (Act)
+	let breaking = breaking_changes(&baseline, &current);
+
+	//-- Checks (Assertions)
+	assert_eq!(breaking.len(), 2);
+}
+
+#[test]
+fn new_field_is_an_addition_not_a_break() {
+	//-- Setup and Fixtures (Arrange)
+	let baseline =
+		parse_baseline("field authentication.UserResponse.email = 2 singular TypeString\n");
+	let mut current = baseline.clone();
+	current.insert(
+		"field authentication.UserResponse.avatar".to_string(),
+		"9 singular TypeString".to_string(),
+	);
+
+	//-- Execute Test (Act)
+	let breaking = breaking_changes(&baseline, &current);
+	let added = additions(&baseline, &current);
+
+	//-- Checks (Assertions)
+	assert!(breaking.is_empty());
+	assert_eq!(added.len(), 1);
+}
+
+#[test]
+fn baseline_round_trips() {
+	let signatures = parse_baseline(
+		"# comment\nrpc authentication.Users.Create = .authentication.CreateUserRequest -> .authentication.UserResponse\n",
+	);
+
+	assert_eq!(parse_baseline(&render_baseline(&signatures)), signatures);
+}
+
+#[test]
+fn legacy_package_baseline_is_renamed() {
+	//-- Setup and Fixtures (Arrange)
+	let legacy = "rpc authentication.UsersService.Create = .authentication.CreateUserRequest -> .authentication.UserResponse\n";
+
+	//-- Execute Function (Act)
+	let renamed = rename_legacy_package(legacy);
+
+	//-- Checks (Assertions)
+	assert_eq!(
+		renamed,
+		"rpc authentication.v1.UsersService.Create = .authentication.v1.CreateUserRequest -> .authentication.v1.UserResponse\n"
+	);
+	assert_eq!(rename_legacy_package(&renamed), renamed);
+}